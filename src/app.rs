@@ -1,11 +1,12 @@
 use crate::browser::dom::virtual_dom_bridge;
 use crate::browser::{
+    route::prefetch::PrefetchListener,
     service::routing,
     url,
     util::{self, window, ClosureNew},
-    Url,
+    RouteLoader, Url,
 };
-use crate::virtual_dom::{patch, El, EventHandlerManager, Mailbox, Node, Tag, View};
+use crate::virtual_dom::{patch, validation, El, EventHandlerManager, Mailbox, Node, Tag, View};
 use builder::{
     init::{Init, InitFn as BuilderInitFn},
     IntoAfterMount, MountPointInitInitAPI, UndefinedInitAPI, UndefinedMountPoint,
@@ -14,44 +15,64 @@ use enclose::{enc, enclose};
 use std::{
     any::Any,
     cell::{Cell, RefCell},
-    collections::VecDeque,
+    collections::{HashSet, VecDeque},
+    fmt,
     marker::PhantomData,
     rc::Rc,
 };
 use types::*;
-use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{closure::Closure, JsCast};
 use web_sys::Element;
 
 pub mod builder;
 pub mod cfg;
+pub(crate) mod clock;
 pub mod cmd_manager;
 pub mod cmds;
+pub mod component;
 pub mod data;
+#[cfg(feature = "devtools")]
+pub mod devtools;
 pub mod effects;
 pub mod get_element;
+pub mod lazy;
+pub mod leak_detector;
+pub mod memo;
 pub mod message_mapper;
+pub mod mutation;
 pub mod orders;
+pub mod pagination;
+pub mod performance_budget;
+pub mod query;
 pub mod render_timestamp_delta;
+#[cfg(feature = "statecharts")]
+pub mod statechart;
 pub mod stream_manager;
 pub mod streams;
 pub mod sub_manager;
 pub mod subs;
 pub mod types;
+pub mod unload_guard;
+pub mod update_effect;
 
 pub use builder::{
     AfterMount, BeforeMount, Builder as AppBuilder, MountPoint, MountType, UndefinedAfterMount,
     UrlHandling,
 };
 pub use cfg::{AppCfg, AppInitCfg};
-pub use cmd_manager::{CmdHandle, CmdManager};
+pub use cmd_manager::{CmdGroups, CmdHandle, CmdManager};
+pub use component::{Child, Component, ComponentMsg};
 pub use data::AppData;
 pub use effects::Effect;
-pub use get_element::GetElement;
+pub use get_element::{GetElement, MissingRoot, MountError};
 pub use message_mapper::MessageMapper;
 pub use orders::{Orders, OrdersContainer, OrdersProxy};
+pub use performance_budget::PerformanceBudget;
 pub use render_timestamp_delta::RenderTimestampDelta;
 pub use stream_manager::{StreamHandle, StreamManager};
 pub use sub_manager::{Notification, SubHandle, SubManager};
+pub use unload_guard::UnloadGuard;
+pub use update_effect::UpdateEffect;
 
 pub struct UndefinedGMsg;
 
@@ -59,6 +80,7 @@ type OptDynInitCfg<Ms, Mdl, ElC, GMs> =
     Option<AppInitCfg<Ms, Mdl, ElC, GMs, dyn IntoAfterMount<Ms, Mdl, ElC, GMs>>>;
 
 /// Determines if an update should cause the `VDom` to rerender or not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ShouldRender {
     Render,
     ForceRenderNow,
@@ -172,7 +194,137 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
             ) as Box<dyn IntoAfterMount<Ms, Mdl, ElC, GMs>>,
         };
         let app = Self::new(
-            update,
+            UpdateHandler::Orders(update),
+            None,
+            view,
+            root_element,
+            None,
+            None,
+            Some(app_init_cfg),
+        );
+        app.run()
+    }
+
+    /// Like `start`, but `on_missing` decides what happens when there is no element
+    /// with the id `root_id` - e.g. when the app is embedded in a page with late-loading containers.
+    ///
+    /// Returns `Ok(None)` when the start has been deferred to `DOMContentLoaded`;
+    /// a deferred start which still can't find the element logs `MountError` to the console.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///#[wasm_bindgen(start)]
+    ///pub fn start() {
+    ///    App::start_with("app", MissingRoot::RetryOnDomContentLoaded, init, update, view)
+    ///        .expect("start app");
+    ///}
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `MountError` when the root element can't be found or created.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `DOMContentLoaded` listener can't be added.
+    pub fn start_with(
+        root_id: &str,
+        on_missing: MissingRoot,
+        init: InitFn<Ms, Mdl, ElC, GMs>,
+        update: UpdateFn<Ms, Mdl, ElC, GMs>,
+        view: ViewFn<Mdl, ElC>,
+    ) -> Result<Option<Self>, MountError> {
+        if let Ok(root_element) = root_id.get_element() {
+            return Ok(Some(Self::start(root_element, init, update, view)));
+        }
+        match on_missing {
+            MissingRoot::Create => {
+                let root_element = get_element::create_root(root_id)?;
+                Ok(Some(Self::start(root_element, init, update, view)))
+            }
+            MissingRoot::RetryOnDomContentLoaded if util::document().ready_state() == "loading" => {
+                let root_id = root_id.to_owned();
+                let retry = Closure::once_into_js(move || {
+                    if let Err(mount_error) =
+                        Self::start_with(&root_id, MissingRoot::Error, init, update, view)
+                    {
+                        util::error(mount_error);
+                    }
+                });
+                util::document()
+                    .add_event_listener_with_callback("DOMContentLoaded", retry.unchecked_ref())
+                    .expect("add `DOMContentLoaded` listener");
+                Ok(None)
+            }
+            _ => Err(MountError::RootNotFound(root_id.to_owned())),
+        }
+    }
+
+    /// Create, mount and start the `App` with a pure `update` - Elm style.
+    ///
+    /// `init` and `update` return the model together with `UpdateEffect`s instead of calling
+    /// `Orders`, so they can be unit-tested by asserting on the returned effects.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///fn init(_: Url) -> (Model, Vec<UpdateEffect<Msg>>) {
+    ///    (Model::default(), vec![UpdateEffect::subscribe(Msg::UrlChanged)])
+    ///}
+    ///
+    ///fn update(msg: Msg, mut model: Model) -> (Model, Vec<UpdateEffect<Msg>>) {
+    ///    match msg {
+    ///        Msg::Clicked => {
+    ///            model.clicks += 1;
+    ///            (model, vec![])
+    ///        }
+    ///        Msg::UrlChanged(_) => (model, vec![UpdateEffect::Skip]),
+    ///    }
+    ///}
+    ///
+    ///#[wasm_bindgen(start)]
+    ///pub fn start() {
+    ///    App::start_pure("app", init, update, view);
+    ///}
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the root element cannot be found.
+    pub fn start_pure(
+        root_element: impl GetElement,
+        init: PureInitFn<Ms, Mdl, GMs>,
+        update: PureUpdateFn<Ms, Mdl, GMs>,
+        view: ViewFn<Mdl, ElC>,
+    ) -> Self {
+        // @TODO: Remove as soon as Webkit is fixed and older browsers are no longer in use.
+        // https://github.com/seed-rs/seed/issues/241
+        // https://bugs.webkit.org/show_bug.cgi?id=202881
+        let _ = util::document().query_selector("html");
+
+        // Allows panic messages to output to the browser console.error.
+        console_error_panic_hook::set_once();
+
+        let root_element = root_element.get_element().expect("get root element");
+
+        let app_init_cfg = AppInitCfg {
+            mount_type: MountType::Takeover,
+            phantom: PhantomData,
+            into_after_mount: Box::new(
+                move |url: Url,
+                      orders: &mut OrdersContainer<Ms, Mdl, ElC, GMs>|
+                      -> AfterMount<Mdl> {
+                    let (model, effects) = init(url);
+                    for effect in effects {
+                        effect.perform(orders);
+                    }
+                    AfterMount::new(model).url_handling(UrlHandling::None)
+                },
+            ) as Box<dyn IntoAfterMount<Ms, Mdl, ElC, GMs>>,
+        };
+        let app = Self::new(
+            UpdateHandler::Pure(update),
             None,
             view,
             root_element,
@@ -261,6 +413,7 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
     }
 
     pub fn process_effect_queue(&self, mut queue: VecDeque<Effect<Ms, GMs>>) {
+        let _counting = leak_detector::count_into(&self.data.live_counts);
         while let Some(effect) = queue.pop_front() {
             match effect {
                 Effect::Msg(msg) => {
@@ -301,9 +454,171 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
             .push(Box::new(listener));
     }
 
+    /// Log each message with its render decision and the update duration in a collapsed
+    /// `console.group` - logs from `update` are nested in the group.
+    pub fn log_messages(&self) -> &Self
+    where
+        Ms: fmt::Debug,
+    {
+        self.log_messages_filtered(|_| true)
+    }
+
+    /// Like `log_messages`, but only messages passing the `filter` are logged.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///App::start("app", init, update, view)
+    ///    .log_messages_filtered(|msg| !matches!(msg, Msg::Tick | Msg::MouseMoved(_)));
+    /// ```
+    pub fn log_messages_filtered(&self, filter: impl Fn(&Ms) -> bool + 'static) -> &Self
+    where
+        Ms: fmt::Debug,
+    {
+        self.data.msg_logger.replace(Some(Box::new(move |msg| {
+            if filter(msg) {
+                Some(format!("{:?}", msg))
+            } else {
+                None
+            }
+        })));
+        self
+    }
+
+    /// Warn in the console about renders exceeding the `budget` - e.g. a slow `view`
+    /// or too many DOM operations. Renders are measured only in debug builds.
+    ///
+    /// See `PerformanceBudget` for an example.
+    pub fn performance_budget(&self, budget: PerformanceBudget) -> &Self {
+        self.data.performance_budget.set(Some(budget));
+        self
+    }
+
+    /// Warn in the console when the number of live handles or event handler listeners grows
+    /// in `renders` consecutive renders (with no decrease in between) -
+    /// e.g. `SubHandle`s pushed to a `Vec` on each update. Resources are counted only in debug builds.
+    ///
+    /// Only resources created by this app are counted - see `leak_detector` for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///App::start("app", init, update, view).detect_leaks(leak_detector::DEFAULT_RENDERS);
+    /// ```
+    pub fn detect_leaks(&self, renders: usize) -> &Self {
+        self.data
+            .leak_detector
+            .replace(Some(leak_detector::LeakDetector::new(renders)));
+        self
+    }
+
+    /// The numbers of live resources created by the app; they are counted only in debug builds.
+    pub fn live_counts(&self) -> leak_detector::LiveCounts {
+        self.data.live_counts.get()
+    }
+
+    /// Call `view` twice per render in debug builds and warn when the results differ -
+    /// e.g. because of random ids or the current time in `view`. Such views cause
+    /// unnecessary patches and hydration mismatches.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///let app = App::start("app", init, update, view);
+    ///#[cfg(debug_assertions)]
+    ///app.strict_mode();
+    /// ```
+    pub fn strict_mode(&self) -> &Self {
+        self.data.strict_mode.set(true);
+        self
+    }
+
+    /// Skip renders after updates which haven't observably changed the model,
+    /// so no-op branches of `update` don't need `orders.skip()`.
+    ///
+    /// `fingerprint` is taken before each update; `should_render` compares it
+    /// with the updated model. An explicit `orders.render()` is skipped too when
+    /// `should_render` returns `false`; `orders.force_render_now()` is always respected
+    /// and the render isn't skipped when `orders.after_next_render` has been called.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///App::start("app", init, update, view).should_render(
+    ///    |model| (model.todos.len(), model.filter),
+    ///    |&(todos, filter), model| todos != model.todos.len() || filter != model.filter,
+    ///);
+    /// ```
+    pub fn should_render<F: 'static>(
+        &self,
+        fingerprint: impl Fn(&Mdl) -> F + 'static,
+        should_render: impl Fn(&F, &Mdl) -> bool + Clone + 'static,
+    ) -> &Self {
+        let should_render_fn = move |model: &Mdl| {
+            let fingerprint = fingerprint(model);
+            let should_render = should_render.clone();
+            Box::new(move |model: &Mdl| should_render(&fingerprint, model)) as RenderCheck<Mdl>
+        };
+        self.data
+            .should_render
+            .replace(Some(Box::new(should_render_fn)));
+        self
+    }
+
+    /// `should_render` which compares the whole models - it clones the model before each update,
+    /// so use it only for small models or models with cheap clones (e.g. `im-rc` collections).
+    pub fn skip_unchanged_renders(&self) -> &Self
+    where
+        Mdl: Clone + PartialEq,
+    {
+        self.should_render(Mdl::clone, |old_model, model| old_model != model)
+    }
+
+    /// Count running cmds and notify `subs::PendingWorkChanged` on each change.
+    pub fn track_pending_work(&self) -> &Self {
+        self.data.pending_work.enable();
+        self
+    }
+
+    /// Expose the model, a message dispatcher and render statistics to external devtools
+    /// as `window.__SEED__` - see `app::devtools`.
+    #[cfg(feature = "devtools")]
+    pub fn devtools(&self) -> &Self
+    where
+        Ms: serde::de::DeserializeOwned,
+        Mdl: serde::Serialize,
+        ElC: 'static,
+    {
+        devtools::install(self);
+        self
+    }
+
+    /// Add the in-page model inspector toggled by `Ctrl + Shift + M` -
+    /// see `app::devtools::model_inspector`.
+    #[cfg(feature = "devtools")]
+    pub fn model_inspector(&self) -> &Self
+    where
+        Ms: fmt::Debug,
+        Mdl: serde::Serialize,
+        ElC: 'static,
+    {
+        devtools::model_inspector::install(self);
+        self
+    }
+
+    /// Prefetch `RouteLoader` data of links to the route `R` marked by
+    /// `route::prefetch::prefetch_attrs` - see `route::prefetch`.
+    pub fn prefetch_links<R: RouteLoader>(&self) -> &Self {
+        self.data
+            .prefetch_listeners
+            .borrow_mut()
+            .push(Box::new(PrefetchListener::<R>::new()));
+        self
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
-        update: UpdateFn<Ms, Mdl, ElC, GMs>,
+        update: UpdateHandler<Ms, Mdl, ElC, GMs>,
         sink: Option<SinkFn<Ms, Mdl, ElC, GMs>>,
         view: ViewFn<Mdl, ElC>,
         mount_point: Element,
@@ -330,13 +645,29 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
                 main_el_vdom: RefCell::new(None),
                 popstate_closure: RefCell::new(None),
                 hashchange_closure: RefCell::new(None),
+                sub_listeners: RefCell::new(None),
                 routes: RefCell::new(routes),
                 window_event_handler_manager: RefCell::new(EventHandlerManager::new()),
                 sub_manager: RefCell::new(SubManager::new()),
+                cmd_groups: CmdGroups::new(),
+                pending_work: subs::pending_work::PendingWork::default(),
+                prefetch_listeners: RefCell::new(Vec::new()),
+                #[cfg(feature = "devtools")]
+                devtools: devtools::Devtools::default(),
                 msg_listeners: RefCell::new(Vec::new()),
+                msg_logger: RefCell::new(None),
+                performance_budget: Cell::new(None),
+                leak_detector: RefCell::new(None),
+                live_counts: Rc::default(),
+                strict_mode: Cell::new(false),
+                nesting_warnings: RefCell::new(HashSet::new()),
+                renders: Cell::new(0),
+                should_render: RefCell::new(None),
                 scheduled_render_handle: RefCell::new(None),
                 after_next_render_callbacks: RefCell::new(Vec::new()),
                 render_timestamp: Cell::new(None),
+                init_cmds: Cell::new(0),
+                init_placeholder: RefCell::new(None),
             }),
         }
     }
@@ -420,28 +751,64 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
             (l)(&message)
         }
 
+        let log_label = self
+            .data
+            .msg_logger
+            .borrow()
+            .as_ref()
+            .and_then(|msg_logger| msg_logger(&message));
+        let update_start = log_label.as_ref().map(|label| {
+            web_sys::console::group_collapsed_1(&format!("Msg: {}", label).into());
+            window().performance().expect("get `Performance`").now()
+        });
+
         let mut orders = OrdersContainer::new(self.clone());
-        (self.cfg.update)(
-            message,
-            &mut self.data.model.borrow_mut().as_mut().unwrap(),
-            &mut orders,
-        );
+        let render_check = self.render_check();
+        match &self.cfg.update {
+            UpdateHandler::Orders(update) => update(
+                message,
+                &mut self.data.model.borrow_mut().as_mut().unwrap(),
+                &mut orders,
+            ),
+            UpdateHandler::Pure(update) => {
+                let model = self.data.model.borrow_mut().take().unwrap();
+                let (model, effects) = update(message, model);
+                self.data.model.replace(Some(model));
+                for effect in effects {
+                    effect.perform(&mut orders);
+                }
+            }
+        }
 
         self.patch_window_event_handlers();
-
-        match orders.should_render {
-            ShouldRender::Render => self.schedule_render(),
-            ShouldRender::ForceRenderNow => {
-                self.cancel_scheduled_render();
-                self.rerender_vdom();
-            }
-            ShouldRender::Skip => (),
-        };
+        let should_render = orders.should_render;
+        let rendered = self.render_after_update(should_render, render_check);
+
+        if let Some(update_start) = update_start {
+            let duration = window().performance().expect("get `Performance`").now() - update_start;
+            let render_decision = match (should_render, rendered) {
+                (ShouldRender::Skip, _) => "skipped",
+                (_, false) => "skipped - the model hasn't changed",
+                (ShouldRender::Render, true) => "scheduled",
+                (ShouldRender::ForceRenderNow, true) => "forced",
+            };
+            web_sys::console::log_1(
+                &format!(
+                    "render: {}, update: {:.2} ms, effects: {}",
+                    render_decision,
+                    duration,
+                    orders.effects.len()
+                )
+                .into(),
+            );
+            web_sys::console::group_end();
+        }
         orders.effects
     }
 
     fn process_queue_global_message(&self, g_message: GMs) -> VecDeque<Effect<Ms, GMs>> {
         let mut orders = OrdersContainer::new(self.clone());
+        let render_check = self.render_check();
 
         if let Some(sink) = self.cfg.sink {
             sink(
@@ -452,16 +819,47 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
         }
 
         self.patch_window_event_handlers();
+        self.render_after_update(orders.should_render, render_check);
+        orders.effects
+    }
 
-        match orders.should_render {
-            ShouldRender::Render => self.schedule_render(),
+    /// Prepare the `should_render` check with the model before the update.
+    fn render_check(&self) -> Option<RenderCheck<Mdl>> {
+        let should_render = self.data.should_render.borrow();
+        let model = self.data.model.borrow();
+        match (should_render.as_ref(), model.as_ref()) {
+            (Some(should_render), Some(model)) => Some(should_render(model)),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` when the render has been scheduled or performed.
+    fn render_after_update(
+        &self,
+        should_render: ShouldRender,
+        render_check: Option<RenderCheck<Mdl>>,
+    ) -> bool {
+        match should_render {
+            ShouldRender::Render => {
+                let model_changed = match (render_check, self.data.model.borrow().as_ref()) {
+                    (Some(render_check), Some(model)) => render_check(model),
+                    _ => true,
+                };
+                // `after_next_render` callbacks would wait for an unrelated render otherwise.
+                let render =
+                    model_changed || !self.data.after_next_render_callbacks.borrow().is_empty();
+                if render {
+                    self.schedule_render();
+                }
+                render
+            }
             ShouldRender::ForceRenderNow => {
                 self.cancel_scheduled_render();
                 self.rerender_vdom();
+                true
             }
-            ShouldRender::Skip => (),
-        };
-        orders.effects
+            ShouldRender::Skip => false,
+        }
     }
 
     fn schedule_render(&self) {
@@ -477,18 +875,72 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
         }
     }
 
+    fn finish_init_cmd(&self) {
+        let init_cmds = self.data.init_cmds.get() - 1;
+        self.data.init_cmds.set(init_cmds);
+        if init_cmds == 0 {
+            self.data.init_placeholder.replace(None);
+            self.schedule_render();
+        }
+    }
+
     fn cancel_scheduled_render(&self) {
         // Cancel animation frame request by dropping it.
         self.data.scheduled_render_handle.borrow_mut().take();
     }
 
+    /// Call `view` again and warn about differences from `nodes` - see `App::strict_mode`.
+    fn check_view_purity(&self, nodes: &[Node<Ms>]) {
+        let second = (self.cfg.view)(self.data.model.borrow().as_ref().unwrap()).els();
+        let differences = validation::differences(nodes, &second);
+        if !differences.is_empty() {
+            web_sys::console::warn_1(
+                &format!(
+                    "Impure `view`: the second call with the same model returned different nodes:\n{}",
+                    differences.join("\n")
+                )
+                .into(),
+            );
+        }
+    }
+
     fn rerender_vdom(&self) {
+        let _counting = leak_detector::count_into(&self.data.live_counts);
         let new_render_timestamp = window().performance().expect("get `Performance`").now();
 
         // Create a new vdom: The top element, and all its children. Does not yet
         // have associated web_sys elements.
         let mut new = El::empty(Tag::Placeholder);
-        new.children = (self.cfg.view)(self.data.model.borrow().as_ref().unwrap()).els();
+        let init_cmds_running = self.data.init_cmds.get() > 0;
+        if init_cmds_running {
+            // Keep the mount point content (e.g. prerendered HTML) without a placeholder.
+            match self.data.init_placeholder.borrow().as_ref() {
+                Some(placeholder) => new.children.clone_from(placeholder),
+                None => return,
+            }
+        } else {
+            new.children = (self.cfg.view)(self.data.model.borrow().as_ref().unwrap()).els();
+            if cfg!(debug_assertions) {
+                let mut logged = self.data.nesting_warnings.borrow_mut();
+                for warning in validation::validate(&new.children) {
+                    if !logged.contains(&warning) {
+                        web_sys::console::warn_1(
+                            &format!("Invalid HTML nesting: {}", warning).into(),
+                        );
+                        logged.insert(warning);
+                    }
+                }
+                if self.data.strict_mode.get() {
+                    self.check_view_purity(&new.children);
+                }
+            }
+        }
+        let view_ms =
+            window().performance().expect("get `Performance`").now() - new_render_timestamp;
+        let render = self.data.renders.get() + 1;
+        self.data.renders.set(render);
+        // Only DOM operations of this patch are counted.
+        virtual_dom_bridge::take_patch_count();
 
         let old = self
             .data
@@ -506,10 +958,37 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
             new.children.iter_mut(),
         );
 
+        let patch_count = virtual_dom_bridge::take_patch_count();
+        if cfg!(debug_assertions) {
+            if let Some(budget) = self.data.performance_budget.get() {
+                let metrics = performance_budget::RenderMetrics {
+                    view_ms,
+                    patch_count,
+                    node_count: performance_budget::count_nodes(&new.children),
+                };
+                performance_budget::check(&budget, render, &metrics);
+            }
+            if let Some(detector) = self.data.leak_detector.borrow_mut().as_mut() {
+                for warning in detector.record(self.data.live_counts.get()) {
+                    web_sys::console::warn_1(&warning.into());
+                }
+            }
+        }
+
         // Now that we've re-rendered, replace our stored El with the new one;
         // it will be used as the old El next time.
         self.data.main_el_vdom.borrow_mut().replace(new);
 
+        #[cfg(feature = "devtools")]
+        self.data.devtools.record_render(
+            window().performance().expect("get `Performance`").now() - new_render_timestamp,
+        );
+
+        // `after_next_render` callbacks wait for the first `view` render.
+        if init_cmds_running {
+            return;
+        }
+
         // Execute `after_next_render_callbacks`.
 
         let old_render_timestamp = self
@@ -556,6 +1035,7 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
         note = "Please use `AppBuilder.build_and_start` instead"
     )]
     pub fn run(mut self) -> Self {
+        let _counting = leak_detector::count_into(&self.data.live_counts);
         let AppInitCfg {
             mount_type,
             into_after_mount,
@@ -622,6 +1102,18 @@ impl<Ms, Mdl, ElC: View<Ms> + 'static, GMs: 'static> App<Ms, Mdl, ElC, GMs> {
             enc!((self => s) move |notification| s.notify_with_notification(notification)),
             routes,
         );
+        self.data.sub_listeners.replace(Some(subs::listeners::setup(
+            enc!((self => s) move |notification| s.notify_with_notification(notification)),
+        )));
+        // Install listeners also for subscriptions created in `init`.
+        self.data
+            .sub_manager
+            .borrow()
+            .watch_types(enc!((self => s) move |type_id, subscribed| {
+                if let Some(sub_listeners) = s.data.sub_listeners.borrow_mut().as_mut() {
+                    sub_listeners.subscriptions_changed(type_id, subscribed);
+                }
+            }));
 
         self.process_effect_queue(orders.effects);
         // TODO: In the future, only run the following line if the above statement:
@@ -642,3 +1134,133 @@ type InitAppBuilder<Ms, Mdl, ElC, GMs> = AppBuilder<
     GMs,
     MountPointInitInitAPI<UndefinedMountPoint, BuilderInitFn<Ms, Mdl, ElC, GMs>>,
 >;
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    thread_local! {
+        static VIEWS: Cell<u32> = Cell::new(0);
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct Model {
+        count: u32,
+    }
+
+    enum Msg {
+        Increment,
+        Render,
+        ForceRenderNow,
+        AfterNextRender,
+        Rendered,
+    }
+
+    fn update(msg: Msg, model: &mut Model, orders: &mut OrdersContainer<Msg, Model, Node<Msg>>) {
+        match msg {
+            Msg::Increment => model.count += 1,
+            Msg::Render => {
+                orders.render();
+            }
+            Msg::ForceRenderNow => {
+                orders.force_render_now();
+            }
+            Msg::AfterNextRender => {
+                orders.after_next_render(|_| Msg::Rendered);
+            }
+            Msg::Rendered => (),
+        }
+    }
+
+    fn view(_: &Model) -> Node<Msg> {
+        VIEWS.with(|views| views.set(views.get() + 1));
+        Node::Empty
+    }
+
+    fn start_app() -> App<Msg, Model, Node<Msg>> {
+        let root = util::document().create_element("div").unwrap();
+        util::body().append_child(&root).unwrap();
+        let app = App::start(root, |_, _| Model { count: 0 }, update, view);
+        app.skip_unchanged_renders();
+        app.cancel_scheduled_render();
+        app
+    }
+
+    fn render_scheduled(app: &App<Msg, Model, Node<Msg>>) -> bool {
+        app.data.scheduled_render_handle.borrow().is_some()
+    }
+
+    #[wasm_bindgen_test]
+    fn unchanged_model_skips_render() {
+        let app = start_app();
+        app.update(Msg::Render);
+        assert!(!render_scheduled(&app));
+
+        app.update(Msg::Increment);
+        assert!(render_scheduled(&app));
+    }
+
+    #[wasm_bindgen_test]
+    fn force_render_now_bypasses_check() {
+        let app = start_app();
+        let views = VIEWS.with(Cell::get);
+        app.update(Msg::ForceRenderNow);
+        assert_eq!(VIEWS.with(Cell::get), views + 1);
+        assert!(!render_scheduled(&app));
+    }
+
+    #[wasm_bindgen_test]
+    fn after_next_render_callback_schedules_render() {
+        let app = start_app();
+        app.update(Msg::AfterNextRender);
+        assert!(render_scheduled(&app));
+    }
+
+    #[wasm_bindgen_test]
+    fn missing_root_is_created() {
+        let app = App::start_with(
+            "start-with-created-root",
+            MissingRoot::Create,
+            |_, _| Model { count: 0 },
+            update,
+            view,
+        )
+        .expect("start app")
+        .expect("app isn't deferred");
+
+        let root = util::document()
+            .get_element_by_id("start-with-created-root")
+            .expect("created root");
+        assert!(root
+            .parent_node()
+            .unwrap()
+            .is_same_node(Some(util::body().as_ref())));
+        assert!(app.cfg.mount_point.is_same_node(Some(root.as_ref())));
+        root.remove();
+    }
+
+    #[wasm_bindgen_test]
+    fn missing_root_is_error() {
+        let result = App::start_with(
+            "start-with-missing-root",
+            MissingRoot::Error,
+            |_, _| Model { count: 0 },
+            update,
+            view,
+        );
+        assert_eq!(
+            result.err(),
+            Some(MountError::RootNotFound(
+                "start-with-missing-root".to_owned()
+            ))
+        );
+        assert!(util::document()
+            .get_element_by_id("start-with-missing-root")
+            .is_none());
+    }
+}
@@ -109,7 +109,7 @@ impl<
         } = builder.init_api;
 
         let mut app = App::new(
-            builder.update,
+            UpdateHandler::Orders(builder.update),
             builder.sink,
             builder.view,
             mount_point.element_getter()(),
@@ -152,7 +152,7 @@ impl<
         } = before_mount_handler(url::current());
 
         App::new(
-            builder.update,
+            UpdateHandler::Orders(builder.update),
             builder.sink,
             builder.view,
             mount_point_getter(),
@@ -24,7 +24,7 @@ where
 {
     pub document: web_sys::Document,
     pub mount_point: web_sys::Element,
-    pub update: UpdateFn<Ms, Mdl, ElC, GMs>,
+    pub update: UpdateHandler<Ms, Mdl, ElC, GMs>,
     pub sink: Option<SinkFn<Ms, Mdl, ElC, GMs>>,
     pub view: ViewFn<Mdl, ElC>,
     pub window_events: Option<WindowEventsFn<Ms, Mdl>>,
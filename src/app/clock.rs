@@ -0,0 +1,172 @@
+//! Time source for `cmds::timeout`, `streams::interval` and the age of cached data.
+//!
+//! Timers use `gloo_timers` by default. When a virtual clock is installed
+//! (see `testing::TestClock`), new timers are scheduled on it instead
+//! and fire only when the virtual time is advanced manually.
+
+use futures::channel::{mpsc, oneshot};
+use futures::future::{self, FutureExt, LocalBoxFuture};
+use futures::stream::{LocalBoxStream, StreamExt};
+use gloo_timers::future::{IntervalStream, TimeoutFuture};
+use std::{cell::RefCell, rc::Rc};
+
+thread_local! {
+    static VIRTUAL_CLOCK: RefCell<Option<Rc<RefCell<VirtualClock>>>> = RefCell::new(None);
+}
+
+/// Resolves after `ms` milliseconds.
+pub(crate) fn sleep(ms: u32) -> LocalBoxFuture<'static, ()> {
+    match installed() {
+        None => TimeoutFuture::new(ms).boxed_local(),
+        Some(clock) => {
+            let (sender, receiver) = oneshot::channel();
+            clock
+                .borrow_mut()
+                .add_timer(ms, None, TimerSender::Once(sender));
+            async move {
+                // The sender is dropped without sending when the clock is uninstalled
+                // - never resolve in that case.
+                if receiver.await.is_err() {
+                    future::pending::<()>().await;
+                }
+            }
+            .boxed_local()
+        }
+    }
+}
+
+/// Streams a value every `ms` milliseconds.
+pub(crate) fn interval(ms: u32) -> LocalBoxStream<'static, ()> {
+    match installed() {
+        None => IntervalStream::new(ms).boxed_local(),
+        Some(clock) => {
+            let (sender, receiver) = mpsc::unbounded();
+            clock
+                .borrow_mut()
+                .add_timer(ms, Some(ms), TimerSender::Repeat(sender));
+            receiver.boxed_local()
+        }
+    }
+}
+
+/// Current time in milliseconds since the Unix epoch, or the virtual time when it's installed.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn now() -> f64 {
+    match installed() {
+        None => js_sys::Date::now(),
+        Some(clock) => clock.borrow().now() as f64,
+    }
+}
+
+pub(crate) fn install(clock: Rc<RefCell<VirtualClock>>) {
+    VIRTUAL_CLOCK.with(|current| current.replace(Some(clock)));
+}
+
+pub(crate) fn uninstall() {
+    VIRTUAL_CLOCK.with(|current| current.replace(None));
+}
+
+fn installed() -> Option<Rc<RefCell<VirtualClock>>> {
+    VIRTUAL_CLOCK.with(|current| current.borrow().clone())
+}
+
+// ------ VirtualClock ------
+
+#[derive(Debug)]
+enum TimerSender {
+    Once(oneshot::Sender<()>),
+    Repeat(mpsc::UnboundedSender<()>),
+}
+
+impl TimerSender {
+    fn is_canceled(&self) -> bool {
+        match self {
+            TimerSender::Once(sender) => sender.is_canceled(),
+            TimerSender::Repeat(sender) => sender.is_closed(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Timer {
+    id: u64,
+    due: u64,
+    period: Option<u32>,
+    sender: TimerSender,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct VirtualClock {
+    now: u64,
+    next_id: u64,
+    timers: Vec<Timer>,
+}
+
+impl VirtualClock {
+    /// Current virtual time in milliseconds.
+    pub(crate) const fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// The number of timers which haven't fired yet or are repeating and still listened to.
+    pub(crate) fn pending_timers(&mut self) -> usize {
+        self.timers.retain(|timer| !timer.sender.is_canceled());
+        self.timers.len()
+    }
+
+    /// The due time of the last one-shot timer.
+    pub(crate) fn last_one_shot_due(&mut self) -> Option<u64> {
+        self.pending_timers();
+        self.timers
+            .iter()
+            .filter(|timer| timer.period.is_none())
+            .map(|timer| timer.due)
+            .max()
+    }
+
+    /// Move the time forward and fire all timers which became due, in order.
+    pub(crate) fn advance(&mut self, ms: u64) {
+        let target = self.now + ms;
+        while let Some(index) = self.next_due_timer(target) {
+            let timer = &mut self.timers[index];
+            self.now = timer.due;
+            match (&timer.sender, timer.period) {
+                (TimerSender::Repeat(sender), Some(period)) => {
+                    if sender.unbounded_send(()).is_ok() {
+                        // Zero period would fire forever.
+                        timer.due += u64::from(period.max(1));
+                        continue;
+                    }
+                    self.timers.remove(index);
+                }
+                _ => {
+                    if let TimerSender::Once(sender) = self.timers.remove(index).sender {
+                        // The receiver could have been dropped - e.g. the cmd has been aborted.
+                        let _ = sender.send(());
+                    }
+                }
+            }
+        }
+        self.now = target;
+    }
+
+    fn add_timer(&mut self, ms: u32, period: Option<u32>, sender: TimerSender) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.timers.push(Timer {
+            id,
+            due: self.now + u64::from(ms),
+            period,
+            sender,
+        });
+    }
+
+    fn next_due_timer(&self, target: u64) -> Option<usize> {
+        self.timers
+            .iter()
+            .enumerate()
+            .filter(|(_, timer)| timer.due <= target)
+            .min_by_key(|(_, timer)| (timer.due, timer.id))
+            .map(|(index, _)| index)
+    }
+}
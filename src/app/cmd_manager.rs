@@ -1,4 +1,7 @@
+use super::leak_detector::{Counted, Resource};
 use futures::future::{abortable, AbortHandle, Future, FutureExt};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use uuid::Uuid;
 use wasm_bindgen_futures::spawn_local;
 
 // @TODO: Tighten up access - e.g. replace `pub` with `pub(crate)`. Applicable to the entire code base.
@@ -18,16 +21,128 @@ impl CmdManager {
         let (cmd, handle) = abortable(cmd);
         // Ignore the error when the future is aborted. I.e. just stop the future execution.
         spawn_local(cmd.map(move |_| ()));
-        CmdHandle(handle)
+        CmdHandle {
+            abort_handle: handle,
+            _counted: Counted::new(Resource::CmdHandle),
+        }
     }
 }
 
 // ------ CmdHandle ------
 
-pub struct CmdHandle(AbortHandle);
+pub struct CmdHandle {
+    abort_handle: AbortHandle,
+    // See `leak_detector`.
+    _counted: Counted,
+}
 
 impl Drop for CmdHandle {
     fn drop(&mut self) {
-        self.0.abort();
+        self.abort_handle.abort();
+    }
+}
+
+// ------ CmdGroups ------
+
+/// Named groups of running cmds - see `Orders::perform_cmd_in_group`.
+#[derive(Default)]
+pub struct CmdGroups {
+    groups: Rc<RefCell<HashMap<String, HashMap<Uuid, AbortHandle>>>>,
+}
+
+impl CmdGroups {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn perform_cmd(&self, group: impl Into<String>, cmd: impl Future<Output = ()> + 'static) {
+        let (group, id) = (group.into(), Uuid::new_v4());
+        let (cmd, handle) = abortable(cmd);
+        self.groups
+            .borrow_mut()
+            .entry(group.clone())
+            .or_default()
+            .insert(id, handle);
+
+        let groups = Rc::clone(&self.groups);
+        spawn_local(cmd.map(move |_| {
+            let mut groups = groups.borrow_mut();
+            if let Some(cmds) = groups.get_mut(&group) {
+                cmds.remove(&id);
+                if cmds.is_empty() {
+                    groups.remove(&group);
+                }
+            }
+        }));
+    }
+
+    /// Abort all running cmds in the group.
+    pub fn abort(&self, group: &str) {
+        let cmds = self.groups.borrow_mut().remove(group);
+        for (_, handle) in cmds.into_iter().flatten() {
+            handle.abort();
+        }
+    }
+
+    /// The number of running cmds in the group.
+    pub fn len(&self, group: &str) -> usize {
+        self.groups.borrow().get(group).map_or(0, HashMap::len)
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::clock;
+    use crate::testing::TestClock;
+    use std::cell::Cell;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Sets `done` after 100 ms.
+    fn cmd(done: &Rc<Cell<bool>>) -> impl Future<Output = ()> {
+        let done = Rc::clone(done);
+        clock::sleep(100).map(move |()| done.set(true))
+    }
+
+    #[wasm_bindgen_test]
+    async fn abort_cancels_only_running_cmds_of_group() {
+        let clock = TestClock::install();
+        let cmd_groups = CmdGroups::new();
+        let (first, second, other) = (
+            Rc::new(Cell::new(false)),
+            Rc::new(Cell::new(false)),
+            Rc::new(Cell::new(false)),
+        );
+        let completed = Rc::new(Cell::new(false));
+
+        let done = Rc::clone(&completed);
+        cmd_groups.perform_cmd("save", async move { done.set(true) });
+        clock.flush().await;
+        assert!(completed.get());
+        assert_eq!(cmd_groups.len("save"), 0);
+
+        cmd_groups.perform_cmd("save", cmd(&first));
+        cmd_groups.perform_cmd("save", cmd(&second));
+        cmd_groups.perform_cmd("load", cmd(&other));
+        assert_eq!(cmd_groups.len("save"), 2);
+
+        cmd_groups.abort("save");
+        assert_eq!(cmd_groups.len("save"), 0);
+        assert_eq!(cmd_groups.len("load"), 1);
+
+        clock.advance(100);
+        clock.flush().await;
+        clock.flush().await;
+        assert!(!first.get() && !second.get());
+        assert!(other.get());
+        assert_eq!(cmd_groups.len("load"), 0);
+
+        // Aborting a group without running cmds is a no-op.
+        cmd_groups.abort("save");
+        cmd_groups.abort("load");
     }
 }
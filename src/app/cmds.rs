@@ -1,12 +1,97 @@
+use super::clock;
 use futures::future::{Future, FutureExt};
-use gloo_timers::future::TimeoutFuture;
 
 // @TODO add fetch cmd?
 
+// ------ Blocking cmds ------
+
+#[cfg(feature = "wasm_threads")]
+mod thread_pool;
+#[cfg(feature = "wasm_threads")]
+pub use thread_pool::{init_thread_pool, spawn_blocking};
+
+// ------ Barcode cmds ------
+
+pub mod barcode;
+pub use barcode::detect_barcodes;
+
+// ------ Crypto cmds ------
+
+pub mod crypto;
+
+// ------ Device sensors cmds ------
+
+pub mod device_sensors;
+
+// ------ File system cmds ------
+
+pub mod file_system;
+
+// ------ Fullscreen cmds ------
+
+pub mod fullscreen;
+
+// ------ Head cmds ------
+
+pub mod head;
+pub use head::FaviconBadge;
+
+// ------ History cmds ------
+
+pub mod history;
+
+// ------ Media element cmds ------
+
+pub mod media;
+
+// ------ Media devices cmds ------
+
+pub mod media_devices;
+pub use media_devices::{screen_capture, ScreenCaptureOptions};
+
+// ------ Media recorder ------
+
+pub mod media_recorder;
+
+// ------ Messaging cmds ------
+
+pub mod messaging;
+
+// ------ Orientation cmds ------
+
+pub mod orientation;
+
+// ------ Payment cmds ------
+
+pub mod payment;
+
+// ------ Picture-in-Picture cmds ------
+
+pub mod picture_in_picture;
+
+// ------ Print cmds ------
+
+pub mod printing;
+pub use printing::{print, print_element};
+
+// ------ Scroll cmds ------
+
+pub mod scroll;
+
+// ------ Speech synthesis cmds ------
+
+pub mod speech;
+
+// ------ Wake lock cmd ------
+
+pub mod wake_lock;
+
 // ------ Timeout cmd ------
 
 /// Set timeout in milliseconds.
 ///
+/// It respects `testing::TestClock` in tests.
+///
 /// # Example
 ///
 /// ```rust,no_run
@@ -16,5 +101,5 @@ pub fn timeout<Ms>(
     ms: u32,
     handler: impl FnOnce() -> Ms + Clone + 'static,
 ) -> impl Future<Output = Ms> {
-    TimeoutFuture::new(ms).map(move |_| handler())
+    clock::sleep(ms).map(move |_| handler())
 }
@@ -0,0 +1,251 @@
+//! Barcode and QR code detection by the
+//! [Barcode Detection API](https://developer.mozilla.org/en-US/docs/Web/API/Barcode_Detection_API).
+//!
+//! Only Chromium-based browsers on Android, Chrome OS and macOS support the API.
+//! Enable the feature `barcode_fallback` and register a Wasm decoder by `set_fallback`
+//! (e.g. a wrapper around the `rqrr` crate) to scan in other browsers.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::Scan => {
+//!    orders.perform_cmd(cmds::detect_barcodes(&model.video, &["qr_code"], Msg::Scanned));
+//!}
+//!Msg::Scanned(Ok(barcodes)) => {
+//!    if let Some(barcode) = barcodes.into_iter().next() {
+//!        model.ticket = Some(barcode.raw_value);
+//!    }
+//!}
+//! ```
+
+#[cfg(feature = "barcode_fallback")]
+use crate::browser::util::document;
+use crate::virtual_dom::ElRef;
+use futures::future::Future;
+#[cfg(feature = "barcode_fallback")]
+use std::cell::Cell;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+mod raw {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        pub type BarcodeDetector;
+
+        #[wasm_bindgen(constructor, catch)]
+        pub fn new(options: &JsValue) -> Result<BarcodeDetector, JsValue>;
+
+        #[wasm_bindgen(method)]
+        pub fn detect(this: &BarcodeDetector, image: &JsValue) -> js_sys::Promise;
+    }
+}
+
+// ------ Barcode ------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Barcode {
+    /// Decoded content, e.g. the URL in a QR code.
+    pub raw_value: String,
+    /// E.g. `"qr_code"`, `"ean_13"` or `"code_128"`.
+    pub format: String,
+    /// In pixels of the source image or video frame.
+    pub bounding_box: BoundingBox,
+    /// Corners from the top-left one clockwise.
+    pub corner_points: Vec<(f64, f64)>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BoundingBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Barcode {
+    fn from_js(barcode: &JsValue) -> Option<Self> {
+        let bounding_box = get(barcode, "boundingBox");
+        Some(Self {
+            raw_value: get(barcode, "rawValue").as_string()?,
+            format: get(barcode, "format").as_string()?,
+            bounding_box: BoundingBox {
+                x: number(&bounding_box, "x"),
+                y: number(&bounding_box, "y"),
+                width: number(&bounding_box, "width"),
+                height: number(&bounding_box, "height"),
+            },
+            corner_points: js_sys::Array::from(&get(barcode, "cornerPoints"))
+                .iter()
+                .map(|point| (number(&point, "x"), number(&point, "y")))
+                .collect(),
+        })
+    }
+}
+
+fn get(target: &JsValue, key: &str) -> JsValue {
+    js_sys::Reflect::get(target, &key.into()).unwrap_or(JsValue::UNDEFINED)
+}
+
+fn number(target: &JsValue, key: &str) -> f64 {
+    get(target, key).as_f64().unwrap_or_default()
+}
+
+// ------ detect_barcodes ------
+
+/// Detect barcodes in the referenced `img`, `video` (its current frame) or `canvas` element.
+///
+/// `formats` limits the detection (e.g. `&["qr_code"]`) - it's faster; pass `&[]` to detect
+/// all supported formats. Call it periodically (e.g. by `streams::interval`) to scan a video.
+///
+/// It fails when the element doesn't exist, the image isn't loaded yet or the API isn't
+/// supported (and there is no fallback).
+pub fn detect_barcodes<E: Clone + JsCast, Ms>(
+    el_ref: &ElRef<E>,
+    formats: &[&str],
+    handler: impl FnOnce(Result<Vec<Barcode>, JsValue>) -> Ms + 'static,
+) -> impl Future<Output = Ms> {
+    let element = el_ref.map_type::<web_sys::HtmlElement>().get();
+    let formats = formats
+        .iter()
+        .copied()
+        .map(JsValue::from)
+        .collect::<js_sys::Array>();
+    async move {
+        let result = async {
+            let element = element.ok_or_else(|| JsValue::from("barcode: element doesn't exist"))?;
+            if !is_supported() {
+                #[cfg(feature = "barcode_fallback")]
+                if let Some(decode) = FALLBACK.with(Cell::get) {
+                    return Ok(decode(&image_data(&element)?));
+                }
+                return Err("barcode: Barcode Detection API isn't supported".into());
+            }
+            let options = js_sys::Object::new();
+            if formats.length() > 0 {
+                js_sys::Reflect::set(&options, &"formats".into(), &formats)?;
+            }
+            let barcodes =
+                JsFuture::from(raw::BarcodeDetector::new(&options)?.detect(&element)).await?;
+            Ok(js_sys::Array::from(&barcodes)
+                .iter()
+                .filter_map(|barcode| Barcode::from_js(&barcode))
+                .collect())
+        }
+        .await;
+        handler(result)
+    }
+}
+
+/// The browser supports the Barcode Detection API.
+pub fn is_supported() -> bool {
+    js_sys::Reflect::has(&js_sys::global(), &"BarcodeDetector".into()).unwrap_or_default()
+}
+
+// ------ Fallback ------
+
+/// Decoder of RGBA pixels.
+#[cfg(feature = "barcode_fallback")]
+pub type Decoder = fn(&web_sys::ImageData) -> Vec<Barcode>;
+
+#[cfg(feature = "barcode_fallback")]
+thread_local! {
+    static FALLBACK: Cell<Option<Decoder>> = Cell::new(None);
+}
+
+/// Register a decoder used by `detect_barcodes` when the browser doesn't support
+/// the Barcode Detection API. It receives RGBA pixels of the image or the video frame.
+///
+/// # Example
+///
+/// ```rust,no_run
+///fn decode_qr(image: &web_sys::ImageData) -> Vec<Barcode> {
+///    let (width, height) = (image.width() as usize, image.height() as usize);
+///    let pixels = image.data();
+///    let mut image = rqrr::PreparedImage::prepare_from_greyscale(width, height, |x, y| {
+///        pixels[(y * width + x) * 4 + 1]
+///    });
+///    ...
+///}
+///
+///cmds::barcode::set_fallback(decode_qr);
+/// ```
+#[cfg(feature = "barcode_fallback")]
+pub fn set_fallback(decode: Decoder) {
+    FALLBACK.with(|fallback| fallback.set(Some(decode)));
+}
+
+/// Draw the element to a canvas and read its pixels.
+#[cfg(feature = "barcode_fallback")]
+fn image_data(element: &web_sys::HtmlElement) -> Result<web_sys::ImageData, JsValue> {
+    let size = |width: &str, height: &str| {
+        Some((
+            get(element, width).as_f64()?,
+            get(element, height).as_f64()?,
+        ))
+    };
+    let (width, height) = size("videoWidth", "videoHeight")
+        .or_else(|| size("naturalWidth", "naturalHeight"))
+        .or_else(|| size("width", "height"))
+        .filter(|(width, height)| *width > 0. && *height > 0.)
+        .ok_or_else(|| JsValue::from("barcode: image isn't loaded"))?;
+
+    let canvas = document()
+        .create_element("canvas")?
+        .unchecked_into::<web_sys::HtmlCanvasElement>();
+    canvas.set_width(width as u32);
+    canvas.set_height(height as u32);
+    let context = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from("barcode: canvas 2d context isn't available"))?
+        .unchecked_into::<web_sys::CanvasRenderingContext2d>();
+
+    if let Some(video) = element.dyn_ref::<web_sys::HtmlVideoElement>() {
+        context.draw_image_with_html_video_element(video, 0., 0.)?;
+    } else if let Some(image) = element.dyn_ref::<web_sys::HtmlImageElement>() {
+        context.draw_image_with_html_image_element(image, 0., 0.)?;
+    } else if let Some(source) = element.dyn_ref::<web_sys::HtmlCanvasElement>() {
+        context.draw_image_with_html_canvas_element(source, 0., 0.)?;
+    } else {
+        return Err("barcode: element isn't an image, video or canvas".into());
+    }
+    context.get_image_data(0., 0., width, height)
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn parse_detected_barcode() {
+        let barcode = js_sys::JSON::parse(
+            r#"{
+                "rawValue": "https://seed-rs.org",
+                "format": "qr_code",
+                "boundingBox": { "x": 10, "y": 20, "width": 100, "height": 100 },
+                "cornerPoints": [{ "x": 10, "y": 20 }, { "x": 110, "y": 20 }]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            Barcode::from_js(&barcode),
+            Some(Barcode {
+                raw_value: "https://seed-rs.org".to_owned(),
+                format: "qr_code".to_owned(),
+                bounding_box: BoundingBox {
+                    x: 10.,
+                    y: 20.,
+                    width: 100.,
+                    height: 100.
+                },
+                corner_points: vec![(10., 20.), (110., 20.)],
+            })
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! [Web Crypto API](https://developer.mozilla.org/en-US/docs/Web/API/SubtleCrypto) wrappers
+//! with byte-slice inputs and outputs - hashing, HMAC signatures and AES-GCM encryption.
+//!
+//! It's available only in secure contexts (HTTPS or `localhost`).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::FileLoaded(bytes) => {
+//!    orders.perform_cmd(cmds::crypto::digest(HashAlgorithm::Sha256, &bytes, Msg::Hashed));
+//!}
+//!Msg::GenerateKey => orders.perform_cmd(cmds::crypto::generate_key(Msg::KeyGenerated)),
+//!Msg::KeyGenerated(Ok(key)) => {
+//!    orders.perform_cmd(cmds::crypto::aes_gcm_encrypt(&key, model.note.as_bytes(), Msg::Encrypted));
+//!    model.key = Some(key);
+//!}
+//! ```
+
+use crate::browser::util::window;
+use futures::future::Future;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// Length of the random AES-GCM initialization vector prepended to ciphertexts.
+pub const AES_GCM_IV_LENGTH: usize = 12;
+
+// ------ HashAlgorithm ------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    /// Use it only for compatibility - SHA-1 isn't collision resistant.
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Sha1 => "SHA-1",
+            Self::Sha256 => "SHA-256",
+            Self::Sha384 => "SHA-384",
+            Self::Sha512 => "SHA-512",
+        }
+    }
+}
+
+// ------ digest ------
+
+/// Hash `data`.
+pub fn digest<Ms>(
+    algorithm: HashAlgorithm,
+    data: &[u8],
+    handler: impl FnOnce(Result<Vec<u8>, JsValue>) -> Ms + Clone + 'static,
+) -> impl Future<Output = Ms> {
+    let data = js_sys::Uint8Array::from(data);
+    async move {
+        let result = async {
+            let promise = subtle()?.digest_with_str_and_buffer_source(algorithm.as_str(), &data)?;
+            bytes(promise).await
+        }
+        .await;
+        handler(result)
+    }
+}
+
+// ------ hmac_sign ------
+
+/// Sign `data` by HMAC with the raw secret `key`.
+pub fn hmac_sign<Ms>(
+    hash: HashAlgorithm,
+    key: &[u8],
+    data: &[u8],
+    handler: impl FnOnce(Result<Vec<u8>, JsValue>) -> Ms + Clone + 'static,
+) -> impl Future<Output = Ms> {
+    let key = js_sys::Uint8Array::from(key);
+    let data = js_sys::Uint8Array::from(data);
+    async move {
+        let result = async {
+            let subtle = subtle()?;
+            let params = web_sys::HmacImportParams::new_with_str("HMAC", hash.as_str());
+            let key = JsFuture::from(subtle.import_key_with_object(
+                "raw",
+                &key,
+                &params,
+                false,
+                &usages(&["sign"]),
+            )?)
+            .await?
+            .unchecked_into::<web_sys::CryptoKey>();
+            bytes(subtle.sign_with_str_and_buffer_source("HMAC", &key, &data)?).await
+        }
+        .await;
+        handler(result)
+    }
+}
+
+// ------ AesKey ------
+
+/// AES-GCM key. Export it by `to_bytes` to store it.
+#[derive(Clone)]
+pub struct AesKey {
+    raw: web_sys::CryptoKey,
+}
+
+impl std::fmt::Debug for AesKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AesKey")
+    }
+}
+
+impl AesKey {
+    /// Import a raw 128-bit or 256-bit key.
+    pub fn from_bytes<Ms>(
+        bytes: &[u8],
+        handler: impl FnOnce(Result<Self, JsValue>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let bytes = js_sys::Uint8Array::from(bytes);
+        async move {
+            let result = async {
+                let promise = subtle()?.import_key_with_str(
+                    "raw",
+                    &bytes,
+                    "AES-GCM",
+                    true,
+                    &usages(&["encrypt", "decrypt"]),
+                )?;
+                Ok(Self {
+                    raw: JsFuture::from(promise).await?.unchecked_into(),
+                })
+            }
+            .await;
+            handler(result)
+        }
+    }
+
+    /// Export the raw key.
+    pub fn to_bytes<Ms>(
+        &self,
+        handler: impl FnOnce(Result<Vec<u8>, JsValue>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let raw = self.raw.clone();
+        async move {
+            let result = async { bytes(subtle()?.export_key("raw", &raw)?).await }.await;
+            handler(result)
+        }
+    }
+
+    pub const fn raw(&self) -> &web_sys::CryptoKey {
+        &self.raw
+    }
+}
+
+// ------ generate_key ------
+
+/// Generate a random exportable 256-bit AES-GCM key.
+pub async fn generate_key<Ms>(
+    handler: impl FnOnce(Result<AesKey, JsValue>) -> Ms + Clone + 'static,
+) -> Ms {
+    let result = async {
+        let params = web_sys::AesKeyGenParams::new("AES-GCM", 256);
+        let promise =
+            subtle()?.generate_key_with_object(&params, true, &usages(&["encrypt", "decrypt"]))?;
+        Ok(AesKey {
+            raw: JsFuture::from(promise).await?.unchecked_into(),
+        })
+    }
+    .await;
+    handler(result)
+}
+
+// ------ aes_gcm_encrypt / aes_gcm_decrypt ------
+
+/// Encrypt `data` by AES-GCM.
+///
+/// The output is a random initialization vector (`AES_GCM_IV_LENGTH` bytes) followed by
+/// the ciphertext with the authentication tag - pass it to `aes_gcm_decrypt` as it is.
+pub fn aes_gcm_encrypt<Ms>(
+    key: &AesKey,
+    data: &[u8],
+    handler: impl FnOnce(Result<Vec<u8>, JsValue>) -> Ms + Clone + 'static,
+) -> impl Future<Output = Ms> {
+    let key = key.raw.clone();
+    let data = js_sys::Uint8Array::from(data);
+    async move {
+        let result = async {
+            let mut iv = random_bytes(AES_GCM_IV_LENGTH)?;
+            let params = web_sys::AesGcmParams::new_with_u8_slice("AES-GCM", &mut iv);
+            let ciphertext =
+                bytes(subtle()?.encrypt_with_object_and_buffer_source(&params, &key, &data)?)
+                    .await?;
+            iv.extend(ciphertext);
+            Ok(iv)
+        }
+        .await;
+        handler(result)
+    }
+}
+
+/// Decrypt the output of `aes_gcm_encrypt`.
+///
+/// It fails when the key is wrong or the data have been modified.
+pub fn aes_gcm_decrypt<Ms>(
+    key: &AesKey,
+    data: &[u8],
+    handler: impl FnOnce(Result<Vec<u8>, JsValue>) -> Ms + Clone + 'static,
+) -> impl Future<Output = Ms> {
+    let key = key.raw.clone();
+    let data = split_iv(data).map(|(iv, ciphertext)| {
+        (
+            js_sys::Uint8Array::from(iv),
+            js_sys::Uint8Array::from(ciphertext),
+        )
+    });
+    async move {
+        let result = async {
+            let (iv, ciphertext) = data?;
+            let params = web_sys::AesGcmParams::new_with_u8_array("AES-GCM", &iv);
+            bytes(subtle()?.decrypt_with_object_and_buffer_source(&params, &key, &ciphertext)?)
+                .await
+        }
+        .await;
+        handler(result)
+    }
+}
+
+fn split_iv(data: &[u8]) -> Result<(&[u8], &[u8]), JsValue> {
+    if data.len() < AES_GCM_IV_LENGTH {
+        return Err("crypto: data are shorter than the initialization vector".into());
+    }
+    Ok(data.split_at(AES_GCM_IV_LENGTH))
+}
+
+// ------ random_bytes ------
+
+/// Cryptographically secure random bytes.
+///
+/// # Errors
+///
+/// Returns error when the browser doesn't support the Web Crypto API
+/// or `length` is greater than 65536.
+pub fn random_bytes(length: usize) -> Result<Vec<u8>, JsValue> {
+    let mut bytes = vec![0; length];
+    window()
+        .crypto()?
+        .get_random_values_with_u8_array(&mut bytes)?;
+    Ok(bytes)
+}
+
+// ------ Helpers ------
+
+fn subtle() -> Result<web_sys::SubtleCrypto, JsValue> {
+    let crypto = window().crypto()?;
+    // `crypto.subtle` is `undefined` in insecure contexts.
+    if js_sys::Reflect::get(&crypto, &"subtle".into())?.is_undefined() {
+        return Err("crypto: Web Crypto API is available only in secure contexts".into());
+    }
+    Ok(crypto.subtle())
+}
+
+fn usages(usages: &[&str]) -> js_sys::Array {
+    usages.iter().copied().map(JsValue::from).collect()
+}
+
+async fn bytes(promise: js_sys::Promise) -> Result<Vec<u8>, JsValue> {
+    let buffer = JsFuture::from(promise).await?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[wasm_bindgen_test]
+    async fn sha256_digest() {
+        let hash = digest(HashAlgorithm::Sha256, b"abc", Result::unwrap).await;
+        assert_eq!(
+            hex(&hash),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn aes_gcm_round_trip() {
+        let key = generate_key(Result::unwrap).await;
+        let encrypted = aes_gcm_encrypt(&key, b"secret", Result::unwrap).await;
+        assert_eq!(encrypted.len(), AES_GCM_IV_LENGTH + b"secret".len() + 16);
+
+        let decrypted = aes_gcm_decrypt(&key, &encrypted, Result::unwrap).await;
+        assert_eq!(decrypted, b"secret");
+
+        let mut tampered = encrypted;
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(aes_gcm_decrypt(&key, &tampered, |result| result.is_err()).await);
+    }
+}
@@ -0,0 +1,40 @@
+//! Permissions for `streams::device_orientation` and `streams::device_motion`.
+
+use crate::browser::util::window;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// Ask the user for the permission to read device orientation and motion sensors.
+/// Only iOS Safari (13+) requires it - other browsers resolve with `Ok(true)` immediately.
+///
+/// The cmd has to be performed in a user gesture handler (click, touch, ...).
+///
+/// # Example
+///
+/// ```rust,no_run
+///Msg::EnableTiltControls => {
+///    orders.perform_cmd(cmds::device_sensors::request_permission(Msg::SensorsPermission));
+///}
+///Msg::SensorsPermission(Ok(true)) => {
+///    model.orientation_stream = Some(orders.stream_with_handle(streams::device_orientation(Msg::Tilted)));
+///}
+/// ```
+pub async fn request_permission<Ms>(
+    handler: impl FnOnce(Result<bool, JsValue>) -> Ms + Clone + 'static,
+) -> Ms {
+    let result = async {
+        let event_class = js_sys::Reflect::get(&window(), &"DeviceOrientationEvent".into())?;
+        let request_permission = js_sys::Reflect::get(&event_class, &"requestPermission".into())?;
+        if !request_permission.is_function() {
+            return Ok(true);
+        }
+        let promise = request_permission
+            .unchecked_into::<js_sys::Function>()
+            .call0(&event_class)?
+            .dyn_into::<js_sys::Promise>()?;
+        let permission = JsFuture::from(promise).await?;
+        Ok(permission.as_string().as_deref() == Some("granted"))
+    }
+    .await;
+    handler(result)
+}
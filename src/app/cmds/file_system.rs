@@ -0,0 +1,770 @@
+//! Open and save real files and store private files of your app.
+//!
+//! It wraps the [File System Access API](https://developer.mozilla.org/en-US/docs/Web/API/File_System_API)
+//! and the [Origin Private File System](https://developer.mozilla.org/en-US/docs/Web/API/File_System_API/Origin_private_file_system)
+//! (OPFS).
+//!
+//! File pickers are supported only in Chromium-based browsers and have to be opened
+//! from a user gesture handler. OPFS (`Directory::root`) is supported in all modern browsers.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::Open => orders.perform_cmd(cmds::file_system::open_files(
+//!    &OpenFileOptions::default().file_type(FileType::new("Markdown").accept("text/markdown", &[".md"])),
+//!    Msg::Opened,
+//!)),
+//!Msg::Opened(Ok(mut handles)) => {
+//!    let handle = handles.remove(0);
+//!    orders.perform_cmd(handle.read_text(Msg::TextLoaded));
+//!    model.handle = Some(handle);
+//!}
+//!Msg::Opened(Err(FileSystemError::Cancelled)) => (),
+//!Msg::Save => {
+//!    if let Some(handle) = &model.handle {
+//!        orders.perform_cmd(handle.write_text(&model.text, Msg::Saved));
+//!    }
+//!}
+//! ```
+
+use crate::browser::util::window;
+use futures::future::Future;
+use std::cell::Cell;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+
+mod raw {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        pub type StorageManager;
+
+        #[wasm_bindgen(method, js_name = getDirectory)]
+        pub fn get_directory(this: &StorageManager) -> js_sys::Promise;
+
+        #[wasm_bindgen(js_name = showOpenFilePicker, catch)]
+        pub fn show_open_file_picker(options: &JsValue) -> Result<js_sys::Promise, JsValue>;
+
+        #[wasm_bindgen(js_name = showSaveFilePicker, catch)]
+        pub fn show_save_file_picker(options: &JsValue) -> Result<js_sys::Promise, JsValue>;
+
+        #[derive(Clone)]
+        pub type FileSystemHandle;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn kind(this: &FileSystemHandle) -> String;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn name(this: &FileSystemHandle) -> String;
+
+        #[wasm_bindgen(extends = FileSystemHandle)]
+        #[derive(Clone)]
+        pub type FileSystemFileHandle;
+
+        #[wasm_bindgen(method, js_name = getFile)]
+        pub fn get_file(this: &FileSystemFileHandle) -> js_sys::Promise;
+
+        #[wasm_bindgen(method, js_name = createWritable)]
+        pub fn create_writable(this: &FileSystemFileHandle, options: &JsValue) -> js_sys::Promise;
+
+        #[wasm_bindgen(extends = FileSystemHandle)]
+        #[derive(Clone)]
+        pub type FileSystemDirectoryHandle;
+
+        #[wasm_bindgen(method, js_name = getFileHandle)]
+        pub fn get_file_handle(
+            this: &FileSystemDirectoryHandle,
+            name: &str,
+            options: &JsValue,
+        ) -> js_sys::Promise;
+
+        #[wasm_bindgen(method, js_name = getDirectoryHandle)]
+        pub fn get_directory_handle(
+            this: &FileSystemDirectoryHandle,
+            name: &str,
+            options: &JsValue,
+        ) -> js_sys::Promise;
+
+        #[wasm_bindgen(method, js_name = removeEntry)]
+        pub fn remove_entry(
+            this: &FileSystemDirectoryHandle,
+            name: &str,
+            options: &JsValue,
+        ) -> js_sys::Promise;
+
+        #[wasm_bindgen(method)]
+        pub fn values(this: &FileSystemDirectoryHandle) -> AsyncIterator;
+
+        pub type AsyncIterator;
+
+        #[wasm_bindgen(method)]
+        pub fn next(this: &AsyncIterator) -> js_sys::Promise;
+
+        #[derive(Clone)]
+        pub type FileSystemWritableFileStream;
+
+        #[wasm_bindgen(method, catch)]
+        pub fn write(
+            this: &FileSystemWritableFileStream,
+            data: &JsValue,
+        ) -> Result<js_sys::Promise, JsValue>;
+
+        #[wasm_bindgen(method)]
+        pub fn seek(this: &FileSystemWritableFileStream, position: f64) -> js_sys::Promise;
+
+        #[wasm_bindgen(method)]
+        pub fn truncate(this: &FileSystemWritableFileStream, size: f64) -> js_sys::Promise;
+
+        #[wasm_bindgen(method)]
+        pub fn close(this: &FileSystemWritableFileStream) -> js_sys::Promise;
+
+        #[wasm_bindgen(method)]
+        pub fn abort(this: &FileSystemWritableFileStream) -> js_sys::Promise;
+    }
+}
+
+pub type Result<T> = std::result::Result<T, FileSystemError>;
+
+// ------ FileSystemError ------
+
+#[derive(Debug, Clone)]
+pub enum FileSystemError {
+    /// The browser doesn't support the API or the page isn't in a secure context.
+    Unsupported,
+    /// The user has closed the file picker.
+    Cancelled,
+    /// The file or directory doesn't exist.
+    NotFound,
+    /// The user or the browser has denied the access.
+    NotAllowed,
+    /// The entry is a file but a directory was expected or vice-versa.
+    TypeMismatch,
+    /// The file is locked by another writer or the directory isn't empty.
+    InvalidModification,
+    Failed(JsValue),
+}
+
+impl From<JsValue> for FileSystemError {
+    fn from(error: JsValue) -> Self {
+        let name = js_sys::Reflect::get(&error, &"name".into())
+            .ok()
+            .and_then(|name| name.as_string());
+        match name.as_deref() {
+            Some("AbortError") => Self::Cancelled,
+            Some("NotFoundError") => Self::NotFound,
+            Some("NotAllowedError") => Self::NotAllowed,
+            Some("SecurityError") => Self::NotAllowed,
+            Some("TypeMismatchError") => Self::TypeMismatch,
+            Some("InvalidModificationError") => Self::InvalidModification,
+            Some("NoModificationAllowedError") => Self::InvalidModification,
+            _ => Self::Failed(error),
+        }
+    }
+}
+
+// ------ Picker options ------
+
+/// A file type offered by file pickers, e.g. `FileType::new("Images").accept("image/*", &[".png", ".jpg"])`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileType {
+    pub description: String,
+    /// MIME types with their file extensions.
+    pub accept: Vec<(String, Vec<String>)>,
+}
+
+impl FileType {
+    pub fn new(description: impl Into<String>) -> Self {
+        Self {
+            description: description.into(),
+            accept: Vec::new(),
+        }
+    }
+
+    pub fn accept(mut self, mime_type: impl Into<String>, extensions: &[&str]) -> Self {
+        let extensions = extensions.iter().copied().map(str::to_owned).collect();
+        self.accept.push((mime_type.into(), extensions));
+        self
+    }
+
+    fn to_js(&self) -> JsValue {
+        let accept = js_sys::Object::new();
+        for (mime_type, extensions) in &self.accept {
+            let extensions = extensions
+                .iter()
+                .map(|extension| JsValue::from(extension.as_str()))
+                .collect::<js_sys::Array>();
+            js_sys::Reflect::set(&accept, &mime_type.into(), &extensions).ok();
+        }
+        let file_type = js_sys::Object::new();
+        js_sys::Reflect::set(
+            &file_type,
+            &"description".into(),
+            &(&self.description).into(),
+        )
+        .ok();
+        js_sys::Reflect::set(&file_type, &"accept".into(), &accept).ok();
+        file_type.into()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpenFileOptions {
+    pub multiple: bool,
+    pub types: Vec<FileType>,
+    /// Hide the "All files" option.
+    pub exclude_accept_all: bool,
+    /// Pickers with the same id remember the last opened directory.
+    pub id: Option<String>,
+}
+
+impl OpenFileOptions {
+    pub const fn multiple(mut self) -> Self {
+        self.multiple = true;
+        self
+    }
+
+    pub fn file_type(mut self, file_type: FileType) -> Self {
+        self.types.push(file_type);
+        self
+    }
+
+    pub const fn exclude_accept_all(mut self) -> Self {
+        self.exclude_accept_all = true;
+        self
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    fn to_js(&self) -> JsValue {
+        let options = picker_options(&self.types, self.exclude_accept_all, self.id.as_deref());
+        js_sys::Reflect::set(&options, &"multiple".into(), &self.multiple.into()).ok();
+        options
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SaveFileOptions {
+    pub suggested_name: Option<String>,
+    pub types: Vec<FileType>,
+    /// Hide the "All files" option.
+    pub exclude_accept_all: bool,
+    /// Pickers with the same id remember the last opened directory.
+    pub id: Option<String>,
+}
+
+impl SaveFileOptions {
+    pub fn suggested_name(mut self, name: impl Into<String>) -> Self {
+        self.suggested_name = Some(name.into());
+        self
+    }
+
+    pub fn file_type(mut self, file_type: FileType) -> Self {
+        self.types.push(file_type);
+        self
+    }
+
+    pub const fn exclude_accept_all(mut self) -> Self {
+        self.exclude_accept_all = true;
+        self
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    fn to_js(&self) -> JsValue {
+        let options = picker_options(&self.types, self.exclude_accept_all, self.id.as_deref());
+        if let Some(name) = &self.suggested_name {
+            js_sys::Reflect::set(&options, &"suggestedName".into(), &name.into()).ok();
+        }
+        options
+    }
+}
+
+fn picker_options(types: &[FileType], exclude_accept_all: bool, id: Option<&str>) -> JsValue {
+    let options = js_sys::Object::new();
+    let types = types.iter().map(FileType::to_js).collect::<js_sys::Array>();
+    js_sys::Reflect::set(&options, &"types".into(), &types).ok();
+    js_sys::Reflect::set(
+        &options,
+        &"excludeAcceptAllOption".into(),
+        &exclude_accept_all.into(),
+    )
+    .ok();
+    if let Some(id) = id {
+        js_sys::Reflect::set(&options, &"id".into(), &id.into()).ok();
+    }
+    options.into()
+}
+
+// ------ open_files / save_file ------
+
+/// Let the user choose files to open.
+///
+/// It fails with `FileSystemError::Cancelled` when the user closes the picker.
+pub fn open_files<Ms>(
+    options: &OpenFileOptions,
+    handler: impl FnOnce(Result<Vec<FileHandle>>) -> Ms + Clone + 'static,
+) -> impl Future<Output = Ms> {
+    let options = options.to_js();
+    async move {
+        let result = async {
+            if !is_supported() {
+                return Err(FileSystemError::Unsupported);
+            }
+            let handles = JsFuture::from(raw::show_open_file_picker(&options)?).await?;
+            Ok(js_sys::Array::from(&handles)
+                .iter()
+                .map(|handle| FileHandle {
+                    raw: handle.unchecked_into(),
+                })
+                .collect())
+        }
+        .await;
+        handler(result)
+    }
+}
+
+/// Let the user choose where to save a file. The file is created empty (or replaced)
+/// when you write to the returned handle.
+///
+/// It fails with `FileSystemError::Cancelled` when the user closes the picker.
+pub fn save_file<Ms>(
+    options: &SaveFileOptions,
+    handler: impl FnOnce(Result<FileHandle>) -> Ms + Clone + 'static,
+) -> impl Future<Output = Ms> {
+    let options = options.to_js();
+    async move {
+        let result = async {
+            if !is_supported() {
+                return Err(FileSystemError::Unsupported);
+            }
+            let raw = JsFuture::from(raw::show_save_file_picker(&options)?).await?;
+            Ok(FileHandle {
+                raw: raw.unchecked_into(),
+            })
+        }
+        .await;
+        handler(result)
+    }
+}
+
+/// The browser supports file pickers.
+pub fn is_supported() -> bool {
+    js_sys::Reflect::has(&window(), &"showOpenFilePicker".into()).unwrap_or_default()
+}
+
+// ------ FileHandle ------
+
+/// A file chosen by the user or stored in OPFS.
+///
+/// Keep it in your `Model` to save the file again without asking the user.
+#[derive(Clone)]
+pub struct FileHandle {
+    raw: raw::FileSystemFileHandle,
+}
+
+impl std::fmt::Debug for FileHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileHandle")
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+impl FileHandle {
+    pub fn name(&self) -> String {
+        self.raw.name()
+    }
+
+    /// The file snapshot - use it to get its size, type or to stream it by `File::stream`.
+    pub fn file<Ms>(
+        &self,
+        handler: impl FnOnce(Result<web_sys::File>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let raw = self.raw.clone();
+        async move {
+            let result = file(&raw).await;
+            handler(result)
+        }
+    }
+
+    pub fn read<Ms>(
+        &self,
+        handler: impl FnOnce(Result<Vec<u8>>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let raw = self.raw.clone();
+        async move {
+            let result = async {
+                let buffer = JsFuture::from(file(&raw).await?.array_buffer()).await?;
+                Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+            }
+            .await;
+            handler(result)
+        }
+    }
+
+    pub fn read_text<Ms>(
+        &self,
+        handler: impl FnOnce(Result<String>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let raw = self.raw.clone();
+        async move {
+            let result = async {
+                let text = JsFuture::from(file(&raw).await?.text()).await?;
+                Ok(text.as_string().unwrap_or_default())
+            }
+            .await;
+            handler(result)
+        }
+    }
+
+    /// Replace the file content.
+    pub fn write<Ms>(
+        &self,
+        data: &[u8],
+        handler: impl FnOnce(Result<()>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        self.write_js(js_sys::Uint8Array::from(data).into(), handler)
+    }
+
+    /// Replace the file content.
+    pub fn write_text<Ms>(
+        &self,
+        text: &str,
+        handler: impl FnOnce(Result<()>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        self.write_js(text.into(), handler)
+    }
+
+    fn write_js<Ms>(
+        &self,
+        data: JsValue,
+        handler: impl FnOnce(Result<()>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let raw = self.raw.clone();
+        async move {
+            let result = async {
+                let writer = writer(&raw, false).await?;
+                // The writer aborts itself on drop when the write fails.
+                write(&writer.raw, &data).await?;
+                writer.close().await
+            }
+            .await;
+            handler(result)
+        }
+    }
+
+    /// Open the file for streaming writes - e.g. large exports written chunk by chunk.
+    ///
+    /// Nothing is written to the file until `FileWriter::close` is called.
+    /// `keep_existing_data` copies the current content into the writer first;
+    /// otherwise the writer starts empty.
+    pub fn writer<Ms>(
+        &self,
+        keep_existing_data: bool,
+        handler: impl FnOnce(Result<FileWriter>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let raw = self.raw.clone();
+        async move {
+            let result = writer(&raw, keep_existing_data).await;
+            handler(result)
+        }
+    }
+
+    pub fn raw(&self) -> &JsValue {
+        self.raw.as_ref()
+    }
+}
+
+async fn file(raw: &raw::FileSystemFileHandle) -> Result<web_sys::File> {
+    Ok(JsFuture::from(raw.get_file()).await?.unchecked_into())
+}
+
+async fn writer(raw: &raw::FileSystemFileHandle, keep_existing_data: bool) -> Result<FileWriter> {
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(
+        &options,
+        &"keepExistingData".into(),
+        &keep_existing_data.into(),
+    )?;
+    Ok(FileWriter {
+        raw: JsFuture::from(raw.create_writable(&options))
+            .await?
+            .unchecked_into(),
+        closed: Cell::new(false),
+    })
+}
+
+// ------ FileWriter ------
+
+/// Streaming writer created by `FileHandle::writer`.
+///
+/// Writes are applied atomically by `close`; dropping the writer without closing it discards them.
+pub struct FileWriter {
+    raw: raw::FileSystemWritableFileStream,
+    closed: Cell<bool>,
+}
+
+impl std::fmt::Debug for FileWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FileWriter")
+    }
+}
+
+impl FileWriter {
+    /// Write `data` at the current position.
+    pub fn write_bytes(&self, data: &[u8]) -> impl Future<Output = Result<()>> {
+        let raw = self.raw.clone();
+        let data = JsValue::from(js_sys::Uint8Array::from(data));
+        async move { write(&raw, &data).await }
+    }
+
+    /// Write `text` at the current position.
+    pub fn write_text(&self, text: &str) -> impl Future<Output = Result<()>> {
+        let raw = self.raw.clone();
+        let data = JsValue::from(text);
+        async move { write(&raw, &data).await }
+    }
+
+    /// Move the current position in bytes.
+    pub fn seek(&self, position: u64) -> impl Future<Output = Result<()>> {
+        #[allow(clippy::cast_precision_loss)]
+        let promise = self.raw.seek(position as f64);
+        done(promise)
+    }
+
+    /// Resize the file in bytes.
+    pub fn truncate(&self, size: u64) -> impl Future<Output = Result<()>> {
+        #[allow(clippy::cast_precision_loss)]
+        let promise = self.raw.truncate(size as f64);
+        done(promise)
+    }
+
+    /// Commit written data to the file.
+    pub fn close(self) -> impl Future<Output = Result<()>> {
+        self.closed.set(true);
+        done(self.raw.close())
+    }
+}
+
+impl Drop for FileWriter {
+    fn drop(&mut self) {
+        if !self.closed.get() {
+            let promise = self.raw.abort();
+            spawn_local(async move {
+                JsFuture::from(promise).await.ok();
+            });
+        }
+    }
+}
+
+async fn write(raw: &raw::FileSystemWritableFileStream, data: &JsValue) -> Result<()> {
+    done(raw.write(data)?).await
+}
+
+async fn done(promise: js_sys::Promise) -> Result<()> {
+    JsFuture::from(promise).await.map(|_| ())?;
+    Ok(())
+}
+
+// ------ Directory ------
+
+/// A directory in the Origin Private File System.
+#[derive(Clone)]
+pub struct Directory {
+    raw: raw::FileSystemDirectoryHandle,
+}
+
+impl std::fmt::Debug for Directory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Directory")
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+/// An item of `Directory::entries`.
+#[derive(Debug, Clone)]
+pub enum Entry {
+    File(FileHandle),
+    Directory(Directory),
+}
+
+impl Entry {
+    pub fn name(&self) -> String {
+        match self {
+            Self::File(file) => file.name(),
+            Self::Directory(directory) => directory.name(),
+        }
+    }
+}
+
+impl Directory {
+    /// The root of the Origin Private File System - private storage of your origin,
+    /// invisible to the user and not limited by the `localStorage` quota.
+    pub async fn root<Ms>(handler: impl FnOnce(Result<Self>) -> Ms + Clone + 'static) -> Ms {
+        let result = async {
+            let storage = js_sys::Reflect::get(&window().navigator(), &"storage".into())?;
+            if !js_sys::Reflect::has(&storage, &"getDirectory".into()).unwrap_or_default() {
+                return Err(FileSystemError::Unsupported);
+            }
+            let storage = storage.unchecked_into::<raw::StorageManager>();
+            Ok(Self {
+                raw: JsFuture::from(storage.get_directory())
+                    .await?
+                    .unchecked_into(),
+            })
+        }
+        .await;
+        handler(result)
+    }
+
+    pub fn name(&self) -> String {
+        self.raw.name()
+    }
+
+    /// Get the file `name`; `create` creates it when it doesn't exist.
+    ///
+    /// It fails with `FileSystemError::NotFound` when the file doesn't exist and `create` is `false`.
+    pub fn file<Ms>(
+        &self,
+        name: &str,
+        create: bool,
+        handler: impl FnOnce(Result<FileHandle>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let promise = self.raw.get_file_handle(name, &create_options(create));
+        async move {
+            let result = async {
+                Ok(FileHandle {
+                    raw: JsFuture::from(promise).await?.unchecked_into(),
+                })
+            }
+            .await;
+            handler(result)
+        }
+    }
+
+    /// Get the subdirectory `name`; `create` creates it when it doesn't exist.
+    ///
+    /// It fails with `FileSystemError::NotFound` when the directory doesn't exist and `create` is `false`.
+    pub fn directory<Ms>(
+        &self,
+        name: &str,
+        create: bool,
+        handler: impl FnOnce(Result<Self>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let promise = self.raw.get_directory_handle(name, &create_options(create));
+        async move {
+            let result = async {
+                Ok(Self {
+                    raw: JsFuture::from(promise).await?.unchecked_into(),
+                })
+            }
+            .await;
+            handler(result)
+        }
+    }
+
+    /// Remove the file or the directory `name`; `recursive` removes also non-empty directories.
+    pub fn remove<Ms>(
+        &self,
+        name: &str,
+        recursive: bool,
+        handler: impl FnOnce(Result<()>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let options = js_sys::Object::new();
+        js_sys::Reflect::set(&options, &"recursive".into(), &recursive.into()).ok();
+        let promise = self.raw.remove_entry(name, &options);
+        async move {
+            let result = done(promise).await;
+            handler(result)
+        }
+    }
+
+    /// Files and subdirectories in the browser order (it isn't sorted).
+    pub fn entries<Ms>(
+        &self,
+        handler: impl FnOnce(Result<Vec<Entry>>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let iterator = self.raw.values();
+        async move {
+            let result = async {
+                let mut entries = Vec::new();
+                loop {
+                    let next = JsFuture::from(iterator.next()).await?;
+                    if js_sys::Reflect::get(&next, &"done".into())?.is_truthy() {
+                        break Ok(entries);
+                    }
+                    let handle = js_sys::Reflect::get(&next, &"value".into())?
+                        .unchecked_into::<raw::FileSystemHandle>();
+                    entries.push(if handle.kind() == "directory" {
+                        Entry::Directory(Self {
+                            raw: handle.unchecked_into(),
+                        })
+                    } else {
+                        Entry::File(FileHandle {
+                            raw: handle.unchecked_into(),
+                        })
+                    });
+                }
+            }
+            .await;
+            handler(result)
+        }
+    }
+
+    pub fn raw(&self) -> &JsValue {
+        self.raw.as_ref()
+    }
+}
+
+fn create_options(create: bool) -> JsValue {
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &"create".into(), &create.into()).ok();
+    options.into()
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    async fn opfs_round_trip() {
+        let root = Directory::root(Result::unwrap).await;
+        let directory = root.directory("seed-test", true, Result::unwrap).await;
+        let file = directory.file("note.txt", true, Result::unwrap).await;
+        file.write_text("hello", Result::unwrap).await;
+        assert_eq!(file.read_text(Result::unwrap).await, "hello");
+
+        let writer = file.writer(true, Result::unwrap).await;
+        writer.seek(5).await.unwrap();
+        writer.write_text(" world").await.unwrap();
+        writer.close().await.unwrap();
+        assert_eq!(file.read_text(Result::unwrap).await, "hello world");
+
+        let names = directory
+            .entries(Result::unwrap)
+            .await
+            .iter()
+            .map(Entry::name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["note.txt".to_owned()]);
+
+        root.remove("seed-test", true, Result::unwrap).await;
+        assert!(matches!(
+            root.directory("seed-test", false, |result| result).await,
+            Err(FileSystemError::NotFound)
+        ));
+    }
+}
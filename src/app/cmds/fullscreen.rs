@@ -0,0 +1,79 @@
+//! [Fullscreen API](https://developer.mozilla.org/en-US/docs/Web/API/Fullscreen_API)
+//! with the `webkit`-prefixed fallback for Safari.
+//!
+//! Subscribe to `subs::FullscreenChanged` to update your `Model` - the user can also
+//! leave the fullscreen mode by the `Esc` key.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::EnterFullscreen => {
+//!    orders.perform_cmd(cmds::fullscreen::enter(&model.player, Msg::FullscreenResult));
+//!}
+//!Msg::ExitFullscreen => {
+//!    orders.perform_cmd(cmds::fullscreen::exit(Msg::FullscreenResult));
+//!}
+//! ```
+
+use crate::browser::util::document;
+use crate::virtual_dom::ElRef;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// Display the referenced element in the fullscreen mode.
+///
+/// It fails when the element doesn't exist, the browser doesn't support the API
+/// or the cmd wasn't performed in a user gesture handler (click, key press, ...).
+pub async fn enter<E: Clone + JsCast, Ms>(
+    el_ref: &ElRef<E>,
+    handler: impl FnOnce(Result<(), JsValue>) -> Ms + Clone + 'static,
+) -> Ms {
+    let element = el_ref.map_type::<web_sys::Element>().get();
+    handler(match element {
+        Some(element) => {
+            call_vendor_method(&element, &["requestFullscreen", "webkitRequestFullscreen"]).await
+        }
+        None => Err("fullscreen: element doesn't exist".into()),
+    })
+}
+
+/// Leave the fullscreen mode. It's a no-op when the page isn't in the fullscreen mode.
+pub async fn exit<Ms>(handler: impl FnOnce(Result<(), JsValue>) -> Ms + Clone + 'static) -> Ms {
+    handler(if element().is_some() {
+        call_vendor_method(&document(), &["exitFullscreen", "webkitExitFullscreen"]).await
+    } else {
+        Ok(())
+    })
+}
+
+/// The element currently displayed in the fullscreen mode.
+pub fn element() -> Option<web_sys::Element> {
+    ["fullscreenElement", "webkitFullscreenElement"]
+        .iter()
+        .find_map(|name| {
+            js_sys::Reflect::get(&document(), &JsValue::from(*name))
+                .ok()?
+                .dyn_into()
+                .ok()
+        })
+}
+
+/// Call the first method from `names` that the browser supports
+/// and wait for its `Promise` - prefixed methods return `undefined`.
+async fn call_vendor_method(target: &JsValue, names: &[&str]) -> Result<(), JsValue> {
+    let method = names
+        .iter()
+        .find_map(|name| {
+            js_sys::Reflect::get(target, &JsValue::from(*name))
+                .ok()?
+                .dyn_into::<js_sys::Function>()
+                .ok()
+        })
+        .ok_or_else(|| JsValue::from("fullscreen: Fullscreen API isn't supported"))?;
+
+    let result = method.call0(target)?;
+    if let Some(promise) = result.dyn_ref::<js_sys::Promise>() {
+        JsFuture::from(promise.clone()).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,311 @@
+//! Change the favicon and the theme color in the document head at runtime -
+//! e.g. a favicon with the number of unread messages or a theme color of the current section.
+//!
+//! The cmds update the head elements rendered by the page template or by `ssg`;
+//! missing elements are created. The first favicon is remembered, so it can be restored
+//! by `reset_favicon` and it's used as the background of badges.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::MessagesReceived(messages) => {
+//!    model.unread += messages.len();
+//!    orders.perform_cmd(cmds::head::set_favicon_badge(
+//!        FaviconBadge::count(model.unread),
+//!        |_| Msg::NoOp,
+//!    ));
+//!}
+//!Msg::SectionChanged(section) => {
+//!    orders.perform_cmd(cmds::head::set_theme_color(section.color(), || Msg::NoOp));
+//!}
+//! ```
+
+use crate::browser::util;
+use futures::channel::oneshot;
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+/// The attribute with the original `href` of the favicon `<link>` changed by the cmds.
+pub const DATA_ORIGINAL_HREF: &str = "data-seed-original-href";
+/// The width and height of favicons with badges in pixels.
+pub const BADGE_CANVAS_SIZE: u32 = 32;
+/// The highest count displayed by `FaviconBadge::count`; higher counts are displayed as `99+`.
+pub const MAX_BADGE_COUNT: usize = 99;
+
+const FAVICON_SELECTOR: &str = r#"link[rel~="icon"]"#;
+const THEME_COLOR_SELECTOR: &str = r#"meta[name="theme-color"]:not([media])"#;
+
+// ------ Favicon ------
+
+/// Replace the favicon with the image at `href` (e.g. an URL or a data URL).
+pub async fn set_favicon<Ms>(href: impl Into<String>, handler: impl FnOnce() -> Ms) -> Ms {
+    set_favicon_href(&href.into());
+    handler()
+}
+
+/// Restore the favicon replaced by `set_favicon` or `set_favicon_badge`.
+pub async fn reset_favicon<Ms>(handler: impl FnOnce() -> Ms) -> Ms {
+    if let Some(original_href) = original_favicon_href() {
+        set_favicon_href(&original_href);
+    }
+    handler()
+}
+
+/// Draw the `badge` onto the original favicon and use the result as the favicon.
+/// A badge with an empty text restores the original favicon.
+///
+/// The error is returned when the canvas can't be drawn or exported -
+/// e.g. when the original favicon is a cross-origin image without CORS headers.
+/// A missing or invalid original favicon isn't an error - the badge is drawn alone.
+pub async fn set_favicon_badge<Ms>(
+    badge: FaviconBadge,
+    handler: impl FnOnce(Result<(), JsValue>) -> Ms,
+) -> Ms {
+    if badge.text.is_empty() {
+        return reset_favicon(|| handler(Ok(()))).await;
+    }
+    let original = match original_favicon_href() {
+        Some(href) => load_image(&href).await,
+        None => None,
+    };
+    let result = draw_badge(&badge, original.as_ref()).map(|data_url| set_favicon_href(&data_url));
+    handler(result)
+}
+
+/// A badge in the bottom-right corner of the favicon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaviconBadge {
+    text: String,
+    background: String,
+    color: String,
+}
+
+impl FaviconBadge {
+    /// A badge with the `count`; `0` removes the badge.
+    pub fn count(count: usize) -> Self {
+        Self::text(match count {
+            0 => String::new(),
+            count if count > MAX_BADGE_COUNT => format!("{}+", MAX_BADGE_COUNT),
+            count => count.to_string(),
+        })
+    }
+
+    /// A badge with a short `text` - e.g. `!`; an empty text removes the badge.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            background: "#e53935".to_owned(),
+            color: "#ffffff".to_owned(),
+        }
+    }
+
+    /// The CSS color of the badge circle, red by default.
+    pub fn background(mut self, background: impl Into<String>) -> Self {
+        self.background = background.into();
+        self
+    }
+
+    /// The CSS color of the text, white by default.
+    pub fn color(mut self, color: impl Into<String>) -> Self {
+        self.color = color.into();
+        self
+    }
+}
+
+/// The favicon `<link>`; it's created when the head doesn't contain any.
+fn favicon_link() -> web_sys::Element {
+    let document = util::document();
+    if let Some(link) = document
+        .query_selector(FAVICON_SELECTOR)
+        .expect("query favicon")
+    {
+        return link;
+    }
+    let link = document.create_element("link").expect("create favicon");
+    link.set_attribute("rel", "icon").expect("set favicon rel");
+    append_to_head(&link);
+    link
+}
+
+fn set_favicon_href(href: &str) {
+    let link = favicon_link();
+    if !link.has_attribute(DATA_ORIGINAL_HREF) {
+        link.set_attribute(
+            DATA_ORIGINAL_HREF,
+            &link.get_attribute("href").unwrap_or_default(),
+        )
+        .expect("set favicon original href");
+    }
+    link.set_attribute("href", href).expect("set favicon href");
+}
+
+/// The `href` of the favicon before the first change; `None` when there isn't any.
+fn original_favicon_href() -> Option<String> {
+    let link = util::document()
+        .query_selector(FAVICON_SELECTOR)
+        .expect("query favicon")?;
+    link.get_attribute(DATA_ORIGINAL_HREF)
+        .or_else(|| link.get_attribute("href"))
+        .filter(|href| !href.is_empty())
+}
+
+/// Load the image; `None` when it fails to load.
+async fn load_image(src: &str) -> Option<web_sys::HtmlImageElement> {
+    let (sender, receiver) = oneshot::channel();
+    let sender = Rc::new(RefCell::new(Some(sender)));
+    let on_event = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        if let Some(sender) = sender.borrow_mut().take() {
+            // The receiver has been dropped only when the cmd has been dropped.
+            let _ = sender.send(event.type_() == "load");
+        }
+    }) as Box<dyn FnMut(web_sys::Event)>);
+
+    let image = web_sys::HtmlImageElement::new().expect("create favicon image");
+    // Cross-origin images without CORS would taint the canvas.
+    image.set_cross_origin(Some("anonymous"));
+    image.set_onload(Some(on_event.as_ref().unchecked_ref()));
+    image.set_onerror(Some(on_event.as_ref().unchecked_ref()));
+    image.set_src(src);
+
+    let loaded = receiver.await.unwrap_or_default();
+    image.set_onload(None);
+    image.set_onerror(None);
+    if loaded {
+        Some(image)
+    } else {
+        None
+    }
+}
+
+/// Draw the `badge` onto the `favicon` and return the PNG data URL.
+#[allow(deprecated)] // `set_fill_style_str` isn't available in the oldest supported `web-sys`.
+fn draw_badge(
+    badge: &FaviconBadge,
+    favicon: Option<&web_sys::HtmlImageElement>,
+) -> Result<String, JsValue> {
+    let size = f64::from(BADGE_CANVAS_SIZE);
+    let canvas: web_sys::HtmlCanvasElement =
+        util::document().create_element("canvas")?.unchecked_into();
+    canvas.set_width(BADGE_CANVAS_SIZE);
+    canvas.set_height(BADGE_CANVAS_SIZE);
+    let context = util::canvas_context_2d(&canvas);
+
+    if let Some(favicon) = favicon {
+        context.draw_image_with_html_image_element_and_dw_and_dh(favicon, 0., 0., size, size)?;
+    }
+
+    // Longer texts (e.g. `99+`) need a bigger badge.
+    let radius = if badge.text.chars().count() > 1 {
+        size * 0.4
+    } else {
+        size * 0.3
+    };
+    context.begin_path();
+    context.arc(
+        size - radius,
+        size - radius,
+        radius,
+        0.,
+        std::f64::consts::PI * 2.,
+    )?;
+    context.set_fill_style(&JsValue::from_str(&badge.background));
+    context.fill();
+
+    context.set_fill_style(&JsValue::from_str(&badge.color));
+    context.set_font(&format!("bold {}px sans-serif", (radius * 1.2).round()));
+    context.set_text_align("center");
+    context.set_text_baseline("middle");
+    context.fill_text_with_max_width(&badge.text, size - radius, size - radius, radius * 1.8)?;
+
+    canvas.to_data_url_with_type("image/png")
+}
+
+// ------ Theme color ------
+
+/// Set `<meta name="theme-color">` - the color of the browser UI around the page.
+/// Theme colors for media queries (with `media` attribute) aren't changed.
+pub async fn set_theme_color<Ms>(color: impl Into<String>, handler: impl FnOnce() -> Ms) -> Ms {
+    theme_color_meta()
+        .set_attribute("content", &color.into())
+        .expect("set theme color");
+    handler()
+}
+
+/// The current theme color; `None` when it isn't set.
+pub fn theme_color() -> Option<String> {
+    util::document()
+        .query_selector(THEME_COLOR_SELECTOR)
+        .expect("query theme color")?
+        .get_attribute("content")
+}
+
+fn theme_color_meta() -> web_sys::Element {
+    let document = util::document();
+    if let Some(meta) = document
+        .query_selector(THEME_COLOR_SELECTOR)
+        .expect("query theme color")
+    {
+        return meta;
+    }
+    let meta = document.create_element("meta").expect("create theme color");
+    meta.set_attribute("name", "theme-color")
+        .expect("set theme color name");
+    append_to_head(&meta);
+    meta
+}
+
+fn append_to_head(element: &web_sys::Element) {
+    util::document()
+        .query_selector("head")
+        .expect("query document head")
+        .expect("document head")
+        .append_child(element)
+        .expect("append to document head");
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn badge_text() {
+        assert_eq!(FaviconBadge::count(0).text, "");
+        assert_eq!(FaviconBadge::count(7).text, "7");
+        assert_eq!(FaviconBadge::count(120).text, "99+");
+    }
+
+    #[wasm_bindgen_test]
+    async fn favicon_is_replaced_and_restored() {
+        let link = favicon_link();
+        link.set_attribute("href", "/favicon.ico").unwrap();
+        link.remove_attribute(DATA_ORIGINAL_HREF).unwrap();
+
+        set_favicon("/unread.png", || ()).await;
+        assert_eq!(link.get_attribute("href").as_deref(), Some("/unread.png"));
+        set_favicon("/unread-2.png", || ()).await;
+        assert_eq!(original_favicon_href().as_deref(), Some("/favicon.ico"));
+
+        reset_favicon(|| ()).await;
+        assert_eq!(link.get_attribute("href").as_deref(), Some("/favicon.ico"));
+    }
+
+    #[wasm_bindgen_test]
+    async fn theme_color_is_set() {
+        set_theme_color("#123456", || ()).await;
+        set_theme_color("#abcdef", || ()).await;
+        assert_eq!(theme_color().as_deref(), Some("#abcdef"));
+        assert_eq!(
+            util::document()
+                .query_selector_all(THEME_COLOR_SELECTOR)
+                .unwrap()
+                .length(),
+            1
+        );
+    }
+}
@@ -0,0 +1,37 @@
+//! Navigate through the browser history.
+//!
+//! The navigation is asynchronous - `handler`'s `Msg` is sent right after the request,
+//! subscribe to `subs::HistoryNavigated` and `subs::UrlChanged` to react on the changed entry.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!orders.perform_cmd(cmds::history::back(|| Msg::NoOp));
+//! ```
+
+use crate::browser::util;
+use futures::future::Future;
+
+/// Go to the previous history entry. Nothing happens when there is no previous entry.
+pub fn back<Ms>(handler: impl FnOnce() -> Ms + Clone + 'static) -> impl Future<Output = Ms> {
+    go(-1, handler)
+}
+
+/// Go to the next history entry. Nothing happens when there is no next entry.
+pub fn forward<Ms>(handler: impl FnOnce() -> Ms + Clone + 'static) -> impl Future<Output = Ms> {
+    go(1, handler)
+}
+
+/// Move `delta` entries through the history - negative `delta` goes back.
+///
+/// _Note:_ `go(0, ..)` reloads the page.
+///
+/// # Panics
+///
+/// Panics when the browser refuses the navigation, e.g. in a sandboxed iframe.
+pub async fn go<Ms>(delta: i32, handler: impl FnOnce() -> Ms + Clone + 'static) -> Ms {
+    util::history()
+        .go_with_delta(delta)
+        .expect("history: go - failed");
+    handler()
+}
@@ -0,0 +1,117 @@
+//! Control `audio` and `video` elements - building blocks for custom players.
+//!
+//! All functions accept `ElRef` of any media element type; they're no-ops when the element
+//! doesn't exist. Subscribe to `subs::{MediaTimeUpdated, MediaPlaybackChanged, MediaBuffering, MediaEnded}`
+//! to render the player state.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::Play => orders.perform_cmd(cmds::media::play(&model.video, Msg::PlayResult)),
+//!Msg::PlayResult(Err(_)) => model.show_play_button = true,
+//!Msg::Pause => cmds::media::pause(&model.video),
+//!Msg::Seek(seconds) => cmds::media::seek(&model.video, seconds),
+//!Msg::TimeUpdated(subs::MediaTimeUpdated { current_time, .. }) => model.position = current_time,
+//! ```
+
+use crate::virtual_dom::ElRef;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// Start the playback.
+///
+/// It fails when the element doesn't exist, the source can't be played or the browser blocks
+/// the autoplay (`NotAllowedError`) - perform it from a user gesture handler or mute the media.
+pub async fn play<E: Clone + JsCast, Ms>(
+    el_ref: &ElRef<E>,
+    handler: impl FnOnce(Result<(), JsValue>) -> Ms + Clone + 'static,
+) -> Ms {
+    let media = media(el_ref);
+    let result = async {
+        let media = media.ok_or_else(|| JsValue::from("media: element doesn't exist"))?;
+        JsFuture::from(media.play()?).await.map(|_| ())
+    }
+    .await;
+    handler(result)
+}
+
+pub fn pause<E: Clone + JsCast>(el_ref: &ElRef<E>) {
+    if let Some(media) = media(el_ref) {
+        // `pause` never fails on media elements.
+        media.pause().ok();
+    }
+}
+
+/// Set the playback position in seconds.
+pub fn seek<E: Clone + JsCast>(el_ref: &ElRef<E>, seconds: f64) {
+    if let Some(media) = media(el_ref) {
+        media.set_current_time(seconds);
+    }
+}
+
+/// Set the volume - it's clamped to `0.0` - `1.0`.
+pub fn set_volume<E: Clone + JsCast>(el_ref: &ElRef<E>, volume: f64) {
+    if let Some(media) = media(el_ref) {
+        media.set_volume(volume.clamp(0., 1.));
+    }
+}
+
+pub fn set_muted<E: Clone + JsCast>(el_ref: &ElRef<E>, muted: bool) {
+    if let Some(media) = media(el_ref) {
+        media.set_muted(muted);
+    }
+}
+
+/// `1.0` is the normal speed.
+pub fn set_playback_rate<E: Clone + JsCast>(el_ref: &ElRef<E>, rate: f64) {
+    if let Some(media) = media(el_ref) {
+        media.set_playback_rate(rate);
+    }
+}
+
+// ------ MediaState ------
+
+/// Snapshot of the media element playback.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MediaState {
+    /// In seconds.
+    pub current_time: f64,
+    /// In seconds; `None` until the metadata are loaded.
+    /// It's `f64::INFINITY` for live streams.
+    pub duration: Option<f64>,
+    /// End of the buffered range containing `current_time` in seconds.
+    pub buffered_end: Option<f64>,
+    pub paused: bool,
+    pub ended: bool,
+    pub volume: f64,
+    pub muted: bool,
+}
+
+impl From<&web_sys::HtmlMediaElement> for MediaState {
+    fn from(media: &web_sys::HtmlMediaElement) -> Self {
+        let current_time = media.current_time();
+        let buffered = media.buffered();
+        let buffered_end = (0..buffered.length())
+            .filter_map(|index| Some((buffered.start(index).ok()?, buffered.end(index).ok()?)))
+            .find(|(start, end)| (*start..=*end).contains(&current_time))
+            .map(|(_, end)| end);
+        Self {
+            current_time,
+            duration: Some(media.duration()).filter(|duration| !duration.is_nan()),
+            buffered_end,
+            paused: media.paused(),
+            ended: media.ended(),
+            volume: media.volume(),
+            muted: media.muted(),
+        }
+    }
+}
+
+/// The current playback state; `None` when the element doesn't exist.
+pub fn state<E: Clone + JsCast>(el_ref: &ElRef<E>) -> Option<MediaState> {
+    media(el_ref).as_ref().map(MediaState::from)
+}
+
+fn media<E: Clone + JsCast>(el_ref: &ElRef<E>) -> Option<web_sys::HtmlMediaElement> {
+    el_ref.map_type::<web_sys::HtmlMediaElement>().get()
+}
@@ -0,0 +1,454 @@
+//! [Media Capture and Streams API](https://developer.mozilla.org/en-US/docs/Web/API/Media_Capture_and_Streams_API) -
+//! camera and microphone access for video chats, QR scanners, voice recorders, etc.
+//!
+//! The browser asks the user for the permission and it's available only in secure
+//! contexts (HTTPS or `localhost`).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::StartCamera => {
+//!    let constraints = MediaConstraints::default()
+//!        .video(VideoConstraints::default().facing_mode(FacingMode::Environment));
+//!    orders.perform_cmd(cmds::media_devices::get_user_media(&constraints, Msg::CameraStarted));
+//!}
+//!Msg::CameraStarted(Ok(stream)) => {
+//!    stream.attach(&model.video);
+//!    model.camera = Some(stream);
+//!}
+//!Msg::StopCamera => model.camera = None,
+//!...
+//!video![el_ref(&model.video), attrs! { At::AutoPlay => AtValue::None, At::PlaysInline => AtValue::None }]
+//! ```
+
+use crate::app::streams::EventStream;
+use crate::browser::util::window;
+use crate::virtual_dom::ElRef;
+use futures::future::Future;
+use futures::stream::{self, Stream, StreamExt};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+pub use web_sys::MediaDeviceKind;
+
+// ------ MediaConstraints ------
+
+/// Requested tracks - `None` means that the track isn't requested.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaConstraints {
+    pub audio: Option<AudioConstraints>,
+    pub video: Option<VideoConstraints>,
+}
+
+impl MediaConstraints {
+    pub fn audio(mut self, audio: AudioConstraints) -> Self {
+        self.audio = Some(audio);
+        self
+    }
+
+    pub fn video(mut self, video: VideoConstraints) -> Self {
+        self.video = Some(video);
+        self
+    }
+
+    fn to_raw(&self) -> Result<web_sys::MediaStreamConstraints, JsValue> {
+        let constraints = web_sys::MediaStreamConstraints::new();
+        match &self.audio {
+            Some(audio) => constraints.set_audio_media_track_constraints(&audio.to_raw()?),
+            None => constraints.set_audio_bool(false),
+        }
+        match &self.video {
+            Some(video) => constraints.set_video_media_track_constraints(&video.to_raw()?),
+            None => constraints.set_video_bool(false),
+        }
+        Ok(constraints)
+    }
+}
+
+/// `None` values are left to the browser.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AudioConstraints {
+    /// `MediaDevice::id` of the required microphone.
+    pub device_id: Option<String>,
+    pub echo_cancellation: Option<bool>,
+    pub noise_suppression: Option<bool>,
+}
+
+impl AudioConstraints {
+    pub fn device_id(mut self, device_id: impl Into<String>) -> Self {
+        self.device_id = Some(device_id.into());
+        self
+    }
+
+    pub const fn echo_cancellation(mut self, echo_cancellation: bool) -> Self {
+        self.echo_cancellation = Some(echo_cancellation);
+        self
+    }
+
+    pub const fn noise_suppression(mut self, noise_suppression: bool) -> Self {
+        self.noise_suppression = Some(noise_suppression);
+        self
+    }
+
+    fn to_raw(&self) -> Result<web_sys::MediaTrackConstraints, JsValue> {
+        let constraints = web_sys::MediaTrackConstraints::new();
+        if let Some(device_id) = &self.device_id {
+            constraints.set_device_id(&exact(device_id)?);
+        }
+        if let Some(echo_cancellation) = self.echo_cancellation {
+            constraints.set_echo_cancellation_bool(echo_cancellation);
+        }
+        if let Some(noise_suppression) = self.noise_suppression {
+            constraints.set_noise_suppression_bool(noise_suppression);
+        }
+        Ok(constraints)
+    }
+}
+
+/// `None` values are left to the browser.
+///
+/// Sizes and the frame rate are only ideal values - the browser chooses the closest supported ones.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VideoConstraints {
+    /// `MediaDevice::id` of the required camera.
+    pub device_id: Option<String>,
+    pub facing_mode: Option<FacingMode>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub frame_rate: Option<f64>,
+}
+
+impl VideoConstraints {
+    pub fn device_id(mut self, device_id: impl Into<String>) -> Self {
+        self.device_id = Some(device_id.into());
+        self
+    }
+
+    pub const fn facing_mode(mut self, facing_mode: FacingMode) -> Self {
+        self.facing_mode = Some(facing_mode);
+        self
+    }
+
+    pub const fn size(mut self, width: u32, height: u32) -> Self {
+        self.width = Some(width);
+        self.height = Some(height);
+        self
+    }
+
+    pub const fn frame_rate(mut self, frame_rate: f64) -> Self {
+        self.frame_rate = Some(frame_rate);
+        self
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn to_raw(&self) -> Result<web_sys::MediaTrackConstraints, JsValue> {
+        let constraints = web_sys::MediaTrackConstraints::new();
+        if let Some(device_id) = &self.device_id {
+            constraints.set_device_id(&exact(device_id)?);
+        }
+        if let Some(facing_mode) = self.facing_mode {
+            constraints.set_facing_mode_str(facing_mode.as_str());
+        }
+        if let Some(width) = self.width {
+            constraints.set_width_i32(width as i32);
+        }
+        if let Some(height) = self.height {
+            constraints.set_height_i32(height as i32);
+        }
+        if let Some(frame_rate) = self.frame_rate {
+            constraints.set_frame_rate_f64(frame_rate);
+        }
+        Ok(constraints)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FacingMode {
+    /// Front camera.
+    User,
+    /// Back camera.
+    Environment,
+}
+
+impl FacingMode {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Environment => "environment",
+        }
+    }
+}
+
+/// A bare device id is only a hint for the browser - `{ exact: id }` is required to choose the device.
+fn exact(value: &str) -> Result<JsValue, JsValue> {
+    let constraint = js_sys::Object::new();
+    js_sys::Reflect::set(&constraint, &"exact".into(), &value.into())?;
+    Ok(constraint.into())
+}
+
+// ------ get_user_media ------
+
+/// Request a camera and / or microphone stream.
+///
+/// It fails when the user denies the permission (`NotAllowedError`),
+/// no device satisfies the constraints (`OverconstrainedError`, `NotFoundError`)
+/// or the page isn't in a secure context.
+pub fn get_user_media<Ms>(
+    constraints: &MediaConstraints,
+    handler: impl FnOnce(Result<MediaStream, JsValue>) -> Ms + Clone + 'static,
+) -> impl Future<Output = Ms> {
+    let constraints = constraints.to_raw();
+    async move {
+        let result = async {
+            let promise = window()
+                .navigator()
+                .media_devices()?
+                .get_user_media_with_constraints(&constraints?)?;
+            let stream = JsFuture::from(promise).await?;
+            Ok(MediaStream {
+                raw: stream.unchecked_into(),
+            })
+        }
+        .await;
+        handler(result)
+    }
+}
+
+// ------ screen_capture ------
+
+/// Options for `screen_capture`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScreenCaptureOptions {
+    /// Capture also the tab or system audio (supported only by Chromium-based browsers).
+    pub audio: bool,
+    /// `device_id` and `facing_mode` are ignored.
+    pub video: VideoConstraints,
+}
+
+impl ScreenCaptureOptions {
+    pub const fn audio(mut self, audio: bool) -> Self {
+        self.audio = audio;
+        self
+    }
+
+    pub fn video(mut self, video: VideoConstraints) -> Self {
+        self.video = video;
+        self
+    }
+
+    fn to_raw(&self) -> Result<web_sys::DisplayMediaStreamConstraints, JsValue> {
+        let constraints = web_sys::DisplayMediaStreamConstraints::new();
+        constraints.set_audio_bool(self.audio);
+        let video = VideoConstraints {
+            device_id: None,
+            facing_mode: None,
+            ..self.video.clone()
+        };
+        constraints.set_video_media_track_constraints(&video.to_raw()?);
+        Ok(constraints)
+    }
+}
+
+/// Let the user choose a screen, window or tab to capture.
+///
+/// Listen for `MediaStream::ended` to know when the user stops sharing by the browser UI.
+///
+/// It fails when the user cancels the dialog (`NotAllowedError`) or the browser doesn't
+/// support the screen capture (mobile browsers). It has to be called from a user gesture
+/// handler (e.g. `click`) in some browsers.
+///
+/// # Example
+///
+/// ```rust,no_run
+///Msg::ShareScreen => {
+///    orders.perform_cmd(cmds::screen_capture(&ScreenCaptureOptions::default(), Msg::ScreenShared));
+///}
+///Msg::ScreenShared(Ok(stream)) => {
+///    model.sharing_ended = Some(orders.stream_with_handle(stream.ended(|| Msg::SharingStopped)));
+///    model.screen = Some(stream);
+///}
+///Msg::SharingStopped => model.screen = None,
+/// ```
+pub fn screen_capture<Ms>(
+    options: &ScreenCaptureOptions,
+    handler: impl FnOnce(Result<MediaStream, JsValue>) -> Ms + Clone + 'static,
+) -> impl Future<Output = Ms> {
+    let constraints = options.to_raw();
+    async move {
+        let result = async {
+            let promise = window()
+                .navigator()
+                .media_devices()?
+                .get_display_media_with_constraints(&constraints?)?;
+            let stream = JsFuture::from(promise).await?;
+            Ok(MediaStream {
+                raw: stream.unchecked_into(),
+            })
+        }
+        .await;
+        handler(result)
+    }
+}
+
+// ------ MediaStream ------
+
+/// Captured stream. Its tracks are stopped on drop - it turns off the camera light.
+pub struct MediaStream {
+    raw: web_sys::MediaStream,
+}
+
+impl std::fmt::Debug for MediaStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MediaStream({})", self.raw.id())
+    }
+}
+
+impl MediaStream {
+    /// Use it for APIs like `MediaRecorder` or `RTCPeerConnection::addTrack`.
+    pub const fn raw(&self) -> &web_sys::MediaStream {
+        &self.raw
+    }
+
+    /// Play the stream in the referenced `video` or `audio` element.
+    ///
+    /// Returns `false` when the element isn't in the DOM or it isn't a media element.
+    ///
+    /// _Note:_ Add `autoplay` and `playsinline` attributes to the element to play the stream on iOS.
+    pub fn attach<E: Clone + JsCast>(&self, media: &ElRef<E>) -> bool {
+        media
+            .get()
+            .and_then(|media| media.dyn_into::<web_sys::HtmlMediaElement>().ok())
+            .map(|media| media.set_src_object(Some(&self.raw)))
+            .is_some()
+    }
+
+    /// Stream one `Msg` when any track has been ended by the browser - e.g. the user has
+    /// stopped the screen sharing or unplugged the camera.
+    ///
+    /// Tracks stopped by the `MediaStream` drop are ignored.
+    pub fn ended<Ms>(
+        &self,
+        handler: impl FnOnce() -> Ms + Clone + 'static,
+    ) -> impl Stream<Item = Ms> {
+        let ended = tracks(&self.raw.get_tracks())
+            .map(|track| EventStream::<web_sys::Event>::new(&track, "ended"))
+            .collect::<Vec<_>>();
+        stream::select_all(ended)
+            .take(1)
+            .map(move |_| handler.clone()())
+    }
+
+    /// Mute / unmute the microphone without stopping it.
+    pub fn set_audio_enabled(&self, enabled: bool) {
+        for track in tracks(&self.raw.get_audio_tracks()) {
+            track.set_enabled(enabled);
+        }
+    }
+
+    /// Turn off / on the camera picture without stopping it.
+    pub fn set_video_enabled(&self, enabled: bool) {
+        for track in tracks(&self.raw.get_video_tracks()) {
+            track.set_enabled(enabled);
+        }
+    }
+}
+
+impl Drop for MediaStream {
+    fn drop(&mut self) {
+        for track in tracks(&self.raw.get_tracks()) {
+            track.stop();
+        }
+    }
+}
+
+fn tracks(tracks: &js_sys::Array) -> impl Iterator<Item = web_sys::MediaStreamTrack> + '_ {
+    tracks.iter().map(JsCast::unchecked_into)
+}
+
+// ------ enumerate_devices ------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaDevice {
+    /// Use it in `AudioConstraints::device_id` or `VideoConstraints::device_id`.
+    pub id: String,
+    pub kind: MediaDeviceKind,
+    /// Labels are empty until the user grants a media permission.
+    pub label: String,
+    /// Devices with the same group id belong to the same physical device (e.g. a headset).
+    pub group_id: String,
+}
+
+/// List available cameras, microphones and speakers.
+pub async fn enumerate_devices<Ms>(
+    handler: impl FnOnce(Result<Vec<MediaDevice>, JsValue>) -> Ms + Clone + 'static,
+) -> Ms {
+    let result = async {
+        let promise = window().navigator().media_devices()?.enumerate_devices()?;
+        let devices = JsFuture::from(promise).await?;
+        Ok(js_sys::Array::from(&devices)
+            .iter()
+            .map(|device| {
+                let device = device.unchecked_into::<web_sys::MediaDeviceInfo>();
+                MediaDevice {
+                    id: device.device_id(),
+                    kind: device.kind(),
+                    label: device.label(),
+                    group_id: device.group_id(),
+                }
+            })
+            .collect())
+    }
+    .await;
+    handler(result)
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn get(target: &JsValue, key: &str) -> JsValue {
+        js_sys::Reflect::get(target, &key.into()).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn raw_constraints() {
+        let constraints = MediaConstraints::default()
+            .video(
+                VideoConstraints::default()
+                    .device_id("camera")
+                    .facing_mode(FacingMode::Environment)
+                    .size(640, 480),
+            )
+            .to_raw()
+            .unwrap();
+
+        assert_eq!(get(&constraints, "audio"), JsValue::FALSE);
+        let video = get(&constraints, "video");
+        assert_eq!(get(&get(&video, "deviceId"), "exact"), "camera");
+        assert_eq!(get(&video, "facingMode"), "environment");
+        assert_eq!(get(&video, "width"), 640);
+        assert!(get(&video, "frameRate").is_undefined());
+    }
+
+    #[wasm_bindgen_test]
+    fn raw_screen_capture_options() {
+        let options = ScreenCaptureOptions::default()
+            .audio(true)
+            .video(
+                VideoConstraints::default()
+                    .device_id("camera")
+                    .frame_rate(30.),
+            )
+            .to_raw()
+            .unwrap();
+
+        assert_eq!(get(&options, "audio"), JsValue::TRUE);
+        let video = get(&options, "video");
+        assert!(get(&video, "deviceId").is_undefined());
+        assert_eq!(get(&video, "frameRate"), 30.);
+    }
+}
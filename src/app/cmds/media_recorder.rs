@@ -0,0 +1,312 @@
+//! [MediaStream Recording API](https://developer.mozilla.org/en-US/docs/Web/API/MediaStream_Recording_API) -
+//! record audio / video from `media_devices::MediaStream`.
+//!
+//! Listen for the recorded data by `MediaRecorder::chunks` (e.g. to upload it while recording)
+//! or `MediaRecorder::recording` (one `Blob` after the stop), then call `MediaRecorder::start`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::StartRecording => {
+//!    let options = RecorderOptions::default().mime_types(&["video/webm;codecs=vp9", "video/webm", "video/mp4"]);
+//!    let recorder = MediaRecorder::new(model.camera.as_ref().unwrap(), &options).unwrap();
+//!    orders.perform_cmd(recorder.recording(Msg::Recorded));
+//!    recorder.start().unwrap();
+//!    model.recorder = Some(recorder);
+//!}
+//!Msg::StopRecording => model.recorder.as_ref().unwrap().stop().unwrap(),
+//!Msg::Recorded(Ok(blob)) => {
+//!    model.recorder = None;
+//!    model.video_url = web_sys::Url::create_object_url_with_blob(&blob).ok();
+//!}
+//! ```
+
+use super::media_devices::MediaStream;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver};
+use futures::future::Future;
+use futures::stream::{Stream, StreamExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+pub use web_sys::RecordingState;
+
+/// The first MIME type supported by the browser's `MediaRecorder`.
+///
+/// Browsers support different containers and codecs - e.g. Safari records only `video/mp4`.
+pub fn supported_mime_type<'a>(candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .copied()
+        .find(|mime_type| web_sys::MediaRecorder::is_type_supported(mime_type))
+}
+
+// ------ RecorderOptions ------
+
+/// `None` values are left to the browser.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecorderOptions {
+    pub mime_type: Option<String>,
+    pub audio_bits_per_second: Option<u32>,
+    pub video_bits_per_second: Option<u32>,
+    /// Chunk duration in milliseconds. There is only one chunk after the stop when it's `None`.
+    pub timeslice: Option<u32>,
+}
+
+impl RecorderOptions {
+    pub fn mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    /// Use the first MIME type supported by the browser (see `supported_mime_type`).
+    /// The browser chooses the type when none of them is supported.
+    pub fn mime_types(mut self, candidates: &[&str]) -> Self {
+        self.mime_type = supported_mime_type(candidates).map(ToOwned::to_owned);
+        self
+    }
+
+    pub const fn audio_bits_per_second(mut self, bits_per_second: u32) -> Self {
+        self.audio_bits_per_second = Some(bits_per_second);
+        self
+    }
+
+    pub const fn video_bits_per_second(mut self, bits_per_second: u32) -> Self {
+        self.video_bits_per_second = Some(bits_per_second);
+        self
+    }
+
+    pub const fn timeslice(mut self, timeslice: u32) -> Self {
+        self.timeslice = Some(timeslice);
+        self
+    }
+
+    fn to_raw(&self) -> web_sys::MediaRecorderOptions {
+        let options = web_sys::MediaRecorderOptions::new();
+        if let Some(mime_type) = &self.mime_type {
+            options.set_mime_type(mime_type);
+        }
+        if let Some(bits_per_second) = self.audio_bits_per_second {
+            options.set_audio_bits_per_second(bits_per_second);
+        }
+        if let Some(bits_per_second) = self.video_bits_per_second {
+            options.set_video_bits_per_second(bits_per_second);
+        }
+        options
+    }
+}
+
+// ------ MediaRecorder ------
+
+/// Recorder of a `MediaStream`. The recording is stopped on drop.
+pub struct MediaRecorder {
+    raw: web_sys::MediaRecorder,
+    timeslice: Option<u32>,
+}
+
+impl std::fmt::Debug for MediaRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MediaRecorder({:?})", self.raw.state())
+    }
+}
+
+impl MediaRecorder {
+    /// Create an inactive recorder.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when the browser doesn't support `MediaRecorder` or the MIME type.
+    pub fn new(stream: &MediaStream, options: &RecorderOptions) -> Result<Self, JsValue> {
+        Ok(Self {
+            raw: web_sys::MediaRecorder::new_with_media_stream_and_media_recorder_options(
+                stream.raw(),
+                &options.to_raw(),
+            )?,
+            timeslice: options.timeslice,
+        })
+    }
+
+    /// Start the recording.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when the recorder isn't inactive or the stream is inactive.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn start(&self) -> Result<(), JsValue> {
+        self.timeslice.map_or_else(
+            || self.raw.start(),
+            |timeslice| self.raw.start_with_time_slice(timeslice as i32),
+        )
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when the recorder is inactive.
+    pub fn pause(&self) -> Result<(), JsValue> {
+        self.raw.pause()
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when the recorder is inactive.
+    pub fn resume(&self) -> Result<(), JsValue> {
+        self.raw.resume()
+    }
+
+    /// Stop the recording - the last chunk is emitted and streams created by `chunks` end.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when the recorder is already inactive.
+    pub fn stop(&self) -> Result<(), JsValue> {
+        self.raw.stop()
+    }
+
+    pub fn state(&self) -> RecordingState {
+        self.raw.state()
+    }
+
+    /// MIME type of the recorded data chosen by the browser.
+    pub fn mime_type(&self) -> String {
+        self.raw.mime_type()
+    }
+
+    /// Stream recorded chunks until the recording is stopped.
+    pub fn chunks<Ms>(
+        &self,
+        handler: impl FnOnce(web_sys::Blob) -> Ms + Clone + 'static,
+    ) -> impl Stream<Item = Ms> {
+        RecorderStream::new(&self.raw).map(move |chunk| handler.clone()(chunk))
+    }
+
+    /// Merge all recorded chunks into one `Blob` when the recording is stopped.
+    pub fn recording<Ms>(
+        &self,
+        handler: impl FnOnce(Result<web_sys::Blob, JsValue>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let raw = self.raw.clone();
+        let chunks = RecorderStream::new(&self.raw);
+        async move {
+            let chunks = chunks
+                .fold(js_sys::Array::new(), |chunks, chunk| {
+                    chunks.push(&chunk);
+                    futures::future::ready(chunks)
+                })
+                .await;
+            let options = web_sys::BlobPropertyBag::new();
+            options.set_type(&raw.mime_type());
+            handler(web_sys::Blob::new_with_blob_sequence_and_options(
+                &chunks, &options,
+            ))
+        }
+    }
+}
+
+impl Drop for MediaRecorder {
+    fn drop(&mut self) {
+        if self.raw.state() != RecordingState::Inactive {
+            self.raw.stop().expect("stop media recorder");
+        }
+    }
+}
+
+// ------ RecorderStream ------
+
+enum RecorderEvent {
+    Chunk(web_sys::Blob),
+    Stop,
+}
+
+type Callback = Closure<dyn Fn(JsValue)>;
+
+/// Recorded chunks; it ends on the recorder stop.
+///
+/// `dataavailable` and `stop` events share one channel to keep their order.
+struct RecorderStream {
+    recorder: web_sys::MediaRecorder,
+    callbacks: Vec<(&'static str, Callback)>,
+    receiver: UnboundedReceiver<RecorderEvent>,
+    finished: bool,
+}
+
+impl RecorderStream {
+    fn new(recorder: &web_sys::MediaRecorder) -> Self {
+        let (sender, receiver) = unbounded();
+
+        let data_sender = sender.clone();
+        let on_data = Closure::wrap(Box::new(move |event: JsValue| {
+            let chunk = event.unchecked_into::<web_sys::BlobEvent>().data();
+            // Browsers emit empty chunks e.g. when the recording is paused.
+            if let Some(chunk) = chunk.filter(|chunk| chunk.size() > 0.) {
+                data_sender.unbounded_send(RecorderEvent::Chunk(chunk)).ok();
+            }
+        }) as Box<dyn Fn(JsValue)>);
+        let on_stop = Closure::wrap(Box::new(move |_| {
+            sender.unbounded_send(RecorderEvent::Stop).ok();
+        }) as Box<dyn Fn(JsValue)>);
+
+        let callbacks = vec![("dataavailable", on_data), ("stop", on_stop)];
+        for (trigger, callback) in &callbacks {
+            recorder
+                .add_event_listener_with_callback(trigger, callback.as_ref().unchecked_ref())
+                .expect("add media recorder listener");
+        }
+        Self {
+            recorder: recorder.clone(),
+            callbacks,
+            receiver,
+            finished: false,
+        }
+    }
+}
+
+impl Stream for RecorderStream {
+    type Item = web_sys::Blob;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        match Stream::poll_next(Pin::new(&mut self.receiver), cx) {
+            Poll::Ready(Some(RecorderEvent::Chunk(chunk))) => Poll::Ready(Some(chunk)),
+            Poll::Ready(_) => {
+                self.finished = true;
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for RecorderStream {
+    fn drop(&mut self) {
+        for (trigger, callback) in &self.callbacks {
+            self.recorder
+                .remove_event_listener_with_callback(trigger, callback.as_ref().unchecked_ref())
+                .expect("remove media recorder listener");
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn negotiate_mime_type() {
+        assert_eq!(supported_mime_type(&["video/unknown-format"]), None);
+
+        let options = RecorderOptions::default().mime_types(&[
+            "video/unknown-format",
+            "video/webm",
+            "video/mp4",
+        ]);
+        assert!(options
+            .mime_type
+            .map_or(true, |mime_type| mime_type.starts_with("video/")));
+    }
+}
@@ -0,0 +1,105 @@
+//! Typed [`postMessage`](https://developer.mozilla.org/en-US/docs/Web/API/Window/postMessage)
+//! to the parent window or to embedded iframes.
+//!
+//! Messages are serialized with `serde` and posted as plain JS objects. Subscribe
+//! to `subs::WindowMessage` to receive them.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!// In the embedded app.
+//!Msg::Resized(height) => {
+//!    cmds::messaging::post_to_parent(&WidgetEvent::Height(height), "https://host.example.com")
+//!        .expect("post message");
+//!}
+//!// In the host app.
+//!Msg::SendTheme => {
+//!    cmds::messaging::post_to_frame(&model.widget, &HostCommand::Theme(Theme::Dark), "https://widget.example.com")
+//!        .expect("post message");
+//!}
+//! ```
+
+use crate::browser::util::window;
+use crate::virtual_dom::ElRef;
+use serde::{de::DeserializeOwned, Serialize};
+use std::rc::Rc;
+use wasm_bindgen::{JsCast, JsValue};
+
+// ------ MessageError ------
+
+#[derive(Debug, Clone)]
+pub enum MessageError {
+    /// The page isn't embedded or the iframe doesn't exist (or isn't loaded yet).
+    MissingTarget,
+    SendError(JsValue),
+    /// Message data can't be converted to the requested type.
+    DataError(&'static str),
+    SerdeError(Rc<serde_json::Error>),
+}
+
+// ------ post_to_parent / post_to_frame ------
+
+/// Post `message` to the window embedding the current page.
+///
+/// `target_origin` is the expected origin of the parent - the browser drops the message
+/// when it doesn't match. Use `"*"` only for messages without sensitive data.
+///
+/// # Errors
+///
+/// Returns `MessageError::MissingTarget` when the page isn't embedded in an iframe
+/// and other variants when serialization or posting fails.
+pub fn post_to_parent<T: Serialize + ?Sized>(
+    message: &T,
+    target_origin: &str,
+) -> Result<(), MessageError> {
+    let window = window();
+    let parent = window
+        .parent()
+        .map_err(MessageError::SendError)?
+        .filter(|parent| parent != &window)
+        .ok_or(MessageError::MissingTarget)?;
+    post(&parent, message, target_origin)
+}
+
+/// Post `message` to the window of the referenced `iframe`.
+///
+/// `target_origin` is the expected origin of the iframe page - the browser drops the message
+/// when it doesn't match. Use `"*"` only for messages without sensitive data.
+///
+/// # Errors
+///
+/// Returns `MessageError::MissingTarget` when the iframe doesn't exist
+/// and other variants when serialization or posting fails.
+pub fn post_to_frame<E: Clone + JsCast, T: Serialize + ?Sized>(
+    iframe: &ElRef<E>,
+    message: &T,
+    target_origin: &str,
+) -> Result<(), MessageError> {
+    let content_window = iframe
+        .map_type::<web_sys::HtmlIFrameElement>()
+        .get()
+        .and_then(|iframe| iframe.content_window())
+        .ok_or(MessageError::MissingTarget)?;
+    post(&content_window, message, target_origin)
+}
+
+pub(crate) fn post<T: Serialize + ?Sized>(
+    target: &web_sys::Window,
+    message: &T,
+    target_origin: &str,
+) -> Result<(), MessageError> {
+    let json =
+        serde_json::to_string(message).map_err(|error| MessageError::SerdeError(Rc::new(error)))?;
+    let message = js_sys::JSON::parse(&json).map_err(MessageError::SendError)?;
+    target
+        .post_message(&message, target_origin)
+        .map_err(MessageError::SendError)
+}
+
+pub(crate) fn deserialize<T: DeserializeOwned>(data: &JsValue) -> Result<T, MessageError> {
+    let json = js_sys::JSON::stringify(data)
+        .ok()
+        .and_then(|json| json.as_string())
+        .ok_or(MessageError::DataError("message data can't be stringified"))?;
+    serde_json::from_str(&json).map_err(|error| MessageError::SerdeError(Rc::new(error)))
+}
@@ -0,0 +1,62 @@
+//! [Screen Orientation API](https://developer.mozilla.org/en-US/docs/Web/API/Screen_Orientation_API) -
+//! lock the orientation for specific views (video player, game, ...).
+//!
+//! Subscribe to `subs::OrientationChanged` to adapt layouts.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!orders.perform_cmd(cmds::orientation::lock(OrientationLockType::Landscape, Msg::OrientationLocked));
+//!...
+//!cmds::orientation::unlock();
+//! ```
+
+use crate::browser::util::window;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+pub use web_sys::{OrientationLockType, OrientationType};
+
+/// Lock the screen orientation.
+///
+/// Most mobile browsers allow locking only in the fullscreen mode (see `cmds::fullscreen`)
+/// and desktop browsers don't support it at all.
+pub async fn lock<Ms>(
+    orientation: OrientationLockType,
+    handler: impl FnOnce(Result<(), JsValue>) -> Ms + Clone + 'static,
+) -> Ms {
+    let result = async {
+        let promise = screen_orientation()?.lock(orientation)?;
+        JsFuture::from(promise).await.map(|_| ())
+    }
+    .await;
+    handler(result)
+}
+
+/// Unlock the orientation locked by `lock`.
+///
+/// # Errors
+///
+/// Returns error when the browser doesn't support the Screen Orientation API.
+pub fn unlock() -> Result<(), JsValue> {
+    screen_orientation()?.unlock()
+}
+
+/// The current orientation and its angle in degrees.
+///
+/// # Errors
+///
+/// Returns error when the browser doesn't support the Screen Orientation API.
+pub fn current() -> Result<(OrientationType, u16), JsValue> {
+    let orientation = screen_orientation()?;
+    Ok((orientation.type_()?, orientation.angle()?))
+}
+
+pub(crate) fn screen_orientation() -> Result<web_sys::ScreenOrientation, JsValue> {
+    let screen = window().screen()?;
+    // Older Safari versions don't support `screen.orientation`.
+    if js_sys::Reflect::has(&screen, &"orientation".into())? {
+        Ok(screen.orientation())
+    } else {
+        Err("orientation: Screen Orientation API isn't supported".into())
+    }
+}
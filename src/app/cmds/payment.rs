@@ -0,0 +1,531 @@
+//! [Payment Request API](https://developer.mozilla.org/en-US/docs/Web/API/Payment_Request_API) -
+//! let the browser collect payment, shipping and contact details for checkouts.
+//!
+//! The lifecycle: create a `PaymentRequest`, `show` it from a user gesture handler,
+//! process the returned `PaymentResponse` on your server and `complete` it to close the sheet.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::Checkout => {
+//!    let methods = [PaymentMethod::new("https://google.com/pay").data(model.google_pay_config.clone())];
+//!    let details = PaymentDetails::new(PaymentItem::new("Total", Amount::new("EUR", "42.00")));
+//!    let request = PaymentRequest::new(&methods, &details, &PaymentOptions::default()).unwrap();
+//!    orders.perform_cmd(request.show(Msg::PaymentResponded));
+//!}
+//!Msg::PaymentResponded(Ok(response)) => {
+//!    orders.perform_cmd(charge(response.details.clone()).map(|charged| Msg::Charged(charged, response)));
+//!}
+//!Msg::Charged(charged, response) => {
+//!    let result = if charged.is_ok() { PaymentComplete::Success } else { PaymentComplete::Fail };
+//!    orders.perform_cmd(response.complete(result, |_| Msg::PaymentCompleted));
+//!}
+//!Msg::PaymentResponded(Err(PaymentError::Cancelled)) => (),
+//! ```
+
+use futures::future::Future;
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+pub use web_sys::PaymentComplete;
+
+mod raw {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(extends = web_sys::EventTarget)]
+        #[derive(Clone)]
+        pub type PaymentRequest;
+
+        #[wasm_bindgen(constructor, catch)]
+        pub fn new(
+            method_data: &JsValue,
+            details: &JsValue,
+            options: &JsValue,
+        ) -> Result<PaymentRequest, JsValue>;
+
+        #[wasm_bindgen(method, catch)]
+        pub fn show(this: &PaymentRequest) -> Result<js_sys::Promise, JsValue>;
+
+        #[wasm_bindgen(method)]
+        pub fn abort(this: &PaymentRequest) -> js_sys::Promise;
+
+        #[wasm_bindgen(method, js_name = canMakePayment)]
+        pub fn can_make_payment(this: &PaymentRequest) -> js_sys::Promise;
+
+        #[wasm_bindgen(method, getter, js_name = shippingOption)]
+        pub fn shipping_option(this: &PaymentRequest) -> Option<String>;
+
+        #[wasm_bindgen(method, getter, js_name = shippingAddress)]
+        pub fn shipping_address(this: &PaymentRequest) -> JsValue;
+
+        pub type PaymentRequestUpdateEvent;
+
+        #[wasm_bindgen(method, js_name = updateWith)]
+        pub fn update_with(this: &PaymentRequestUpdateEvent, details: &JsValue);
+    }
+}
+
+// ------ PaymentError ------
+
+#[derive(Debug, Clone)]
+pub enum PaymentError {
+    /// The browser doesn't support the Payment Request API or the page isn't in a secure context.
+    Unsupported,
+    /// None of the payment methods is supported.
+    NotSupported,
+    /// The user has closed the payment sheet or `abort` has been called.
+    Cancelled,
+    /// Invalid methods or details, e.g. a negative total.
+    InvalidData(Rc<serde_json::Error>),
+    Failed(JsValue),
+}
+
+impl From<JsValue> for PaymentError {
+    fn from(error: JsValue) -> Self {
+        let name = js_sys::Reflect::get(&error, &"name".into())
+            .ok()
+            .and_then(|name| name.as_string());
+        match name.as_deref() {
+            Some("NotSupportedError") => Self::NotSupported,
+            Some("AbortError") => Self::Cancelled,
+            _ => Self::Failed(error),
+        }
+    }
+}
+
+// ------ Request data ------
+
+/// A payment method, e.g. `"https://google.com/pay"` or `"https://apple.com/apple-pay"`
+/// with its method-specific `data`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentMethod {
+    pub supported_methods: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl PaymentMethod {
+    pub fn new(supported_methods: impl Into<String>) -> Self {
+        Self {
+            supported_methods: supported_methods.into(),
+            data: None,
+        }
+    }
+
+    pub fn data(mut self, data: serde_json::Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+/// Monetary amount - `value` is a decimal string like `"42.00"`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Amount {
+    /// ISO 4217 currency code, e.g. `"EUR"`.
+    pub currency: String,
+    pub value: String,
+}
+
+impl Amount {
+    pub fn new(currency: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            currency: currency.into(),
+            value: value.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PaymentItem {
+    pub label: String,
+    pub amount: Amount,
+    /// The amount isn't final yet (e.g. shipping before the address is known).
+    pub pending: bool,
+}
+
+impl PaymentItem {
+    pub fn new(label: impl Into<String>, amount: Amount) -> Self {
+        Self {
+            label: label.into(),
+            amount,
+            pending: false,
+        }
+    }
+
+    pub const fn pending(mut self) -> Self {
+        self.pending = true;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ShippingOption {
+    pub id: String,
+    pub label: String,
+    pub amount: Amount,
+    pub selected: bool,
+}
+
+impl ShippingOption {
+    pub fn new(id: impl Into<String>, label: impl Into<String>, amount: Amount) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            amount,
+            selected: false,
+        }
+    }
+
+    pub const fn selected(mut self) -> Self {
+        self.selected = true;
+        self
+    }
+}
+
+/// What the user pays for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentDetails {
+    pub total: PaymentItem,
+    pub display_items: Vec<PaymentItem>,
+    /// Required when `PaymentOptions::request_shipping` is set;
+    /// an empty list means that the shipping address isn't supported.
+    pub shipping_options: Vec<ShippingOption>,
+    /// Error displayed in the payment sheet, e.g. after an invalid shipping address.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl PaymentDetails {
+    pub const fn new(total: PaymentItem) -> Self {
+        Self {
+            total,
+            display_items: Vec::new(),
+            shipping_options: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub fn display_items(mut self, items: Vec<PaymentItem>) -> Self {
+        self.display_items = items;
+        self
+    }
+
+    pub fn shipping_options(mut self, options: Vec<ShippingOption>) -> Self {
+        self.shipping_options = options;
+        self
+    }
+
+    pub fn error(mut self, error: impl Into<String>) -> Self {
+        self.error = Some(error.into());
+        self
+    }
+}
+
+/// Which details the browser should collect.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentOptions {
+    pub request_payer_name: bool,
+    pub request_payer_email: bool,
+    pub request_payer_phone: bool,
+    pub request_shipping: bool,
+}
+
+// ------ PaymentRequest ------
+
+type DetailsUpdater<T> = Rc<dyn Fn(T) -> PaymentDetails>;
+
+/// A payment request; it can be shown only once.
+#[derive(Clone)]
+pub struct PaymentRequest {
+    raw: raw::PaymentRequest,
+    shipping_option_changed: Option<DetailsUpdater<String>>,
+    shipping_address_changed: Option<DetailsUpdater<ShippingAddress>>,
+}
+
+impl std::fmt::Debug for PaymentRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PaymentRequest")
+    }
+}
+
+impl PaymentRequest {
+    /// # Errors
+    ///
+    /// Returns error when the API isn't supported or the details are invalid.
+    pub fn new(
+        methods: &[PaymentMethod],
+        details: &PaymentDetails,
+        options: &PaymentOptions,
+    ) -> Result<Self, PaymentError> {
+        if !js_sys::Reflect::has(&js_sys::global(), &"PaymentRequest".into()).unwrap_or_default() {
+            return Err(PaymentError::Unsupported);
+        }
+        Ok(Self {
+            raw: raw::PaymentRequest::new(&to_js(methods)?, &to_js(details)?, &to_js(options)?)?,
+            shipping_option_changed: None,
+            shipping_address_changed: None,
+        })
+    }
+
+    /// Recalculate details (e.g. the total) when the user selects another shipping option.
+    /// `update` receives `ShippingOption::id`.
+    pub fn on_shipping_option_change(
+        mut self,
+        update: impl Fn(String) -> PaymentDetails + 'static,
+    ) -> Self {
+        self.shipping_option_changed = Some(Rc::new(update));
+        self
+    }
+
+    /// Recalculate details (e.g. available shipping options) when the user changes the address.
+    /// Set `PaymentDetails::error` to reject the address.
+    pub fn on_shipping_address_change(
+        mut self,
+        update: impl Fn(ShippingAddress) -> PaymentDetails + 'static,
+    ) -> Self {
+        self.shipping_address_changed = Some(Rc::new(update));
+        self
+    }
+
+    /// The user has a supported payment method ready, e.g. a card saved in Google Pay.
+    pub fn can_make_payment<Ms>(
+        &self,
+        handler: impl FnOnce(bool) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let promise = self.raw.can_make_payment();
+        async move {
+            let can_make_payment = JsFuture::from(promise)
+                .await
+                .ok()
+                .and_then(|can| can.as_bool())
+                .unwrap_or_default();
+            handler(can_make_payment)
+        }
+    }
+
+    /// Show the payment sheet. It has to be performed in a user gesture handler.
+    pub fn show<Ms>(
+        &self,
+        handler: impl FnOnce(Result<PaymentResponse, PaymentError>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let request = self.clone();
+        async move {
+            let result = async {
+                let raw = request.raw.clone();
+                let _listeners = request.listen();
+                let response = JsFuture::from(raw.show()?).await?;
+                Ok(PaymentResponse::new(response.unchecked_into()))
+            }
+            .await;
+            handler(result)
+        }
+    }
+
+    /// Close the payment sheet - `show` resolves with `PaymentError::Cancelled`.
+    pub fn abort(&self) {
+        let _ = self.raw.abort();
+    }
+
+    /// Listeners calling the details updaters while the sheet is open.
+    fn listen(&self) -> Vec<UpdateListener> {
+        let mut listeners = Vec::new();
+        if let Some(update) = &self.shipping_option_changed {
+            let (raw, update) = (self.raw.clone(), Rc::clone(update));
+            listeners.push(UpdateListener::new(
+                &self.raw,
+                "shippingoptionchange",
+                move || raw.shipping_option().map(|option| update(option)),
+            ));
+        }
+        if let Some(update) = &self.shipping_address_changed {
+            let (raw, update) = (self.raw.clone(), Rc::clone(update));
+            listeners.push(UpdateListener::new(
+                &self.raw,
+                "shippingaddresschange",
+                move || from_js(&raw.shipping_address()).map(|address| update(address)),
+            ));
+        }
+        listeners
+    }
+}
+
+struct UpdateListener {
+    target: web_sys::EventTarget,
+    trigger: &'static str,
+    callback: Closure<dyn Fn(JsValue)>,
+}
+
+impl UpdateListener {
+    fn new(
+        request: &raw::PaymentRequest,
+        trigger: &'static str,
+        details: impl Fn() -> Option<PaymentDetails> + 'static,
+    ) -> Self {
+        let callback = Closure::wrap(Box::new(move |event: JsValue| {
+            // `updateWith` has to be called synchronously in the event handler.
+            if let Some(details) = details().and_then(|details| to_js(&details).ok()) {
+                event
+                    .unchecked_into::<raw::PaymentRequestUpdateEvent>()
+                    .update_with(&details);
+            }
+        }) as Box<dyn Fn(JsValue)>);
+        request
+            .add_event_listener_with_callback(trigger, callback.as_ref().unchecked_ref())
+            .expect("add payment request listener");
+        Self {
+            target: request.clone().into(),
+            trigger,
+            callback,
+        }
+    }
+}
+
+impl Drop for UpdateListener {
+    fn drop(&mut self) {
+        self.target
+            .remove_event_listener_with_callback(
+                self.trigger,
+                self.callback.as_ref().unchecked_ref(),
+            )
+            .expect("remove payment request listener");
+    }
+}
+
+// ------ PaymentResponse ------
+
+/// Details provided by the user. Call `complete` after processing the payment.
+#[derive(Clone)]
+pub struct PaymentResponse {
+    raw: web_sys::PaymentResponse,
+    pub method_name: String,
+    /// Method-specific data, e.g. a payment token for your payment processor.
+    pub details: serde_json::Value,
+    pub payer_name: Option<String>,
+    pub payer_email: Option<String>,
+    pub payer_phone: Option<String>,
+    /// `ShippingOption::id`.
+    pub shipping_option: Option<String>,
+    pub shipping_address: Option<ShippingAddress>,
+}
+
+impl std::fmt::Debug for PaymentResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PaymentResponse")
+            .field("method_name", &self.method_name)
+            .field("details", &self.details)
+            .field("payer_name", &self.payer_name)
+            .field("payer_email", &self.payer_email)
+            .field("payer_phone", &self.payer_phone)
+            .field("shipping_option", &self.shipping_option)
+            .field("shipping_address", &self.shipping_address)
+            .finish()
+    }
+}
+
+impl PaymentResponse {
+    fn new(raw: web_sys::PaymentResponse) -> Self {
+        Self {
+            method_name: raw.method_name(),
+            details: from_js(&raw.details()).unwrap_or(serde_json::Value::Null),
+            payer_name: raw.payer_name(),
+            payer_email: raw.payer_email(),
+            payer_phone: raw.payer_phone(),
+            shipping_option: raw.shipping_option(),
+            shipping_address: raw.shipping_address().and_then(|address| from_js(&address)),
+            raw,
+        }
+    }
+
+    /// Close the payment sheet with the result of the payment processing.
+    pub fn complete<Ms>(
+        &self,
+        result: PaymentComplete,
+        handler: impl FnOnce(Result<(), JsValue>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let promise = self.raw.complete_with_result(result);
+        async move { handler(JsFuture::from(promise).await.map(|_| ())) }
+    }
+}
+
+// ------ ShippingAddress ------
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShippingAddress {
+    /// ISO 3166-1 alpha-2 code, e.g. `"CZ"`.
+    pub country: String,
+    pub address_line: Vec<String>,
+    pub region: String,
+    pub city: String,
+    pub dependent_locality: String,
+    pub postal_code: String,
+    pub sorting_code: String,
+    pub organization: String,
+    pub recipient: String,
+    pub phone: String,
+}
+
+// ------ Helpers ------
+
+fn to_js<T: Serialize + ?Sized>(value: &T) -> Result<JsValue, PaymentError> {
+    let json =
+        serde_json::to_string(value).map_err(|error| PaymentError::InvalidData(Rc::new(error)))?;
+    Ok(js_sys::JSON::parse(&json)?)
+}
+
+fn from_js<T: serde::de::DeserializeOwned>(value: &JsValue) -> Option<T> {
+    let json = js_sys::JSON::stringify(value).ok()?.as_string()?;
+    serde_json::from_str(&json).ok()
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn serialize_details() {
+        let details = PaymentDetails::new(PaymentItem::new("Total", Amount::new("EUR", "42.00")))
+            .shipping_options(vec![ShippingOption::new(
+                "standard",
+                "Standard",
+                Amount::new("EUR", "0.00"),
+            )
+            .selected()]);
+        assert_eq!(
+            serde_json::to_value(&details).unwrap(),
+            serde_json::json!({
+                "total": { "label": "Total", "amount": { "currency": "EUR", "value": "42.00" }, "pending": false },
+                "displayItems": [],
+                "shippingOptions": [{
+                    "id": "standard",
+                    "label": "Standard",
+                    "amount": { "currency": "EUR", "value": "0.00" },
+                    "selected": true
+                }]
+            })
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn parse_shipping_address() {
+        let address = js_sys::JSON::parse(
+            r#"{ "country": "CZ", "addressLine": ["Václavské náměstí 1"], "city": "Praha" }"#,
+        )
+        .unwrap();
+        let address = from_js::<ShippingAddress>(&address).unwrap();
+        assert_eq!(address.country, "CZ");
+        assert_eq!(address.address_line, vec!["Václavské náměstí 1"]);
+        assert_eq!(address.postal_code, "");
+    }
+}
@@ -0,0 +1,125 @@
+//! [Picture-in-Picture API](https://developer.mozilla.org/en-US/docs/Web/API/Picture-in-Picture_API) -
+//! play a video in a floating window above other windows.
+//!
+//! Subscribe to `subs::PictureInPictureEntered` and `subs::PictureInPictureLeft` to update your `Model` -
+//! the user can also close the window or return to the page by the browser UI.
+//!
+//! [Document Picture-in-Picture](https://developer.mozilla.org/en-US/docs/Web/API/Document_Picture-in-Picture_API)
+//! (a floating window with any content) is still experimental - enable it with the feature `document_pip`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::EnterPictureInPicture => {
+//!    orders.perform_cmd(cmds::picture_in_picture::enter(&model.video, Msg::PictureInPictureResult));
+//!}
+//!Msg::ExitPictureInPicture => {
+//!    orders.perform_cmd(cmds::picture_in_picture::exit(Msg::PictureInPictureResult));
+//!}
+//! ```
+
+use crate::browser::util::document;
+#[cfg(feature = "document_pip")]
+use crate::browser::util::window;
+use crate::virtual_dom::ElRef;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// Play the referenced video in the Picture-in-Picture window.
+///
+/// It fails when the element doesn't exist or it isn't a `video` element, the browser doesn't
+/// support the API, the video metadata aren't loaded yet or the cmd wasn't performed
+/// in a user gesture handler (click, key press, ...).
+pub async fn enter<E: Clone + JsCast, Ms>(
+    el_ref: &ElRef<E>,
+    handler: impl FnOnce(Result<(), JsValue>) -> Ms + Clone + 'static,
+) -> Ms {
+    let video = el_ref.map_type::<web_sys::HtmlVideoElement>().get();
+    handler(match video {
+        Some(video) => call_method(&video, "requestPictureInPicture").await,
+        None => Err("picture_in_picture: video element doesn't exist".into()),
+    })
+}
+
+/// Close the Picture-in-Picture window. It's a no-op when there is no window.
+pub async fn exit<Ms>(handler: impl FnOnce(Result<(), JsValue>) -> Ms + Clone + 'static) -> Ms {
+    handler(if element().is_some() {
+        call_method(&document(), "exitPictureInPicture").await
+    } else {
+        Ok(())
+    })
+}
+
+/// The browser supports Picture-in-Picture and the user hasn't disabled it.
+pub fn is_enabled() -> bool {
+    js_sys::Reflect::get(&document(), &"pictureInPictureEnabled".into())
+        .ok()
+        .and_then(|enabled| enabled.as_bool())
+        .unwrap_or_default()
+}
+
+/// The video element currently played in the Picture-in-Picture window.
+pub fn element() -> Option<web_sys::HtmlVideoElement> {
+    js_sys::Reflect::get(&document(), &"pictureInPictureElement".into())
+        .ok()?
+        .dyn_into()
+        .ok()
+}
+
+/// Call the method and wait for its `Promise`.
+async fn call_method(target: &JsValue, name: &str) -> Result<(), JsValue> {
+    let method = js_sys::Reflect::get(target, &name.into())?
+        .dyn_into::<js_sys::Function>()
+        .map_err(|_| JsValue::from("picture_in_picture: Picture-in-Picture API isn't supported"))?;
+    JsFuture::from(method.call0(target)?.dyn_into::<js_sys::Promise>()?).await?;
+    Ok(())
+}
+
+// ------ Document Picture-in-Picture ------
+
+/// Open an empty always-on-top window for any content (e.g. player controls or a video call).
+///
+/// Render into its `document` (e.g. by a second Seed `App`) and listen for its `pagehide`
+/// event to know when it's closed. It can be opened only from a user gesture handler.
+///
+/// _Note:_ It's supported only by Chromium-based browsers.
+#[cfg(feature = "document_pip")]
+pub async fn request_window<Ms>(
+    width: u32,
+    height: u32,
+    handler: impl FnOnce(Result<web_sys::Window, JsValue>) -> Ms + Clone + 'static,
+) -> Ms {
+    let result = async {
+        let document_pip = document_picture_in_picture()?;
+        let options = js_sys::Object::new();
+        js_sys::Reflect::set(&options, &"width".into(), &width.into())?;
+        js_sys::Reflect::set(&options, &"height".into(), &height.into())?;
+
+        let request_window = js_sys::Reflect::get(&document_pip, &"requestWindow".into())?
+            .dyn_into::<js_sys::Function>()?;
+        let promise = request_window
+            .call1(&document_pip, &options)?
+            .dyn_into::<js_sys::Promise>()?;
+        JsFuture::from(promise).await?.dyn_into()
+    }
+    .await;
+    handler(result)
+}
+
+/// The opened Document Picture-in-Picture window.
+#[cfg(feature = "document_pip")]
+pub fn document_window() -> Option<web_sys::Window> {
+    js_sys::Reflect::get(&document_picture_in_picture().ok()?, &"window".into())
+        .ok()?
+        .dyn_into()
+        .ok()
+}
+
+#[cfg(feature = "document_pip")]
+fn document_picture_in_picture() -> Result<JsValue, JsValue> {
+    let document_pip = js_sys::Reflect::get(&window(), &"documentPictureInPicture".into())?;
+    if document_pip.is_undefined() {
+        return Err("picture_in_picture: Document Picture-in-Picture API isn't supported".into());
+    }
+    Ok(document_pip)
+}
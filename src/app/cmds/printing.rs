@@ -0,0 +1,131 @@
+//! Print the page or only its part.
+//!
+//! Subscribe to `subs::{BeforePrint, AfterPrint}` to switch the page into a print-friendly
+//! layout - they're fired also when the user prints by the browser menu or `Ctrl + P`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::PrintPage => cmds::print().unwrap(),
+//!Msg::PrintReport => orders.perform_cmd(cmds::print_element(&model.report, Msg::Printed)),
+//!Msg::BeforePrint => model.expand_all_rows = true,
+//! ```
+
+use crate::app::streams::EventStream;
+use crate::browser::util::{body, document, window};
+use crate::virtual_dom::ElRef;
+use futures::future::Future;
+use futures::stream::StreamExt;
+use wasm_bindgen::{JsCast, JsValue};
+
+/// Open the browser print dialog for the whole page.
+///
+/// The most browsers block until the dialog is closed.
+///
+/// # Errors
+///
+/// Returns error when the browser prevents printing (e.g. in a sandboxed iframe).
+pub fn print() -> Result<(), JsValue> {
+    window().print()
+}
+
+/// Print only the referenced element.
+///
+/// The element is cloned into a hidden iframe together with the page stylesheets, so the rest
+/// of the page doesn't have to be hidden by print CSS. The handler is called when the print
+/// dialog is closed.
+///
+/// Only the HTML is cloned - `canvas` content and current values of form fields typed
+/// by the user aren't printed.
+///
+/// It fails when the element doesn't exist or the browser prevents printing.
+pub fn print_element<E: Clone + JsCast, Ms>(
+    el_ref: &ElRef<E>,
+    handler: impl FnOnce(Result<(), JsValue>) -> Ms + Clone + 'static,
+) -> impl Future<Output = Ms> {
+    let html = el_ref
+        .map_type::<web_sys::Element>()
+        .get()
+        .map(|element| page_html(&element.outer_html()));
+    async move {
+        let result = async {
+            let html = html.ok_or_else(|| JsValue::from("print: element doesn't exist"))?;
+            let iframe = document()
+                .create_element("iframe")?
+                .unchecked_into::<web_sys::HtmlIFrameElement>();
+            // `display: none` iframes aren't printable in some browsers.
+            iframe.set_attribute(
+                "style",
+                "position: fixed; width: 0; height: 0; border: 0; visibility: hidden;",
+            )?;
+            iframe.set_srcdoc(&html);
+
+            // `load` is fired when the stylesheets are loaded.
+            let mut loaded = EventStream::<web_sys::Event>::new(&iframe, "load");
+            body().append_child(&iframe)?;
+            loaded.next().await;
+
+            let result = print_iframe(&iframe).await;
+            iframe.remove();
+            result
+        }
+        .await;
+        handler(result)
+    }
+}
+
+async fn print_iframe(iframe: &web_sys::HtmlIFrameElement) -> Result<(), JsValue> {
+    let content_window = iframe
+        .content_window()
+        .ok_or_else(|| JsValue::from("print: iframe window isn't available"))?;
+    let mut printed = EventStream::<web_sys::Event>::new(&content_window, "afterprint");
+    content_window.print()?;
+    printed.next().await;
+    Ok(())
+}
+
+/// Standalone document with the element and stylesheets of the current page.
+fn page_html(element_html: &str) -> String {
+    let document = document();
+    let base = document.base_uri().ok().flatten().unwrap_or_default();
+    let mut styles = String::new();
+    if let Ok(nodes) = document.query_selector_all("style, link[rel=stylesheet]") {
+        for index in 0..nodes.length() {
+            if let Some(node) = nodes
+                .get(index)
+                .and_then(|node| node.dyn_into::<web_sys::Element>().ok())
+            {
+                styles.push_str(&node.outer_html());
+            }
+        }
+    }
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><base href=\"{}\">{}</head><body>{}</body></html>",
+        base.replace('"', "&quot;"),
+        styles,
+        element_html
+    )
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn page_html_contains_stylesheets() {
+        let style = document().create_element("style").unwrap();
+        style.set_text_content(Some(".report { color: red; }"));
+        body().append_child(&style).unwrap();
+
+        let html = page_html("<div class=\"report\">Q3</div>");
+        style.remove();
+
+        assert!(html.contains("<style>.report { color: red; }</style>"));
+        assert!(html.ends_with("<body><div class=\"report\">Q3</div></body></html>"));
+    }
+}
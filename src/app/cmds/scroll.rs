@@ -0,0 +1,221 @@
+//! Scroll the page or elements with optional smooth behavior.
+//!
+//! The cmds wait for the next render, so the scrolled elements and the page height
+//! already reflect the `Model` changed in the same `update` - no `orders.after_next_render` needed.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::PageChanged(page) => {
+//!    model.page = page;
+//!    orders.perform_cmd(cmds::scroll::to_top(ScrollBehavior::Smooth, || Msg::NoOp));
+//!}
+//!Msg::CommentAdded(comment) => {
+//!    model.comments.push(comment);
+//!    orders.perform_cmd(cmds::scroll::into_view(
+//!        &model.last_comment,
+//!        scroll::IntoViewOptions::new().smooth().block(ScrollLogicalPosition::Center).margin("64px"),
+//!        |_| Msg::NoOp,
+//!    ));
+//!}
+//! ```
+
+use crate::browser::util::{self, RequestAnimationFrameTime};
+use crate::virtual_dom::ElRef;
+use futures::channel::oneshot;
+use futures::future::Future;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+pub use web_sys::{ScrollBehavior, ScrollLogicalPosition};
+
+/// Scroll the page to the top.
+pub async fn to_top<Ms>(behavior: ScrollBehavior, handler: impl FnOnce() -> Ms) -> Ms {
+    to(0., 0., behavior, handler).await
+}
+
+/// Scroll the page to the position `x`, `y` in pixels.
+pub async fn to<Ms>(x: f64, y: f64, behavior: ScrollBehavior, handler: impl FnOnce() -> Ms) -> Ms {
+    next_render().await;
+    util::window().scroll_to_with_scroll_to_options(
+        web_sys::ScrollToOptions::new()
+            .left(x)
+            .top(y)
+            .behavior(behavior),
+    );
+    handler()
+}
+
+/// Scroll the page by `x`, `y` pixels - negative values scroll left / up.
+pub async fn by<Ms>(x: f64, y: f64, behavior: ScrollBehavior, handler: impl FnOnce() -> Ms) -> Ms {
+    next_render().await;
+    util::window().scroll_by_with_scroll_to_options(
+        web_sys::ScrollToOptions::new()
+            .left(x)
+            .top(y)
+            .behavior(behavior),
+    );
+    handler()
+}
+
+/// Scroll the referenced element's ancestors so the element is visible.
+///
+/// It fails when the element doesn't exist after the next render.
+pub fn into_view<E: Clone + JsCast, Ms>(
+    el_ref: &ElRef<E>,
+    options: IntoViewOptions,
+    handler: impl FnOnce(Result<(), JsValue>) -> Ms,
+) -> impl Future<Output = Ms> {
+    let el_ref = el_ref.map_type::<web_sys::Element>();
+    async move {
+        next_render().await;
+        handler(match el_ref.get() {
+            Some(element) => {
+                options.scroll(&element);
+                Ok(())
+            }
+            None => Err("scroll: element doesn't exist".into()),
+        })
+    }
+}
+
+// ------ IntoViewOptions ------
+
+/// Options of `into_view`; the defaults are the browser defaults -
+/// `ScrollBehavior::Auto` (see CSS `scroll-behavior`), the block start and the nearest inline edge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntoViewOptions {
+    behavior: ScrollBehavior,
+    block: ScrollLogicalPosition,
+    inline: ScrollLogicalPosition,
+    margin: Option<String>,
+}
+
+impl Default for IntoViewOptions {
+    fn default() -> Self {
+        Self {
+            behavior: ScrollBehavior::Auto,
+            block: ScrollLogicalPosition::Start,
+            inline: ScrollLogicalPosition::Nearest,
+            margin: None,
+        }
+    }
+}
+
+impl IntoViewOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub const fn behavior(mut self, behavior: ScrollBehavior) -> Self {
+        self.behavior = behavior;
+        self
+    }
+
+    pub const fn smooth(self) -> Self {
+        self.behavior(ScrollBehavior::Smooth)
+    }
+
+    /// The vertical alignment of the element.
+    pub const fn block(mut self, block: ScrollLogicalPosition) -> Self {
+        self.block = block;
+        self
+    }
+
+    /// The horizontal alignment of the element.
+    pub const fn inline(mut self, inline: ScrollLogicalPosition) -> Self {
+        self.inline = inline;
+        self
+    }
+
+    /// The CSS `scroll-margin` of the element during the scroll (e.g. `64px` for a sticky header);
+    /// the element's own scroll margin is used by default.
+    pub fn margin(mut self, margin: impl Into<String>) -> Self {
+        self.margin = Some(margin.into());
+        self
+    }
+
+    fn scroll(&self, element: &web_sys::Element) {
+        let mut options = web_sys::ScrollIntoViewOptions::new();
+        options
+            .behavior(self.behavior)
+            .block(self.block)
+            .inline(self.inline);
+
+        let style = match (&self.margin, element.dyn_ref::<web_sys::HtmlElement>()) {
+            (Some(margin), Some(element)) => Some((margin, element.style())),
+            _ => None,
+        };
+        match style {
+            Some((margin, style)) => {
+                // Browsers read the margin when the scroll starts, so it can be restored right away.
+                let original = style
+                    .get_property_value("scroll-margin")
+                    .unwrap_or_default();
+                style
+                    .set_property("scroll-margin", margin)
+                    .expect("set scroll-margin");
+                element.scroll_into_view_with_scroll_into_view_options(&options);
+                style
+                    .set_property("scroll-margin", &original)
+                    .expect("restore scroll-margin");
+            }
+            None => element.scroll_into_view_with_scroll_into_view_options(&options),
+        }
+    }
+}
+
+/// Resolves in the next animation frame.
+///
+/// Cmds are started after `update` returns and the render is scheduled, so the render's
+/// animation frame callback is requested first and runs before this one.
+async fn next_render() {
+    let (sender, receiver) = oneshot::channel();
+    let mut sender = Some(sender);
+    let on_frame = Closure::wrap(Box::new(move |_: RequestAnimationFrameTime| {
+        if let Some(sender) = sender.take() {
+            let _ = sender.send(());
+        }
+    }) as Box<dyn FnMut(RequestAnimationFrameTime)>);
+    // The frame is cancelled when the handle is dropped together with the cmd.
+    let _frame_handle = util::request_animation_frame(on_frame);
+    let _ = receiver.await;
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    async fn margin_is_restored_after_scroll() {
+        let element: web_sys::HtmlElement = util::document()
+            .create_element("div")
+            .unwrap()
+            .unchecked_into();
+        element
+            .style()
+            .set_property("scroll-margin", "8px")
+            .unwrap();
+        util::body().append_child(&element).unwrap();
+
+        let el_ref = ElRef::<web_sys::HtmlElement>::default();
+        let result = into_view(&el_ref, IntoViewOptions::new(), |result| result).await;
+        assert!(result.is_err());
+
+        IntoViewOptions::new()
+            .smooth()
+            .margin("64px")
+            .scroll(&element);
+        assert_eq!(
+            element.style().get_property_value("scroll-margin").unwrap(),
+            "8px"
+        );
+        element.remove();
+
+        assert_eq!(by(0., 10., ScrollBehavior::Instant, || 1).await, 1);
+    }
+}
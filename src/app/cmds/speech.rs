@@ -0,0 +1,304 @@
+//! [Speech synthesis](https://developer.mozilla.org/en-US/docs/Web/API/SpeechSynthesis) -
+//! read texts aloud for accessibility and language-learning apps.
+//!
+//! Utterances are queued by the browser - control the queue by `pause`, `resume` and `cancel`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::ReadAloud => {
+//!    let utterance = cmds::speech::Utterance::new(&model.text).lang("cs-CZ").rate(0.9);
+//!    model.speech = Some(orders.stream_with_handle(cmds::speech::speak(&utterance, Msg::Speech)));
+//!}
+//!Msg::Speech(SpeechEvent::Boundary { char_index, .. }) => model.highlighted_char = char_index,
+//!Msg::Speech(SpeechEvent::End) => model.speech = None,
+//!Msg::Pause => cmds::speech::pause(),
+//! ```
+
+use crate::browser::util::window;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver};
+use futures::stream::{Stream, StreamExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+// ------ Utterance ------
+
+/// Text with speech options.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Utterance {
+    pub text: String,
+    /// BCP 47 language tag, e.g. `"en-US"`. The document language is used by default.
+    pub lang: Option<String>,
+    /// `Voice::name`; the default voice for `lang` is used when it's `None` or not found.
+    pub voice: Option<String>,
+    /// `0.1` - `10`, default `1`.
+    pub rate: f32,
+    /// `0` - `2`, default `1`.
+    pub pitch: f32,
+    /// `0` - `1`, default `1`.
+    pub volume: f32,
+}
+
+impl Utterance {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            lang: None,
+            voice: None,
+            rate: 1.,
+            pitch: 1.,
+            volume: 1.,
+        }
+    }
+
+    pub fn lang(mut self, lang: impl Into<String>) -> Self {
+        self.lang = Some(lang.into());
+        self
+    }
+
+    pub fn voice(mut self, voice: impl Into<String>) -> Self {
+        self.voice = Some(voice.into());
+        self
+    }
+
+    pub const fn rate(mut self, rate: f32) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    pub const fn pitch(mut self, pitch: f32) -> Self {
+        self.pitch = pitch;
+        self
+    }
+
+    pub const fn volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    fn to_raw(&self) -> Result<web_sys::SpeechSynthesisUtterance, JsValue> {
+        let utterance = web_sys::SpeechSynthesisUtterance::new_with_text(&self.text)?;
+        if let Some(lang) = &self.lang {
+            utterance.set_lang(lang);
+        }
+        if let Some(name) = &self.voice {
+            let voice = raw_voices()?
+                .into_iter()
+                .find(|voice| &voice.name() == name);
+            utterance.set_voice(voice.as_ref());
+        }
+        utterance.set_rate(self.rate);
+        utterance.set_pitch(self.pitch);
+        utterance.set_volume(self.volume);
+        Ok(utterance)
+    }
+}
+
+// ------ SpeechEvent ------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpeechEvent {
+    Start,
+    /// The speech has reached a word or sentence boundary.
+    Boundary {
+        /// Index of the first character of the word or sentence in `Utterance::text`.
+        /// _Note:_ Browsers count UTF-16 code units.
+        char_index: u32,
+        char_length: Option<u32>,
+        /// `"word"` or `"sentence"`.
+        name: Option<String>,
+    },
+    /// The speech has finished or it has been cancelled. It's the last event.
+    End,
+    /// The speech has failed, e.g. because of an unsupported language. It's the last event.
+    Error(String),
+}
+
+// ------ speak ------
+
+/// Add the utterance to the speech queue and stream its events.
+/// The stream ends after `SpeechEvent::End` or `SpeechEvent::Error`.
+///
+/// Dropping the stream doesn't stop the speech - call `cancel`.
+pub fn speak<Ms>(
+    utterance: &Utterance,
+    handler: impl FnOnce(SpeechEvent) -> Ms + Clone + 'static,
+) -> impl Stream<Item = Ms> {
+    SpeechStream::new(utterance).map(move |event| handler.clone()(event))
+}
+
+/// Pause the speech queue.
+pub fn pause() {
+    if let Ok(synthesis) = window().speech_synthesis() {
+        synthesis.pause();
+    }
+}
+
+/// Resume the paused speech queue.
+pub fn resume() {
+    if let Ok(synthesis) = window().speech_synthesis() {
+        synthesis.resume();
+    }
+}
+
+/// Stop the speech and remove all utterances from the queue.
+pub fn cancel() {
+    if let Ok(synthesis) = window().speech_synthesis() {
+        synthesis.cancel();
+    }
+}
+
+// ------ Voice ------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Voice {
+    pub name: String,
+    pub lang: String,
+    /// The voice doesn't require a network connection.
+    pub local: bool,
+    pub default: bool,
+}
+
+/// Available voices.
+///
+/// _Note:_ Chrome loads voices asynchronously - the list may be empty right after the page load.
+pub fn voices() -> Vec<Voice> {
+    raw_voices()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|voice| Voice {
+            name: voice.name(),
+            lang: voice.lang(),
+            local: voice.local_service(),
+            default: voice.default(),
+        })
+        .collect()
+}
+
+fn raw_voices() -> Result<Vec<web_sys::SpeechSynthesisVoice>, JsValue> {
+    Ok(window()
+        .speech_synthesis()?
+        .get_voices()
+        .iter()
+        .map(JsCast::unchecked_into)
+        .collect())
+}
+
+// ------ SpeechStream ------
+
+type Callback = Closure<dyn Fn(JsValue)>;
+type ToEvent = fn(JsValue) -> SpeechEvent;
+
+struct SpeechStream {
+    utterance: Option<web_sys::SpeechSynthesisUtterance>,
+    callbacks: Vec<(&'static str, Callback)>,
+    receiver: UnboundedReceiver<SpeechEvent>,
+    finished: bool,
+}
+
+impl SpeechStream {
+    fn new(utterance: &Utterance) -> Self {
+        let (sender, receiver) = unbounded();
+        let mut stream = Self {
+            utterance: None,
+            callbacks: Vec::new(),
+            receiver,
+            finished: false,
+        };
+
+        let raw = window()
+            .speech_synthesis()
+            .and_then(|synthesis| Ok((synthesis, utterance.to_raw()?)));
+        let (synthesis, raw_utterance) = match raw {
+            Ok(raw) => raw,
+            Err(error) => {
+                let error = error
+                    .as_string()
+                    .unwrap_or_else(|| "speech synthesis isn't supported".to_owned());
+                sender.unbounded_send(SpeechEvent::Error(error)).ok();
+                return stream;
+            }
+        };
+
+        let events: [(&'static str, ToEvent); 4] = [
+            ("start", |_| SpeechEvent::Start),
+            ("boundary", |event| {
+                let event = event.unchecked_into::<web_sys::SpeechSynthesisEvent>();
+                SpeechEvent::Boundary {
+                    char_index: event.char_index(),
+                    char_length: event.char_length(),
+                    name: event.name(),
+                }
+            }),
+            ("end", |_| SpeechEvent::End),
+            ("error", |event| {
+                let error = js_sys::Reflect::get(&event, &"error".into())
+                    .ok()
+                    .and_then(|error| error.as_string());
+                SpeechEvent::Error(error.unwrap_or_default())
+            }),
+        ];
+        for (trigger, to_event) in events.iter().copied() {
+            let sender = sender.clone();
+            let callback = Closure::wrap(Box::new(move |event| {
+                sender.unbounded_send(to_event(event)).ok();
+            }) as Box<dyn Fn(JsValue)>);
+            raw_utterance
+                .add_event_listener_with_callback(trigger, callback.as_ref().unchecked_ref())
+                .expect("add speech listener");
+            stream.callbacks.push((trigger, callback));
+        }
+
+        synthesis.speak(&raw_utterance);
+        stream.utterance = Some(raw_utterance);
+        stream
+    }
+}
+
+impl Stream for SpeechStream {
+    type Item = SpeechEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        let poll = Stream::poll_next(Pin::new(&mut self.receiver), cx);
+        if let Poll::Ready(Some(event)) = &poll {
+            self.finished = matches!(event, SpeechEvent::End | SpeechEvent::Error(_));
+        }
+        poll
+    }
+}
+
+impl Drop for SpeechStream {
+    fn drop(&mut self) {
+        if let Some(utterance) = &self.utterance {
+            for (trigger, callback) in &self.callbacks {
+                utterance
+                    .remove_event_listener_with_callback(trigger, callback.as_ref().unchecked_ref())
+                    .expect("remove speech listener");
+            }
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn utterance_options() {
+        let utterance = Utterance::new("Ahoj").lang("cs-CZ").rate(0.5).pitch(2.);
+        let raw = utterance.to_raw().unwrap();
+        assert_eq!(raw.text(), "Ahoj");
+        assert_eq!(raw.lang(), "cs-CZ");
+        assert!((raw.rate() - 0.5).abs() < f32::EPSILON);
+        assert!((raw.volume() - 1.).abs() < f32::EPSILON);
+    }
+}
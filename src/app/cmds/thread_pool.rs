@@ -0,0 +1,123 @@
+//! Thread pool backed by Web Workers sharing the app's memory.
+//!
+//! It requires the `atomics` target feature - build your app with nightly Rust and
+//! `RUSTFLAGS="-C target-feature=+atomics,+bulk-memory,+mutable-globals"`,
+//! `cargo build -Z build-std=std,panic_abort` and `wasm-bindgen --target no-modules`.
+
+use futures::channel::oneshot;
+use futures::future::{Future, FutureExt};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use wasm_bindgen::prelude::*;
+
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+compile_error!("feature `wasm_threads` requires target feature `atomics`");
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Jobs waiting for a worker. Statics live in the shared memory, so all workers see them.
+static JOBS: Mutex<VecDeque<Job>> = Mutex::new(VecDeque::new());
+
+thread_local! {
+    static WORKERS: RefCell<Vec<web_sys::Worker>> = RefCell::new(Vec::new());
+    static NEXT_WORKER: Cell<usize> = Cell::new(0);
+}
+
+/// Called by workers - each "run" message runs one job.
+#[wasm_bindgen(js_name = __seedRunJob)]
+pub fn run_job() {
+    let job = JOBS.lock().expect("thread pool: lock jobs").pop_front();
+    if let Some(job) = job {
+        job();
+    }
+}
+
+fn worker_script(bindgen_script_url: &str) -> String {
+    format!(
+        "importScripts({url});
+        let ready = null;
+        self.onmessage = async event => {{
+            if (ready === null) {{
+                ready = wasm_bindgen(event.data[0], event.data[1]);
+                return;
+            }}
+            await ready;
+            wasm_bindgen.__seedRunJob();
+        }};",
+        url = serde_json::to_string(bindgen_script_url).expect("thread pool: serialize URL")
+    )
+}
+
+/// Start `size` workers for `cmds::spawn_blocking`.
+///
+/// `bindgen_script_url` is the JS file generated by `wasm-bindgen --target no-modules`
+/// - e.g. `"/pkg/package.js"`. Call it once, before the first `spawn_blocking`.
+///
+/// # Errors
+///
+/// Returns error when a worker can't be created.
+pub fn init_thread_pool(size: usize, bindgen_script_url: &str) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&JsValue::from(worker_script(bindgen_script_url)));
+    let mut options = web_sys::BlobPropertyBag::new();
+    options.type_("text/javascript");
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let script_url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let init = js_sys::Array::of2(&wasm_bindgen::module(), &wasm_bindgen::memory());
+    let workers = (0..size)
+        .map(|_| {
+            let worker = web_sys::Worker::new(&script_url)?;
+            worker.post_message(&init)?;
+            Ok(worker)
+        })
+        .collect::<Result<Vec<_>, JsValue>>()?;
+    web_sys::Url::revoke_object_url(&script_url)?;
+
+    WORKERS.with(|old_workers| {
+        for worker in old_workers.replace(workers) {
+            worker.terminate();
+        }
+    });
+    Ok(())
+}
+
+/// Run CPU-heavy `job` on a worker from the pool and pass its output to `handler`.
+/// `update` and rendering aren't blocked while the job is running.
+///
+/// A worker accepts the next job once it finishes the previous one; a panic in `job`
+/// kills its worker and the cmd never resolves.
+///
+/// # Panics
+///
+/// Panics when `init_thread_pool` hasn't been called or the job can't be sent to a worker.
+///
+/// # Example
+///
+/// ```rust,no_run
+///cmds::init_thread_pool(4, "/pkg/package.js").expect("thread pool");
+///...
+///let pixels = model.pixels.clone();
+///orders.perform_cmd(cmds::spawn_blocking(move || blur(pixels, 5), Msg::Blurred));
+/// ```
+pub fn spawn_blocking<T: Send + 'static, Ms>(
+    job: impl FnOnce() -> T + Send + 'static,
+    handler: impl FnOnce(T) -> Ms + 'static,
+) -> impl Future<Output = Ms> {
+    let (sender, receiver) = oneshot::channel();
+    JOBS.lock()
+        .expect("thread pool: lock jobs")
+        .push_back(Box::new(move || {
+            // The receiver has been dropped when the cmd has been aborted.
+            let _ = sender.send(job());
+        }));
+    WORKERS.with(|workers| {
+        let workers = workers.borrow();
+        assert!(!workers.is_empty(), "call `cmds::init_thread_pool` first");
+        let index = NEXT_WORKER.with(|next| next.replace((next.get() + 1) % workers.len()));
+        workers[index % workers.len()]
+            .post_message(&JsValue::TRUE)
+            .expect("thread pool: send job to worker");
+    });
+    receiver.map(|output| handler(output.expect("thread pool: job output")))
+}
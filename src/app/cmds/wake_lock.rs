@@ -0,0 +1,108 @@
+//! [Screen Wake Lock API](https://developer.mozilla.org/en-US/docs/Web/API/Screen_Wake_Lock_API) -
+//! keep the screen on for presentations, recipes, kiosks, etc.
+//!
+//! The browser releases the lock when the page is hidden (e.g. another tab is selected),
+//! so request a new one when the page is visible again.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::KeepScreenOn => {
+//!    orders.perform_cmd(cmds::wake_lock::request(Msg::WakeLockAcquired));
+//!}
+//!Msg::WakeLockAcquired(Ok(wake_lock)) => {
+//!    model.released_stream = Some(orders.stream_with_handle(wake_lock.released(|| Msg::WakeLockReleased)));
+//!    model.wake_lock = Some(wake_lock);
+//!}
+//!Msg::AllowScreenOff => model.wake_lock = None,
+//! ```
+
+use crate::app::streams::EventStream;
+use crate::browser::util::window;
+use futures::stream::{Stream, StreamExt};
+use std::{cell::Cell, rc::Rc};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+mod raw {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        pub type WakeLock;
+
+        #[wasm_bindgen(method)]
+        pub fn request(this: &WakeLock, type_: &str) -> js_sys::Promise;
+
+        #[wasm_bindgen(extends = web_sys::EventTarget)]
+        pub type WakeLockSentinel;
+
+        #[wasm_bindgen(method)]
+        pub fn release(this: &WakeLockSentinel) -> js_sys::Promise;
+    }
+}
+
+/// Request the screen wake lock.
+///
+/// It fails when the browser doesn't support the API, the page is hidden
+/// or the lock is disallowed (e.g. by a low battery or a permission policy).
+pub async fn request<Ms>(
+    handler: impl FnOnce(Result<WakeLock, JsValue>) -> Ms + Clone + 'static,
+) -> Ms {
+    handler(request_screen_lock().await)
+}
+
+async fn request_screen_lock() -> Result<WakeLock, JsValue> {
+    let wake_lock = js_sys::Reflect::get(&window().navigator(), &"wakeLock".into())?;
+    if wake_lock.is_undefined() {
+        return Err("wake_lock: Screen Wake Lock API isn't supported".into());
+    }
+    let sentinel = JsFuture::from(
+        wake_lock
+            .unchecked_into::<raw::WakeLock>()
+            .request("screen"),
+    )
+    .await?
+    .unchecked_into();
+    Ok(WakeLock {
+        sentinel,
+        released_by_app: Rc::new(Cell::new(false)),
+    })
+}
+
+// ------ WakeLock ------
+
+/// Acquired screen wake lock. It's released on drop.
+pub struct WakeLock {
+    sentinel: raw::WakeLockSentinel,
+    released_by_app: Rc<Cell<bool>>,
+}
+
+impl std::fmt::Debug for WakeLock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WakeLock")
+    }
+}
+
+impl WakeLock {
+    /// Stream one `Msg` when the browser releases the lock (page hidden, low battery, ...).
+    /// The release caused by the `WakeLock` drop is ignored.
+    pub fn released<Ms>(
+        &self,
+        handler: impl FnOnce() -> Ms + Clone + 'static,
+    ) -> impl Stream<Item = Ms> {
+        let released_by_app = Rc::clone(&self.released_by_app);
+        EventStream::<web_sys::Event>::new(&self.sentinel, "release")
+            .take(1)
+            .filter(move |_| futures::future::ready(!released_by_app.get()))
+            .map(move |_| handler.clone()())
+    }
+}
+
+impl Drop for WakeLock {
+    fn drop(&mut self) {
+        self.released_by_app.set(true);
+        // It's a no-op when the browser has already released the lock.
+        let _ = self.sentinel.release();
+    }
+}
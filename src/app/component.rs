@@ -0,0 +1,291 @@
+//! Reusable widgets with private state.
+//!
+//! A `Component` has its own model, `Msg`, `update` and `view`. The parent stores it
+//! in a `Child`, which proxies the component's messages through one parent `Msg` variant,
+//! so the parent `Model` and `Msg` contain only the `Child` and that variant.
+//!
+//! Every `Child` is a new instance - messages from commands and views of a replaced
+//! or re-initialized instance are ignored.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!struct Counter {
+//!    value: i32,
+//!}
+//!
+//!enum CounterMsg {
+//!    Increment,
+//!}
+//!
+//!impl Component for Counter {
+//!    type Props = i32;
+//!    type Msg = CounterMsg;
+//!
+//!    fn init<GMs: 'static>(value: i32, _: &mut impl Orders<CounterMsg, GMs>) -> Self {
+//!        Self { value }
+//!    }
+//!
+//!    fn update<GMs: 'static>(&mut self, msg: CounterMsg, _: &mut impl Orders<CounterMsg, GMs>) {
+//!        match msg {
+//!            CounterMsg::Increment => self.value += 1,
+//!        }
+//!    }
+//!
+//!    fn view(&self) -> Node<CounterMsg> {
+//!        button![self.value, ev(Ev::Click, |_| CounterMsg::Increment)]
+//!    }
+//!}
+//!
+//!struct Model {
+//!    counter: Child<Counter, Msg>,
+//!}
+//!
+//!enum Msg {
+//!    Counter(ComponentMsg<Counter>),
+//!}
+//!
+//!fn init(_: Url, orders: &mut impl Orders<Msg>) -> Model {
+//!    Model {
+//!        counter: Child::init(10, orders, Msg::Counter),
+//!    }
+//!}
+//!
+//!fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+//!    match msg {
+//!        Msg::Counter(msg) => model.counter.update(msg, orders),
+//!    }
+//!}
+//!
+//!fn view(model: &Model) -> Node<Msg> {
+//!    div![model.counter.view()]
+//!}
+//! ```
+
+use super::{MessageMapper, Orders};
+use crate::virtual_dom::Node;
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
+
+thread_local! {
+    static NEXT_INSTANCE_ID: Cell<u64> = Cell::new(0);
+}
+
+fn next_instance_id() -> u64 {
+    NEXT_INSTANCE_ID.with(|id| {
+        let next = id.get();
+        id.set(next + 1);
+        next
+    })
+}
+
+// ------ Component ------
+
+pub trait Component: Sized + 'static {
+    /// Data from the parent needed to create the component.
+    type Props;
+    type Msg: 'static;
+
+    fn init<GMs: 'static>(props: Self::Props, orders: &mut impl Orders<Self::Msg, GMs>) -> Self;
+
+    fn update<GMs: 'static>(&mut self, msg: Self::Msg, orders: &mut impl Orders<Self::Msg, GMs>);
+
+    fn view(&self) -> Node<Self::Msg>;
+}
+
+// ------ ComponentMsg ------
+
+/// A component's `Msg` tagged by the instance which has sent it.
+pub struct ComponentMsg<C: Component> {
+    instance_id: u64,
+    msg: C::Msg,
+}
+
+impl<C: Component> ComponentMsg<C> {
+    /// The message for the component.
+    pub fn into_inner(self) -> C::Msg {
+        self.msg
+    }
+}
+
+impl<C: Component> Clone for ComponentMsg<C>
+where
+    C::Msg: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            instance_id: self.instance_id,
+            msg: self.msg.clone(),
+        }
+    }
+}
+
+impl<C: Component> fmt::Debug for ComponentMsg<C>
+where
+    C::Msg: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ComponentMsg")
+            .field("instance_id", &self.instance_id)
+            .field("msg", &self.msg)
+            .finish()
+    }
+}
+
+// ------ Child ------
+
+/// A component instance embedded in the parent. See the module docs.
+pub struct Child<C: Component, ParentMs> {
+    component: C,
+    instance_id: u64,
+    to_parent: Rc<dyn Fn(ComponentMsg<C>) -> ParentMs>,
+}
+
+impl<C: Component + fmt::Debug, ParentMs> fmt::Debug for Child<C, ParentMs> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Child")
+            .field("component", &self.component)
+            .field("instance_id", &self.instance_id)
+            .finish()
+    }
+}
+
+impl<C: Component, ParentMs: 'static> Child<C, ParentMs> {
+    /// Create a new component instance. `to_parent` wraps its messages into the parent `Msg`.
+    pub fn init<GMs: 'static>(
+        props: C::Props,
+        orders: &mut impl Orders<ParentMs, GMs>,
+        to_parent: impl Fn(ComponentMsg<C>) -> ParentMs + 'static,
+    ) -> Self {
+        let instance_id = next_instance_id();
+        let to_parent: Rc<dyn Fn(ComponentMsg<C>) -> ParentMs> = Rc::new(to_parent);
+        let component = C::init(
+            props,
+            &mut orders.proxy(Self::mapper(instance_id, &to_parent)),
+        );
+        Self {
+            component,
+            instance_id,
+            to_parent,
+        }
+    }
+
+    /// Pass the message to the component; messages of other instances are ignored.
+    pub fn update<GMs: 'static>(
+        &mut self,
+        msg: ComponentMsg<C>,
+        orders: &mut impl Orders<ParentMs, GMs>,
+    ) {
+        if msg.instance_id == self.instance_id {
+            self.send(msg.msg, orders);
+        }
+    }
+
+    /// Send a message to the component from the parent.
+    pub fn send<GMs: 'static>(&mut self, msg: C::Msg, orders: &mut impl Orders<ParentMs, GMs>) {
+        let mapper = Self::mapper(self.instance_id, &self.to_parent);
+        self.component.update(msg, &mut orders.proxy(mapper));
+    }
+
+    pub fn view(&self) -> Node<ParentMs> {
+        self.component
+            .view()
+            .map_msg(Self::mapper(self.instance_id, &self.to_parent))
+    }
+
+    /// The component's state - e.g. for reading its value.
+    pub const fn component(&self) -> &C {
+        &self.component
+    }
+
+    pub fn component_mut(&mut self) -> &mut C {
+        &mut self.component
+    }
+
+    fn mapper(
+        instance_id: u64,
+        to_parent: &Rc<dyn Fn(ComponentMsg<C>) -> ParentMs>,
+    ) -> impl FnOnce(C::Msg) -> ParentMs + Clone + 'static {
+        let to_parent = Rc::clone(to_parent);
+        move |msg| to_parent(ComponentMsg { instance_id, msg })
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{App, OrdersContainer};
+    use crate::browser::{util, Url};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    struct Counter {
+        value: i32,
+    }
+
+    enum CounterMsg {
+        Increment,
+    }
+
+    impl Component for Counter {
+        type Props = i32;
+        type Msg = CounterMsg;
+
+        fn init<GMs: 'static>(value: i32, _: &mut impl Orders<CounterMsg, GMs>) -> Self {
+            Self { value }
+        }
+
+        fn update<GMs: 'static>(&mut self, msg: CounterMsg, _: &mut impl Orders<CounterMsg, GMs>) {
+            match msg {
+                CounterMsg::Increment => self.value += 1,
+            }
+        }
+
+        fn view(&self) -> Node<CounterMsg> {
+            Node::Empty
+        }
+    }
+
+    enum Msg {
+        Counter(ComponentMsg<Counter>),
+    }
+
+    type TestOrders = OrdersContainer<Msg, (), Node<Msg>>;
+
+    fn orders() -> TestOrders {
+        let root = util::document().create_element("div").unwrap();
+        util::body().append_child(&root).unwrap();
+        let app = App::start(
+            root,
+            |_: Url, _: &mut TestOrders| (),
+            |_, _, _| (),
+            |_| Node::Empty,
+        );
+        OrdersContainer::new(app)
+    }
+
+    fn increment(child: &Child<Counter, Msg>) -> ComponentMsg<Counter> {
+        let Msg::Counter(msg) =
+            Child::mapper(child.instance_id, &child.to_parent)(CounterMsg::Increment);
+        msg
+    }
+
+    #[wasm_bindgen_test]
+    fn messages_of_replaced_instance_are_dropped() {
+        let mut orders = orders();
+        let mut child = Child::init(10, &mut orders, Msg::Counter);
+        let from_old_instance = increment(&child);
+
+        child = Child::init(0, &mut orders, Msg::Counter);
+        child.update(from_old_instance, &mut orders);
+        assert_eq!(child.component().value, 0);
+
+        let from_current_instance = increment(&child);
+        child.update(from_current_instance, &mut orders);
+        assert_eq!(child.component().value, 1);
+    }
+}
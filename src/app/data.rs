@@ -1,7 +1,12 @@
-use super::{render_timestamp_delta::RenderTimestamp, types::*, RenderTimestampDelta, SubManager};
+use super::{
+    render_timestamp_delta::RenderTimestamp, subs::listeners::LazySubListeners,
+    subs::pending_work::PendingWork, types::*, CmdGroups, RenderTimestampDelta, SubManager,
+};
 use crate::browser::util;
-use crate::virtual_dom::{El, EventHandlerManager};
+use crate::virtual_dom::{El, EventHandlerManager, Node};
+use std::any::Any;
 use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
 use wasm_bindgen::closure::Closure;
 
 type StoredPopstate = RefCell<Option<Closure<dyn FnMut(web_sys::Event)>>>;
@@ -14,12 +19,34 @@ pub struct AppData<Ms: 'static, Mdl> {
     pub main_el_vdom: RefCell<Option<El<Ms>>>,
     pub popstate_closure: StoredPopstate,
     pub hashchange_closure: StoredPopstate,
+    pub(crate) sub_listeners: RefCell<Option<LazySubListeners>>,
     pub routes: RefCell<Option<RoutesFn<Ms>>>,
     pub window_event_handler_manager: RefCell<EventHandlerManager<Ms>>,
     pub sub_manager: RefCell<SubManager<Ms>>,
+    pub cmd_groups: CmdGroups,
+    pub(crate) pending_work: PendingWork,
+    /// `route::prefetch::PrefetchListener`s registered by `App::prefetch_links`.
+    pub(crate) prefetch_listeners: RefCell<Vec<Box<dyn Any>>>,
+    #[cfg(feature = "devtools")]
+    pub(crate) devtools: super::devtools::Devtools,
     pub msg_listeners: RefCell<MsgListeners<Ms>>,
+    pub msg_logger: RefCell<Option<MsgLogger<Ms>>>,
+    pub(crate) performance_budget: Cell<Option<super::PerformanceBudget>>,
+    /// The number of `view` renders - used to identify renders in performance budget warnings.
+    pub(crate) renders: Cell<u64>,
+    pub(crate) leak_detector: RefCell<Option<super::leak_detector::LeakDetector>>,
+    /// Live resources created by the app, see `leak_detector`.
+    pub(crate) live_counts: super::leak_detector::Counter,
+    /// See `App::strict_mode`.
+    pub(crate) strict_mode: Cell<bool>,
+    /// `validation::validate` warnings already logged - each one is logged only once.
+    pub(crate) nesting_warnings: RefCell<HashSet<String>>,
+    pub should_render: RefCell<Option<ShouldRenderFn<Mdl>>>,
     pub scheduled_render_handle: RefCell<Option<util::RequestAnimationFrameHandle>>,
     pub after_next_render_callbacks:
         RefCell<Vec<Box<dyn FnOnce(Option<RenderTimestampDelta>) -> Ms>>>,
     pub render_timestamp: Cell<Option<RenderTimestamp>>,
+    // The number of running init cmds - `view` isn't rendered until it's zero.
+    pub init_cmds: Cell<usize>,
+    pub init_placeholder: RefCell<Option<Vec<Node<Ms>>>>,
 }
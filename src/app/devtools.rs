@@ -0,0 +1,174 @@
+//! `window.__SEED__` - a bridge for external devtools, e.g. browser extensions.
+//!
+//! It's enabled by the feature `devtools` and installed by `App::devtools`:
+//! - `__SEED__.model()` - the current `Model` serialized by `serde`.
+//! - `__SEED__.dispatch(msg)` - deserialize `msg` into `Msg` and send it to `update`;
+//!   e.g. `__SEED__.dispatch({ "SearchChanged": "seed" })` for `Msg::SearchChanged(String)`.
+//! - `__SEED__.stats()` - `{ renders, lastRenderMs, totalRenderMs }`, see `RenderStats`.
+//!
+//! See also the in-page `model_inspector`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!#[derive(Serialize)]
+//!struct Model { search: String }
+//!
+//!#[derive(Deserialize)]
+//!enum Msg { SearchChanged(String) }
+//!
+//!let app = App::start("app", init, update, view);
+//!#[cfg(debug_assertions)]
+//!app.devtools();
+//! ```
+
+pub mod model_inspector;
+
+use super::App;
+use crate::browser::util;
+use crate::virtual_dom::View;
+use serde::{de::DeserializeOwned, Serialize};
+use std::cell::{Cell, RefCell};
+use wasm_bindgen::{closure::Closure, JsValue};
+
+/// The name of the global object.
+pub const GLOBAL_NAME: &str = "__SEED__";
+
+type BridgeFn = Closure<dyn Fn(JsValue) -> Result<JsValue, JsValue>>;
+
+// ------ RenderStats ------
+
+/// Statistics of `view` renders and DOM patches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderStats {
+    pub renders: u64,
+    pub last_render_ms: f64,
+    pub total_render_ms: f64,
+}
+
+impl RenderStats {
+    fn record(&mut self, duration_ms: f64) {
+        self.renders += 1;
+        self.last_render_ms = duration_ms;
+        self.total_render_ms += duration_ms;
+    }
+}
+
+// ------ Devtools ------
+
+/// Devtools state stored in `AppData`.
+#[derive(Default)]
+pub(crate) struct Devtools {
+    stats: Cell<RenderStats>,
+    bridge: RefCell<Vec<BridgeFn>>,
+    /// Refreshes `model_inspector`.
+    after_render: RefCell<Option<Box<dyn Fn()>>>,
+}
+
+impl Devtools {
+    pub fn record_render(&self, duration_ms: f64) {
+        let mut stats = self.stats.get();
+        stats.record(duration_ms);
+        self.stats.set(stats);
+        if let Some(after_render) = self.after_render.borrow().as_ref() {
+            after_render();
+        }
+    }
+
+    pub fn stats(&self) -> RenderStats {
+        self.stats.get()
+    }
+}
+
+/// Create `window.__SEED__` for the `app`.
+pub(crate) fn install<Ms, Mdl, ElC, GMs>(app: &App<Ms, Mdl, ElC, GMs>)
+where
+    Ms: DeserializeOwned + 'static,
+    Mdl: Serialize + 'static,
+    ElC: View<Ms> + 'static,
+    GMs: 'static,
+{
+    let model = {
+        let app = app.clone();
+        Closure::wrap(Box::new(move |_| {
+            let model = app.data.model.borrow();
+            let json = serde_json::to_string(&*model)
+                .map_err(|error| JsValue::from_str(&error.to_string()))?;
+            js_sys::JSON::parse(&json)
+        })
+            as Box<dyn Fn(JsValue) -> Result<JsValue, JsValue>>)
+    };
+
+    let dispatch = {
+        let app = app.clone();
+        Closure::wrap(Box::new(move |msg: JsValue| {
+            let json = String::from(js_sys::JSON::stringify(&msg)?);
+            let msg = serde_json::from_str::<Ms>(&json).map_err(|error| {
+                JsValue::from_str(&format!(
+                    "cannot deserialize `Msg` from {}: {}",
+                    json, error
+                ))
+            })?;
+            app.update(msg);
+            Ok(JsValue::UNDEFINED)
+        })
+            as Box<dyn Fn(JsValue) -> Result<JsValue, JsValue>>)
+    };
+
+    let stats = {
+        let app = app.clone();
+        Closure::wrap(Box::new(move |_| {
+            let json = serde_json::to_string(&app.data.devtools.stats())
+                .map_err(|error| JsValue::from_str(&error.to_string()))?;
+            js_sys::JSON::parse(&json)
+        })
+            as Box<dyn Fn(JsValue) -> Result<JsValue, JsValue>>)
+    };
+
+    let bridge = js_sys::Object::new();
+    for (name, function) in &[
+        ("model", &model),
+        ("dispatch", &dispatch),
+        ("stats", &stats),
+    ] {
+        js_sys::Reflect::set(&bridge, &JsValue::from_str(name), function.as_ref())
+            .expect("set devtools function");
+    }
+    js_sys::Reflect::set(&util::window(), &JsValue::from_str(GLOBAL_NAME), &bridge)
+        .expect("set `window.__SEED__`");
+
+    app.data
+        .devtools
+        .bridge
+        .replace(vec![model, dispatch, stats]);
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn render_stats() {
+        let devtools = Devtools::default();
+        devtools.record_render(2.);
+        devtools.record_render(3.);
+        assert_eq!(
+            devtools.stats(),
+            RenderStats {
+                renders: 2,
+                last_render_ms: 3.,
+                total_render_ms: 5.,
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&devtools.stats()).unwrap(),
+            r#"{"renders":2,"lastRenderMs":3.0,"totalRenderMs":5.0}"#
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! In-page model inspector - a collapsible tree of the serialized `Model` with the parts
+//! changed by the last update highlighted, and the latest messages.
+//!
+//! It's installed by `App::model_inspector` and toggled by `Ctrl + Shift + M`.
+//! The overlay is rendered outside of the app's root element and refreshed after each render.
+
+use super::super::App;
+use crate::browser::util;
+use crate::virtual_dom::View;
+use serde::Serialize;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::{HashSet, VecDeque};
+use std::fmt::Debug;
+use std::rc::Rc;
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// The number of displayed latest messages.
+pub const MESSAGE_LIMIT: usize = 20;
+/// The toggle key pressed together with `Ctrl` and `Shift`.
+pub const HOTKEY: &str = "M";
+
+const OVERLAY_ID: &str = "seed-model-inspector";
+const CHANGED_STYLE: &str = "background: rgba(255, 200, 0, 0.35)";
+
+#[derive(Default)]
+struct State {
+    previous_model: Option<Value>,
+    messages: VecDeque<String>,
+}
+
+/// Create the overlay and its listeners for the `app`.
+pub(crate) fn install<Ms, Mdl, ElC, GMs>(app: &App<Ms, Mdl, ElC, GMs>)
+where
+    Ms: Debug + 'static,
+    Mdl: Serialize + 'static,
+    ElC: View<Ms> + 'static,
+    GMs: 'static,
+{
+    let document = util::document();
+    let overlay = document.create_element("div").expect("create inspector");
+    overlay.set_id(OVERLAY_ID);
+    overlay
+        .set_attribute(
+            "style",
+            "display: none; position: fixed; top: 0; right: 0; width: 30em; max-height: 100vh; \
+             overflow: auto; padding: 0.5em; z-index: 10000; background: rgba(0, 0, 0, 0.85); \
+             color: white; font: 12px monospace",
+        )
+        .expect("set inspector style");
+    util::body()
+        .append_child(&overlay)
+        .expect("append inspector");
+
+    let state = Rc::new(RefCell::new(State::default()));
+
+    app.add_message_listener({
+        let state = Rc::clone(&state);
+        move |msg| {
+            let mut state = state.borrow_mut();
+            if state.messages.len() == MESSAGE_LIMIT {
+                state.messages.pop_back();
+            }
+            state.messages.push_front(format!("{:?}", msg));
+        }
+    });
+
+    let refresh: Rc<dyn Fn()> = {
+        let app = app.clone();
+        let overlay = overlay.clone();
+        Rc::new(move || {
+            let model = serde_json::to_value(&*app.data.model.borrow()).unwrap_or(Value::Null);
+            let mut state = state.borrow_mut();
+            if overlay
+                .get_attribute("style")
+                .map_or(false, |style| !style.contains("display: none"))
+            {
+                let open_paths = open_paths(&overlay);
+                overlay.set_inner_html(&format!(
+                    "<strong>Model</strong>{}<strong>Messages</strong><ol>{}</ol>",
+                    render_tree(&model, state.previous_model.as_ref(), "", &open_paths),
+                    state
+                        .messages
+                        .iter()
+                        .map(|msg| format!("<li>{}</li>", escape(msg)))
+                        .collect::<String>(),
+                ));
+            }
+            state.previous_model = Some(model);
+        })
+    };
+
+    let toggle = {
+        let refresh = Rc::clone(&refresh);
+        Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+            if event.ctrl_key() && event.shift_key() && event.key().eq_ignore_ascii_case(HOTKEY) {
+                let style = overlay.get_attribute("style").unwrap_or_default();
+                let style = if style.contains("display: none") {
+                    style.replacen("display: none", "display: block", 1)
+                } else {
+                    style.replacen("display: block", "display: none", 1)
+                };
+                overlay
+                    .set_attribute("style", &style)
+                    .expect("set inspector style");
+                refresh();
+            }
+        }) as Box<dyn FnMut(web_sys::KeyboardEvent)>)
+    };
+    util::window()
+        .add_event_listener_with_callback("keydown", toggle.as_ref().unchecked_ref())
+        .expect("add inspector hotkey listener");
+    // The overlay lives as long as the app.
+    toggle.forget();
+
+    app.data
+        .devtools
+        .after_render
+        .replace(Some(Box::new(move || refresh())));
+}
+
+/// Paths of opened `details` - they are kept open after the refresh.
+fn open_paths(overlay: &web_sys::Element) -> HashSet<String> {
+    let mut paths = HashSet::new();
+    if let Ok(details) = overlay.query_selector_all("details[open]") {
+        for index in 0..details.length() {
+            if let Some(path) = details
+                .get(index)
+                .and_then(|node| node.dyn_into::<web_sys::Element>().ok())
+                .and_then(|el| el.get_attribute("data-path"))
+            {
+                paths.insert(path);
+            }
+        }
+    }
+    paths
+}
+
+/// HTML of the `value` tree. Values which differ from `previous` are highlighted
+/// and objects and arrays with changes are open.
+fn render_tree(
+    value: &Value,
+    previous: Option<&Value>,
+    path: &str,
+    open_paths: &HashSet<String>,
+) -> String {
+    let changed = previous != Some(value);
+    let style = if changed { CHANGED_STYLE } else { "" };
+    let children = match value {
+        Value::Object(object) => object
+            .iter()
+            .map(|(key, child)| {
+                let previous = previous.and_then(|previous| previous.get(key));
+                (key.clone(), child, previous)
+            })
+            .collect::<Vec<_>>(),
+        Value::Array(array) => array
+            .iter()
+            .enumerate()
+            .map(|(index, child)| {
+                let previous = previous.and_then(|previous| previous.get(index));
+                (index.to_string(), child, previous)
+            })
+            .collect(),
+        _ => {
+            return format!(
+                "<span style=\"{}\">{}</span>",
+                style,
+                escape(&value.to_string())
+            )
+        }
+    };
+
+    let open = (changed && previous.is_some()) || open_paths.contains(path);
+    let summary = match value {
+        Value::Array(array) => format!("[{}]", array.len()),
+        _ => format!("{{{}}}", children.len()),
+    };
+    let items = children
+        .into_iter()
+        .map(|(key, child, previous)| {
+            let child_path = format!("{}/{}", path, key);
+            format!(
+                "<li>{}: {}</li>",
+                escape(&key),
+                render_tree(child, previous, &child_path, open_paths)
+            )
+        })
+        .collect::<String>();
+    format!(
+        "<details data-path=\"{}\"{}><summary style=\"{}\">{}</summary>\
+         <ul style=\"margin: 0; padding-left: 1.2em\">{}</ul></details>",
+        escape(path),
+        if open { " open" } else { "" },
+        style,
+        summary,
+        items
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn changed_values_are_highlighted() {
+        let previous = json!({"count": 1, "name": "seed"});
+        let model = json!({"count": 2, "name": "seed"});
+        let html = render_tree(&model, Some(&previous), "", &HashSet::new());
+
+        assert!(html.contains(&format!("<span style=\"{}\">2</span>", CHANGED_STYLE)));
+        assert!(html.contains("<span style=\"\">&quot;seed&quot;</span>"));
+        assert!(html.starts_with("<details data-path=\"\" open>"));
+        assert!(!render_tree(&model, Some(&model), "", &HashSet::new()).contains(" open"));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::browser::util::document;
+use crate::browser::util::{body, document};
 use web_sys::{Element, HtmlElement};
 
 pub trait GetElement {
@@ -29,3 +29,36 @@ impl GetElement for HtmlElement {
         Ok(self.into())
     }
 }
+
+// ------ MissingRoot ------
+
+/// What `App::start_with` does when the root element doesn't exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingRoot {
+    /// Return `MountError::RootNotFound`.
+    Error,
+    /// Append a new `div` with the given id to the `body`.
+    Create,
+    /// Try to find the element again on `DOMContentLoaded` - e.g. for scripts loaded in `head`.
+    /// `MountError::RootNotFound` is returned when the document has been already loaded.
+    RetryOnDomContentLoaded,
+}
+
+// ------ MountError ------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MountError {
+    /// There is no element with the id.
+    RootNotFound(String),
+    /// The element with the id can't be created.
+    RootNotCreated(String),
+}
+
+/// Append a new `div` with the id `root_id` to the `body`.
+pub(crate) fn create_root(root_id: &str) -> Result<Element, MountError> {
+    let not_created = |_| MountError::RootNotCreated(root_id.to_owned());
+    let root_element = document().create_element("div").map_err(not_created)?;
+    root_element.set_id(root_id);
+    body().append_child(&root_element).map_err(not_created)?;
+    Ok(root_element)
+}
@@ -0,0 +1,365 @@
+//! Route-based code splitting - load secondary Seed apps ("feature apps") when they are needed,
+//! so the initial bundle doesn't grow with every page.
+//!
+//! A feature app is a separate crate built by `wasm-bindgen --target web`. It exports
+//! the function `seed_mount` (see `mount_handle`) and it communicates with the host app
+//! by JSON messages.
+//!
+//! # Example
+//!
+//! Host app:
+//!
+//! ```rust,no_run
+//!fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+//!    match msg {
+//!        Msg::UrlChanged(subs::UrlChanged(url)) => {
+//!            model.page = Page::from(url);
+//!            if model.page == Page::Admin && model.admin.is_none() {
+//!                orders.perform_cmd(lazy::load("/admin/admin.js", Msg::AdminLoaded));
+//!            }
+//!        }
+//!        Msg::AdminLoaded(Ok(module)) => {
+//!            orders.after_next_render(|_| Msg::MountAdmin(module));
+//!        }
+//!        Msg::MountAdmin(module) => {
+//!            let root = model.admin_root.get().expect("admin root");
+//!            let admin = module.mount(&root, orders, Msg::FromAdmin).expect("mount admin");
+//!            admin.send(&ToAdmin::User(model.user.clone())).expect("send user");
+//!            model.admin = Some(admin);
+//!        }
+//!        ...
+//!    }
+//!}
+//! ```
+//!
+//! Feature app:
+//!
+//! ```rust,no_run
+//!#[wasm_bindgen]
+//!pub fn seed_mount(root: web_sys::Element, send_to_host: js_sys::Function) -> JsValue {
+//!    let app = App::start(root.clone(), init, update, view);
+//!    app.update(Msg::HostConnected(lazy::Host::<ToHost>::new(send_to_host)));
+//!    lazy::mount_handle(root, move |msg| app.update(Msg::FromHost(msg)))
+//!}
+//! ```
+
+use super::Orders;
+use crate::browser::util::error;
+use futures::future::{Future, FutureExt};
+use serde::{de::DeserializeOwned, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::{fmt, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// Name of the function exported by feature apps.
+pub const MOUNT_EXPORT: &str = "seed_mount";
+
+thread_local! {
+    /// Module URL -> promise of the initialized module.
+    static MODULES: RefCell<HashMap<String, js_sys::Promise>> = RefCell::new(HashMap::new());
+}
+
+// ------ Loader ------
+
+/// Load and initialize the feature app module `module_url` (the JS file generated by `wasm-bindgen`).
+///
+/// The module is loaded only once - next calls with the same URL resolve immediately.
+/// A failed load isn't cached, so the next call tries again (e.g. after a network error).
+pub fn load<Ms>(
+    module_url: impl Into<String>,
+    handler: impl FnOnce(Result<FeatureModule, JsValue>) -> Ms + 'static,
+) -> impl Future<Output = Ms> {
+    let module_url = module_url.into();
+    let promise = MODULES.with(|modules| {
+        modules
+            .borrow_mut()
+            .entry(module_url.clone())
+            .or_insert_with_key(|url| import_and_init(url))
+            .clone()
+    });
+    JsFuture::from(promise.clone()).map(move |exports| {
+        if exports.is_err() {
+            forget_failed(&module_url, &promise);
+        }
+        handler(exports.map(|exports| FeatureModule { exports }))
+    })
+}
+
+/// Remove the rejected `promise` from the cache - unless it has been already replaced by a retry.
+fn forget_failed(module_url: &str, promise: &js_sys::Promise) {
+    MODULES.with(|modules| {
+        let mut modules = modules.borrow_mut();
+        let is_cached = modules.get(module_url).map_or(false, |cached| {
+            JsValue::from(cached) == JsValue::from(promise)
+        });
+        if is_cached {
+            modules.remove(module_url);
+        }
+    });
+}
+
+fn import_and_init(module_url: &str) -> js_sys::Promise {
+    let import = js_sys::Function::new_with_args(
+        "url",
+        "return import(url).then(module => module.default().then(() => module))",
+    );
+    import
+        .call1(&JsValue::NULL, &JsValue::from(module_url))
+        .map_or_else(
+            |error| js_sys::Promise::reject(&error),
+            JsCast::unchecked_into,
+        )
+}
+
+// ------ FeatureModule ------
+
+/// Loaded feature app module. See `load`.
+#[derive(Clone)]
+pub struct FeatureModule {
+    exports: JsValue,
+}
+
+impl fmt::Debug for FeatureModule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FeatureModule")
+    }
+}
+
+impl FeatureModule {
+    /// Start the feature app in `root`. Messages from the app are passed to `handler`
+    /// and the result is sent to your app.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when the module doesn't export `seed_mount` or the mount fails.
+    pub fn mount<Out: Serialize, In: DeserializeOwned + 'static, Ms: 'static, GMs: 'static>(
+        &self,
+        root: &web_sys::Element,
+        orders: &impl Orders<Ms, GMs>,
+        handler: impl FnOnce(In) -> Ms + Clone + 'static,
+    ) -> Result<MountedFeature<Out>, JsValue> {
+        let (app, msg_mapper) = (orders.clone_app(), orders.msg_mapper());
+        self.mount_with(
+            root,
+            Rc::new(move |message| app.update(msg_mapper(handler.clone()(message)))),
+        )
+    }
+
+    fn mount_with<Out, In: DeserializeOwned + 'static>(
+        &self,
+        root: &web_sys::Element,
+        on_message: Rc<dyn Fn(In)>,
+    ) -> Result<MountedFeature<Out>, JsValue> {
+        let mount: js_sys::Function =
+            js_sys::Reflect::get(&self.exports, &MOUNT_EXPORT.into())?.dyn_into()?;
+        let on_message = Closure::wrap(Box::new(move |json: JsValue| match deserialize(&json) {
+            Ok(message) => on_message(message),
+            Err(message_error) => {
+                error(message_error);
+            }
+        }) as Box<dyn Fn(JsValue)>);
+        let handle = mount.call2(&JsValue::NULL, root, on_message.as_ref())?;
+        Ok(MountedFeature {
+            handle,
+            _on_message: on_message,
+            message_type: PhantomData,
+        })
+    }
+}
+
+fn deserialize<T: DeserializeOwned>(json: &JsValue) -> Result<T, String> {
+    let json = json
+        .as_string()
+        .ok_or("feature app message is not a string")?;
+    serde_json::from_str(&json).map_err(|serde_error| serde_error.to_string())
+}
+
+fn call_method(object: &JsValue, name: &str, args: &js_sys::Array) -> Result<JsValue, JsValue> {
+    let method: js_sys::Function = js_sys::Reflect::get(object, &name.into())?.dyn_into()?;
+    method.apply(object, args)
+}
+
+// ------ MountedFeature ------
+
+/// Running feature app - it's unmounted when dropped.
+pub struct MountedFeature<Out> {
+    handle: JsValue,
+    _on_message: Closure<dyn Fn(JsValue)>,
+    message_type: PhantomData<fn(Out)>,
+}
+
+impl<Out> fmt::Debug for MountedFeature<Out> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MountedFeature")
+    }
+}
+
+impl<Out: Serialize> MountedFeature<Out> {
+    /// Send the message to the feature app.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when serialization fails or the app rejects the message.
+    pub fn send(&self, message: &Out) -> Result<(), JsValue> {
+        let json = serde_json::to_string(message)
+            .map_err(|serde_error| JsValue::from(serde_error.to_string()))?;
+        call_method(&self.handle, "send", &js_sys::Array::of1(&json.into())).map(|_| ())
+    }
+}
+
+impl<Out> Drop for MountedFeature<Out> {
+    fn drop(&mut self) {
+        if let Err(unmount_error) = call_method(&self.handle, "unmount", &js_sys::Array::new()) {
+            error(unmount_error);
+        }
+    }
+}
+
+// ------ Feature app helpers ------
+
+/// Feature app's connection to the host app. Create it in `seed_mount`.
+pub struct Host<Out> {
+    send_to_host: js_sys::Function,
+    message_type: PhantomData<fn(Out)>,
+}
+
+impl<Out> Clone for Host<Out> {
+    fn clone(&self) -> Self {
+        Self {
+            send_to_host: self.send_to_host.clone(),
+            message_type: PhantomData,
+        }
+    }
+}
+
+impl<Out> fmt::Debug for Host<Out> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Host")
+    }
+}
+
+impl<Out: Serialize> Host<Out> {
+    pub fn new(send_to_host: js_sys::Function) -> Self {
+        Self {
+            send_to_host,
+            message_type: PhantomData,
+        }
+    }
+
+    /// Send the message to the host app.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when serialization fails or the host rejects the message.
+    pub fn send(&self, message: &Out) -> Result<(), JsValue> {
+        let json = serde_json::to_string(message)
+            .map_err(|serde_error| JsValue::from(serde_error.to_string()))?;
+        self.send_to_host
+            .call1(&JsValue::NULL, &json.into())
+            .map(|_| ())
+    }
+}
+
+/// Create the value returned from `seed_mount`.
+///
+/// Messages from the host are passed to `on_message` until the feature app is unmounted.
+/// Unmounting clears `root`.
+pub fn mount_handle<In: DeserializeOwned + 'static>(
+    root: web_sys::Element,
+    on_message: impl Fn(In) + 'static,
+) -> JsValue {
+    let mounted = Rc::new(Cell::new(true));
+    let send = {
+        let mounted = Rc::clone(&mounted);
+        Closure::wrap(Box::new(move |json: JsValue| {
+            if !mounted.get() {
+                return;
+            }
+            match deserialize(&json) {
+                Ok(message) => on_message(message),
+                Err(message_error) => {
+                    error(message_error);
+                }
+            }
+        }) as Box<dyn Fn(JsValue)>)
+    };
+    let unmount = Closure::wrap(Box::new(move || {
+        mounted.set(false);
+        root.set_inner_html("");
+    }) as Box<dyn Fn()>);
+
+    let handle = js_sys::Object::new();
+    js_sys::Reflect::set(&handle, &"send".into(), &send.into_js_value()).expect("lazy: set `send`");
+    js_sys::Reflect::set(&handle, &"unmount".into(), &unmount.into_js_value())
+        .expect("lazy: set `unmount`");
+    handle.into()
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+    enum Message {
+        Ping(u32),
+    }
+
+    #[wasm_bindgen_test]
+    async fn failed_load_is_retried() {
+        let url = "/seed-lazy-test-missing-module.js";
+        let is_cached = || MODULES.with(|modules| modules.borrow().contains_key(url));
+
+        assert!(load(url, |result| result).await.is_err());
+        assert!(!is_cached());
+
+        let retry = load(url, |result| result);
+        assert!(is_cached());
+        assert!(retry.await.is_err());
+        assert!(!is_cached());
+    }
+
+    #[wasm_bindgen_test]
+    fn mount_send_and_unmount() {
+        let root = crate::browser::util::document()
+            .create_element("div")
+            .unwrap();
+        root.set_inner_html("<p>feature</p>");
+
+        // Feature app that echoes messages back to the host.
+        let mount = Closure::wrap(Box::new(|root: web_sys::Element, send: js_sys::Function| {
+            let host = Host::new(send);
+            mount_handle(root, move |message: Message| host.send(&message).unwrap())
+        })
+            as Box<dyn Fn(web_sys::Element, js_sys::Function) -> JsValue>);
+        let exports = js_sys::Object::new();
+        js_sys::Reflect::set(&exports, &MOUNT_EXPORT.into(), mount.as_ref()).unwrap();
+        let module = FeatureModule {
+            exports: exports.into(),
+        };
+
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let mounted = {
+            let received = Rc::clone(&received);
+            module
+                .mount_with::<Message, Message>(
+                    &root,
+                    Rc::new(move |message| received.borrow_mut().push(message)),
+                )
+                .unwrap()
+        };
+        mounted.send(&Message::Ping(1)).unwrap();
+        assert_eq!(*received.borrow(), vec![Message::Ping(1)]);
+
+        drop(mounted);
+        assert_eq!(root.inner_html(), "");
+    }
+}
@@ -0,0 +1,292 @@
+//! Detection of leaked handles and DOM listeners - see `App::detect_leaks`.
+//!
+//! Live `CmdHandle`s, `SubHandle`s, `StreamHandle`s and DOM listeners of event handlers
+//! (`ev`, window events) are counted per app in debug builds. Counts growing across many renders usually mean handles
+//! are stored in an ever-growing `Vec` and never dropped.
+//!
+//! A resource is counted in the app whose `init`, `update` or render has created it;
+//! resources created elsewhere - e.g. by `CmdManager` called directly from a cmd - aren't counted.
+//! Other closures - e.g. listeners of built-in subscriptions, routing listeners
+//! or closures created by your code - aren't counted either.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+/// The default number of renders with growing counts before a warning.
+pub const DEFAULT_RENDERS: usize = 20;
+
+/// Live counts of one app, stored in `AppData`.
+pub(crate) type Counter = Rc<Cell<LiveCounts>>;
+
+thread_local! {
+    /// The counter of the app which is running `init`, `update` or render.
+    static CURRENT: RefCell<Option<Counter>> = RefCell::new(None);
+}
+
+// ------ Resource ------
+
+/// A counted resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    CmdHandle,
+    SubHandle,
+    StreamHandle,
+    /// DOM listener of an event handler - `ev` or a window event.
+    Listener,
+}
+
+impl Resource {
+    pub const ALL: [Self; 4] = [
+        Self::CmdHandle,
+        Self::SubHandle,
+        Self::StreamHandle,
+        Self::Listener,
+    ];
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::CmdHandle => "`CmdHandle`s",
+            Self::SubHandle => "`SubHandle`s",
+            Self::StreamHandle => "`StreamHandle`s",
+            Self::Listener => "DOM listeners",
+        }
+    }
+}
+
+// ------ LiveCounts ------
+
+/// The numbers of live resources.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LiveCounts {
+    pub cmd_handles: usize,
+    pub sub_handles: usize,
+    pub stream_handles: usize,
+    pub listeners: usize,
+}
+
+impl LiveCounts {
+    pub const fn get(&self, resource: Resource) -> usize {
+        match resource {
+            Resource::CmdHandle => self.cmd_handles,
+            Resource::SubHandle => self.sub_handles,
+            Resource::StreamHandle => self.stream_handles,
+            Resource::Listener => self.listeners,
+        }
+    }
+
+    fn get_mut(&mut self, resource: Resource) -> &mut usize {
+        match resource {
+            Resource::CmdHandle => &mut self.cmd_handles,
+            Resource::SubHandle => &mut self.sub_handles,
+            Resource::StreamHandle => &mut self.stream_handles,
+            Resource::Listener => &mut self.listeners,
+        }
+    }
+}
+
+// ------ Counting ------
+
+/// Count resources created until the guard is dropped into `counter`.
+pub(crate) fn count_into(counter: &Counter) -> CountingGuard {
+    CountingGuard(CURRENT.with(|current| current.replace(Some(Rc::clone(counter)))))
+}
+
+/// Restores the counter of the outer app - e.g. when an app is started from `update` of another one.
+pub(crate) struct CountingGuard(Option<Counter>);
+
+impl Drop for CountingGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        CURRENT.with(|current| current.replace(previous));
+    }
+}
+
+/// A resource counted in the app which has created it until the resource is dropped.
+pub(crate) struct Counted {
+    counter: Option<Counter>,
+    resource: Resource,
+}
+
+impl Counted {
+    pub(crate) fn new(resource: Resource) -> Self {
+        let counter = if cfg!(debug_assertions) {
+            CURRENT.with(|current| current.borrow().clone())
+        } else {
+            None
+        };
+        if let Some(counter) = &counter {
+            let mut counts = counter.get();
+            *counts.get_mut(resource) += 1;
+            counter.set(counts);
+        }
+        Self { counter, resource }
+    }
+}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        if let Some(counter) = &self.counter {
+            let mut counts = counter.get();
+            let count = counts.get_mut(self.resource);
+            *count = count.saturating_sub(1);
+            counter.set(counts);
+        }
+    }
+}
+
+// ------ LeakDetector ------
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Growth {
+    start: usize,
+    renders: usize,
+}
+
+/// Compares live counts after renders, stored in `AppData`.
+pub(crate) struct LeakDetector {
+    renders: usize,
+    previous: Option<LiveCounts>,
+    growths: [Growth; 4],
+}
+
+impl LeakDetector {
+    pub fn new(renders: usize) -> Self {
+        Self {
+            renders,
+            previous: None,
+            growths: [Growth::default(); 4],
+        }
+    }
+
+    /// Record `counts` after a render and return warnings about resources whose count
+    /// has grown in `renders` renders without decreasing in between.
+    pub fn record(&mut self, counts: LiveCounts) -> Vec<String> {
+        let previous = match self.previous.replace(counts) {
+            Some(previous) => previous,
+            None => return Vec::new(),
+        };
+        let mut warnings = Vec::new();
+        for (resource, growth) in Resource::ALL.iter().zip(self.growths.iter_mut()) {
+            let (previous, current) = (previous.get(*resource), counts.get(*resource));
+            if current < previous {
+                *growth = Growth::default();
+            } else if current > previous {
+                if growth.renders == 0 {
+                    growth.start = previous;
+                }
+                growth.renders += 1;
+                if growth.renders == self.renders {
+                    warnings.push(format!(
+                        "Possible leak: live {} have grown in {} renders from {} to {}.",
+                        resource.name(),
+                        self.renders,
+                        growth.start,
+                        current
+                    ));
+                    *growth = Growth::default();
+                }
+            }
+        }
+        warnings
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{App, Orders, OrdersContainer, SubHandle};
+    use crate::browser::util;
+    use crate::virtual_dom::Node;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn listeners(listeners: usize) -> LiveCounts {
+        LiveCounts {
+            listeners,
+            ..LiveCounts::default()
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn growing_counts_are_reported() {
+        let mut detector = LeakDetector::new(3);
+        assert!(detector.record(listeners(5)).is_empty());
+        assert!(detector.record(listeners(6)).is_empty());
+        assert!(detector.record(listeners(6)).is_empty());
+        assert!(detector.record(listeners(7)).is_empty());
+        assert_eq!(
+            detector.record(listeners(9)),
+            vec![
+                "Possible leak: live DOM listeners have grown in 3 renders from 5 to 9.".to_owned()
+            ]
+        );
+
+        assert!(detector.record(listeners(10)).is_empty());
+        assert!(detector.record(listeners(8)).is_empty());
+        assert!(detector.record(listeners(9)).is_empty());
+        assert!(detector.record(listeners(10)).is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn handles_are_counted_per_app() {
+        let (app_1, app_2) = (Counter::default(), Counter::default());
+        let counting = count_into(&app_1);
+        let handle = crate::app::CmdManager::perform_cmd_with_handle(async {});
+        let nested_counting = count_into(&app_2);
+        let stream_handle = crate::app::StreamManager::stream_with_handle(futures::stream::empty());
+        drop(nested_counting);
+        let listener = Counted::new(Resource::Listener);
+        drop(counting);
+        let uncounted = Counted::new(Resource::Listener);
+
+        assert_eq!(
+            app_1.get(),
+            LiveCounts {
+                cmd_handles: 1,
+                listeners: 1,
+                ..LiveCounts::default()
+            }
+        );
+        assert_eq!(app_2.get().stream_handles, 1);
+
+        drop((handle, stream_handle, listener, uncounted));
+        assert_eq!(app_1.get(), LiveCounts::default());
+        assert_eq!(app_2.get(), LiveCounts::default());
+    }
+
+    struct Subscribe;
+
+    #[derive(Clone)]
+    struct Never;
+
+    type TestApp = App<Subscribe, Vec<SubHandle>, Node<Subscribe>>;
+
+    fn start_app() -> TestApp {
+        let root = util::document().create_element("div").unwrap();
+        util::body().append_child(&root).unwrap();
+        App::start(
+            root,
+            |_, _| Vec::new(),
+            |Subscribe, handles, orders: &mut OrdersContainer<_, _, _>| {
+                handles.push(orders.subscribe_with_handle(|_: Never| Subscribe));
+            },
+            |_| Node::Empty,
+        )
+    }
+
+    #[wasm_bindgen_test]
+    fn apps_count_their_own_handles() {
+        let (app_1, app_2) = (start_app(), start_app());
+        let before = app_2.live_counts();
+        app_1.update(Subscribe);
+        app_1.update(Subscribe);
+        assert_eq!(app_1.live_counts().sub_handles, 2);
+        assert_eq!(app_2.live_counts(), before);
+
+        app_1.data.model.borrow_mut().as_mut().unwrap().clear();
+        assert_eq!(app_1.live_counts().sub_handles, 0);
+    }
+}
@@ -0,0 +1,160 @@
+//! Memoized state derived from the `Model` - e.g. filtered or sorted lists.
+//!
+//! `selector!` defines a function which caches its last result and recomputes it
+//! only when the arguments aren't `CheapEq` to the previous ones. The cache lives
+//! in the function, so `view` can call it without any cache in the `Model`.
+//! Pass `Rc`s or `im-rc` collections and other cheap-to-clone arguments - they're stored
+//! as the cache key.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!selector! {
+//!    /// Todos matching the filter, sorted by title.
+//!    fn visible_todos(todos: Rc<Vec<Todo>>, filter: Filter) -> Vec<Todo> {
+//!        let mut todos = todos
+//!            .iter()
+//!            .filter(|todo| filter.matches(todo))
+//!            .cloned()
+//!            .collect::<Vec<_>>();
+//!        todos.sort_by(|a, b| a.title.cmp(&b.title));
+//!        todos
+//!    }
+//!}
+//!
+//!fn view(model: &Model) -> Node<Msg> {
+//!    let todos = visible_todos(Rc::clone(&model.todos), model.filter);
+//!    ul![todos.iter().map(view_todo)]
+//!}
+//! ```
+
+use crate::virtual_dom::CheapEq;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
+// ------ Memo ------
+
+/// The last value computed from a key. `selector!` stores it in a `thread_local`;
+/// it can also be stored e.g. in a component's `Model`.
+pub struct Memo<K, V> {
+    cached: RefCell<Option<(K, Rc<V>)>>,
+    computations: Cell<usize>,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cached: RefCell::new(None),
+            computations: Cell::new(0),
+        }
+    }
+}
+
+impl<K, V> fmt::Debug for Memo<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Memo")
+            .field("computations", &self.computations.get())
+            .finish()
+    }
+}
+
+impl<K: CheapEq, V> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the cached value when `key` is `CheapEq` to the previous key,
+    /// otherwise compute it by `compute` and cache it.
+    pub fn get(&self, key: K, compute: impl FnOnce(&K) -> V) -> Rc<V> {
+        if let Some((cached_key, value)) = self.cached.borrow().as_ref() {
+            if cached_key.cheap_eq(&key) {
+                return Rc::clone(value);
+            }
+        }
+        // `compute` may call other selectors, so the cache isn't borrowed during it.
+        let value = Rc::new(compute(&key));
+        self.computations.set(self.computations.get() + 1);
+        self.cached.replace(Some((key, Rc::clone(&value))));
+        value
+    }
+
+    /// Forget the cached value - the next `get` call computes it.
+    pub fn invalidate(&self) {
+        self.cached.replace(None);
+    }
+
+    /// How many times the value has been computed - e.g. for tests and profiling.
+    pub fn computations(&self) -> usize {
+        self.computations.get()
+    }
+}
+
+// ------ selector! ------
+
+/// Define a function with memoized result. The arguments are the cache key; they're
+/// available by reference in the body. The function returns `Rc<output type>`.
+/// See `seed::app::memo`.
+#[macro_export]
+macro_rules! selector {
+    {
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident($($arg:ident: $arg_type:ty),* $(,)?) -> $output:ty $body:block
+    } => {
+        $(#[$attr])*
+        $vis fn $name($($arg: $arg_type),*) -> std::rc::Rc<$output> {
+            thread_local! {
+                static MEMO: $crate::app::memo::Memo<($($arg_type,)*), $output> =
+                    $crate::app::memo::Memo::new();
+            }
+            MEMO.with(|memo| {
+                memo.get(($($arg,)*), |key| {
+                    #[allow(unused_variables)]
+                    let ($($arg,)*) = key;
+                    $body
+                })
+            })
+        }
+    };
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    thread_local! {
+        static SUM_CALLS: Cell<usize> = Cell::new(0);
+    }
+
+    selector! {
+        fn sum_above(numbers: Rc<Vec<u32>>, limit: u32) -> u32 {
+            SUM_CALLS.with(|calls| calls.set(calls.get() + 1));
+            numbers.iter().filter(|number| *number > limit).sum()
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn selector_recomputes_only_for_new_arguments() {
+        let numbers = Rc::new(vec![1, 5, 10]);
+        assert_eq!(*sum_above(Rc::clone(&numbers), 2), 15);
+        assert_eq!(*sum_above(Rc::clone(&numbers), 2), 15);
+        assert_eq!(SUM_CALLS.with(Cell::get), 1);
+
+        assert_eq!(*sum_above(Rc::clone(&numbers), 5), 10);
+        assert_eq!(SUM_CALLS.with(Cell::get), 2);
+    }
+
+    #[wasm_bindgen_test]
+    fn memo_invalidate() {
+        let memo = Memo::<u32, String>::new();
+        assert_eq!(*memo.get(1, u32::to_string), "1");
+        memo.invalidate();
+        assert_eq!(*memo.get(1, u32::to_string), "1");
+        assert_eq!(memo.computations(), 2);
+    }
+}
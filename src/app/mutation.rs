@@ -0,0 +1,156 @@
+//! Optimistic updates - change the `Model` immediately and roll back when the server fails.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!enum Msg {
+//!    ToggleTodo(TodoId),
+//!    TodoSaved(MutationResult<Vec<Todo>, Todo, FailReason<Todo>>),
+//!}
+//!
+//!fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+//!    match msg {
+//!        Msg::ToggleTodo(id) => {
+//!            let request = save_todo_request(&model.todos, id);
+//!            mutation::mutate(
+//!                &mut model.todos,
+//!                |todos| toggle(todos, id),
+//!                request.fetch_json_data(identity),
+//!                Msg::TodoSaved,
+//!                orders,
+//!            );
+//!        }
+//!        Msg::TodoSaved(result) => {
+//!            let settled = result.settle(&mut model.todos, |todos, saved_todo| {
+//!                // Reconcile with the server response.
+//!                replace(todos, saved_todo)
+//!            });
+//!            if let Err(fail_reason) = settled {
+//!                model.error = Some(fail_reason);
+//!            }
+//!        }
+//!    }
+//!}
+//! ```
+
+use super::Orders;
+use futures::future::{Future, FutureExt};
+use std::fmt;
+
+// ------ MutationResult ------
+
+/// Result of the mutation's command created by `mutate`.
+pub enum MutationResult<S, R, E> {
+    /// The command succeeded with the server `response`.
+    Succeeded { response: R },
+    /// The command failed. `previous` is the state before the optimistic change.
+    Failed { error: E, previous: S },
+}
+
+impl<S, R, E> MutationResult<S, R, E> {
+    /// Apply the result to the `state`:
+    /// - `Succeeded` - call `reconcile` with the server response and return `Ok`.
+    /// - `Failed` - restore the previous state and return the error.
+    ///
+    /// _Note:_ The rollback restores the whole `state`, so it also reverts changes made
+    /// after the mutation has been started (e.g. other optimistic changes).
+    ///
+    /// # Errors
+    ///
+    /// Returns the command's error when the mutation has failed.
+    pub fn settle(self, state: &mut S, reconcile: impl FnOnce(&mut S, R)) -> Result<(), E> {
+        match self {
+            MutationResult::Succeeded { response } => {
+                reconcile(state, response);
+                Ok(())
+            }
+            MutationResult::Failed { error, previous } => {
+                *state = previous;
+                Err(error)
+            }
+        }
+    }
+
+    /// Like `settle`, but also calls `on_rollback` with the restored state and the error
+    /// - e.g. to show a notification.
+    pub fn settle_with(
+        self,
+        state: &mut S,
+        reconcile: impl FnOnce(&mut S, R),
+        on_rollback: impl FnOnce(&mut S, E),
+    ) {
+        if let Err(error) = self.settle(state, reconcile) {
+            on_rollback(state, error);
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, MutationResult::Succeeded { .. })
+    }
+}
+
+impl<S, R, E: fmt::Debug> fmt::Debug for MutationResult<S, R, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MutationResult::Succeeded { .. } => write!(f, "Succeeded"),
+            MutationResult::Failed { error, .. } => {
+                f.debug_struct("Failed").field("error", error).finish()
+            }
+        }
+    }
+}
+
+// ------ mutate ------
+
+/// Save a snapshot of `state`, apply the `optimistic` change and perform `cmd`.
+///
+/// The `cmd`'s result is delivered as `MutationResult` wrapped by `to_msg`.
+/// Call `MutationResult::settle` in your `update` function to reconcile or roll back.
+pub fn mutate<S, R, E, Ms, GMs>(
+    state: &mut S,
+    optimistic: impl FnOnce(&mut S),
+    cmd: impl Future<Output = Result<R, E>> + 'static,
+    to_msg: impl FnOnce(MutationResult<S, R, E>) -> Ms + 'static,
+    orders: &mut impl Orders<Ms, GMs>,
+) where
+    S: Clone + 'static,
+    R: 'static,
+    E: 'static,
+    Ms: 'static,
+{
+    let previous = state.clone();
+    optimistic(state);
+    orders.perform_cmd(cmd.map(move |result| {
+        to_msg(match result {
+            Ok(response) => MutationResult::Succeeded { response },
+            Err(error) => MutationResult::Failed { error, previous },
+        })
+    }));
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn settle_reconciles_success() {
+        let mut state = vec![1, 2, 3];
+        let result: MutationResult<Vec<i32>, i32, ()> = MutationResult::Succeeded { response: 4 };
+        assert!(result.settle(&mut state, Vec::push).is_ok());
+        assert_eq!(state, vec![1, 2, 3, 4]);
+    }
+
+    #[wasm_bindgen_test]
+    fn settle_rolls_back_failure() {
+        let mut state = vec![1, 2, 3, 4];
+        let result: MutationResult<Vec<i32>, i32, &str> = MutationResult::Failed {
+            error: "offline",
+            previous: vec![1, 2, 3],
+        };
+        assert_eq!(result.settle(&mut state, |_, _| ()), Err("offline"));
+        assert_eq!(state, vec![1, 2, 3]);
+    }
+}
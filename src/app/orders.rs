@@ -1,6 +1,11 @@
-use super::{App, CmdHandle, RenderTimestampDelta, StreamHandle, SubHandle, UndefinedGMsg};
+use super::subs;
+use super::{
+    App, CmdHandle, RenderTimestampDelta, StreamHandle, SubHandle, UndefinedGMsg, UnloadGuard,
+};
+use crate::browser::{dom::custom_event::DispatchTarget, Route, RouteLoader};
 use crate::virtual_dom::View;
 use futures::stream::Stream;
+use serde::Serialize;
 use std::{any::Any, future::Future};
 
 // @TODO: Add links to doc comment once https://github.com/rust-lang/rust/issues/43466 is resolved
@@ -91,6 +96,49 @@ pub trait Orders<Ms: 'static, GMs = UndefinedGMsg> {
     #[must_use = "cmd is aborted on its handle drop"]
     fn perform_cmd_with_handle(&mut self, cmd: impl Future<Output = Ms> + 'static) -> CmdHandle;
 
+    /// Similar to `perform_cmd`, but `cmd` is added to the group `group` -
+    /// all running cmds in the group can be aborted together by `abort_group`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///Msg::SearchQueryChanged(query) => {
+    ///    // Superseded searches are aborted; their results wouldn't be relevant.
+    ///    orders
+    ///        .abort_group("search")
+    ///        .perform_cmd_in_group("search", async move { Msg::SearchResults(search(query).await) });
+    ///}
+    /// ```
+    fn perform_cmd_in_group(
+        &mut self,
+        group: impl Into<String>,
+        cmd: impl Future<Output = Ms> + 'static,
+    ) -> &mut Self;
+
+    /// Abort all running cmds in the group `group`; see `perform_cmd_in_group`.
+    fn abort_group(&mut self, group: &str) -> &mut Self;
+
+    /// Similar to `perform_cmd`, but the app isn't rendered until all init cmds are finished
+    /// and their `Msg`s are handled - use it in `init` to load critical data (session, feature flags, etc.).
+    /// The mount point content (e.g. prerendered HTML) or the placeholder set by `init_placeholder`
+    /// is displayed in the meantime.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///fn init(_: Url, orders: &mut impl Orders<Msg>) -> Model {
+    ///    orders
+    ///        .perform_init_cmd(async { Msg::SessionLoaded(load_session().await) })
+    ///        .init_placeholder(div![C!["spinner"]]);
+    ///    Model::default()
+    ///}
+    /// ```
+    fn perform_init_cmd(&mut self, cmd: impl Future<Output = Ms> + 'static) -> &mut Self;
+
+    /// Set the content displayed instead of `view` while init cmds are running.
+    /// See `perform_init_cmd`.
+    fn init_placeholder(&mut self, placeholder: impl View<Ms>) -> &mut Self;
+
     /// Similar to `send_msg`, but calls function `sink` with the given global message.
     fn send_g_msg(&mut self, g_msg: GMs) -> &mut Self;
 
@@ -135,6 +183,43 @@ pub trait Orders<Ms: 'static, GMs = UndefinedGMsg> {
         callback: impl FnOnce(Option<RenderTimestampDelta>) -> Ms + 'static,
     ) -> &mut Self;
 
+    /// Dispatch `CustomEvent` with serialized `detail` on the `target` (`ElRef`, `Window`, ...)
+    /// immediately, e.g. to notify web components or other scripts on the page.
+    ///
+    /// Errors (e.g. missing target element) are logged into the console.
+    /// See `dom::custom_event::dispatch_custom_event` for more info.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///orders.dispatch_event(&model.cart, "cart-changed", &model.cart_items);
+    ///orders.dispatch_event(&window(), "app-ready", &());
+    /// ```
+    fn dispatch_event<T: Serialize + ?Sized>(
+        &mut self,
+        target: &impl DispatchTarget,
+        name: &str,
+        detail: &T,
+    ) -> &mut Self;
+
+    /// Ask the user to confirm leaving the page while `message` returns `Some`,
+    /// e.g. to protect a form with unsaved changes.
+    /// - Returns `UnloadGuard` that you should save to your `Model`.
+    ///   The guard is removed on its drop.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///model.unload_guard = Some(orders.block_unload_with(|| Some("You have unsaved changes")));
+    ///...
+    ///Msg::Saved => model.unload_guard = None,
+    /// ```
+    #[must_use = "guard is removed on its drop"]
+    fn block_unload_with<M: Into<String>>(
+        &mut self,
+        message: impl Fn() -> Option<M> + 'static,
+    ) -> UnloadGuard;
+
     /// Subscribe for messages with the `handler`s input type.
     ///
     /// # Example
@@ -154,6 +239,62 @@ pub trait Orders<Ms: 'static, GMs = UndefinedGMsg> {
         handler: impl FnOnce(SubMs) -> Ms + Clone + 'static,
     ) -> &mut Self;
 
+    /// Subscribe for url changes which match the typed route `R`; `handler` receives
+    /// the parsed route parameters.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///// `Msg::ProductPage(subs::RouteMatched<ProductRoute>)`
+    ///model.route_sub = orders.subscribe_route_with_handle(Msg::ProductPage);
+    ///...
+    ///Msg::ProductPage(subs::RouteMatched { route: ProductRoute { id }, .. }) => model.load_product(id, orders),
+    /// ```
+    ///
+    /// _Note:_: Use the alternative `subscribe_route_with_handle` to control `sub`'s lifetime.
+    fn subscribe_route<R: Route>(
+        &mut self,
+        handler: impl FnOnce(subs::RouteMatched<R>) -> Ms + Clone + 'static,
+    ) -> &mut Self;
+
+    /// Like `subscribe_route`, but it returns `SubHandle` that you should save to your `Model`.
+    /// The `sub` is cancelled on the handle drop.
+    #[must_use = "subscription is cancelled on its handle drop"]
+    fn subscribe_route_with_handle<R: Route>(
+        &mut self,
+        handler: impl FnOnce(subs::RouteMatched<R>) -> Ms + Clone + 'static,
+    ) -> SubHandle;
+
+    /// Run `RouteLoader::load` when the url changes to the route `R` and send the loaded data
+    /// or the error by `handler`. The pending load is aborted when the url changes again.
+    ///
+    /// `subs::NavigationPending` notifications are sent while the data are loading.
+    /// Loads are counted in `subs::PendingWorkChanged` if `App::track_pending_work` is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///// `Msg::ProductLoaded(subs::RouteLoaded<ProductRoute>)`
+    ///orders.subscribe_route_loader(Msg::ProductLoaded);
+    ///...
+    ///Msg::ProductLoaded(subs::RouteLoaded { result: Ok(product), .. }) => model.page = Page::Product(product),
+    ///Msg::ProductLoaded(subs::RouteLoaded { result: Err(error), .. }) => model.error = Some(error),
+    /// ```
+    ///
+    /// _Note:_: Use the alternative `subscribe_route_loader_with_handle` to control `sub`'s lifetime.
+    fn subscribe_route_loader<R: RouteLoader>(
+        &mut self,
+        handler: impl FnOnce(subs::RouteLoaded<R>) -> Ms + Clone + 'static,
+    ) -> &mut Self;
+
+    /// Like `subscribe_route_loader`, but it returns `SubHandle` that you should save to your `Model`.
+    /// The `sub` is cancelled on the handle drop.
+    #[must_use = "subscription is cancelled on its handle drop"]
+    fn subscribe_route_loader_with_handle<R: RouteLoader>(
+        &mut self,
+        handler: impl FnOnce(subs::RouteLoaded<R>) -> Ms + Clone + 'static,
+    ) -> SubHandle;
+
     /// Subscribe for messages with the `handler`s input type.
     /// - Returns `SubHandle` that you should save to your `Model`.
     ///   The `sub` is cancelled on the handle drop.
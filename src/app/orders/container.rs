@@ -1,11 +1,17 @@
 use crate::app::orders::{proxy::OrdersProxy, Orders};
+use crate::app::subs;
+use crate::app::subs::pending_work::PendingWorkGuard;
 use crate::app::{
     effects::Effect, render_timestamp_delta::RenderTimestampDelta, App, CmdHandle, CmdManager,
-    Notification, ShouldRender, StreamHandle, StreamManager, SubHandle, UndefinedGMsg,
+    Notification, ShouldRender, StreamHandle, StreamManager, SubHandle, UndefinedGMsg, UnloadGuard,
 };
+use crate::browser::dom::custom_event::{dispatch_custom_event, DispatchTarget};
+use crate::browser::util::error;
+use crate::browser::{route::prefetch, Route, RouteLoader};
 use crate::virtual_dom::view::View;
 use futures::future::FutureExt;
 use futures::stream::{Stream, StreamExt};
+use serde::Serialize;
 use std::{any::Any, collections::VecDeque, convert::identity, future::Future};
 
 #[allow(clippy::module_name_repetitions)]
@@ -30,6 +36,89 @@ impl<Ms, Mdl, ElC: View<Ms>, GMs> OrdersContainer<Ms, Mdl, ElC, GMs> {
     }
 }
 
+impl<Ms: 'static, Mdl, ElC: View<Ms> + 'static, GMs: 'static> OrdersContainer<Ms, Mdl, ElC, GMs> {
+    /// Count `cmd` in `subs::PendingWorkChanged` if the tracking is enabled.
+    fn track(
+        &mut self,
+        group: Option<String>,
+        cmd: impl Future<Output = ()> + 'static,
+    ) -> impl Future<Output = ()> + 'static {
+        let guard = start_pending_work(&self.app, group).map(|(state, guard)| {
+            self.notify(state);
+            guard
+        });
+        async move {
+            cmd.await;
+            drop(guard);
+        }
+    }
+
+    /// `UrlChanged` handler which loads the data of the matched route `R` in a cmd group,
+    /// so the previous load is aborted on the next navigation.
+    fn route_loader<R: RouteLoader>(
+        &self,
+        handler: impl FnOnce(subs::RouteLoaded<R>) -> Ms + Clone + 'static,
+    ) -> impl FnOnce(subs::UrlChanged) -> Option<Ms> + Clone + 'static {
+        let app = self.app.clone();
+        move |url_changed| {
+            let group = format!("seed::route_loader::{}", std::any::type_name::<R>());
+            // Abort also when the new url doesn't match `R` - the old route isn't shown anymore.
+            app.data.cmd_groups.abort(&group);
+            let subs::RouteMatched { route, url } =
+                subs::RouteMatched::<R>::from_url_changed(url_changed)?;
+
+            let (cmd_app, cmd_group) = (app.clone(), group.clone());
+            app.data.cmd_groups.perform_cmd(group, async move {
+                // Notified from the cmd, so subscribers can't change subscriptions
+                // while the `UrlChanged` subscribers are being called.
+                let _pending_work =
+                    start_pending_work(&cmd_app, Some(cmd_group)).map(|(state, guard)| {
+                        cmd_app.notify(state);
+                        guard
+                    });
+                cmd_app.notify(subs::NavigationPending {
+                    url: url.clone(),
+                    pending: true,
+                });
+                // Sends `pending: false` also when the load is aborted.
+                let navigation_pending = {
+                    let (app, url) = (cmd_app.clone(), url.clone());
+                    PendingWorkGuard::new(move || {
+                        app.notify(subs::NavigationPending {
+                            url,
+                            pending: false,
+                        });
+                    })
+                };
+                let load = prefetch::take(&route).unwrap_or_else(|| route.load());
+                let result = load.await;
+                drop(navigation_pending);
+                cmd_app.update(handler(subs::RouteLoaded { route, url, result }));
+            });
+            None
+        }
+    }
+}
+
+/// Count a started cmd in `subs::PendingWorkChanged` if the tracking is enabled.
+/// Returns the new state and the guard which counts the cmd as finished on drop.
+fn start_pending_work<Ms: 'static, Mdl, ElC: View<Ms> + 'static, GMs: 'static>(
+    app: &App<Ms, Mdl, ElC, GMs>,
+    group: Option<String>,
+) -> Option<(subs::PendingWorkChanged, PendingWorkGuard)> {
+    let pending_work = &app.data.pending_work;
+    if !pending_work.is_enabled() {
+        return None;
+    }
+    let state = pending_work.start(group.as_deref());
+    let app = app.clone();
+    let guard = PendingWorkGuard::new(move || {
+        let state = app.data.pending_work.finish(group.as_deref());
+        app.notify(state);
+    });
+    Some((state, guard))
+}
+
 impl<Ms: 'static, Mdl, ElC: View<Ms> + 'static, GMs: 'static> Orders<Ms, GMs>
     for OrdersContainer<Ms, Mdl, ElC, GMs>
 {
@@ -74,14 +163,51 @@ impl<Ms: 'static, Mdl, ElC: View<Ms> + 'static, GMs: 'static> Orders<Ms, GMs>
     fn perform_cmd(&mut self, cmd: impl Future<Output = Ms> + 'static) -> &mut Self {
         let app = self.app.clone();
         let cmd = cmd.map(move |msg| app.update(msg));
-        CmdManager::perform_cmd(cmd);
+        CmdManager::perform_cmd(self.track(None, cmd));
         self
     }
 
     fn perform_cmd_with_handle(&mut self, cmd: impl Future<Output = Ms> + 'static) -> CmdHandle {
         let app = self.app.clone();
         let cmd = cmd.map(move |msg| app.update(msg));
-        CmdManager::perform_cmd_with_handle(cmd)
+        CmdManager::perform_cmd_with_handle(self.track(None, cmd))
+    }
+
+    fn perform_cmd_in_group(
+        &mut self,
+        group: impl Into<String>,
+        cmd: impl Future<Output = Ms> + 'static,
+    ) -> &mut Self {
+        let app = self.app.clone();
+        let group = group.into();
+        let cmd = cmd.map(move |msg| app.update(msg));
+        let cmd = self.track(Some(group.clone()), cmd);
+        self.app.data.cmd_groups.perform_cmd(group, cmd);
+        self
+    }
+
+    fn abort_group(&mut self, group: &str) -> &mut Self {
+        self.app.data.cmd_groups.abort(group);
+        self
+    }
+
+    fn perform_init_cmd(&mut self, cmd: impl Future<Output = Ms> + 'static) -> &mut Self {
+        let app = self.app.clone();
+        app.data.init_cmds.set(app.data.init_cmds.get() + 1);
+        let cmd = cmd.map(move |msg| {
+            app.update(msg);
+            app.finish_init_cmd();
+        });
+        CmdManager::perform_cmd(self.track(None, cmd));
+        self
+    }
+
+    fn init_placeholder(&mut self, placeholder: impl View<Ms>) -> &mut Self {
+        self.app
+            .data
+            .init_placeholder
+            .replace(Some(placeholder.els()));
+        self
     }
 
     fn send_g_msg(&mut self, g_msg: GMs) -> &mut Self {
@@ -93,14 +219,14 @@ impl<Ms: 'static, Mdl, ElC: View<Ms> + 'static, GMs: 'static> Orders<Ms, GMs>
     fn perform_g_cmd(&mut self, cmd: impl Future<Output = GMs> + 'static) -> &mut Self {
         let app = self.app.clone();
         let cmd = cmd.map(move |msg| app.sink(msg));
-        CmdManager::perform_cmd(cmd);
+        CmdManager::perform_cmd(self.track(None, cmd));
         self
     }
 
     fn perform_g_cmd_with_handle(&mut self, cmd: impl Future<Output = GMs> + 'static) -> CmdHandle {
         let app = self.app.clone();
         let cmd = cmd.map(move |msg| app.sink(msg));
-        CmdManager::perform_cmd_with_handle(cmd)
+        CmdManager::perform_cmd_with_handle(self.track(None, cmd))
     }
 
     fn clone_app(&self) -> App<Self::AppMs, Self::Mdl, Self::ElC, GMs> {
@@ -123,6 +249,25 @@ impl<Ms: 'static, Mdl, ElC: View<Ms> + 'static, GMs: 'static> Orders<Ms, GMs>
         self
     }
 
+    fn dispatch_event<T: Serialize + ?Sized>(
+        &mut self,
+        target: &impl DispatchTarget,
+        name: &str,
+        detail: &T,
+    ) -> &mut Self {
+        if let Err(dispatch_error) = dispatch_custom_event(target, name, detail) {
+            error(dispatch_error);
+        }
+        self
+    }
+
+    fn block_unload_with<M: Into<String>>(
+        &mut self,
+        message: impl Fn() -> Option<M> + 'static,
+    ) -> UnloadGuard {
+        UnloadGuard::new(message)
+    }
+
     fn subscribe<SubMs: 'static + Clone>(
         &mut self,
         handler: impl FnOnce(SubMs) -> Ms + Clone + 'static,
@@ -142,6 +287,58 @@ impl<Ms: 'static, Mdl, ElC: View<Ms> + 'static, GMs: 'static> Orders<Ms, GMs>
             .subscribe_with_handle(handler)
     }
 
+    fn subscribe_route<R: Route>(
+        &mut self,
+        handler: impl FnOnce(subs::RouteMatched<R>) -> Ms + Clone + 'static,
+    ) -> &mut Self {
+        self.app
+            .data
+            .sub_manager
+            .borrow_mut()
+            .subscribe_filter_map(move |url_changed| {
+                subs::RouteMatched::from_url_changed(url_changed).map(handler)
+            });
+        self
+    }
+
+    fn subscribe_route_with_handle<R: Route>(
+        &mut self,
+        handler: impl FnOnce(subs::RouteMatched<R>) -> Ms + Clone + 'static,
+    ) -> SubHandle {
+        self.app
+            .data
+            .sub_manager
+            .borrow_mut()
+            .subscribe_filter_map_with_handle(move |url_changed| {
+                subs::RouteMatched::from_url_changed(url_changed).map(handler)
+            })
+    }
+
+    fn subscribe_route_loader<R: RouteLoader>(
+        &mut self,
+        handler: impl FnOnce(subs::RouteLoaded<R>) -> Ms + Clone + 'static,
+    ) -> &mut Self {
+        let loader = self.route_loader(handler);
+        self.app
+            .data
+            .sub_manager
+            .borrow_mut()
+            .subscribe_filter_map(loader);
+        self
+    }
+
+    fn subscribe_route_loader_with_handle<R: RouteLoader>(
+        &mut self,
+        handler: impl FnOnce(subs::RouteLoaded<R>) -> Ms + Clone + 'static,
+    ) -> SubHandle {
+        let loader = self.route_loader(handler);
+        self.app
+            .data
+            .sub_manager
+            .borrow_mut()
+            .subscribe_filter_map_with_handle(loader)
+    }
+
     fn stream(&mut self, stream: impl Stream<Item = Ms> + 'static) -> &mut Self {
         let app = self.app.clone();
         let stream = stream.map(move |msg| app.update(msg));
@@ -155,3 +352,174 @@ impl<Ms: 'static, Mdl, ElC: View<Ms> + 'static, GMs: 'static> Orders<Ms, GMs>
         StreamManager::stream_with_handle(stream)
     }
 }
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::clock;
+    use crate::browser::{util, Url};
+    use crate::testing::TestClock;
+    use crate::virtual_dom::Node;
+    use futures::future::LocalBoxFuture;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    /// `/slow/<ms>` - the data are loaded after `ms` milliseconds.
+    #[derive(Clone)]
+    struct SlowRoute(u32);
+
+    impl Route for SlowRoute {
+        fn from_url(url: &Url) -> Option<Self> {
+            match url.path.as_slice() {
+                [slow, ms] if slow == "slow" => ms.parse().ok().map(Self),
+                _ => None,
+            }
+        }
+
+        fn to_url(&self) -> Url {
+            Url::new(vec!["slow".to_owned(), self.0.to_string()])
+        }
+    }
+
+    impl RouteLoader for SlowRoute {
+        type Data = u32;
+        type Error = ();
+
+        fn load(&self) -> LocalBoxFuture<'static, Result<u32, ()>> {
+            let ms = self.0;
+            clock::sleep(ms).map(move |()| Ok(ms)).boxed_local()
+        }
+    }
+
+    enum Msg {
+        Loaded(u32),
+        NavigationPending(bool),
+        Matched(u32),
+    }
+
+    #[derive(Default)]
+    struct Model {
+        loaded: Vec<u32>,
+        pending: Vec<bool>,
+        matched: Vec<u32>,
+    }
+
+    type TestApp = App<Msg, Model, Node<Msg>>;
+
+    fn init(_: Url, orders: &mut OrdersContainer<Msg, Model, Node<Msg>>) -> Model {
+        orders
+            .subscribe_route_loader(|loaded: subs::RouteLoaded<SlowRoute>| {
+                Msg::Loaded(loaded.result.unwrap())
+            })
+            .subscribe(|navigation: subs::NavigationPending| {
+                Msg::NavigationPending(navigation.pending)
+            });
+        Model::default()
+    }
+
+    fn update(msg: Msg, model: &mut Model, _: &mut OrdersContainer<Msg, Model, Node<Msg>>) {
+        match msg {
+            Msg::Loaded(ms) => model.loaded.push(ms),
+            Msg::NavigationPending(pending) => model.pending.push(pending),
+            Msg::Matched(ms) => model.matched.push(ms),
+        }
+    }
+
+    fn view(_: &Model) -> Node<Msg> {
+        Node::Empty
+    }
+
+    fn start_app() -> TestApp {
+        let root = util::document().create_element("div").unwrap();
+        util::body().append_child(&root).unwrap();
+        App::start(root, init, update, view)
+    }
+
+    fn navigate(app: &TestApp, path: &[&str]) {
+        app.notify(subs::UrlChanged(Url::new(path.to_vec())));
+    }
+
+    fn route_loader_group() -> String {
+        format!("seed::route_loader::{}", std::any::type_name::<SlowRoute>())
+    }
+
+    /// Let aborted and woken cmds run.
+    async fn settle(clock: &TestClock) {
+        clock.flush().await;
+        clock.flush().await;
+    }
+
+    fn model<T>(app: &TestApp, f: impl FnOnce(&Model) -> T) -> T {
+        f(app.data.model.borrow().as_ref().unwrap())
+    }
+
+    #[wasm_bindgen_test]
+    async fn navigating_away_aborts_route_load() {
+        let clock = TestClock::install();
+        let app = start_app();
+
+        navigate(&app, &["slow", "100"]);
+        settle(&clock).await;
+        assert_eq!(model(&app, |model| model.pending.clone()), vec![true]);
+
+        navigate(&app, &["elsewhere"]);
+        clock.advance(100);
+        settle(&clock).await;
+        assert!(model(&app, |model| model.loaded.is_empty()));
+        assert_eq!(
+            model(&app, |model| model.pending.clone()),
+            vec![true, false]
+        );
+        assert_eq!(app.data.cmd_groups.len(&route_loader_group()), 0);
+    }
+
+    #[wasm_bindgen_test]
+    async fn next_navigation_supersedes_route_load() {
+        let clock = TestClock::install();
+        let app = start_app();
+
+        navigate(&app, &["slow", "100"]);
+        settle(&clock).await;
+        navigate(&app, &["slow", "50"]);
+        settle(&clock).await;
+
+        clock.advance(100);
+        settle(&clock).await;
+        assert_eq!(model(&app, |model| model.loaded.clone()), vec![50]);
+        assert_eq!(
+            model(&app, |model| model.pending.clone()),
+            vec![true, false, true, false]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn route_subscriptions_receive_matching_routes() {
+        let app = start_app();
+        let mut orders = OrdersContainer::new(app.clone());
+        orders.subscribe_route(|matched: subs::RouteMatched<SlowRoute>| {
+            Msg::Matched(matched.route.0)
+        });
+        let handle =
+            orders.subscribe_route_with_handle(|matched: subs::RouteMatched<SlowRoute>| {
+                assert_eq!(matched.url, matched.route.to_url());
+                Msg::Matched(matched.route.0 * 10)
+            });
+        let matched = || {
+            let mut matched = model(&app, |model| model.matched.clone());
+            matched.sort_unstable();
+            matched
+        };
+
+        navigate(&app, &["slow", "7"]);
+        assert_eq!(matched(), vec![7, 70]);
+        navigate(&app, &["elsewhere"]);
+        assert_eq!(matched(), vec![7, 70]);
+
+        drop(handle);
+        navigate(&app, &["slow", "8"]);
+        assert_eq!(matched(), vec![7, 8, 70]);
+    }
+}
@@ -1,10 +1,16 @@
 use super::{
-    super::{App, CmdHandle, RenderTimestampDelta, StreamHandle, SubHandle, UndefinedGMsg},
+    super::{
+        App, CmdHandle, RenderTimestampDelta, StreamHandle, SubHandle, UndefinedGMsg, UnloadGuard,
+    },
     Orders, OrdersContainer,
 };
+use crate::app::subs;
+use crate::app::MessageMapper;
+use crate::browser::{dom::custom_event::DispatchTarget, Route, RouteLoader};
 use crate::virtual_dom::View;
 use futures::future::{Future, FutureExt};
 use futures::stream::{Stream, StreamExt};
+use serde::Serialize;
 use std::{any::Any, rc::Rc};
 
 #[allow(clippy::module_name_repetitions)]
@@ -92,6 +98,38 @@ impl<'a, Ms: 'static, AppMs: 'static, Mdl, ElC: View<AppMs> + 'static, GMs> Orde
             .perform_cmd_with_handle(cmd.map(move |ms| f(ms)))
     }
 
+    fn perform_cmd_in_group(
+        &mut self,
+        group: impl Into<String>,
+        cmd: impl Future<Output = Ms> + 'static,
+    ) -> &mut Self {
+        let f = self.f.clone();
+        self.orders_container
+            .perform_cmd_in_group(group, cmd.map(move |ms| f(ms)));
+        self
+    }
+
+    fn abort_group(&mut self, group: &str) -> &mut Self {
+        self.orders_container.abort_group(group);
+        self
+    }
+
+    #[allow(clippy::redundant_closure)]
+    fn perform_init_cmd(&mut self, cmd: impl Future<Output = Ms> + 'static) -> &mut Self {
+        let f = self.f.clone();
+        self.orders_container
+            .perform_init_cmd(cmd.map(move |ms| f(ms)));
+        self
+    }
+
+    #[allow(clippy::redundant_closure)]
+    fn init_placeholder(&mut self, placeholder: impl View<Ms>) -> &mut Self {
+        let f = self.f.clone();
+        self.orders_container
+            .init_placeholder(placeholder.els().map_msg(move |ms| f(ms)));
+        self
+    }
+
     fn send_g_msg(&mut self, g_msg: GMs) -> &mut Self {
         self.orders_container.send_g_msg(g_msg);
         self
@@ -129,6 +167,23 @@ impl<'a, Ms: 'static, AppMs: 'static, Mdl, ElC: View<AppMs> + 'static, GMs> Orde
         self
     }
 
+    fn dispatch_event<T: Serialize + ?Sized>(
+        &mut self,
+        target: &impl DispatchTarget,
+        name: &str,
+        detail: &T,
+    ) -> &mut Self {
+        self.orders_container.dispatch_event(target, name, detail);
+        self
+    }
+
+    fn block_unload_with<M: Into<String>>(
+        &mut self,
+        message: impl Fn() -> Option<M> + 'static,
+    ) -> UnloadGuard {
+        self.orders_container.block_unload_with(message)
+    }
+
     fn subscribe<SubMs: 'static + Clone>(
         &mut self,
         handler: impl FnOnce(SubMs) -> Ms + Clone + 'static,
@@ -148,6 +203,44 @@ impl<'a, Ms: 'static, AppMs: 'static, Mdl, ElC: View<AppMs> + 'static, GMs> Orde
             .subscribe_with_handle(move |sub_ms| f(handler(sub_ms)))
     }
 
+    fn subscribe_route<R: Route>(
+        &mut self,
+        handler: impl FnOnce(subs::RouteMatched<R>) -> Ms + Clone + 'static,
+    ) -> &mut Self {
+        let f = self.f.clone();
+        self.orders_container
+            .subscribe_route(move |route_matched| f(handler(route_matched)));
+        self
+    }
+
+    fn subscribe_route_with_handle<R: Route>(
+        &mut self,
+        handler: impl FnOnce(subs::RouteMatched<R>) -> Ms + Clone + 'static,
+    ) -> SubHandle {
+        let f = self.f.clone();
+        self.orders_container
+            .subscribe_route_with_handle(move |route_matched| f(handler(route_matched)))
+    }
+
+    fn subscribe_route_loader<R: RouteLoader>(
+        &mut self,
+        handler: impl FnOnce(subs::RouteLoaded<R>) -> Ms + Clone + 'static,
+    ) -> &mut Self {
+        let f = self.f.clone();
+        self.orders_container
+            .subscribe_route_loader(move |route_loaded| f(handler(route_loaded)));
+        self
+    }
+
+    fn subscribe_route_loader_with_handle<R: RouteLoader>(
+        &mut self,
+        handler: impl FnOnce(subs::RouteLoaded<R>) -> Ms + Clone + 'static,
+    ) -> SubHandle {
+        let f = self.f.clone();
+        self.orders_container
+            .subscribe_route_loader_with_handle(move |route_loaded| f(handler(route_loaded)))
+    }
+
     fn stream(&mut self, stream: impl Stream<Item = Ms> + 'static) -> &mut Self {
         let f = self.f.clone();
         self.orders_container.stream(stream.map(move |ms| f(ms)));
@@ -0,0 +1,267 @@
+//! Offset / cursor pagination and infinite scrolling - see `Pages`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!fn fetch_page(offset: Option<&usize>) -> impl Future<Output = Result<Page<Item, usize>, String>> {
+//!    let offset = offset.copied().unwrap_or_default();
+//!    Request::new(format!("/api/items?offset={}&limit=20", offset))
+//!        .fetch_json_data(move |result| {
+//!            result
+//!                .map(|items| Page::offset(items, offset, 20))
+//!                .map_err(|fail_reason| format!("{:?}", fail_reason))
+//!        })
+//!}
+//!
+//!fn init(_: Url, orders: &mut impl Orders<Msg>) -> Init<Model> {
+//!    let mut items = Pages::new(fetch_page, Msg::Items);
+//!    items.fetch_next_page(orders);
+//!    orders.after_next_render(|_| Msg::Rendered);
+//!    Init::new(Model { items, trigger: ElRef::default() })
+//!}
+//!
+//!fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+//!    match msg {
+//!        Msg::Items(pages_msg) => model.items.update(pages_msg, orders),
+//!        Msg::Rendered => {
+//!            if let Some(trigger) = model.trigger.get() {
+//!                model.items.observe_trigger(&trigger, orders);
+//!            }
+//!        }
+//!    }
+//!}
+//! ```
+
+use super::{streams, Orders, StreamHandle};
+use futures::future::{Future, FutureExt, LocalBoxFuture};
+use std::{fmt, rc::Rc};
+
+type PageFetcher<T, C, E> =
+    Rc<dyn Fn(Option<&C>) -> LocalBoxFuture<'static, Result<Page<T, C>, E>>>;
+
+// ------ Page ------
+
+/// A single page returned by the page fetcher.
+#[derive(Debug, Clone)]
+pub struct Page<T, C> {
+    pub items: Vec<T>,
+    /// Cursor (or offset) of the next page. `None` if it's the last page.
+    pub next: Option<C>,
+}
+
+impl<T, C> Page<T, C> {
+    pub const fn new(items: Vec<T>, next: Option<C>) -> Self {
+        Self { items, next }
+    }
+}
+
+impl<T> Page<T, usize> {
+    /// Page for offset pagination. The next page exists only if this one is full.
+    pub fn offset(items: Vec<T>, offset: usize, limit: usize) -> Self {
+        let next = if items.len() < limit {
+            None
+        } else {
+            Some(offset + items.len())
+        };
+        Self { items, next }
+    }
+}
+
+// ------ PagesMsg ------
+
+/// Messages handled by `Pages::update`.
+pub enum PagesMsg<T, C, E> {
+    PageFetched {
+        generation: u64,
+        result: Result<Page<T, C>, E>,
+    },
+    TriggerVisibilityChanged(bool),
+}
+
+impl<T, C, E> fmt::Debug for PagesMsg<T, C, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PagesMsg::PageFetched { generation, result } => f
+                .debug_struct("PageFetched")
+                .field("generation", generation)
+                .field("ok", &result.is_ok())
+                .finish(),
+            PagesMsg::TriggerVisibilityChanged(visible) => f
+                .debug_tuple("TriggerVisibilityChanged")
+                .field(visible)
+                .finish(),
+        }
+    }
+}
+
+// ------ Pages ------
+
+/// Accumulates items from pages fetched one after another. Store it in your `Model`.
+///
+/// `C` is the page cursor - e.g. `usize` for offset pagination (see `Page::offset`)
+/// or `String` for cursor pagination.
+pub struct Pages<T, C, E, Ms> {
+    items: Vec<T>,
+    page_count: usize,
+    next: Option<C>,
+    has_more: bool,
+    is_fetching: bool,
+    error: Option<E>,
+    generation: u64,
+    fetch_page: PageFetcher<T, C, E>,
+    to_msg: Rc<dyn Fn(PagesMsg<T, C, E>) -> Ms>,
+    trigger_handle: Option<StreamHandle>,
+}
+
+impl<T, C: fmt::Debug, E: fmt::Debug, Ms> fmt::Debug for Pages<T, C, E, Ms> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pages")
+            .field("items", &self.items.len())
+            .field("page_count", &self.page_count)
+            .field("next", &self.next)
+            .field("has_more", &self.has_more)
+            .field("is_fetching", &self.is_fetching)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl<T: 'static, C: Clone + 'static, E: 'static, Ms: 'static> Pages<T, C, E, Ms> {
+    /// Create an empty instance. Nothing is fetched until you call `fetch_next_page`.
+    ///
+    /// `fetch_page` gets the cursor of the requested page - `None` for the first page.
+    pub fn new<F>(
+        fetch_page: impl Fn(Option<&C>) -> F + 'static,
+        to_msg: impl FnOnce(PagesMsg<T, C, E>) -> Ms + Clone + 'static,
+    ) -> Self
+    where
+        F: Future<Output = Result<Page<T, C>, E>> + 'static,
+    {
+        Self {
+            items: Vec::new(),
+            page_count: 0,
+            next: None,
+            has_more: true,
+            is_fetching: false,
+            error: None,
+            generation: 0,
+            fetch_page: Rc::new(move |cursor| fetch_page(cursor).boxed_local()),
+            to_msg: Rc::new(move |msg| to_msg.clone()(msg)),
+            trigger_handle: None,
+        }
+    }
+
+    /// All items from the fetched pages.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// The number of fetched pages.
+    pub const fn page_count(&self) -> usize {
+        self.page_count
+    }
+
+    /// Is there a next page? It's `true` before the first page is fetched.
+    pub const fn has_more(&self) -> bool {
+        self.has_more
+    }
+
+    pub const fn is_fetching(&self) -> bool {
+        self.is_fetching
+    }
+
+    /// Error from the last fetch, if it failed.
+    pub const fn error(&self) -> Option<&E> {
+        self.error.as_ref()
+    }
+
+    /// Fetch the next page unless a fetch is in flight or there are no more pages.
+    pub fn fetch_next_page<GMs>(&mut self, orders: &mut impl Orders<Ms, GMs>) {
+        if self.is_fetching || !self.has_more {
+            return;
+        }
+        self.is_fetching = true;
+        self.error = None;
+
+        let generation = self.generation;
+        let to_msg = Rc::clone(&self.to_msg);
+        orders.perform_cmd(
+            (self.fetch_page)(self.next.as_ref())
+                .map(move |result| to_msg(PagesMsg::PageFetched { generation, result })),
+        );
+    }
+
+    /// Remove all items and fetch the first page again - e.g. when filters have been changed.
+    /// The result of a fetch in flight is ignored.
+    pub fn reset<GMs>(&mut self, orders: &mut impl Orders<Ms, GMs>) {
+        self.generation += 1;
+        self.items.clear();
+        self.page_count = 0;
+        self.next = None;
+        self.has_more = true;
+        self.is_fetching = false;
+        self.fetch_next_page(orders);
+    }
+
+    /// Fetch the next page whenever `trigger` (e.g. a spinner below the list) becomes visible.
+    /// Observing is stopped when `Pages` are dropped or `stop_observing_trigger` is called.
+    pub fn observe_trigger<GMs>(
+        &mut self,
+        trigger: &web_sys::Element,
+        orders: &mut impl Orders<Ms, GMs>,
+    ) {
+        let to_msg = Rc::clone(&self.to_msg);
+        self.trigger_handle = Some(
+            orders.stream_with_handle(streams::intersection(trigger, move |entry| {
+                to_msg(PagesMsg::TriggerVisibilityChanged(entry.is_intersecting()))
+            })),
+        );
+    }
+
+    pub fn stop_observing_trigger(&mut self) {
+        self.trigger_handle = None;
+    }
+
+    /// Handle `PagesMsg`. Call it in your `update` function.
+    pub fn update<GMs>(&mut self, msg: PagesMsg<T, C, E>, orders: &mut impl Orders<Ms, GMs>) {
+        match msg {
+            PagesMsg::PageFetched { generation, result } => {
+                if generation != self.generation {
+                    orders.skip();
+                    return;
+                }
+                self.is_fetching = false;
+                match result {
+                    Ok(page) => {
+                        self.items.extend(page.items);
+                        self.page_count += 1;
+                        self.has_more = page.next.is_some();
+                        self.next = page.next;
+                    }
+                    Err(error) => self.error = Some(error),
+                }
+            }
+            PagesMsg::TriggerVisibilityChanged(visible) => {
+                if visible && self.error.is_none() && !self.is_fetching && self.has_more {
+                    self.fetch_next_page(orders);
+                } else {
+                    orders.skip();
+                }
+            }
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn offset_page() {
+        assert_eq!(Page::offset(vec![1, 2], 10, 2).next, Some(12));
+        assert_eq!(Page::offset(vec![1], 10, 2).next, None);
+    }
+}
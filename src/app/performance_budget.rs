@@ -0,0 +1,154 @@
+//! Performance budget of renders - see `App::performance_budget`.
+//!
+//! Renders exceeding any threshold are reported by `console.warn` in debug builds,
+//! so performance regressions are noticed during development.
+
+use crate::virtual_dom::Node;
+
+// ------ PerformanceBudget ------
+
+/// Thresholds of a single render; `None` means unlimited.
+///
+/// # Example
+///
+/// ```rust,no_run
+///App::start("app", init, update, view).performance_budget(
+///    PerformanceBudget::new()
+///        .view_ms(8.)
+///        .patch_count(500)
+///        .node_count(5_000),
+///);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PerformanceBudget {
+    /// The maximum duration of the `view` call in milliseconds.
+    pub view_ms: Option<f64>,
+    /// The maximum number of DOM operations performed by the patch.
+    pub patch_count: Option<usize>,
+    /// The maximum number of nodes in the new virtual DOM.
+    pub node_count: Option<usize>,
+}
+
+impl PerformanceBudget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub const fn view_ms(mut self, view_ms: f64) -> Self {
+        self.view_ms = Some(view_ms);
+        self
+    }
+
+    pub const fn patch_count(mut self, patch_count: usize) -> Self {
+        self.patch_count = Some(patch_count);
+        self
+    }
+
+    pub const fn node_count(mut self, node_count: usize) -> Self {
+        self.node_count = Some(node_count);
+        self
+    }
+
+    /// Descriptions of the thresholds exceeded by the render.
+    pub fn exceeded(&self, metrics: &RenderMetrics) -> Vec<String> {
+        let mut exceeded = Vec::new();
+        if let Some(view_ms) = self.view_ms {
+            if metrics.view_ms > view_ms {
+                exceeded.push(format!(
+                    "view took {:.1} ms (budget {:.1} ms)",
+                    metrics.view_ms, view_ms
+                ));
+            }
+        }
+        if let Some(patch_count) = self.patch_count {
+            if metrics.patch_count > patch_count {
+                exceeded.push(format!(
+                    "patch performed {} DOM operations (budget {})",
+                    metrics.patch_count, patch_count
+                ));
+            }
+        }
+        if let Some(node_count) = self.node_count {
+            if metrics.node_count > node_count {
+                exceeded.push(format!(
+                    "view returned {} nodes (budget {})",
+                    metrics.node_count, node_count
+                ));
+            }
+        }
+        exceeded
+    }
+}
+
+// ------ RenderMetrics ------
+
+/// Measured costs of a single render.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderMetrics {
+    pub view_ms: f64,
+    pub patch_count: usize,
+    pub node_count: usize,
+}
+
+/// The number of nodes in `nodes` including all descendants; `Node::Empty`s aren't counted.
+pub(crate) fn count_nodes<Ms>(nodes: &[Node<Ms>]) -> usize {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Element(el) => 1 + count_nodes(&el.children),
+            Node::Text(_) => 1,
+            Node::Empty => 0,
+        })
+        .sum()
+}
+
+/// Warn about the render when it exceeds the `budget`.
+pub(crate) fn check(budget: &PerformanceBudget, render: u64, metrics: &RenderMetrics) {
+    let exceeded = budget.exceeded(metrics);
+    if !exceeded.is_empty() {
+        web_sys::console::warn_1(
+            &format!(
+                "Render #{} exceeded the performance budget: {}.",
+                render,
+                exceeded.join(", ")
+            )
+            .into(),
+        );
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::virtual_dom::{El, Tag, UpdateEl};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn exceeded_thresholds() {
+        let budget = PerformanceBudget::new().view_ms(8.).patch_count(10);
+        let metrics = RenderMetrics {
+            view_ms: 12.5,
+            patch_count: 10,
+            node_count: 1_000_000,
+        };
+        assert_eq!(
+            budget.exceeded(&metrics),
+            vec!["view took 12.5 ms (budget 8.0 ms)".to_owned()]
+        );
+        assert!(PerformanceBudget::new().exceeded(&metrics).is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn nodes_are_counted_recursively() {
+        let nodes: Vec<Node<()>> = vec![
+            div![ul![li!["a"], li!["b"]], Node::Empty],
+            Node::new_text("c"),
+            Node::Element(El::empty(Tag::Span)),
+        ];
+        assert_eq!(count_nodes(&nodes), 8);
+    }
+}
@@ -0,0 +1,558 @@
+//! Async data management per key - caching, deduplication, refetching and invalidation.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!struct Model {
+//!    users: QueryClient<Vec<User>, FailReason<Vec<User>>, Msg>,
+//!}
+//!
+//!enum Msg {
+//!    UsersQuery(QueryMsg<Vec<User>, FailReason<Vec<User>>>),
+//!    UserSaved,
+//!}
+//!
+//!fn fetch_users() -> impl Future<Output = Result<Vec<User>, FailReason<Vec<User>>>> {
+//!    Request::new("/api/users").fetch_json_data(identity)
+//!}
+//!
+//!fn init(_: Url, orders: &mut impl Orders<Msg>) -> Init<Model> {
+//!    let mut users = QueryClient::new(Msg::UsersQuery).stale_time(30_000);
+//!    users.subscribe(orders);
+//!    users.fetch("all", fetch_users, orders);
+//!    Init::new(Model { users })
+//!}
+//!
+//!fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+//!    match msg {
+//!        Msg::UsersQuery(query_msg) => model.users.update(query_msg, orders),
+//!        Msg::UserSaved => model.users.invalidate("all", orders),
+//!    }
+//!}
+//!
+//!fn view(model: &Model) -> Node<Msg> {
+//!    match model.users.query("all").map(Query::status) {
+//!        Some(QueryStatus::Success) => ...,
+//!        Some(QueryStatus::Failure) => ...,
+//!        _ => div!["Loading..."],
+//!    }
+//!}
+//! ```
+//!
+//! Or let `Node::suspense` render the placeholder until the data arrive:
+//!
+//! ```rust,no_run
+//!fn view(model: &Model) -> Node<Msg> {
+//!    Node::suspense(model.users.query("all"), div!["Loading..."], |users| view_users(users))
+//!}
+//! ```
+
+use super::{streams, Orders, StreamHandle};
+use crate::virtual_dom::Ev;
+use futures::future::{Future, FutureExt, LocalBoxFuture};
+use std::{collections::HashMap, fmt, rc::Rc};
+
+type Fetcher<T, E> = Rc<dyn Fn() -> LocalBoxFuture<'static, Result<T, E>>>;
+
+// ------ QueryStatus ------
+
+/// Status of `Query` - useful in `view` functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryStatus {
+    /// The query hasn't been fetched yet.
+    Idle,
+    /// The first fetch is in flight.
+    Loading,
+    /// The last fetch succeeded. Check `Query::is_fetching` to see if a refetch is running.
+    Success,
+    /// The last fetch failed. `Query::data` can still contain older data.
+    Failure,
+}
+
+// ------ Query ------
+
+/// State of data for a single key.
+pub struct Query<T, E> {
+    /// Data from the last successful fetch.
+    pub data: Option<T>,
+    /// Error from the last fetch if it failed.
+    pub error: Option<E>,
+    is_fetching: bool,
+    invalidated: bool,
+    updated_at: Option<f64>,
+    generation: u64,
+    fetcher: Option<Fetcher<T, E>>,
+}
+
+impl<T: fmt::Debug, E: fmt::Debug> fmt::Debug for Query<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Query")
+            .field("data", &self.data)
+            .field("error", &self.error)
+            .field("is_fetching", &self.is_fetching)
+            .field("invalidated", &self.invalidated)
+            .field("updated_at", &self.updated_at)
+            .finish()
+    }
+}
+
+impl<T, E> Default for Query<T, E> {
+    fn default() -> Self {
+        Self {
+            data: None,
+            error: None,
+            is_fetching: false,
+            invalidated: false,
+            updated_at: None,
+            generation: 0,
+            fetcher: None,
+        }
+    }
+}
+
+impl<T, E> Query<T, E> {
+    pub fn status(&self) -> QueryStatus {
+        match (&self.error, &self.updated_at, self.is_fetching) {
+            (Some(_), ..) => QueryStatus::Failure,
+            (None, Some(_), _) => QueryStatus::Success,
+            (None, None, true) => QueryStatus::Loading,
+            (None, None, false) => QueryStatus::Idle,
+        }
+    }
+
+    /// Is a fetch (the first one or a refetch) in flight?
+    pub const fn is_fetching(&self) -> bool {
+        self.is_fetching
+    }
+
+    /// Time in milliseconds since the Unix epoch when the data were successfully fetched.
+    pub const fn updated_at(&self) -> Option<f64> {
+        self.updated_at
+    }
+
+    fn is_stale(&self, stale_time: u32) -> bool {
+        self.invalidated
+            || self.updated_at.map_or(true, |updated_at| {
+                js_sys::Date::now() - updated_at >= f64::from(stale_time)
+            })
+    }
+}
+
+// ------ QueryMsg ------
+
+/// Messages handled by `QueryClient::update`.
+pub enum QueryMsg<T, E> {
+    Fetched {
+        key: String,
+        generation: u64,
+        result: Result<T, E>,
+    },
+    WindowFocused,
+    Reconnected,
+}
+
+impl<T, E> fmt::Debug for QueryMsg<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryMsg::Fetched {
+                key,
+                generation,
+                result,
+            } => f
+                .debug_struct("Fetched")
+                .field("key", key)
+                .field("generation", generation)
+                .field("ok", &result.is_ok())
+                .finish(),
+            QueryMsg::WindowFocused => write!(f, "WindowFocused"),
+            QueryMsg::Reconnected => write!(f, "Reconnected"),
+        }
+    }
+}
+
+// ------ QueryClient ------
+
+/// Manages `Query`s with data of the same type. Store it in your `Model`.
+///
+/// - Fetches for the same key are deduplicated - only one request is in flight at a time.
+/// - Data are cached and refetched only when they are older than `stale_time` (0 by default)
+///   or invalidated.
+/// - Stale queries are refetched on window focus and network reconnect once you call `subscribe`.
+pub struct QueryClient<T, E, Ms> {
+    queries: HashMap<String, Query<T, E>>,
+    stale_time: u32,
+    to_msg: Rc<dyn Fn(QueryMsg<T, E>) -> Ms>,
+    stream_handles: Vec<StreamHandle>,
+}
+
+impl<T, E, Ms> fmt::Debug for QueryClient<T, E, Ms> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryClient")
+            .field("keys", &self.queries.keys().collect::<Vec<_>>())
+            .field("stale_time", &self.stale_time)
+            .field("subscribed", &!self.stream_handles.is_empty())
+            .finish()
+    }
+}
+
+impl<T: 'static, E: 'static, Ms: 'static> QueryClient<T, E, Ms> {
+    /// Create a new client. `to_msg` wraps `QueryMsg`s into your `Msg`.
+    pub fn new(to_msg: impl FnOnce(QueryMsg<T, E>) -> Ms + Clone + 'static) -> Self {
+        Self {
+            queries: HashMap::new(),
+            stale_time: 0,
+            to_msg: Rc::new(move |msg| to_msg.clone()(msg)),
+            stream_handles: Vec::new(),
+        }
+    }
+
+    /// Set how long fetched data are considered fresh, in milliseconds.
+    pub const fn stale_time(mut self, millis: u32) -> Self {
+        self.stale_time = millis;
+        self
+    }
+
+    /// Refetch stale queries on window focus and when the browser goes online.
+    /// Subscriptions are cancelled when the client is dropped.
+    pub fn subscribe<GMs>(&mut self, orders: &mut impl Orders<Ms, GMs>) {
+        let (on_focus, on_online) = (Rc::clone(&self.to_msg), Rc::clone(&self.to_msg));
+        self.stream_handles = vec![
+            orders.stream_with_handle(streams::window_event(Ev::Focus, move |_| {
+                on_focus(QueryMsg::WindowFocused)
+            })),
+            orders.stream_with_handle(streams::window_event(Ev::Online, move |_| {
+                on_online(QueryMsg::Reconnected)
+            })),
+        ];
+    }
+
+    /// Returns the query for the `key`.
+    pub fn query(&self, key: &str) -> Option<&Query<T, E>> {
+        self.queries.get(key)
+    }
+
+    /// Returns the query's data for the `key`.
+    pub fn data(&self, key: &str) -> Option<&T> {
+        self.query(key).and_then(|query| query.data.as_ref())
+    }
+
+    /// Register `fetcher` for the `key` and fetch data if they are missing or stale
+    /// and no fetch is in flight.
+    pub fn fetch<F, GMs>(
+        &mut self,
+        key: impl Into<String>,
+        fetcher: impl Fn() -> F + 'static,
+        orders: &mut impl Orders<Ms, GMs>,
+    ) where
+        F: Future<Output = Result<T, E>> + 'static,
+    {
+        let key = key.into();
+        let query = self.queries.entry(key.clone()).or_default();
+        query.fetcher = Some(Rc::new(move || fetcher().boxed_local()));
+        if !query.is_fetching && query.is_stale(self.stale_time) {
+            self.start_fetch(&key, orders);
+        }
+    }
+
+    /// Fetch the `key` again even if its data are fresh. A fetch in flight is reused.
+    pub fn refetch<GMs>(&mut self, key: &str, orders: &mut impl Orders<Ms, GMs>) {
+        if self
+            .queries
+            .get(key)
+            .map_or(false, |query| !query.is_fetching)
+        {
+            self.start_fetch(key, orders);
+        }
+    }
+
+    /// Mark the `key` as stale and refetch it.
+    /// The result of a fetch in flight is ignored because it could contain outdated data.
+    pub fn invalidate<GMs>(&mut self, key: &str, orders: &mut impl Orders<Ms, GMs>) {
+        if let Some(query) = self.queries.get_mut(key) {
+            query.invalidated = true;
+            self.start_fetch(key, orders);
+        }
+    }
+
+    /// Invalidate all keys starting with `prefix` - e.g. `"users/"`.
+    pub fn invalidate_prefix<GMs>(&mut self, prefix: &str, orders: &mut impl Orders<Ms, GMs>) {
+        let keys = self
+            .queries
+            .keys()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect::<Vec<_>>();
+        for key in keys {
+            self.invalidate(&key, orders);
+        }
+    }
+
+    /// Replace the `key`'s data - e.g. with data returned from a mutation.
+    pub fn set_data(&mut self, key: impl Into<String>, data: T) {
+        let query = self.queries.entry(key.into()).or_default();
+        query.data = Some(data);
+        query.error = None;
+        query.invalidated = false;
+        query.updated_at = Some(js_sys::Date::now());
+    }
+
+    /// Remove the query - its data and fetcher - for the `key`.
+    pub fn remove(&mut self, key: &str) -> Option<Query<T, E>> {
+        self.queries.remove(key)
+    }
+
+    /// Handle `QueryMsg`. Call it in your `update` function.
+    pub fn update<GMs>(&mut self, msg: QueryMsg<T, E>, orders: &mut impl Orders<Ms, GMs>) {
+        match msg {
+            QueryMsg::Fetched {
+                key,
+                generation,
+                result,
+            } => {
+                let query = match self.queries.get_mut(&key) {
+                    Some(query) if query.generation == generation => query,
+                    // The query has been removed or invalidated in the meantime.
+                    _ => {
+                        orders.skip();
+                        return;
+                    }
+                };
+                query.is_fetching = false;
+                match result {
+                    Ok(data) => {
+                        query.data = Some(data);
+                        query.error = None;
+                        query.invalidated = false;
+                        query.updated_at = Some(js_sys::Date::now());
+                    }
+                    Err(error) => query.error = Some(error),
+                }
+            }
+            QueryMsg::WindowFocused | QueryMsg::Reconnected => {
+                let stale_time = self.stale_time;
+                let keys = self
+                    .queries
+                    .iter()
+                    .filter(|(_, query)| query.fetcher.is_some())
+                    .filter(|(_, query)| !query.is_fetching && query.is_stale(stale_time))
+                    .map(|(key, _)| key.clone())
+                    .collect::<Vec<_>>();
+                if keys.is_empty() {
+                    orders.skip();
+                }
+                for key in keys {
+                    self.start_fetch(&key, orders);
+                }
+            }
+        }
+    }
+
+    fn start_fetch<GMs>(&mut self, key: &str, orders: &mut impl Orders<Ms, GMs>) {
+        let query = match self.queries.get_mut(key) {
+            Some(query) => query,
+            None => return,
+        };
+        let fetcher = match &query.fetcher {
+            Some(fetcher) => Rc::clone(fetcher),
+            None => return,
+        };
+        query.generation += 1;
+        query.is_fetching = true;
+
+        let (key, generation, to_msg) = (key.to_owned(), query.generation, Rc::clone(&self.to_msg));
+        orders.perform_cmd(fetcher().map(move |result| {
+            to_msg(QueryMsg::Fetched {
+                key,
+                generation,
+                result,
+            })
+        }));
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{clock, App, OrdersContainer};
+    use crate::browser::{util, Url};
+    use crate::testing::TestClock;
+    use crate::virtual_dom::Node;
+    use std::cell::Cell;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Responds with the number of the request after 100 ms.
+    /// Requests are counted per app - apps of other tests stay alive and refetch their queries too.
+    fn fetch_number(requests: &Cell<u32>) -> impl Future<Output = Result<u32, ()>> {
+        let request = requests.get() + 1;
+        requests.set(request);
+        clock::sleep(100).map(move |()| Ok(request))
+    }
+
+    enum Msg {
+        Query(QueryMsg<u32, ()>),
+        Fetch(&'static str),
+        Invalidate(&'static str),
+        InvalidatePrefix(&'static str),
+    }
+
+    struct Model {
+        numbers: QueryClient<u32, (), Msg>,
+        requests: Rc<Cell<u32>>,
+    }
+
+    type TestApp = App<Msg, Model, Node<Msg>>;
+
+    fn init(_: Url, orders: &mut OrdersContainer<Msg, Model, Node<Msg>>) -> Model {
+        let mut numbers = QueryClient::new(Msg::Query);
+        numbers.subscribe(orders);
+        Model {
+            numbers,
+            requests: Rc::default(),
+        }
+    }
+
+    fn update(msg: Msg, model: &mut Model, orders: &mut OrdersContainer<Msg, Model, Node<Msg>>) {
+        match msg {
+            Msg::Query(query_msg) => model.numbers.update(query_msg, orders),
+            Msg::Fetch(key) => {
+                let requests = Rc::clone(&model.requests);
+                model
+                    .numbers
+                    .fetch(key, move || fetch_number(&requests), orders);
+            }
+            Msg::Invalidate(key) => model.numbers.invalidate(key, orders),
+            Msg::InvalidatePrefix(prefix) => model.numbers.invalidate_prefix(prefix, orders),
+        }
+    }
+
+    fn view(_: &Model) -> Node<Msg> {
+        Node::Empty
+    }
+
+    fn start_app() -> TestApp {
+        let root = util::document().create_element("div").unwrap();
+        util::body().append_child(&root).unwrap();
+        App::start(root, init, update, view)
+    }
+
+    /// Let the fetches in flight respond.
+    async fn respond(clock: &TestClock) {
+        clock.flush().await;
+        clock.advance(100);
+        clock.flush().await;
+        clock.flush().await;
+    }
+
+    fn data(app: &TestApp, key: &str) -> Option<u32> {
+        app.data
+            .model
+            .borrow()
+            .as_ref()
+            .unwrap()
+            .numbers
+            .data(key)
+            .copied()
+    }
+
+    fn requests(app: &TestApp) -> u32 {
+        app.data.model.borrow().as_ref().unwrap().requests.get()
+    }
+
+    fn generation(app: &TestApp, key: &str) -> u64 {
+        app.data.model.borrow().as_ref().unwrap().numbers.queries[key].generation
+    }
+
+    #[wasm_bindgen_test]
+    async fn concurrent_fetches_make_one_request() {
+        let clock = TestClock::install();
+        let app = start_app();
+        let before = requests(&app);
+
+        app.update(Msg::Fetch("numbers"));
+        app.update(Msg::Fetch("numbers"));
+        assert_eq!(requests(&app), before + 1);
+
+        respond(&clock).await;
+        assert_eq!(data(&app, "numbers"), Some(before + 1));
+    }
+
+    #[wasm_bindgen_test]
+    async fn outdated_response_is_dropped() {
+        let clock = TestClock::install();
+        let app = start_app();
+
+        app.update(Msg::Fetch("numbers"));
+        let generation = generation(&app, "numbers");
+        app.update(Msg::Query(QueryMsg::Fetched {
+            key: "numbers".to_owned(),
+            generation: generation - 1,
+            result: Ok(999),
+        }));
+        assert_eq!(data(&app, "numbers"), None);
+
+        app.update(Msg::Invalidate("numbers"));
+        respond(&clock).await;
+        // The response of the first fetch arrives too, but only the second one is stored.
+        assert_eq!(data(&app, "numbers"), Some(requests(&app)));
+        assert!(
+            !app.data.model.borrow().as_ref().unwrap().numbers.queries["numbers"].is_fetching()
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn invalidated_queries_are_refetched() {
+        let clock = TestClock::install();
+        let app = start_app();
+        for key in ["users/1", "users/2", "posts"].iter().copied() {
+            app.update(Msg::Fetch(key));
+        }
+        respond(&clock).await;
+
+        let before = requests(&app);
+        app.update(Msg::InvalidatePrefix("users/"));
+        assert_eq!(requests(&app), before + 2);
+        app.update(Msg::Invalidate("posts"));
+        assert_eq!(requests(&app), before + 3);
+
+        respond(&clock).await;
+        assert_eq!(data(&app, "posts"), Some(before + 3));
+    }
+
+    #[wasm_bindgen_test]
+    async fn stale_queries_are_refetched_on_focus_and_online() {
+        let clock = TestClock::install();
+        let app = start_app();
+        app.update(Msg::Fetch("numbers"));
+        respond(&clock).await;
+
+        // The default `stale_time` is 0 - the data are stale immediately.
+        for trigger in &["focus", "online"] {
+            let before = requests(&app);
+            util::window()
+                .dispatch_event(&web_sys::Event::new(trigger).unwrap())
+                .unwrap();
+            clock.flush().await;
+            assert_eq!(requests(&app), before + 1);
+            respond(&clock).await;
+            assert_eq!(data(&app, "numbers"), Some(before + 1));
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn suspense_renders_loading_until_data() {
+        let mut client = QueryClient::<u32, (), ()>::new(|_| ());
+        let render = |client: &QueryClient<u32, (), ()>| {
+            Node::<()>::suspense(client.query("count"), Node::new_text("Loading"), |count| {
+                Node::new_text(count.to_string())
+            })
+            .get_text()
+        };
+        assert_eq!(render(&client), "Loading");
+        client.set_data("count", 3);
+        assert_eq!(render(&client), "3");
+    }
+}
@@ -0,0 +1,600 @@
+//! Hierarchical state machines for complex flows - multi-step wizards, checkouts, media players.
+//!
+//! Declare the states, their parents, guarded transitions and entry / exit actions,
+//! then `start` the chart and `send` events to it from `update`. Actions emit messages
+//! and commands through `Emit`; they're performed by `Orders` after the transition.
+//!
+//! An event is handled by the innermost active state which has a matching transition
+//! with a passing guard - otherwise it bubbles to the state's parent.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+//!enum Step { Form, Account, Address, Submitting, Done }
+//!
+//!#[derive(Debug, PartialEq)]
+//!enum Event { Next, Back, Submitted }
+//!
+//!fn wizard() -> Statechart<Step, Event, Msg> {
+//!    Statechart::new(Step::Form)
+//!        .state(Step::Form, State::new().initial(Step::Account))
+//!        .state(Step::Account, State::new().parent(Step::Form).on(Event::Next, Step::Address))
+//!        .state(
+//!            Step::Address,
+//!            State::new()
+//!                .parent(Step::Form)
+//!                .on(Event::Back, Step::Account)
+//!                .on(Event::Next, Step::Submitting),
+//!        )
+//!        .state(
+//!            Step::Submitting,
+//!            State::new()
+//!                .on_entry(|emit| { emit.cmd(async { Msg::Submitted(submit().await) }); })
+//!                .on(Event::Submitted, Step::Done),
+//!        )
+//!        .state(Step::Done, State::new())
+//!}
+//!
+//!fn init(_: Url, orders: &mut impl Orders<Msg>) -> Model {
+//!    let mut wizard = wizard();
+//!    wizard.start(orders).expect("valid wizard statechart");
+//!    Model { wizard }
+//!}
+//!
+//!fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+//!    match msg {
+//!        Msg::NextClicked => { model.wizard.send(&Event::Next, orders); }
+//!        Msg::BackClicked => { model.wizard.send(&Event::Back, orders); }
+//!        Msg::Submitted(_) => { model.wizard.send(&Event::Submitted, orders); }
+//!    }
+//!}
+//!
+//!fn view(model: &Model) -> Node<Msg> {
+//!    div![
+//!        IF!(model.wizard.is_in(Step::Address) => view_address_form(model)),
+//!        // Debug inspector of the chart.
+//!        model.wizard.view_inspector(),
+//!    ]
+//!}
+//! ```
+
+use super::Orders;
+use crate::virtual_dom::{At, El, Node, St, ToClasses, UpdateEl};
+use futures::future::{Future, FutureExt, LocalBoxFuture};
+use indexmap::IndexMap;
+use std::{fmt, hash::Hash};
+
+type Action<Ms> = Box<dyn Fn(&mut Emit<Ms>)>;
+type TransitionAction<E, Ms> = Box<dyn Fn(&E, &mut Emit<Ms>)>;
+type Guard<E> = Box<dyn Fn(&E) -> bool>;
+
+/// Statechart result.
+pub type Result<T> = std::result::Result<T, StatechartError>;
+
+// ------ StatechartError ------
+
+/// The statechart isn't valid; returned by `Statechart::start`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatechartError {
+    /// The state is used as an initial state, a parent or a target, but it hasn't been declared.
+    UndeclaredState(String),
+    /// The initial child of the state isn't its child.
+    InvalidInitialChild { state: String, child: String },
+    /// The state is its own ancestor.
+    ParentCycle(String),
+}
+
+// ------ Emit ------
+
+/// Messages and commands emitted by actions.
+pub struct Emit<Ms> {
+    msgs: Vec<Ms>,
+    cmds: Vec<LocalBoxFuture<'static, Ms>>,
+}
+
+impl<Ms> fmt::Debug for Emit<Ms> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Emit")
+            .field("msgs", &self.msgs.len())
+            .field("cmds", &self.cmds.len())
+            .finish()
+    }
+}
+
+impl<Ms: 'static> Emit<Ms> {
+    const fn new() -> Self {
+        Self {
+            msgs: Vec::new(),
+            cmds: Vec::new(),
+        }
+    }
+
+    /// Send the message after the transition - see `Orders::send_msg`.
+    pub fn msg(&mut self, msg: Ms) -> &mut Self {
+        self.msgs.push(msg);
+        self
+    }
+
+    /// Perform the command after the transition - see `Orders::perform_cmd`.
+    pub fn cmd(&mut self, cmd: impl Future<Output = Ms> + 'static) -> &mut Self {
+        self.cmds.push(cmd.boxed_local());
+        self
+    }
+
+    fn perform<GMs: 'static>(self, orders: &mut impl Orders<Ms, GMs>) {
+        for msg in self.msgs {
+            orders.send_msg(msg);
+        }
+        for cmd in self.cmds {
+            orders.perform_cmd(cmd);
+        }
+    }
+}
+
+// ------ State ------
+
+/// Declaration of a single state; see `Statechart::state`.
+pub struct State<S, E, Ms> {
+    parent: Option<S>,
+    initial: Option<S>,
+    transitions: Vec<Transition<S, E, Ms>>,
+    on_entry: Vec<Action<Ms>>,
+    on_exit: Vec<Action<Ms>>,
+}
+
+struct Transition<S, E, Ms> {
+    event: E,
+    target: S,
+    guard: Option<Guard<E>>,
+    action: Option<TransitionAction<E, Ms>>,
+}
+
+impl<S, E, Ms> Default for State<S, E, Ms> {
+    fn default() -> Self {
+        Self {
+            parent: None,
+            initial: None,
+            transitions: Vec::new(),
+            on_entry: Vec::new(),
+            on_exit: Vec::new(),
+        }
+    }
+}
+
+impl<S: fmt::Debug, E: fmt::Debug, Ms> fmt::Debug for State<S, E, Ms> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("State")
+            .field("parent", &self.parent)
+            .field("initial", &self.initial)
+            .field(
+                "transitions",
+                &self
+                    .transitions
+                    .iter()
+                    .map(|transition| (&transition.event, &transition.target))
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl<S, E, Ms: 'static> State<S, E, Ms> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make the state a child of `parent`. States without a parent are top-level states.
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn parent(mut self, parent: S) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// The child entered when the state itself is the transition target.
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn initial(mut self, child: S) -> Self {
+        self.initial = Some(child);
+        self
+    }
+
+    /// Go to `target` on `event`.
+    pub fn on(mut self, event: E, target: S) -> Self {
+        self.transitions.push(Transition {
+            event,
+            target,
+            guard: None,
+            action: None,
+        });
+        self
+    }
+
+    /// Go to `target` on `event` when `guard` returns `true`. Transitions are checked
+    /// in the declaration order.
+    pub fn on_if(mut self, event: E, target: S, guard: impl Fn(&E) -> bool + 'static) -> Self {
+        self.transitions.push(Transition {
+            event,
+            target,
+            guard: Some(Box::new(guard)),
+            action: None,
+        });
+        self
+    }
+
+    /// Go to `target` on `event` and call `action` between exiting and entering states.
+    pub fn on_with(
+        mut self,
+        event: E,
+        target: S,
+        action: impl Fn(&E, &mut Emit<Ms>) + 'static,
+    ) -> Self {
+        self.transitions.push(Transition {
+            event,
+            target,
+            guard: None,
+            action: Some(Box::new(action)),
+        });
+        self
+    }
+
+    /// Call `action` when the state is entered.
+    pub fn on_entry(mut self, action: impl Fn(&mut Emit<Ms>) + 'static) -> Self {
+        self.on_entry.push(Box::new(action));
+        self
+    }
+
+    /// Call `action` when the state is exited.
+    pub fn on_exit(mut self, action: impl Fn(&mut Emit<Ms>) + 'static) -> Self {
+        self.on_exit.push(Box::new(action));
+        self
+    }
+}
+
+// ------ Statechart ------
+
+/// A hierarchical state machine. See the module docs.
+pub struct Statechart<S, E, Ms> {
+    initial: S,
+    states: IndexMap<S, State<S, E, Ms>>,
+    /// Active states from the top-level state to the leaf; empty until `start`.
+    active: Vec<S>,
+}
+
+impl<S, E, Ms> fmt::Debug for Statechart<S, E, Ms>
+where
+    S: fmt::Debug + Eq + Hash,
+    E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Statechart")
+            .field("initial", &self.initial)
+            .field("states", &self.states)
+            .field("active", &self.active)
+            .finish()
+    }
+}
+
+/// The chart as an indented tree; active states are marked by `*`.
+impl<S, E, Ms> fmt::Display for Statechart<S, E, Ms>
+where
+    S: Copy + Eq + Hash + fmt::Debug,
+    E: PartialEq,
+    Ms: 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (state, depth) in self.tree() {
+            let marker = if self.is_in(state) { "*" } else { " " };
+            writeln!(f, "{}{} {:?}", "  ".repeat(depth), marker, state)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S, E, Ms> Statechart<S, E, Ms>
+where
+    S: Copy + Eq + Hash + fmt::Debug,
+    E: PartialEq,
+    Ms: 'static,
+{
+    /// Create a chart which starts in the top-level state `initial`.
+    pub fn new(initial: S) -> Self {
+        Self {
+            initial,
+            states: IndexMap::new(),
+            active: Vec::new(),
+        }
+    }
+
+    /// Declare a state. Declaring it again replaces the previous declaration.
+    pub fn state(mut self, state: S, declaration: State<S, E, Ms>) -> Self {
+        self.states.insert(state, declaration);
+        self
+    }
+
+    /// Validate the chart and enter the initial states.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StatechartError` when the chart isn't valid.
+    pub fn start<GMs: 'static>(&mut self, orders: &mut impl Orders<Ms, GMs>) -> Result<()> {
+        let mut emit = Emit::new();
+        self.enter_initial(&mut emit)?;
+        emit.perform(orders);
+        Ok(())
+    }
+
+    /// Handle the event; returns `true` when a transition has been taken.
+    pub fn send<GMs: 'static>(&mut self, event: &E, orders: &mut impl Orders<Ms, GMs>) -> bool {
+        let mut emit = Emit::new();
+        let transitioned = self.transition(event, &mut emit);
+        emit.perform(orders);
+        transitioned
+    }
+
+    fn enter_initial(&mut self, emit: &mut Emit<Ms>) -> Result<()> {
+        self.validate()?;
+        self.active.clear();
+        self.enter(self.path(self.initial), emit);
+        Ok(())
+    }
+
+    fn transition(&mut self, event: &E, emit: &mut Emit<Ms>) -> bool {
+        let (source, transition_index) = match self.find_transition(event) {
+            Some(found) => found,
+            None => return false,
+        };
+        let target = self.states[&source].transitions[transition_index].target;
+
+        // The states above the transition's domain stay active.
+        let target_path = self.path(target);
+        let source_path = self.path(source);
+        let mut common = source_path
+            .iter()
+            .zip(&target_path)
+            .take_while(|(source, target)| source == target)
+            .count();
+        // Self-transitions and transitions to ancestors exit and re-enter the target.
+        if common == target_path.len() {
+            common -= 1;
+        }
+
+        while self.active.len() > common {
+            let state = self.active.pop().expect("active state to exit");
+            for action in &self.states[&state].on_exit {
+                action(emit);
+            }
+        }
+        if let Some(action) = &self.states[&source].transitions[transition_index].action {
+            action(event, emit);
+        }
+        self.enter(target_path[common..].to_vec(), emit);
+        true
+    }
+
+    /// The state or one of its descendants is active.
+    pub fn is_in(&self, state: S) -> bool {
+        self.active.contains(&state)
+    }
+
+    /// The innermost active state; `None` before `start`.
+    pub fn current(&self) -> Option<S> {
+        self.active.last().copied()
+    }
+
+    /// Active states from the top-level state to the innermost one.
+    pub fn active(&self) -> &[S] {
+        &self.active
+    }
+
+    /// Debug inspector - the chart as a nested list with the active states highlighted.
+    pub fn view_inspector<ParentMs>(&self) -> Node<ParentMs> {
+        let items = self
+            .tree()
+            .into_iter()
+            .map(|(state, depth)| {
+                let active = self.is_in(state);
+                li![
+                    C!["statechart-state", IF!(active => "active")],
+                    style! {
+                        St::PaddingLeft => format!("{}em", depth),
+                        St::FontWeight => if active { "bold" } else { "normal" },
+                    },
+                    format!("{:?}", state),
+                ]
+            })
+            .collect::<Vec<_>>();
+        ul![C!["statechart-inspector"], items]
+    }
+
+    fn find_transition(&self, event: &E) -> Option<(S, usize)> {
+        self.active.iter().rev().find_map(|state| {
+            self.states[state]
+                .transitions
+                .iter()
+                .position(|transition| {
+                    transition.event == *event
+                        && transition.guard.as_ref().map_or(true, |guard| guard(event))
+                })
+                .map(|index| (*state, index))
+        })
+    }
+
+    /// Enter `path` and then initial children of its last state.
+    fn enter(&mut self, mut path: Vec<S>, emit: &mut Emit<Ms>) {
+        while let Some(initial) = path.last().and_then(|state| self.states[state].initial) {
+            path.push(initial);
+        }
+        for state in path {
+            self.active.push(state);
+            for action in &self.states[&state].on_entry {
+                action(emit);
+            }
+        }
+    }
+
+    /// The state and its ancestors, from the top-level state.
+    fn path(&self, state: S) -> Vec<S> {
+        let mut path = vec![state];
+        let mut current = state;
+        while let Some(parent) = self.states[&current].parent {
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// States in the depth-first order with their depth.
+    fn tree(&self) -> Vec<(S, usize)> {
+        let mut tree = Vec::new();
+        self.push_subtree(None, 0, &mut tree);
+        tree
+    }
+
+    fn push_subtree(&self, parent: Option<S>, depth: usize, tree: &mut Vec<(S, usize)>) {
+        for (state, declaration) in &self.states {
+            if declaration.parent == parent {
+                tree.push((*state, depth));
+                self.push_subtree(Some(*state), depth + 1, tree);
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let undeclared = |state: &S| StatechartError::UndeclaredState(format!("{:?}", state));
+        if !self.states.contains_key(&self.initial) {
+            return Err(undeclared(&self.initial));
+        }
+        for (state, declaration) in &self.states {
+            let referenced = declaration.parent.iter().chain(&declaration.initial).chain(
+                declaration
+                    .transitions
+                    .iter()
+                    .map(|transition| &transition.target),
+            );
+            for referenced in referenced {
+                if !self.states.contains_key(referenced) {
+                    return Err(undeclared(referenced));
+                }
+            }
+            if let Some(initial) = declaration.initial {
+                if self.states[&initial].parent != Some(*state) {
+                    return Err(StatechartError::InvalidInitialChild {
+                        state: format!("{:?}", state),
+                        child: format!("{:?}", initial),
+                    });
+                }
+            }
+            let mut ancestor = declaration.parent;
+            for _ in 0..self.states.len() {
+                ancestor = match ancestor {
+                    Some(ancestor) => self.states[&ancestor].parent,
+                    None => break,
+                };
+            }
+            if ancestor.is_some() {
+                return Err(StatechartError::ParentCycle(format!("{:?}", state)));
+            }
+        }
+        Ok(())
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Step {
+        Form,
+        Account,
+        Address,
+        Done,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        Next,
+        Back,
+        Reset,
+    }
+
+    fn wizard(address_valid: bool) -> Statechart<Step, Event, &'static str> {
+        Statechart::new(Step::Form)
+            .state(
+                Step::Form,
+                State::new()
+                    .initial(Step::Account)
+                    .on_entry(|emit| {
+                        emit.msg("enter form");
+                    })
+                    .on_exit(|emit| {
+                        emit.msg("exit form");
+                    })
+                    .on(Event::Reset, Step::Form),
+            )
+            .state(
+                Step::Account,
+                State::new()
+                    .parent(Step::Form)
+                    .on(Event::Next, Step::Address),
+            )
+            .state(
+                Step::Address,
+                State::new()
+                    .parent(Step::Form)
+                    .on(Event::Back, Step::Account)
+                    .on_if(Event::Next, Step::Done, move |_| address_valid),
+            )
+            .state(Step::Done, State::new())
+    }
+
+    #[wasm_bindgen_test]
+    fn transitions_enter_and_exit_states() {
+        let mut chart = wizard(true);
+        let mut emit = Emit::new();
+        chart.enter_initial(&mut emit).unwrap();
+        assert_eq!(chart.active(), &[Step::Form, Step::Account]);
+
+        assert!(chart.transition(&Event::Next, &mut emit));
+        assert_eq!(chart.current(), Some(Step::Address));
+
+        // `Reset` bubbles to `Form` and re-enters it.
+        assert!(chart.transition(&Event::Reset, &mut emit));
+        assert_eq!(chart.current(), Some(Step::Account));
+
+        chart.transition(&Event::Next, &mut emit);
+        assert!(chart.transition(&Event::Next, &mut emit));
+        assert_eq!(chart.active(), &[Step::Done]);
+        assert_eq!(
+            emit.msgs,
+            vec!["enter form", "exit form", "enter form", "exit form"]
+        );
+        assert!(!chart.transition(&Event::Back, &mut emit));
+    }
+
+    #[wasm_bindgen_test]
+    fn guard_blocks_transition() {
+        let mut chart = wizard(false);
+        let mut emit = Emit::new();
+        chart.enter_initial(&mut emit).unwrap();
+        chart.transition(&Event::Next, &mut emit);
+        assert!(!chart.transition(&Event::Next, &mut emit));
+        assert_eq!(chart.current(), Some(Step::Address));
+    }
+
+    #[wasm_bindgen_test]
+    fn undeclared_target_is_invalid() {
+        let mut chart: Statechart<Step, Event, ()> =
+            Statechart::new(Step::Form).state(Step::Form, State::new().on(Event::Next, Step::Done));
+        assert_eq!(
+            chart.enter_initial(&mut Emit::new()),
+            Err(StatechartError::UndeclaredState("Done".to_owned()))
+        );
+    }
+}
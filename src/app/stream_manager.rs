@@ -1,3 +1,4 @@
+use super::leak_detector::{Counted, Resource};
 use futures::future::{abortable, ready, AbortHandle, FutureExt};
 use futures::stream::{Stream, StreamExt};
 use wasm_bindgen_futures::spawn_local;
@@ -19,16 +20,23 @@ impl StreamManager {
         let (stream, handle) = abortable(stream);
         // Ignore the error when the future is aborted. I.e. just stop the stream.
         spawn_local(stream.map(move |_| ()));
-        StreamHandle(handle)
+        StreamHandle {
+            abort_handle: handle,
+            _counted: Counted::new(Resource::StreamHandle),
+        }
     }
 }
 
 // ------ StreamHandle ------
 
-pub struct StreamHandle(AbortHandle);
+pub struct StreamHandle {
+    abort_handle: AbortHandle,
+    // See `leak_detector`.
+    _counted: Counted,
+}
 
 impl Drop for StreamHandle {
     fn drop(&mut self) {
-        self.0.abort();
+        self.abort_handle.abort();
     }
 }
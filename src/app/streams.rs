@@ -1,10 +1,12 @@
+use super::clock;
 use futures::stream::{Stream, StreamExt};
-use gloo_timers::future::IntervalStream;
 
 // ------ Interval stream ------
 
 /// Stream no values on predefined time interval in milliseconds.
 ///
+/// It respects `testing::TestClock` in tests.
+///
 /// # Example
 ///
 /// ```rust,no_run
@@ -14,10 +16,45 @@ pub fn interval<Ms>(
     ms: u32,
     handler: impl FnOnce() -> Ms + Clone + 'static,
 ) -> impl Stream<Item = Ms> {
-    IntervalStream::new(ms).map(move |_| handler.clone()())
+    clock::interval(ms).map(move |_| handler.clone()())
 }
 
 // ------ Window Event stream ------
 
 mod window_event;
 pub use window_event::window_event;
+pub(crate) use window_event::EventStream;
+
+// ------ Intersection stream ------
+
+mod intersection;
+pub use intersection::{intersection, IntersectionStream};
+
+// ------ Gestures stream ------
+
+mod gestures;
+pub use gestures::{gestures, Gesture, GestureConfig, GestureStream, SwipeDirection};
+
+// ------ User idle stream ------
+
+mod user_idle;
+pub use user_idle::{user_idle, IdleError, IdleState, ScreenState, UserIdleStream, UserState};
+
+// ------ Device sensors streams ------
+
+mod device_sensors;
+pub use device_sensors::{
+    device_motion, device_orientation, Acceleration, DeviceMotion, DeviceOrientation, RotationRate,
+};
+
+// ------ Gamepads stream ------
+
+mod gamepads;
+pub use gamepads::{gamepads, Gamepad, GamepadButton, GamepadInput};
+
+// ------ Speech recognition stream ------
+
+mod speech_recognition;
+pub use speech_recognition::{
+    speech_recognition, RecognitionEvent, RecognitionOptions, Transcript,
+};
@@ -0,0 +1,170 @@
+use super::EventStream;
+use crate::browser::util::window;
+use futures::stream::{Stream, StreamExt};
+
+// ------ Device orientation stream ------
+
+/// Stream device orientation readings (`deviceorientation` events) for tilt-based interactions.
+///
+/// iOS requires the user's permission - see `cmds::device_sensors::request_permission`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.stream(streams::device_orientation(|orientation| Msg::Tilted(orientation.gamma)));
+/// ```
+pub fn device_orientation<Ms>(
+    handler: impl FnOnce(DeviceOrientation) -> Ms + Clone + 'static,
+) -> impl Stream<Item = Ms> {
+    EventStream::<web_sys::DeviceOrientationEvent>::new(&window(), "deviceorientation")
+        .map(move |event| handler.clone()(DeviceOrientation::from(&event)))
+}
+
+/// Device rotation in degrees. Values are `None` when the device doesn't have the sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeviceOrientation {
+    /// Rotation around the z axis, `0` - `360`.
+    pub alpha: Option<f64>,
+    /// Front-to-back tilt, `-180` - `180`.
+    pub beta: Option<f64>,
+    /// Left-to-right tilt, `-90` - `90`.
+    pub gamma: Option<f64>,
+    /// `alpha` is relative to the Earth's coordinate frame instead of an arbitrary frame.
+    pub absolute: bool,
+}
+
+impl From<&web_sys::DeviceOrientationEvent> for DeviceOrientation {
+    fn from(event: &web_sys::DeviceOrientationEvent) -> Self {
+        Self {
+            alpha: event.alpha(),
+            beta: event.beta(),
+            gamma: event.gamma(),
+            absolute: event.absolute(),
+        }
+    }
+}
+
+// ------ Device motion stream ------
+
+/// Stream device motion readings (`devicemotion` events) for shake-based interactions.
+///
+/// iOS requires the user's permission - see `cmds::device_sensors::request_permission`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.stream(streams::device_motion(|motion| {
+///    let shaken = motion.acceleration.map_or(false, |acceleration| acceleration.magnitude() > 15.);
+///    if shaken { Some(Msg::Shaken) } else { None }
+///}));
+/// ```
+pub fn device_motion<Ms>(
+    handler: impl FnOnce(DeviceMotion) -> Ms + Clone + 'static,
+) -> impl Stream<Item = Ms> {
+    EventStream::<web_sys::DeviceMotionEvent>::new(&window(), "devicemotion")
+        .map(move |event| handler.clone()(DeviceMotion::from(&event)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeviceMotion {
+    /// Acceleration without the effect of gravity, when the device can measure it.
+    pub acceleration: Option<Acceleration>,
+    pub acceleration_including_gravity: Option<Acceleration>,
+    pub rotation_rate: Option<RotationRate>,
+    /// Interval between readings in milliseconds.
+    pub interval: f64,
+}
+
+impl From<&web_sys::DeviceMotionEvent> for DeviceMotion {
+    fn from(event: &web_sys::DeviceMotionEvent) -> Self {
+        Self {
+            acceleration: event.acceleration().as_ref().and_then(Acceleration::new),
+            acceleration_including_gravity: event
+                .acceleration_including_gravity()
+                .as_ref()
+                .and_then(Acceleration::new),
+            rotation_rate: event.rotation_rate().as_ref().and_then(RotationRate::new),
+            interval: event.interval().unwrap_or_default(),
+        }
+    }
+}
+
+/// Acceleration in m/s².
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Acceleration {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Acceleration {
+    fn new(acceleration: &web_sys::DeviceAcceleration) -> Option<Self> {
+        Some(Self {
+            x: acceleration.x()?,
+            y: acceleration.y()?,
+            z: acceleration.z()?,
+        })
+    }
+
+    /// Length of the acceleration vector.
+    pub fn magnitude(&self) -> f64 {
+        self.x.hypot(self.y).hypot(self.z)
+    }
+}
+
+/// Rotation rate in degrees per second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RotationRate {
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+}
+
+impl RotationRate {
+    fn new(rotation_rate: &web_sys::DeviceRotationRate) -> Option<Self> {
+        Some(Self {
+            alpha: rotation_rate.alpha()?,
+            beta: rotation_rate.beta()?,
+            gamma: rotation_rate.gamma()?,
+        })
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn orientation_from_event() {
+        let mut init = web_sys::DeviceOrientationEventInit::new();
+        init.beta(Some(45.)).gamma(Some(-10.));
+        let event =
+            web_sys::DeviceOrientationEvent::new_with_event_init_dict("deviceorientation", &init)
+                .unwrap();
+
+        assert_eq!(
+            DeviceOrientation::from(&event),
+            DeviceOrientation {
+                alpha: None,
+                beta: Some(45.),
+                gamma: Some(-10.),
+                absolute: false,
+            }
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn acceleration_magnitude() {
+        let acceleration = Acceleration {
+            x: 3.,
+            y: 4.,
+            z: 12.,
+        };
+        assert!((acceleration.magnitude() - 13.).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,211 @@
+use super::EventStream;
+use crate::app::clock;
+use crate::browser::util::window;
+use futures::stream::{self, Stream, StreamExt};
+use std::collections::BTreeMap;
+use wasm_bindgen::JsCast;
+
+// ------ Gamepads stream ------
+
+/// Stream gamepad connections and button / axis changes.
+///
+/// The state is polled every `poll_rate` milliseconds because browsers don't fire events
+/// for input changes - use `16` to check it in every frame of 60 fps games.
+///
+/// It respects `testing::TestClock` in tests.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.stream(streams::gamepads(16, Msg::Gamepad));
+///...
+///Msg::Gamepad(GamepadInput::Changed(gamepad)) => {
+///    model.player.jump = gamepad.buttons.get(0).map_or(false, |button| button.pressed);
+///    model.player.speed = gamepad.axes.get(0).copied().unwrap_or_default();
+///}
+/// ```
+pub fn gamepads<Ms>(
+    poll_rate: u32,
+    handler: impl FnOnce(GamepadInput) -> Ms + Clone + 'static,
+) -> impl Stream<Item = Ms> {
+    let connected = EventStream::<web_sys::GamepadEvent>::new(&window(), "gamepadconnected")
+        .filter_map(|event| async move { event.gamepad().map(Source::Connected) });
+    let disconnected = EventStream::<web_sys::GamepadEvent>::new(&window(), "gamepaddisconnected")
+        .filter_map(|event| async move { event.gamepad().map(Source::Disconnected) });
+    let polls = clock::interval(poll_rate).map(|_| Source::Poll);
+
+    let mut tracker = Tracker::default();
+    stream::select(stream::select(connected, disconnected), polls)
+        .flat_map(move |source| stream::iter(tracker.handle(source)))
+        .map(move |input| handler.clone()(input))
+}
+
+// ------ GamepadInput ------
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GamepadInput {
+    Connected(Gamepad),
+    /// Index of the disconnected gamepad.
+    Disconnected(u32),
+    /// Buttons or axes of the gamepad have changed since the last poll.
+    Changed(Gamepad),
+}
+
+// ------ Gamepad ------
+
+/// Gamepad state read from a `web_sys::Gamepad`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gamepad {
+    /// Stable index while the gamepad is connected.
+    pub index: u32,
+    /// Device description, e.g. `"Xbox 360 Controller (XInput STANDARD GAMEPAD)"`.
+    pub id: String,
+    /// See [standard mapping](https://w3c.github.io/gamepad/#remapping) for the buttons order.
+    pub buttons: Vec<GamepadButton>,
+    /// `-1.0` - `1.0`.
+    pub axes: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GamepadButton {
+    pub pressed: bool,
+    /// `0.0` - `1.0` for analog buttons (triggers).
+    pub value: f64,
+}
+
+impl From<&web_sys::Gamepad> for Gamepad {
+    fn from(gamepad: &web_sys::Gamepad) -> Self {
+        Self {
+            index: gamepad.index(),
+            id: gamepad.id(),
+            buttons: gamepad
+                .buttons()
+                .iter()
+                .map(|button| {
+                    let button = button.unchecked_into::<web_sys::GamepadButton>();
+                    GamepadButton {
+                        pressed: button.pressed(),
+                        value: button.value(),
+                    }
+                })
+                .collect(),
+            axes: gamepad
+                .axes()
+                .iter()
+                .map(|axis| axis.as_f64().unwrap_or_default())
+                .collect(),
+        }
+    }
+}
+
+// ------ Tracker ------
+
+enum Source {
+    Connected(web_sys::Gamepad),
+    Disconnected(web_sys::Gamepad),
+    Poll,
+}
+
+/// Gamepad states from the last poll.
+#[derive(Default)]
+struct Tracker {
+    gamepads: BTreeMap<u32, Gamepad>,
+}
+
+impl Tracker {
+    fn handle(&mut self, source: Source) -> Vec<GamepadInput> {
+        match source {
+            Source::Connected(gamepad) => {
+                let gamepad = Gamepad::from(&gamepad);
+                if self.gamepads.contains_key(&gamepad.index) {
+                    return Vec::new();
+                }
+                self.gamepads.insert(gamepad.index, gamepad.clone());
+                vec![GamepadInput::Connected(gamepad)]
+            }
+            Source::Disconnected(gamepad) => self
+                .gamepads
+                .remove(&gamepad.index())
+                .map(|gamepad| GamepadInput::Disconnected(gamepad.index))
+                .into_iter()
+                .collect(),
+            Source::Poll => {
+                // `getGamepads` contains `null`s for disconnected gamepads.
+                let gamepads = window()
+                    .navigator()
+                    .get_gamepads()
+                    .map(|gamepads| {
+                        gamepads
+                            .iter()
+                            .filter_map(|gamepad| gamepad.dyn_into::<web_sys::Gamepad>().ok())
+                            .filter(web_sys::Gamepad::connected)
+                            .map(|gamepad| Gamepad::from(&gamepad))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                self.poll(gamepads)
+            }
+        }
+    }
+
+    fn poll(&mut self, gamepads: Vec<Gamepad>) -> Vec<GamepadInput> {
+        let mut inputs = Vec::new();
+        let mut old_gamepads = std::mem::take(&mut self.gamepads);
+        for gamepad in gamepads {
+            match old_gamepads.remove(&gamepad.index) {
+                None => inputs.push(GamepadInput::Connected(gamepad.clone())),
+                Some(old_gamepad) if old_gamepad != gamepad => {
+                    inputs.push(GamepadInput::Changed(gamepad.clone()));
+                }
+                Some(_) => (),
+            }
+            self.gamepads.insert(gamepad.index, gamepad);
+        }
+        inputs.extend(old_gamepads.keys().copied().map(GamepadInput::Disconnected));
+        inputs
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn gamepad(index: u32, pressed: bool) -> Gamepad {
+        Gamepad {
+            index,
+            id: "Standard Gamepad".to_owned(),
+            buttons: vec![GamepadButton {
+                pressed,
+                value: if pressed { 1. } else { 0. },
+            }],
+            axes: vec![0., 0.],
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn poll_changes() {
+        let mut tracker = Tracker::default();
+
+        assert_eq!(
+            tracker.poll(vec![gamepad(0, false)]),
+            vec![GamepadInput::Connected(gamepad(0, false))]
+        );
+        assert!(tracker.poll(vec![gamepad(0, false)]).is_empty());
+        assert_eq!(
+            tracker.poll(vec![gamepad(0, true), gamepad(1, false)]),
+            vec![
+                GamepadInput::Changed(gamepad(0, true)),
+                GamepadInput::Connected(gamepad(1, false))
+            ]
+        );
+        assert_eq!(
+            tracker.poll(vec![gamepad(1, false)]),
+            vec![GamepadInput::Disconnected(0)]
+        );
+    }
+}
@@ -0,0 +1,411 @@
+use crate::app::{clock, CmdHandle, CmdManager};
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::future::FutureExt;
+use futures::stream::{Stream, StreamExt};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
+use std::task::{Context, Poll};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::PointerEvent;
+
+// ------ Gestures stream ------
+
+/// Stream `Gesture`s recognized from pointer events on the `target`.
+/// It works with touch, pen and mouse.
+///
+/// _Note:_ Set CSS `touch-action: none` on the `target`, otherwise the browser
+/// scrolls and zooms the page instead of sending pointer events.
+///
+/// # Example
+///
+/// ```rust,no_run
+///if let Some(gallery) = model.gallery.get() {
+///    model.gesture_stream = Some(orders.stream_with_handle(streams::gestures(&gallery, |gesture| {
+///        match gesture {
+///            Gesture::Swipe { direction: SwipeDirection::Left, .. } => Msg::NextPhoto,
+///            Gesture::Swipe { direction: SwipeDirection::Right, .. } => Msg::PreviousPhoto,
+///            Gesture::Pinch { scale } => Msg::Zoomed(scale),
+///            _ => Msg::NoOp,
+///        }
+///    })));
+///}
+/// ```
+pub fn gestures<Ms>(
+    target: &web_sys::Element,
+    handler: impl FnOnce(Gesture) -> Ms + Clone + 'static,
+) -> impl Stream<Item = Ms> {
+    GestureStream::new(target, GestureConfig::default())
+        .map(move |gesture| handler.clone()(gesture))
+}
+
+// ------ Gesture ------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gesture {
+    /// A fast movement of one pointer. `velocity` is in px/ms.
+    Swipe {
+        direction: SwipeDirection,
+        velocity: f64,
+    },
+    /// Two pointers are moving. `scale` is relative to their distance at the pinch start.
+    Pinch { scale: f64 },
+    /// One of the pinching pointers has been released.
+    PinchEnd { scale: f64 },
+    /// The pointer has been held down without moving.
+    LongPress { client_x: i32, client_y: i32 },
+}
+
+// ------ GestureConfig ------
+
+#[derive(Debug, Clone, Copy)]
+pub struct GestureConfig {
+    /// Minimal swipe distance in px. Default is `30`.
+    pub swipe_min_distance: f64,
+    /// Minimal swipe velocity in px/ms. Default is `0.3`.
+    pub swipe_min_velocity: f64,
+    /// Long press duration in ms. Default is `500`.
+    /// It respects `testing::TestClock` in tests.
+    pub long_press_delay: u32,
+    /// Maximal pointer movement in px during a long press. Default is `10`.
+    pub long_press_tolerance: f64,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            swipe_min_distance: 30.,
+            swipe_min_velocity: 0.3,
+            long_press_delay: 500,
+            long_press_tolerance: 10.,
+        }
+    }
+}
+
+// ------ Recognizer ------
+
+struct Position {
+    start_x: f64,
+    start_y: f64,
+    start_time: f64,
+    x: f64,
+    y: f64,
+}
+
+impl Position {
+    fn distance_from_start(&self) -> f64 {
+        (self.x - self.start_x).hypot(self.y - self.start_y)
+    }
+}
+
+struct Recognizer {
+    config: GestureConfig,
+    sender: UnboundedSender<Gesture>,
+    pointers: BTreeMap<i32, Position>,
+    pinch_start_distance: Option<f64>,
+    last_scale: f64,
+    long_press: Option<CmdHandle>,
+    // Swipe isn't recognized after a long press or a pinch.
+    swipe_cancelled: bool,
+}
+
+impl Recognizer {
+    fn send(&self, gesture: Gesture) {
+        // The receiver has been dropped when nobody listens anymore.
+        let _ = self.sender.unbounded_send(gesture);
+    }
+
+    fn pointers_distance(&self) -> Option<f64> {
+        let mut pointers = self.pointers.values();
+        match (pointers.next(), pointers.next()) {
+            (Some(a), Some(b)) => Some((a.x - b.x).hypot(a.y - b.y)),
+            _ => None,
+        }
+    }
+
+    fn pointer_down(recognizer: &Rc<RefCell<Self>>, event: &PointerEvent) {
+        let mut this = recognizer.borrow_mut();
+        let (x, y) = (f64::from(event.client_x()), f64::from(event.client_y()));
+        this.pointers.insert(
+            event.pointer_id(),
+            Position {
+                start_x: x,
+                start_y: y,
+                start_time: event.time_stamp(),
+                x,
+                y,
+            },
+        );
+        match this.pointers.len() {
+            1 => {
+                this.swipe_cancelled = false;
+                let weak_recognizer = Rc::downgrade(recognizer);
+                let (client_x, client_y) = (event.client_x(), event.client_y());
+                this.long_press = Some(CmdManager::perform_cmd_with_handle(
+                    clock::sleep(this.config.long_press_delay)
+                        .map(move |_| Self::long_press(&weak_recognizer, client_x, client_y)),
+                ));
+            }
+            2 => {
+                this.long_press = None;
+                this.swipe_cancelled = true;
+                this.pinch_start_distance = this.pointers_distance();
+                this.last_scale = 1.;
+            }
+            _ => (),
+        }
+    }
+
+    fn long_press(recognizer: &Weak<RefCell<Self>>, client_x: i32, client_y: i32) {
+        if let Some(recognizer) = recognizer.upgrade() {
+            let mut this = recognizer.borrow_mut();
+            this.swipe_cancelled = true;
+            this.send(Gesture::LongPress { client_x, client_y });
+        }
+    }
+
+    fn pointer_move(&mut self, event: &PointerEvent) {
+        let tolerance = self.config.long_press_tolerance;
+        let moved_too_far = match self.pointers.get_mut(&event.pointer_id()) {
+            Some(position) => {
+                position.x = f64::from(event.client_x());
+                position.y = f64::from(event.client_y());
+                position.distance_from_start() > tolerance
+            }
+            None => return,
+        };
+        if moved_too_far {
+            self.long_press = None;
+        }
+        if let (Some(start_distance), Some(distance)) =
+            (self.pinch_start_distance, self.pointers_distance())
+        {
+            if start_distance > 0. {
+                self.last_scale = distance / start_distance;
+                self.send(Gesture::Pinch {
+                    scale: self.last_scale,
+                });
+            }
+        }
+    }
+
+    fn pointer_up(&mut self, event: &PointerEvent, cancelled: bool) {
+        let position = match self.pointers.remove(&event.pointer_id()) {
+            Some(position) => position,
+            None => return,
+        };
+        self.long_press = None;
+
+        if self.pinch_start_distance.is_some() {
+            if self.pointers.len() < 2 {
+                self.pinch_start_distance = None;
+                self.send(Gesture::PinchEnd {
+                    scale: self.last_scale,
+                });
+            }
+            return;
+        }
+        if cancelled || self.swipe_cancelled || !self.pointers.is_empty() {
+            return;
+        }
+
+        let (dx, dy) = (
+            f64::from(event.client_x()) - position.start_x,
+            f64::from(event.client_y()) - position.start_y,
+        );
+        let distance = dx.hypot(dy);
+        // Synthetic and coalesced events may have the same timestamps.
+        let duration = (event.time_stamp() - position.start_time).max(1.);
+        let velocity = distance / duration;
+        if distance < self.config.swipe_min_distance || velocity < self.config.swipe_min_velocity {
+            return;
+        }
+        let direction = if dx.abs() >= dy.abs() {
+            if dx > 0. {
+                SwipeDirection::Right
+            } else {
+                SwipeDirection::Left
+            }
+        } else if dy > 0. {
+            SwipeDirection::Down
+        } else {
+            SwipeDirection::Up
+        };
+        self.send(Gesture::Swipe {
+            direction,
+            velocity,
+        });
+    }
+}
+
+// ------ GestureStream ------
+
+type PointerHandler = Box<dyn Fn(&Rc<RefCell<Recognizer>>, &PointerEvent)>;
+type Listener = (&'static str, Closure<dyn Fn(JsValue)>);
+
+pub struct GestureStream {
+    target: web_sys::Element,
+    listeners: Vec<Listener>,
+    receiver: UnboundedReceiver<Gesture>,
+}
+
+impl GestureStream {
+    /// Recognize gestures on `target` with the given `config`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when event listeners can't be added.
+    pub fn new(target: &web_sys::Element, config: GestureConfig) -> Self {
+        let (sender, receiver) = unbounded();
+        let recognizer = Rc::new(RefCell::new(Recognizer {
+            config,
+            sender,
+            pointers: BTreeMap::new(),
+            pinch_start_distance: None,
+            last_scale: 1.,
+            long_press: None,
+            swipe_cancelled: false,
+        }));
+
+        let listener = |handler: PointerHandler| {
+            let recognizer = Rc::clone(&recognizer);
+            Closure::wrap(Box::new(move |event: JsValue| {
+                handler(&recognizer, event.unchecked_ref())
+            }) as Box<dyn Fn(JsValue)>)
+        };
+        let listeners = vec![
+            (
+                "pointerdown",
+                listener(Box::new({
+                    let target = target.clone();
+                    move |recognizer, event| {
+                        // Receive also events outside of the target.
+                        // It fails for inactive (e.g. synthetic) pointers.
+                        let _ = target.set_pointer_capture(event.pointer_id());
+                        Recognizer::pointer_down(recognizer, event)
+                    }
+                })),
+            ),
+            (
+                "pointermove",
+                listener(Box::new(|recognizer, event| {
+                    recognizer.borrow_mut().pointer_move(event)
+                })),
+            ),
+            (
+                "pointerup",
+                listener(Box::new(|recognizer, event| {
+                    recognizer.borrow_mut().pointer_up(event, false)
+                })),
+            ),
+            (
+                "pointercancel",
+                listener(Box::new(|recognizer, event| {
+                    recognizer.borrow_mut().pointer_up(event, true)
+                })),
+            ),
+        ];
+        for (trigger, callback) in &listeners {
+            target
+                .add_event_listener_with_callback(trigger, callback.as_ref().unchecked_ref())
+                .expect("add gesture listener");
+        }
+
+        Self {
+            target: target.clone(),
+            listeners,
+            receiver,
+        }
+    }
+}
+
+impl Stream for GestureStream {
+    type Item = Gesture;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        Stream::poll_next(Pin::new(&mut self.receiver), cx)
+    }
+}
+
+impl Drop for GestureStream {
+    fn drop(&mut self) {
+        for (trigger, callback) in &self.listeners {
+            self.target
+                .remove_event_listener_with_callback(trigger, callback.as_ref().unchecked_ref())
+                .expect("remove gesture listener");
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestClock;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn target() -> web_sys::Element {
+        crate::browser::util::document()
+            .create_element("div")
+            .unwrap()
+    }
+
+    fn dispatch(target: &web_sys::Element, trigger: &str, pointer_id: i32, x: i32, y: i32) {
+        let mut init = web_sys::PointerEventInit::new();
+        init.pointer_id(pointer_id).client_x(x).client_y(y);
+        let event = PointerEvent::new_with_event_init_dict(trigger, &init).unwrap();
+        target.dispatch_event(&event).unwrap();
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn swipe_and_pinch() {
+        let target = target();
+        let mut stream = GestureStream::new(&target, GestureConfig::default());
+
+        dispatch(&target, "pointerdown", 1, 0, 0);
+        dispatch(&target, "pointerup", 1, 100, 5);
+        match stream.next().await {
+            Some(Gesture::Swipe { direction, .. }) => assert_eq!(direction, SwipeDirection::Right),
+            gesture => panic!("unexpected gesture {:?}", gesture),
+        }
+
+        dispatch(&target, "pointerdown", 1, 0, 0);
+        dispatch(&target, "pointerdown", 2, 100, 0);
+        dispatch(&target, "pointermove", 2, 200, 0);
+        dispatch(&target, "pointerup", 2, 200, 0);
+        dispatch(&target, "pointerup", 1, 0, 0);
+        assert_eq!(stream.next().await, Some(Gesture::Pinch { scale: 2. }));
+        assert_eq!(stream.next().await, Some(Gesture::PinchEnd { scale: 2. }));
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn long_press() {
+        let clock = TestClock::install();
+        let target = target();
+        let mut stream = GestureStream::new(&target, GestureConfig::default());
+
+        dispatch(&target, "pointerdown", 1, 10, 20);
+        dispatch(&target, "pointermove", 1, 12, 20);
+        clock.advance(500);
+        assert_eq!(
+            stream.next().await,
+            Some(Gesture::LongPress {
+                client_x: 10,
+                client_y: 20
+            })
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use futures::channel::mpsc::{unbounded, UnboundedReceiver};
+use futures::stream::{Stream, StreamExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{IntersectionObserver, IntersectionObserverEntry, IntersectionObserverInit};
+
+// ------ Intersection stream ------
+
+/// Stream `IntersectionObserverEntry`s when the `target` enters or leaves the viewport.
+/// It's useful for infinite scrolling or lazy loading.
+///
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/Intersection_Observer_API)
+///
+/// # Example
+///
+/// ```rust,no_run
+///if let Some(trigger) = model.load_more_trigger.get() {
+///    model.trigger_stream = Some(orders.stream_with_handle(streams::intersection(
+///        &trigger,
+///        |entry| Msg::TriggerVisibilityChanged(entry.is_intersecting()),
+///    )));
+///}
+/// ```
+pub fn intersection<Ms>(
+    target: &web_sys::Element,
+    handler: impl FnOnce(IntersectionObserverEntry) -> Ms + Clone + 'static,
+) -> impl Stream<Item = Ms> {
+    IntersectionStream::new(target, &IntersectionObserverInit::new())
+        .map(move |entry| handler.clone()(entry))
+}
+
+// ------ IntersectionStream ------
+
+pub struct IntersectionStream {
+    observer: IntersectionObserver,
+    _callback: Closure<dyn FnMut(js_sys::Array)>,
+    receiver: UnboundedReceiver<IntersectionObserverEntry>,
+}
+
+impl IntersectionStream {
+    /// Observe `target` with given `options` (e.g. `root_margin` or `threshold`).
+    ///
+    /// # Panics
+    ///
+    /// Panics when the browser doesn't support `IntersectionObserver`.
+    pub fn new(target: &web_sys::Element, options: &IntersectionObserverInit) -> Self {
+        let (sender, receiver) = unbounded();
+
+        let callback = Closure::wrap(Box::new(move |entries: js_sys::Array| {
+            for entry in entries.iter() {
+                sender
+                    .unbounded_send(entry.unchecked_into())
+                    .expect("send IntersectionObserverEntry");
+            }
+        }) as Box<dyn FnMut(js_sys::Array)>);
+
+        let observer =
+            IntersectionObserver::new_with_options(callback.as_ref().unchecked_ref(), options)
+                .expect("create IntersectionObserver");
+        observer.observe(target);
+
+        Self {
+            observer,
+            _callback: callback,
+            receiver,
+        }
+    }
+}
+
+impl Stream for IntersectionStream {
+    type Item = IntersectionObserverEntry;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        Stream::poll_next(Pin::new(&mut self.receiver), cx)
+    }
+}
+
+impl Drop for IntersectionStream {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
@@ -0,0 +1,271 @@
+use crate::browser::util::window;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver};
+use futures::stream::{Stream, StreamExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+// ------ Speech recognition stream ------
+
+/// Stream transcripts of the user's speech from the
+/// [Web Speech API](https://developer.mozilla.org/en-US/docs/Web/API/SpeechRecognition).
+///
+/// The recognition starts immediately (the browser asks for the microphone permission)
+/// and it's aborted on the stream drop - keep the `StreamHandle` while you're listening.
+/// The stream ends after `RecognitionEvent::End`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///Msg::StartDictation => {
+///    let options = RecognitionOptions::default().lang("en-US").interim_results(true);
+///    model.dictation = Some(orders.stream_with_handle(streams::speech_recognition(&options, Msg::Recognition)));
+///}
+///Msg::Recognition(RecognitionEvent::Transcript(transcript)) if transcript.is_final => {
+///    model.text.push_str(&transcript.text);
+///}
+///Msg::StopDictation | Msg::Recognition(RecognitionEvent::End) => model.dictation = None,
+/// ```
+pub fn speech_recognition<Ms>(
+    options: &RecognitionOptions,
+    handler: impl FnOnce(RecognitionEvent) -> Ms + Clone + 'static,
+) -> impl Stream<Item = Ms> {
+    SpeechRecognitionStream::new(options).map(move |event| handler.clone()(event))
+}
+
+// ------ RecognitionOptions ------
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecognitionOptions {
+    /// BCP 47 language tag, e.g. `"en-US"`. The document language is used by default.
+    pub lang: Option<String>,
+    /// Keep listening after the user stops speaking.
+    pub continuous: bool,
+    /// Stream also transcripts that may change.
+    pub interim_results: bool,
+}
+
+impl RecognitionOptions {
+    pub fn lang(mut self, lang: impl Into<String>) -> Self {
+        self.lang = Some(lang.into());
+        self
+    }
+
+    pub const fn continuous(mut self, continuous: bool) -> Self {
+        self.continuous = continuous;
+        self
+    }
+
+    pub const fn interim_results(mut self, interim_results: bool) -> Self {
+        self.interim_results = interim_results;
+        self
+    }
+}
+
+// ------ RecognitionEvent ------
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecognitionEvent {
+    Transcript(Transcript),
+    /// E.g. `"not-allowed"`, `"no-speech"` or `"network"`. `End` follows.
+    Error {
+        error: String,
+        message: Option<String>,
+    },
+    /// The recognition has stopped. It's the last event.
+    End,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transcript {
+    /// Index of the recognized phrase - interim transcripts with the same index
+    /// are replaced by newer ones until the final one.
+    pub index: u32,
+    pub text: String,
+    /// `0.0` - `1.0`; some browsers return `0.0` for interim transcripts.
+    pub confidence: f32,
+    pub is_final: bool,
+}
+
+fn transcripts(event: &web_sys::SpeechRecognitionEvent) -> Vec<Transcript> {
+    event
+        .results()
+        .map(|results| {
+            (event.result_index()..results.length())
+                .filter_map(|index| {
+                    let result = results.get(index)?;
+                    let alternative = result.get(0)?;
+                    Some(Transcript {
+                        index,
+                        text: alternative.transcript(),
+                        confidence: alternative.confidence(),
+                        is_final: result.is_final(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// ------ SpeechRecognitionStream ------
+
+type Callback = Closure<dyn Fn(JsValue)>;
+
+struct SpeechRecognitionStream {
+    recognition: Option<web_sys::SpeechRecognition>,
+    callbacks: Vec<(&'static str, Callback)>,
+    receiver: UnboundedReceiver<RecognitionEvent>,
+    finished: bool,
+}
+
+impl SpeechRecognitionStream {
+    fn new(options: &RecognitionOptions) -> Self {
+        let (sender, receiver) = unbounded();
+        let mut stream = Self {
+            recognition: None,
+            callbacks: Vec::new(),
+            receiver,
+            finished: false,
+        };
+
+        let recognition = match create_recognition(options) {
+            Ok(recognition) => recognition,
+            Err(error) => {
+                let message = error.as_string();
+                sender
+                    .unbounded_send(RecognitionEvent::Error {
+                        error: "not-supported".to_owned(),
+                        message,
+                    })
+                    .ok();
+                sender.unbounded_send(RecognitionEvent::End).ok();
+                return stream;
+            }
+        };
+
+        let listen = |trigger: &'static str, callback: Callback| {
+            recognition
+                .add_event_listener_with_callback(trigger, callback.as_ref().unchecked_ref())
+                .expect("add speech recognition listener");
+            (trigger, callback)
+        };
+        stream.callbacks = vec![
+            listen(
+                "result",
+                Closure::wrap(Box::new({
+                    let sender = sender.clone();
+                    move |event: JsValue| {
+                        for transcript in transcripts(event.unchecked_ref()) {
+                            sender
+                                .unbounded_send(RecognitionEvent::Transcript(transcript))
+                                .ok();
+                        }
+                    }
+                }) as Box<dyn Fn(JsValue)>),
+            ),
+            listen(
+                "error",
+                Closure::wrap(Box::new({
+                    let sender = sender.clone();
+                    move |event: JsValue| {
+                        let property = |name: &str| {
+                            js_sys::Reflect::get(&event, &name.into())
+                                .ok()
+                                .and_then(|value| value.as_string())
+                        };
+                        sender
+                            .unbounded_send(RecognitionEvent::Error {
+                                error: property("error").unwrap_or_default(),
+                                message: property("message").filter(|message| !message.is_empty()),
+                            })
+                            .ok();
+                    }
+                }) as Box<dyn Fn(JsValue)>),
+            ),
+            listen(
+                "end",
+                Closure::wrap(Box::new(move |_| {
+                    sender.unbounded_send(RecognitionEvent::End).ok();
+                }) as Box<dyn Fn(JsValue)>),
+            ),
+        ];
+
+        // `start` fails only when the recognition has been already started.
+        recognition.start().ok();
+        stream.recognition = Some(recognition);
+        stream
+    }
+}
+
+/// Chromium-based browsers support only the prefixed `webkitSpeechRecognition`.
+fn create_recognition(options: &RecognitionOptions) -> Result<web_sys::SpeechRecognition, JsValue> {
+    let constructor = ["SpeechRecognition", "webkitSpeechRecognition"]
+        .iter()
+        .find_map(|name| {
+            js_sys::Reflect::get(&window(), &JsValue::from(*name))
+                .ok()?
+                .dyn_into::<js_sys::Function>()
+                .ok()
+        })
+        .ok_or_else(|| JsValue::from("speech recognition isn't supported"))?;
+    let recognition = js_sys::Reflect::construct(&constructor, &js_sys::Array::new())?
+        .unchecked_into::<web_sys::SpeechRecognition>();
+
+    if let Some(lang) = &options.lang {
+        recognition.set_lang(lang);
+    }
+    recognition.set_continuous(options.continuous)?;
+    recognition.set_interim_results(options.interim_results);
+    Ok(recognition)
+}
+
+impl Stream for SpeechRecognitionStream {
+    type Item = RecognitionEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        let poll = Stream::poll_next(Pin::new(&mut self.receiver), cx);
+        self.finished = matches!(poll, Poll::Ready(Some(RecognitionEvent::End)));
+        poll
+    }
+}
+
+impl Drop for SpeechRecognitionStream {
+    fn drop(&mut self) {
+        if let Some(recognition) = &self.recognition {
+            for (trigger, callback) in &self.callbacks {
+                recognition
+                    .remove_event_listener_with_callback(trigger, callback.as_ref().unchecked_ref())
+                    .expect("remove speech recognition listener");
+            }
+            recognition.abort();
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn recognition_options() {
+        let options = RecognitionOptions::default()
+            .lang("cs-CZ")
+            .interim_results(true);
+        assert_eq!(
+            options,
+            RecognitionOptions {
+                lang: Some("cs-CZ".to_owned()),
+                continuous: false,
+                interim_results: true,
+            }
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use crate::app::{CmdHandle, CmdManager};
+use crate::browser::util::window;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::stream::{Stream, StreamExt};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+mod raw {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        pub type IdleDetector;
+
+        #[wasm_bindgen(constructor, catch)]
+        pub fn new() -> Result<IdleDetector, JsValue>;
+
+        #[wasm_bindgen(static_method_of = IdleDetector, js_name = requestPermission)]
+        pub fn request_permission() -> js_sys::Promise;
+
+        #[wasm_bindgen(method)]
+        pub fn start(this: &IdleDetector, options: &JsValue) -> js_sys::Promise;
+
+        #[wasm_bindgen(method, getter, js_name = userState)]
+        pub fn user_state(this: &IdleDetector) -> Option<String>;
+
+        #[wasm_bindgen(method, getter, js_name = screenState)]
+        pub fn screen_state(this: &IdleDetector) -> Option<String>;
+
+        #[wasm_bindgen(method, setter)]
+        pub fn set_onchange(this: &IdleDetector, handler: Option<&js_sys::Function>);
+    }
+}
+
+// ------ User idle stream ------
+
+/// Stream user idle state changes from the
+/// [Idle Detection API](https://developer.mozilla.org/en-US/docs/Web/API/Idle_Detection_API).
+/// The current state is streamed once the detector starts.
+///
+/// The browser asks the user for permission - create the stream in a click handler.
+/// `threshold` is in milliseconds and browsers require at least `60_000`.
+///
+/// The first error (unsupported browser, denied permission, ...) is the last item.
+///
+/// # Example
+///
+/// ```rust,no_run
+///Msg::EnableAutoLogout => {
+///    model.idle_stream = Some(orders.stream_with_handle(streams::user_idle(5 * 60_000, Msg::IdleChanged)));
+///}
+///Msg::IdleChanged(Ok(IdleState { user: UserState::Idle, .. })) => orders.send_msg(Msg::LogOut),
+/// ```
+pub fn user_idle<Ms>(
+    threshold: u32,
+    handler: impl FnOnce(Result<IdleState, IdleError>) -> Ms + Clone + 'static,
+) -> impl Stream<Item = Ms> {
+    UserIdleStream::new(threshold).map(move |state| handler.clone()(state))
+}
+
+// ------ IdleState ------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleState {
+    pub user: UserState,
+    pub screen: ScreenState,
+}
+
+impl IdleState {
+    fn from_raw(user: Option<&str>, screen: Option<&str>) -> Self {
+        Self {
+            user: if user == Some("idle") {
+                UserState::Idle
+            } else {
+                UserState::Active
+            },
+            screen: if screen == Some("locked") {
+                ScreenState::Locked
+            } else {
+                ScreenState::Unlocked
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserState {
+    Active,
+    /// The user hasn't interacted with the device for `threshold` milliseconds.
+    Idle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenState {
+    Locked,
+    Unlocked,
+}
+
+// ------ IdleError ------
+
+#[derive(Debug, Clone)]
+pub enum IdleError {
+    /// The browser doesn't support the Idle Detection API.
+    Unsupported,
+    PermissionDenied,
+    /// `IdleDetector` can't be created or started, e.g. because of an invalid `threshold`.
+    Failed(JsValue),
+}
+
+// ------ UserIdleStream ------
+
+type IdleResult = Result<IdleState, IdleError>;
+
+pub struct UserIdleStream {
+    detector: Option<Rc<raw::IdleDetector>>,
+    abort_controller: Option<web_sys::AbortController>,
+    _callback: Option<Closure<dyn Fn()>>,
+    _start: Option<CmdHandle>,
+    receiver: UnboundedReceiver<IdleResult>,
+    finished: bool,
+}
+
+impl UserIdleStream {
+    pub fn new(threshold: u32) -> Self {
+        let (sender, receiver) = unbounded();
+
+        let supported = js_sys::Reflect::has(&window(), &"IdleDetector".into()).unwrap_or_default();
+        if !supported {
+            sender.unbounded_send(Err(IdleError::Unsupported)).ok();
+            return Self::without_detector(receiver);
+        }
+        let (detector, abort_controller) = match raw::IdleDetector::new()
+            .and_then(|detector| Ok((Rc::new(detector), web_sys::AbortController::new()?)))
+        {
+            Ok(objects) => objects,
+            Err(error) => {
+                sender.unbounded_send(Err(IdleError::Failed(error))).ok();
+                return Self::without_detector(receiver);
+            }
+        };
+
+        let callback = Closure::wrap(Box::new({
+            let (detector, sender) = (Rc::clone(&detector), sender.clone());
+            move || {
+                sender.unbounded_send(Ok(state(&detector))).ok();
+            }
+        }) as Box<dyn Fn()>);
+        detector.set_onchange(Some(callback.as_ref().unchecked_ref()));
+
+        let start = CmdManager::perform_cmd_with_handle(start(
+            Rc::clone(&detector),
+            threshold,
+            abort_controller.signal(),
+            sender,
+        ));
+
+        Self {
+            detector: Some(detector),
+            abort_controller: Some(abort_controller),
+            _callback: Some(callback),
+            _start: Some(start),
+            receiver,
+            finished: false,
+        }
+    }
+
+    const fn without_detector(receiver: UnboundedReceiver<IdleResult>) -> Self {
+        Self {
+            detector: None,
+            abort_controller: None,
+            _callback: None,
+            _start: None,
+            receiver,
+            finished: false,
+        }
+    }
+}
+
+fn state(detector: &raw::IdleDetector) -> IdleState {
+    IdleState::from_raw(
+        detector.user_state().as_deref(),
+        detector.screen_state().as_deref(),
+    )
+}
+
+async fn start(
+    detector: Rc<raw::IdleDetector>,
+    threshold: u32,
+    signal: web_sys::AbortSignal,
+    sender: UnboundedSender<IdleResult>,
+) {
+    let result = async {
+        let permission = JsFuture::from(raw::IdleDetector::request_permission())
+            .await
+            .map_err(IdleError::Failed)?;
+        if permission.as_string().as_deref() != Some("granted") {
+            return Err(IdleError::PermissionDenied);
+        }
+
+        let options = js_sys::Object::new();
+        js_sys::Reflect::set(&options, &"threshold".into(), &threshold.into())
+            .map_err(IdleError::Failed)?;
+        js_sys::Reflect::set(&options, &"signal".into(), &signal).map_err(IdleError::Failed)?;
+        JsFuture::from(detector.start(&options))
+            .await
+            .map_err(IdleError::Failed)?;
+        Ok(state(&detector))
+    }
+    .await;
+    sender.unbounded_send(result).ok();
+}
+
+impl Stream for UserIdleStream {
+    type Item = IdleResult;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        let poll = Stream::poll_next(Pin::new(&mut self.receiver), cx);
+        // Errors are final.
+        if let Poll::Ready(Some(Err(_))) = poll {
+            self.finished = true;
+        }
+        poll
+    }
+}
+
+impl Drop for UserIdleStream {
+    fn drop(&mut self) {
+        if let Some(detector) = &self.detector {
+            detector.set_onchange(None);
+        }
+        if let Some(abort_controller) = &self.abort_controller {
+            abort_controller.abort();
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn parse_idle_state() {
+        assert_eq!(
+            IdleState::from_raw(Some("idle"), Some("locked")),
+            IdleState {
+                user: UserState::Idle,
+                screen: ScreenState::Locked
+            }
+        );
+        assert_eq!(
+            IdleState::from_raw(None, None),
+            IdleState {
+                user: UserState::Active,
+                screen: ScreenState::Unlocked
+            }
+        );
+    }
+}
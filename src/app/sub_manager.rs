@@ -1,3 +1,4 @@
+use super::leak_detector::{Counted, Resource};
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::{cell::RefCell, rc::Rc};
@@ -6,31 +7,45 @@ use uuid::Uuid;
 // ------ SubManager ------
 
 type Subscriptions<Ms> = HashMap<TypeId, HashMap<Uuid, Subscription<Ms>>>;
+type Handler<Ms> = Box<dyn Fn(&Box<dyn Any>) -> Option<Ms>>;
+type TypeWatcher = Rc<RefCell<Option<Box<dyn Fn(TypeId, bool)>>>>;
 
 #[derive(Default)]
 pub struct SubManager<Ms> {
     subs: Rc<RefCell<Subscriptions<Ms>>>,
+    type_watcher: TypeWatcher,
 }
 
 impl<Ms: 'static> SubManager<Ms> {
     pub fn new() -> Self {
         Self {
             subs: Rc::new(RefCell::new(HashMap::new())),
+            type_watcher: TypeWatcher::default(),
         }
     }
 
+    /// Call `watcher` with `true` when the first subscription of a notification type is added
+    /// and with `false` when the last one is removed.
+    /// It's called immediately for the types which already have subscriptions.
+    pub fn watch_types(&self, watcher: impl Fn(TypeId, bool) + 'static) {
+        let subscribed_types = self
+            .subs
+            .borrow()
+            .iter()
+            .filter(|(_, subscriptions)| !subscriptions.is_empty())
+            .map(|(type_id, _)| *type_id)
+            .collect::<Vec<_>>();
+        for type_id in subscribed_types {
+            watcher(type_id, true);
+        }
+        self.type_watcher.replace(Some(Box::new(watcher)));
+    }
+
     pub fn subscribe<SubMs: 'static + Clone>(
         &mut self,
         handler: impl FnOnce(SubMs) -> Ms + Clone + 'static,
     ) {
-        let sub = Subscription::new(handler);
-        let (type_id, id) = (sub.type_id, sub.id);
-
-        self.subs
-            .borrow_mut()
-            .entry(type_id)
-            .or_insert_with(HashMap::new)
-            .insert(id, sub);
+        self.insert(Subscription::new(handler));
     }
 
     pub fn subscribe_with_handle<SubMs: 'static + Clone>(
@@ -38,22 +53,61 @@ impl<Ms: 'static> SubManager<Ms> {
         handler: impl FnOnce(SubMs) -> Ms + Clone + 'static,
     ) -> SubHandle {
         let sub = Subscription::new(handler);
-        let (type_id, id) = (sub.type_id, sub.id);
+        self.insert_with_handle(sub)
+    }
 
-        self.subs
-            .borrow_mut()
-            .entry(type_id)
-            .or_insert_with(HashMap::new)
-            .insert(id, sub);
+    /// Like `subscribe`, but messages are sent only when `handler` returns `Some`.
+    pub fn subscribe_filter_map<SubMs: 'static + Clone>(
+        &mut self,
+        handler: impl FnOnce(SubMs) -> Option<Ms> + Clone + 'static,
+    ) {
+        self.insert(Subscription::filter_map(handler));
+    }
+
+    /// Like `subscribe_with_handle`, but messages are sent only when `handler` returns `Some`.
+    pub fn subscribe_filter_map_with_handle<SubMs: 'static + Clone>(
+        &mut self,
+        handler: impl FnOnce(SubMs) -> Option<Ms> + Clone + 'static,
+    ) -> SubHandle {
+        self.insert_with_handle(Subscription::filter_map(handler))
+    }
+
+    fn insert(&self, sub: Subscription<Ms>) {
+        let type_id = sub.type_id;
+        let first = {
+            let mut subs = self.subs.borrow_mut();
+            let subscriptions = subs.entry(type_id).or_default();
+            subscriptions.insert(sub.id, sub);
+            subscriptions.len() == 1
+        };
+        if first {
+            watch(&self.type_watcher, type_id, true);
+        }
+    }
+
+    fn insert_with_handle(&self, sub: Subscription<Ms>) -> SubHandle {
+        let (type_id, id) = (sub.type_id, sub.id);
+        self.insert(sub);
 
-        let subs = Rc::clone(&self.subs);
+        let (subs, type_watcher) = (Rc::clone(&self.subs), Rc::clone(&self.type_watcher));
         SubHandle {
+            _counted: Counted::new(Resource::SubHandle),
             unsubscriber: Box::new(move || {
-                subs.borrow_mut()
-                    .get_mut(&type_id)
-                    .expect("get subscriptions by `type_id`")
-                    .remove(&id)
-                    .expect("remove subscription");
+                let last = {
+                    let mut subs = subs.borrow_mut();
+                    let subscriptions = subs
+                        .get_mut(&type_id)
+                        .expect("get subscriptions by `type_id`");
+                    subscriptions.remove(&id).expect("remove subscription");
+                    let last = subscriptions.is_empty();
+                    if last {
+                        subs.remove(&type_id);
+                    }
+                    last
+                };
+                if last {
+                    watch(&type_watcher, type_id, false);
+                }
             }),
         }
     }
@@ -65,17 +119,25 @@ impl<Ms: 'static> SubManager<Ms> {
             .map(|subscriptions| {
                 subscriptions
                     .values()
-                    .map(|subscription| (&subscription.handler)(&notification.message))
+                    .filter_map(|subscription| (subscription.handler)(&notification.message))
                     .collect()
             })
             .unwrap_or_default()
     }
 }
 
+fn watch(type_watcher: &TypeWatcher, type_id: TypeId, subscribed: bool) {
+    if let Some(watcher) = type_watcher.borrow().as_ref() {
+        watcher(type_id, subscribed);
+    }
+}
+
 // ------ SubHandle ------
 
 pub struct SubHandle {
     unsubscriber: Box<dyn Fn()>,
+    // See `leak_detector`.
+    _counted: Counted,
 }
 
 impl Drop for SubHandle {
@@ -89,13 +151,19 @@ impl Drop for SubHandle {
 struct Subscription<Ms> {
     type_id: TypeId,
     id: Uuid,
-    handler: Box<dyn Fn(&Box<dyn Any>) -> Ms>,
+    handler: Handler<Ms>,
 }
 
 impl<Ms: 'static> Subscription<Ms> {
-    #[allow(clippy::shadow_unrelated)]
     pub fn new<SubMs: 'static + Clone>(
         handler: impl FnOnce(SubMs) -> Ms + Clone + 'static,
+    ) -> Self {
+        Self::filter_map(move |sub_msg| Some(handler(sub_msg)))
+    }
+
+    #[allow(clippy::shadow_unrelated)]
+    pub fn filter_map<SubMs: 'static + Clone>(
+        handler: impl FnOnce(SubMs) -> Option<Ms> + Clone + 'static,
     ) -> Self {
         // Convert `FnOnce + Clone` to `Fn`.
         let handler = move |sub_msg: SubMs| handler.clone()(sub_msg);
@@ -131,3 +199,52 @@ impl Notification {
         }
     }
 }
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Clone)]
+    struct Tick;
+
+    #[wasm_bindgen_test]
+    fn watcher_is_called_for_first_and_last_subscription() {
+        let mut sub_manager = SubManager::<()>::new();
+        sub_manager.subscribe(|()| ());
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        sub_manager.watch_types({
+            let changes = Rc::clone(&changes);
+            move |type_id, subscribed| changes.borrow_mut().push((type_id, subscribed))
+        });
+        assert_eq!(*changes.borrow(), vec![(TypeId::of::<()>(), true)]);
+        changes.borrow_mut().clear();
+
+        let first = sub_manager.subscribe_with_handle(|Tick| ());
+        let second = sub_manager.subscribe_with_handle(|Tick| ());
+        drop(first);
+        assert_eq!(*changes.borrow(), vec![(TypeId::of::<Tick>(), true)]);
+        drop(second);
+        assert_eq!(
+            *changes.borrow(),
+            vec![(TypeId::of::<Tick>(), true), (TypeId::of::<Tick>(), false)]
+        );
+        assert!(sub_manager.notify(&Notification::new(Tick)).is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn filter_map_subscriptions_skip_none() {
+        let mut sub_manager = SubManager::<u32>::new();
+        sub_manager.subscribe_filter_map(|n: u32| if n % 2 == 0 { Some(n) } else { None });
+        let handle = sub_manager.subscribe_filter_map_with_handle(|n: u32| Some(n * 10));
+        assert_eq!(sub_manager.notify(&Notification::new(1_u32)), vec![10]);
+
+        drop(handle);
+        assert!(sub_manager.notify(&Notification::new(1_u32)).is_empty());
+        assert_eq!(sub_manager.notify(&Notification::new(2_u32)), vec![2]);
+    }
+}
@@ -1,10 +1,72 @@
-use crate::browser::Url;
+use crate::browser::{route, Route, RouteLoader, Url};
+
+pub(crate) mod listeners;
 
 // ------ UrlRequested sub ------
 
 pub mod url_requested;
 pub use url_requested::UrlRequested;
 
+// ------ Page lifecycle subs ------
+
+pub mod page_lifecycle;
+pub use page_lifecycle::{PageFrozen, PageHidden, PageResumed, PageShown};
+
+// ------ FullscreenChanged sub ------
+
+mod fullscreen;
+pub use fullscreen::FullscreenChanged;
+
+// ------ Media subs ------
+
+mod media;
+pub use media::{MediaBuffering, MediaEnded, MediaPlaybackChanged, MediaTimeUpdated};
+
+// ------ Picture-in-Picture subs ------
+
+mod picture_in_picture;
+pub use picture_in_picture::{PictureInPictureEntered, PictureInPictureLeft};
+
+// ------ Print subs ------
+
+mod print;
+pub use print::{AfterPrint, BeforePrint};
+
+// ------ Pasted sub ------
+
+mod paste;
+pub use paste::Pasted;
+
+// ------ SelectionChanged sub ------
+
+mod selection;
+pub use selection::SelectionChanged;
+
+// ------ WindowMessage sub ------
+
+mod window_message;
+pub use window_message::WindowMessage;
+
+// ------ OrientationChanged sub ------
+
+mod orientation;
+pub use orientation::OrientationChanged;
+
+// ------ ConnectionChanged sub ------
+
+mod connection;
+pub use connection::{ConnectionChanged, EffectiveConnectionType};
+
+// ------ DirectionChanged sub ------
+
+mod direction;
+pub use direction::DirectionChanged;
+
+// ------ PendingWorkChanged sub ------
+
+pub(crate) mod pending_work;
+pub use pending_work::PendingWorkChanged;
+
 // ------ UrlChanged sub ------
 
 /// Subscribe to url changes.
@@ -18,3 +80,75 @@ pub use url_requested::UrlRequested;
 /// ```
 #[derive(Clone)]
 pub struct UrlChanged(pub Url);
+
+// ------ RouteMatched sub ------
+
+/// Subscribe to url changes which match the typed route `R` - see `Orders::subscribe_route`.
+#[derive(Debug, Clone)]
+pub struct RouteMatched<R> {
+    /// The route parsed from `url`.
+    pub route: R,
+    pub url: Url,
+}
+
+impl<R: Route> RouteMatched<R> {
+    /// Parse the route from the changed url; `None` when it doesn't match
+    /// and `R` has no `Route::not_found` fallback.
+    pub fn from_url_changed(UrlChanged(url): UrlChanged) -> Option<Self> {
+        route::resolve(&url).map(|route| Self { route, url })
+    }
+}
+
+// ------ RouteLoaded sub ------
+
+/// The data of the route `R` loaded by `RouteLoader::load` - see `Orders::subscribe_route_loader`.
+///
+/// Swap the page in `update` when it arrives, so the old page stays visible during loading.
+pub struct RouteLoaded<R: RouteLoader> {
+    pub route: R,
+    pub url: Url,
+    pub result: Result<R::Data, R::Error>,
+}
+
+// ------ NavigationPending sub ------
+
+/// Subscribe to route loading - it's fired with `pending: true` when a `RouteLoader` starts
+/// and with `pending: false` when its data are loaded or the load is aborted by the next navigation.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(|subs::NavigationPending { pending, .. }| Msg::NavigationPending(pending));
+/// ```
+#[derive(Debug, Clone)]
+pub struct NavigationPending {
+    pub url: Url,
+    pub pending: bool,
+}
+
+// ------ HistoryNavigated sub ------
+
+/// Subscribe to back / forward navigation - i.e. browser buttons, `cmds::history` or `history.go` calls.
+///
+/// It isn't fired for `push_route` and `replace_route`. It's fired before the associated `UrlChanged`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(Msg::HistoryNavigated);
+///...
+///update(... Msg::HistoryNavigated(subs::HistoryNavigated { direction: subs::HistoryDirection::Back(steps) }) =>
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct HistoryNavigated {
+    pub direction: HistoryDirection,
+}
+
+/// Direction of the history navigation with the number of steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryDirection {
+    Back(u32),
+    Forward(u32),
+    /// The target entry wasn't created by Seed, e.g. by a hash link click or on another page.
+    Unknown,
+}
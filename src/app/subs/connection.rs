@@ -0,0 +1,152 @@
+use super::listeners::SubListeners;
+use crate::app::Notification;
+use crate::browser::util::window;
+use wasm_bindgen::JsCast;
+
+mod raw {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(extends = web_sys::EventTarget)]
+        pub type NetworkInformation;
+
+        #[wasm_bindgen(method, getter, js_name = effectiveType)]
+        pub fn effective_type(this: &NetworkInformation) -> Option<String>;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn downlink(this: &NetworkInformation) -> Option<f64>;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn rtt(this: &NetworkInformation) -> Option<f64>;
+
+        #[wasm_bindgen(method, getter, js_name = saveData)]
+        pub fn save_data(this: &NetworkInformation) -> Option<bool>;
+    }
+}
+
+// ------ ConnectionChanged sub ------
+
+/// Subscribe to network connection changes.
+///
+/// Use the [Network Information API](https://developer.mozilla.org/en-US/docs/Web/API/Network_Information_API)
+/// data to load smaller images or pause prefetching on slow or metered connections.
+///
+/// Only Chromium-based browsers support the API - use `ConnectionChanged::current` to get the initial value.
+///
+/// # Example
+///
+/// ```rust,no_run
+///let connection = subs::ConnectionChanged::current();
+///orders.subscribe(Msg::ConnectionChanged);
+///...
+///Msg::ConnectionChanged(connection) => model.low_quality_images = connection.is_constrained(),
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConnectionChanged {
+    /// `None` when the browser doesn't know it.
+    pub effective_type: Option<EffectiveConnectionType>,
+    /// Estimated bandwidth in megabits per second.
+    pub downlink: Option<f64>,
+    /// Estimated round-trip time in milliseconds.
+    pub rtt: Option<f64>,
+    /// The user has enabled a reduced data usage option.
+    pub save_data: bool,
+}
+
+/// Effective connection type estimated from recently observed `rtt` and `downlink`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EffectiveConnectionType {
+    Slow2G,
+    TwoG,
+    ThreeG,
+    FourG,
+}
+
+impl EffectiveConnectionType {
+    fn from_str(effective_type: &str) -> Option<Self> {
+        match effective_type {
+            "slow-2g" => Some(Self::Slow2G),
+            "2g" => Some(Self::TwoG),
+            "3g" => Some(Self::ThreeG),
+            "4g" => Some(Self::FourG),
+            _ => None,
+        }
+    }
+}
+
+impl ConnectionChanged {
+    /// The current connection; `None` when the browser doesn't support the API.
+    pub fn current() -> Option<Self> {
+        network_information().map(|connection| Self::from(&connection))
+    }
+
+    /// Save data is requested or the connection is slow (`3g` or worse).
+    pub fn is_constrained(&self) -> bool {
+        self.save_data
+            || self.effective_type.map_or(false, |effective_type| {
+                effective_type <= EffectiveConnectionType::ThreeG
+            })
+    }
+}
+
+impl From<&raw::NetworkInformation> for ConnectionChanged {
+    fn from(connection: &raw::NetworkInformation) -> Self {
+        Self {
+            effective_type: connection
+                .effective_type()
+                .as_deref()
+                .and_then(EffectiveConnectionType::from_str),
+            downlink: connection.downlink(),
+            rtt: connection.rtt(),
+            save_data: connection.save_data().unwrap_or_default(),
+        }
+    }
+}
+
+fn network_information() -> Option<raw::NetworkInformation> {
+    js_sys::Reflect::get(&window().navigator(), &"connection".into())
+        .ok()?
+        .dyn_into()
+        .ok()
+}
+
+pub fn listen(listeners: &mut SubListeners) {
+    if let Some(connection) = network_information() {
+        listeners.listen(connection.clone(), "change", move |_| {
+            Some(Notification::new(ConnectionChanged::from(&connection)))
+        });
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn constrained_connection() {
+        let connection = ConnectionChanged {
+            effective_type: EffectiveConnectionType::from_str("3g"),
+            downlink: Some(1.5),
+            rtt: Some(300.),
+            save_data: false,
+        };
+        assert!(connection.is_constrained());
+
+        let connection = ConnectionChanged {
+            effective_type: EffectiveConnectionType::from_str("4g"),
+            ..connection
+        };
+        assert!(!connection.is_constrained());
+        assert!(ConnectionChanged {
+            save_data: true,
+            ..connection
+        }
+        .is_constrained());
+    }
+}
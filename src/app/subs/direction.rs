@@ -0,0 +1,33 @@
+use super::listeners::SubListeners;
+use crate::app::Notification;
+use crate::browser::dom::direction::{self, Direction};
+use crate::browser::util::document;
+use std::cell::Cell;
+
+// ------ DirectionChanged sub ------
+
+/// Subscribe to changes of the document direction (`dir` of `<html>`) -
+/// e.g. set by `direction::set_language` after switching to an RTL locale.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(|subs::DirectionChanged(direction)| Msg::DirectionChanged(direction));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirectionChanged(pub Direction);
+
+pub fn listen(listeners: &mut SubListeners) {
+    let root = match document().document_element() {
+        Some(root) => root,
+        None => return,
+    };
+    let current = Cell::new(direction::get());
+    listeners.observe_attributes(&root, &["dir"], move || {
+        let new = direction::get();
+        if current.replace(new) == new {
+            return None;
+        }
+        Some(Notification::new(DirectionChanged(new)))
+    });
+}
@@ -0,0 +1,33 @@
+use super::listeners::SubListeners;
+use crate::app::{cmds::fullscreen, Notification};
+use crate::browser::util::document;
+
+// ------ FullscreenChanged sub ------
+
+/// Subscribe to fullscreen mode changes.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(|subs::FullscreenChanged { element }| Msg::FullscreenChanged(element.is_some()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FullscreenChanged {
+    /// The element in the fullscreen mode; `None` when the fullscreen mode has been left.
+    pub element: Option<web_sys::Element>,
+}
+
+pub fn listen(listeners: &mut SubListeners) {
+    // Browsers with the standard API may fire also the prefixed event.
+    let trigger =
+        if js_sys::Reflect::has(&document(), &"onfullscreenchange".into()).unwrap_or_default() {
+            "fullscreenchange"
+        } else {
+            "webkitfullscreenchange"
+        };
+    listeners.listen(document(), trigger, |_| {
+        Some(Notification::new(FullscreenChanged {
+            element: fullscreen::element(),
+        }))
+    });
+}
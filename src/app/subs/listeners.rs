@@ -0,0 +1,318 @@
+use super::{
+    AfterPrint, BeforePrint, ConnectionChanged, DirectionChanged, FullscreenChanged,
+    MediaBuffering, MediaEnded, MediaPlaybackChanged, MediaTimeUpdated, OrientationChanged,
+    PageFrozen, PageHidden, PageResumed, PageShown, Pasted, PictureInPictureEntered,
+    PictureInPictureLeft, SelectionChanged, WindowMessage,
+};
+use crate::app::Notification;
+use std::{any::TypeId, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+type Callback = Closure<dyn Fn(JsValue)>;
+
+struct Listener {
+    target: web_sys::EventTarget,
+    trigger: &'static str,
+    capture: bool,
+    callback: Callback,
+}
+
+struct AttributeObserver {
+    observer: web_sys::MutationObserver,
+    _callback: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+// ------ SubListeners ------
+
+/// Browser event listeners and observers which fire notifications for built-in subscriptions
+/// (page lifecycle, fullscreen, ...). They're removed on drop.
+pub struct SubListeners {
+    notify: Rc<dyn Fn(Notification)>,
+    listeners: Vec<Listener>,
+    observers: Vec<AttributeObserver>,
+}
+
+impl SubListeners {
+    pub fn with_notify(notify: Rc<dyn Fn(Notification)>) -> Self {
+        Self {
+            notify,
+            listeners: Vec::new(),
+            observers: Vec::new(),
+        }
+    }
+
+    /// Fire the notification returned by `notification` on each `trigger` event.
+    pub fn listen(
+        &mut self,
+        target: impl Into<web_sys::EventTarget>,
+        trigger: &'static str,
+        notification: impl Fn(JsValue) -> Option<Notification> + 'static,
+    ) {
+        self.add(target.into(), trigger, false, notification);
+    }
+
+    /// Like `listen`, but in the capture phase - use it for events that don't bubble
+    /// (e.g. media events) to receive them from all descendants of `target`.
+    pub fn capture(
+        &mut self,
+        target: impl Into<web_sys::EventTarget>,
+        trigger: &'static str,
+        notification: impl Fn(JsValue) -> Option<Notification> + 'static,
+    ) {
+        self.add(target.into(), trigger, true, notification);
+    }
+
+    /// Fire the notification returned by `notification` when any of the `attributes`
+    /// of `target` is set or removed.
+    pub fn observe_attributes(
+        &mut self,
+        target: &web_sys::Element,
+        attributes: &[&str],
+        notification: impl Fn() -> Option<Notification> + 'static,
+    ) {
+        let notify = Rc::clone(&self.notify);
+        let callback = Closure::wrap(Box::new(move |_: js_sys::Array| {
+            if let Some(notification) = notification() {
+                notify(notification);
+            }
+        }) as Box<dyn FnMut(js_sys::Array)>);
+        let observer = web_sys::MutationObserver::new(callback.as_ref().unchecked_ref())
+            .expect("create subscription MutationObserver");
+        let options = web_sys::MutationObserverInit::new();
+        options.set_attributes(true);
+        options.set_attribute_filter(
+            &attributes
+                .iter()
+                .map(|attribute| JsValue::from_str(attribute))
+                .collect::<js_sys::Array>(),
+        );
+        observer
+            .observe_with_options(target, &options)
+            .expect("observe subscription attributes");
+        self.observers.push(AttributeObserver {
+            observer,
+            _callback: callback,
+        });
+    }
+
+    fn add(
+        &mut self,
+        target: web_sys::EventTarget,
+        trigger: &'static str,
+        capture: bool,
+        notification: impl Fn(JsValue) -> Option<Notification> + 'static,
+    ) {
+        let notify = Rc::clone(&self.notify);
+        let callback = Closure::wrap(Box::new(move |event| {
+            if let Some(notification) = notification(event) {
+                notify(notification);
+            }
+        }) as Box<dyn Fn(JsValue)>);
+        target
+            .add_event_listener_with_callback_and_bool(
+                trigger,
+                callback.as_ref().unchecked_ref(),
+                capture,
+            )
+            .expect("add subscription listener");
+        self.listeners.push(Listener {
+            target,
+            trigger,
+            capture,
+            callback,
+        });
+    }
+}
+
+impl Drop for SubListeners {
+    fn drop(&mut self) {
+        for listener in &self.listeners {
+            listener
+                .target
+                .remove_event_listener_with_callback_and_bool(
+                    listener.trigger,
+                    listener.callback.as_ref().unchecked_ref(),
+                    listener.capture,
+                )
+                .expect("remove subscription listener");
+        }
+        for observer in &self.observers {
+            observer.observer.disconnect();
+        }
+    }
+}
+
+// ------ LazySubListeners ------
+
+struct Group {
+    /// Notification types fired by the group's listeners.
+    types: Vec<TypeId>,
+    install: fn(&mut SubListeners),
+    /// `types` with at least one subscription.
+    subscribed: Vec<TypeId>,
+    listeners: Option<SubListeners>,
+}
+
+/// `SubListeners` grouped by their notification types. A group is installed on the first
+/// subscription to any of its types and removed when the last one is dropped,
+/// so apps don't pay for listeners nobody subscribes to.
+pub struct LazySubListeners {
+    notify: Rc<dyn Fn(Notification)>,
+    groups: Vec<Group>,
+}
+
+impl LazySubListeners {
+    pub fn new(notify: impl Fn(Notification) + 'static) -> Self {
+        Self {
+            notify: Rc::new(notify),
+            groups: Vec::new(),
+        }
+    }
+
+    /// Register listeners added by `install` which fire notifications of `types`.
+    pub fn group(&mut self, types: Vec<TypeId>, install: fn(&mut SubListeners)) {
+        self.groups.push(Group {
+            types,
+            install,
+            subscribed: Vec::new(),
+            listeners: None,
+        });
+    }
+
+    /// Install or remove groups when the notification type `type_id` gets
+    /// its first subscription (`subscribed` is `true`) or loses the last one.
+    pub fn subscriptions_changed(&mut self, type_id: TypeId, subscribed: bool) {
+        for group in &mut self.groups {
+            if !group.types.contains(&type_id) {
+                continue;
+            }
+            group
+                .subscribed
+                .retain(|subscribed_type| *subscribed_type != type_id);
+            if subscribed {
+                group.subscribed.push(type_id);
+            }
+            match (group.subscribed.is_empty(), &group.listeners) {
+                (false, None) => {
+                    let mut listeners = SubListeners::with_notify(Rc::clone(&self.notify));
+                    (group.install)(&mut listeners);
+                    group.listeners = Some(listeners);
+                }
+                (true, Some(_)) => group.listeners = None,
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Register listeners for all built-in browser subscriptions.
+pub fn setup(notify: impl Fn(Notification) + 'static) -> LazySubListeners {
+    let mut listeners = LazySubListeners::new(notify);
+    listeners.group(
+        vec![
+            TypeId::of::<PageHidden>(),
+            TypeId::of::<PageShown>(),
+            TypeId::of::<PageFrozen>(),
+            TypeId::of::<PageResumed>(),
+        ],
+        super::page_lifecycle::listen,
+    );
+    listeners.group(
+        vec![TypeId::of::<FullscreenChanged>()],
+        super::fullscreen::listen,
+    );
+    listeners.group(
+        vec![
+            TypeId::of::<PictureInPictureEntered>(),
+            TypeId::of::<PictureInPictureLeft>(),
+        ],
+        super::picture_in_picture::listen,
+    );
+    listeners.group(
+        vec![
+            TypeId::of::<MediaTimeUpdated>(),
+            TypeId::of::<MediaPlaybackChanged>(),
+            TypeId::of::<MediaBuffering>(),
+            TypeId::of::<MediaEnded>(),
+        ],
+        super::media::listen,
+    );
+    listeners.group(
+        vec![TypeId::of::<BeforePrint>(), TypeId::of::<AfterPrint>()],
+        super::print::listen,
+    );
+    listeners.group(
+        vec![TypeId::of::<WindowMessage>()],
+        super::window_message::listen,
+    );
+    listeners.group(vec![TypeId::of::<Pasted>()], super::paste::listen);
+    listeners.group(
+        vec![TypeId::of::<SelectionChanged>()],
+        super::selection::listen,
+    );
+    listeners.group(
+        vec![TypeId::of::<OrientationChanged>()],
+        super::orientation::listen,
+    );
+    listeners.group(
+        vec![TypeId::of::<ConnectionChanged>()],
+        super::connection::listen,
+    );
+    listeners.group(
+        vec![TypeId::of::<DirectionChanged>()],
+        super::direction::listen,
+    );
+    listeners
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::util::document;
+    use std::cell::Cell;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn freeze() {
+        document()
+            .dispatch_event(&web_sys::Event::new("freeze").unwrap())
+            .unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    fn groups_are_installed_while_subscribed() {
+        let notifications = Rc::new(Cell::new(0));
+        let mut listeners = setup({
+            let notifications = Rc::clone(&notifications);
+            move |_| notifications.set(notifications.get() + 1)
+        });
+        let installed = |listeners: &LazySubListeners| {
+            listeners
+                .groups
+                .iter()
+                .filter(|group| group.listeners.is_some())
+                .count()
+        };
+        freeze();
+        assert_eq!(notifications.get(), 0);
+        assert_eq!(installed(&listeners), 0);
+
+        listeners.subscriptions_changed(TypeId::of::<PageFrozen>(), true);
+        listeners.subscriptions_changed(TypeId::of::<PageHidden>(), true);
+        assert_eq!(installed(&listeners), 1);
+        freeze();
+        assert_eq!(notifications.get(), 1);
+
+        listeners.subscriptions_changed(TypeId::of::<PageFrozen>(), false);
+        freeze();
+        assert_eq!(notifications.get(), 2);
+
+        listeners.subscriptions_changed(TypeId::of::<PageHidden>(), false);
+        assert_eq!(installed(&listeners), 0);
+        freeze();
+        assert_eq!(notifications.get(), 2);
+    }
+}
@@ -0,0 +1,112 @@
+use super::listeners::SubListeners;
+use crate::app::Notification;
+use crate::browser::util::document;
+use wasm_bindgen::{JsCast, JsValue};
+
+// ------ MediaTimeUpdated sub ------
+
+/// Subscribe to playback position changes of `audio` and `video` elements.
+/// Browsers fire it 4-66 times per second while playing and after seeking.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(|subs::MediaTimeUpdated { current_time, duration, .. }| {
+///    Msg::Progress(duration.map(|duration| current_time / duration))
+///});
+/// ```
+#[derive(Debug, Clone)]
+pub struct MediaTimeUpdated {
+    pub media: web_sys::HtmlMediaElement,
+    /// In seconds.
+    pub current_time: f64,
+    /// In seconds; `None` until the metadata are loaded.
+    pub duration: Option<f64>,
+}
+
+// ------ MediaPlaybackChanged sub ------
+
+/// Subscribe to the playback start or pause of `audio` and `video` elements.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(|subs::MediaPlaybackChanged { paused, .. }| Msg::Paused(paused));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MediaPlaybackChanged {
+    pub media: web_sys::HtmlMediaElement,
+    pub paused: bool,
+}
+
+// ------ MediaBuffering sub ------
+
+/// Subscribe to buffering of `audio` and `video` elements - `buffering` is `true`
+/// when the playback has stopped because of missing data and `false` when it continues.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(|subs::MediaBuffering { buffering, .. }| Msg::ShowSpinner(buffering));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MediaBuffering {
+    pub media: web_sys::HtmlMediaElement,
+    pub buffering: bool,
+}
+
+// ------ MediaEnded sub ------
+
+/// Subscribe to the playback end of `audio` and `video` elements.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(|_: subs::MediaEnded| Msg::PlayNext);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MediaEnded {
+    pub media: web_sys::HtmlMediaElement,
+}
+
+/// Media events don't bubble - they're captured on the document.
+pub fn listen(listeners: &mut SubListeners) {
+    listeners.capture(document(), "timeupdate", |event| {
+        let media = target_media(&event)?;
+        Some(Notification::new(MediaTimeUpdated {
+            current_time: media.current_time(),
+            duration: Some(media.duration()).filter(|duration| !duration.is_nan()),
+            media,
+        }))
+    });
+    for trigger in ["play", "pause"].iter().copied() {
+        listeners.capture(document(), trigger, |event| {
+            let media = target_media(&event)?;
+            Some(Notification::new(MediaPlaybackChanged {
+                paused: media.paused(),
+                media,
+            }))
+        });
+    }
+    for (trigger, buffering) in [("waiting", true), ("playing", false)].iter().copied() {
+        listeners.capture(document(), trigger, move |event| {
+            Some(Notification::new(MediaBuffering {
+                media: target_media(&event)?,
+                buffering,
+            }))
+        });
+    }
+    listeners.capture(document(), "ended", |event| {
+        Some(Notification::new(MediaEnded {
+            media: target_media(&event)?,
+        }))
+    });
+}
+
+fn target_media(event: &JsValue) -> Option<web_sys::HtmlMediaElement> {
+    event
+        .unchecked_ref::<web_sys::Event>()
+        .target()?
+        .dyn_into()
+        .ok()
+}
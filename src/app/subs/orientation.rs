@@ -0,0 +1,26 @@
+use super::listeners::SubListeners;
+use crate::app::{cmds::orientation, Notification};
+use web_sys::OrientationType;
+
+/// Subscribe to screen orientation changes.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(|subs::OrientationChanged { orientation, .. }| Msg::OrientationChanged(orientation));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct OrientationChanged {
+    pub orientation: OrientationType,
+    /// Angle in degrees - `0`, `90`, `180` or `270`.
+    pub angle: u16,
+}
+
+pub fn listen(listeners: &mut SubListeners) {
+    if let Ok(screen_orientation) = orientation::screen_orientation() {
+        listeners.listen(screen_orientation, "change", |_| {
+            let (orientation, angle) = orientation::current().ok()?;
+            Some(Notification::new(OrientationChanged { orientation, angle }))
+        });
+    }
+}
@@ -0,0 +1,102 @@
+//! [Page Lifecycle API](https://developer.chrome.com/blog/page-lifecycle-api/) subscriptions.
+//!
+//! Use them to flush analytics and persist state instead of the `unload` event - it isn't fired
+//! reliably on mobile browsers and it prevents the page from entering the back/forward cache.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!orders
+//!    .subscribe(|_: subs::PageHidden| Msg::SaveDraft)
+//!    .subscribe(|_: subs::PageFrozen| Msg::StopTimers)
+//!    .subscribe(|_: subs::PageResumed| Msg::StartTimers);
+//! ```
+
+use super::listeners::SubListeners;
+use crate::app::Notification;
+use crate::browser::util::{document, window};
+use wasm_bindgen::{JsCast, JsValue};
+
+/// The page is being unloaded or stored in the back/forward cache (`pagehide`).
+/// It's the last reliable place to persist state.
+#[derive(Debug, Clone, Copy)]
+pub struct PageHidden {
+    /// The page may be restored from the back/forward cache.
+    pub persisted: bool,
+}
+
+/// The page has been loaded or restored from the back/forward cache (`pageshow`).
+#[derive(Debug, Clone, Copy)]
+pub struct PageShown {
+    /// The page has been restored from the back/forward cache.
+    pub persisted: bool,
+}
+
+/// The browser has frozen the hidden page to save resources (`freeze`) - timers and
+/// cmds are paused until `PageResumed`. The page may be discarded without other events.
+#[derive(Debug, Clone, Copy)]
+pub struct PageFrozen;
+
+/// The frozen page is running again (`resume`).
+#[derive(Debug, Clone, Copy)]
+pub struct PageResumed;
+
+// ------ Listeners ------
+
+pub(crate) fn listen(listeners: &mut SubListeners) {
+    let persisted = |event: &JsValue| {
+        event
+            .unchecked_ref::<web_sys::PageTransitionEvent>()
+            .persisted()
+    };
+
+    listeners.listen(window(), "pagehide", move |event| {
+        Some(Notification::new(PageHidden {
+            persisted: persisted(&event),
+        }))
+    });
+    listeners.listen(window(), "pageshow", move |event| {
+        Some(Notification::new(PageShown {
+            persisted: persisted(&event),
+        }))
+    });
+    listeners.listen(document(), "freeze", |_| {
+        Some(Notification::new(PageFrozen))
+    });
+    listeners.listen(document(), "resume", |_| {
+        Some(Notification::new(PageResumed))
+    });
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::Cell, rc::Rc};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn notify_on_lifecycle_events() {
+        let notifications = Rc::new(Cell::new(0));
+        let mut listeners = SubListeners::with_notify(Rc::new({
+            let notifications = Rc::clone(&notifications);
+            move |_| notifications.set(notifications.get() + 1)
+        }));
+        listen(&mut listeners);
+
+        for trigger in &["freeze", "resume"] {
+            let event = web_sys::Event::new(trigger).unwrap();
+            document().dispatch_event(&event).unwrap();
+        }
+        assert_eq!(notifications.get(), 2);
+
+        drop(listeners);
+        document()
+            .dispatch_event(&web_sys::Event::new("freeze").unwrap())
+            .unwrap();
+        assert_eq!(notifications.get(), 2);
+    }
+}
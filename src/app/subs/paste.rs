@@ -0,0 +1,51 @@
+use super::listeners::SubListeners;
+use crate::app::Notification;
+use crate::browser::dom::content_editable;
+use crate::browser::util::document;
+use wasm_bindgen::JsCast;
+
+// ------ Pasted sub ------
+
+/// Subscribe to content pasted anywhere in the document - e.g. images pasted for upload.
+///
+/// It's fired also for pastes into inputs and editors; use `paste_ev` to handle
+/// pastes only in a specific element or to prevent the default behavior.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(|pasted: subs::Pasted| {
+///    IF!(!pasted.files.is_empty() => Msg::UploadFiles(pasted.files))
+///});
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pasted {
+    pub text: String,
+    /// Unsanitized HTML - pass it through `content_editable::sanitize_html` before inserting.
+    pub html: Option<String>,
+    /// Pasted files - e.g. a copied screenshot or files copied in the file manager.
+    pub files: Vec<web_sys::File>,
+}
+
+impl From<&web_sys::ClipboardEvent> for Pasted {
+    fn from(event: &web_sys::ClipboardEvent) -> Self {
+        let content_editable::Pasted { text, html } = event.into();
+        let files = event
+            .clipboard_data()
+            .and_then(|data| data.files())
+            .map(|files| {
+                (0..files.length())
+                    .filter_map(|index| files.get(index))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { text, html, files }
+    }
+}
+
+pub fn listen(listeners: &mut SubListeners) {
+    listeners.listen(document(), "paste", |event| {
+        let event = event.dyn_ref::<web_sys::ClipboardEvent>()?;
+        Some(Notification::new(Pasted::from(event)))
+    });
+}
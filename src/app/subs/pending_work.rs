@@ -0,0 +1,128 @@
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+
+// ------ PendingWorkChanged sub ------
+
+/// Subscribe to changes of the number of running cmds - e.g. for a global loading bar
+/// or a "Saving..." indicator.
+///
+/// Tracking is opt-in - enable it by `App::track_pending_work`. All cmds performed by `Orders`
+/// are counted; cmds performed by `Orders::perform_cmd_in_group` are counted also by their group.
+///
+/// # Example
+///
+/// ```rust,no_run
+///App::start("app", init, update, view).track_pending_work();
+///...
+///orders.subscribe(Msg::PendingWorkChanged);
+///...
+///Msg::PendingWorkChanged(pending_work) => {
+///    model.loading = pending_work.is_pending();
+///    model.saving = pending_work.count("save") > 0;
+///}
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PendingWorkChanged {
+    /// The number of all running cmds.
+    pub total: usize,
+    /// The number of running cmds by their group.
+    pub by_group: BTreeMap<String, usize>,
+}
+
+impl PendingWorkChanged {
+    pub const fn is_pending(&self) -> bool {
+        self.total > 0
+    }
+
+    /// The number of running cmds in the group `group`.
+    pub fn count(&self, group: &str) -> usize {
+        self.by_group.get(group).copied().unwrap_or_default()
+    }
+}
+
+// ------ PendingWork ------
+
+/// Counters of running cmds; stored in `AppData`.
+#[derive(Default)]
+pub(crate) struct PendingWork {
+    enabled: Cell<bool>,
+    state: RefCell<PendingWorkChanged>,
+}
+
+impl PendingWork {
+    pub fn enable(&self) {
+        self.enabled.set(true);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.get()
+    }
+
+    /// Count a started cmd; returns the new state.
+    pub fn start(&self, group: Option<&str>) -> PendingWorkChanged {
+        let mut state = self.state.borrow_mut();
+        state.total += 1;
+        if let Some(group) = group {
+            *state.by_group.entry(group.to_owned()).or_default() += 1;
+        }
+        state.clone()
+    }
+
+    /// Count a finished or aborted cmd; returns the new state.
+    pub fn finish(&self, group: Option<&str>) -> PendingWorkChanged {
+        let mut state = self.state.borrow_mut();
+        state.total = state.total.saturating_sub(1);
+        if let Some(group) = group {
+            if let Some(count) = state.by_group.get_mut(group) {
+                *count -= 1;
+                if *count == 0 {
+                    state.by_group.remove(group);
+                }
+            }
+        }
+        state.clone()
+    }
+}
+
+// ------ PendingWorkGuard ------
+
+/// Counts the cmd as finished on drop - i.e. also when the cmd is aborted.
+pub(crate) struct PendingWorkGuard(Option<Box<dyn FnOnce()>>);
+
+impl PendingWorkGuard {
+    pub fn new(on_finish: impl FnOnce() + 'static) -> Self {
+        Self(Some(Box::new(on_finish)))
+    }
+}
+
+impl Drop for PendingWorkGuard {
+    fn drop(&mut self) {
+        if let Some(on_finish) = self.0.take() {
+            on_finish();
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn counts_by_group() {
+        let pending_work = PendingWork::default();
+        pending_work.start(None);
+        let state = pending_work.start(Some("save"));
+        assert_eq!(state.total, 2);
+        assert_eq!(state.count("save"), 1);
+
+        let state = pending_work.finish(Some("save"));
+        assert!(state.is_pending());
+        assert!(state.by_group.is_empty());
+        assert!(!pending_work.finish(None).is_pending());
+    }
+}
@@ -0,0 +1,66 @@
+use super::listeners::SubListeners;
+use crate::app::Notification;
+use crate::browser::util::document;
+use wasm_bindgen::{JsCast, JsValue};
+
+// ------ PictureInPictureEntered sub ------
+
+/// Subscribe to a video entering the Picture-in-Picture mode.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(|subs::PictureInPictureEntered { width, height, .. }| Msg::PipResized(width, height));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PictureInPictureEntered {
+    pub video: web_sys::HtmlVideoElement,
+    /// Size of the Picture-in-Picture window.
+    pub width: u32,
+    pub height: u32,
+}
+
+// ------ PictureInPictureLeft sub ------
+
+/// Subscribe to a video leaving the Picture-in-Picture mode.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(|_: subs::PictureInPictureLeft| Msg::PipClosed);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PictureInPictureLeft {
+    pub video: web_sys::HtmlVideoElement,
+}
+
+/// Both events bubble from the video element.
+pub fn listen(listeners: &mut SubListeners) {
+    listeners.listen(document(), "enterpictureinpicture", |event| {
+        let size = |name: &str| {
+            js_sys::Reflect::get(&event, &"pictureInPictureWindow".into())
+                .and_then(|window| js_sys::Reflect::get(&window, &name.into()))
+                .ok()
+                .and_then(|size| size.as_f64())
+                .map_or(0, |size| size as u32)
+        };
+        Some(Notification::new(PictureInPictureEntered {
+            video: target_video(&event)?,
+            width: size("width"),
+            height: size("height"),
+        }))
+    });
+    listeners.listen(document(), "leavepictureinpicture", |event| {
+        Some(Notification::new(PictureInPictureLeft {
+            video: target_video(&event)?,
+        }))
+    });
+}
+
+fn target_video(event: &JsValue) -> Option<web_sys::HtmlVideoElement> {
+    event
+        .unchecked_ref::<web_sys::Event>()
+        .target()?
+        .dyn_into()
+        .ok()
+}
@@ -0,0 +1,39 @@
+use super::listeners::SubListeners;
+use crate::app::Notification;
+use crate::browser::util::window;
+
+// ------ BeforePrint sub ------
+
+/// Subscribe to the page being printed - e.g. to expand collapsed sections.
+///
+/// Rendering is asynchronous so the browser may lay out the page before your changes;
+/// prefer `@media print` styles for layout changes.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(|_: subs::BeforePrint| Msg::PrintLayout(true));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BeforePrint;
+
+// ------ AfterPrint sub ------
+
+/// Subscribe to the print dialog being closed (the page was printed or the printing cancelled).
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(|_: subs::AfterPrint| Msg::PrintLayout(false));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AfterPrint;
+
+pub fn listen(listeners: &mut SubListeners) {
+    listeners.listen(window(), "beforeprint", |_| {
+        Some(Notification::new(BeforePrint))
+    });
+    listeners.listen(window(), "afterprint", |_| {
+        Some(Notification::new(AfterPrint))
+    });
+}
@@ -0,0 +1,24 @@
+use super::listeners::SubListeners;
+use crate::app::Notification;
+use crate::browser::util::document;
+
+// ------ SelectionChanged sub ------
+
+/// Subscribe to changes of the text selection or the caret position in the document.
+/// Read the selection by `browser::selection::get`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.subscribe(|_: subs::SelectionChanged| Msg::SelectionChanged);
+///...
+///update(... Msg::SelectionChanged => model.selection = selection::get(&model.editor),
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SelectionChanged;
+
+pub fn listen(listeners: &mut SubListeners) {
+    listeners.listen(document(), "selectionchange", |_| {
+        Some(Notification::new(SelectionChanged))
+    });
+}
@@ -0,0 +1,121 @@
+use super::listeners::SubListeners;
+use crate::app::cmds::messaging::{self, MessageError};
+use crate::app::Notification;
+use crate::browser::util::window;
+use serde::{de::DeserializeOwned, Serialize};
+use std::cell::RefCell;
+use wasm_bindgen::JsCast;
+
+thread_local! {
+    /// `None` means only the origin of the current page.
+    static ALLOWED_ORIGINS: RefCell<Option<Vec<String>>> = RefCell::new(None);
+}
+
+// ------ WindowMessage sub ------
+
+/// Subscribe to messages posted to the current window - e.g. from the parent window,
+/// embedded iframes or popups.
+///
+/// Only messages from the origin of the current page are received by default;
+/// allow other origins by `WindowMessage::allow_origins`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///subs::WindowMessage::allow_origins(&["https://host.example.com"]);
+///orders.subscribe(|message: subs::WindowMessage| Msg::HostCommand(message.data()));
+///...
+///update(... Msg::HostCommand(Ok(HostCommand::Theme(theme))) =>
+/// ```
+#[derive(Debug, Clone)]
+pub struct WindowMessage {
+    /// Origin of the sender, e.g. `"https://host.example.com"`.
+    pub origin: String,
+    pub raw_data: wasm_bindgen::JsValue,
+    /// The sender window.
+    pub source: Option<web_sys::Window>,
+}
+
+impl WindowMessage {
+    /// Replace the list of origins allowed to send messages to the app.
+    /// `&["*"]` allows all origins - validate the messages then.
+    pub fn allow_origins(origins: &[&str]) {
+        let origins = origins.iter().copied().map(str::to_owned).collect();
+        ALLOWED_ORIGINS.with(|allowed| allowed.replace(Some(origins)));
+    }
+
+    /// Deserialize the message data.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when the data can't be converted to `T`.
+    pub fn data<T: DeserializeOwned>(&self) -> Result<T, MessageError> {
+        messaging::deserialize(&self.raw_data)
+    }
+
+    /// Post `message` back to the sender window.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MessageError::MissingTarget` when the sender isn't a window
+    /// and other variants when serialization or posting fails.
+    pub fn reply<T: Serialize + ?Sized>(&self, message: &T) -> Result<(), MessageError> {
+        let source = self.source.as_ref().ok_or(MessageError::MissingTarget)?;
+        messaging::post(source, message, &self.origin)
+    }
+}
+
+fn is_allowed(origin: &str) -> bool {
+    ALLOWED_ORIGINS.with(|allowed| match &*allowed.borrow() {
+        Some(allowed) => allowed
+            .iter()
+            .any(|allowed| allowed == "*" || allowed == origin),
+        None => window()
+            .location()
+            .origin()
+            .map_or(false, |own_origin| own_origin == origin),
+    })
+}
+
+pub fn listen(listeners: &mut SubListeners) {
+    listeners.listen(window(), "message", |event| {
+        let event = event.unchecked_into::<web_sys::MessageEvent>();
+        let origin = event.origin();
+        if !is_allowed(&origin) {
+            return None;
+        }
+        Some(Notification::new(WindowMessage {
+            origin,
+            raw_data: event.data(),
+            // `source` is a `WindowProxy` - it isn't an instance of this window's `Window`
+            // when it comes from another origin.
+            source: event.source().map(JsCast::unchecked_into),
+        }))
+    });
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn allowed_origins() {
+        let own_origin = window().location().origin().unwrap();
+        assert!(is_allowed(&own_origin));
+        assert!(!is_allowed("https://evil.example.com"));
+
+        WindowMessage::allow_origins(&["https://host.example.com"]);
+        assert!(is_allowed("https://host.example.com"));
+        assert!(!is_allowed(&own_origin));
+
+        WindowMessage::allow_origins(&["*"]);
+        assert!(is_allowed("https://evil.example.com"));
+
+        ALLOWED_ORIGINS.with(|allowed| allowed.replace(None));
+    }
+}
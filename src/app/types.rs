@@ -1,11 +1,25 @@
-use super::OrdersContainer;
+use super::{OrdersContainer, UpdateEffect};
 use crate::browser::Url;
-use crate::virtual_dom::EventHandler;
+use crate::virtual_dom::{EventHandler, View};
 
 pub type InitFn<Ms, Mdl, ElC, GMs> = fn(Url, &mut OrdersContainer<Ms, Mdl, ElC, GMs>) -> Mdl;
 pub type UpdateFn<Ms, Mdl, ElC, GMs> = fn(Ms, &mut Mdl, &mut OrdersContainer<Ms, Mdl, ElC, GMs>);
+/// `update` of `App::start_pure` - it returns the new model and effects instead of calling `Orders`.
+pub type PureUpdateFn<Ms, Mdl, GMs> = fn(Ms, Mdl) -> (Mdl, Vec<UpdateEffect<Ms, GMs>>);
+pub type PureInitFn<Ms, Mdl, GMs> = fn(Url) -> (Mdl, Vec<UpdateEffect<Ms, GMs>>);
 pub type SinkFn<Ms, Mdl, ElC, GMs> = fn(GMs, &mut Mdl, &mut OrdersContainer<Ms, Mdl, ElC, GMs>);
 pub type ViewFn<Mdl, ElC> = fn(&Mdl) -> ElC;
 pub type RoutesFn<Ms> = fn(Url) -> Option<Ms>;
 pub type WindowEventsFn<Ms, Mdl> = fn(&Mdl) -> Vec<EventHandler<Ms>>;
 pub type MsgListeners<Ms> = Vec<Box<dyn Fn(&Ms)>>;
+/// Returns the label of the message when it should be logged - see `App::log_messages`.
+pub type MsgLogger<Ms> = Box<dyn Fn(&Ms) -> Option<String>>;
+/// Called with the model before an update; the returned check is called with the updated model
+/// and decides whether it should be rendered.
+pub type ShouldRenderFn<Mdl> = Box<dyn Fn(&Mdl) -> RenderCheck<Mdl>>;
+pub type RenderCheck<Mdl> = Box<dyn FnOnce(&Mdl) -> bool>;
+
+pub enum UpdateHandler<Ms: 'static, Mdl: 'static, ElC: View<Ms>, GMs> {
+    Orders(UpdateFn<Ms, Mdl, ElC, GMs>),
+    Pure(PureUpdateFn<Ms, Mdl, GMs>),
+}
@@ -0,0 +1,77 @@
+use crate::browser::util::window;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+// ------ UnloadGuard ------
+
+/// Asks the user to confirm leaving the page (closing the tab, reload, external link, ...).
+/// It's created by `Orders::block_unload_with` and the confirmation is disabled on its drop.
+///
+/// _Note:_ Browsers display their own generic text instead of the returned message
+/// and they may ignore the guard when the user hasn't interacted with the page yet.
+pub struct UnloadGuard {
+    callback: Closure<dyn Fn(JsValue)>,
+}
+
+impl UnloadGuard {
+    pub(crate) fn new<M: Into<String>>(message: impl Fn() -> Option<M> + 'static) -> Self {
+        let callback = Closure::wrap(Box::new(move |event: JsValue| {
+            if let Some(message) = message() {
+                let event = event.unchecked_into::<web_sys::BeforeUnloadEvent>();
+                event.prevent_default();
+                // Chrome and older browsers require `returnValue` to show the dialog.
+                event.set_return_value(&message.into());
+            }
+        }) as Box<dyn Fn(JsValue)>);
+
+        window()
+            .add_event_listener_with_callback("beforeunload", callback.as_ref().unchecked_ref())
+            .expect("add `beforeunload` listener");
+
+        Self { callback }
+    }
+}
+
+impl Drop for UnloadGuard {
+    fn drop(&mut self) {
+        window()
+            .remove_event_listener_with_callback(
+                "beforeunload",
+                self.callback.as_ref().unchecked_ref(),
+            )
+            .expect("remove `beforeunload` listener");
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::Cell, rc::Rc};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn dispatch_beforeunload() -> bool {
+        let mut init = web_sys::EventInit::new();
+        init.cancelable(true);
+        let event = web_sys::Event::new_with_event_init_dict("beforeunload", &init).unwrap();
+        window().dispatch_event(&event).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn block_only_when_message_is_returned() {
+        let dirty = Rc::new(Cell::new(false));
+        let guard = UnloadGuard::new({
+            let dirty = Rc::clone(&dirty);
+            move || Some("You have unsaved changes").filter(|_| dirty.get())
+        });
+
+        assert!(dispatch_beforeunload());
+        dirty.set(true);
+        assert!(!dispatch_beforeunload());
+
+        drop(guard);
+        assert!(dispatch_beforeunload());
+    }
+}
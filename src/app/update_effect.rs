@@ -0,0 +1,162 @@
+//! Effects returned by a pure `update` - see `App::start_pure`.
+//!
+//! The update function returns the new model together with a list of effects
+//! instead of calling `Orders`, so tests can assert on the effects directly.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!fn update(msg: Msg, mut model: Model) -> (Model, Vec<UpdateEffect<Msg>>) {
+//!    match msg {
+//!        Msg::SaveClicked => (model, vec![UpdateEffect::cmd(save())]),
+//!        Msg::Saved(result) => {
+//!            model.saved = result.is_ok();
+//!            (model, vec![])
+//!        }
+//!        Msg::Tick => (model, vec![UpdateEffect::Skip]),
+//!    }
+//!}
+//!
+//!#[wasm_bindgen_test]
+//!fn save_clicked_saves() {
+//!    let (_, effects) = update(Msg::SaveClicked, Model::default());
+//!    assert!(matches!(effects.as_slice(), [UpdateEffect::Cmd(_)]));
+//!}
+//! ```
+
+use super::{effects::Effect, Notification, Orders, OrdersContainer, SubManager, UndefinedGMsg};
+use crate::virtual_dom::View;
+use futures::future::{Future, FutureExt, LocalBoxFuture};
+use std::{any::Any, fmt};
+
+type Subscriber<Ms> = Box<dyn FnOnce(&mut SubManager<Ms>)>;
+
+pub enum UpdateEffect<Ms, GMs = UndefinedGMsg> {
+    /// See `Orders::render`; it's the default.
+    Render,
+    /// See `Orders::force_render_now`.
+    ForceRenderNow,
+    /// See `Orders::skip`.
+    Skip,
+    /// See `Orders::send_msg`.
+    Msg(Ms),
+    /// See `Orders::perform_cmd`; create it by `UpdateEffect::cmd`.
+    Cmd(LocalBoxFuture<'static, Ms>),
+    /// See `Orders::send_g_msg`.
+    GMsg(GMs),
+    /// See `Orders::perform_g_cmd`; create it by `UpdateEffect::g_cmd`.
+    GCmd(LocalBoxFuture<'static, GMs>),
+    /// See `Orders::notify`; create it by `UpdateEffect::notify`.
+    Notify(Notification),
+    /// See `Orders::subscribe`; create it by `UpdateEffect::subscribe`.
+    Subscribe(Subscriber<Ms>),
+}
+
+impl<Ms: fmt::Debug, GMs> fmt::Debug for UpdateEffect<Ms, GMs> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Render => write!(f, "Render"),
+            Self::ForceRenderNow => write!(f, "ForceRenderNow"),
+            Self::Skip => write!(f, "Skip"),
+            Self::Msg(msg) => f.debug_tuple("Msg").field(msg).finish(),
+            Self::Cmd(_) => write!(f, "Cmd"),
+            Self::GMsg(_) => write!(f, "GMsg"),
+            Self::GCmd(_) => write!(f, "GCmd"),
+            Self::Notify(_) => write!(f, "Notify"),
+            Self::Subscribe(_) => write!(f, "Subscribe"),
+        }
+    }
+}
+
+impl<Ms: 'static, GMs: 'static> UpdateEffect<Ms, GMs> {
+    pub fn cmd(cmd: impl Future<Output = Ms> + 'static) -> Self {
+        Self::Cmd(cmd.boxed_local())
+    }
+
+    pub fn g_cmd(g_cmd: impl Future<Output = GMs> + 'static) -> Self {
+        Self::GCmd(g_cmd.boxed_local())
+    }
+
+    pub fn notify(message: impl Any + Clone) -> Self {
+        Self::Notify(Notification::new(message))
+    }
+
+    /// Subscribe for the app's lifetime.
+    pub fn subscribe<SubMs: 'static + Clone>(
+        handler: impl FnOnce(SubMs) -> Ms + Clone + 'static,
+    ) -> Self {
+        Self::Subscribe(Box::new(move |sub_manager| {
+            sub_manager.subscribe(handler);
+        }))
+    }
+
+    /// The message when the effect is `UpdateEffect::Msg` - e.g. for assertions in tests.
+    pub const fn msg(&self) -> Option<&Ms> {
+        match self {
+            Self::Msg(msg) => Some(msg),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn perform<Mdl, ElC: View<Ms> + 'static>(
+        self,
+        orders: &mut OrdersContainer<Ms, Mdl, ElC, GMs>,
+    ) {
+        match self {
+            Self::Render => {
+                orders.render();
+            }
+            Self::ForceRenderNow => {
+                orders.force_render_now();
+            }
+            Self::Skip => {
+                orders.skip();
+            }
+            Self::Msg(msg) => {
+                orders.send_msg(msg);
+            }
+            Self::Cmd(cmd) => {
+                orders.perform_cmd(cmd);
+            }
+            Self::GMsg(g_msg) => {
+                orders.send_g_msg(g_msg);
+            }
+            Self::GCmd(g_cmd) => {
+                orders.perform_g_cmd(g_cmd);
+            }
+            Self::Notify(notification) => {
+                orders.effects.push_back(Effect::Notification(notification));
+            }
+            Self::Subscribe(subscribe) => {
+                subscribe(&mut orders.clone_app().data.sub_manager.borrow_mut());
+            }
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn msg_of_effect() {
+        let effects: Vec<UpdateEffect<u32>> = vec![
+            UpdateEffect::Skip,
+            UpdateEffect::Msg(3),
+            UpdateEffect::cmd(async { 4 }),
+        ];
+        assert_eq!(
+            effects
+                .iter()
+                .filter_map(UpdateEffect::msg)
+                .collect::<Vec<_>>(),
+            vec![&3]
+        );
+        assert_eq!(format!("{:?}", effects), "[Skip, Msg(3), Cmd]");
+    }
+}
@@ -1,6 +1,20 @@
+pub mod audio;
+pub mod cookies;
 pub mod dom;
+pub mod midi;
+pub mod offscreen_canvas;
+pub mod popup;
+pub mod route;
+pub mod rtc;
+pub mod selection;
 pub mod service;
+pub mod shared_worker;
 pub mod url;
 pub mod util;
+pub mod web_socket;
+#[cfg(feature = "web_transport")]
+pub mod web_transport;
+pub mod webauthn;
 
-pub use url::Url;
+pub use route::{Route, RouteLoader};
+pub use url::{Url, UrlSearch};
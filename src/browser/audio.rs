@@ -0,0 +1,367 @@
+//! A thin [Web Audio](https://developer.mozilla.org/en-US/docs/Web/API/Web_Audio_API) layer
+//! for UI sounds and simple games.
+//!
+//! `Audio` owns an `AudioContext` with a master gain node; sounds are decoded once
+//! and then played as one-shots, optionally scheduled on the audio clock.
+//!
+//! _Note:_ Browsers start the context suspended until the user interacts with the page -
+//! call `Audio::resume` from a click or key handler.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!fn init(_: Url, orders: &mut impl Orders<Msg>) -> Model {
+//!    let audio = Audio::new().expect("audio context");
+//!    orders.perform_cmd({
+//!        let audio = audio.clone();
+//!        async move { Msg::ClickSoundLoaded(audio.load("/sounds/click.ogg").await) }
+//!    });
+//!    Model { audio, click_sound: None }
+//!}
+//!...
+//!Msg::ButtonClicked => {
+//!    if let Some(click_sound) = &model.click_sound {
+//!        model.audio.play(click_sound).expect("play sound");
+//!    }
+//!}
+//!Msg::VolumeChanged(volume) => model.audio.set_master_volume(volume),
+//! ```
+
+use crate::app::streams::EventStream;
+use crate::browser::util::window;
+use futures::StreamExt;
+use std::fmt;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// Web Audio result.
+pub type Result<T> = std::result::Result<T, AudioError>;
+
+// ------ AudioError ------
+
+#[derive(Debug, Clone)]
+pub enum AudioError {
+    /// The browser doesn't support Web Audio.
+    Unsupported,
+    /// The sound file can't be fetched or the response status isn't successful.
+    FetchError(JsValue),
+    /// The data isn't in an audio format supported by the browser.
+    DecodeError(JsValue),
+    PlayError(JsValue),
+}
+
+// ------ Sound ------
+
+/// Decoded audio data; it can be played any number of times, also concurrently. Cloning is cheap.
+#[derive(Clone)]
+pub struct Sound(web_sys::AudioBuffer);
+
+impl fmt::Debug for Sound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sound")
+            .field("duration", &self.duration())
+            .finish()
+    }
+}
+
+impl Sound {
+    /// Duration in seconds.
+    pub fn duration(&self) -> f64 {
+        self.0.duration()
+    }
+
+    pub const fn raw(&self) -> &web_sys::AudioBuffer {
+        &self.0
+    }
+}
+
+// ------ PlayOptions ------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlayOptions {
+    /// Gain of the sound before the master gain; `1.0` by default.
+    pub volume: f32,
+    /// Speed and pitch multiplier; `1.0` by default.
+    pub playback_rate: f32,
+    /// Start after `delay` seconds; `0.0` (now) by default.
+    pub delay: f64,
+    /// Repeat until `Playing::stop` is called.
+    pub looped: bool,
+}
+
+impl Default for PlayOptions {
+    fn default() -> Self {
+        Self {
+            volume: 1.,
+            playback_rate: 1.,
+            delay: 0.,
+            looped: false,
+        }
+    }
+}
+
+impl PlayOptions {
+    pub const fn volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub const fn playback_rate(mut self, playback_rate: f32) -> Self {
+        self.playback_rate = playback_rate;
+        self
+    }
+
+    pub const fn delay(mut self, delay: f64) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub const fn looped(mut self) -> Self {
+        self.looped = true;
+        self
+    }
+}
+
+// ------ Audio ------
+
+/// Audio context with a master gain node. Cloning is cheap - clones share the context.
+#[derive(Clone)]
+pub struct Audio {
+    context: web_sys::AudioContext,
+    master: web_sys::GainNode,
+}
+
+impl fmt::Debug for Audio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Audio")
+            .field("state", &self.context.state())
+            .field("master_volume", &self.master_volume())
+            .finish()
+    }
+}
+
+impl Audio {
+    /// Create a new audio context. Browsers limit the number of contexts - create one per app.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AudioError::Unsupported` when the browser doesn't support Web Audio.
+    pub fn new() -> Result<Self> {
+        let context = web_sys::AudioContext::new().map_err(|_| AudioError::Unsupported)?;
+        let master = context.create_gain().map_err(|_| AudioError::Unsupported)?;
+        master
+            .connect_with_audio_node(&context.destination())
+            .map_err(|_| AudioError::Unsupported)?;
+        Ok(Self { context, master })
+    }
+
+    /// Resume the context suspended by the browser's autoplay policy or by `suspend`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AudioError::PlayError` when the browser refuses to resume the context.
+    pub async fn resume(&self) -> Result<()> {
+        let promise = self.context.resume().map_err(AudioError::PlayError)?;
+        JsFuture::from(promise)
+            .await
+            .map(|_| ())
+            .map_err(AudioError::PlayError)
+    }
+
+    /// Pause all sounds - e.g. when the game is paused or the page is hidden.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AudioError::PlayError` when the context is closed.
+    pub async fn suspend(&self) -> Result<()> {
+        let promise = self.context.suspend().map_err(AudioError::PlayError)?;
+        JsFuture::from(promise)
+            .await
+            .map(|_| ())
+            .map_err(AudioError::PlayError)
+    }
+
+    /// The context is running - sounds are audible.
+    pub fn is_running(&self) -> bool {
+        self.context.state() == web_sys::AudioContextState::Running
+    }
+
+    pub fn master_volume(&self) -> f32 {
+        self.master.gain().value()
+    }
+
+    /// Set the gain of all sounds; `0.0` mutes them, `1.0` is the original volume.
+    pub fn set_master_volume(&self, volume: f32) {
+        self.master.gain().set_value(volume);
+    }
+
+    /// The audio clock in seconds - use it to schedule sounds precisely by `PlayOptions::delay`.
+    pub fn current_time(&self) -> f64 {
+        self.context.current_time()
+    }
+
+    /// Fetch and decode a sound file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AudioError::FetchError` or `AudioError::DecodeError`.
+    pub async fn load(&self, url: &str) -> Result<Sound> {
+        let response = JsFuture::from(window().fetch_with_str(url))
+            .await
+            .map_err(AudioError::FetchError)?
+            .unchecked_into::<web_sys::Response>();
+        if !response.ok() {
+            return Err(AudioError::FetchError(JsValue::from(format!(
+                "{} {}",
+                response.status(),
+                response.status_text()
+            ))));
+        }
+        let array_buffer = response.array_buffer().map_err(AudioError::FetchError)?;
+        let array_buffer = JsFuture::from(array_buffer)
+            .await
+            .map_err(AudioError::FetchError)?;
+        self.decode_array_buffer(&array_buffer.unchecked_into())
+            .await
+    }
+
+    /// Decode a sound from the file content, e.g. from `FileHandle::read`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AudioError::DecodeError`.
+    pub async fn decode(&self, bytes: &[u8]) -> Result<Sound> {
+        let array_buffer = js_sys::Uint8Array::from(bytes).buffer();
+        self.decode_array_buffer(&array_buffer).await
+    }
+
+    async fn decode_array_buffer(&self, array_buffer: &js_sys::ArrayBuffer) -> Result<Sound> {
+        let promise = self
+            .context
+            .decode_audio_data(array_buffer)
+            .map_err(AudioError::DecodeError)?;
+        let buffer = JsFuture::from(promise)
+            .await
+            .map_err(AudioError::DecodeError)?;
+        Ok(Sound(buffer.unchecked_into()))
+    }
+
+    /// Play the sound once, now and with the original volume.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AudioError::PlayError` when the sound can't be started.
+    pub fn play(&self, sound: &Sound) -> Result<Playing> {
+        self.play_with(sound, PlayOptions::default())
+    }
+
+    /// Play or schedule the sound.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AudioError::PlayError` when the sound can't be started.
+    pub fn play_with(&self, sound: &Sound, options: PlayOptions) -> Result<Playing> {
+        let source = self
+            .context
+            .create_buffer_source()
+            .map_err(AudioError::PlayError)?;
+        source.set_buffer(Some(&sound.0));
+        source.set_loop(options.looped);
+        source.playback_rate().set_value(options.playback_rate);
+
+        let gain = self.context.create_gain().map_err(AudioError::PlayError)?;
+        gain.gain().set_value(options.volume);
+        source
+            .connect_with_audio_node(&gain)
+            .and_then(|_| gain.connect_with_audio_node(&self.master))
+            .map_err(AudioError::PlayError)?;
+
+        source
+            .start_with_when(self.current_time() + options.delay.max(0.))
+            .map_err(AudioError::PlayError)?;
+        Ok(Playing { source })
+    }
+
+    pub const fn raw(&self) -> &web_sys::AudioContext {
+        &self.context
+    }
+
+    /// The master gain node - connect your own nodes to it to respect the master volume.
+    pub const fn master(&self) -> &web_sys::GainNode {
+        &self.master
+    }
+}
+
+// ------ Playing ------
+
+/// A playing or scheduled sound. Dropping it doesn't stop the sound.
+#[derive(Clone)]
+pub struct Playing {
+    source: web_sys::AudioBufferSourceNode,
+}
+
+impl fmt::Debug for Playing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Playing")
+    }
+}
+
+impl Playing {
+    /// Stop the sound now; it's a no-op when it has already ended.
+    pub fn stop(&self) {
+        let _ = self.source.stop();
+    }
+
+    /// Resolve when the sound has ended or has been stopped.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///let playing = model.audio.play_with(&model.music, PlayOptions::default())?;
+    ///orders.perform_cmd(async move {
+    ///    playing.ended().await;
+    ///    Msg::MusicEnded
+    ///});
+    /// ```
+    pub async fn ended(&self) {
+        let target: &web_sys::EventTarget = self.source.as_ref();
+        EventStream::<web_sys::Event>::new(target, "ended")
+            .next()
+            .await;
+    }
+
+    pub const fn raw(&self) -> &web_sys::AudioBufferSourceNode {
+        &self.source
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn master_volume() {
+        let audio = Audio::new().unwrap();
+        audio.set_master_volume(0.25);
+        assert!((audio.master_volume() - 0.25).abs() < f32::EPSILON);
+    }
+
+    #[wasm_bindgen_test]
+    fn play_options_builder() {
+        let options = PlayOptions::default().volume(0.5).delay(1.5).looped();
+        assert_eq!(
+            options,
+            PlayOptions {
+                volume: 0.5,
+                playback_rate: 1.,
+                delay: 1.5,
+                looped: true,
+            }
+        );
+    }
+}
@@ -0,0 +1,335 @@
+//! Typed access to `document.cookie`.
+//!
+//! Names and values are URI-encoded when they are set and decoded when they are read.
+//!
+//! [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/Document/cookie)
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!cookies::set(
+//!    &Cookie::new("theme", "dark")
+//!        .path("/")
+//!        .max_age(365 * 24 * 60 * 60)
+//!        .same_site(SameSite::Lax)
+//!        .secure(),
+//!);
+//!assert_eq!(cookies::get("theme").as_deref(), Some("dark"));
+//!cookies::remove("theme");
+//! ```
+
+use crate::app::{clock, streams::EventStream};
+use crate::browser::util::html_document;
+use crate::virtual_dom::Ev;
+use futures::stream::{self, LocalBoxStream, Stream, StreamExt};
+use std::{collections::BTreeMap, fmt};
+use wasm_bindgen::{JsCast, JsValue};
+
+// ------ SameSite ------
+
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Set-Cookie/SameSite)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    /// Requires `Cookie::secure`.
+    None,
+}
+
+impl SameSite {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+}
+
+// ------ Cookie ------
+
+/// Cookie with its attributes. Pass it to `cookies::set`.
+///
+/// Cookies without `max_age` and `expires` are session cookies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cookie {
+    name: String,
+    value: String,
+    path: Option<String>,
+    domain: Option<String>,
+    max_age: Option<i64>,
+    expires: Option<String>,
+    same_site: Option<SameSite>,
+    secure: bool,
+}
+
+impl Cookie {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            path: None,
+            domain: None,
+            max_age: None,
+            expires: None,
+            same_site: None,
+            secure: false,
+        }
+    }
+
+    /// Default is the current path.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Default is the current host without subdomains.
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Lifetime in seconds. It takes precedence over `expires`.
+    pub const fn max_age(mut self, seconds: i64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Expiration date.
+    pub fn expires(mut self, date: &js_sys::Date) -> Self {
+        self.expires = Some(String::from(date.to_utc_string()));
+        self
+    }
+
+    pub const fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Send the cookie only over HTTPS.
+    pub const fn secure(mut self) -> Self {
+        self.secure = true;
+        self
+    }
+}
+
+/// Formats the cookie for `document.cookie`.
+impl fmt::Display for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", encode(&self.name), encode(&self.value))?;
+        if let Some(path) = &self.path {
+            write!(f, "; path={}", path)?;
+        }
+        if let Some(domain) = &self.domain {
+            write!(f, "; domain={}", domain)?;
+        }
+        if let Some(max_age) = self.max_age {
+            write!(f, "; max-age={}", max_age)?;
+        }
+        if let Some(expires) = &self.expires {
+            write!(f, "; expires={}", expires)?;
+        }
+        if let Some(same_site) = self.same_site {
+            write!(f, "; samesite={}", same_site.as_str())?;
+        }
+        if self.secure {
+            write!(f, "; secure")?;
+        }
+        Ok(())
+    }
+}
+
+// ------ get / set / remove ------
+
+/// Get the value of the cookie `name`.
+pub fn get(name: &str) -> Option<String> {
+    get_all().remove(name)
+}
+
+/// Get all cookies accessible from JS (i.e. without `HttpOnly` attribute).
+pub fn get_all() -> BTreeMap<String, String> {
+    html_document()
+        .cookie()
+        .map(|cookies| parse(&cookies))
+        .unwrap_or_default()
+}
+
+/// Set the cookie or replace the one with the same name, path and domain.
+///
+/// # Panics
+///
+/// Panics when the document doesn't allow cookies (e.g. it's sandboxed).
+pub fn set(cookie: &Cookie) {
+    html_document()
+        .set_cookie(&cookie.to_string())
+        .expect("set cookie");
+}
+
+/// Remove the cookie `name` with the default path and domain.
+/// Use `remove_cookie` if the cookie has been set with a custom path or domain.
+pub fn remove(name: &str) {
+    remove_cookie(Cookie::new(name, ""));
+}
+
+/// Remove the cookie with the same name, path and domain as `cookie`.
+pub fn remove_cookie(cookie: Cookie) {
+    set(&Cookie {
+        value: String::new(),
+        max_age: Some(0),
+        expires: None,
+        ..cookie
+    });
+}
+
+fn parse(cookies: &str) -> BTreeMap<String, String> {
+    cookies
+        .split(';')
+        .filter_map(|cookie| {
+            let mut name_and_value = cookie.splitn(2, '=');
+            let name = decode(name_and_value.next()?.trim());
+            let value = decode(name_and_value.next().unwrap_or_default().trim());
+            if name.is_empty() {
+                None
+            } else {
+                Some((name, value))
+            }
+        })
+        .collect()
+}
+
+fn encode(text: &str) -> String {
+    js_sys::encode_uri_component(text).into()
+}
+
+/// Text that can't be decoded (e.g. a cookie set by a server without encoding) is returned as is.
+fn decode(text: &str) -> String {
+    js_sys::decode_uri_component(text).map_or_else(|_| text.to_owned(), String::from)
+}
+
+// ------ Changes ------
+
+/// A cookie has been set, changed or removed (`value` is `None`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CookieChange {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+/// Stream `CookieChange`s - including changes made by the server or other tabs.
+///
+/// It listens to [`CookieStore`](https://developer.mozilla.org/en-US/docs/Web/API/CookieStore)
+/// change events where available, otherwise it compares cookies every `poll_interval` milliseconds.
+///
+/// # Example
+///
+/// ```rust,no_run
+///orders.stream(cookies::changes(1000, Msg::CookieChanged));
+/// ```
+pub fn changes<Ms>(
+    poll_interval: u32,
+    handler: impl FnOnce(CookieChange) -> Ms + Clone + 'static,
+) -> impl Stream<Item = Ms> {
+    let triggers: LocalBoxStream<'static, ()> = match cookie_store() {
+        Some(cookie_store) => EventStream::<JsValue>::new(&cookie_store, Ev::Change)
+            .map(|_| ())
+            .boxed_local(),
+        None => clock::interval(poll_interval),
+    };
+    let mut snapshot = get_all();
+    triggers
+        .flat_map(move |()| {
+            let cookies = get_all();
+            let changes = diff(&snapshot, &cookies);
+            snapshot = cookies;
+            stream::iter(changes)
+        })
+        .map(move |change| handler.clone()(change))
+}
+
+fn cookie_store() -> Option<web_sys::EventTarget> {
+    js_sys::Reflect::get(&crate::browser::util::window(), &"cookieStore".into())
+        .ok()?
+        .dyn_into()
+        .ok()
+}
+
+fn diff(old: &BTreeMap<String, String>, new: &BTreeMap<String, String>) -> Vec<CookieChange> {
+    let changed = new
+        .iter()
+        .filter(|(name, value)| old.get(*name) != Some(value))
+        .map(|(name, value)| CookieChange {
+            name: name.clone(),
+            value: Some(value.clone()),
+        });
+    let removed = old
+        .keys()
+        .filter(|name| !new.contains_key(*name))
+        .map(|name| CookieChange {
+            name: name.clone(),
+            value: None,
+        });
+    changed.chain(removed).collect()
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn format_cookie() {
+        let cookie = Cookie::new("user name", "Martin; admin")
+            .path("/")
+            .max_age(3600)
+            .same_site(SameSite::Strict)
+            .secure();
+        assert_eq!(
+            cookie.to_string(),
+            "user%20name=Martin%3B%20admin; path=/; max-age=3600; samesite=Strict; secure"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn parse_cookies() {
+        let cookies = parse("a=1; user%20name=Martin%3B%20admin;  empty=; broken=%E0%A4%A");
+        assert_eq!(cookies["a"], "1");
+        assert_eq!(cookies["user name"], "Martin; admin");
+        assert_eq!(cookies["empty"], "");
+        assert_eq!(cookies["broken"], "%E0%A4%A");
+    }
+
+    #[wasm_bindgen_test]
+    fn set_get_remove() {
+        set(&Cookie::new("seed_test", "value"));
+        assert_eq!(get("seed_test").as_deref(), Some("value"));
+        remove("seed_test");
+        assert_eq!(get("seed_test"), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn diff_cookies() {
+        let old = parse("a=1; b=2; c=3");
+        let new = parse("a=1; b=20; d=4");
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                CookieChange {
+                    name: "b".to_owned(),
+                    value: Some("20".to_owned())
+                },
+                CookieChange {
+                    name: "d".to_owned(),
+                    value: Some("4".to_owned())
+                },
+                CookieChange {
+                    name: "c".to_owned(),
+                    value: None
+                },
+            ]
+        );
+    }
+}
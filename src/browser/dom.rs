@@ -2,9 +2,18 @@
 //! These are the types used internally by our virtual dom.
 
 pub mod cast;
+pub mod content_editable;
 pub mod css_units;
+pub mod custom_event;
+pub mod dialog;
+pub mod direction;
 pub mod event_handler;
+pub mod icons;
+pub mod key;
 pub mod namespace;
+pub mod pointer;
+pub mod popover;
+pub mod responsive_image;
 pub mod virtual_dom_bridge;
 
 pub use namespace::Namespace;
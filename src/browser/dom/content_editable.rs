@@ -0,0 +1,400 @@
+//! Helpers for `contenteditable` regions - rich-text editors, inline editing, etc.
+//!
+//! Mark the region with `UnmanagedContent` so Seed doesn't overwrite the user's edits
+//! on re-render, read its content through an `ElRef`, normalize `beforeinput` events
+//! by `editor_input_ev` and sanitize pasted HTML by `paste_ev`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!div![
+//!    UnmanagedContent,
+//!    el_ref(&model.editor),
+//!    attrs! {At::ContentEditable => true},
+//!    editor_input_ev(Ev::BeforeInput, Msg::EditorInput),
+//!    paste_ev(Msg::Pasted),
+//!    ev(Ev::Input, |_| Msg::Changed),
+//!]
+//!...
+//!Msg::EditorInput(input) if input.kind == InputKind::FormatBold => {
+//!    input.prevent_default();
+//!    model.show_toolbar_hint = true;
+//!}
+//!Msg::Pasted(pasted) => {
+//!    let html = match &pasted.html {
+//!        Some(html) => content_editable::sanitize_html(html, content_editable::BASIC_TAGS),
+//!        None => content_editable::escape_html(&pasted.text),
+//!    };
+//!    content_editable::insert_html(&html);
+//!}
+//!Msg::Changed => model.draft = content_editable::html(&model.editor).unwrap_or_default(),
+//! ```
+
+use crate::browser::util::document;
+use crate::virtual_dom::ElRef;
+use wasm_bindgen::JsCast;
+
+/// Inline formatting tags for `sanitize_html`.
+pub const BASIC_TAGS: &[&str] = &[
+    "a",
+    "b",
+    "strong",
+    "i",
+    "em",
+    "u",
+    "s",
+    "code",
+    "br",
+    "p",
+    "ul",
+    "ol",
+    "li",
+    "blockquote",
+];
+
+/// Elements removed together with their content by `sanitize_html`.
+const DROPPED_TAGS: &[&str] = &[
+    "script", "style", "template", "iframe", "object", "embed", "noscript", "head", "title",
+];
+
+const VOID_TAGS: &[&str] = &["br", "hr"];
+
+// ------ Content ------
+
+/// HTML content of the referenced element.
+pub fn html<E: Clone + JsCast>(el_ref: &ElRef<E>) -> Option<String> {
+    el_ref
+        .map_type::<web_sys::Element>()
+        .get()
+        .map(|element| element.inner_html())
+}
+
+/// Replace the content of the referenced element. It's a no-op when the element doesn't exist.
+///
+/// _Note:_ It resets the caret position and the undo history.
+pub fn set_html<E: Clone + JsCast>(el_ref: &ElRef<E>, html: &str) {
+    if let Some(element) = el_ref.map_type::<web_sys::Element>().get() {
+        element.set_inner_html(html);
+    }
+}
+
+/// Text of the referenced element as rendered - line breaks included.
+pub fn text<E: Clone + JsCast>(el_ref: &ElRef<E>) -> Option<String> {
+    el_ref
+        .map_type::<web_sys::HtmlElement>()
+        .get()
+        .map(|element| element.inner_text())
+}
+
+/// Replace the content of the referenced element by plain text.
+/// It's a no-op when the element doesn't exist.
+pub fn set_text<E: Clone + JsCast>(el_ref: &ElRef<E>, text: &str) {
+    if let Some(element) = el_ref.map_type::<web_sys::HtmlElement>().get() {
+        element.set_inner_text(text);
+    }
+}
+
+/// Insert `html` at the caret position (or replace the selected content) in the focused
+/// editable element. Unlike `set_html`, the change can be undone by the user.
+///
+/// Returns `false` when nothing has been inserted (e.g. there isn't any focused editable element).
+pub fn insert_html(html: &str) -> bool {
+    exec_command("insertHTML", html)
+}
+
+/// Like `insert_html`, but for plain text.
+pub fn insert_text(text: &str) -> bool {
+    exec_command("insertText", text)
+}
+
+fn exec_command(command: &str, value: &str) -> bool {
+    document()
+        .unchecked_into::<web_sys::HtmlDocument>()
+        .exec_command_with_show_ui_and_value(command, false, value)
+        .unwrap_or_default()
+}
+
+// ------ InputKind ------
+
+/// [InputEvent.inputType](https://developer.mozilla.org/en-US/docs/Web/API/InputEvent/inputType)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputKind {
+    InsertText,
+    /// `Shift + Enter`.
+    InsertLineBreak,
+    /// `Enter`.
+    InsertParagraph,
+    InsertFromPaste,
+    InsertFromDrop,
+    /// Text inserted by IME (e.g. Chinese or Japanese input), spell checker or autocorrect.
+    InsertReplacementText,
+    InsertCompositionText,
+    DeleteContentBackward,
+    DeleteContentForward,
+    DeleteWordBackward,
+    DeleteWordForward,
+    DeleteByCut,
+    DeleteByDrag,
+    HistoryUndo,
+    HistoryRedo,
+    FormatBold,
+    FormatItalic,
+    FormatUnderline,
+    FormatStrikeThrough,
+    Other(String),
+}
+
+impl From<&str> for InputKind {
+    fn from(input_type: &str) -> Self {
+        match input_type {
+            "insertText" => Self::InsertText,
+            "insertLineBreak" => Self::InsertLineBreak,
+            "insertParagraph" => Self::InsertParagraph,
+            "insertFromPaste" => Self::InsertFromPaste,
+            "insertFromDrop" => Self::InsertFromDrop,
+            "insertReplacementText" => Self::InsertReplacementText,
+            "insertCompositionText" => Self::InsertCompositionText,
+            "deleteContentBackward" => Self::DeleteContentBackward,
+            "deleteContentForward" => Self::DeleteContentForward,
+            "deleteWordBackward" => Self::DeleteWordBackward,
+            "deleteWordForward" => Self::DeleteWordForward,
+            "deleteByCut" => Self::DeleteByCut,
+            "deleteByDrag" => Self::DeleteByDrag,
+            "historyUndo" => Self::HistoryUndo,
+            "historyRedo" => Self::HistoryRedo,
+            "formatBold" => Self::FormatBold,
+            "formatItalic" => Self::FormatItalic,
+            "formatUnderline" => Self::FormatUnderline,
+            "formatStrikeThrough" => Self::FormatStrikeThrough,
+            input_type => Self::Other(input_type.to_owned()),
+        }
+    }
+}
+
+impl InputKind {
+    pub fn is_insert(&self) -> bool {
+        match self {
+            Self::Other(input_type) => input_type.starts_with("insert"),
+            kind => matches!(
+                kind,
+                Self::InsertText
+                    | Self::InsertLineBreak
+                    | Self::InsertParagraph
+                    | Self::InsertFromPaste
+                    | Self::InsertFromDrop
+                    | Self::InsertReplacementText
+                    | Self::InsertCompositionText
+            ),
+        }
+    }
+
+    pub fn is_delete(&self) -> bool {
+        match self {
+            Self::Other(input_type) => input_type.starts_with("delete"),
+            kind => matches!(
+                kind,
+                Self::DeleteContentBackward
+                    | Self::DeleteContentForward
+                    | Self::DeleteWordBackward
+                    | Self::DeleteWordForward
+                    | Self::DeleteByCut
+                    | Self::DeleteByDrag
+            ),
+        }
+    }
+}
+
+// ------ EditorInput ------
+
+/// Normalized `beforeinput` or `input` event for `editor_input_ev`.
+#[derive(Debug, Clone)]
+pub struct EditorInput {
+    pub kind: InputKind,
+    /// Inserted text; `None` for deletions, formatting and pasted or dropped rich content.
+    pub data: Option<String>,
+    /// The event is a part of an IME composition - don't modify the content until it ends.
+    pub is_composing: bool,
+    pub event: web_sys::InputEvent,
+}
+
+impl From<&web_sys::InputEvent> for EditorInput {
+    fn from(event: &web_sys::InputEvent) -> Self {
+        Self {
+            kind: InputKind::from(event.input_type().as_str()),
+            data: event.data(),
+            is_composing: event.is_composing(),
+            event: event.clone(),
+        }
+    }
+}
+
+impl EditorInput {
+    /// Cancel the edit - call it in the `beforeinput` handler.
+    /// Composition events (`InsertCompositionText`) can't be cancelled.
+    pub fn prevent_default(&self) {
+        self.event.prevent_default();
+    }
+}
+
+// ------ Pasted ------
+
+/// Clipboard content of a `paste` event for `paste_ev`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pasted {
+    pub text: String,
+    /// Unsanitized HTML - pass it through `sanitize_html` before inserting.
+    pub html: Option<String>,
+}
+
+impl From<&web_sys::ClipboardEvent> for Pasted {
+    fn from(event: &web_sys::ClipboardEvent) -> Self {
+        let data = event.clipboard_data();
+        let get = |format: &str| {
+            data.as_ref()
+                .and_then(|data| data.get_data(format).ok())
+                .filter(|content| !content.is_empty())
+        };
+        Self {
+            text: get("text/plain").unwrap_or_default(),
+            html: get("text/html"),
+        }
+    }
+}
+
+// ------ Sanitization ------
+
+/// Escape `text` for inserting as HTML; line breaks are converted to `<br>`.
+pub fn escape_html(text: &str) -> String {
+    escape(&text.replace('\r', "")).replace('\n', "<br>")
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// Keep only `allowed_tags` (e.g. `BASIC_TAGS`) without attributes - except safe `href`s of links.
+///
+/// Other elements are replaced by their content; scripts, styles and embedded objects
+/// are removed. The HTML is parsed in an inert document - no scripts are executed
+/// and no images are loaded.
+pub fn sanitize_html(html: &str, allowed_tags: &[&str]) -> String {
+    let parsed = web_sys::DomParser::new()
+        .and_then(|parser| parser.parse_from_string(html, web_sys::SupportedType::TextHtml));
+    let mut sanitized = String::new();
+    if let Ok(parsed) = parsed {
+        if let Some(body) = parsed.body() {
+            sanitize_children(&body, allowed_tags, &mut sanitized);
+        }
+    }
+    sanitized
+}
+
+fn sanitize_children(node: &web_sys::Node, allowed_tags: &[&str], output: &mut String) {
+    let children = node.child_nodes();
+    for index in 0..children.length() {
+        if let Some(child) = children.get(index) {
+            sanitize_node(&child, allowed_tags, output);
+        }
+    }
+}
+
+fn sanitize_node(node: &web_sys::Node, allowed_tags: &[&str], output: &mut String) {
+    match node.node_type() {
+        web_sys::Node::TEXT_NODE => {
+            let text = node.text_content().unwrap_or_default();
+            output.push_str(&escape(&text));
+        }
+        web_sys::Node::ELEMENT_NODE => {
+            let element = node.unchecked_ref::<web_sys::Element>();
+            let tag = element.tag_name().to_lowercase();
+            if DROPPED_TAGS.contains(&tag.as_str()) {
+                return;
+            }
+            if !allowed_tags.contains(&tag.as_str()) {
+                sanitize_children(node, allowed_tags, output);
+                return;
+            }
+            output.push('<');
+            output.push_str(&tag);
+            if tag == "a" {
+                if let Some(href) = element
+                    .get_attribute("href")
+                    .filter(|href| is_safe_url(href))
+                {
+                    output.push_str(" href=\"");
+                    output.push_str(&escape(&href));
+                    output.push('"');
+                }
+            }
+            output.push('>');
+            if !VOID_TAGS.contains(&tag.as_str()) {
+                sanitize_children(node, allowed_tags, output);
+                output.push_str("</");
+                output.push_str(&tag);
+                output.push('>');
+            }
+        }
+        // Comments, processing instructions, etc.
+        _ => (),
+    }
+}
+
+fn is_safe_url(url: &str) -> bool {
+    let url = url.trim().to_lowercase();
+    match url.find(':') {
+        // Relative URL, e.g. `/docs` or `#section`.
+        None => true,
+        Some(scheme_end) if url[..scheme_end].contains('/') => true,
+        Some(_) => {
+            url.starts_with("http:") || url.starts_with("https:") || url.starts_with("mailto:")
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn sanitize_pasted_html() {
+        let html = r#"<meta charset="utf-8"><style>b { color: red }</style>
+            <div class="doc"><b style="font-weight: 700" onclick="alert(1)">Bold</b> &amp; <span>plain</span>
+            <a href="javascript:alert(1)">bad</a> <a href="https://seed-rs.org" target="_blank">good</a>
+            <img src="x" onerror="alert(1)"><script>alert(1)</script><br></div>"#;
+        assert_eq!(
+            sanitize_html(html, BASIC_TAGS).trim(),
+            "<b>Bold</b> &amp; plain\n            <a>bad</a> <a href=\"https://seed-rs.org\">good</a>\n            <br>"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn escape_plain_text() {
+        assert_eq!(escape_html("a < b\r\nc"), "a &lt; b<br>c");
+    }
+
+    #[wasm_bindgen_test]
+    fn input_kinds() {
+        assert_eq!(
+            InputKind::from("insertParagraph"),
+            InputKind::InsertParagraph
+        );
+        assert!(InputKind::from("insertFromYank").is_insert());
+        assert!(InputKind::DeleteByCut.is_delete());
+        assert!(!InputKind::FormatBold.is_insert());
+    }
+}
@@ -0,0 +1,120 @@
+//! Dispatch `CustomEvent`s to communicate with page code outside of the app - e.g. with
+//! web components or analytics scripts. See `Orders::dispatch_event`.
+
+use crate::virtual_dom::ElRef;
+use serde::Serialize;
+use wasm_bindgen::{JsCast, JsValue};
+
+// ------ DispatchTarget ------
+
+/// Element, `Window` or `Document` where the event is dispatched.
+pub trait DispatchTarget {
+    /// `None` if the target doesn't exist - e.g. `ElRef` isn't attached to a DOM element.
+    fn event_target(&self) -> Option<web_sys::EventTarget>;
+}
+
+impl<E: Clone + JsCast> DispatchTarget for ElRef<E> {
+    fn event_target(&self) -> Option<web_sys::EventTarget> {
+        self.map_type::<web_sys::EventTarget>().get()
+    }
+}
+
+impl DispatchTarget for web_sys::EventTarget {
+    fn event_target(&self) -> Option<web_sys::EventTarget> {
+        Some(self.clone())
+    }
+}
+
+impl DispatchTarget for web_sys::Window {
+    fn event_target(&self) -> Option<web_sys::EventTarget> {
+        Some(self.clone().into())
+    }
+}
+
+impl DispatchTarget for web_sys::Document {
+    fn event_target(&self) -> Option<web_sys::EventTarget> {
+        Some(self.clone().into())
+    }
+}
+
+impl DispatchTarget for web_sys::Element {
+    fn event_target(&self) -> Option<web_sys::EventTarget> {
+        Some(self.clone().into())
+    }
+}
+
+// ------ dispatch_custom_event ------
+
+/// Dispatch bubbling and composed (i.e. crossing shadow DOM boundaries) `CustomEvent`
+/// with serialized `detail`. Listeners read it as a plain JS object from `event.detail`.
+///
+/// Returns `false` if a listener has called `preventDefault`.
+///
+/// # Errors
+///
+/// Returns error when the target doesn't exist, `detail` can't be serialized
+/// or the event can't be created.
+pub fn dispatch_custom_event<T: Serialize + ?Sized>(
+    target: &impl DispatchTarget,
+    name: &str,
+    detail: &T,
+) -> Result<bool, JsValue> {
+    let target = target
+        .event_target()
+        .ok_or_else(|| JsValue::from("dispatch_custom_event: target doesn't exist"))?;
+    let json = serde_json::to_string(detail)
+        .map_err(|serde_error| JsValue::from(serde_error.to_string()))?;
+
+    let mut init = web_sys::CustomEventInit::new();
+    init.bubbles(true)
+        .cancelable(true)
+        .composed(true)
+        .detail(&js_sys::JSON::parse(&json)?);
+    let event = web_sys::CustomEvent::new_with_event_init_dict(name, &init)?;
+    target.dispatch_event(&event)
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Serialize)]
+    struct Selected {
+        id: u32,
+    }
+
+    #[wasm_bindgen_test]
+    fn dispatch_with_detail() {
+        let parent = crate::browser::util::document()
+            .create_element("div")
+            .unwrap();
+        let child = crate::browser::util::document()
+            .create_element("span")
+            .unwrap();
+        parent.append_child(&child).unwrap();
+
+        let received = Rc::new(RefCell::new(None));
+        let listener = Closure::wrap(Box::new({
+            let received = Rc::clone(&received);
+            move |event: JsValue| {
+                let event: web_sys::CustomEvent = event.unchecked_into();
+                let id = js_sys::Reflect::get(&event.detail(), &"id".into()).unwrap();
+                *received.borrow_mut() = id.as_f64();
+            }
+        }) as Box<dyn Fn(JsValue)>);
+        parent
+            .add_event_listener_with_callback("item-selected", listener.as_ref().unchecked_ref())
+            .unwrap();
+
+        assert!(dispatch_custom_event(&child, "item-selected", &Selected { id: 3 }).unwrap());
+        assert_eq!(*received.borrow(), Some(3.));
+        assert!(dispatch_custom_event(&ElRef::<web_sys::Element>::new(), "x", &()).is_err());
+    }
+}
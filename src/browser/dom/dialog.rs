@@ -0,0 +1,162 @@
+//! Native [`<dialog>`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/dialog) helpers.
+//!
+//! Modal dialogs are rendered in the top layer, make the rest of the page inert and are closed
+//! by `Esc` (`Ev::Cancel`). Seed returns the focus to the element which was focused before
+//! the dialog has been opened, once it's closed in any way.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!dialog![
+//!    el_ref(&model.confirm_dialog),
+//!    dialog_close_ev(Msg::ConfirmDialogClosed),
+//!    form![
+//!        attrs! {At::Method => "dialog"},
+//!        button![attrs! {At::Value => "cancel"}, "Cancel"],
+//!        button![attrs! {At::Value => "delete"}, "Delete"],
+//!    ],
+//!],
+//!...
+//!Msg::DeleteClicked => dialog::show_modal(&model.confirm_dialog).expect("show dialog"),
+//!Msg::ConfirmDialogClosed(closed) if closed.return_value == "delete" => {
+//!    orders.send_msg(Msg::Delete);
+//!}
+//!Msg::ConfirmDialogClosed(_) => (),
+//! ```
+
+use crate::browser::util::document;
+use crate::virtual_dom::ElRef;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+// ------ Commands ------
+
+/// Open the referenced dialog as modal.
+///
+/// # Errors
+///
+/// Returns error when the element doesn't exist, it isn't a `<dialog>`,
+/// it's already open non-modally or it isn't in the document.
+pub fn show_modal<E: Clone + JsCast>(el_ref: &ElRef<E>) -> Result<(), JsValue> {
+    let dialog = dialog_element(el_ref)?;
+    if dialog.open() {
+        return Ok(());
+    }
+    let return_focus = return_focus_on_close(&dialog);
+    dialog.show_modal().map_err(|error| {
+        dialog
+            .remove_event_listener_with_callback("close", return_focus.unchecked_ref())
+            .ok();
+        error
+    })
+}
+
+/// Open the referenced dialog non-modally - the rest of the page stays interactive.
+///
+/// # Errors
+///
+/// Returns error when the element doesn't exist or it isn't a `<dialog>`.
+pub fn show<E: Clone + JsCast>(el_ref: &ElRef<E>) -> Result<(), JsValue> {
+    let dialog = dialog_element(el_ref)?;
+    if !dialog.open() {
+        return_focus_on_close(&dialog);
+        dialog.show();
+    }
+    Ok(())
+}
+
+/// Close the referenced dialog; `return_value` is passed to `DialogClosed`.
+/// It's a no-op when the dialog isn't open.
+pub fn close<E: Clone + JsCast>(el_ref: &ElRef<E>, return_value: Option<&str>) {
+    if let Ok(dialog) = dialog_element(el_ref) {
+        match return_value {
+            Some(return_value) => dialog.close_with_return_value(return_value),
+            None => dialog.close(),
+        }
+    }
+}
+
+/// Whether the referenced dialog is open - modally or not.
+pub fn is_open<E: Clone + JsCast>(el_ref: &ElRef<E>) -> bool {
+    dialog_element(el_ref).map_or(false, |dialog| dialog.open())
+}
+
+fn dialog_element<E: Clone + JsCast>(
+    el_ref: &ElRef<E>,
+) -> Result<web_sys::HtmlDialogElement, JsValue> {
+    el_ref
+        .map_type::<web_sys::HtmlDialogElement>()
+        .get()
+        .ok_or_else(|| JsValue::from("the dialog element doesn't exist"))
+}
+
+/// Register a one-time `close` listener which focuses the currently focused element again.
+/// Returns the listener so it can be removed when the dialog can't be opened.
+fn return_focus_on_close(dialog: &web_sys::HtmlDialogElement) -> js_sys::Function {
+    let focused = document()
+        .active_element()
+        .and_then(|element| element.dyn_into::<web_sys::HtmlElement>().ok());
+    let return_focus = Closure::once_into_js(move || {
+        if let Some(focused) = focused.filter(|focused| focused.is_connected()) {
+            focused.focus().ok();
+        }
+    })
+    .unchecked_into::<js_sys::Function>();
+
+    let mut options = web_sys::AddEventListenerOptions::new();
+    options.once(true);
+    dialog
+        .add_event_listener_with_callback_and_add_event_listener_options(
+            "close",
+            &return_focus,
+            &options,
+        )
+        .expect("add dialog close listener");
+    return_focus
+}
+
+// ------ DialogClosed ------
+
+/// `close` event of a dialog for `dialog_close_ev`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DialogClosed {
+    /// Value of the submit button of the `method="dialog"` form, the value passed to `close`,
+    /// or an empty string when the dialog has been cancelled by `Esc`.
+    pub return_value: String,
+}
+
+impl From<&web_sys::HtmlDialogElement> for DialogClosed {
+    fn from(dialog: &web_sys::HtmlDialogElement) -> Self {
+        Self {
+            return_value: dialog.return_value(),
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::util::body;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn show_and_close_with_return_value() {
+        let dialog = document()
+            .create_element("dialog")
+            .unwrap()
+            .unchecked_into::<web_sys::HtmlDialogElement>();
+        body().append_child(&dialog).unwrap();
+        let mut el_ref = ElRef::<web_sys::HtmlDialogElement>::default();
+        el_ref.shared_node_ws.set(dialog.clone().into());
+
+        show_modal(&el_ref).unwrap();
+        assert!(is_open(&el_ref));
+        close(&el_ref, Some("confirm"));
+        assert!(!is_open(&el_ref));
+        assert_eq!(DialogClosed::from(&dialog).return_value, "confirm");
+        dialog.remove();
+    }
+}
@@ -0,0 +1,281 @@
+//! Text direction - `dir` of the document root for apps switching between RTL and LTR locales,
+//! and `LogicalStyle` mapping CSS logical properties to physical ones for older browsers.
+//!
+//! Subscribe to `subs::DirectionChanged` to re-render `LogicalStyle`s after the change.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::LocaleChanged(locale) => {
+//!    model.direction = direction::set_language(&locale);
+//!}
+//!...
+//!div![
+//!    LogicalStyle::new(model.direction)
+//!        .add(LogicalProperty::MarginInlineStart, px(16))
+//!        .text_align_start(),
+//!    "..."
+//!]
+//! ```
+
+use crate::browser::util;
+use crate::virtual_dom::{CSSValue, El, St, Style, UpdateEl};
+use std::fmt;
+
+/// Primary language subtags of languages written from right to left.
+const RTL_LANGUAGES: &[&str] = &[
+    "ar", "arc", "ckb", "dv", "fa", "he", "iw", "khw", "ks", "ps", "sd", "ug", "ur", "yi",
+];
+
+// ------ Direction ------
+
+/// The direction of the text - `dir="ltr"` or `dir="rtl"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Ltr,
+    Rtl,
+}
+
+impl Default for Direction {
+    fn default() -> Self {
+        Self::Ltr
+    }
+}
+
+impl Direction {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Ltr => "ltr",
+            Self::Rtl => "rtl",
+        }
+    }
+
+    /// The direction of the language with the `language` tag, e.g. `ar-EG` or `en`.
+    pub fn of_language(language: &str) -> Self {
+        let primary = language
+            .split(|character| character == '-' || character == '_')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if RTL_LANGUAGES.contains(&primary.as_str()) {
+            Self::Rtl
+        } else {
+            Self::Ltr
+        }
+    }
+
+    /// The direction from the `dir` attribute value; `auto` and invalid values are `Ltr`.
+    pub fn from_attribute(value: &str) -> Self {
+        if value.trim().eq_ignore_ascii_case("rtl") {
+            Self::Rtl
+        } else {
+            Self::Ltr
+        }
+    }
+
+    pub fn is_rtl(self) -> bool {
+        self == Self::Rtl
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The direction of the document (`dir` of `<html>`).
+pub fn get() -> Direction {
+    util::document()
+        .document_element()
+        .and_then(|root| root.get_attribute("dir"))
+        .map_or_else(Direction::default, |dir| Direction::from_attribute(&dir))
+}
+
+/// Set the direction of the document (`dir` of `<html>`).
+pub fn set(direction: Direction) {
+    root()
+        .set_attribute("dir", direction.as_str())
+        .expect("set document dir");
+}
+
+/// Set `lang` and `dir` of the document for the `language` tag (e.g. `he-IL`)
+/// and return the direction.
+pub fn set_language(language: &str) -> Direction {
+    let direction = Direction::of_language(language);
+    root()
+        .set_attribute("lang", language)
+        .expect("set document lang");
+    set(direction);
+    direction
+}
+
+fn root() -> web_sys::Element {
+    util::document()
+        .document_element()
+        .expect("document element")
+}
+
+// ------ LogicalProperty ------
+
+/// CSS logical properties in the horizontal writing mode - `start` is left in `Ltr`
+/// and right in `Rtl`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogicalProperty {
+    MarginInlineStart,
+    MarginInlineEnd,
+    PaddingInlineStart,
+    PaddingInlineEnd,
+    BorderInlineStart,
+    BorderInlineEnd,
+    BorderInlineStartColor,
+    BorderInlineEndColor,
+    BorderInlineStartStyle,
+    BorderInlineEndStyle,
+    BorderInlineStartWidth,
+    BorderInlineEndWidth,
+    InsetInlineStart,
+    InsetInlineEnd,
+    BorderStartStartRadius,
+    BorderStartEndRadius,
+    BorderEndStartRadius,
+    BorderEndEndRadius,
+}
+
+impl LogicalProperty {
+    /// The physical property in the `direction`.
+    pub fn physical(self, direction: Direction) -> St {
+        let (ltr, rtl) = match self {
+            Self::MarginInlineStart => (St::MarginLeft, St::MarginRight),
+            Self::MarginInlineEnd => (St::MarginRight, St::MarginLeft),
+            Self::PaddingInlineStart => (St::PaddingLeft, St::PaddingRight),
+            Self::PaddingInlineEnd => (St::PaddingRight, St::PaddingLeft),
+            Self::BorderInlineStart => (St::BorderLeft, St::BorderRight),
+            Self::BorderInlineEnd => (St::BorderRight, St::BorderLeft),
+            Self::BorderInlineStartColor => (St::BorderLeftColor, St::BorderRightColor),
+            Self::BorderInlineEndColor => (St::BorderRightColor, St::BorderLeftColor),
+            Self::BorderInlineStartStyle => (St::BorderLeftStyle, St::BorderRightStyle),
+            Self::BorderInlineEndStyle => (St::BorderRightStyle, St::BorderLeftStyle),
+            Self::BorderInlineStartWidth => (St::BorderLeftWidth, St::BorderRightWidth),
+            Self::BorderInlineEndWidth => (St::BorderRightWidth, St::BorderLeftWidth),
+            Self::InsetInlineStart => (St::Left, St::Right),
+            Self::InsetInlineEnd => (St::Right, St::Left),
+            Self::BorderStartStartRadius => (St::BorderTopLeftRadius, St::BorderTopRightRadius),
+            Self::BorderStartEndRadius => (St::BorderTopRightRadius, St::BorderTopLeftRadius),
+            Self::BorderEndStartRadius => (St::BorderBottomLeftRadius, St::BorderBottomRightRadius),
+            Self::BorderEndEndRadius => (St::BorderBottomRightRadius, St::BorderBottomLeftRadius),
+        };
+        match direction {
+            Direction::Ltr => ltr,
+            Direction::Rtl => rtl,
+        }
+    }
+}
+
+// ------ LogicalStyle ------
+
+/// Style with logical properties written as physical ones for the `direction`,
+/// so it works also in browsers without logical properties support.
+/// It can be passed to element macros like `Style`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogicalStyle {
+    direction: Direction,
+    style: Style,
+}
+
+impl LogicalStyle {
+    pub fn new(direction: Direction) -> Self {
+        Self {
+            direction,
+            style: Style::empty(),
+        }
+    }
+
+    pub fn add(mut self, property: LogicalProperty, value: impl Into<CSSValue>) -> Self {
+        self.style.add(property.physical(self.direction), value);
+        self
+    }
+
+    /// `text-align: start`
+    pub fn text_align_start(self) -> Self {
+        let align = match self.direction {
+            Direction::Ltr => "left",
+            Direction::Rtl => "right",
+        };
+        self.physical(St::TextAlign, align)
+    }
+
+    /// `text-align: end`
+    pub fn text_align_end(self) -> Self {
+        let align = match self.direction {
+            Direction::Ltr => "right",
+            Direction::Rtl => "left",
+        };
+        self.physical(St::TextAlign, align)
+    }
+
+    /// Add a property which doesn't depend on the direction.
+    pub fn physical(mut self, property: impl Into<St>, value: impl Into<CSSValue>) -> Self {
+        self.style.add(property, value);
+        self
+    }
+
+    pub fn into_style(self) -> Style {
+        self.style
+    }
+}
+
+impl<Ms> UpdateEl<Ms> for LogicalStyle {
+    fn update_el(self, el: &mut El<Ms>) {
+        self.style.update_el(el)
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::virtual_dom::Tag;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn direction_of_language() {
+        assert_eq!(Direction::of_language("ar-EG"), Direction::Rtl);
+        assert_eq!(Direction::of_language("HE"), Direction::Rtl);
+        assert_eq!(Direction::of_language("fa_IR"), Direction::Rtl);
+        assert_eq!(Direction::of_language("en-US"), Direction::Ltr);
+        assert_eq!(Direction::of_language(""), Direction::Ltr);
+    }
+
+    #[wasm_bindgen_test]
+    fn document_direction() {
+        assert_eq!(set_language("he"), Direction::Rtl);
+        assert_eq!(get(), Direction::Rtl);
+        assert_eq!(set_language("en"), Direction::Ltr);
+        assert_eq!(get(), Direction::Ltr);
+    }
+
+    #[wasm_bindgen_test]
+    fn logical_style_is_mirrored() {
+        let style = |direction| {
+            let mut el = El::<()>::empty(Tag::Div);
+            LogicalStyle::new(direction)
+                .add(LogicalProperty::MarginInlineStart, "8px")
+                .add(LogicalProperty::BorderStartEndRadius, "4px")
+                .text_align_end()
+                .update_el(&mut el);
+            el.style.to_string()
+        };
+        assert_eq!(
+            style(Direction::Ltr),
+            "margin-left:8px;border-top-right-radius:4px;text-align:right"
+        );
+        assert_eq!(
+            style(Direction::Rtl),
+            "margin-right:8px;border-top-left-radius:4px;text-align:left"
+        );
+    }
+}
@@ -2,6 +2,11 @@
 //! `web_sys::Event`
 
 use super::super::util;
+use super::content_editable::{EditorInput, Pasted};
+use super::dialog::DialogClosed;
+use super::key::{Key, Modifiers};
+use super::pointer::Pointer;
+use super::popover::PopoverToggle;
 use crate::virtual_dom::{Ev, EventHandler};
 use wasm_bindgen::JsCast;
 
@@ -36,6 +41,29 @@ pub fn keyboard_ev<Ms>(
     EventHandler::new(trigger, closure_handler)
 }
 
+/// Similar to `keyboard_ev`, but it passes the parsed `Key` and pressed `Modifiers`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///key_ev(Ev::KeyDown, |key, modifiers| match key {
+///    Key::Enter => Msg::Submit,
+///    Key::Escape => Msg::Cancel,
+///    Key::Char('s') if modifiers.ctrl_or_meta() => Msg::Save,
+///    _ => Msg::NoOp,
+///})
+/// ```
+pub fn key_ev<Ms>(
+    trigger: impl Into<Ev>,
+    handler: impl FnOnce(Key, Modifiers) -> Ms + 'static + Clone,
+) -> EventHandler<Ms> {
+    let closure_handler = move |event: web_sys::Event| {
+        let event = event.dyn_ref::<web_sys::KeyboardEvent>().unwrap();
+        (handler.clone())(Key::from(event), Modifiers::from(event))
+    };
+    EventHandler::new(trigger, closure_handler)
+}
+
 /// See `keyboard_ev`
 pub fn mouse_ev<Ms>(
     trigger: impl Into<Ev>,
@@ -58,6 +86,102 @@ pub fn pointer_ev<Ms>(
     EventHandler::new(trigger, closure_handler)
 }
 
+/// Similar to `pointer_ev`, but it passes `Pointer` with pressure, pointer type and coalesced events.
+///
+/// # Example
+///
+/// ```rust,no_run
+///canvas![
+///    el_ref(&model.canvas),
+///    pointer_data_ev(Ev::PointerDown, Msg::DrawStarted),
+///    pointer_data_ev(Ev::PointerMove, Msg::Drawn),
+///    pointer_data_ev(Ev::PointerUp, Msg::DrawEnded),
+///]
+///...
+///Msg::DrawStarted(pointer) => {
+///    // Receive `pointermove` events also when the pen leaves the canvas.
+///    model.canvas.set_pointer_capture(pointer.id).expect("capture pointer");
+///}
+/// ```
+pub fn pointer_data_ev<Ms>(
+    trigger: impl Into<Ev>,
+    handler: impl FnOnce(Pointer) -> Ms + 'static + Clone,
+) -> EventHandler<Ms> {
+    let closure_handler = move |event: web_sys::Event| {
+        (handler.clone())(Pointer::from(
+            event.dyn_ref::<web_sys::PointerEvent>().unwrap(),
+        ))
+    };
+    EventHandler::new(trigger, closure_handler)
+}
+
+/// Create an event handler for `Ev::BeforeInput` or `Ev::Input` that passes a normalized `EditorInput`.
+/// See `browser::dom::content_editable`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///editor_input_ev(Ev::BeforeInput, Msg::EditorInput)
+/// ```
+pub fn editor_input_ev<Ms>(
+    trigger: impl Into<Ev>,
+    handler: impl FnOnce(EditorInput) -> Ms + 'static + Clone,
+) -> EventHandler<Ms> {
+    let closure_handler = move |event: web_sys::Event| {
+        (handler.clone())(EditorInput::from(
+            event.dyn_ref::<web_sys::InputEvent>().unwrap(),
+        ))
+    };
+    EventHandler::new(trigger, closure_handler)
+}
+
+/// Create a `paste` event handler that cancels the browser's insertion of the clipboard content
+/// and passes the content to `handler` - sanitize it and insert it by
+/// `content_editable::insert_html`. See `browser::dom::content_editable`.
+pub fn paste_ev<Ms>(handler: impl FnOnce(Pasted) -> Ms + 'static + Clone) -> EventHandler<Ms> {
+    let closure_handler = move |event: web_sys::Event| {
+        event.prevent_default();
+        (handler.clone())(Pasted::from(
+            event.dyn_ref::<web_sys::ClipboardEvent>().unwrap(),
+        ))
+    };
+    EventHandler::new(Ev::Paste, closure_handler)
+}
+
+/// Create an event handler for `Ev::BeforeToggle` or `Ev::Toggle` of a popover
+/// that passes its old and new state. See `browser::dom::popover`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///popover_toggle_ev(Ev::Toggle, |toggle| Msg::MenuToggled(toggle.is_open()))
+/// ```
+pub fn popover_toggle_ev<Ms>(
+    trigger: impl Into<Ev>,
+    handler: impl FnOnce(PopoverToggle) -> Ms + 'static + Clone,
+) -> EventHandler<Ms> {
+    let closure_handler =
+        move |event: web_sys::Event| (handler.clone())(PopoverToggle::from(&event));
+    EventHandler::new(trigger, closure_handler)
+}
+
+/// Create a `close` event handler for a `<dialog>` that passes its return value.
+/// Cancel `Esc` closing by `ev(Ev::Cancel, |event| event.prevent_default())`.
+/// See `browser::dom::dialog`.
+pub fn dialog_close_ev<Ms>(
+    handler: impl FnOnce(DialogClosed) -> Ms + 'static + Clone,
+) -> EventHandler<Ms> {
+    let closure_handler = move |event: web_sys::Event| {
+        (handler.clone())(DialogClosed::from(
+            event
+                .current_target()
+                .unwrap()
+                .unchecked_ref::<web_sys::HtmlDialogElement>(),
+        ))
+    };
+    EventHandler::new(Ev::Close, closure_handler)
+}
+
 /// Create an event that accepts a closure, and passes a `web_sys::Event`, allowing full control of
 /// event-handling.
 #[deprecated(since = "0.6.0", note = "Use `ev` instead.")]
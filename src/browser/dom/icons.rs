@@ -0,0 +1,178 @@
+//! SVG icon sprite - icons are registered once as `<symbol>`s in a hidden sprite sheet
+//! and referenced by small `<svg><use></svg>` nodes, so large SVG trees aren't repeated
+//! in the virtual DOM and in the DOM.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!icons::register("check", "0 0 24 24", r#"<path d="M9 16.2L4.8 12l-1.4 1.4L9 19 21 7l-1.4-1.4z"/>"#);
+//!icons::register_svg("close", include_str!("../icons/close.svg"));
+//!...
+//!button![icon!("check"), "Save"],
+//!button![icon!("close", "24px", "danger"), "Cancel"],
+//! ```
+
+use super::Namespace;
+use crate::browser::util;
+use crate::virtual_dom::{At, El, Node, Tag};
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+/// The id of the hidden `<svg>` with the registered symbols.
+pub const SPRITE_ID: &str = "seed-icon-sprite";
+/// The prefix of symbol ids - e.g. `seed-icon-check`.
+pub const SYMBOL_ID_PREFIX: &str = "seed-icon-";
+/// The width and height of icons created by `icon!` without a size.
+pub const DEFAULT_SIZE: &str = "1em";
+
+thread_local! {
+    static REGISTERED: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+// ------ Registration ------
+
+/// Register the icon `name` with the SVG `content` (e.g. `<path>`s) and the `view_box`.
+/// The icon is replaced when it's already registered.
+pub fn register(name: &str, view_box: &str, content: &str) {
+    let document = util::document();
+    let symbol_id = symbol_id(name);
+    if let Some(old_symbol) = document.get_element_by_id(&symbol_id) {
+        old_symbol.remove();
+    }
+    let symbol = document
+        .create_element_ns(Some(Namespace::Svg.as_str()), "symbol")
+        .expect("create icon symbol");
+    symbol.set_id(&symbol_id);
+    symbol
+        .set_attribute("viewBox", view_box)
+        .expect("set icon viewBox");
+    symbol.set_inner_html(content);
+    sprite().append_child(&symbol).expect("append icon symbol");
+    REGISTERED.with(|registered| registered.borrow_mut().insert(name.to_owned()));
+}
+
+/// Register the icon `name` from the `<svg>` markup - its `viewBox` and children are used.
+///
+/// # Panics
+///
+/// Panics when `svg` doesn't contain an `<svg>` element.
+pub fn register_svg(name: &str, svg: &str) {
+    let wrapper = util::document()
+        .create_element("div")
+        .expect("create icon wrapper");
+    wrapper.set_inner_html(svg);
+    let svg = wrapper
+        .query_selector("svg")
+        .expect("query svg")
+        .expect("icon markup has to contain an `<svg>` element");
+    register(
+        name,
+        &svg.get_attribute("viewBox").unwrap_or_default(),
+        &svg.inner_html(),
+    );
+}
+
+pub fn is_registered(name: &str) -> bool {
+    REGISTERED.with(|registered| registered.borrow().contains(name))
+}
+
+fn symbol_id(name: &str) -> String {
+    format!("{}{}", SYMBOL_ID_PREFIX, name)
+}
+
+/// The hidden sprite sheet; it's created on the first registration.
+fn sprite() -> web_sys::Element {
+    let document = util::document();
+    if let Some(sprite) = document.get_element_by_id(SPRITE_ID) {
+        return sprite;
+    }
+    let sprite = document
+        .create_element_ns(Some(Namespace::Svg.as_str()), "svg")
+        .expect("create icon sprite");
+    sprite.set_id(SPRITE_ID);
+    for (name, value) in &[
+        ("aria-hidden", "true"),
+        (
+            "style",
+            "position: absolute; width: 0; height: 0; overflow: hidden",
+        ),
+    ] {
+        sprite
+            .set_attribute(name, value)
+            .expect("set icon sprite attribute");
+    }
+    let body = util::body();
+    body.insert_before(&sprite, body.first_child().as_ref())
+        .expect("insert icon sprite");
+    sprite
+}
+
+// ------ Icon ------
+
+/// An `<svg>` referencing the registered icon `name`; see `icon!`.
+/// Icons are decorative (`aria-hidden`) - add a label to the parent element.
+pub fn icon<Ms>(name: &str, size: &str, class: &str) -> Node<Ms> {
+    if cfg!(debug_assertions) && !is_registered(name) {
+        web_sys::console::warn_1(&format!("Icon `{}` isn't registered.", name).into());
+    }
+
+    let mut use_el = El::empty_svg(Tag::Use);
+    use_el.attrs.add(At::Href, format!("#{}", symbol_id(name)));
+
+    let mut svg = El::empty_svg(Tag::Svg);
+    let class = if class.is_empty() {
+        "icon".to_owned()
+    } else {
+        format!("icon {}", class)
+    };
+    svg.attrs.add(At::Class, class);
+    svg.attrs.add(At::Width, size);
+    svg.attrs.add(At::Height, size);
+    svg.attrs.add(At::from("aria-hidden"), "true");
+    svg.attrs.add(At::from("focusable"), "false");
+    svg.children.push(Node::Element(use_el));
+    Node::Element(svg)
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::virtual_dom::AtValue;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn registered_icon_is_referenced() {
+        register_svg(
+            "test-check",
+            r#"<svg viewBox="0 0 24 24"><path d="M9 16.2L4.8 12z"/></svg>"#,
+        );
+        register("test-check", "0 0 16 16", r#"<path d="M1 1z"/>"#);
+        assert!(is_registered("test-check"));
+
+        let symbols = util::document()
+            .query_selector_all(&format!("#{} > #seed-icon-test-check", SPRITE_ID))
+            .unwrap();
+        assert_eq!(symbols.length(), 1);
+        let symbol: web_sys::Element = symbols.get(0).unwrap().unchecked_into();
+        assert_eq!(
+            symbol.get_attribute("viewBox").as_deref(),
+            Some("0 0 16 16")
+        );
+
+        let node: Node<()> = icon!("test-check", "24px", "success");
+        let svg = node.el().unwrap();
+        assert_eq!(
+            svg.attrs.vals.get(&At::Class),
+            Some(&AtValue::Some("icon success".to_owned()))
+        );
+        assert_eq!(
+            svg.select("use")[0].attrs.vals.get(&At::Href),
+            Some(&AtValue::Some("#seed-icon-test-check".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Parsed keyboard keys for `key_ev`.
+
+/// [KeyboardEvent.key](https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/key/Key_Values)
+/// values that apps usually handle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Key {
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    Delete,
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    /// Printable character - e.g. `Key::Char('a')` or `Key::Char(' ')` for the space bar.
+    Char(char),
+    /// Other keys - e.g. `"F1"` or `"Shift"`.
+    Other(String),
+}
+
+impl From<&str> for Key {
+    fn from(key: &str) -> Self {
+        match key {
+            "Enter" => Self::Enter,
+            "Escape" | "Esc" => Self::Escape,
+            "Tab" => Self::Tab,
+            "Backspace" => Self::Backspace,
+            "Delete" | "Del" => Self::Delete,
+            "ArrowUp" | "Up" => Self::ArrowUp,
+            "ArrowDown" | "Down" => Self::ArrowDown,
+            "ArrowLeft" | "Left" => Self::ArrowLeft,
+            "ArrowRight" | "Right" => Self::ArrowRight,
+            "Home" => Self::Home,
+            "End" => Self::End,
+            "PageUp" => Self::PageUp,
+            "PageDown" => Self::PageDown,
+            "Spacebar" => Self::Char(' '),
+            key => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(character), None) => Self::Char(character),
+                    _ => Self::Other(key.to_owned()),
+                }
+            }
+        }
+    }
+}
+
+impl From<&web_sys::KeyboardEvent> for Key {
+    fn from(event: &web_sys::KeyboardEvent) -> Self {
+        Self::from(event.key().as_str())
+    }
+}
+
+/// Modifier keys pressed during a keyboard event.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+impl Modifiers {
+    /// No modifier key is pressed.
+    pub fn is_empty(self) -> bool {
+        self == Self::default()
+    }
+
+    /// `Ctrl` or `Meta` (`Cmd` on macOS) is pressed - use it for shortcuts like `Ctrl+S` / `Cmd+S`.
+    pub fn ctrl_or_meta(self) -> bool {
+        self.ctrl || self.meta
+    }
+}
+
+impl From<&web_sys::KeyboardEvent> for Modifiers {
+    fn from(event: &web_sys::KeyboardEvent) -> Self {
+        Self {
+            shift: event.shift_key(),
+            ctrl: event.ctrl_key(),
+            alt: event.alt_key(),
+            meta: event.meta_key(),
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn parse_keys() {
+        assert_eq!(Key::from("Enter"), Key::Enter);
+        assert_eq!(Key::from("Esc"), Key::Escape);
+        assert_eq!(Key::from("ArrowLeft"), Key::ArrowLeft);
+        assert_eq!(Key::from("a"), Key::Char('a'));
+        assert_eq!(Key::from(" "), Key::Char(' '));
+        assert_eq!(Key::from("ř"), Key::Char('ř'));
+        assert_eq!(Key::from("F1"), Key::Other("F1".to_owned()));
+    }
+
+    #[wasm_bindgen_test]
+    fn modifiers_from_event() {
+        let mut init = web_sys::KeyboardEventInit::new();
+        init.key("s").ctrl_key(true);
+        let event =
+            web_sys::KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init).unwrap();
+
+        let modifiers = Modifiers::from(&event);
+        assert_eq!(Key::from(&event), Key::Char('s'));
+        assert!(modifiers.ctrl_or_meta());
+        assert!(!modifiers.shift && !modifiers.is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+//! Pointer (mouse, pen and touch) data for `pointer_data_ev`.
+
+use wasm_bindgen::JsCast;
+
+/// [PointerEvent.pointerType](https://developer.mozilla.org/en-US/docs/Web/API/PointerEvent/pointerType)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PointerType {
+    Mouse,
+    Pen,
+    Touch,
+    /// Unknown type - an empty string when the browser can't detect the device.
+    Other(String),
+}
+
+impl From<&str> for PointerType {
+    fn from(pointer_type: &str) -> Self {
+        match pointer_type {
+            "mouse" => Self::Mouse,
+            "pen" => Self::Pen,
+            "touch" => Self::Touch,
+            pointer_type => Self::Other(pointer_type.to_owned()),
+        }
+    }
+}
+
+/// Pointer state read from a `web_sys::PointerEvent`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pointer {
+    /// Pass it to `ElRef::set_pointer_capture`.
+    pub id: i32,
+    pub pointer_type: PointerType,
+    pub is_primary: bool,
+    pub client_x: i32,
+    pub client_y: i32,
+    /// Position relative to the target element.
+    pub offset_x: i32,
+    pub offset_y: i32,
+    /// `0.0` - `1.0`; `0.5` for pressed buttons of devices without pressure support.
+    pub pressure: f32,
+    pub tilt_x: i32,
+    pub tilt_y: i32,
+    pub width: i32,
+    pub height: i32,
+    /// [MouseEvent.buttons](https://developer.mozilla.org/en-US/docs/Web/API/MouseEvent/buttons)
+    pub buttons: u16,
+    /// Samples merged by the browser into one `pointermove` event - use them to draw smooth lines.
+    /// It's empty for other events and for samples themselves.
+    pub coalesced: Vec<Pointer>,
+}
+
+impl Pointer {
+    fn without_coalesced(event: &web_sys::PointerEvent) -> Self {
+        Self {
+            id: event.pointer_id(),
+            pointer_type: PointerType::from(event.pointer_type().as_str()),
+            is_primary: event.is_primary(),
+            client_x: event.client_x(),
+            client_y: event.client_y(),
+            offset_x: event.offset_x(),
+            offset_y: event.offset_y(),
+            pressure: event.pressure(),
+            tilt_x: event.tilt_x(),
+            tilt_y: event.tilt_y(),
+            width: event.width(),
+            height: event.height(),
+            buttons: event.buttons(),
+            coalesced: Vec::new(),
+        }
+    }
+}
+
+impl From<&web_sys::PointerEvent> for Pointer {
+    fn from(event: &web_sys::PointerEvent) -> Self {
+        let mut pointer = Self::without_coalesced(event);
+        // `getCoalescedEvents` isn't supported by all browsers.
+        let has_coalesced_events =
+            js_sys::Reflect::has(event, &"getCoalescedEvents".into()).unwrap_or_default();
+        if has_coalesced_events && event.type_() == "pointermove" {
+            pointer.coalesced = event
+                .get_coalesced_events()
+                .iter()
+                .filter_map(|sample| {
+                    sample
+                        .dyn_ref::<web_sys::PointerEvent>()
+                        .map(Self::without_coalesced)
+                })
+                .collect();
+        }
+        pointer
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn pointer_from_event() {
+        let mut init = web_sys::PointerEventInit::new();
+        init.pointer_id(7)
+            .pointer_type("pen")
+            .pressure(0.25)
+            .is_primary(true)
+            .client_x(10)
+            .client_y(20)
+            .buttons(1);
+        let event = web_sys::PointerEvent::new_with_event_init_dict("pointerdown", &init).unwrap();
+
+        let pointer = Pointer::from(&event);
+        assert_eq!(pointer.id, 7);
+        assert_eq!(pointer.pointer_type, PointerType::Pen);
+        assert!(pointer.is_primary);
+        assert_eq!((pointer.client_x, pointer.client_y), (10, 20));
+        assert!((pointer.pressure - 0.25).abs() < f32::EPSILON);
+        assert_eq!(pointer.buttons, 1);
+        assert!(pointer.coalesced.is_empty());
+    }
+}
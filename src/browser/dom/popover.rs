@@ -0,0 +1,247 @@
+//! The [Popover API](https://developer.mozilla.org/en-US/docs/Web/API/Popover_API).
+//!
+//! Tooltips, menus and other lightweight overlays are rendered in the browser's top layer,
+//! so they don't need `z-index` or portals.
+//!
+//! Mark the element by `At::Popover`, open it declaratively from a button by
+//! `At::PopoverTarget` or imperatively through an `ElRef`, and listen to `Ev::BeforeToggle`
+//! or `Ev::Toggle` by `popover_toggle_ev` to keep the `Model` in sync - `Auto` popovers
+//! are closed by the browser on `Esc` and outside clicks.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!button![
+//!    attrs! {At::PopoverTarget => "menu", At::PopoverTargetAction => PopoverTargetAction::Toggle},
+//!    "Menu",
+//!],
+//!div![
+//!    id!("menu"),
+//!    el_ref(&model.menu),
+//!    attrs! {At::Popover => PopoverMode::Auto},
+//!    popover_toggle_ev(Ev::Toggle, |toggle| Msg::MenuToggled(toggle.is_open())),
+//!    ul![...],
+//!],
+//!...
+//!Msg::ItemSelected(item) => {
+//!    model.selected = Some(item);
+//!    popover::hide(&model.menu).expect("hide menu");
+//!}
+//! ```
+
+use crate::virtual_dom::ElRef;
+use std::fmt;
+use wasm_bindgen::{JsCast, JsValue};
+
+mod raw {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        pub type PopoverElement;
+
+        #[wasm_bindgen(method, catch, js_name = showPopover)]
+        pub fn show_popover(this: &PopoverElement) -> Result<(), JsValue>;
+
+        #[wasm_bindgen(method, catch, js_name = hidePopover)]
+        pub fn hide_popover(this: &PopoverElement) -> Result<(), JsValue>;
+
+        #[wasm_bindgen(method, catch, js_name = togglePopover)]
+        pub fn toggle_popover(this: &PopoverElement) -> Result<bool, JsValue>;
+
+        #[wasm_bindgen(method, catch, js_name = togglePopover)]
+        pub fn toggle_popover_with_force(
+            this: &PopoverElement,
+            force: bool,
+        ) -> Result<bool, JsValue>;
+    }
+}
+
+// ------ PopoverMode ------
+
+/// Value of `At::Popover`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PopoverMode {
+    /// Light-dismissed by the browser (`Esc`, click outside); opening it closes other `Auto` popovers.
+    Auto,
+    /// Closed only explicitly - e.g. toasts.
+    Manual,
+}
+
+impl fmt::Display for PopoverMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = match self {
+            Self::Auto => "auto",
+            Self::Manual => "manual",
+        };
+        write!(f, "{}", mode)
+    }
+}
+
+// ------ PopoverTargetAction ------
+
+/// Value of `At::PopoverTargetAction` - what a button with `At::PopoverTarget` does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PopoverTargetAction {
+    Toggle,
+    Show,
+    Hide,
+}
+
+impl fmt::Display for PopoverTargetAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self {
+            Self::Toggle => "toggle",
+            Self::Show => "show",
+            Self::Hide => "hide",
+        };
+        write!(f, "{}", action)
+    }
+}
+
+// ------ Commands ------
+
+/// Whether the browser supports popovers.
+pub fn is_supported() -> bool {
+    let prototype = js_sys::Reflect::get(&js_sys::global(), &JsValue::from("HTMLElement"))
+        .and_then(|html_element| js_sys::Reflect::get(&html_element, &JsValue::from("prototype")));
+    prototype
+        .and_then(|prototype| js_sys::Reflect::has(&prototype, &JsValue::from("showPopover")))
+        .unwrap_or_default()
+}
+
+/// Open the referenced popover.
+///
+/// # Errors
+///
+/// Returns error when the element doesn't exist, it isn't a popover or it's already open.
+pub fn show<E: Clone + JsCast>(el_ref: &ElRef<E>) -> Result<(), JsValue> {
+    popover_element(el_ref)?.show_popover()
+}
+
+/// Close the referenced popover.
+///
+/// # Errors
+///
+/// Returns error when the element doesn't exist or it isn't a popover.
+pub fn hide<E: Clone + JsCast>(el_ref: &ElRef<E>) -> Result<(), JsValue> {
+    popover_element(el_ref)?.hide_popover()
+}
+
+/// Toggle the referenced popover; `force` opens (`Some(true)`) or closes (`Some(false)`) it.
+/// Returns `true` when the popover is open after the call.
+///
+/// # Errors
+///
+/// Returns error when the element doesn't exist or it isn't a popover.
+pub fn toggle<E: Clone + JsCast>(el_ref: &ElRef<E>, force: Option<bool>) -> Result<bool, JsValue> {
+    let element = popover_element(el_ref)?;
+    match force {
+        Some(force) => element.toggle_popover_with_force(force),
+        None => element.toggle_popover(),
+    }
+}
+
+/// Whether the referenced popover is open.
+pub fn is_open<E: Clone + JsCast>(el_ref: &ElRef<E>) -> bool {
+    el_ref
+        .map_type::<web_sys::Element>()
+        .get()
+        .and_then(|element| element.matches(":popover-open").ok())
+        .unwrap_or_default()
+}
+
+fn popover_element<E: Clone + JsCast>(el_ref: &ElRef<E>) -> Result<raw::PopoverElement, JsValue> {
+    el_ref
+        .map_type::<web_sys::HtmlElement>()
+        .get()
+        .map(JsCast::unchecked_into)
+        .ok_or_else(|| JsValue::from("the popover element doesn't exist"))
+}
+
+// ------ PopoverToggle ------
+
+/// `beforetoggle` or `toggle` event of a popover for `popover_toggle_ev`.
+#[derive(Debug, Clone)]
+pub struct PopoverToggle {
+    pub old_state: PopoverState,
+    pub new_state: PopoverState,
+    pub event: web_sys::Event,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PopoverState {
+    Open,
+    Closed,
+}
+
+impl From<&web_sys::Event> for PopoverToggle {
+    fn from(event: &web_sys::Event) -> Self {
+        // `ToggleEvent` isn't in `web_sys` yet.
+        let state = |name: &str| {
+            let state = js_sys::Reflect::get(event, &JsValue::from(name))
+                .ok()
+                .and_then(|state| state.as_string());
+            match state.as_deref() {
+                Some("open") => PopoverState::Open,
+                _ => PopoverState::Closed,
+            }
+        };
+        Self {
+            old_state: state("oldState"),
+            new_state: state("newState"),
+            event: event.clone(),
+        }
+    }
+}
+
+impl PopoverToggle {
+    /// The popover is open (`Ev::Toggle`) or is being opened (`Ev::BeforeToggle`).
+    pub fn is_open(&self) -> bool {
+        self.new_state == PopoverState::Open
+    }
+
+    /// Keep the popover closed - call it in the `Ev::BeforeToggle` handler.
+    /// Closing can't be cancelled.
+    pub fn prevent_default(&self) {
+        self.event.prevent_default();
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::util::{body, document};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn attribute_values() {
+        assert_eq!(PopoverMode::Manual.to_string(), "manual");
+        assert_eq!(PopoverTargetAction::Hide.to_string(), "hide");
+    }
+
+    #[wasm_bindgen_test]
+    fn show_and_hide() {
+        if !is_supported() {
+            return;
+        }
+        let element = document().create_element("div").unwrap();
+        element.set_attribute("popover", "manual").unwrap();
+        body().append_child(&element).unwrap();
+        let mut el_ref = ElRef::<web_sys::HtmlElement>::default();
+        el_ref.shared_node_ws.set(element.clone().into());
+
+        show(&el_ref).unwrap();
+        assert!(is_open(&el_ref));
+        assert_eq!(toggle(&el_ref, None), Ok(false));
+        assert!(!is_open(&el_ref));
+        assert_eq!(toggle(&el_ref, Some(true)), Ok(true));
+        hide(&el_ref).unwrap();
+        assert!(!is_open(&el_ref));
+        element.remove();
+    }
+}
@@ -0,0 +1,532 @@
+//! Responsive images - `srcset` and `sizes` built from typed widths and densities,
+//! `<picture>` sources, native lazy loading and blur-up placeholders.
+//!
+//! [MDN reference](https://developer.mozilla.org/en-US/docs/Learn/HTML/Multimedia_and_embedding/Responsive_images)
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Image::new("photo-800.jpg")
+//!    .alt("Sunset")
+//!    .srcset(SrcSet::widths(|width| format!("photo-{}.jpg", width), &[400, 800, 1600]))
+//!    .sizes(Sizes::new().media("(max-width: 600px)", "100vw").fallback("50vw"))
+//!    .dimensions(800, 600)
+//!    .lazy()
+//!    .view()
+//!...
+//!Picture::new(Image::new("photo.jpg").alt("Sunset"))
+//!    .source(Source::new(SrcSet::new().density("photo.avif", 1.).density("photo@2x.avif", 2.)).mime_type("image/avif"))
+//!    .view()
+//! ```
+//!
+//! ## Blur-up placeholder
+//!
+//! A tiny blurred placeholder is rendered until the full image is loaded. The full image is loaded
+//! when it's about to enter the viewport - observed by `streams::IntersectionStream`.
+//!
+//! ```rust,no_run
+//!// view
+//!Image::new("photo-800.jpg")
+//!    .blur_up("photo-placeholder.jpg", model.photo_loaded)
+//!    .el()
+//!    .with(el_ref(&model.photo))
+//!    .into_node()
+//!
+//!// after render (e.g. in `init` with `orders.after_next_render`)
+//!if let Some(photo) = model.photo.get() {
+//!    model.photo_loader = Some(orders.stream_with_handle(responsive_image::load_when_visible(
+//!        &photo,
+//!        || Msg::PhotoLoaded,
+//!    )));
+//!}
+//! ```
+
+use crate::app::streams::IntersectionStream;
+use crate::virtual_dom::{At, El, Node, St, Tag, UpdateEl};
+use futures::channel::oneshot;
+use futures::future;
+use futures::stream::{Stream, StreamExt};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::IntersectionObserverInit;
+
+/// The attribute with the full image `src` of a blur-up image that hasn't been loaded yet.
+pub const DATA_SRC: &str = "data-seed-src";
+/// The attribute with the full image `srcset` of a blur-up image.
+pub const DATA_SRCSET: &str = "data-seed-srcset";
+/// The attribute with the `sizes` of a blur-up image.
+pub const DATA_SIZES: &str = "data-seed-sizes";
+/// The CSS filter of blur-up placeholders.
+pub const PLACEHOLDER_FILTER: &str = "blur(20px)";
+/// The CSS transition of blur-up images when the full image replaces the placeholder.
+pub const REVEAL_TRANSITION: &str = "filter 0.3s ease-out";
+/// The margin around the viewport where the full images of blur-up images start loading.
+pub const LOAD_MARGIN: &str = "200px";
+
+// ------ Descriptor ------
+
+/// The condition of a `srcset` candidate - e.g. `480w` or `2x`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Descriptor {
+    /// The intrinsic width of the image in pixels.
+    Width(u32),
+    /// The pixel density of the image.
+    Density(f64),
+}
+
+impl fmt::Display for Descriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Width(width) => write!(f, "{}w", width),
+            Self::Density(density) => write!(f, "{}x", density),
+        }
+    }
+}
+
+// ------ SrcSet ------
+
+/// The value of the `srcset` attribute, e.g. `photo-400.jpg 400w, photo-800.jpg 800w`.
+///
+/// Don't mix widths and densities in one `SrcSet` - browsers ignore such candidates.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SrcSet {
+    candidates: Vec<(String, Descriptor)>,
+}
+
+impl SrcSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A candidate for each of the `widths` with the URL created by `url`.
+    pub fn widths(url: impl Fn(u32) -> String, widths: &[u32]) -> Self {
+        widths.iter().fold(Self::new(), |srcset, width| {
+            srcset.width(url(*width), *width)
+        })
+    }
+
+    pub fn width(self, url: impl Into<String>, width: u32) -> Self {
+        self.candidate(url, Descriptor::Width(width))
+    }
+
+    pub fn density(self, url: impl Into<String>, density: f64) -> Self {
+        self.candidate(url, Descriptor::Density(density))
+    }
+
+    pub fn candidate(mut self, url: impl Into<String>, descriptor: Descriptor) -> Self {
+        self.candidates.push((url.into(), descriptor));
+        self
+    }
+
+    pub fn candidates(&self) -> &[(String, Descriptor)] {
+        &self.candidates
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.candidates.is_empty()
+    }
+}
+
+impl fmt::Display for SrcSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, (url, descriptor)) in self.candidates.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} {}", url, descriptor)?;
+        }
+        Ok(())
+    }
+}
+
+// ------ Sizes ------
+
+/// The value of the `sizes` attribute - slot widths with media conditions,
+/// e.g. `(max-width: 600px) 100vw, 50vw`. Use `css_units` functions for lengths.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sizes {
+    conditional: Vec<(String, String)>,
+    fallback: Option<String>,
+}
+
+impl Sizes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The slot `length` when the media `condition` matches; conditions are evaluated in order.
+    pub fn media(mut self, condition: impl Into<String>, length: impl Into<String>) -> Self {
+        self.conditional.push((condition.into(), length.into()));
+        self
+    }
+
+    /// The slot `length` when no condition matches.
+    pub fn fallback(mut self, length: impl Into<String>) -> Self {
+        self.fallback = Some(length.into());
+        self
+    }
+}
+
+impl fmt::Display for Sizes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sizes = self
+            .conditional
+            .iter()
+            .map(|(condition, length)| format!("{} {}", condition, length))
+            .chain(self.fallback.clone())
+            .collect::<Vec<_>>();
+        f.write_str(&sizes.join(", "))
+    }
+}
+
+// ------ Image ------
+
+/// An `<img>` builder.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    src: String,
+    alt: String,
+    srcset: Option<SrcSet>,
+    sizes: Option<Sizes>,
+    dimensions: Option<(u32, u32)>,
+    lazy: bool,
+    blur_up: Option<(String, bool)>,
+}
+
+impl Image {
+    /// An image with the fallback `src` for browsers without `srcset` support.
+    pub fn new(src: impl Into<String>) -> Self {
+        Self {
+            src: src.into(),
+            alt: String::new(),
+            srcset: None,
+            sizes: None,
+            dimensions: None,
+            lazy: false,
+            blur_up: None,
+        }
+    }
+
+    /// The text alternative; the default empty `alt` marks the image as decorative.
+    pub fn alt(mut self, alt: impl Into<String>) -> Self {
+        self.alt = alt.into();
+        self
+    }
+
+    pub fn srcset(mut self, srcset: SrcSet) -> Self {
+        self.srcset = Some(srcset);
+        self
+    }
+
+    pub fn sizes(mut self, sizes: Sizes) -> Self {
+        self.sizes = Some(sizes);
+        self
+    }
+
+    /// The intrinsic `width` and `height` - the browser reserves the space before loading the image.
+    pub const fn dimensions(mut self, width: u32, height: u32) -> Self {
+        self.dimensions = Some((width, height));
+        self
+    }
+
+    /// Native lazy loading - `loading="lazy"` and `decoding="async"`.
+    pub const fn lazy(mut self) -> Self {
+        self.lazy = true;
+        self
+    }
+
+    /// Render the `placeholder` (e.g. a tiny image or a data URL) blurred until `loaded`;
+    /// load the full image by `load_when_visible`.
+    ///
+    /// _Note:_ Browsers load `<picture>` sources regardless of the placeholder,
+    /// so use it only with standalone images.
+    pub fn blur_up(mut self, placeholder: impl Into<String>, loaded: bool) -> Self {
+        self.blur_up = Some((placeholder.into(), loaded));
+        self
+    }
+
+    pub fn el<Ms>(self) -> El<Ms> {
+        let mut el = El::tag(Tag::Img).attr(At::Alt, self.alt);
+        if let Some((width, height)) = self.dimensions {
+            el = el.attr(At::Width, width).attr(At::Height, height);
+        }
+
+        let placeholder = match self.blur_up {
+            Some((placeholder, loaded)) => {
+                el = el.style(St::Transition, REVEAL_TRANSITION);
+                if loaded {
+                    None
+                } else {
+                    Some(placeholder)
+                }
+            }
+            None => None,
+        };
+        if let Some(placeholder) = placeholder {
+            el = el
+                .attr(At::Src, placeholder)
+                .attr(At::from(DATA_SRC), self.src)
+                .style(St::Filter, PLACEHOLDER_FILTER);
+            if let Some(srcset) = self.srcset {
+                el = el.attr(At::from(DATA_SRCSET), srcset);
+            }
+            if let Some(sizes) = self.sizes {
+                el = el.attr(At::from(DATA_SIZES), sizes);
+            }
+            return el;
+        }
+
+        el = el.attr(At::Src, self.src);
+        if let Some(srcset) = self.srcset {
+            el = el.attr(At::SrcSet, srcset);
+        }
+        if let Some(sizes) = self.sizes {
+            el = el.attr(At::Sizes, sizes);
+        }
+        if self.lazy {
+            el = el.attr(At::Loading, "lazy").attr(At::Decoding, "async");
+        }
+        el
+    }
+
+    pub fn view<Ms>(self) -> Node<Ms> {
+        self.el().into_node()
+    }
+}
+
+impl<Ms> UpdateEl<Ms> for Image {
+    fn update_el(self, el: &mut El<Ms>) {
+        el.children.push(self.view())
+    }
+}
+
+// ------ Source ------
+
+/// A `<source>` of `Picture` - e.g. a modern image format or art direction for a media condition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Source {
+    srcset: SrcSet,
+    sizes: Option<Sizes>,
+    media: Option<String>,
+    mime_type: Option<String>,
+}
+
+impl Source {
+    pub fn new(srcset: SrcSet) -> Self {
+        Self {
+            srcset,
+            sizes: None,
+            media: None,
+            mime_type: None,
+        }
+    }
+
+    pub fn sizes(mut self, sizes: Sizes) -> Self {
+        self.sizes = Some(sizes);
+        self
+    }
+
+    /// The media condition, e.g. `(min-width: 800px)`.
+    pub fn media(mut self, media: impl Into<String>) -> Self {
+        self.media = Some(media.into());
+        self
+    }
+
+    /// The MIME type, e.g. `image/avif`; browsers skip sources with unsupported types.
+    pub fn mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    pub fn view<Ms>(self) -> Node<Ms> {
+        let mut el = El::tag(Tag::Source).attr(At::SrcSet, self.srcset);
+        if let Some(sizes) = self.sizes {
+            el = el.attr(At::Sizes, sizes);
+        }
+        if let Some(media) = self.media {
+            el = el.attr(At::Media, media);
+        }
+        if let Some(mime_type) = self.mime_type {
+            el = el.attr(At::Type, mime_type);
+        }
+        el.into_node()
+    }
+}
+
+// ------ Picture ------
+
+/// A `<picture>` builder - the browser uses the first matching source
+/// or the fallback image, which also provides `alt`, dimensions and lazy loading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Picture {
+    sources: Vec<Source>,
+    image: Image,
+}
+
+impl Picture {
+    pub fn new(image: Image) -> Self {
+        Self {
+            sources: Vec::new(),
+            image,
+        }
+    }
+
+    pub fn source(mut self, source: Source) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    pub fn view<Ms>(self) -> Node<Ms> {
+        El::tag(Tag::Picture)
+            .children(self.sources.into_iter().map(Source::view))
+            .child(self.image.view())
+            .into_node()
+    }
+}
+
+impl<Ms> UpdateEl<Ms> for Picture {
+    fn update_el(self, el: &mut El<Ms>) {
+        el.children.push(self.view())
+    }
+}
+
+// ------ Loading ------
+
+/// Load the full image of the blur-up `img` when it's about to enter the viewport
+/// and emit the message created by `handler` once the image is loaded (or has failed to load).
+/// Then render the image with `loaded` set to `true` - the browser takes the image from its cache.
+///
+/// The stream emits only once; an image without a pending full image is reported when it's visible.
+///
+/// # Panics
+///
+/// Panics when the browser doesn't support `IntersectionObserver`.
+pub fn load_when_visible<Ms>(
+    img: &web_sys::Element,
+    handler: impl FnOnce() -> Ms + Clone + 'static,
+) -> impl Stream<Item = Ms> {
+    let src = img.get_attribute(DATA_SRC);
+    let srcset = img.get_attribute(DATA_SRCSET);
+    let sizes = img.get_attribute(DATA_SIZES);
+
+    let mut options = IntersectionObserverInit::new();
+    options.root_margin(LOAD_MARGIN);
+
+    IntersectionStream::new(img, &options)
+        .filter(|entry| future::ready(entry.is_intersecting()))
+        .take(1)
+        .then(move |_| {
+            let (src, srcset, sizes) = (src.clone(), srcset.clone(), sizes.clone());
+            async move {
+                if let Some(src) = src {
+                    preload(&src, srcset.as_deref(), sizes.as_deref()).await
+                }
+            }
+        })
+        .map(move |()| handler.clone()())
+}
+
+/// Load the image into the browser cache.
+async fn preload(src: &str, srcset: Option<&str>, sizes: Option<&str>) {
+    let (sender, receiver) = oneshot::channel();
+    let sender = Rc::new(RefCell::new(Some(sender)));
+    let on_done = Closure::wrap(Box::new(move || {
+        if let Some(sender) = sender.borrow_mut().take() {
+            // The receiver has been dropped only when the stream has been dropped.
+            let _ = sender.send(());
+        }
+    }) as Box<dyn FnMut()>);
+
+    let image = web_sys::HtmlImageElement::new().expect("create image");
+    image.set_onload(Some(on_done.as_ref().unchecked_ref()));
+    image.set_onerror(Some(on_done.as_ref().unchecked_ref()));
+    // `sizes` and `srcset` have to be set before `src` to load the right candidate.
+    if let Some(sizes) = sizes {
+        image.set_sizes(sizes);
+    }
+    if let Some(srcset) = srcset {
+        image.set_srcset(srcset);
+    }
+    image.set_src(src);
+
+    let _ = receiver.await;
+    image.set_onload(None);
+    image.set_onerror(None);
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::virtual_dom::AtValue;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn attr(el: &El<()>, at: impl Into<At>) -> Option<String> {
+        match el.attrs.vals.get(&at.into()) {
+            Some(AtValue::Some(value)) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn srcset_and_sizes() {
+        let srcset = SrcSet::widths(|width| format!("a-{}.jpg", width), &[400, 800]);
+        assert_eq!(srcset.to_string(), "a-400.jpg 400w, a-800.jpg 800w");
+        let srcset = SrcSet::new().density("a.jpg", 1.).density("a@2x.jpg", 2.5);
+        assert_eq!(srcset.to_string(), "a.jpg 1x, a@2x.jpg 2.5x");
+
+        let sizes = Sizes::new()
+            .media("(max-width: 600px)", "100vw")
+            .fallback("50vw");
+        assert_eq!(sizes.to_string(), "(max-width: 600px) 100vw, 50vw");
+    }
+
+    #[wasm_bindgen_test]
+    fn lazy_image_and_picture() {
+        let image = Image::new("a.jpg")
+            .alt("A")
+            .srcset(SrcSet::new().width("a-400.jpg", 400))
+            .sizes(Sizes::new().fallback("100vw"))
+            .dimensions(400, 300)
+            .lazy();
+        let el = image.clone().el::<()>();
+        assert_eq!(attr(&el, At::Src).as_deref(), Some("a.jpg"));
+        assert_eq!(attr(&el, At::SrcSet).as_deref(), Some("a-400.jpg 400w"));
+        assert_eq!(attr(&el, At::Sizes).as_deref(), Some("100vw"));
+        assert_eq!(attr(&el, At::Width).as_deref(), Some("400"));
+        assert_eq!(attr(&el, At::Loading).as_deref(), Some("lazy"));
+
+        let node: Node<()> = Picture::new(image)
+            .source(Source::new(SrcSet::new().density("a.avif", 1.)).mime_type("image/avif"))
+            .view();
+        let picture = node.el().unwrap();
+        let source = picture.children[0].el().unwrap();
+        assert_eq!(attr(source, At::Type).as_deref(), Some("image/avif"));
+        assert_eq!(picture.children[1].el().unwrap().tag, Tag::Img);
+    }
+
+    #[wasm_bindgen_test]
+    fn blur_up_placeholder() {
+        let image = Image::new("a.jpg")
+            .srcset(SrcSet::new().width("a-400.jpg", 400))
+            .lazy();
+        let el = image.clone().blur_up("tiny.jpg", false).el::<()>();
+        assert_eq!(attr(&el, At::Src).as_deref(), Some("tiny.jpg"));
+        assert_eq!(attr(&el, DATA_SRC).as_deref(), Some("a.jpg"));
+        assert_eq!(attr(&el, DATA_SRCSET).as_deref(), Some("a-400.jpg 400w"));
+        assert_eq!(attr(&el, At::SrcSet), None);
+        assert_eq!(attr(&el, At::Loading), None);
+        assert!(el.style.to_string().contains(PLACEHOLDER_FILTER));
+
+        let el = image.blur_up("tiny.jpg", true).el::<()>();
+        assert_eq!(attr(&el, At::Src).as_deref(), Some("a.jpg"));
+        assert_eq!(attr(&el, DATA_SRC), None);
+        assert!(!el.style.to_string().contains(PLACEHOLDER_FILTER));
+    }
+}
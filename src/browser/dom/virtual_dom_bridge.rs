@@ -2,11 +2,29 @@
 
 use super::Namespace;
 use crate::virtual_dom::{At, AtValue, Attrs, El, Mailbox, Node, Style, Text};
+use std::cell::Cell;
 use wasm_bindgen::JsCast;
 use web_sys::Document;
 
+thread_local! {
+    /// The number of DOM operations since the last `take_patch_count` call.
+    static PATCH_COUNT: Cell<usize> = Cell::new(0);
+}
+
+/// Count a DOM operation - an inserted, removed or replaced node,
+/// or a changed attribute or text.
+pub(crate) fn count_patch() {
+    PATCH_COUNT.with(|count| count.set(count.get() + 1));
+}
+
+/// The number of DOM operations since the last call.
+pub(crate) fn take_patch_count() -> usize {
+    PATCH_COUNT.with(|count| count.replace(0))
+}
+
 /// Convenience function to reduce repetition
 fn set_style(el_ws: &web_sys::Node, style: &Style) {
+    count_patch();
     el_ws
         .dyn_ref::<web_sys::Element>()
         .expect("Problem casting Node as Element while setting style")
@@ -54,6 +72,7 @@ fn node_to_element(el_ws: &web_sys::Node) -> Result<&web_sys::Element, &'static
 }
 
 fn set_attr_value(el_ws: &web_sys::Node, at: &At, at_value: &AtValue) {
+    count_patch();
     match at_value {
         AtValue::Some(value) => {
             node_to_element(el_ws)
@@ -133,6 +152,7 @@ pub(crate) fn make_websys_el<Ms>(el: &mut El<Ms>, document: &web_sys::Document)
 /// Similar to `attach_el_and_children`, but for text nodes
 pub fn attach_text_node(text: &mut Text, parent: &web_sys::Node) {
     let node_ws = text.node_ws.take().expect("Missing websys node for Text");
+    count_patch();
     parent
         .append_child(&node_ws)
         .expect("Problem appending text node");
@@ -171,6 +191,7 @@ pub fn attach_el_and_children<Ms>(el: &mut El<Ms>, parent: &web_sys::Node, mailb
     // Append the element
 
     // todo: This error can occur with raw html elements, but am unsure of the cause.
+    count_patch();
     if parent.append_child(el_ws).is_err() {
         crate::error("Minor problem with html element (append)");
     }
@@ -266,6 +287,7 @@ pub fn patch_el_details<Ms>(
     for name in old.attrs.vals.keys() {
         if new.attrs.vals.get(name).is_none() {
             // todo get to the bottom of this
+            count_patch();
             match old_el_ws.dyn_ref::<web_sys::Element>() {
                 Some(el) => el
                     .remove_attribute(name.as_str())
@@ -465,6 +487,7 @@ pub(crate) fn insert_node(
     parent: &web_sys::Node,
     next: Option<web_sys::Node>,
 ) {
+    count_patch();
     match next {
         Some(n) => {
             parent
@@ -478,12 +501,14 @@ pub(crate) fn insert_node(
 }
 
 pub(crate) fn remove_node(node: &web_sys::Node, parent: &web_sys::Node) {
+    count_patch();
     parent
         .remove_child(node)
         .expect("Problem removing old el_ws when updating to empty");
 }
 
 pub(crate) fn replace_child(new: &web_sys::Node, old: &web_sys::Node, parent: &web_sys::Node) {
+    count_patch();
     parent
         .replace_child(new, old)
         .expect("Problem replacing element");
@@ -0,0 +1,418 @@
+//! [Web MIDI](https://developer.mozilla.org/en-US/docs/Web/API/Web_MIDI_API) support
+//! for music tools - receive messages from MIDI keyboards and controllers and send them to synths.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!fn init(_: Url, orders: &mut impl Orders<Msg>) -> Model {
+//!    orders.perform_cmd(async { Msg::MidiAccess(midi::request_access(false).await) });
+//!    Model::default()
+//!}
+//!
+//!fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+//!    match msg {
+//!        Msg::MidiAccess(Ok(access)) => {
+//!            model.midi_messages = Some(orders.stream_with_handle(access.messages().map(Msg::Midi)));
+//!            model.synth = access.outputs().into_iter().next();
+//!        }
+//!        Msg::Midi(message) => {
+//!            if let (MidiMessageKind::NoteOn { .. }, Some(synth)) = (message.kind(), &model.synth) {
+//!                orders.perform_cmd(synth.send(&message.data, Msg::Sent));
+//!            }
+//!        }
+//!        ...
+//!    }
+//!}
+//! ```
+
+use super::util::window;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::future::Future;
+use futures::stream::Stream;
+use std::cell::RefCell;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::{fmt, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// Web MIDI result.
+pub type Result<T> = std::result::Result<T, MidiError>;
+
+// ------ MidiError ------
+
+#[derive(Debug, Clone)]
+pub enum MidiError {
+    /// The browser doesn't support Web MIDI.
+    Unsupported,
+    /// The user or a permission policy has denied the access.
+    PermissionDenied,
+    /// The message is invalid or the port is disconnected.
+    SendError(JsValue),
+}
+
+// ------ request_access ------
+
+/// Ask the user for the access to MIDI devices.
+/// `sysex` enables System Exclusive messages - browsers display a stronger warning for them.
+///
+/// # Errors
+///
+/// Returns `MidiError::Unsupported` or `MidiError::PermissionDenied`.
+pub async fn request_access(sysex: bool) -> Result<MidiAccess> {
+    let navigator = window().navigator();
+    if !js_sys::Reflect::has(&navigator, &"requestMIDIAccess".into()).unwrap_or_default() {
+        return Err(MidiError::Unsupported);
+    }
+    let mut options = web_sys::MidiOptions::new();
+    options.sysex(sysex);
+    let promise = navigator
+        .request_midi_access_with_options(&options)
+        .map_err(|_| MidiError::Unsupported)?;
+    let access = JsFuture::from(promise)
+        .await
+        .map_err(|_| MidiError::PermissionDenied)?;
+    Ok(MidiAccess(access.unchecked_into()))
+}
+
+// ------ MidiAccess ------
+
+/// Access to MIDI devices. Cloning is cheap.
+#[derive(Clone)]
+pub struct MidiAccess(web_sys::MidiAccess);
+
+impl fmt::Debug for MidiAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MidiAccess")
+    }
+}
+
+impl MidiAccess {
+    /// Connected input devices.
+    pub fn inputs(&self) -> Vec<MidiPortInfo> {
+        self.raw_inputs()
+            .iter()
+            .map(|input| MidiPortInfo::from(input.as_ref()))
+            .collect()
+    }
+
+    /// Connected output devices.
+    pub fn outputs(&self) -> Vec<MidiOutput> {
+        self.0
+            .outputs()
+            .values()
+            .into_iter()
+            .filter_map(|output| output.ok()?.dyn_into().ok())
+            .map(MidiOutput)
+            .collect()
+    }
+
+    /// Stream messages from all inputs, including devices connected later.
+    pub fn messages(&self) -> MidiMessageStream {
+        MidiMessageStream::new(self)
+    }
+
+    fn raw_inputs(&self) -> Vec<web_sys::MidiInput> {
+        self.0
+            .inputs()
+            .values()
+            .into_iter()
+            .filter_map(|input| input.ok()?.dyn_into().ok())
+            .collect()
+    }
+}
+
+// ------ MidiPortInfo ------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MidiPortInfo {
+    /// Unique and persistent port identifier.
+    pub id: String,
+    pub name: Option<String>,
+    pub manufacturer: Option<String>,
+}
+
+impl From<&web_sys::MidiPort> for MidiPortInfo {
+    fn from(port: &web_sys::MidiPort) -> Self {
+        Self {
+            id: port.id(),
+            name: port.name(),
+            manufacturer: port.manufacturer(),
+        }
+    }
+}
+
+// ------ MidiOutput ------
+
+/// Output device, e.g. a synth.
+#[derive(Clone)]
+pub struct MidiOutput(web_sys::MidiOutput);
+
+impl fmt::Debug for MidiOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MidiOutput").field(&self.info()).finish()
+    }
+}
+
+impl MidiOutput {
+    pub fn info(&self) -> MidiPortInfo {
+        MidiPortInfo::from(self.0.as_ref())
+    }
+
+    /// Send the message (e.g. `[0x90, 60, 127]` - note on, middle C, full velocity).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///orders.perform_cmd(synth.send(&[0x90, 60, 127], Msg::Sent));
+    /// ```
+    pub fn send<Ms>(
+        &self,
+        data: &[u8],
+        handler: impl FnOnce(Result<()>) -> Ms + Clone + 'static,
+    ) -> impl Future<Output = Ms> {
+        let (output, data) = (self.0.clone(), js_sys::Uint8Array::from(data));
+        async move { handler(output.send(&data).map_err(MidiError::SendError)) }
+    }
+}
+
+// ------ MidiMessage ------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MidiMessage {
+    /// `MidiPortInfo::id` of the input.
+    pub port_id: String,
+    /// Raw message bytes.
+    pub data: Vec<u8>,
+    /// `performance.now()` timestamp in milliseconds.
+    pub timestamp: f64,
+}
+
+impl MidiMessage {
+    /// Parse channel voice messages.
+    pub fn kind(&self) -> MidiMessageKind {
+        match *self.data.as_slice() {
+            // Note on with zero velocity means note off.
+            [status, note, 0] if status & 0xF0 == 0x90 => MidiMessageKind::NoteOff {
+                channel: status & 0x0F,
+                note,
+                velocity: 0,
+            },
+            [status, note, velocity] if status & 0xF0 == 0x90 => MidiMessageKind::NoteOn {
+                channel: status & 0x0F,
+                note,
+                velocity,
+            },
+            [status, note, velocity] if status & 0xF0 == 0x80 => MidiMessageKind::NoteOff {
+                channel: status & 0x0F,
+                note,
+                velocity,
+            },
+            [status, controller, value] if status & 0xF0 == 0xB0 => {
+                MidiMessageKind::ControlChange {
+                    channel: status & 0x0F,
+                    controller,
+                    value,
+                }
+            }
+            [status, lsb, msb] if status & 0xF0 == 0xE0 => MidiMessageKind::PitchBend {
+                channel: status & 0x0F,
+                value: (u16::from(msb) << 7) | u16::from(lsb),
+            },
+            _ => MidiMessageKind::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MidiMessageKind {
+    NoteOn {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    NoteOff {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+    },
+    /// `value` is `0` - `16383`, `8192` is the center.
+    PitchBend {
+        channel: u8,
+        value: u16,
+    },
+    /// System, aftertouch, program change and other messages - read `MidiMessage::data`.
+    Other,
+}
+
+// ------ MidiMessageStream ------
+
+type Callback = Closure<dyn Fn(JsValue)>;
+
+struct Inputs {
+    inputs: Vec<web_sys::MidiInput>,
+    on_message: Callback,
+}
+
+impl Inputs {
+    fn listen(&mut self, input: web_sys::MidiInput) {
+        if self
+            .inputs
+            .iter()
+            .any(|listened| listened.id() == input.id())
+        {
+            return;
+        }
+        // Adding the listener opens the port.
+        input
+            .add_event_listener_with_callback(
+                "midimessage",
+                self.on_message.as_ref().unchecked_ref(),
+            )
+            .expect("add `midimessage` listener");
+        self.inputs.push(input);
+    }
+}
+
+/// Stream of `MidiMessage`s from all inputs, created by `MidiAccess::messages`.
+pub struct MidiMessageStream {
+    access: web_sys::MidiAccess,
+    inputs: Rc<RefCell<Inputs>>,
+    on_state_change: Callback,
+    receiver: UnboundedReceiver<MidiMessage>,
+}
+
+impl MidiMessageStream {
+    fn new(access: &MidiAccess) -> Self {
+        let (sender, receiver) = unbounded();
+        let inputs = Rc::new(RefCell::new(Inputs {
+            inputs: Vec::new(),
+            on_message: on_message(sender),
+        }));
+        for input in access.raw_inputs() {
+            inputs.borrow_mut().listen(input);
+        }
+
+        let on_state_change = Closure::wrap(Box::new({
+            let inputs = Rc::clone(&inputs);
+            move |event: JsValue| {
+                let port = event
+                    .unchecked_into::<web_sys::MidiConnectionEvent>()
+                    .port();
+                if let Some(input) = port.and_then(|port| port.dyn_into().ok()) {
+                    inputs.borrow_mut().listen(input);
+                }
+            }
+        }) as Box<dyn Fn(JsValue)>);
+        access
+            .0
+            .add_event_listener_with_callback(
+                "statechange",
+                on_state_change.as_ref().unchecked_ref(),
+            )
+            .expect("add `statechange` listener");
+
+        Self {
+            access: access.0.clone(),
+            inputs,
+            on_state_change,
+            receiver,
+        }
+    }
+}
+
+fn on_message(sender: UnboundedSender<MidiMessage>) -> Callback {
+    Closure::wrap(Box::new(move |event: JsValue| {
+        let event = event.unchecked_into::<web_sys::MidiMessageEvent>();
+        let port_id = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::MidiPort>().ok())
+            .map(|port| port.id())
+            .unwrap_or_default();
+        let message = MidiMessage {
+            port_id,
+            data: event.data().unwrap_or_default(),
+            timestamp: event.time_stamp(),
+        };
+        sender.unbounded_send(message).ok();
+    }) as Box<dyn Fn(JsValue)>)
+}
+
+impl Stream for MidiMessageStream {
+    type Item = MidiMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        Stream::poll_next(Pin::new(&mut self.receiver), cx)
+    }
+}
+
+impl Drop for MidiMessageStream {
+    fn drop(&mut self) {
+        self.access
+            .remove_event_listener_with_callback(
+                "statechange",
+                self.on_state_change.as_ref().unchecked_ref(),
+            )
+            .expect("remove `statechange` listener");
+        let inputs = self.inputs.borrow();
+        for input in &inputs.inputs {
+            input
+                .remove_event_listener_with_callback(
+                    "midimessage",
+                    inputs.on_message.as_ref().unchecked_ref(),
+                )
+                .expect("remove `midimessage` listener");
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn message(data: &[u8]) -> MidiMessage {
+        MidiMessage {
+            port_id: "keyboard".to_owned(),
+            data: data.to_vec(),
+            timestamp: 0.,
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn parse_message_kind() {
+        assert_eq!(
+            message(&[0x91, 60, 100]).kind(),
+            MidiMessageKind::NoteOn {
+                channel: 1,
+                note: 60,
+                velocity: 100
+            }
+        );
+        assert_eq!(
+            message(&[0x90, 60, 0]).kind(),
+            MidiMessageKind::NoteOff {
+                channel: 0,
+                note: 60,
+                velocity: 0
+            }
+        );
+        assert_eq!(
+            message(&[0xE0, 0, 0x40]).kind(),
+            MidiMessageKind::PitchBend {
+                channel: 0,
+                value: 8192
+            }
+        );
+        assert_eq!(message(&[0xF8]).kind(), MidiMessageKind::Other);
+    }
+}
@@ -0,0 +1,376 @@
+//! Render to a canvas from a dedicated worker by means of
+//! [`OffscreenCanvas`](https://developer.mozilla.org/en-US/docs/Web/API/OffscreenCanvas).
+//!
+//! The app transfers the canvas to the worker by `CanvasWorker::start` and controls it
+//! by typed messages; the worker receives the canvas by `on_canvas` and draws it
+//! in `WorkerCanvas::run` - the main thread stays free for the UI.
+//!
+//! Messages are serialized with `serde`, like `SharedWorker` messages.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!// In the app.
+//!Msg::CanvasRendered => {
+//!    let worker = CanvasWorker::<Command, Event>::start(&model.canvas, "/chart_worker.js")
+//!        .expect("start canvas worker");
+//!    orders.stream(worker.events().map(Msg::Chart));
+//!    model.chart = Some(worker);
+//!}
+//!Msg::DataLoaded(points) => {
+//!    model.chart.as_ref().map(|chart| chart.send(&Command::Points(points)));
+//!}
+//!
+//!// In the worker - `/chart_worker.js` loads this Wasm module.
+//!#[wasm_bindgen(start)]
+//!pub fn start() {
+//!    offscreen_canvas::on_canvas(|canvas: WorkerCanvas<Event, Command>| {
+//!        let context = canvas.context_2d().expect("2d context");
+//!        let mut points = Vec::new();
+//!        canvas.run(move |canvas, frame| {
+//!            for command in frame.messages.into_iter().filter_map(Result::ok) {
+//!                let Command::Points(new_points) = command;
+//!                points = new_points;
+//!            }
+//!            draw_chart(&context, canvas.canvas(), &points);
+//!        });
+//!    });
+//!}
+//! ```
+
+use crate::app::streams::EventStream;
+use crate::browser::shared_worker::{self, Result, WorkerError, WorkerEvent};
+use crate::virtual_dom::ElRef;
+use futures::stream::{self, Stream, StreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::{fmt, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+const KIND: &str = "seedCanvas";
+const KIND_INIT: &str = "init";
+const KIND_MESSAGE: &str = "message";
+const KIND_RESIZE: &str = "resize";
+
+/// Control message `{seedCanvas: kind, ..fields}` from `CanvasWorker` to `WorkerCanvas`.
+fn envelope(kind: &str, fields: &[(&str, &JsValue)]) -> JsValue {
+    let envelope = js_sys::Object::new();
+    // `Reflect::set` can't fail on a plain object.
+    js_sys::Reflect::set(&envelope, &KIND.into(), &kind.into()).ok();
+    for (key, value) in fields {
+        js_sys::Reflect::set(&envelope, &JsValue::from(*key), value).ok();
+    }
+    envelope.into()
+}
+
+fn field(envelope: &JsValue, key: &str) -> JsValue {
+    js_sys::Reflect::get(envelope, &key.into()).unwrap_or(JsValue::UNDEFINED)
+}
+
+// ------ CanvasWorker ------
+
+/// The app side - a dedicated worker drawing on the transferred canvas. It sends `Out` messages
+/// and receives `In` messages.
+///
+/// The worker is terminated when `CanvasWorker` is dropped.
+pub struct CanvasWorker<Out, In> {
+    worker: web_sys::Worker,
+    message_types: PhantomData<fn(Out) -> In>,
+}
+
+impl<Out, In> fmt::Debug for CanvasWorker<Out, In> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CanvasWorker").finish()
+    }
+}
+
+impl<Out, In> Drop for CanvasWorker<Out, In> {
+    fn drop(&mut self) {
+        self.worker.terminate();
+    }
+}
+
+impl<Out: Serialize, In: DeserializeOwned> CanvasWorker<Out, In> {
+    /// Start the worker script `script_url` and transfer the referenced canvas to it.
+    ///
+    /// The canvas can be transferred only once - keep its element stable
+    /// (e.g. by `el_key`) so Seed doesn't replace it, and don't set its size attributes
+    /// after the transfer; use `resize`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WorkerError::ConnectError` when the canvas doesn't exist or has been
+    /// already transferred, or the worker can't be started.
+    pub fn start<E: Clone + JsCast>(canvas: &ElRef<E>, script_url: &str) -> Result<Self> {
+        let canvas = canvas
+            .map_type::<web_sys::HtmlCanvasElement>()
+            .get()
+            .ok_or_else(|| WorkerError::ConnectError("the canvas element doesn't exist".into()))?;
+        let offscreen = canvas
+            .transfer_control_to_offscreen()
+            .map_err(WorkerError::ConnectError)?;
+        let worker = web_sys::Worker::new(script_url).map_err(WorkerError::ConnectError)?;
+        let init = envelope(KIND_INIT, &[("canvas", &offscreen)]);
+        worker
+            .post_message_with_transfer(&init, &js_sys::Array::of1(&offscreen))
+            .map_err(WorkerError::ConnectError)?;
+        Ok(Self {
+            worker,
+            message_types: PhantomData,
+        })
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when serialization fails or the message can't be posted.
+    pub fn send(&self, message: &Out) -> Result<()> {
+        let message = envelope(
+            KIND_MESSAGE,
+            &[("data", &shared_worker::serialize(message)?)],
+        );
+        self.worker
+            .post_message(&message)
+            .map_err(WorkerError::SendError)
+    }
+
+    /// Change the canvas size in pixels - e.g. on window resize. `Frame::resized` is `true`
+    /// in the next frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when the message can't be posted.
+    pub fn resize(&self, width: u32, height: u32) -> Result<()> {
+        let message = envelope(
+            KIND_RESIZE,
+            &[("width", &width.into()), ("height", &height.into())],
+        );
+        self.worker
+            .post_message(&message)
+            .map_err(WorkerError::SendError)
+    }
+
+    /// Stream of messages sent by `WorkerCanvas::send` and worker errors.
+    pub fn events(&self) -> impl Stream<Item = WorkerEvent<In>> {
+        let target: &web_sys::EventTarget = self.worker.as_ref();
+        let messages = EventStream::<web_sys::MessageEvent>::new(target, "message").map(|event| {
+            shared_worker::deserialize(&event.data())
+                .map_or_else(WorkerEvent::InvalidMessage, WorkerEvent::Message)
+        });
+        let errors =
+            EventStream::<web_sys::Event>::new(target, "error").map(|_| WorkerEvent::Error);
+        stream::select(messages, errors)
+    }
+
+    pub const fn raw(&self) -> &web_sys::Worker {
+        &self.worker
+    }
+}
+
+// ------ on_canvas ------
+
+type Start = Box<dyn FnOnce(web_sys::OffscreenCanvas)>;
+
+#[derive(Default)]
+struct Inbox {
+    messages: VecDeque<JsValue>,
+    /// The latest size from `CanvasWorker::resize` - it's applied when the next frame starts.
+    size: Option<(u32, u32)>,
+}
+
+thread_local! {
+    static INBOX: Rc<RefCell<Inbox>> = Rc::new(RefCell::new(Inbox::default()));
+}
+
+fn scope() -> web_sys::DedicatedWorkerGlobalScope {
+    js_sys::global().unchecked_into()
+}
+
+/// The worker side - call `start` once the canvas transferred by `CanvasWorker::start` arrives.
+/// Call it in the worker's entry point.
+///
+/// `WorkerCanvas` sends `Out` messages and receives `In` messages.
+pub fn on_canvas<Out, In>(start: impl FnOnce(WorkerCanvas<Out, In>) + 'static)
+where
+    Out: Serialize + 'static,
+    In: DeserializeOwned + 'static,
+{
+    let start = RefCell::new(Some(Box::new(move |offscreen| {
+        start(WorkerCanvas {
+            canvas: offscreen,
+            inbox: INBOX.with(Rc::clone),
+            message_types: PhantomData,
+        });
+    }) as Start));
+    let on_message = Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
+        let data = event.data();
+        let kind = field(&data, KIND).as_string().unwrap_or_default();
+        match kind.as_str() {
+            KIND_INIT => {
+                if let Some(start) = start.borrow_mut().take() {
+                    start(field(&data, "canvas").unchecked_into());
+                }
+            }
+            KIND_MESSAGE => INBOX.with(|inbox| {
+                inbox.borrow_mut().messages.push_back(field(&data, "data"));
+            }),
+            KIND_RESIZE => {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let dimension = |key| field(&data, key).as_f64().unwrap_or_default() as u32;
+                let size = (dimension("width"), dimension("height"));
+                INBOX.with(|inbox| inbox.borrow_mut().size = Some(size));
+            }
+            _ => (),
+        }
+    }) as Box<dyn FnMut(web_sys::MessageEvent)>);
+    scope().set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    // The handler lives as long as the worker.
+    on_message.forget();
+}
+
+// ------ WorkerCanvas ------
+
+/// One animation frame of `WorkerCanvas::run`.
+#[derive(Debug)]
+pub struct Frame<In> {
+    /// `requestAnimationFrame` timestamp in milliseconds.
+    pub timestamp: f64,
+    /// Messages sent by `CanvasWorker::send` since the previous frame.
+    pub messages: Vec<Result<In>>,
+    /// The canvas has been resized by `CanvasWorker::resize` since the previous frame.
+    pub resized: bool,
+}
+
+/// The canvas transferred to the worker. See `on_canvas`.
+pub struct WorkerCanvas<Out, In> {
+    canvas: web_sys::OffscreenCanvas,
+    inbox: Rc<RefCell<Inbox>>,
+    message_types: PhantomData<fn(Out) -> In>,
+}
+
+impl<Out, In> fmt::Debug for WorkerCanvas<Out, In> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorkerCanvas")
+            .field("width", &self.canvas.width())
+            .field("height", &self.canvas.height())
+            .finish()
+    }
+}
+
+impl<Out: Serialize + 'static, In: DeserializeOwned + 'static> WorkerCanvas<Out, In> {
+    pub const fn canvas(&self) -> &web_sys::OffscreenCanvas {
+        &self.canvas
+    }
+
+    /// The canvas 2D context; `None` when the canvas has already another context type.
+    pub fn context_2d(&self) -> Option<web_sys::OffscreenCanvasRenderingContext2d> {
+        self.canvas
+            .get_context("2d")
+            .ok()
+            .flatten()
+            .map(JsCast::unchecked_into)
+    }
+
+    /// Send `message` to the app - see `CanvasWorker::events`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when serialization fails or the message can't be posted.
+    pub fn send(&self, message: &Out) -> Result<()> {
+        scope()
+            .post_message(&shared_worker::serialize(message)?)
+            .map_err(WorkerError::SendError)
+    }
+
+    /// Call `draw` in every animation frame, with the messages received since the previous one.
+    pub fn run(self, mut draw: impl FnMut(&Self, Frame<In>) + 'static) {
+        let frame_callback = Rc::new(RefCell::new(None::<Closure<dyn FnMut(f64)>>));
+        let frame_callback_clone = Rc::clone(&frame_callback);
+        *frame_callback.borrow_mut() = Some(Closure::wrap(Box::new(move |timestamp| {
+            let (messages, size) = {
+                let mut inbox = self.inbox.borrow_mut();
+                let messages = inbox.messages.drain(..).collect::<Vec<_>>();
+                (messages, inbox.size.take())
+            };
+            if let Some((width, height)) = size {
+                self.canvas.set_width(width);
+                self.canvas.set_height(height);
+            }
+            let frame = Frame {
+                timestamp,
+                messages: messages.iter().map(shared_worker::deserialize).collect(),
+                resized: size.is_some(),
+            };
+            draw(&self, frame);
+            if let Some(callback) = frame_callback_clone.borrow().as_ref() {
+                request_frame(callback);
+            }
+        }) as Box<dyn FnMut(f64)>));
+        // The loop holds itself - it runs as long as the worker.
+        let frame_callback = frame_callback.borrow();
+        if let Some(callback) = frame_callback.as_ref() {
+            request_frame(callback);
+        }
+    }
+}
+
+fn request_frame(callback: &Closure<dyn FnMut(f64)>) {
+    scope()
+        .request_animation_frame(callback.as_ref().unchecked_ref())
+        .expect("request animation frame in worker");
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::util::document;
+    use serde::Deserialize;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Size {
+        width: u32,
+    }
+
+    fn size_worker_url() -> String {
+        let script = "onmessage = event => { \
+                      const data = event.data; \
+                      if (data.seedCanvas === 'init') postMessage({ width: data.canvas.width }); \
+                      if (data.seedCanvas === 'resize') postMessage({ width: data.width }); \
+                      };";
+        let parts = js_sys::Array::of1(&JsValue::from(script));
+        let mut options = web_sys::BlobPropertyBag::new();
+        options.type_("text/javascript");
+        let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options).unwrap();
+        web_sys::Url::create_object_url_with_blob(&blob).unwrap()
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn transfer_and_resize() {
+        let canvas = document()
+            .create_element("canvas")
+            .unwrap()
+            .unchecked_into::<web_sys::HtmlCanvasElement>();
+        canvas.set_width(40);
+        let mut canvas_ref = ElRef::<web_sys::HtmlCanvasElement>::default();
+        canvas_ref.shared_node_ws.set(canvas.into());
+
+        let worker = CanvasWorker::<(), Size>::start(&canvas_ref, &size_worker_url()).unwrap();
+        let mut events = Box::pin(worker.events());
+        match events.next().await {
+            Some(WorkerEvent::Message(size)) => assert_eq!(size, Size { width: 40 }),
+            event => panic!("unexpected event {:?}", event),
+        }
+
+        worker.resize(80, 20).unwrap();
+        match events.next().await {
+            Some(WorkerEvent::Message(size)) => assert_eq!(size, Size { width: 80 }),
+            event => panic!("unexpected event {:?}", event),
+        }
+    }
+}
@@ -0,0 +1,379 @@
+//! Typed connection to a popup window opened by your app (OAuth flows, detached tool windows)
+//! or to the window which has opened your app.
+//!
+//! Messages are serialized with `serde` and exchanged by `postMessage`. Only messages
+//! from the connected window and its expected origin are received; messages are sent
+//! only when the window is still at that origin.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::SignIn => {
+//!    // Open it in the click handler, otherwise the browser blocks it.
+//!    let popup = Popup::<AuthCommand, AuthEvent>::open(
+//!        "https://auth.example.com/authorize",
+//!        &PopupOptions::default().size(480, 640),
+//!    );
+//!    match popup {
+//!        Ok(popup) => {
+//!            orders.stream(popup.events().map(Msg::Auth));
+//!            model.popup = Some(popup);
+//!        }
+//!        Err(PopupError::Blocked) => model.show_popup_hint = true,
+//!        Err(error) => log!(error),
+//!    }
+//!}
+//!Msg::Auth(PopupEvent::Message(AuthEvent::Token(token))) => {
+//!    model.token = Some(token);
+//!    model.popup.take().map(|popup| popup.close());
+//!}
+//!Msg::Auth(PopupEvent::Closed) => model.popup = None,
+//! ```
+
+use crate::app::clock;
+use crate::app::streams::EventStream;
+use crate::browser::util::window;
+use futures::stream::{LocalBoxStream, Stream};
+use serde::{de::DeserializeOwned, Serialize};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::{fmt, rc::Rc};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::MessageEvent;
+
+/// How often `PopupEvents` checks whether the window has been closed - browsers don't fire
+/// any event for cross-origin windows.
+const CLOSED_POLL_INTERVAL_MS: u32 = 500;
+
+/// `Popup` result.
+pub type Result<T> = std::result::Result<T, PopupError>;
+
+// ------ PopupError ------
+
+#[derive(Debug, Clone)]
+pub enum PopupError {
+    /// The browser has blocked the popup - open it in a user gesture handler (click, key press, ...).
+    Blocked,
+    InvalidUrl(JsValue),
+    SendError(JsValue),
+    /// Message data can't be converted to the requested type.
+    DataError(&'static str),
+    SerdeError(Rc<serde_json::Error>),
+}
+
+// ------ PopupEvent ------
+
+/// Event streamed by `Popup::events`.
+#[derive(Debug, Clone)]
+pub enum PopupEvent<In> {
+    Message(In),
+    /// Message that can't be deserialized.
+    InvalidMessage(PopupError),
+    /// The window has been closed; it's the last event.
+    Closed,
+}
+
+// ------ PopupOptions ------
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PopupOptions {
+    /// Window name - opening a popup with the same name reuses the window. A new window by default.
+    pub name: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Position on the screen; the popup is centered over the current window by default.
+    pub left: Option<i32>,
+    pub top: Option<i32>,
+    /// Origin of the popup page when it exchanges messages - e.g. your origin when
+    /// the popup is redirected back to your callback page. The origin of the opened URL by default.
+    pub origin: Option<String>,
+}
+
+impl PopupOptions {
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub const fn size(mut self, width: u32, height: u32) -> Self {
+        self.width = Some(width);
+        self.height = Some(height);
+        self
+    }
+
+    pub const fn position(mut self, left: i32, top: i32) -> Self {
+        self.left = Some(left);
+        self.top = Some(top);
+        self
+    }
+
+    pub fn origin(mut self, origin: impl Into<String>) -> Self {
+        self.origin = Some(origin.into());
+        self
+    }
+
+    /// `window.open` features, e.g. `"popup,width=480,height=640,left=100,top=50"`.
+    fn features(&self) -> String {
+        let window = window();
+        let metric = |value: std::result::Result<JsValue, JsValue>| {
+            value
+                .ok()
+                .and_then(|value| value.as_f64())
+                .unwrap_or_default()
+        };
+        #[allow(clippy::cast_possible_truncation)]
+        let center = |size: u32, offset: f64, outer_size: f64| {
+            (offset + (outer_size - f64::from(size)) / 2.) as i32
+        };
+
+        let mut features = vec!["popup".to_owned()];
+        if let Some(width) = self.width {
+            features.push(format!("width={}", width));
+            let left = self.left.unwrap_or_else(|| {
+                center(
+                    width,
+                    metric(window.screen_x()),
+                    metric(window.outer_width()),
+                )
+            });
+            features.push(format!("left={}", left));
+        }
+        if let Some(height) = self.height {
+            features.push(format!("height={}", height));
+            let top = self.top.unwrap_or_else(|| {
+                center(
+                    height,
+                    metric(window.screen_y()),
+                    metric(window.outer_height()),
+                )
+            });
+            features.push(format!("top={}", top));
+        }
+        features.join(",")
+    }
+}
+
+// ------ Popup ------
+
+/// Connection to a popup window - it sends `Out` messages and receives `In` messages.
+///
+/// It doesn't close the window on drop - call `close`.
+pub struct Popup<Out, In> {
+    window: web_sys::Window,
+    origin: String,
+    message_types: PhantomData<fn(Out) -> In>,
+}
+
+impl<Out, In> fmt::Debug for Popup<Out, In> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Popup")
+            .field("origin", &self.origin)
+            .finish()
+    }
+}
+
+impl<Out, In> Clone for Popup<Out, In> {
+    fn clone(&self) -> Self {
+        Self {
+            window: self.window.clone(),
+            origin: self.origin.clone(),
+            message_types: PhantomData,
+        }
+    }
+}
+
+impl<Out: Serialize, In: DeserializeOwned> Popup<Out, In> {
+    /// Open `url` in a popup window.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PopupError::Blocked` when the browser has blocked the popup
+    /// and `PopupError::InvalidUrl` when `url` can't be resolved.
+    pub fn open(url: &str, options: &PopupOptions) -> Result<Self> {
+        let origin = match &options.origin {
+            Some(origin) => origin.clone(),
+            None => {
+                let base = window().location().href().map_err(PopupError::InvalidUrl)?;
+                web_sys::Url::new_with_base(url, &base)
+                    .map_err(PopupError::InvalidUrl)?
+                    .origin()
+            }
+        };
+        let popup = window()
+            .open_with_url_and_target_and_features(
+                url,
+                options.name.as_deref().unwrap_or("_blank"),
+                &options.features(),
+            )
+            .map_err(|_| PopupError::Blocked)?
+            .ok_or(PopupError::Blocked)?;
+
+        Ok(Self {
+            window: popup,
+            origin,
+            message_types: PhantomData,
+        })
+    }
+
+    /// Connect to the window which has opened the current page; `origin` is its expected origin.
+    /// `None` when the page hasn't been opened by another window (or it has been opened
+    /// with `noopener`).
+    pub fn opener(origin: impl Into<String>) -> Option<Self> {
+        let opener = window().opener().ok()?;
+        if opener.is_null() || opener.is_undefined() {
+            return None;
+        }
+        Some(Self {
+            window: opener.unchecked_into(),
+            origin: origin.into(),
+            message_types: PhantomData,
+        })
+    }
+
+    /// Post `message` to the window.
+    ///
+    /// The browser silently drops it when the window isn't at the expected origin
+    /// (e.g. the OAuth provider page hasn't redirected back yet).
+    ///
+    /// # Errors
+    ///
+    /// Returns error when serialization fails or the message can't be posted.
+    pub fn send(&self, message: &Out) -> Result<()> {
+        let json = serde_json::to_string(message)
+            .map_err(|error| PopupError::SerdeError(Rc::new(error)))?;
+        let message = js_sys::JSON::parse(&json).map_err(PopupError::SendError)?;
+        self.window
+            .post_message(&message, &self.origin)
+            .map_err(PopupError::SendError)
+    }
+
+    /// Stream of messages from the window, ended by `PopupEvent::Closed`.
+    pub fn events(&self) -> PopupEvents<In> {
+        PopupEvents {
+            messages: EventStream::new(&window(), "message"),
+            closed_poll: clock::interval(CLOSED_POLL_INTERVAL_MS),
+            window: self.window.clone(),
+            origin: self.origin.clone(),
+            finished: false,
+            message_type: PhantomData,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.window.closed().unwrap_or(true)
+    }
+
+    /// Close the window. It's a no-op when it's already closed.
+    /// Browsers may ignore it for windows not opened by the page.
+    pub fn close(&self) {
+        let _ = self.window.close();
+    }
+
+    pub fn focus(&self) {
+        let _ = self.window.focus();
+    }
+
+    pub const fn raw(&self) -> &web_sys::Window {
+        &self.window
+    }
+
+    pub fn origin(&self) -> &str {
+        &self.origin
+    }
+}
+
+// ------ PopupEvents ------
+
+/// See `Popup::events`.
+pub struct PopupEvents<In> {
+    messages: EventStream<MessageEvent>,
+    closed_poll: LocalBoxStream<'static, ()>,
+    window: web_sys::Window,
+    origin: String,
+    finished: bool,
+    message_type: PhantomData<fn() -> In>,
+}
+
+impl<In> fmt::Debug for PopupEvents<In> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PopupEvents")
+            .field("origin", &self.origin)
+            .field("finished", &self.finished)
+            .finish()
+    }
+}
+
+impl<In> PopupEvents<In> {
+    fn is_from_window(&self, event: &MessageEvent) -> bool {
+        let source = event.source().map(JsValue::from);
+        event.origin() == self.origin && source.as_ref() == Some(self.window.as_ref())
+    }
+}
+
+impl<In: DeserializeOwned> Stream for PopupEvents<In> {
+    type Item = PopupEvent<In>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        loop {
+            match Stream::poll_next(Pin::new(&mut self.messages), cx) {
+                Poll::Ready(Some(event)) => {
+                    if self.is_from_window(&event) {
+                        return Poll::Ready(Some(
+                            deserialize(&event.data())
+                                .map_or_else(PopupEvent::InvalidMessage, PopupEvent::Message),
+                        ));
+                    }
+                    continue;
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => (),
+            }
+            match self.closed_poll.as_mut().poll_next(cx) {
+                Poll::Ready(Some(())) => {
+                    if self.window.closed().unwrap_or(true) {
+                        self.finished = true;
+                        return Poll::Ready(Some(PopupEvent::Closed));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+fn deserialize<In: DeserializeOwned>(data: &JsValue) -> Result<In> {
+    let json = js_sys::JSON::stringify(data)
+        .ok()
+        .and_then(|json| json.as_string())
+        .ok_or(PopupError::DataError("message data can't be stringified"))?;
+    serde_json::from_str(&json).map_err(|error| PopupError::SerdeError(Rc::new(error)))
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn features_with_explicit_position() {
+        let options = PopupOptions::default().size(480, 640).position(10, 20);
+        assert_eq!(
+            options.features(),
+            "popup,width=480,left=10,height=640,top=20"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn features_without_size() {
+        assert_eq!(PopupOptions::default().features(), "popup");
+    }
+}
@@ -0,0 +1,221 @@
+pub mod inspector;
+pub mod prefetch;
+pub mod transition;
+
+use super::{service::routing, url, Url};
+use crate::app::{subs, Orders};
+use crate::virtual_dom::{At, Attrs, El, Node, ToClasses, UpdateEl};
+use futures::future::LocalBoxFuture;
+
+// ------ Route ------
+
+/// A typed route - e.g. an enum of the app's pages with their parameters.
+///
+/// # Example
+///
+/// ```rust,no_run
+///#[derive(Clone)]
+///enum Page {
+///    Home,
+///    Product { id: u32 },
+///    NotFound(Url),
+///}
+///
+///impl seed::browser::Route for Page {
+///    fn from_url(url: &Url) -> Option<Self> {
+///        match url.path.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+///            [] => Some(Self::Home),
+///            ["products", id] => id.parse().ok().map(|id| Self::Product { id }),
+///            _ => None,
+///        }
+///    }
+///
+///    fn to_url(&self) -> Url {
+///        match self {
+///            Self::Home => Url::new(Vec::<&str>::new()),
+///            Self::Product { id } => Url::new(vec!["products".to_owned(), id.to_string()]),
+///            Self::NotFound(url) => url.clone(),
+///        }
+///    }
+///
+///    fn not_found(url: &Url) -> Option<Self> {
+///        Some(Self::NotFound(url.clone()))
+///    }
+///}
+///...
+///fn view(model: &Model) -> Node<Msg> {
+///    match &model.page {
+///        Page::Home => view_home(),
+///        Page::Product { id } => view_product(*id),
+///        Page::NotFound(url) => seed::browser::route::not_found_view(url),
+///    }
+///}
+/// ```
+pub trait Route: Sized + Clone + 'static {
+    /// Parse the route from the `url`; `None` when the `url` doesn't match.
+    fn from_url(url: &Url) -> Option<Self>;
+
+    fn to_url(&self) -> Url;
+
+    /// The fallback route for urls which don't match - e.g. `Self::NotFound(url.clone())`.
+    /// `RouteMatched` subscriptions and `resolve` use it, so unknown urls
+    /// don't need a `match` arm in every `update`.
+    fn not_found(_url: &Url) -> Option<Self> {
+        None
+    }
+}
+
+// ------ RouteLoader ------
+
+/// A route with data loaded before its page is shown - see `Orders::subscribe_route_loader`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///impl seed::browser::RouteLoader for ProductRoute {
+///    type Data = Product;
+///    type Error = FetchError;
+///
+///    fn load(&self) -> LocalBoxFuture<'static, Result<Product, FetchError>> {
+///        fetch_product(self.id).boxed_local()
+///    }
+///}
+/// ```
+pub trait RouteLoader: Route {
+    type Data: 'static;
+    type Error: 'static;
+
+    fn load(&self) -> LocalBoxFuture<'static, Result<Self::Data, Self::Error>>;
+}
+
+// ------ Not found ------
+
+/// Parse the route from the `url` with the fallback to `Route::not_found`.
+pub fn resolve<R: Route>(url: &Url) -> Option<R> {
+    R::from_url(url).or_else(|| R::not_found(url))
+}
+
+/// Replace the current url with the `route` and notify `UrlChanged` subscribers -
+/// e.g. to redirect unknown urls to the home page:
+///
+/// ```rust,no_run
+///Msg::UrlChanged(subs::UrlChanged(url)) => match Page::from_url(&url) {
+///    Some(page) => model.page = page,
+///    None => route::redirect(&Page::Home, orders),
+///}
+/// ```
+pub fn redirect<R: Route, Ms: 'static, GMs>(route: &R, orders: &mut impl Orders<Ms, GMs>) {
+    let url = routing::replace_route(route.to_url());
+    orders.notify(subs::UrlChanged(url));
+}
+
+/// A simple 404 page with the unknown path and a link to the root.
+pub fn not_found_view<Ms>(url: &Url) -> Node<Ms> {
+    let mut home_attrs = Attrs::empty();
+    home_attrs.add(At::Href, "/");
+    div![
+        C!["not-found"],
+        h1!["Page not found"],
+        p![format!("There is no page at `{}`.", url.to_href())],
+        a![home_attrs, "Home"],
+    ]
+}
+
+// ------ Links ------
+
+/// The route's path is the current path - the search and hash are ignored.
+pub fn is_active<R: Route>(route: &R) -> bool {
+    let non_empty = |path: &[String]| {
+        path.iter()
+            .filter(|segment| !segment.is_empty())
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    non_empty(&route.to_url().path) == non_empty(&url::current().path)
+}
+
+/// `href` of the route and `aria-current="page"` when the route is active - see `a_route!`.
+///
+/// The `href` is root-relative, so clicks are intercepted and handled by Seed routing.
+pub fn link_attrs<R: Route>(route: &R) -> Attrs {
+    let mut attrs = Attrs::empty();
+    attrs.add(At::Href, route.to_url().to_href());
+    if is_active(route) {
+        attrs.add(At::AriaCurrent, "page");
+    }
+    attrs
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Clone)]
+    struct UserRoute {
+        id: u32,
+    }
+
+    impl Route for UserRoute {
+        fn from_url(url: &Url) -> Option<Self> {
+            match url.path.as_slice() {
+                [users, id] if users == "users" => id.parse().ok().map(|id| Self { id }),
+                _ => None,
+            }
+        }
+
+        fn to_url(&self) -> Url {
+            Url::new(vec!["users".to_owned(), self.id.to_string()])
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Page {
+        User(u32),
+        NotFound(Vec<String>),
+    }
+
+    impl Route for Page {
+        fn from_url(url: &Url) -> Option<Self> {
+            UserRoute::from_url(url).map(|user| Self::User(user.id))
+        }
+
+        fn to_url(&self) -> Url {
+            match self {
+                Self::User(id) => UserRoute { id: *id }.to_url(),
+                Self::NotFound(path) => Url::new(path.clone()),
+            }
+        }
+
+        fn not_found(url: &Url) -> Option<Self> {
+            Some(Self::NotFound(url.path.clone()))
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn resolve_falls_back_to_not_found() {
+        assert_eq!(resolve(&Url::new(vec!["users", "3"])), Some(Page::User(3)));
+        assert_eq!(
+            resolve(&Url::new(vec!["nope"])),
+            Some(Page::NotFound(vec!["nope".to_owned()]))
+        );
+        assert!(resolve::<UserRoute>(&Url::new(vec!["nope"])).is_none());
+    }
+
+    #[wasm_bindgen_test]
+    fn a_route_renders_href() {
+        let link: Node<()> = a_route![UserRoute { id: 7 }, "Profile"];
+        let el = link.el().unwrap();
+        assert_eq!(
+            el.attrs.vals.get(&At::Href),
+            Some(&AtValue::Some("/users/7".into()))
+        );
+        assert_eq!(el.attrs.vals.get(&At::AriaCurrent), None);
+        assert_eq!(link.get_text(), "Profile");
+    }
+}
@@ -0,0 +1,247 @@
+//! Debug API of the typed router - registered routes, routes matching the current url
+//! with their parsed parameters and the navigation history.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!route::inspector::register::<Page>();
+//!route::inspector::register::<AdminRoute>();
+//!...
+//!log!(route::inspector::snapshot());
+//!...
+//!fn view(model: &Model) -> Vec<Node<Msg>> {
+//!    vec![
+//!        view_page(model),
+//!        IF!(model.debug => route::inspector::view_overlay()).unwrap_or_else(empty),
+//!    ]
+//!}
+//! ```
+
+use super::Route;
+use crate::browser::{url, Url};
+use crate::virtual_dom::{At, El, Node, St, ToClasses, UpdateEl};
+use indexmap::IndexMap;
+use std::any::{type_name, TypeId};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// The number of kept navigation history entries.
+pub const HISTORY_LIMIT: usize = 50;
+
+type Matcher = Rc<dyn Fn(&Url) -> Option<String>>;
+
+struct RegisteredRoute {
+    type_id: TypeId,
+    name: &'static str,
+    matcher: Matcher,
+}
+
+thread_local! {
+    static ROUTES: RefCell<Vec<RegisteredRoute>> = RefCell::new(Vec::new());
+    static HISTORY: RefCell<VecDeque<NavigationEntry>> = RefCell::new(VecDeque::new());
+}
+
+// ------ Snapshot ------
+
+/// A route matching the current url.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedRoute {
+    /// The name of the route type.
+    pub name: &'static str,
+    /// The parsed route formatted by `Debug`.
+    pub route: String,
+}
+
+/// A url entered by the routing service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavigationEntry {
+    pub url: Url,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: f64,
+}
+
+/// The state of the router - see `snapshot`.
+#[derive(Debug, Clone)]
+pub struct RouterSnapshot {
+    /// Names of the registered route types.
+    pub routes: Vec<&'static str>,
+    pub current_url: Url,
+    pub matched: Vec<MatchedRoute>,
+    /// The last `HISTORY_LIMIT` entered urls, from the oldest.
+    pub history: Vec<NavigationEntry>,
+}
+
+impl RouterSnapshot {
+    /// The registered routes which don't match the current url - all of them for an unknown url.
+    pub fn unmatched(&self) -> Vec<&'static str> {
+        self.routes
+            .iter()
+            .filter(|name| !self.matched.iter().any(|matched| matched.name == **name))
+            .copied()
+            .collect()
+    }
+}
+
+// ------ API ------
+
+/// Register the route `R` for introspection; registering it again has no effect.
+pub fn register<R: Route + Debug>() {
+    ROUTES.with(|routes| {
+        let mut routes = routes.borrow_mut();
+        if routes
+            .iter()
+            .any(|route| route.type_id == TypeId::of::<R>())
+        {
+            return;
+        }
+        routes.push(RegisteredRoute {
+            type_id: TypeId::of::<R>(),
+            name: type_name::<R>(),
+            matcher: Rc::new(|url| R::from_url(url).map(|route| format!("{:?}", route))),
+        });
+    });
+}
+
+/// Registered routes matching the `url` with their parsed parameters.
+pub fn matched(url: &Url) -> Vec<MatchedRoute> {
+    let matchers = ROUTES.with(|routes| {
+        routes
+            .borrow()
+            .iter()
+            .map(|route| (route.name, Rc::clone(&route.matcher)))
+            .collect::<Vec<_>>()
+    });
+    matchers
+        .into_iter()
+        .filter_map(|(name, matcher)| matcher(url).map(|route| MatchedRoute { name, route }))
+        .collect()
+}
+
+pub fn snapshot() -> RouterSnapshot {
+    let current_url = url::current();
+    RouterSnapshot {
+        routes: ROUTES.with(|routes| routes.borrow().iter().map(|route| route.name).collect()),
+        matched: matched(&current_url),
+        current_url,
+        history: HISTORY.with(|history| history.borrow().iter().cloned().collect()),
+    }
+}
+
+/// Record the entered `url` in the history.
+pub(crate) fn record(url: &Url) {
+    HISTORY.with(|history| {
+        let mut history = history.borrow_mut();
+        if history.len() == HISTORY_LIMIT {
+            history.pop_front();
+        }
+        history.push_back(NavigationEntry {
+            url: url.clone(),
+            timestamp: js_sys::Date::now(),
+        });
+    });
+}
+
+// ------ Overlay ------
+
+/// Debug overlay fixed in the bottom right corner - the current url, matched routes,
+/// unmatched routes and the latest history entries.
+pub fn view_overlay<Ms>() -> Node<Ms> {
+    let snapshot = snapshot();
+    let matched = snapshot
+        .matched
+        .iter()
+        .map(|matched| {
+            li![
+                C!["router-inspector-matched"],
+                format!("{} = {}", matched.name, matched.route)
+            ]
+        })
+        .collect::<Vec<_>>();
+    let unmatched = snapshot
+        .unmatched()
+        .into_iter()
+        .map(|name| {
+            li![
+                C!["router-inspector-unmatched"],
+                style! {St::Opacity => "0.6"},
+                name
+            ]
+        })
+        .collect::<Vec<_>>();
+    let history = snapshot
+        .history
+        .iter()
+        .rev()
+        .take(10)
+        .map(|entry| li![entry.url.to_href()])
+        .collect::<Vec<_>>();
+
+    div![
+        C!["router-inspector"],
+        style! {
+            St::Position => "fixed",
+            St::Right => "0",
+            St::Bottom => "0",
+            St::Padding => "0.5em",
+            St::Background => "rgba(0, 0, 0, 0.8)",
+            St::Color => "white",
+            St::FontFamily => "monospace",
+            St::FontSize => "12px",
+            St::ZIndex => "10000",
+        },
+        strong![snapshot.current_url.to_href()],
+        ul![matched, unmatched],
+        IF!(!history.is_empty() => ol![history]),
+    ]
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Clone, Debug)]
+    struct ArticleRoute {
+        slug: String,
+    }
+
+    impl Route for ArticleRoute {
+        fn from_url(url: &Url) -> Option<Self> {
+            match url.path.as_slice() {
+                [articles, slug] if articles == "articles" => Some(Self { slug: slug.clone() }),
+                _ => None,
+            }
+        }
+
+        fn to_url(&self) -> Url {
+            Url::new(vec!["articles", &self.slug])
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn matched_routes_and_history() {
+        register::<ArticleRoute>();
+        register::<ArticleRoute>();
+
+        let url = Url::new(vec!["articles", "seed"]);
+        assert_eq!(
+            matched(&url),
+            vec![MatchedRoute {
+                name: type_name::<ArticleRoute>(),
+                route: r#"ArticleRoute { slug: "seed" }"#.to_owned(),
+            }]
+        );
+        assert!(matched(&Url::new(vec!["about"])).is_empty());
+
+        record(&url);
+        let snapshot = snapshot();
+        assert_eq!(snapshot.routes, vec![type_name::<ArticleRoute>()]);
+        assert_eq!(snapshot.history.last().map(|entry| &entry.url), Some(&url));
+    }
+}
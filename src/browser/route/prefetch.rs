@@ -0,0 +1,426 @@
+//! Prefetch the data of `RouteLoader`s before the navigation - the links are hovered,
+//! focused or visible, or the browser is idle.
+//!
+//! Prefetched data are used once by `Orders::subscribe_route_loader` instead of a new load.
+//! Data older than `MAX_AGE` are loaded again; at most `MAX_PREFETCHED` routes are kept
+//! and the oldest ones are forgotten first.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!App::start("app", init, update, view).prefetch_links::<ProductRoute>();
+//!...
+//!a![
+//!    route::link_attrs(&ProductRoute { id }),
+//!    route::prefetch::prefetch_attrs(PrefetchOn::Visible),
+//!    "Product",
+//!]
+//!...
+//!route::prefetch::prefetch_when_idle(ProductRoute { id: featured_id });
+//! ```
+
+use super::RouteLoader;
+use crate::app::clock;
+use crate::browser::{util, Url};
+use crate::virtual_dom::{At, Attrs};
+use futures::channel::oneshot;
+use futures::future::{FutureExt, LocalBoxFuture};
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use wasm_bindgen::{closure::Closure, JsCast};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{
+    IntersectionObserver, IntersectionObserverEntry, IntersectionObserverInit, MutationObserver,
+    MutationObserverInit,
+};
+
+/// Prefetched data older than `MAX_AGE` milliseconds aren't used.
+pub const MAX_AGE: u32 = 30_000;
+/// The maximum number of prefetched routes waiting for the navigation.
+pub const MAX_PREFETCHED: usize = 32;
+
+const PREFETCH_ATTR: &str = "data-prefetch";
+const PREFETCHED_ATTR: &str = "data-prefetched";
+
+type Key = (TypeId, String);
+
+thread_local! {
+    /// Receivers of the prefetched data by the route type and `href`.
+    static PREFETCHED: RefCell<HashMap<Key, Entry>> = RefCell::new(HashMap::new());
+}
+
+struct Entry {
+    receiver: Box<dyn Any>,
+    prefetched_at: f64,
+}
+
+impl Entry {
+    fn is_expired(&self, now: f64) -> bool {
+        now - self.prefetched_at > f64::from(MAX_AGE)
+    }
+}
+
+/// Remove expired entries and then the oldest ones, so a new entry fits into `MAX_PREFETCHED`.
+fn make_room(prefetched: &mut HashMap<Key, Entry>, now: f64) {
+    prefetched.retain(|_, entry| !entry.is_expired(now));
+    while prefetched.len() >= MAX_PREFETCHED {
+        let oldest = prefetched
+            .iter()
+            .min_by(|(_, a), (_, b)| {
+                a.prefetched_at
+                    .partial_cmp(&b.prefetched_at)
+                    .unwrap_or(Ordering::Equal)
+            })
+            .map(|(key, _)| key.clone());
+        match oldest {
+            Some(oldest) => prefetched.remove(&oldest),
+            None => break,
+        };
+    }
+}
+
+type Prefetched<R> = oneshot::Receiver<Result<<R as RouteLoader>::Data, <R as RouteLoader>::Error>>;
+
+// ------ PrefetchOn ------
+
+/// When the link is prefetched - see `prefetch_attrs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefetchOn {
+    /// The link is hovered or focused.
+    Hover,
+    /// The link enters the viewport.
+    Visible,
+}
+
+impl PrefetchOn {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Hover => "hover",
+            Self::Visible => "visible",
+        }
+    }
+}
+
+/// Mark the link for prefetching by listeners registered by `App::prefetch_links`.
+pub fn prefetch_attrs(on: PrefetchOn) -> Attrs {
+    let mut attrs = Attrs::empty();
+    attrs.add(At::from(PREFETCH_ATTR), on.as_str());
+    attrs
+}
+
+// ------ Prefetching ------
+
+/// Start loading the data of the `route`; it's ignored when they are already prefetched
+/// and not older than `MAX_AGE`.
+pub fn prefetch<R: RouteLoader>(route: &R) {
+    let key = (TypeId::of::<R>(), route.to_url().to_href());
+    PREFETCHED.with(|prefetched| {
+        let mut prefetched = prefetched.borrow_mut();
+        let now = clock::now();
+        if prefetched
+            .get(&key)
+            .map_or(false, |entry| !entry.is_expired(now))
+        {
+            return;
+        }
+        prefetched.remove(&key);
+        make_room(&mut prefetched, now);
+        let (sender, receiver): (_, Prefetched<R>) = oneshot::channel();
+        prefetched.insert(
+            key,
+            Entry {
+                receiver: Box::new(receiver),
+                prefetched_at: now,
+            },
+        );
+        spawn_local(route.load().map(move |result| {
+            // The receiver is dropped when the prefetched data aren't needed anymore.
+            let _ = sender.send(result);
+        }));
+    });
+}
+
+/// Prefetch the `route` when the browser is idle - e.g. the most likely next page.
+/// The data are prefetched immediately in browsers without `requestIdleCallback`.
+pub fn prefetch_when_idle<R: RouteLoader>(route: R) {
+    let callback = Closure::once_into_js(move || prefetch(&route));
+    if util::window()
+        .request_idle_callback(callback.unchecked_ref())
+        .is_err()
+    {
+        callback
+            .unchecked_ref::<js_sys::Function>()
+            .call0(&wasm_bindgen::JsValue::NULL)
+            .expect("call prefetch callback");
+    }
+}
+
+/// Take the data prefetched for the `route`; the `route` is loaded again
+/// when the prefetch has failed to finish. `None` when there are no data or they're expired.
+pub(crate) fn take<R: RouteLoader>(
+    route: &R,
+) -> Option<LocalBoxFuture<'static, Result<R::Data, R::Error>>> {
+    let key = (TypeId::of::<R>(), route.to_url().to_href());
+    let entry = PREFETCHED.with(|prefetched| {
+        let mut prefetched = prefetched.borrow_mut();
+        let now = clock::now();
+        prefetched.retain(|_, entry| !entry.is_expired(now));
+        prefetched.remove(&key)
+    })?;
+    let receiver = entry.receiver.downcast::<Prefetched<R>>().ok()?;
+    let route = route.clone();
+    Some(
+        async move {
+            match receiver.await {
+                Ok(result) => result,
+                Err(oneshot::Canceled) => route.load().await,
+            }
+        }
+        .boxed_local(),
+    )
+}
+
+fn prefetch_link<R: RouteLoader>(link: &web_sys::Element) {
+    let route = link
+        .get_attribute("href")
+        .and_then(|href| Url::try_from(href).ok())
+        .and_then(|url| R::from_url(&url));
+    if let Some(route) = route {
+        prefetch(&route);
+    }
+}
+
+// ------ PrefetchListener ------
+
+/// Document listeners which prefetch links with `prefetch_attrs` of the route `R`.
+/// They are removed on drop. See `App::prefetch_links`.
+pub struct PrefetchListener<R> {
+    hover: Closure<dyn FnMut(web_sys::Event)>,
+    intersection_observer: IntersectionObserver,
+    _intersection_callback: Closure<dyn FnMut(js_sys::Array, IntersectionObserver)>,
+    mutation_observer: MutationObserver,
+    _mutation_callback: Closure<dyn FnMut(js_sys::Array)>,
+    _route: PhantomData<R>,
+}
+
+impl<R: RouteLoader> PrefetchListener<R> {
+    /// # Panics
+    ///
+    /// Panics when the browser doesn't support `IntersectionObserver` or `MutationObserver`.
+    pub fn new() -> Self {
+        let document = util::document();
+
+        let hover_selector = format!("a[{}=\"hover\"]", PREFETCH_ATTR);
+        let hover = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let link = event
+                .target()
+                .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                .and_then(|el| el.closest(&hover_selector).ok().flatten());
+            if let Some(link) = link {
+                prefetch_link::<R>(&link);
+            }
+        }) as Box<dyn FnMut(web_sys::Event)>);
+        for trigger in &["mouseover", "focusin"] {
+            document
+                .add_event_listener_with_callback(trigger, hover.as_ref().unchecked_ref())
+                .expect("add prefetch listener");
+        }
+
+        let intersection_callback = Closure::wrap(Box::new(
+            move |entries: js_sys::Array, observer: IntersectionObserver| {
+                for entry in entries.iter() {
+                    let entry: IntersectionObserverEntry = entry.unchecked_into();
+                    if entry.is_intersecting() {
+                        let link = entry.target();
+                        observer.unobserve(&link);
+                        link.set_attribute(PREFETCHED_ATTR, "")
+                            .expect("set prefetched attribute");
+                        prefetch_link::<R>(&link);
+                    }
+                }
+            },
+        )
+            as Box<dyn FnMut(js_sys::Array, IntersectionObserver)>);
+        let intersection_observer = IntersectionObserver::new_with_options(
+            intersection_callback.as_ref().unchecked_ref(),
+            &IntersectionObserverInit::new(),
+        )
+        .expect("create IntersectionObserver");
+
+        // New visible links are observed after each DOM change.
+        let visible_selector = format!(
+            "a[{}=\"visible\"]:not([{}])",
+            PREFETCH_ATTR, PREFETCHED_ATTR
+        );
+        let observe_links = {
+            let intersection_observer = intersection_observer.clone();
+            move || {
+                let links = util::document()
+                    .query_selector_all(&visible_selector)
+                    .expect("query links to prefetch");
+                for index in 0..links.length() {
+                    if let Some(link) = links.get(index) {
+                        intersection_observer.observe(link.unchecked_ref());
+                    }
+                }
+            }
+        };
+        observe_links();
+        let mutation_callback = Closure::wrap(
+            Box::new(move |_: js_sys::Array| observe_links()) as Box<dyn FnMut(js_sys::Array)>
+        );
+        let mutation_observer = MutationObserver::new(mutation_callback.as_ref().unchecked_ref())
+            .expect("create MutationObserver");
+        let options = MutationObserverInit::new();
+        options.set_child_list(true);
+        options.set_subtree(true);
+        mutation_observer
+            .observe_with_options(&document, &options)
+            .expect("observe document mutations");
+
+        Self {
+            hover,
+            intersection_observer,
+            _intersection_callback: intersection_callback,
+            mutation_observer,
+            _mutation_callback: mutation_callback,
+            _route: PhantomData,
+        }
+    }
+}
+
+impl<R> Drop for PrefetchListener<R> {
+    fn drop(&mut self) {
+        let document = util::document();
+        for trigger in &["mouseover", "focusin"] {
+            document
+                .remove_event_listener_with_callback(trigger, self.hover.as_ref().unchecked_ref())
+                .expect("remove prefetch listener");
+        }
+        self.intersection_observer.disconnect();
+        self.mutation_observer.disconnect();
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::Route;
+    use crate::testing::TestClock;
+    use futures::future::ready;
+    use std::cell::Cell;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    thread_local! {
+        static LOADS: Cell<u32> = Cell::new(0);
+        static PAGE_LOADS: Cell<u32> = Cell::new(0);
+    }
+
+    #[derive(Clone)]
+    struct DocsRoute;
+
+    impl Route for DocsRoute {
+        fn from_url(url: &Url) -> Option<Self> {
+            if url.path == ["docs"] {
+                Some(Self)
+            } else {
+                None
+            }
+        }
+
+        fn to_url(&self) -> Url {
+            Url::new(vec!["docs"])
+        }
+    }
+
+    impl RouteLoader for DocsRoute {
+        type Data = u32;
+        type Error = ();
+
+        fn load(&self) -> LocalBoxFuture<'static, Result<u32, ()>> {
+            LOADS.with(|loads| loads.set(loads.get() + 1));
+            ready(Ok(42)).boxed_local()
+        }
+    }
+
+    /// `/pages/<number>`
+    #[derive(Clone)]
+    struct PageRoute(usize);
+
+    impl Route for PageRoute {
+        fn from_url(_: &Url) -> Option<Self> {
+            None
+        }
+
+        fn to_url(&self) -> Url {
+            Url::new(vec!["pages".to_owned(), self.0.to_string()])
+        }
+    }
+
+    impl RouteLoader for PageRoute {
+        type Data = usize;
+        type Error = ();
+
+        fn load(&self) -> LocalBoxFuture<'static, Result<usize, ()>> {
+            PAGE_LOADS.with(|loads| loads.set(loads.get() + 1));
+            ready(Ok(self.0)).boxed_local()
+        }
+    }
+
+    fn prefetched_count() -> usize {
+        PREFETCHED.with(|prefetched| prefetched.borrow().len())
+    }
+
+    #[wasm_bindgen_test]
+    async fn stale_prefetched_data_are_reloaded() {
+        let clock = TestClock::install();
+        let route = PageRoute(100);
+        let loads = PAGE_LOADS.with(Cell::get);
+        prefetch(&route);
+        clock.advance(u64::from(MAX_AGE) + 1);
+        assert!(take(&route).is_none());
+
+        prefetch(&route);
+        clock.advance(u64::from(MAX_AGE) + 1);
+        prefetch(&route);
+        assert_eq!(PAGE_LOADS.with(Cell::get), loads + 3);
+        assert_eq!(take(&route).unwrap().await, Ok(100));
+    }
+
+    #[wasm_bindgen_test]
+    async fn oldest_prefetched_data_are_evicted() {
+        let clock = TestClock::install();
+        for page in 0..=MAX_PREFETCHED {
+            prefetch(&PageRoute(page));
+            clock.advance(1);
+        }
+        assert_eq!(prefetched_count(), MAX_PREFETCHED);
+        assert!(take(&PageRoute(0)).is_none());
+        assert_eq!(
+            take(&PageRoute(MAX_PREFETCHED)).unwrap().await,
+            Ok(MAX_PREFETCHED)
+        );
+
+        clock.advance(u64::from(MAX_AGE) + 1);
+        assert!(take(&PageRoute(1)).is_none());
+        assert_eq!(prefetched_count(), 0);
+    }
+
+    #[wasm_bindgen_test]
+    async fn prefetched_data_are_taken_once() {
+        prefetch(&DocsRoute);
+        prefetch(&DocsRoute);
+        assert_eq!(LOADS.with(Cell::get), 1);
+
+        assert_eq!(take(&DocsRoute).unwrap().await, Ok(42));
+        assert!(take(&DocsRoute).is_none());
+    }
+}
@@ -0,0 +1,289 @@
+//! Navigation hooks of typed routes - e.g. exit animations, saving drafts or blocking
+//! leaving a page with unsaved changes.
+//!
+//! `before_leave` hooks run before Seed pushes a new url on an internal link click
+//! or by `navigate`. `after_enter` hooks run after each `UrlChanged` notification fired
+//! by the routing service - i.e. also on back / forward navigation, which can't be blocked.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!let app = orders.clone_app();
+//!model.leave_hook = Some(route::transition::before_leave(move |_: &EditorRoute, _target| {
+//!    if draft_saved() {
+//!        Leave::Allow
+//!    } else {
+//!        Leave::wait(confirm_leaving())
+//!    }
+//!}));
+//!model.enter_hook = Some(route::transition::after_enter(move |_: &EditorRoute, _url| {
+//!    app.update(Msg::EditorEntered)
+//!}));
+//! ```
+
+use super::{inspector, Route};
+use crate::app::{subs, Orders};
+use crate::browser::{service::routing, url, Url};
+use futures::future::{join_all, Future, FutureExt, LocalBoxFuture};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen_futures::spawn_local;
+
+type BeforeLeaveHook = Rc<dyn Fn(&Url, &Url) -> Option<Leave>>;
+type AfterEnterHook = Rc<dyn Fn(&Url)>;
+
+thread_local! {
+    static NEXT_HOOK_ID: Cell<u64> = Cell::new(0);
+    static BEFORE_LEAVE_HOOKS: RefCell<Vec<(u64, BeforeLeaveHook)>> = RefCell::new(Vec::new());
+    static AFTER_ENTER_HOOKS: RefCell<Vec<(u64, AfterEnterHook)>> = RefCell::new(Vec::new());
+}
+
+fn next_hook_id() -> u64 {
+    NEXT_HOOK_ID.with(|id| {
+        let next = id.get();
+        id.set(next + 1);
+        next
+    })
+}
+
+// ------ Leave ------
+
+/// The decision of a `before_leave` hook.
+pub enum Leave {
+    Allow,
+    Block,
+    /// Navigate when the future resolves to `true` - e.g. after an exit animation
+    /// or a confirmation dialog. Create it by `Leave::wait`.
+    Wait(LocalBoxFuture<'static, bool>),
+}
+
+impl Leave {
+    pub fn wait(allowed: impl Future<Output = bool> + 'static) -> Self {
+        Self::Wait(allowed.boxed_local())
+    }
+}
+
+// ------ Hooks ------
+
+/// Call `hook` with the route and the target url when the app is going to leave a url
+/// matching the route `R`. The hook is removed on the handle drop.
+pub fn before_leave<R: Route>(hook: impl Fn(&R, &Url) -> Leave + 'static) -> TransitionHookHandle {
+    let id = next_hook_id();
+    let hook: BeforeLeaveHook =
+        Rc::new(move |from, to| R::from_url(from).map(|route| hook(&route, to)));
+    BEFORE_LEAVE_HOOKS.with(|hooks| hooks.borrow_mut().push((id, hook)));
+    TransitionHookHandle(id)
+}
+
+/// Call `hook` with the route and the url when the app has entered a url matching the route `R`.
+/// The hook is removed on the handle drop.
+pub fn after_enter<R: Route>(hook: impl Fn(&R, &Url) + 'static) -> TransitionHookHandle {
+    let id = next_hook_id();
+    let hook: AfterEnterHook = Rc::new(move |url| {
+        if let Some(route) = R::from_url(url) {
+            hook(&route, url)
+        }
+    });
+    AFTER_ENTER_HOOKS.with(|hooks| hooks.borrow_mut().push((id, hook)));
+    TransitionHookHandle(id)
+}
+
+// ------ TransitionHookHandle ------
+
+#[must_use = "the hook is removed on its handle drop"]
+pub struct TransitionHookHandle(u64);
+
+impl Drop for TransitionHookHandle {
+    fn drop(&mut self) {
+        let id = self.0;
+        BEFORE_LEAVE_HOOKS.with(|hooks| hooks.borrow_mut().retain(|(hook_id, _)| *hook_id != id));
+        AFTER_ENTER_HOOKS.with(|hooks| hooks.borrow_mut().retain(|(hook_id, _)| *hook_id != id));
+    }
+}
+
+// ------ Navigation ------
+
+/// Push the `url` and notify `UrlChanged` subscribers when `before_leave` hooks allow it.
+///
+/// The navigation starts after the current `update`, so `after_enter` hooks can send messages
+/// and they see the `Model` already updated by the `UrlChanged` handlers.
+///
+/// # Example
+///
+/// ```rust,no_run
+///Msg::CancelClicked => route::transition::navigate(Url::new(vec!["documents"]), orders),
+/// ```
+pub fn navigate<Ms: 'static, GMs: 'static>(url: impl Into<Url>, orders: &mut impl Orders<Ms, GMs>) {
+    let url = url.into();
+    let allowed = match leave(&url::current(), &url) {
+        Leave::Allow => None,
+        Leave::Block => return,
+        Leave::Wait(allowed) => Some(allowed),
+    };
+    let app = orders.clone_app();
+    spawn_local(async move {
+        if let Some(allowed) = allowed {
+            if !allowed.await {
+                return;
+            }
+        }
+        routing::push_route(url.clone());
+        app.notify(subs::UrlChanged(url.clone()));
+        enter(&url);
+    });
+}
+
+/// The combined decision of `before_leave` hooks matching `from` -
+/// blocked when any hook blocks, waiting until all waiting hooks allow it.
+pub(crate) fn leave(from: &Url, to: &Url) -> Leave {
+    // Hooks are cloned, so they can create or drop other hooks.
+    let hooks = BEFORE_LEAVE_HOOKS.with(|hooks| {
+        hooks
+            .borrow()
+            .iter()
+            .map(|(_, hook)| Rc::clone(hook))
+            .collect::<Vec<_>>()
+    });
+    let mut waiting = Vec::new();
+    for decision in hooks.iter().filter_map(|hook| hook(from, to)) {
+        match decision {
+            Leave::Allow => (),
+            Leave::Block => return Leave::Block,
+            Leave::Wait(allowed) => waiting.push(allowed),
+        }
+    }
+    if waiting.is_empty() {
+        Leave::Allow
+    } else {
+        Leave::wait(join_all(waiting).map(|allowed| allowed.into_iter().all(|allowed| allowed)))
+    }
+}
+
+/// Record `url` in the `inspector` history and call `after_enter` hooks matching it.
+pub(crate) fn enter(url: &Url) {
+    inspector::record(url);
+    let hooks = AFTER_ENTER_HOOKS.with(|hooks| {
+        hooks
+            .borrow()
+            .iter()
+            .map(|(_, hook)| Rc::clone(hook))
+            .collect::<Vec<_>>()
+    });
+    for hook in hooks {
+        hook(url);
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{App, OrdersContainer};
+    use crate::browser::util;
+    use crate::testing::TestClock;
+    use crate::virtual_dom::Node;
+    use futures::future::ready;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Clone)]
+    struct EditorRoute;
+
+    impl Route for EditorRoute {
+        fn from_url(url: &Url) -> Option<Self> {
+            if url.path == ["editor"] {
+                Some(Self)
+            } else {
+                None
+            }
+        }
+
+        fn to_url(&self) -> Url {
+            Url::new(vec!["editor"])
+        }
+    }
+
+    #[wasm_bindgen_test]
+    async fn leave_combines_hooks() {
+        let editor = Url::new(vec!["editor"]);
+        let home = Url::new(vec!["home"]);
+
+        let wait_hook = before_leave(|_: &EditorRoute, _| Leave::wait(ready(true)));
+        assert!(matches!(leave(&home, &editor), Leave::Allow));
+        match leave(&editor, &home) {
+            Leave::Wait(allowed) => assert!(allowed.await),
+            _ => panic!("leaving should wait"),
+        }
+
+        let block_hook = before_leave(|_: &EditorRoute, to| {
+            if to.path == ["home"] {
+                Leave::Block
+            } else {
+                Leave::Allow
+            }
+        });
+        assert!(matches!(leave(&editor, &home), Leave::Block));
+
+        drop(block_hook);
+        drop(wait_hook);
+        assert!(matches!(leave(&editor, &home), Leave::Allow));
+    }
+
+    enum Msg {
+        OpenEditor,
+        UrlChanged,
+        EditorEntered,
+    }
+
+    #[derive(Default)]
+    struct Model {
+        events: Vec<&'static str>,
+        enter_hook: Option<TransitionHookHandle>,
+    }
+
+    fn update(msg: Msg, model: &mut Model, orders: &mut OrdersContainer<Msg, Model, Node<Msg>>) {
+        match msg {
+            Msg::OpenEditor => {
+                navigate(Url::new(vec!["editor"]), orders);
+                model.events.push("navigate");
+            }
+            Msg::UrlChanged => model.events.push("url changed"),
+            Msg::EditorEntered => model.events.push("entered"),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    async fn after_enter_hook_can_send_messages_after_navigate() {
+        let clock = TestClock::install();
+        let root = util::document().create_element("div").unwrap();
+        util::body().append_child(&root).unwrap();
+        let app = App::start(
+            root,
+            |_, orders: &mut OrdersContainer<Msg, Model, Node<Msg>>| {
+                orders.subscribe(|_: subs::UrlChanged| Msg::UrlChanged);
+                let app = orders.clone_app();
+                Model {
+                    enter_hook: Some(after_enter(move |_: &EditorRoute, _| {
+                        app.update(Msg::EditorEntered);
+                    })),
+                    ..Model::default()
+                }
+            },
+            update,
+            |_| Node::Empty,
+        );
+
+        app.update(Msg::OpenEditor);
+        clock.flush().await;
+        let mut model = app.data.model.borrow_mut();
+        let model = model.as_mut().unwrap();
+        assert_eq!(model.events, vec!["navigate", "url changed", "entered"]);
+        // The hook is global - don't call it in other tests.
+        model.enter_hook = None;
+        util::history()
+            .replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some("/"))
+            .unwrap();
+    }
+}
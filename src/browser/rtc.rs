@@ -0,0 +1,703 @@
+//! `web_sys::RtcPeerConnection` wrapper integrated with `Orders` - peer-to-peer data channels
+//! without the `web_sys` glue.
+//!
+//! Seed doesn't transfer signals between peers - send `Signal`s produced by `Builder::on_signal`
+//! through your own channel (e.g. a `WebSocket`) and pass them to the remote
+//! `PeerConnection::handle_signal`.
+//!
+//! [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/WebRTC_API)
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!fn init(_: Url, orders: &mut impl Orders<Msg>) -> Init<Model> {
+//!    let peer = PeerConnection::builder(orders)
+//!        .ice_server(IceServer::new("stun:stun.l.google.com:19302"))
+//!        .on_signal(Msg::SignalCreated)
+//!        .on_data_channel(Msg::DataChannelOpened)
+//!        .build()
+//!        .expect("create peer connection");
+//!    Init::new(Model { peer, chat: None })
+//!}
+//!
+//!fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+//!    match msg {
+//!        Msg::Call => {
+//!            let channel = model.peer.create_data_channel("chat");
+//!            orders.send_msg(Msg::DataChannelOpened(channel));
+//!            orders.perform_cmd(model.peer.create_offer().map(Msg::Signaled));
+//!        }
+//!        Msg::SignalCreated(signal) => model.signaling.send_json(&signal).expect("send signal"),
+//!        Msg::SignalReceived(signal) => {
+//!            orders.perform_cmd(model.peer.handle_signal(signal).map(Msg::Signaled));
+//!        }
+//!        Msg::DataChannelOpened(channel) => {
+//!            let (sender, receiver) = channel.split::<ChatMessage, ChatMessage>();
+//!            orders.stream(receiver.map(Msg::Chat));
+//!            model.chat = Some(sender);
+//!        }
+//!        Msg::Chat(DataChannelEvent::Message(message)) => model.messages.push(message),
+//!        ...
+//!    }
+//!}
+//! ```
+
+use crate::app::Orders;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver};
+use futures::future::Future;
+use futures::stream::Stream;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::{fmt, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    RtcConfiguration, RtcDataChannel, RtcDataChannelEvent, RtcDataChannelType, RtcIceCandidateInit,
+    RtcPeerConnectionIceEvent, RtcSdpType, RtcSessionDescriptionInit,
+};
+
+pub use web_sys::{RtcDataChannelState as DataChannelState, RtcPeerConnectionState as State};
+
+type Dispatch<Ms> = Rc<dyn Fn(Ms)>;
+
+/// `rtc` result.
+pub type Result<T> = std::result::Result<T, RtcError>;
+
+// ------ RtcError ------
+
+#[derive(Debug, Clone)]
+pub enum RtcError {
+    /// Invalid configuration - e.g. an ICE server URL.
+    ConnectionError(JsValue),
+    /// Session description or ICE candidate has been rejected.
+    SignalingError(JsValue),
+    SendError(JsValue),
+    /// Message data can't be converted to the requested type.
+    DataError(&'static str),
+    SerdeError(Rc<serde_json::Error>),
+}
+
+// ------ Signal ------
+
+/// Message for the remote peer. It's serializable, so you can send it as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Signal {
+    Offer {
+        sdp: String,
+    },
+    Answer {
+        sdp: String,
+    },
+    IceCandidate {
+        candidate: String,
+        sdp_mid: Option<String>,
+        sdp_m_line_index: Option<u16>,
+    },
+}
+
+// ------ IceServer ------
+
+/// STUN or TURN server.
+///
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/RTCIceServer)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IceServer {
+    url: String,
+    credentials: Option<(String, String)>,
+}
+
+impl IceServer {
+    /// E.g. `"stun:stun.example.com"` or `"turn:turn.example.com:3478"`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            credentials: None,
+        }
+    }
+
+    /// Credentials for TURN servers.
+    pub fn credentials(
+        mut self,
+        username: impl Into<String>,
+        credential: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some((username.into(), credential.into()));
+        self
+    }
+
+    fn to_raw(&self) -> web_sys::RtcIceServer {
+        let mut ice_server = web_sys::RtcIceServer::new();
+        ice_server.urls(&JsValue::from(&self.url));
+        if let Some((username, credential)) = &self.credentials {
+            ice_server.username(username).credential(credential);
+        }
+        ice_server
+    }
+}
+
+// ------ Builder ------
+
+/// Created by `PeerConnection::builder`.
+pub struct Builder<Ms: 'static> {
+    ice_servers: Vec<IceServer>,
+    dispatch: Dispatch<Ms>,
+    on_signal: Option<Rc<dyn Fn(Signal) -> Ms>>,
+    on_state_change: Option<Rc<dyn Fn(State) -> Ms>>,
+    on_data_channel: Option<Rc<dyn Fn(DataChannel) -> Ms>>,
+}
+
+impl<Ms> fmt::Debug for Builder<Ms> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Builder")
+            .field("ice_servers", &self.ice_servers)
+            .finish()
+    }
+}
+
+impl<Ms: 'static> Builder<Ms> {
+    pub fn ice_server(mut self, ice_server: IceServer) -> Self {
+        self.ice_servers.push(ice_server);
+        self
+    }
+
+    /// Local offers, answers and ICE candidates - send them to the remote peer.
+    pub fn on_signal(mut self, handler: impl FnOnce(Signal) -> Ms + Clone + 'static) -> Self {
+        self.on_signal = Some(Rc::new(move |signal| handler.clone()(signal)));
+        self
+    }
+
+    /// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/RTCPeerConnection/connectionState)
+    pub fn on_state_change(mut self, handler: impl FnOnce(State) -> Ms + Clone + 'static) -> Self {
+        self.on_state_change = Some(Rc::new(move |state| handler.clone()(state)));
+        self
+    }
+
+    /// Data channel created by the remote peer.
+    pub fn on_data_channel(
+        mut self,
+        handler: impl FnOnce(DataChannel) -> Ms + Clone + 'static,
+    ) -> Self {
+        self.on_data_channel = Some(Rc::new(move |channel| handler.clone()(channel)));
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Returns `RtcError::ConnectionError` when the configuration is invalid.
+    pub fn build(self) -> Result<PeerConnection> {
+        let ice_servers = self
+            .ice_servers
+            .iter()
+            .map(IceServer::to_raw)
+            .collect::<js_sys::Array>();
+        let mut configuration = RtcConfiguration::new();
+        configuration.ice_servers(&ice_servers);
+        let pc = web_sys::RtcPeerConnection::new_with_configuration(&configuration)
+            .map_err(RtcError::ConnectionError)?;
+
+        let dispatch = self.dispatch;
+        let send_signal: Rc<dyn Fn(Signal)> = {
+            let (dispatch, handler) = (Rc::clone(&dispatch), self.on_signal);
+            Rc::new(move |signal| {
+                if let Some(handler) = &handler {
+                    dispatch(handler(signal));
+                }
+            })
+        };
+        let callbacks = PeerCallbacks {
+            on_ice_candidate: {
+                let send_signal = Rc::clone(&send_signal);
+                callback(move |event| {
+                    let event: RtcPeerConnectionIceEvent = event.unchecked_into();
+                    // `None` means that all candidates have been gathered.
+                    if let Some(candidate) = event.candidate() {
+                        send_signal(Signal::IceCandidate {
+                            candidate: candidate.candidate(),
+                            sdp_mid: candidate.sdp_mid(),
+                            sdp_m_line_index: candidate.sdp_m_line_index(),
+                        });
+                    }
+                })
+            },
+            on_connection_state_change: {
+                let (pc, dispatch) = (pc.clone(), Rc::clone(&dispatch));
+                let handler = self.on_state_change;
+                callback(move |_| {
+                    if let Some(handler) = &handler {
+                        dispatch(handler(pc.connection_state()));
+                    }
+                })
+            },
+            on_data_channel: {
+                let handler = self.on_data_channel;
+                callback(move |event| {
+                    let event: RtcDataChannelEvent = event.unchecked_into();
+                    // The channel is closed when it's dropped, so we don't create it for nobody.
+                    if let Some(handler) = &handler {
+                        dispatch(handler(DataChannel::new(event.channel())));
+                    }
+                })
+            },
+        };
+        pc.set_onicecandidate(Some(as_function(&callbacks.on_ice_candidate)));
+        pc.set_onconnectionstatechange(Some(as_function(&callbacks.on_connection_state_change)));
+        pc.set_ondatachannel(Some(as_function(&callbacks.on_data_channel)));
+
+        Ok(PeerConnection(Rc::new(Peer {
+            pc,
+            send_signal,
+            pending_candidates: RefCell::default(),
+            _callbacks: callbacks,
+        })))
+    }
+}
+
+fn callback(f: impl Fn(JsValue) + 'static) -> Closure<dyn Fn(JsValue)> {
+    Closure::wrap(Box::new(f) as Box<dyn Fn(JsValue)>)
+}
+
+fn as_function(closure: &Closure<dyn Fn(JsValue)>) -> &js_sys::Function {
+    closure.as_ref().unchecked_ref()
+}
+
+// ------ Peer ------
+
+struct PeerCallbacks {
+    on_ice_candidate: Closure<dyn Fn(JsValue)>,
+    on_connection_state_change: Closure<dyn Fn(JsValue)>,
+    on_data_channel: Closure<dyn Fn(JsValue)>,
+}
+
+struct Peer {
+    pc: web_sys::RtcPeerConnection,
+    send_signal: Rc<dyn Fn(Signal)>,
+    /// ICE candidates received before the remote description.
+    pending_candidates: RefCell<Vec<RtcIceCandidateInit>>,
+    _callbacks: PeerCallbacks,
+}
+
+impl Peer {
+    async fn set_local_description(
+        &self,
+        sdp_type: RtcSdpType,
+        description: JsValue,
+    ) -> Result<String> {
+        let sdp = js_sys::Reflect::get(&description, &"sdp".into())
+            .ok()
+            .and_then(|sdp| sdp.as_string())
+            .unwrap_or_default();
+        let mut description = RtcSessionDescriptionInit::new(sdp_type);
+        description.sdp(&sdp);
+        JsFuture::from(self.pc.set_local_description(&description))
+            .await
+            .map_err(RtcError::SignalingError)?;
+        Ok(sdp)
+    }
+
+    async fn set_remote_description(&self, sdp_type: RtcSdpType, sdp: &str) -> Result<()> {
+        let mut description = RtcSessionDescriptionInit::new(sdp_type);
+        description.sdp(sdp);
+        JsFuture::from(self.pc.set_remote_description(&description))
+            .await
+            .map_err(RtcError::SignalingError)?;
+
+        let pending_candidates = self.pending_candidates.replace(Vec::new());
+        for candidate in pending_candidates {
+            self.add_ice_candidate(&candidate).await?;
+        }
+        Ok(())
+    }
+
+    async fn add_ice_candidate(&self, candidate: &RtcIceCandidateInit) -> Result<()> {
+        JsFuture::from(
+            self.pc
+                .add_ice_candidate_with_opt_rtc_ice_candidate_init(Some(candidate)),
+        )
+        .await
+        .map(|_| ())
+        .map_err(RtcError::SignalingError)
+    }
+
+    async fn create_offer(&self) -> Result<()> {
+        let offer = JsFuture::from(self.pc.create_offer())
+            .await
+            .map_err(RtcError::SignalingError)?;
+        let sdp = self.set_local_description(RtcSdpType::Offer, offer).await?;
+        (self.send_signal)(Signal::Offer { sdp });
+        Ok(())
+    }
+
+    async fn handle_signal(&self, signal: Signal) -> Result<()> {
+        match signal {
+            Signal::Offer { sdp } => {
+                self.set_remote_description(RtcSdpType::Offer, &sdp).await?;
+                let answer = JsFuture::from(self.pc.create_answer())
+                    .await
+                    .map_err(RtcError::SignalingError)?;
+                let sdp = self
+                    .set_local_description(RtcSdpType::Answer, answer)
+                    .await?;
+                (self.send_signal)(Signal::Answer { sdp });
+                Ok(())
+            }
+            Signal::Answer { sdp } => self.set_remote_description(RtcSdpType::Answer, &sdp).await,
+            Signal::IceCandidate {
+                candidate,
+                sdp_mid,
+                sdp_m_line_index,
+            } => {
+                let mut init = RtcIceCandidateInit::new(&candidate);
+                init.sdp_mid(sdp_mid.as_deref())
+                    .sdp_m_line_index(sdp_m_line_index);
+                if self.pc.remote_description().is_some() {
+                    self.add_ice_candidate(&init).await
+                } else {
+                    self.pending_candidates.borrow_mut().push(init);
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Peer {
+    fn drop(&mut self) {
+        self.pc.set_onicecandidate(None);
+        self.pc.set_onconnectionstatechange(None);
+        self.pc.set_ondatachannel(None);
+        self.pc.close();
+    }
+}
+
+// ------ PeerConnection ------
+
+/// Peer connection. It's closed when all its clones are dropped.
+#[derive(Clone)]
+pub struct PeerConnection(Rc<Peer>);
+
+impl fmt::Debug for PeerConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PeerConnection")
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+impl PeerConnection {
+    /// Create a builder. Messages returned from the builder's callbacks are sent to your app.
+    pub fn builder<Ms: 'static, GMs: 'static>(orders: &impl Orders<Ms, GMs>) -> Builder<Ms> {
+        let (app, msg_mapper) = (orders.clone_app(), orders.msg_mapper());
+        Builder {
+            ice_servers: Vec::new(),
+            dispatch: Rc::new(move |msg| app.update(msg_mapper(msg))),
+            on_signal: None,
+            on_state_change: None,
+            on_data_channel: None,
+        }
+    }
+
+    /// Create a data channel. Create channels before `create_offer`,
+    /// otherwise the connection has to be renegotiated.
+    pub fn create_data_channel(&self, label: &str) -> DataChannel {
+        DataChannel::new(self.0.pc.create_data_channel(label))
+    }
+
+    /// Start the connection - the offer is passed to `Builder::on_signal`.
+    pub fn create_offer(&self) -> impl Future<Output = Result<()>> {
+        let peer = Rc::clone(&self.0);
+        async move { peer.create_offer().await }
+    }
+
+    /// Apply the signal from the remote peer. The answer to an offer is passed to `Builder::on_signal`.
+    ///
+    /// ICE candidates received before the offer or answer are applied once it arrives.
+    pub fn handle_signal(&self, signal: Signal) -> impl Future<Output = Result<()>> {
+        let peer = Rc::clone(&self.0);
+        async move { peer.handle_signal(signal).await }
+    }
+
+    pub fn state(&self) -> State {
+        self.0.pc.connection_state()
+    }
+
+    pub fn close(&self) {
+        self.0.pc.close();
+    }
+
+    pub fn raw_peer_connection(&self) -> &web_sys::RtcPeerConnection {
+        &self.0.pc
+    }
+}
+
+// ------ DataChannel ------
+
+enum RawEvent {
+    Open,
+    Message(JsValue),
+    Close,
+}
+
+struct ChannelCallbacks {
+    on_open: Closure<dyn Fn(JsValue)>,
+    on_message: Closure<dyn Fn(JsValue)>,
+    on_close: Closure<dyn Fn(JsValue)>,
+}
+
+struct Channel {
+    channel: RtcDataChannel,
+    receiver: RefCell<Option<UnboundedReceiver<RawEvent>>>,
+    _callbacks: ChannelCallbacks,
+}
+
+impl Channel {
+    fn send_json<T: Serialize + ?Sized>(&self, data: &T) -> Result<()> {
+        let text =
+            serde_json::to_string(data).map_err(|error| RtcError::SerdeError(Rc::new(error)))?;
+        self.channel
+            .send_with_str(&text)
+            .map_err(RtcError::SendError)
+    }
+}
+
+impl Drop for Channel {
+    fn drop(&mut self) {
+        self.channel.set_onopen(None);
+        self.channel.set_onmessage(None);
+        self.channel.set_onclose(None);
+        self.channel.close();
+    }
+}
+
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/RTCDataChannel)
+///
+/// Received messages are buffered until the channel is split. It's closed when dropped.
+pub struct DataChannel(Rc<Channel>);
+
+impl fmt::Debug for DataChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DataChannel")
+            .field("label", &self.label())
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+impl DataChannel {
+    fn new(channel: RtcDataChannel) -> Self {
+        channel.set_binary_type(RtcDataChannelType::Arraybuffer);
+        let (sender, receiver) = unbounded();
+        let send = move |event| {
+            // The receiver has been dropped when nobody listens anymore.
+            let _ = sender.unbounded_send(event);
+        };
+        let send = Rc::new(send) as Rc<dyn Fn(RawEvent)>;
+        let callbacks = ChannelCallbacks {
+            on_open: {
+                let send = Rc::clone(&send);
+                callback(move |_| send(RawEvent::Open))
+            },
+            on_message: {
+                let send = Rc::clone(&send);
+                callback(move |event| {
+                    let event: web_sys::MessageEvent = event.unchecked_into();
+                    send(RawEvent::Message(event.data()));
+                })
+            },
+            on_close: callback(move |_| send(RawEvent::Close)),
+        };
+        channel.set_onopen(Some(as_function(&callbacks.on_open)));
+        channel.set_onmessage(Some(as_function(&callbacks.on_message)));
+        channel.set_onclose(Some(as_function(&callbacks.on_close)));
+
+        Self(Rc::new(Channel {
+            channel,
+            receiver: RefCell::new(Some(receiver)),
+            _callbacks: callbacks,
+        }))
+    }
+
+    pub fn label(&self) -> String {
+        self.0.channel.label()
+    }
+
+    pub fn state(&self) -> DataChannelState {
+        self.0.channel.ready_state()
+    }
+
+    pub fn raw_data_channel(&self) -> &RtcDataChannel {
+        &self.0.channel
+    }
+
+    /// Split into a sender of `Out` messages and a stream of `In` messages.
+    /// Messages are sent as JSON text.
+    ///
+    /// The stream ends after `DataChannelEvent::Closed`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the channel has been already split.
+    pub fn split<Out: Serialize, In: DeserializeOwned>(
+        self,
+    ) -> (DataChannelSender<Out>, DataChannelReceiver<In>) {
+        let receiver = self
+            .0
+            .receiver
+            .borrow_mut()
+            .take()
+            .expect("data channel has been already split");
+        (
+            DataChannelSender {
+                channel: Rc::clone(&self.0),
+                message_type: PhantomData,
+            },
+            DataChannelReceiver {
+                receiver,
+                closed: false,
+                _channel: self.0,
+                message_type: PhantomData,
+            },
+        )
+    }
+}
+
+// ------ DataChannelSender ------
+
+/// Typed sending half of `DataChannel`. See `DataChannel::split`.
+pub struct DataChannelSender<Out> {
+    channel: Rc<Channel>,
+    message_type: PhantomData<fn(Out)>,
+}
+
+impl<Out> Clone for DataChannelSender<Out> {
+    fn clone(&self) -> Self {
+        Self {
+            channel: Rc::clone(&self.channel),
+            message_type: PhantomData,
+        }
+    }
+}
+
+impl<Out> fmt::Debug for DataChannelSender<Out> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DataChannelSender")
+            .field("label", &self.channel.channel.label())
+            .finish()
+    }
+}
+
+impl<Out: Serialize> DataChannelSender<Out> {
+    /// # Errors
+    ///
+    /// Returns error when serialization fails or the channel isn't open.
+    pub fn send(&self, message: &Out) -> Result<()> {
+        self.channel.send_json(message)
+    }
+
+    /// Send raw bytes - the remote receiver reports them as `RtcError::DataError`.
+    /// Use it with `raw_data_channel` listeners.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RtcError::SendError` when the channel isn't open.
+    pub fn send_bytes(&self, bytes: &[u8]) -> Result<()> {
+        self.channel
+            .channel
+            .send_with_u8_array(bytes)
+            .map_err(RtcError::SendError)
+    }
+
+    pub fn state(&self) -> DataChannelState {
+        self.channel.channel.ready_state()
+    }
+}
+
+// ------ DataChannelReceiver ------
+
+/// Event received by `DataChannelReceiver`.
+#[derive(Debug, Clone)]
+pub enum DataChannelEvent<In> {
+    Opened,
+    Message(In),
+    /// Binary message or message that can't be deserialized.
+    InvalidMessage(RtcError),
+    Closed,
+}
+
+/// Typed stream of `DataChannel` events. See `DataChannel::split`.
+pub struct DataChannelReceiver<In> {
+    receiver: UnboundedReceiver<RawEvent>,
+    closed: bool,
+    _channel: Rc<Channel>,
+    message_type: PhantomData<fn() -> In>,
+}
+
+impl<In> fmt::Debug for DataChannelReceiver<In> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DataChannelReceiver").finish()
+    }
+}
+
+impl<In: DeserializeOwned> Stream for DataChannelReceiver<In> {
+    type Item = DataChannelEvent<In>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        if self.closed {
+            return Poll::Ready(None);
+        }
+        let event = match Stream::poll_next(Pin::new(&mut self.receiver), cx) {
+            Poll::Ready(Some(event)) => event,
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => return Poll::Pending,
+        };
+        Poll::Ready(Some(match event {
+            RawEvent::Open => DataChannelEvent::Opened,
+            RawEvent::Message(data) => match data.as_string() {
+                Some(text) => serde_json::from_str(&text).map_or_else(
+                    |error| DataChannelEvent::InvalidMessage(RtcError::SerdeError(Rc::new(error))),
+                    DataChannelEvent::Message,
+                ),
+                None => DataChannelEvent::InvalidMessage(RtcError::DataError(
+                    "message data is not a string",
+                )),
+            },
+            RawEvent::Close => {
+                self.closed = true;
+                DataChannelEvent::Closed
+            }
+        }))
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn signal_json() {
+        let signal = Signal::IceCandidate {
+            candidate: "candidate:1 1 udp 2122260223 192.168.1.2 54321 typ host".to_owned(),
+            sdp_mid: Some("0".to_owned()),
+            sdp_m_line_index: Some(0),
+        };
+        let json = serde_json::to_string(&signal).unwrap();
+        assert!(json.starts_with(r#"{"type":"ice_candidate","candidate":"#));
+        assert_eq!(serde_json::from_str::<Signal>(&json).unwrap(), signal);
+
+        let offer: Signal = serde_json::from_str(r#"{"type":"offer","sdp":"v=0"}"#).unwrap();
+        assert_eq!(
+            offer,
+            Signal::Offer {
+                sdp: "v=0".to_owned()
+            }
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//! The current [Selection](https://developer.mozilla.org/en-US/docs/Web/API/Selection)
+//! expressed as text offsets relative to an element.
+//!
+//! Offsets are counted in `char`s of the element's text content, so they survive re-renders
+//! which replace DOM nodes but keep the text - save the selection before the change
+//! and restore it after the render. Subscribe to `subs::SelectionChanged` to track it.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::SelectionChanged => {
+//!    model.selection = selection::get(&model.editor);
+//!}
+//!Msg::Highlight => {
+//!    let saved = selection::get(&model.editor);
+//!    model.highlight = saved.map(|saved| (saved.start(), saved.end()));
+//!    orders.after_next_render(move |_| Msg::RestoreSelection(saved));
+//!}
+//!Msg::RestoreSelection(Some(saved)) => {
+//!    selection::set(&model.editor, saved);
+//!}
+//! ```
+
+use crate::browser::util::{document, window};
+use crate::virtual_dom::ElRef;
+use std::convert::TryFrom;
+use wasm_bindgen::JsCast;
+
+// ------ TextSelection ------
+
+/// Selection inside an element; `anchor` is where the user has started selecting
+/// and `focus` where the caret is - `focus` is less than `anchor` for backward selections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextSelection {
+    pub anchor: u32,
+    pub focus: u32,
+}
+
+impl TextSelection {
+    /// Collapsed selection - a caret at `offset`.
+    pub const fn caret(offset: u32) -> Self {
+        Self {
+            anchor: offset,
+            focus: offset,
+        }
+    }
+
+    pub fn start(self) -> u32 {
+        self.anchor.min(self.focus)
+    }
+
+    pub fn end(self) -> u32 {
+        self.anchor.max(self.focus)
+    }
+
+    pub const fn is_collapsed(self) -> bool {
+        self.anchor == self.focus
+    }
+
+    pub const fn is_backward(self) -> bool {
+        self.focus < self.anchor
+    }
+}
+
+// ------ get / set ------
+
+/// The current selection when both its ends are inside the referenced element.
+pub fn get<E: Clone + JsCast>(root: &ElRef<E>) -> Option<TextSelection> {
+    let root = root.map_type::<web_sys::Node>().get()?;
+    let selection = raw()?;
+    Some(TextSelection {
+        anchor: text_offset(&root, &selection.anchor_node()?, selection.anchor_offset())?,
+        focus: text_offset(&root, &selection.focus_node()?, selection.focus_offset())?,
+    })
+}
+
+/// Select the text inside the referenced element; offsets greater than the text length
+/// are moved to its end.
+///
+/// Returns `false` when the element doesn't exist or the selection can't be changed.
+///
+/// _Note:_ The selection is displayed in an input-like element only when the element is focused.
+pub fn set<E: Clone + JsCast>(root: &ElRef<E>, text_selection: TextSelection) -> bool {
+    let root = match root.map_type::<web_sys::Node>().get() {
+        Some(root) => root,
+        None => return false,
+    };
+    let (anchor_node, anchor_offset) = dom_position(&root, text_selection.anchor);
+    let (focus_node, focus_offset) = dom_position(&root, text_selection.focus);
+    raw().map_or(false, |selection| {
+        selection
+            .set_base_and_extent(&anchor_node, anchor_offset, &focus_node, focus_offset)
+            .is_ok()
+    })
+}
+
+/// Offset of the caret (the selection focus) inside the referenced element.
+pub fn caret<E: Clone + JsCast>(root: &ElRef<E>) -> Option<u32> {
+    get(root).map(|text_selection| text_selection.focus)
+}
+
+/// Move the caret to `offset` inside the referenced element. See `set`.
+pub fn set_caret<E: Clone + JsCast>(root: &ElRef<E>, offset: u32) -> bool {
+    set(root, TextSelection::caret(offset))
+}
+
+/// Select the whole content of the referenced element.
+pub fn select_all<E: Clone + JsCast>(root: &ElRef<E>) -> bool {
+    match (root.map_type::<web_sys::Node>().get(), raw()) {
+        (Some(root), Some(selection)) => selection.select_all_children(&root).is_ok(),
+        _ => false,
+    }
+}
+
+/// Deselect everything.
+pub fn clear() {
+    if let Some(selection) = raw() {
+        // `removeAllRanges` never fails.
+        selection.remove_all_ranges().ok();
+    }
+}
+
+/// The selected text in the whole document.
+pub fn text() -> String {
+    raw()
+        .map(|selection| String::from(selection.unchecked_ref::<js_sys::Object>().to_string()))
+        .unwrap_or_default()
+}
+
+/// Viewport position and size of the selection - e.g. to show a floating toolbar above it.
+/// `None` when nothing is selected.
+pub fn bounding_rect() -> Option<web_sys::DomRect> {
+    let selection = raw().filter(|selection| selection.range_count() > 0)?;
+    selection
+        .get_range_at(0)
+        .ok()
+        .map(|range| range.get_bounding_client_rect())
+}
+
+/// `window.getSelection()`.
+pub fn raw() -> Option<web_sys::Selection> {
+    window().get_selection().ok().flatten()
+}
+
+// ------ Offset conversion ------
+
+/// Count `char`s from the start of `root` to the DOM position; `None` when it's outside `root`.
+fn text_offset(root: &web_sys::Node, node: &web_sys::Node, offset: u32) -> Option<u32> {
+    if !root.contains(Some(node)) {
+        return None;
+    }
+    let range = document().create_range().ok()?;
+    range.select_node_contents(root).ok()?;
+    range.set_end(node, offset).ok()?;
+    let text = String::from(range.unchecked_ref::<js_sys::Object>().to_string());
+    u32::try_from(text.chars().count()).ok()
+}
+
+/// Find the text node and its UTF-16 offset for the `char` offset inside `root`.
+fn dom_position(root: &web_sys::Node, offset: u32) -> (web_sys::Node, u32) {
+    let mut remaining = offset;
+    let mut last_text_node = None;
+    let mut stack = vec![root.clone()];
+    while let Some(node) = stack.pop() {
+        if node.node_type() == web_sys::Node::TEXT_NODE {
+            let text = node.text_content().unwrap_or_default();
+            let length = u32::try_from(text.chars().count()).unwrap_or(u32::MAX);
+            if remaining <= length {
+                return (node, utf16_offset(&text, remaining));
+            }
+            remaining -= length;
+            last_text_node = Some((node, text));
+            continue;
+        }
+        let children = node.child_nodes();
+        // Reversed to pop children in the document order.
+        for index in (0..children.length()).rev() {
+            if let Some(child) = children.get(index) {
+                stack.push(child);
+            }
+        }
+    }
+    match last_text_node {
+        Some((node, text)) => {
+            let end = utf16_offset(&text, u32::MAX);
+            (node, end)
+        }
+        None => (root.clone(), 0),
+    }
+}
+
+fn utf16_offset(text: &str, chars: u32) -> u32 {
+    let units: usize = text
+        .chars()
+        .take(usize::try_from(chars).unwrap_or(usize::MAX))
+        .map(char::len_utf16)
+        .sum();
+    u32::try_from(units).unwrap_or(u32::MAX)
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::util::body;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn set_and_get_selection() {
+        let root = document().create_element("div").unwrap();
+        root.set_inner_html("Hello <b>wörld</b> 🌱 seed");
+        body().append_child(&root).unwrap();
+        let mut root_ref = ElRef::<web_sys::HtmlElement>::default();
+        root_ref.shared_node_ws.set(root.clone().into());
+
+        let backward = TextSelection {
+            anchor: 14,
+            focus: 6,
+        };
+        assert!(set(&root_ref, backward));
+        assert_eq!(get(&root_ref), Some(backward));
+        assert_eq!(text(), "wörld 🌱 ");
+
+        assert!(set_caret(&root_ref, 100));
+        assert_eq!(caret(&root_ref), Some(18));
+
+        clear();
+        assert_eq!(get(&root_ref), None);
+        root.remove();
+    }
+}
@@ -1,3 +1,4 @@
 pub mod fetch;
 pub mod routing;
+pub mod rpc;
 pub mod storage;
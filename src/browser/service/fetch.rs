@@ -1,7 +1,8 @@
 //! High-level interface for `web_sys` HTTP requests.
 use std::future::Future;
 
-use gloo_timers::callback::Timeout;
+use crate::app::{clock, CmdHandle, CmdManager};
+use futures::future::{FutureExt, LocalBoxFuture};
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json;
 use std::{borrow::Cow, cell::RefCell, collections::HashMap, convert::identity, rc::Rc};
@@ -30,6 +31,72 @@ pub type DataResult<T> = Result<T, DataError>;
 
 type Json = String;
 
+pub mod auth;
+pub mod cached;
+pub mod endpoint;
+#[cfg(feature = "isomorphic_fetch_trait")]
+pub mod isomorphic;
+pub mod mock;
+pub mod multipart;
+
+pub use cached::{
+    Cache, CacheApiBackend, CacheBackend, CachedResponse, LocalStorageBackend, MemoryBackend,
+};
+pub use endpoint::{Api, Endpoint};
+pub use mock::{MockFetch, MockResponse, RecordedRequest};
+pub use multipart::{Multipart, Part};
+
+// ---------- Transport ----------
+
+/// Sends requests created by `Request.fetch*` methods.
+///
+/// The default transport calls the browser's `fetch`.
+/// You can replace it with `set_transport` - e.g. with `MockFetch` in tests.
+pub trait Transport {
+    /// Send the `request`. `init` contains all request options including the abort signal.
+    fn send(
+        &self,
+        request: &Request,
+        init: web_sys::RequestInit,
+    ) -> LocalBoxFuture<'static, Result<web_sys::Response, JsValue>>;
+}
+
+/// `Transport` which calls the browser's `fetch`.
+///
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/WindowOrWorkerGlobalScope/fetch)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrowserTransport;
+
+impl Transport for BrowserTransport {
+    fn send(
+        &self,
+        request: &Request,
+        init: web_sys::RequestInit,
+    ) -> LocalBoxFuture<'static, Result<web_sys::Response, JsValue>> {
+        let fetch_promise = web_sys::window()
+            .expect("fetch: cannot find window")
+            .fetch_with_str_and_init(&request.url, &init);
+
+        JsFuture::from(fetch_promise)
+            .map(|result| result.map(Into::into))
+            .boxed_local()
+    }
+}
+
+thread_local! {
+    static TRANSPORT: RefCell<Rc<dyn Transport>> = RefCell::new(Rc::new(BrowserTransport));
+}
+
+/// Replace the transport used by all subsequent requests.
+pub fn set_transport(transport: impl Transport + 'static) {
+    TRANSPORT.with(|current| current.replace(Rc::new(transport)));
+}
+
+/// Restore the default `BrowserTransport`.
+pub fn reset_transport() {
+    set_transport(BrowserTransport);
+}
+
 // ---------- FetchObject ----------
 
 #[derive(Debug, Clone)]
@@ -77,6 +144,57 @@ impl<T> FetchObject<T> {
     }
 }
 
+impl FetchObject<String> {
+    /// Try to deserialize the body - it's used in `Request.fetch_json` and `cached`.
+    fn deserialize_json<T: DeserializeOwned>(self) -> FetchObject<T> {
+        let fetch_result = self.result;
+        let request = self.request;
+
+        match fetch_result {
+            // There was problem with fetching - just change generic parameter from String to T.
+            Err(request_error) => FetchObject::<T> {
+                request,
+                result: Err(request_error),
+            },
+            Ok(response) => {
+                match response.data {
+                    // There was problem with converting to String
+                    // - just change generic parameter from String to T.
+                    Err(data_error) => FetchObject::<T> {
+                        request,
+                        result: Ok(ResponseWithDataResult {
+                            raw: response.raw,
+                            status: response.status,
+                            data: Err(data_error),
+                        }),
+                    },
+                    Ok(text) => {
+                        match serde_json::from_str(&text) {
+                            // Deserialization failed.
+                            Err(serde_error) => FetchObject::<T> {
+                                request,
+                                result: Ok(ResponseWithDataResult {
+                                    raw: response.raw,
+                                    status: response.status,
+                                    data: Err(DataError::SerdeError(Rc::new(serde_error), text)),
+                                }),
+                            },
+                            Ok(value) => FetchObject::<T> {
+                                request,
+                                result: Ok(ResponseWithDataResult {
+                                    raw: response.raw,
+                                    status: response.status,
+                                    data: Ok(value),
+                                }),
+                            },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 // ---------- Fails ----------
 
 #[derive(Debug, Clone)]
@@ -100,12 +218,21 @@ pub enum DataError {
 
 // ---------- RequestController ----------
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 /// It allows to abort request or disable request's timeout.
 /// You can get it by calling method `Request.controller`.
 pub struct RequestController {
     abort_controller: Rc<web_sys::AbortController>,
-    timeout_handle: Rc<RefCell<Option<Timeout>>>,
+    timeout_handle: Rc<RefCell<Option<CmdHandle>>>,
+}
+
+impl std::fmt::Debug for RequestController {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RequestController")
+            .field("abort_controller", &self.abort_controller)
+            .field("timeout_enabled", &self.timeout_handle.borrow().is_some())
+            .finish()
+    }
 }
 
 impl RequestController {
@@ -189,8 +316,14 @@ impl Status {
 
 impl From<&web_sys::Response> for Status {
     fn from(response: &web_sys::Response) -> Self {
-        let text = response.status_text();
-        match response.status() {
+        Self::new(response.status(), response.status_text())
+    }
+}
+
+impl Status {
+    /// Create `Status` with the category derived from the `code`.
+    pub fn new(code: u16, text: String) -> Self {
+        match code {
             code @ 100..=199 => Status {
                 code,
                 text,
@@ -246,7 +379,7 @@ pub struct ResponseWithDataResult<T> {
 /// HTTP Method types.
 ///
 /// [https://developer.mozilla.org/en-US/docs/Web/HTTP/Methods](https://developer.mozilla.org/en-US/docs/Web/HTTP/Methods)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Method {
     Get,
     Head,
@@ -260,7 +393,7 @@ pub enum Method {
 }
 
 impl Method {
-    fn as_str(&self) -> &str {
+    pub fn as_str(&self) -> &str {
         match *self {
             Method::Get => "GET",
             Method::Head => "HEAD",
@@ -352,6 +485,19 @@ impl Request {
             .body_json(data)
     }
 
+    /// Set body to `multipart/form-data` - e.g. to upload files.
+    /// _Note_: Don't set `Content-Type` header manually - the browser adds it with the boundary.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the browser fails to create `FormData`.
+    pub fn multipart(self, multipart: &Multipart) -> Self {
+        let form_data = multipart
+            .to_form_data()
+            .expect("fetch: create FormData - failed");
+        self.body(form_data.into())
+    }
+
     /// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/Request/cache)
     pub fn cache(mut self, cache: web_sys::RequestCache) -> Self {
         self.cache = Some(cache);
@@ -395,6 +541,9 @@ impl Request {
     }
 
     /// Enable request timeout and set it to given milliseconds.
+    /// The request is aborted through its `AbortController` when the time is up.
+    ///
+    /// It respects `testing::TestClock` in tests.
     pub fn timeout(mut self, millis: u32) -> Self {
         self.timeout = Some(millis);
         self
@@ -431,6 +580,10 @@ impl Request {
     /// if you want to get body data, you have to use field `raw` to get raw `web_sys::Response`.
     /// (Or use methods like `fetch_string` / `fetch_json`.)
     ///
+    /// The request is aborted when the `Future` is dropped before it's resolved.
+    /// So you can pass it to `orders.perform_cmd_with_handle` to abort the in-flight request
+    /// by dropping the returned `CmdHandle`.
+    ///
     /// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/Fetch_API/Using_Fetch)
     ///
     /// # Example
@@ -441,11 +594,20 @@ impl Request {
     ///        .fetch(Msg::Fetched)
     ///        .await
     ///}
+    ///
+    ///model.request_handle = Some(orders.perform_cmd_with_handle(
+    ///    fetch::Request::new(get_request_url())
+    ///        .timeout(5000)
+    ///        .fetch_json_data(Msg::Fetched)
+    ///));
+    ///// Abort the request.
+    ///model.request_handle = None;
     /// ```
     pub async fn fetch<U>(self, f: impl FnOnce(FetchObject<()>) -> U) -> U
     where
         U: 'static,
     {
+        let abort_guard = AbortOnDrop::new(&self.controller);
         let fetch_result = self
             .send_request()
             .await
@@ -455,6 +617,7 @@ impl Request {
                 data: Ok(()),
             })
             .map_err(|js_value_error| RequestError::DomException(js_value_error.into()));
+        abort_guard.disarm();
         f(FetchObject {
             request: self,
             result: fetch_result,
@@ -467,6 +630,8 @@ impl Request {
     where
         U: 'static,
     {
+        // Abort also reading of the body when the future is dropped.
+        let abort_guard = AbortOnDrop::new(&self.controller);
         let fetch_object = self.fetch(identity).await;
         let fetch_result = fetch_object.result;
         let request = fetch_object.request;
@@ -520,6 +685,7 @@ impl Request {
                 }
             }
         };
+        abort_guard.disarm();
         f(fetch_object)
     }
 
@@ -541,52 +707,7 @@ impl Request {
         T: DeserializeOwned + 'static,
         U: 'static,
     {
-        let fetch_object = self.fetch_string(identity).await;
-        let fetch_result = fetch_object.result;
-        let request = fetch_object.request;
-
-        let fetch_object = match fetch_result {
-            // There was problem with fetching - just change generic parameter from String to T.
-            Err(request_error) => FetchObject::<T> {
-                request,
-                result: Err(request_error),
-            },
-            Ok(response) => {
-                match response.data {
-                    // There was problem with converting to String
-                    // - just change generic parameter from String to T.
-                    Err(data_error) => FetchObject::<T> {
-                        request,
-                        result: Ok(ResponseWithDataResult {
-                            raw: response.raw,
-                            status: response.status,
-                            data: Err(data_error),
-                        }),
-                    },
-                    Ok(text) => {
-                        match serde_json::from_str(&text) {
-                            // Deserialization failed.
-                            Err(serde_error) => FetchObject::<T> {
-                                request,
-                                result: Ok(ResponseWithDataResult {
-                                    raw: response.raw,
-                                    status: response.status,
-                                    data: Err(DataError::SerdeError(Rc::new(serde_error), text)),
-                                }),
-                            },
-                            Ok(value) => FetchObject::<T> {
-                                request,
-                                result: Ok(ResponseWithDataResult {
-                                    raw: response.raw,
-                                    status: response.status,
-                                    data: Ok(value),
-                                }),
-                            },
-                        }
-                    }
-                }
-            }
-        };
+        let fetch_object = self.fetch_string(identity).await.deserialize_json();
         f(fetch_object)
     }
 
@@ -606,12 +727,8 @@ impl Request {
 
     async fn send_request(&self) -> Result<web_sys::Response, JsValue> {
         let request_init = self.init_request_and_start_timeout();
-
-        let fetch_promise = web_sys::window()
-            .expect("fetch: cannot find window")
-            .fetch_with_str_and_init(&self.url, &request_init);
-
-        JsFuture::from(fetch_promise).await.map(Into::into)
+        let transport = TRANSPORT.with(|transport| Rc::clone(&transport.borrow()));
+        transport.send(self, request_init).await
     }
 
     fn init_request_and_start_timeout(&self) -> web_sys::RequestInit {
@@ -670,11 +787,13 @@ impl Request {
         }
 
         // timeout
-        if let Some(timeout) = &self.timeout {
+        if let Some(timeout) = self.timeout {
             let abort_controller = self.controller.clone();
             *self.controller.timeout_handle.borrow_mut() = Some(
                 // abort request on timeout
-                Timeout::new(*timeout, move || abort_controller.abort()),
+                CmdManager::perform_cmd_with_handle(
+                    clock::sleep(timeout).map(move |_| abort_controller.abort()),
+                ),
             );
         }
 
@@ -685,3 +804,27 @@ impl Request {
         init
     }
 }
+
+// ---------- AbortOnDrop ----------
+
+/// Aborts the request when the guard is dropped before `disarm` is called
+/// - i.e. when the fetch future has been dropped before completion.
+struct AbortOnDrop(Option<RequestController>);
+
+impl AbortOnDrop {
+    fn new(controller: &RequestController) -> Self {
+        Self(Some(controller.clone()))
+    }
+
+    fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        if let Some(controller) = self.0.take() {
+            controller.abort();
+        }
+    }
+}
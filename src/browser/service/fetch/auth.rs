@@ -0,0 +1,554 @@
+//! Auth session - attaches access tokens to requests and refreshes them on `401 Unauthorized`.
+//!
+//! The session is optional - it's active only after `SessionBuilder::install`,
+//! which wraps the current fetch `Transport`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!fn refresh(tokens: Tokens) -> impl Future<Output = Option<Tokens>> {
+//!    fetch::Request::new("/api/auth/refresh")
+//!        .method(fetch::Method::Post)
+//!        .send_json(&tokens.refresh)
+//!        .fetch_json_data(Result::ok)
+//!}
+//!
+//!fn init(_: Url, orders: &mut impl Orders<Msg>) -> Init<Model> {
+//!    let session = Session::builder(WebStorage::local("session"))
+//!        .refresh_with(refresh)
+//!        .exclude_prefix("/api/auth/")
+//!        .install(orders);
+//!    orders.subscribe(|_: SessionExpired| Msg::SessionExpired);
+//!    Init::new(Model { session })
+//!}
+//!
+//!fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+//!    match msg {
+//!        Msg::LoggedIn(tokens) => model.session.set_tokens(tokens),
+//!        Msg::SessionExpired => orders.send_msg(Msg::ShowLogin),
+//!        ...
+//!    }
+//!}
+//! ```
+
+use super::{Request, Transport, TRANSPORT};
+use crate::app::Orders;
+use crate::browser::service::storage;
+use futures::future::{self, FutureExt, LocalBoxFuture, Shared};
+use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, fmt, future::Future, rc::Rc};
+use wasm_bindgen::{JsCast, JsValue};
+
+type Refresher = Rc<dyn Fn(Tokens) -> LocalBoxFuture<'static, Option<Tokens>>>;
+
+const UNAUTHORIZED: u16 = 401;
+
+// ------ Tokens ------
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tokens {
+    pub access: String,
+    /// Token sent to the refresh endpoint. The session can't be refreshed without it.
+    pub refresh: Option<String>,
+}
+
+impl Tokens {
+    pub fn new(access: impl Into<String>) -> Self {
+        Self {
+            access: access.into(),
+            refresh: None,
+        }
+    }
+
+    pub fn with_refresh(mut self, refresh: impl Into<String>) -> Self {
+        self.refresh = Some(refresh.into());
+        self
+    }
+}
+
+// ------ SessionExpired ------
+
+/// Notification sent when the session can't be refreshed.
+/// Handle it with `orders.subscribe(|_: SessionExpired| Msg::SessionExpired)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionExpired;
+
+// ------ TokenStorage ------
+
+/// Persists `Tokens` - e.g. to keep the user logged in after page reload.
+pub trait TokenStorage {
+    fn load(&self) -> Option<Tokens>;
+    fn save(&self, tokens: &Tokens);
+    fn clear(&self);
+}
+
+/// Keeps tokens only in memory - they are lost on page reload.
+#[derive(Debug, Default)]
+pub struct MemoryStorage(RefCell<Option<Tokens>>);
+
+impl TokenStorage for MemoryStorage {
+    fn load(&self) -> Option<Tokens> {
+        self.0.borrow().clone()
+    }
+
+    fn save(&self, tokens: &Tokens) {
+        self.0.replace(Some(tokens.clone()));
+    }
+
+    fn clear(&self) {
+        self.0.replace(None);
+    }
+}
+
+/// Stores tokens as JSON under the `key` in `LocalStorage` or `SessionStorage`.
+#[derive(Debug, Clone)]
+pub struct WebStorage {
+    storage: storage::Storage,
+    key: String,
+}
+
+impl WebStorage {
+    /// # Panics
+    ///
+    /// Panics when `LocalStorage` isn't available.
+    pub fn local(key: impl Into<String>) -> Self {
+        Self {
+            storage: storage::get_storage().expect("get `LocalStorage`"),
+            key: key.into(),
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics when `SessionStorage` isn't available.
+    pub fn session(key: impl Into<String>) -> Self {
+        let storage = web_sys::window()
+            .expect("get `window`")
+            .session_storage()
+            .ok()
+            .flatten()
+            .expect("get `SessionStorage`");
+        Self {
+            storage,
+            key: key.into(),
+        }
+    }
+}
+
+impl TokenStorage for WebStorage {
+    fn load(&self) -> Option<Tokens> {
+        // Ignore invalid data - e.g. saved by an older app version.
+        self.storage
+            .get_item(&self.key)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+    }
+
+    fn save(&self, tokens: &Tokens) {
+        storage::store_data(&self.storage, &self.key, tokens);
+    }
+
+    fn clear(&self) {
+        self.storage
+            .remove_item(&self.key)
+            .expect("remove item from `Storage`");
+    }
+}
+
+// ------ SessionBuilder ------
+
+/// Created by `Session::builder`.
+pub struct SessionBuilder {
+    storage: Box<dyn TokenStorage>,
+    refresher: Option<Refresher>,
+    header: String,
+    scheme: String,
+    excluded_prefixes: Vec<String>,
+}
+
+impl fmt::Debug for SessionBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionBuilder")
+            .field("refresh", &self.refresher.is_some())
+            .field("header", &self.header)
+            .field("scheme", &self.scheme)
+            .field("excluded_prefixes", &self.excluded_prefixes)
+            .finish()
+    }
+}
+
+impl SessionBuilder {
+    /// Set the function which exchanges expired tokens for new ones - e.g. by calling
+    /// your refresh endpoint. Return `None` when the refresh fails.
+    ///
+    /// _Note:_ Requests sent by `refresh` must not resolve to `401` through the session,
+    /// so send them to an excluded URL (see `exclude_prefix`).
+    pub fn refresh_with<F>(mut self, refresh: impl Fn(Tokens) -> F + 'static) -> Self
+    where
+        F: Future<Output = Option<Tokens>> + 'static,
+    {
+        self.refresher = Some(Rc::new(move |tokens| refresh(tokens).boxed_local()));
+        self
+    }
+
+    /// Set the header with the access token. Default is `Authorization`.
+    pub fn header(mut self, name: impl Into<String>) -> Self {
+        self.header = name.into();
+        self
+    }
+
+    /// Set the scheme written before the access token. Default is `Bearer`.
+    /// An empty scheme means the header contains only the token.
+    pub fn scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.scheme = scheme.into();
+        self
+    }
+
+    /// Don't touch requests with URL starting with `prefix` - e.g. login and refresh endpoints
+    /// or third-party APIs.
+    pub fn exclude_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.excluded_prefixes.push(prefix.into());
+        self
+    }
+
+    /// Activate the session - all subsequent fetch requests go through it.
+    /// `SessionExpired` is sent to your app when the session can't be refreshed.
+    pub fn install<Ms: 'static, GMs: 'static>(self, orders: &impl Orders<Ms, GMs>) -> Session {
+        let app = orders.clone_app();
+        self.install_with(Rc::new(move || app.notify(SessionExpired)))
+    }
+
+    fn install_with(self, on_expired: Rc<dyn Fn()>) -> Session {
+        let session = Session(Rc::new(SessionInner {
+            tokens: RefCell::new(self.storage.load()),
+            storage: self.storage,
+            refresher: self.refresher,
+            header: self.header,
+            scheme: self.scheme,
+            excluded_prefixes: self.excluded_prefixes,
+            refreshing: RefCell::new(None),
+            on_expired,
+        }));
+        let inner = TRANSPORT.with(|transport| Rc::clone(&transport.borrow()));
+        super::set_transport(SessionTransport {
+            session: session.clone(),
+            inner,
+        });
+        session
+    }
+}
+
+// ------ Session ------
+
+struct SessionInner {
+    tokens: RefCell<Option<Tokens>>,
+    storage: Box<dyn TokenStorage>,
+    refresher: Option<Refresher>,
+    header: String,
+    scheme: String,
+    excluded_prefixes: Vec<String>,
+    refreshing: RefCell<Option<Shared<LocalBoxFuture<'static, bool>>>>,
+    on_expired: Rc<dyn Fn()>,
+}
+
+/// Handle to the installed session. Store it in your `Model`. Cloning is cheap.
+#[derive(Clone)]
+pub struct Session(Rc<SessionInner>);
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("authenticated", &self.is_authenticated())
+            .field("refreshing", &self.is_refreshing())
+            .finish()
+    }
+}
+
+impl Session {
+    /// Create a builder. Tokens are loaded from the `storage` on `install`.
+    pub fn builder(storage: impl TokenStorage + 'static) -> SessionBuilder {
+        SessionBuilder {
+            storage: Box::new(storage),
+            refresher: None,
+            header: "Authorization".to_owned(),
+            scheme: "Bearer".to_owned(),
+            excluded_prefixes: Vec::new(),
+        }
+    }
+
+    pub fn tokens(&self) -> Option<Tokens> {
+        self.0.tokens.borrow().clone()
+    }
+
+    pub fn is_authenticated(&self) -> bool {
+        self.0.tokens.borrow().is_some()
+    }
+
+    /// Save tokens - e.g. after login.
+    pub fn set_tokens(&self, tokens: Tokens) {
+        self.0.storage.save(&tokens);
+        self.0.tokens.replace(Some(tokens));
+    }
+
+    /// Remove tokens from the session and its storage. `SessionExpired` isn't sent.
+    pub fn logout(&self) {
+        self.0.storage.clear();
+        self.0.tokens.replace(None);
+    }
+
+    fn is_refreshing(&self) -> bool {
+        self.0.refreshing.borrow().is_some()
+    }
+
+    fn access_token(&self) -> Option<String> {
+        self.0
+            .tokens
+            .borrow()
+            .as_ref()
+            .map(|tokens| tokens.access.clone())
+    }
+
+    fn header_value(&self, access_token: &str) -> String {
+        if self.0.scheme.is_empty() {
+            access_token.to_owned()
+        } else {
+            format!("{} {}", self.0.scheme, access_token)
+        }
+    }
+
+    fn handles(&self, request: &Request) -> bool {
+        !request.headers.contains_key(&self.0.header)
+            && !self
+                .0
+                .excluded_prefixes
+                .iter()
+                .any(|prefix| request.url.starts_with(prefix))
+    }
+
+    fn expire(&self) {
+        self.logout();
+        (self.0.on_expired)();
+    }
+
+    /// Refresh tokens after `rejected_token` has been rejected.
+    /// Concurrent calls share one refresh. Resolves to `true` if there is a new access token.
+    fn refresh(&self, rejected_token: &str) -> LocalBoxFuture<'static, bool> {
+        if let Some(refreshing) = self.0.refreshing.borrow().clone() {
+            return refreshing.boxed_local();
+        }
+        let tokens = match self.tokens() {
+            // Another request has already refreshed tokens.
+            Some(tokens) if tokens.access != rejected_token => {
+                return future::ready(true).boxed_local();
+            }
+            Some(tokens) => tokens,
+            None => return future::ready(false).boxed_local(),
+        };
+        let refresher = match (&self.0.refresher, &tokens.refresh) {
+            (Some(refresher), Some(_)) => Rc::clone(refresher),
+            _ => {
+                self.expire();
+                return future::ready(false).boxed_local();
+            }
+        };
+
+        let session = self.clone();
+        let refreshing = async move {
+            let refreshed = match refresher(tokens).await {
+                Some(tokens) => {
+                    session.set_tokens(tokens);
+                    true
+                }
+                None => {
+                    session.expire();
+                    false
+                }
+            };
+            session.0.refreshing.replace(None);
+            refreshed
+        }
+        .boxed_local()
+        .shared();
+
+        self.0.refreshing.replace(Some(refreshing.clone()));
+        refreshing.boxed_local()
+    }
+
+    /// Clone the `request` with the access token in the header. It's also set in `init`
+    /// because `init` already contains headers built from the original request.
+    fn authorize(
+        &self,
+        request: &Request,
+        init: &web_sys::RequestInit,
+        access_token: &str,
+    ) -> Result<Request, JsValue> {
+        let value = self.header_value(access_token);
+        let headers: web_sys::Headers =
+            js_sys::Reflect::get(init, &JsValue::from_str("headers"))?.dyn_into()?;
+        headers.set(&self.0.header, &value)?;
+        Ok(request.clone().header(&self.0.header, &value))
+    }
+}
+
+// ------ SessionTransport ------
+
+struct SessionTransport {
+    session: Session,
+    inner: Rc<dyn Transport>,
+}
+
+impl Transport for SessionTransport {
+    fn send(
+        &self,
+        request: &Request,
+        init: web_sys::RequestInit,
+    ) -> LocalBoxFuture<'static, Result<web_sys::Response, JsValue>> {
+        let access_token = match self.session.access_token() {
+            Some(access_token) if self.session.handles(request) => access_token,
+            _ => return self.inner.send(request, init),
+        };
+        // Requests started during a refresh (including the ones sent by the refresher)
+        // aren't replayed to prevent deadlocks.
+        let replayable = !self.session.is_refreshing();
+        let (session, inner, request) = (
+            self.session.clone(),
+            Rc::clone(&self.inner),
+            request.clone(),
+        );
+
+        async move {
+            let authorized_request = session.authorize(&request, &init, &access_token)?;
+            let response = inner.send(&authorized_request, init.clone()).await?;
+            if response.status() != UNAUTHORIZED || !replayable {
+                return Ok(response);
+            }
+            if !session.refresh(&access_token).await {
+                return Ok(response);
+            }
+            match session.access_token() {
+                Some(access_token) => {
+                    let authorized_request = session.authorize(&request, &init, &access_token)?;
+                    inner.send(&authorized_request, init).await
+                }
+                None => Ok(response),
+            }
+        }
+        .boxed_local()
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::super::{reset_transport, FailReason, MockFetch, MockResponse, ResponseDataResult};
+    use super::*;
+    use crate::browser::service::fetch::Method;
+    use std::cell::Cell;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn install(mock: &MockFetch, builder: SessionBuilder) -> (Session, Rc<Cell<u32>>) {
+        let expired_count = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&expired_count);
+        mock.install();
+        let session = builder.install_with(Rc::new(move || counter.set(counter.get() + 1)));
+        (session, expired_count)
+    }
+
+    fn fetch_user() -> impl Future<Output = ResponseDataResult<String>> {
+        Request::new("/api/user").fetch_string_data(|result| result)
+    }
+
+    fn authorization_headers(mock: &MockFetch) -> Vec<Option<String>> {
+        mock.requests()
+            .into_iter()
+            .map(|request| request.headers.get("Authorization").cloned())
+            .collect()
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn attaches_token() {
+        let mock = MockFetch::new();
+        mock.respond(Method::Get, "/api/user", MockResponse::text("Martin"));
+        let storage = MemoryStorage::default();
+        storage.save(&Tokens::new("access"));
+        let (_session, _) = install(&mock, Session::builder(storage));
+
+        let result = fetch_user().await;
+        reset_transport();
+
+        assert_eq!(result.expect("user"), "Martin");
+        assert_eq!(
+            authorization_headers(&mock),
+            vec![Some("Bearer access".to_owned())]
+        );
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn refreshes_and_replays_on_unauthorized() {
+        let mock = Rc::new(MockFetch::new());
+        mock.respond(Method::Get, "/api/user", MockResponse::status(401));
+        let refresh_mock = Rc::clone(&mock);
+        let builder = Session::builder(MemoryStorage::default()).refresh_with(move |tokens| {
+            assert_eq!(tokens.refresh.as_deref(), Some("refresh"));
+            refresh_mock.respond(Method::Get, "/api/user", MockResponse::text("Martin"));
+            future::ready(Some(Tokens::new("new_access").with_refresh("new_refresh")))
+        });
+        let (session, expired_count) = install(&mock, builder);
+        session.set_tokens(Tokens::new("old_access").with_refresh("refresh"));
+
+        let result = fetch_user().await;
+        reset_transport();
+
+        assert_eq!(result.expect("user"), "Martin");
+        assert_eq!(
+            authorization_headers(&mock),
+            vec![
+                Some("Bearer old_access".to_owned()),
+                Some("Bearer new_access".to_owned())
+            ]
+        );
+        assert_eq!(session.tokens().expect("tokens").access, "new_access");
+        assert_eq!(expired_count.get(), 0);
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn expires_when_refresh_fails() {
+        let mock = MockFetch::new();
+        mock.respond(Method::Get, "/api/user", MockResponse::status(401));
+        let builder =
+            Session::builder(MemoryStorage::default()).refresh_with(|_| future::ready(None));
+        let (session, expired_count) = install(&mock, builder);
+        session.set_tokens(Tokens::new("access").with_refresh("refresh"));
+
+        let result = fetch_user().await;
+        reset_transport();
+
+        match result {
+            Err(FailReason::Status(status, _)) => assert_eq!(status.code, 401),
+            _ => panic!("fetch should fail with status 401"),
+        }
+        assert!(!session.is_authenticated());
+        assert_eq!(expired_count.get(), 1);
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn skips_excluded_requests() {
+        let mock = MockFetch::new();
+        mock.respond(Method::Get, "/api/user", MockResponse::text("Martin"));
+        let (session, _) = install(
+            &mock,
+            Session::builder(MemoryStorage::default()).exclude_prefix("/api/"),
+        );
+        session.set_tokens(Tokens::new("access"));
+
+        let result = fetch_user().await;
+        reset_transport();
+
+        assert!(result.is_ok());
+        assert_eq!(authorization_headers(&mock), vec![None]);
+    }
+}
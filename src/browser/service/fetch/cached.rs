@@ -0,0 +1,418 @@
+//! Stale-while-revalidate cache for fetch requests - see `Cache`.
+
+use super::{Request, ResponseDataResult};
+use crate::browser::util::window;
+use futures::future::{self, FutureExt, LocalBoxFuture};
+use futures::stream::{self, Stream};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{cell::RefCell, collections::HashMap, convert::identity, fmt, rc::Rc};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+// ------ CachedResponse ------
+
+/// Data delivered by `Cache::fetch_json`.
+#[derive(Debug, Clone)]
+pub enum CachedResponse<T> {
+    /// Data loaded from the cache.
+    /// When `stale` is `true`, `Fetched` with refreshed data follows.
+    Cached { data: T, stale: bool },
+    /// Data fetched from the server.
+    Fetched(ResponseDataResult<T>),
+}
+
+// ------ CacheBackend ------
+
+/// Storage for cached responses. Values are serialized cache entries.
+pub trait CacheBackend {
+    fn get(&self, key: &str) -> LocalBoxFuture<'static, Option<String>>;
+    fn set(&self, key: &str, value: String) -> LocalBoxFuture<'static, ()>;
+    fn remove(&self, key: &str) -> LocalBoxFuture<'static, ()>;
+}
+
+/// Keeps entries in memory - they are lost on page reload.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryBackend {
+    entries: Rc<RefCell<HashMap<String, String>>>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CacheBackend for MemoryBackend {
+    fn get(&self, key: &str) -> LocalBoxFuture<'static, Option<String>> {
+        future::ready(self.entries.borrow().get(key).cloned()).boxed_local()
+    }
+
+    fn set(&self, key: &str, value: String) -> LocalBoxFuture<'static, ()> {
+        self.entries.borrow_mut().insert(key.to_owned(), value);
+        future::ready(()).boxed_local()
+    }
+
+    fn remove(&self, key: &str) -> LocalBoxFuture<'static, ()> {
+        self.entries.borrow_mut().remove(key);
+        future::ready(()).boxed_local()
+    }
+}
+
+/// Keeps entries in `LocalStorage` under keys starting with `prefix`.
+///
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/Window/localStorage)
+#[derive(Debug, Clone)]
+pub struct LocalStorageBackend {
+    prefix: String,
+}
+
+impl LocalStorageBackend {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+
+    fn item_name(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+}
+
+impl CacheBackend for LocalStorageBackend {
+    fn get(&self, key: &str) -> LocalBoxFuture<'static, Option<String>> {
+        let value = crate::storage::get_storage()
+            .and_then(|storage| storage.get_item(&self.item_name(key)).ok().flatten());
+        future::ready(value).boxed_local()
+    }
+
+    fn set(&self, key: &str, value: String) -> LocalBoxFuture<'static, ()> {
+        if let Some(storage) = crate::storage::get_storage() {
+            // The storage may be full - the response just won't be cached.
+            if let Err(error) = storage.set_item(&self.item_name(key), &value) {
+                crate::error(error);
+            }
+        }
+        future::ready(()).boxed_local()
+    }
+
+    fn remove(&self, key: &str) -> LocalBoxFuture<'static, ()> {
+        if let Some(storage) = crate::storage::get_storage() {
+            let _ = storage.remove_item(&self.item_name(key));
+        }
+        future::ready(()).boxed_local()
+    }
+}
+
+/// Keeps entries in the cache of the given name in the Cache API.
+///
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/Cache)
+#[derive(Debug, Clone)]
+pub struct CacheApiBackend {
+    cache_name: String,
+}
+
+impl CacheApiBackend {
+    pub fn new(cache_name: impl Into<String>) -> Self {
+        Self {
+            cache_name: cache_name.into(),
+        }
+    }
+
+    async fn open(cache_name: String) -> Result<web_sys::Cache, JsValue> {
+        let cache = JsFuture::from(window().caches()?.open(&cache_name)).await?;
+        Ok(cache.unchecked_into())
+    }
+
+    /// Cache API accepts only HTTP(S) requests, so we have to transform keys to URLs.
+    fn url(key: &str) -> String {
+        let origin = window().location().origin().unwrap_or_default();
+        format!(
+            "{}/__seed_cache/{}",
+            origin,
+            String::from(js_sys::encode_uri_component(key))
+        )
+    }
+}
+
+impl CacheBackend for CacheApiBackend {
+    fn get(&self, key: &str) -> LocalBoxFuture<'static, Option<String>> {
+        let (cache_name, url) = (self.cache_name.clone(), Self::url(key));
+        async move {
+            let cache = Self::open(cache_name).await?;
+            let response = JsFuture::from(cache.match_with_str(&url)).await?;
+            if response.is_undefined() {
+                return Ok(None);
+            }
+            let response: web_sys::Response = response.unchecked_into();
+            Ok(JsFuture::from(response.text()?).await?.as_string())
+        }
+        .map(|result: Result<_, JsValue>| result.unwrap_or(None))
+        .boxed_local()
+    }
+
+    fn set(&self, key: &str, value: String) -> LocalBoxFuture<'static, ()> {
+        let (cache_name, url) = (self.cache_name.clone(), Self::url(key));
+        async move {
+            let cache = Self::open(cache_name).await?;
+            let response = web_sys::Response::new_with_opt_str(Some(&value))?;
+            JsFuture::from(cache.put_with_str(&url, &response)).await?;
+            Ok(())
+        }
+        .map(|result: Result<_, JsValue>| {
+            if let Err(error) = result {
+                crate::error(error);
+            }
+        })
+        .boxed_local()
+    }
+
+    fn remove(&self, key: &str) -> LocalBoxFuture<'static, ()> {
+        let (cache_name, url) = (self.cache_name.clone(), Self::url(key));
+        async move {
+            let cache = Self::open(cache_name).await?;
+            JsFuture::from(cache.delete_with_str(&url)).await?;
+            Ok(())
+        }
+        .map(|_: Result<_, JsValue>| ())
+        .boxed_local()
+    }
+}
+
+// ------ Cache ------
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// Milliseconds since the Unix epoch.
+    stored_at: f64,
+    body: String,
+}
+
+/// Caches JSON responses with stale-while-revalidate semantics.
+///
+/// Entries are keyed by request method, URL and body.
+/// An entry is fresh for `ttl` milliseconds after it was stored - it's delivered without fetching.
+/// Then it's stale for `max_stale` milliseconds (forever by default) - it's delivered immediately
+/// and refreshed data follow. Older entries are ignored.
+///
+/// # Example
+///
+/// ```rust,no_run
+///let cache = Cache::new(LocalStorageBackend::new("api-cache:")).ttl(60_000);
+///
+///orders.stream(cache.fetch_json(Request::new("/api/user"), Msg::UserReceived));
+///
+///fn update(msg: Msg, model: &mut Model, _: &mut impl Orders<Msg>) {
+///    match msg {
+///        Msg::UserReceived(CachedResponse::Cached { data, .. }) => model.user = Some(data),
+///        Msg::UserReceived(CachedResponse::Fetched(Ok(data))) => model.user = Some(data),
+///        Msg::UserReceived(CachedResponse::Fetched(Err(fail_reason))) => error!(fail_reason),
+///    }
+///}
+/// ```
+#[derive(Clone)]
+pub struct Cache {
+    backend: Rc<dyn CacheBackend>,
+    ttl: u32,
+    max_stale: Option<u32>,
+}
+
+impl fmt::Debug for Cache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cache")
+            .field("ttl", &self.ttl)
+            .field("max_stale", &self.max_stale)
+            .finish()
+    }
+}
+
+impl Cache {
+    /// Create a new cache. Entries are immediately stale and can be stale forever by default.
+    pub fn new(backend: impl CacheBackend + 'static) -> Self {
+        Self {
+            backend: Rc::new(backend),
+            ttl: 0,
+            max_stale: None,
+        }
+    }
+
+    /// Set how long entries are fresh, in milliseconds.
+    pub const fn ttl(mut self, millis: u32) -> Self {
+        self.ttl = millis;
+        self
+    }
+
+    /// Set how long entries can be delivered as stale, in milliseconds.
+    pub const fn max_stale(mut self, millis: u32) -> Self {
+        self.max_stale = Some(millis);
+        self
+    }
+
+    /// Deliver cached data and/or refreshed data through `handler`.
+    /// Use it with `orders.stream` or `orders.stream_with_handle`.
+    /// Only successful responses are stored.
+    pub fn fetch_json<T, Ms>(
+        &self,
+        request: Request,
+        handler: impl FnOnce(CachedResponse<T>) -> Ms + Clone + 'static,
+    ) -> impl Stream<Item = Ms>
+    where
+        T: DeserializeOwned + 'static,
+        Ms: 'static,
+    {
+        #[derive(Clone, Copy)]
+        enum Step {
+            Lookup,
+            Refresh,
+            Done,
+        }
+
+        let cache = self.clone();
+        let key = cache_key(&request);
+
+        stream::unfold(Step::Lookup, move |step| {
+            let (cache, key, request, handler) =
+                (cache.clone(), key.clone(), request.clone(), handler.clone());
+            async move {
+                let (response, next_step) = match step {
+                    Step::Done => return None,
+                    Step::Refresh => (
+                        CachedResponse::Fetched(cache.refresh(request, &key).await),
+                        Step::Done,
+                    ),
+                    Step::Lookup => match cache.load::<T>(&key).await {
+                        Some((data, age)) if age <= f64::from(cache.ttl) => {
+                            (CachedResponse::Cached { data, stale: false }, Step::Done)
+                        }
+                        Some((data, age)) if cache.is_usable_stale(age) => {
+                            (CachedResponse::Cached { data, stale: true }, Step::Refresh)
+                        }
+                        _ => (
+                            CachedResponse::Fetched(cache.refresh(request, &key).await),
+                            Step::Done,
+                        ),
+                    },
+                };
+                Some((handler(response), next_step))
+            }
+        })
+    }
+
+    /// Remove the entry for the `request`.
+    pub fn invalidate(&self, request: &Request) -> impl std::future::Future<Output = ()> {
+        self.backend.remove(&cache_key(request))
+    }
+
+    fn is_usable_stale(&self, age: f64) -> bool {
+        self.max_stale.map_or(true, |max_stale| {
+            age <= f64::from(self.ttl) + f64::from(max_stale)
+        })
+    }
+
+    async fn load<T: DeserializeOwned>(&self, key: &str) -> Option<(T, f64)> {
+        let entry: Entry = serde_json::from_str(&self.backend.get(key).await?).ok()?;
+        let data = serde_json::from_str(&entry.body).ok()?;
+        Some((data, js_sys::Date::now() - entry.stored_at))
+    }
+
+    async fn refresh<T: DeserializeOwned>(
+        &self,
+        request: Request,
+        key: &str,
+    ) -> ResponseDataResult<T> {
+        let fetch_object = request.fetch_string(identity).await;
+        let body = fetch_object
+            .result
+            .as_ref()
+            .ok()
+            .filter(|response| !response.status.is_error())
+            .and_then(|response| response.data.as_ref().ok())
+            .cloned();
+
+        let data = fetch_object.deserialize_json().response_data();
+        if let (Ok(_), Some(body)) = (&data, body) {
+            let entry = Entry {
+                stored_at: js_sys::Date::now(),
+                body,
+            };
+            let entry = serde_json::to_string(&entry).expect("serialize cache entry");
+            self.backend.set(key, entry).await;
+        }
+        data
+    }
+}
+
+fn cache_key(request: &Request) -> String {
+    let body = request
+        .body
+        .as_ref()
+        .and_then(JsValue::as_string)
+        .unwrap_or_default();
+    format!("{} {} {}", request.method.as_str(), request.url, body)
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::super::{reset_transport, MockFetch, MockResponse};
+    use super::*;
+    use futures::StreamExt;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn fetched_data(responses: &[CachedResponse<u32>]) -> Vec<(String, u32)> {
+        responses
+            .iter()
+            .map(|response| match response {
+                CachedResponse::Cached { data, stale } => (format!("cached {}", stale), *data),
+                CachedResponse::Fetched(result) => {
+                    ("fetched".to_owned(), *result.as_ref().expect("data"))
+                }
+            })
+            .collect()
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn stale_while_revalidate() {
+        let mock = MockFetch::new();
+        mock.respond_any("/api/count", MockResponse::json(&1));
+        mock.install();
+        let cache = Cache::new(MemoryBackend::new());
+        let request = || Request::new("/api/count");
+
+        let first: Vec<_> = cache.fetch_json(request(), identity).collect().await;
+        mock.respond_any("/api/count", MockResponse::json(&2));
+        let second: Vec<_> = cache.fetch_json(request(), identity).collect().await;
+        reset_transport();
+
+        assert_eq!(fetched_data(&first), vec![("fetched".to_owned(), 1)]);
+        assert_eq!(
+            fetched_data(&second),
+            vec![("cached true".to_owned(), 1), ("fetched".to_owned(), 2)]
+        );
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn fresh_entry_and_invalidation() {
+        let mock = MockFetch::new();
+        mock.respond_any("/api/count", MockResponse::json(&1));
+        mock.install();
+        let cache = Cache::new(MemoryBackend::new()).ttl(60_000);
+        let request = || Request::new("/api/count");
+
+        let _: Vec<CachedResponse<u32>> = cache.fetch_json(request(), identity).collect().await;
+        let fresh: Vec<_> = cache.fetch_json(request(), identity).collect().await;
+        cache.invalidate(&request()).await;
+        let after_invalidation: Vec<_> = cache.fetch_json(request(), identity).collect().await;
+        reset_transport();
+
+        assert_eq!(fetched_data(&fresh), vec![("cached false".to_owned(), 1)]);
+        assert_eq!(
+            fetched_data(&after_invalidation),
+            vec![("fetched".to_owned(), 1)]
+        );
+        assert_eq!(mock.requests().len(), 2);
+    }
+}
@@ -0,0 +1,383 @@
+//! Typed REST endpoints - declare the API surface once and call it from anywhere.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!mod api {
+//!    const GET_USER: Endpoint<UserId, (), User> = Endpoint::get("/users/{id}");
+//!    const SEARCH_USERS: Endpoint<Search, (), Vec<User>> = Endpoint::get("/users");
+//!    const UPDATE_USER: Endpoint<UserId, User, User> = Endpoint::put("/users/{id}");
+//!
+//!    fn api() -> Api<ApiError> {
+//!        Api::new("https://example.com/api/v1")
+//!            .header("X-Client", "seed")
+//!            .timeout(5000)
+//!            .map_error(ApiError::from)
+//!    }
+//!
+//!    pub fn get_user(id: UserId) -> impl Future<Output = Result<User, ApiError>> {
+//!        // `UserId` is serialized as a scalar, so it fills the first placeholder.
+//!        GET_USER.call(&api(), &id, &())
+//!    }
+//!
+//!    pub fn search_users(search: Search) -> impl Future<Output = Result<Vec<User>, ApiError>> {
+//!        // Fields without placeholders are sent as query parameters
+//!        // - e.g. `/users?name=Martin&page=2`.
+//!        SEARCH_USERS.call(&api(), &search, &())
+//!    }
+//!}
+//!
+//!orders.perform_cmd(api::get_user(id).map(Msg::UserFetched));
+//! ```
+
+use super::{DataError, FailReason, FetchObject, Method, Request};
+use futures::future::{Future, FutureExt};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{convert::identity, fmt, marker::PhantomData, rc::Rc};
+
+// ------ Api ------
+
+/// Settings shared by all endpoints - base URL, headers, timeout and error mapping.
+pub struct Api<E = FailReason<String>> {
+    base_url: String,
+    headers: Vec<(String, String)>,
+    timeout: Option<u32>,
+    map_error: Rc<dyn Fn(FailReason<String>) -> E>,
+}
+
+impl<E> Clone for Api<E> {
+    fn clone(&self) -> Self {
+        Self {
+            base_url: self.base_url.clone(),
+            headers: self.headers.clone(),
+            timeout: self.timeout,
+            map_error: Rc::clone(&self.map_error),
+        }
+    }
+}
+
+impl<E> fmt::Debug for Api<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Api")
+            .field("base_url", &self.base_url)
+            .field("headers", &self.headers)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl Api {
+    /// `base_url` is prepended to endpoint paths - e.g. `"/api"` or `"https://example.com/api"`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            headers: Vec::new(),
+            timeout: None,
+            map_error: Rc::new(identity),
+        }
+    }
+}
+
+impl<E> Api<E> {
+    /// Add a header sent with all requests.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set timeout for all requests - see `Request::timeout`.
+    pub const fn timeout(mut self, millis: u32) -> Self {
+        self.timeout = Some(millis);
+        self
+    }
+
+    /// Map all failures to your error type.
+    pub fn map_error<NewE>(
+        self,
+        map_error: impl Fn(FailReason<String>) -> NewE + 'static,
+    ) -> Api<NewE> {
+        Api {
+            base_url: self.base_url,
+            headers: self.headers,
+            timeout: self.timeout,
+            map_error: Rc::new(map_error),
+        }
+    }
+
+    fn request(&self, method: Method, url: String) -> Request {
+        let mut request = Request::new(url).method(method);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        match self.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+}
+
+// ------ Endpoint ------
+
+/// Endpoint with path `Params`, request `Body` and `Response` types.
+///
+/// - `Params` serialized as an object fill path placeholders (e.g. `{id}`) with the same name;
+///   the other fields are sent as query parameters (`None` fields are skipped, sequences
+///   are sent as repeated parameters).
+/// - `Params` serialized as a scalar (e.g. a number or a newtype) fill the first placeholder.
+/// - Use `()` when the endpoint doesn't have params or body, or when it responds with an empty body.
+pub struct Endpoint<Params, Body, Response> {
+    method: Method,
+    path: &'static str,
+    types: PhantomData<fn(&Params, &Body) -> Response>,
+}
+
+impl<Params, Body, Response> Clone for Endpoint<Params, Body, Response> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Params, Body, Response> Copy for Endpoint<Params, Body, Response> {}
+
+impl<Params, Body, Response> fmt::Debug for Endpoint<Params, Body, Response> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Endpoint({} {})", self.method.as_str(), self.path)
+    }
+}
+
+impl<Params, Body, Response> Endpoint<Params, Body, Response> {
+    pub const fn new(method: Method, path: &'static str) -> Self {
+        Self {
+            method,
+            path,
+            types: PhantomData,
+        }
+    }
+
+    pub const fn get(path: &'static str) -> Self {
+        Self::new(Method::Get, path)
+    }
+
+    pub const fn post(path: &'static str) -> Self {
+        Self::new(Method::Post, path)
+    }
+
+    pub const fn put(path: &'static str) -> Self {
+        Self::new(Method::Put, path)
+    }
+
+    pub const fn patch(path: &'static str) -> Self {
+        Self::new(Method::Patch, path)
+    }
+
+    pub const fn delete(path: &'static str) -> Self {
+        Self::new(Method::Delete, path)
+    }
+}
+
+impl<Params, Body, Response> Endpoint<Params, Body, Response>
+where
+    Params: Serialize,
+    Body: Serialize,
+    Response: DeserializeOwned + 'static,
+{
+    /// Create the `Request` - e.g. to customize it before calling `fetch*` methods.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `params` or `body` can't be serialized.
+    pub fn request<E>(&self, api: &Api<E>, params: &Params, body: &Body) -> Request {
+        let params = serde_json::to_value(params).expect("endpoint: serialize params - failed");
+        let url = format!("{}{}", api.base_url, expand_path(self.path, params));
+        let request = api.request(self.method, url);
+        let body = serde_json::to_value(body).expect("endpoint: serialize body - failed");
+        if body.is_null() {
+            request
+        } else {
+            request.send_json(&body)
+        }
+    }
+
+    /// Send the request and deserialize the response.
+    ///
+    /// An empty response body is deserialized as `null` - so `()` and `Option` responses
+    /// work with `204 No Content`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `params` or `body` can't be serialized.
+    pub fn call<E: 'static>(
+        &self,
+        api: &Api<E>,
+        params: &Params,
+        body: &Body,
+    ) -> impl Future<Output = Result<Response, E>> {
+        let map_error = Rc::clone(&api.map_error);
+        self.request(api, params, body)
+            .fetch_string(identity)
+            .map(move |fetch_object| {
+                deserialize_response(fetch_object).map_err(|fail| map_error(fail))
+            })
+    }
+}
+
+fn deserialize_response<T: DeserializeOwned>(
+    fetch_object: FetchObject<String>,
+) -> Result<T, FailReason<String>> {
+    let text = fetch_object.clone().response_data()?;
+    let json = if text.trim().is_empty() {
+        "null"
+    } else {
+        &text
+    };
+    serde_json::from_str(json).map_err(|serde_error| {
+        FailReason::DataError(
+            DataError::SerdeError(Rc::new(serde_error), text.clone()),
+            fetch_object,
+        )
+    })
+}
+
+/// Fill path placeholders and append a query built from the remaining params.
+fn expand_path(path: &str, params: Value) -> String {
+    let mut path = path.to_owned();
+    let mut query = Vec::new();
+    match params {
+        Value::Null => (),
+        Value::Object(params) => {
+            for (name, value) in params {
+                let placeholder = format!("{{{}}}", name);
+                if path.contains(&placeholder) {
+                    path = path.replace(&placeholder, &encode(&value));
+                } else {
+                    push_query_param(&mut query, &name, value);
+                }
+            }
+        }
+        value => {
+            if let (Some(start), Some(end)) = (path.find('{'), path.find('}')) {
+                path.replace_range(start..=end, &encode(&value));
+            }
+        }
+    }
+    if query.is_empty() {
+        path
+    } else {
+        format!("{}?{}", path, query.join("&"))
+    }
+}
+
+fn push_query_param(query: &mut Vec<String>, name: &str, value: Value) {
+    match value {
+        Value::Null => (),
+        Value::Array(values) => {
+            for value in values {
+                push_query_param(query, name, value);
+            }
+        }
+        value => query.push(format!(
+            "{}={}",
+            js_sys::encode_uri_component(name),
+            encode(&value)
+        )),
+    }
+}
+
+fn encode(value: &Value) -> String {
+    let text = match value {
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    };
+    js_sys::encode_uri_component(&text).into()
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::super::{reset_transport, MockFetch, MockResponse};
+    use super::*;
+    use serde::Deserialize;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Serialize)]
+    struct Search<'a> {
+        team: u32,
+        name: Option<&'a str>,
+        tags: Vec<&'a str>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct User {
+        name: String,
+    }
+
+    #[wasm_bindgen_test]
+    fn expand_paths() {
+        let search = Search {
+            team: 7,
+            name: Some("Martin K"),
+            tags: vec!["a", "b"],
+        };
+        assert_eq!(
+            expand_path(
+                "/teams/{team}/users",
+                serde_json::to_value(&search).unwrap()
+            ),
+            "/teams/7/users?name=Martin%20K&tags=a&tags=b"
+        );
+        assert_eq!(
+            expand_path("/users/{id}/posts", Value::from(42)),
+            "/users/42/posts"
+        );
+        assert_eq!(expand_path("/users", Value::Null), "/users");
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn call_endpoint() {
+        const UPDATE_USER: Endpoint<u32, User, User> = Endpoint::put("/users/{id}");
+        let user = User {
+            name: "Martin".to_owned(),
+        };
+        let mock = MockFetch::new();
+        mock.respond(Method::Put, "/api/users/1", MockResponse::json(&user));
+        mock.install();
+
+        let api = Api::new("/api")
+            .header("X-Client", "seed")
+            .map_error(|_| "failed");
+        let result = UPDATE_USER.call(&api, &1, &user).await;
+        reset_transport();
+
+        assert_eq!(result, Ok(user));
+        let request = &mock.requests()[0];
+        assert_eq!(
+            request.headers.get("X-Client").map(String::as_str),
+            Some("seed")
+        );
+        assert_eq!(request.body.as_deref(), Some(r#"{"name":"Martin"}"#));
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn empty_response_and_error_mapping() {
+        const DELETE_USER: Endpoint<u32, (), ()> = Endpoint::delete("/users/{id}");
+        let mock = MockFetch::new();
+        mock.respond(Method::Delete, "/users/1", MockResponse::status(204));
+        mock.respond(Method::Delete, "/users/2", MockResponse::status(404));
+        mock.install();
+
+        let api = Api::new("").map_error(|fail_reason| match fail_reason {
+            FailReason::Status(status, _) => status.code,
+            _ => 0,
+        });
+        let deleted = DELETE_USER.call(&api, &1, &()).await;
+        let missing = DELETE_USER.call(&api, &2, &()).await;
+        reset_transport();
+
+        assert_eq!(deleted, Ok(()));
+        assert_eq!(missing, Err(404));
+    }
+}
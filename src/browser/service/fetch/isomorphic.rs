@@ -0,0 +1,283 @@
+//! Requests that work in the browser and also on non-wasm targets - e.g. during server-side
+//! rendering or static site generation, so both can share the same data-loading code.
+//!
+//! Requests are sent by the `Backend`:
+//! - In the browser the default backend calls `fetch::Request` - i.e. it respects
+//!   `fetch::set_transport` and `MockFetch`.
+//! - On other targets there is no default backend - plug in your HTTP client by `set_backend`.
+//!
+//! Seed doesn't ship a backend for non-wasm targets - the feature `isomorphic_fetch_trait`
+//! contains only the `Backend` trait, so Seed doesn't depend on an HTTP client.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!// Shared by the app and by the build script.
+//!async fn load_posts() -> isomorphic::Result<Vec<Post>> {
+//!    isomorphic::Request::new("https://example.com/api/posts").fetch_json().await
+//!}
+//!
+//!// Build script.
+//!struct Reqwest(reqwest::Client);
+//!
+//!impl isomorphic::Backend for Reqwest {
+//!    fn send(&self, request: isomorphic::Request) -> LocalBoxFuture<'static, isomorphic::Result<isomorphic::Response>> {
+//!        let client = self.0.clone();
+//!        async move {
+//!            let response = client
+//!                .request(request.method.as_str().parse().unwrap(), &request.url)
+//!                .body(request.body.unwrap_or_default())
+//!                .send()
+//!                .await
+//!                .map_err(|error| FetchError::RequestError(error.to_string()))?;
+//!            let (code, text) = (response.status().as_u16(), response.status().to_string());
+//!            let body = response.text().await.map_err(|error| FetchError::DataError(error.to_string()))?;
+//!            Ok(isomorphic::Response { status: Status::new(code, text), body })
+//!        }
+//!        .boxed_local()
+//!    }
+//!}
+//!
+//!isomorphic::set_backend(Reqwest(reqwest::Client::new()));
+//!let posts = futures::executor::block_on(load_posts()).expect("load posts");
+//! ```
+
+use super::{Method, Status};
+use futures::future::{FutureExt, LocalBoxFuture};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{cell::RefCell, rc::Rc};
+
+/// Isomorphic fetch result.
+pub type Result<T> = std::result::Result<T, FetchError>;
+
+// ------ FetchError ------
+
+#[derive(Debug, Clone)]
+pub enum FetchError {
+    /// The request was aborted, there was a network error, no backend is set, etc.
+    RequestError(String),
+    /// The body can't be read.
+    DataError(String),
+    /// Response status code is in range 400-599.
+    Status(Status),
+    SerdeError(Rc<serde_json::Error>),
+}
+
+// ------ Request ------
+
+/// Request sent by the current `Backend`.
+#[derive(Debug, Clone, Default)]
+pub struct Request {
+    pub url: String,
+    pub method: Method,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl Request {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Set the HTTP method. Default is GET.
+    pub const fn method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Add a header.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Set body to serialized `data`
+    /// and set header `Content-Type` to `application/json; charset=utf-8`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `data` can't be serialized.
+    pub fn send_json<T: Serialize>(self, data: &T) -> Self {
+        let json = serde_json::to_string(data).expect("fetch: serialize body to JSON - failed");
+        self.header("Content-Type", "application/json; charset=utf-8")
+            .body(json)
+    }
+
+    /// Send the request by the current `Backend`.
+    /// It fails only if the request can't be sent or the body can't be read - check `Response.status`.
+    pub fn fetch(self) -> LocalBoxFuture<'static, Result<Response>> {
+        BACKEND
+            .with(|backend| Rc::clone(&backend.borrow()))
+            .send(self)
+    }
+
+    /// Send the request and deserialize the body of a successful response.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FetchError::Status` when the response status code is in range 400-599.
+    pub async fn fetch_json<T: DeserializeOwned>(self) -> Result<T> {
+        let response = self.fetch().await?;
+        if response.status.is_error() {
+            return Err(FetchError::Status(response.status));
+        }
+        response.json()
+    }
+}
+
+// ------ Response ------
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: Status,
+    pub body: String,
+}
+
+impl Response {
+    /// Deserialize the body.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FetchError::SerdeError` when the body isn't a valid `T`.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_str(&self.body).map_err(|error| FetchError::SerdeError(Rc::new(error)))
+    }
+}
+
+// ------ Backend ------
+
+/// Sends isomorphic requests - implement it for your HTTP client to fetch on non-wasm targets.
+pub trait Backend {
+    fn send(&self, request: Request) -> LocalBoxFuture<'static, Result<Response>>;
+}
+
+/// `Backend` which sends requests by `fetch::Request`. It's the default one in the browser.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrowserBackend;
+
+impl Backend for BrowserBackend {
+    fn send(&self, request: Request) -> LocalBoxFuture<'static, Result<Response>> {
+        let mut browser_request = super::Request::new(request.url).method(request.method);
+        for (name, value) in &request.headers {
+            browser_request = browser_request.header(name, value);
+        }
+        if let Some(body) = request.body {
+            browser_request = browser_request.body(body.as_str().into());
+        }
+        browser_request
+            .fetch_string(|fetch_object| match fetch_object.result {
+                Err(super::RequestError::DomException(exception)) => {
+                    Err(FetchError::RequestError(exception.message()))
+                }
+                Ok(response) => match response.data {
+                    Ok(body) => Ok(Response {
+                        status: response.status,
+                        body,
+                    }),
+                    Err(data_error) => Err(FetchError::DataError(format!("{:?}", data_error))),
+                },
+            })
+            .boxed_local()
+    }
+}
+
+/// Default backend on non-wasm targets.
+struct MissingBackend;
+
+impl Backend for MissingBackend {
+    fn send(&self, _: Request) -> LocalBoxFuture<'static, Result<Response>> {
+        futures::future::ready(Err(FetchError::RequestError(
+            "fetch: no backend - call `isomorphic::set_backend` first".to_owned(),
+        )))
+        .boxed_local()
+    }
+}
+
+fn default_backend() -> Rc<dyn Backend> {
+    if cfg!(target_arch = "wasm32") {
+        Rc::new(BrowserBackend)
+    } else {
+        Rc::new(MissingBackend)
+    }
+}
+
+thread_local! {
+    static BACKEND: RefCell<Rc<dyn Backend>> = RefCell::new(default_backend());
+}
+
+/// Replace the backend used by all subsequent requests.
+pub fn set_backend(backend: impl Backend + 'static) {
+    BACKEND.with(|current| current.replace(Rc::new(backend)));
+}
+
+/// Restore the default backend.
+pub fn reset_backend() {
+    BACKEND.with(|current| current.replace(default_backend()));
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Post {
+        title: String,
+    }
+
+    struct EchoBackend;
+
+    impl Backend for EchoBackend {
+        fn send(&self, request: Request) -> LocalBoxFuture<'static, Result<Response>> {
+            let status = if request.method == Method::Post {
+                Status::new(200, "OK".to_owned())
+            } else {
+                Status::new(405, "Method Not Allowed".to_owned())
+            };
+            futures::future::ready(Ok(Response {
+                status,
+                body: request.body.unwrap_or_default(),
+            }))
+            .boxed_local()
+        }
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn fetch_json_with_custom_backend() {
+        set_backend(EchoBackend);
+
+        let post: Post = Request::new("/api/echo")
+            .method(Method::Post)
+            .body(r#"{"title":"Hello"}"#)
+            .fetch_json()
+            .await
+            .unwrap();
+        assert_eq!(
+            post,
+            Post {
+                title: "Hello".to_owned()
+            }
+        );
+
+        let error = Request::new("/api/echo")
+            .fetch_json::<Post>()
+            .await
+            .unwrap_err();
+        assert!(matches!(error, FetchError::Status(status) if status.code == 405));
+
+        reset_backend();
+    }
+}
@@ -0,0 +1,388 @@
+//! Fetch transport for tests - see `MockFetch`.
+
+use super::{Method, Request, Transport};
+use crate::app::clock;
+use futures::future::{FutureExt, LocalBoxFuture};
+use serde::Serialize;
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use wasm_bindgen::JsValue;
+
+// ------ RecordedRequest ------
+
+/// Request sent through `MockFetch`.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub url: String,
+    pub method: Method,
+    pub headers: HashMap<String, String>,
+    /// `None` if the request doesn't have a body or the body isn't a string.
+    pub body: Option<String>,
+}
+
+// ------ MockResponse ------
+
+/// Response fixture for `MockFetch`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///MockResponse::json(&user).header("X-Total-Count", "1").delay(300)
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: u16,
+    status_text: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    network_error: Option<String>,
+    delay: Option<u32>,
+}
+
+impl Default for MockResponse {
+    fn default() -> Self {
+        Self {
+            status: 200,
+            status_text: "OK".into(),
+            headers: Vec::new(),
+            body: None,
+            network_error: None,
+            delay: None,
+        }
+    }
+}
+
+impl MockResponse {
+    /// Empty response with status `200 OK`.
+    pub fn ok() -> Self {
+        Self::default()
+    }
+
+    /// Response with status `200 OK` and the given body.
+    pub fn text(body: impl Into<String>) -> Self {
+        Self {
+            body: Some(body.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Response with status `200 OK`, serialized `data`
+    /// and header `Content-Type` set to `application/json; charset=utf-8`.
+    pub fn json<T: Serialize>(data: &T) -> Self {
+        let json = serde_json::to_string(data).expect("MockResponse: serialize JSON - failed");
+        Self::text(json).header("Content-Type", "application/json; charset=utf-8")
+    }
+
+    /// Empty response with the given status code - e.g. `MockResponse::status(404)`.
+    pub fn status(code: u16) -> Self {
+        Self::ok().with_status(code, "")
+    }
+
+    /// The request fails like there was a network error - i.e. fetch's promise is rejected
+    /// and `FetchObject.result` will be `Err(RequestError::DomException(..))`.
+    pub fn network_error(message: impl Into<String>) -> Self {
+        Self {
+            network_error: Some(message.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Set status code and status text.
+    pub fn with_status(mut self, code: u16, text: impl Into<String>) -> Self {
+        self.status = code;
+        self.status_text = text.into();
+        self
+    }
+
+    /// Add a response header.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Resolve the request after the given milliseconds to simulate network latency.
+    /// It respects `testing::TestClock`.
+    pub fn delay(mut self, millis: u32) -> Self {
+        self.delay = Some(millis);
+        self
+    }
+
+    fn to_web_sys_response(&self) -> Result<web_sys::Response, JsValue> {
+        if let Some(message) = &self.network_error {
+            return Err(dom_exception(message, "NetworkError"));
+        }
+
+        let headers = web_sys::Headers::new()?;
+        for (name, value) in &self.headers {
+            headers.append(name, value)?;
+        }
+
+        let mut init = web_sys::ResponseInit::new();
+        init.status(self.status);
+        init.status_text(&self.status_text);
+        init.headers(&headers.into());
+
+        web_sys::Response::new_with_opt_str_and_init(self.body.as_deref(), &init)
+    }
+}
+
+fn dom_exception(message: &str, name: &str) -> JsValue {
+    web_sys::DomException::new_with_message_and_name(message, name)
+        .expect("MockFetch: create DomException - failed")
+        .into()
+}
+
+// ------ MockFetch ------
+
+#[derive(Debug)]
+struct Fixture {
+    method: Option<Method>,
+    url: String,
+    response: MockResponse,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    fixtures: Vec<Fixture>,
+    requests: Vec<RecordedRequest>,
+}
+
+/// `Transport` which responds with registered fixtures instead of sending requests to a server.
+///
+/// Requests without a matching fixture get `404 Not Found`. All requests are recorded.
+///
+/// # Example
+///
+/// ```rust,no_run
+///let mock = MockFetch::new();
+///mock.respond(Method::Get, "/api/user", MockResponse::json(&user))
+///    .respond(Method::Post, "/api/logout", MockResponse::network_error("offline"));
+///mock.install();
+///
+///// ... run the code that sends requests ...
+///
+///mock.assert_requested(Method::Get, "/api/user");
+///fetch::reset_transport();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockFetch {
+    state: Rc<RefCell<State>>,
+}
+
+impl MockFetch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use this mock as the transport for all subsequent requests.
+    /// Call `fetch::reset_transport` to restore the browser's `fetch`.
+    pub fn install(&self) {
+        super::set_transport(self.clone());
+    }
+
+    /// Register the `response` for requests with the given `method` and `url`.
+    /// Fixtures are reusable; the latest registered fixture wins when more of them match.
+    pub fn respond(&self, method: Method, url: impl Into<String>, response: MockResponse) -> &Self {
+        self.add_fixture(Some(method), url.into(), response)
+    }
+
+    /// Register the `response` for requests with any method and the given `url`.
+    pub fn respond_any(&self, url: impl Into<String>, response: MockResponse) -> &Self {
+        self.add_fixture(None, url.into(), response)
+    }
+
+    /// Returns all requests sent through this mock in the order they were sent.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.borrow().requests.clone()
+    }
+
+    /// Remove recorded requests.
+    pub fn clear_requests(&self) {
+        self.state.borrow_mut().requests.clear();
+    }
+
+    /// Remove registered fixtures.
+    pub fn clear_fixtures(&self) {
+        self.state.borrow_mut().fixtures.clear();
+    }
+
+    /// # Panics
+    ///
+    /// Panics if no request with the given `method` and `url` has been sent.
+    pub fn assert_requested(&self, method: Method, url: &str) {
+        let requests = self.requests();
+        assert!(
+            requests
+                .iter()
+                .any(|request| request.method == method && request.url == url),
+            "MockFetch: request {} {} hasn't been sent. Sent requests: {:?}",
+            method.as_str(),
+            url,
+            requests
+                .iter()
+                .map(|request| format!("{} {}", request.method.as_str(), request.url))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    fn add_fixture(&self, method: Option<Method>, url: String, response: MockResponse) -> &Self {
+        self.state.borrow_mut().fixtures.push(Fixture {
+            method,
+            url,
+            response,
+        });
+        self
+    }
+
+    fn find_response(&self, method: Method, url: &str) -> Option<MockResponse> {
+        self.state
+            .borrow()
+            .fixtures
+            .iter()
+            .rev()
+            .find(|fixture| fixture.url == url && fixture.method.map_or(true, |m| m == method))
+            .map(|fixture| fixture.response.clone())
+    }
+}
+
+impl Transport for MockFetch {
+    fn send(
+        &self,
+        request: &Request,
+        _init: web_sys::RequestInit,
+    ) -> LocalBoxFuture<'static, Result<web_sys::Response, JsValue>> {
+        let url = request.url.to_string();
+        let response = self.find_response(request.method, &url).unwrap_or_else(|| {
+            MockResponse::text(format!(
+                "MockFetch: no fixture for {} {}",
+                request.method.as_str(),
+                url
+            ))
+            .with_status(404, "Not Found")
+        });
+
+        self.state.borrow_mut().requests.push(RecordedRequest {
+            url,
+            method: request.method,
+            headers: request.headers.clone(),
+            body: request.body.as_ref().and_then(JsValue::as_string),
+        });
+
+        let abort_signal = request.controller.abort_controller.signal();
+        async move {
+            if let Some(delay) = response.delay {
+                clock::sleep(delay).await;
+            }
+            if abort_signal.aborted() {
+                return Err(dom_exception("The request was aborted.", "AbortError"));
+            }
+            response.to_web_sys_response()
+        }
+        .boxed_local()
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        reset_transport, FailReason, FetchObject, RequestController, RequestError,
+        ResponseDataResult,
+    };
+    use super::*;
+    use crate::testing::TestClock;
+    use serde::Deserialize;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct User {
+        name: String,
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn respond_with_fixture() {
+        let user = User {
+            name: "Martin".to_owned(),
+        };
+        let mock = MockFetch::new();
+        mock.respond(Method::Post, "/api/user", MockResponse::json(&user));
+        mock.install();
+
+        let result = Request::new("/api/user")
+            .method(Method::Post)
+            .send_json(&user)
+            .fetch_json_data(|result: ResponseDataResult<User>| result)
+            .await;
+        reset_transport();
+
+        assert_eq!(result.expect("user"), user);
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].body.as_deref(), Some(r#"{"name":"Martin"}"#));
+        mock.assert_requested(Method::Post, "/api/user");
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn network_error_and_missing_fixture() {
+        let mock = MockFetch::new();
+        mock.respond_any("/api/offline", MockResponse::network_error("offline"));
+        mock.install();
+
+        let offline = Request::new("/api/offline")
+            .fetch(FetchObject::response)
+            .await;
+        let missing = Request::new("/api/missing")
+            .fetch(FetchObject::response)
+            .await;
+        reset_transport();
+
+        assert!(matches!(
+            offline,
+            Err(FailReason::RequestError(RequestError::DomException(_), _))
+        ));
+        assert!(matches!(missing, Err(FailReason::Status(status, _)) if status.code == 404));
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn timeout_aborts_request() {
+        let clock = TestClock::install();
+        let mock = MockFetch::new();
+        mock.respond_any("/api/slow", MockResponse::ok().delay(1000));
+        mock.install();
+
+        let mut request = Request::new("/api/slow")
+            .timeout(500)
+            .fetch(FetchObject::response)
+            .boxed_local();
+        assert!((&mut request).now_or_never().is_none());
+
+        clock.advance(1000);
+        clock.flush().await;
+        let result = request.await;
+        reset_transport();
+
+        assert!(matches!(result, Err(FailReason::RequestError(..))));
+    }
+
+    #[wasm_bindgen_test]
+    fn dropped_fetch_aborts_request() {
+        let _clock = TestClock::install();
+        let mock = MockFetch::new();
+        mock.respond_any("/api/slow", MockResponse::ok().delay(1000));
+        mock.install();
+
+        let mut controller: Option<RequestController> = None;
+        let mut request = Request::new("/api/slow")
+            .controller(|c| controller = Some(c))
+            .fetch(FetchObject::response)
+            .boxed_local();
+        assert!((&mut request).now_or_never().is_none());
+        drop(request);
+        reset_transport();
+
+        let controller = controller.expect("controller");
+        assert!(controller.abort_controller.signal().aborted());
+    }
+}
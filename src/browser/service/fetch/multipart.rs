@@ -0,0 +1,233 @@
+use serde::Serialize;
+use wasm_bindgen::JsValue;
+
+// ------ Part ------
+
+#[derive(Debug, Clone)]
+enum Content {
+    Text(String),
+    Bytes(Vec<u8>),
+    Blob(web_sys::Blob),
+}
+
+/// A single part of `Multipart` body.
+///
+/// # Example
+///
+/// ```rust,no_run
+///Part::bytes(image_bytes)
+///    .content_type("image/png")
+///    .filename("avatar.png")
+/// ```
+#[derive(Debug, Clone)]
+pub struct Part {
+    content: Content,
+    content_type: Option<String>,
+    filename: Option<String>,
+}
+
+impl Part {
+    /// Plain text field.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::new(Content::Text(text.into()))
+    }
+
+    /// Binary content. Browsers send it as a file, so set `filename` if the server needs it.
+    pub fn bytes(bytes: impl Into<Vec<u8>>) -> Self {
+        Self::new(Content::Bytes(bytes.into()))
+    }
+
+    /// Serialized `data` with content type `application/json`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `data` cannot be serialized.
+    pub fn json<T: Serialize>(data: &T) -> Self {
+        let json = serde_json::to_string(data).expect("fetch: serialize part to JSON - failed");
+        Self::text(json).content_type("application/json")
+    }
+
+    /// File - e.g. selected in `<input type="file">` or dropped into a drop zone.
+    /// Its name and type are used unless you set `filename` or `content_type`.
+    pub fn file(file: &web_sys::File) -> Self {
+        let blob: &web_sys::Blob = file.as_ref();
+        Self::blob(blob).filename(file.name())
+    }
+
+    /// Blob. Its type is used unless you set `content_type`.
+    pub fn blob(blob: &web_sys::Blob) -> Self {
+        Self::new(Content::Blob(blob.clone()))
+    }
+
+    /// Set the part's `Content-Type`.
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Set the file name sent in part's `Content-Disposition`.
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    const fn new(content: Content) -> Self {
+        Self {
+            content,
+            content_type: None,
+            filename: None,
+        }
+    }
+
+    fn append_to(&self, name: &str, form_data: &web_sys::FormData) -> Result<(), JsValue> {
+        match (&self.content, &self.content_type, &self.filename) {
+            // Simple text field without any metadata.
+            (Content::Text(text), None, None) => form_data.append_with_str(name, text),
+            (content, content_type, filename) => {
+                let blob = match content {
+                    Content::Blob(blob) => match content_type {
+                        None => blob.clone(),
+                        Some(content_type) => {
+                            let size = blob.size();
+                            blob.slice_with_f64_and_f64_and_content_type(0., size, content_type)?
+                        }
+                    },
+                    Content::Text(text) => {
+                        new_blob(&JsValue::from_str(text), content_type.as_deref())?
+                    }
+                    Content::Bytes(bytes) => {
+                        let bytes = js_sys::Uint8Array::from(bytes.as_slice());
+                        new_blob(&bytes, content_type.as_deref())?
+                    }
+                };
+                match filename {
+                    Some(filename) => {
+                        form_data.append_with_blob_and_filename(name, &blob, filename)
+                    }
+                    None => form_data.append_with_blob(name, &blob),
+                }
+            }
+        }
+    }
+}
+
+fn new_blob(content: &JsValue, content_type: Option<&str>) -> Result<web_sys::Blob, JsValue> {
+    let parts = js_sys::Array::of1(content);
+    let mut options = web_sys::BlobPropertyBag::new();
+    if let Some(content_type) = content_type {
+        options.type_(content_type);
+    }
+    web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+}
+
+// ------ Multipart ------
+
+/// Typed builder for `multipart/form-data` request bodies.
+///
+/// Pass it to `Request::multipart`. The header `Content-Type` (including the boundary)
+/// is set by the browser.
+///
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/FormData)
+///
+/// # Example
+///
+/// ```rust,no_run
+///let form = Multipart::new()
+///    .text("title", "Holiday")
+///    .part("metadata", Part::json(&metadata))
+///    .file("photo", &file);
+///
+///fetch::Request::new("/api/photos")
+///    .method(fetch::Method::Post)
+///    .multipart(&form)
+///    .fetch_json_data(Msg::Uploaded)
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Multipart {
+    parts: Vec<(String, Part)>,
+}
+
+impl Multipart {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a plain text field.
+    pub fn text(self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.part(name, Part::text(value))
+    }
+
+    /// Add a file part.
+    pub fn file(self, name: impl Into<String>, file: &web_sys::File) -> Self {
+        self.part(name, Part::file(file))
+    }
+
+    /// Add a part. Parts with the same name are all sent.
+    pub fn part(mut self, name: impl Into<String>, part: Part) -> Self {
+        self.parts.push((name.into(), part));
+        self
+    }
+
+    /// Names of all parts in the order they were added.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Convert into `web_sys::FormData`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when the browser fails to create `FormData` or a `Blob`.
+    pub fn to_form_data(&self) -> Result<web_sys::FormData, JsValue> {
+        let form_data = web_sys::FormData::new()?;
+        for (name, part) in &self.parts {
+            part.append_to(name, &form_data)?;
+        }
+        Ok(form_data)
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn text_and_bytes_parts() {
+        let form_data = Multipart::new()
+            .text("title", "Holiday")
+            .part(
+                "photo",
+                Part::bytes(vec![1, 2, 3])
+                    .content_type("image/png")
+                    .filename("photo.png"),
+            )
+            .to_form_data()
+            .expect("form data");
+
+        assert_eq!(
+            form_data.get("title").as_string(),
+            Some("Holiday".to_owned())
+        );
+        let photo: web_sys::File = form_data.get("photo").dyn_into().expect("file");
+        assert_eq!(photo.name(), "photo.png");
+        assert_eq!(photo.type_(), "image/png");
+        assert_eq!(photo.size(), 3.);
+    }
+
+    #[wasm_bindgen_test]
+    fn json_part() {
+        let form_data = Multipart::new()
+            .part("metadata", Part::json(&vec!["a", "b"]))
+            .to_form_data()
+            .expect("form data");
+
+        let metadata: web_sys::Blob = form_data.get("metadata").dyn_into().expect("blob");
+        assert_eq!(metadata.type_(), "application/json");
+    }
+}
@@ -1,21 +1,47 @@
 use super::super::{
+    route::transition::{self, Leave},
     url,
     util::{self, ClosureNew},
     Url,
 };
 use crate::app::{subs, Notification};
-use std::convert::{TryFrom, TryInto};
+use std::{
+    cell::{Cell, RefCell},
+    cmp::Ordering,
+    convert::{TryFrom, TryInto},
+    rc::Rc,
+};
 use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::spawn_local;
 
 /// Add a new route using history's `push_state` method.
 ///
 /// # References
 /// * [MDN docs](https://developer.mozilla.org/en-US/docs/Web/API/History_API)
 pub fn push_route<U: Into<Url>>(url: U) -> Url {
-    let url = url.into();
+    change_route(url.into(), false)
+}
+
+/// Replace the current route using history's `replace_state` method,
+/// e.g. to update `Url::state` without a new history entry.
+///
+/// # References
+/// * [MDN docs](https://developer.mozilla.org/en-US/docs/Web/API/History/replaceState)
+pub fn replace_route<U: Into<Url>>(url: U) -> Url {
+    change_route(url.into(), true)
+}
+
+fn change_route(url: Url, replace: bool) -> Url {
+    let position = if replace {
+        current_position()
+    } else {
+        current_position() + 1
+    };
+
     // We use data to evaluate the path instead of the path displayed in the url.
-    let data =
-        JsValue::from_str(&serde_json::to_string(&url).expect("Problem serializing route data"));
+    let mut data = serde_json::to_value(&url).expect("Problem serializing route data");
+    data[POSITION_KEY] = position.into();
+    let data = JsValue::from_str(&data.to_string());
 
     // title is currently unused by Firefox.
     let title = match &url.title {
@@ -25,19 +51,87 @@ pub fn push_route<U: Into<Url>>(url: U) -> Url {
 
     // Prepending / means replace
     // the existing path. Not doing so will add the path to the existing one.
-    let mut path = String::from("/") + &url.path.join("/");
-    if let Some(search) = &url.search {
-        path = path + "?" + search;
+    let path = url.to_href();
+
+    let history = util::history();
+    if replace {
+        history
+            .replace_state_with_url(&data, title, Some(&path))
+            .expect("Problem replacing state");
+    } else {
+        history
+            .push_state_with_url(&data, title, Some(&path))
+            .expect("Problem pushing state");
     }
+    POSITION.with(|current| current.set(Some(position)));
+    url
+}
 
-    if let Some(hash) = &url.hash {
-        path = path + "#" + hash;
+// ------ History position ------
+
+/// Key of the history entry position in the pushed state data.
+const POSITION_KEY: &str = "history_position";
+
+thread_local! {
+    /// Position of the current history entry; `None` until it's read from the history state.
+    static POSITION: Cell<Option<u32>> = Cell::new(None);
+    /// The last handled `popstate` event and its direction.
+    static LAST_POPSTATE: RefCell<Option<(JsValue, subs::HistoryDirection)>> = RefCell::new(None);
+}
+
+fn position_from_state(state: &JsValue) -> Option<u32> {
+    let state = serde_json::from_str::<serde_json::Value>(&state.as_string()?).ok()?;
+    u32::try_from(state.get(POSITION_KEY)?.as_u64()?).ok()
+}
+
+/// Position of the current history entry - it survives page reloads.
+fn current_position() -> u32 {
+    POSITION.with(|current| {
+        current.get().unwrap_or_else(|| {
+            let state = util::history().state().unwrap_or(JsValue::NULL);
+            position_from_state(&state).unwrap_or_default()
+        })
+    })
+}
+
+/// Write the position into the current history entry when it hasn't been created by Seed -
+/// e.g. the initial entry of the page - so navigation back to it has a direction.
+fn stamp_current_entry() {
+    let state = util::history().state().unwrap_or(JsValue::NULL);
+    if position_from_state(&state).is_none() {
+        replace_route(url::current());
     }
+}
 
-    util::history()
-        .push_state_with_url(&data, title, Some(&path))
-        .expect("Problem pushing state");
-    url
+/// The direction of the `popstate` navigation; it moves `POSITION` to the target entry.
+///
+/// Each app has its own `popstate` listener, so the direction is computed only once per event.
+fn popstate_direction(event: &web_sys::PopStateEvent) -> subs::HistoryDirection {
+    LAST_POPSTATE.with(|last| {
+        if let Some((last_event, direction)) = last.borrow().as_ref() {
+            if last_event == AsRef::<JsValue>::as_ref(event) {
+                return *direction;
+            }
+        }
+        let direction = match position_from_state(&event.state()) {
+            Some(position) => {
+                let direction = direction(current_position(), position);
+                POSITION.with(|current| current.set(Some(position)));
+                direction
+            }
+            None => subs::HistoryDirection::Unknown,
+        };
+        last.replace(Some((event.into(), direction)));
+        direction
+    })
+}
+
+fn direction(from: u32, to: u32) -> subs::HistoryDirection {
+    match to.cmp(&from) {
+        Ordering::Less => subs::HistoryDirection::Back(from - to),
+        Ordering::Greater => subs::HistoryDirection::Forward(to - from),
+        Ordering::Equal => subs::HistoryDirection::Unknown,
+    }
 }
 
 /// Add a listener that handles routing for navigation events like forward and back.
@@ -50,11 +144,15 @@ pub fn setup_popstate_listener<Ms>(
 ) where
     Ms: 'static,
 {
+    stamp_current_entry();
     let closure = Closure::new(move |ev: web_sys::Event| {
         let ev = ev
             .dyn_ref::<web_sys::PopStateEvent>()
             .expect("Problem casting as Popstate event");
 
+        let direction = popstate_direction(ev);
+        notify(Notification::new(subs::HistoryNavigated { direction }));
+
         let url = match ev.state().as_string() {
             Some(state_str) => {
                 serde_json::from_str(&state_str).expect("Problem deserializing popstate state")
@@ -64,6 +162,7 @@ pub fn setup_popstate_listener<Ms>(
         };
 
         notify(Notification::new(subs::UrlChanged(url.clone())));
+        transition::enter(&url);
 
         if let Some(routes) = routes {
             if let Some(routing_msg) = routes(url) {
@@ -100,6 +199,7 @@ pub fn setup_hashchange_listener<Ms>(
             .expect("cast hashchange event url to `Url`");
 
         notify(Notification::new(subs::UrlChanged(url.clone())));
+        transition::enter(&url);
 
         if let Some(routes) = routes {
             if let Some(routing_msg) = routes(url) {
@@ -125,6 +225,7 @@ pub fn setup_link_listener<Ms>(
 ) where
     Ms: 'static,
 {
+    let notify = Rc::new(notify);
     let closure = Closure::new(move |event: web_sys::Event| {
         event.target()
             .and_then(|et| et.dyn_into::<web_sys::Element>().ok())
@@ -163,9 +264,27 @@ pub fn setup_link_listener<Ms>(
                     )));
                     match url_request_controller.status() {
                         subs::url_requested::UrlRequestStatus::Unhandled => {
-                            push_route(url.clone());
                             event.prevent_default(); // Prevent page refresh
-                            notify(Notification::new(subs::UrlChanged(url.clone())));
+                            let navigate = {
+                                let notify = Rc::clone(&notify);
+                                move |url: Url| {
+                                    push_route(url.clone());
+                                    notify(Notification::new(subs::UrlChanged(url.clone())));
+                                    transition::enter(&url);
+                                }
+                            };
+                            match transition::leave(&url::current(), &url) {
+                                Leave::Allow => navigate(url.clone()),
+                                Leave::Block => (),
+                                Leave::Wait(allowed) => {
+                                    let url = url.clone();
+                                    spawn_local(async move {
+                                        if allowed.await {
+                                            navigate(url);
+                                        }
+                                    });
+                                }
+                            }
                         }
                         subs::url_requested::UrlRequestStatus::Handled(prevent_default) => {
                             if prevent_default {
@@ -198,6 +317,10 @@ mod tests {
     use wasm_bindgen_test::*;
 
     use super::*;
+    use crate::app::{App, Orders, OrdersContainer};
+    use crate::virtual_dom::Node;
+    use gloo_timers::future::TimeoutFuture;
+    use serde::{Deserialize, Serialize};
 
     wasm_bindgen_test_configure!(run_in_browser);
 
@@ -208,6 +331,7 @@ mod tests {
             hash: None,
             search: None,
             title: None,
+            state: None,
         };
 
         let actual: Url = "/path1/path2".to_string().try_into().unwrap();
@@ -221,6 +345,7 @@ mod tests {
             hash: Some("hash".into()),
             search: Some("search=query".into()),
             title: None,
+            state: None,
         };
 
         let actual: Url = "/path?search=query#hash".to_string().try_into().unwrap();
@@ -234,6 +359,7 @@ mod tests {
             hash: Some("hash".into()),
             search: None,
             title: None,
+            state: None,
         };
 
         let actual: Url = "/path#hash".to_string().try_into().unwrap();
@@ -247,9 +373,119 @@ mod tests {
             hash: Some("/discover".into()),
             search: None,
             title: None,
+            state: None,
         };
 
         let actual: Url = "/#/discover".to_string().try_into().unwrap();
         assert_eq!(expected, actual)
     }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ModalState {
+        open: bool,
+    }
+
+    #[wasm_bindgen_test]
+    fn restore_pushed_state() {
+        let original = url::current();
+
+        push_route(Url::new(vec!["photos"]).state(&ModalState { open: true }));
+        assert_eq!(
+            url::current().typed_state(),
+            Some(ModalState { open: true })
+        );
+
+        replace_route(Url::new(vec!["photos"]));
+        assert_eq!(url::current().typed_state::<ModalState>(), None);
+
+        replace_route(original);
+    }
+
+    #[wasm_bindgen_test]
+    fn track_history_position() {
+        let original = url::current();
+        let start = current_position();
+
+        push_route(Url::new(vec!["step-1"]));
+        push_route(Url::new(vec!["step-2"]));
+        assert_eq!(current_position(), start + 2);
+        assert_eq!(
+            position_from_state(&util::history().state().unwrap()),
+            Some(start + 2)
+        );
+
+        replace_route(Url::new(vec!["step-2b"]));
+        assert_eq!(current_position(), start + 2);
+
+        assert_eq!(direction(3, 1), subs::HistoryDirection::Back(2));
+        assert_eq!(direction(1, 2), subs::HistoryDirection::Forward(1));
+        assert_eq!(direction(2, 2), subs::HistoryDirection::Unknown);
+
+        replace_route(original);
+    }
+
+    type Msg = subs::HistoryDirection;
+
+    #[derive(Default)]
+    struct Model {
+        directions: Vec<subs::HistoryDirection>,
+    }
+
+    type TestApp = App<Msg, Model, Node<Msg>>;
+
+    fn start_app() -> TestApp {
+        let root = util::document().create_element("div").unwrap();
+        util::body().append_child(&root).unwrap();
+        App::start(
+            root,
+            |_, orders: &mut OrdersContainer<Msg, Model, Node<Msg>>| {
+                orders.subscribe(|navigated: subs::HistoryNavigated| navigated.direction);
+                Model::default()
+            },
+            |direction, model, _| model.directions.push(direction),
+            |_| Node::Empty,
+        )
+    }
+
+    /// Wait until the app has received `count` `HistoryNavigated` notifications.
+    async fn directions(app: &TestApp, count: usize) -> Vec<subs::HistoryDirection> {
+        let directions = || app.data.model.borrow().as_ref().unwrap().directions.clone();
+        for _ in 0..100 {
+            if directions().len() >= count {
+                break;
+            }
+            TimeoutFuture::new(10).await;
+        }
+        directions()
+    }
+
+    #[wasm_bindgen_test]
+    async fn navigate_back_to_initial_entry() {
+        let original = url::current();
+        // The initial entry of a page isn't created by Seed.
+        util::history()
+            .replace_state_with_url(&JsValue::NULL, "", None)
+            .unwrap();
+        POSITION.with(|current| current.set(None));
+
+        let app = start_app();
+        push_route(Url::new(vec!["pushed"]));
+        util::history().back().unwrap();
+        assert_eq!(
+            directions(&app, 1).await,
+            vec![subs::HistoryDirection::Back(1)]
+        );
+        assert_eq!(current_position(), 0);
+
+        util::history().forward().unwrap();
+        assert_eq!(
+            directions(&app, 2).await,
+            vec![
+                subs::HistoryDirection::Back(1),
+                subs::HistoryDirection::Forward(1)
+            ]
+        );
+
+        replace_route(original);
+    }
 }
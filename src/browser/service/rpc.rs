@@ -0,0 +1,647 @@
+//! [JSON-RPC 2.0](https://www.jsonrpc.org/specification) client over `fetch` (`HttpRpc`)
+//! or `WebSocket` (`WebSocketRpc`).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!let rpc = HttpRpc::new("/api/rpc");
+//!
+//!orders.perform_cmd(
+//!    rpc.call("subtract", &[42, 23])
+//!        .map(|result: RpcResult<i32>| Msg::Subtracted(result))
+//!);
+//!
+//!let mut batch = Batch::new();
+//!let user = batch.call::<_, User>("get_user", &json!({ "id": 1 }));
+//!let orders_count = batch.call::<_, u32>("orders_count", &json!({ "user_id": 1 }));
+//!batch.notify("log", &["user opened"]);
+//!let response = rpc.batch(batch).await?;
+//!let (user, orders_count) = (response.get(&user)?, response.get(&orders_count)?);
+//! ```
+
+use super::fetch::{FailReason, Method, Request};
+use crate::browser::web_socket::{WebSocket, WebSocketError, WebSocketMessage};
+use futures::channel::oneshot;
+use futures::future::{Future, FutureExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fmt,
+    marker::PhantomData,
+    rc::Rc,
+};
+
+const VERSION: &str = "2.0";
+
+type Responses = HashMap<u64, Result<Value, RpcError>>;
+
+thread_local! {
+    static NEXT_ID: Cell<u64> = Cell::new(1);
+}
+
+fn next_id() -> u64 {
+    NEXT_ID.with(|next_id| {
+        let id = next_id.get();
+        next_id.set(id + 1);
+        id
+    })
+}
+
+// ------ RpcError ------
+
+/// Error object returned by the server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+// ------ RpcFailure ------
+
+pub type RpcResult<T> = Result<T, RpcFailure>;
+
+#[derive(Debug, Clone)]
+pub enum RpcFailure {
+    /// The server has returned an error object.
+    Rpc(RpcError),
+    Fetch(FailReason<String>),
+    WebSocket(WebSocketError),
+    /// The response or its result can't be deserialized.
+    Serde(Rc<serde_json::Error>),
+    /// The response is missing or it isn't a valid JSON-RPC response.
+    InvalidResponse(String),
+    /// The connection has been closed before the response arrived.
+    Disconnected,
+}
+
+fn serde_failure(error: serde_json::Error) -> RpcFailure {
+    RpcFailure::Serde(Rc::new(error))
+}
+
+// ------ Wire format ------
+
+#[derive(Debug, Serialize)]
+struct RequestObject {
+    jsonrpc: &'static str,
+    method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    params: Option<Value>,
+    /// Notifications don't have `id`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+}
+
+impl RequestObject {
+    fn new<P: Serialize + ?Sized>(method: &str, params: &P, id: Option<u64>) -> Self {
+        let params = serde_json::to_value(params).expect("rpc: serialize params - failed");
+        Self {
+            jsonrpc: VERSION,
+            method: method.to_owned(),
+            // `()` means no params.
+            params: if params.is_null() { None } else { Some(params) },
+            id,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseObject {
+    id: Option<Value>,
+    #[serde(default)]
+    result: Value,
+    error: Option<RpcError>,
+}
+
+/// Notification (or request) sent by the server over `WebSocket`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RpcNotification {
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+    /// `Some` if the server expects a response.
+    #[serde(default)]
+    pub id: Option<Value>,
+}
+
+impl RpcNotification {
+    /// Deserialize `params`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RpcFailure::Serde` when `params` can't be deserialized.
+    pub fn params<T: DeserializeOwned>(&self) -> RpcResult<T> {
+        T::deserialize(&self.params).map_err(serde_failure)
+    }
+}
+
+/// Split incoming message into responses (by `id`) and notifications.
+/// An empty message means no responses - e.g. the server's answer to a notification.
+fn parse_message(text: &str) -> RpcResult<(Responses, Vec<RpcNotification>)> {
+    let mut responses = HashMap::new();
+    let mut notifications = Vec::new();
+    if text.trim().is_empty() {
+        return Ok((responses, notifications));
+    }
+    let objects = match serde_json::from_str(text).map_err(serde_failure)? {
+        Value::Array(objects) => objects,
+        object => vec![object],
+    };
+    for object in objects {
+        if object.get("method").is_some() {
+            notifications.push(serde_json::from_value(object).map_err(serde_failure)?);
+            continue;
+        }
+        let response: ResponseObject = serde_json::from_value(object).map_err(serde_failure)?;
+        let id = response.id.as_ref().and_then(Value::as_u64);
+        match (id, response.error) {
+            (Some(id), Some(error)) => {
+                responses.insert(id, Err(error));
+            }
+            (Some(id), None) => {
+                responses.insert(id, Ok(response.result));
+            }
+            // Errors without `id` means the server couldn't parse the request.
+            (None, Some(error)) => return Err(RpcFailure::Rpc(error)),
+            (None, None) => {
+                return Err(RpcFailure::InvalidResponse(
+                    "response without `id`".to_owned(),
+                ))
+            }
+        }
+    }
+    Ok((responses, notifications))
+}
+
+fn decode_result<R: DeserializeOwned>(result: Result<Value, RpcError>) -> RpcResult<R> {
+    serde_json::from_value(result.map_err(RpcFailure::Rpc)?).map_err(serde_failure)
+}
+
+fn missing_response(id: u64) -> RpcFailure {
+    RpcFailure::InvalidResponse(format!("missing response for id {}", id))
+}
+
+// ------ Batch ------
+
+/// Calls and notifications sent in one message.
+#[derive(Debug, Default)]
+pub struct Batch {
+    requests: Vec<RequestObject>,
+}
+
+impl Batch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a call. Use the returned `BatchCall` to get its result from `BatchResponse`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `params` can't be serialized.
+    pub fn call<P: Serialize + ?Sized, R: DeserializeOwned>(
+        &mut self,
+        method: &str,
+        params: &P,
+    ) -> BatchCall<R> {
+        let id = next_id();
+        self.requests
+            .push(RequestObject::new(method, params, Some(id)));
+        BatchCall {
+            id,
+            result: PhantomData,
+        }
+    }
+
+    /// Add a notification - the server doesn't respond to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `params` can't be serialized.
+    pub fn notify<P: Serialize + ?Sized>(&mut self, method: &str, params: &P) {
+        self.requests.push(RequestObject::new(method, params, None));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    fn ids(&self) -> Vec<u64> {
+        self.requests
+            .iter()
+            .filter_map(|request| request.id)
+            .collect()
+    }
+}
+
+/// Typed reference to a call in `Batch`.
+pub struct BatchCall<R> {
+    id: u64,
+    result: PhantomData<R>,
+}
+
+impl<R> fmt::Debug for BatchCall<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchCall").field("id", &self.id).finish()
+    }
+}
+
+/// Results of batch calls.
+#[derive(Debug, Clone, Default)]
+pub struct BatchResponse {
+    responses: Responses,
+}
+
+impl BatchResponse {
+    /// Get the result of the `call`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when the call has failed, its result can't be deserialized
+    /// or the server hasn't responded to it.
+    pub fn get<R: DeserializeOwned>(&self, call: &BatchCall<R>) -> RpcResult<R> {
+        let result = self
+            .responses
+            .get(&call.id)
+            .cloned()
+            .ok_or_else(|| missing_response(call.id))?;
+        decode_result(result)
+    }
+}
+
+// ------ HttpRpc ------
+
+/// JSON-RPC client sending `POST` requests through `fetch`.
+#[derive(Debug, Clone)]
+pub struct HttpRpc {
+    request: Request,
+}
+
+impl HttpRpc {
+    pub fn new(url: impl Into<Cow<'static, str>>) -> Self {
+        Self::with_request(Request::new(url))
+    }
+
+    /// Use `request` as a template for all calls - e.g. with custom headers or timeout.
+    pub fn with_request(request: Request) -> Self {
+        Self {
+            request: request.method(Method::Post),
+        }
+    }
+
+    /// Call `method` and deserialize its result. Pass `&()` as `params` if there are none.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `params` can't be serialized.
+    pub fn call<P: Serialize + ?Sized, R: DeserializeOwned + 'static>(
+        &self,
+        method: &str,
+        params: &P,
+    ) -> impl Future<Output = RpcResult<R>> {
+        let id = next_id();
+        self.send(&RequestObject::new(method, params, Some(id)))
+            .map(move |responses| {
+                let result = responses?.remove(&id).ok_or_else(|| missing_response(id))?;
+                decode_result(result)
+            })
+    }
+
+    /// Send a notification.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `params` can't be serialized.
+    pub fn notify<P: Serialize + ?Sized>(
+        &self,
+        method: &str,
+        params: &P,
+    ) -> impl Future<Output = RpcResult<()>> {
+        self.send(&RequestObject::new(method, params, None))
+            .map(|responses| responses.map(|_| ()))
+    }
+
+    pub fn batch(&self, batch: Batch) -> impl Future<Output = RpcResult<BatchResponse>> {
+        self.send(&batch.requests).map(|responses| {
+            Ok(BatchResponse {
+                responses: responses?,
+            })
+        })
+    }
+
+    fn send<T: Serialize>(&self, body: &T) -> impl Future<Output = RpcResult<Responses>> {
+        self.request
+            .clone()
+            .send_json(body)
+            .fetch_string_data(|result| {
+                let (responses, _) = parse_message(&result.map_err(RpcFailure::Fetch)?)?;
+                Ok(responses)
+            })
+    }
+}
+
+// ------ WebSocketRpc ------
+
+#[derive(Debug, Default)]
+struct Pending(RefCell<HashMap<u64, oneshot::Sender<Result<Value, RpcError>>>>);
+
+impl Pending {
+    fn register(&self, id: u64) -> oneshot::Receiver<Result<Value, RpcError>> {
+        let (sender, receiver) = oneshot::channel();
+        self.0.borrow_mut().insert(id, sender);
+        receiver
+    }
+
+    fn resolve(&self, responses: Responses) {
+        let mut pending = self.0.borrow_mut();
+        for (id, result) in responses {
+            if let Some(sender) = pending.remove(&id) {
+                // The receiver has been dropped when nobody waits for the result anymore.
+                let _ = sender.send(result);
+            }
+        }
+    }
+
+    fn remove(&self, ids: &[u64]) {
+        let mut pending = self.0.borrow_mut();
+        for id in ids {
+            pending.remove(id);
+        }
+    }
+
+    fn reject_all(&self) {
+        // Dropped senders resolve receivers with `Canceled`.
+        self.0.borrow_mut().clear();
+    }
+}
+
+async fn receive(receiver: oneshot::Receiver<Result<Value, RpcError>>) -> RpcResult<Value> {
+    receiver
+        .await
+        .map_err(|_| RpcFailure::Disconnected)?
+        .map_err(RpcFailure::Rpc)
+}
+
+/// JSON-RPC client over `WebSocket`. Store it in your `Model` and pass it all received
+/// messages through `handle_message`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///Msg::MessageReceived(message) => {
+///    for notification in model.rpc.handle_message(&message).unwrap_or_default() {
+///        orders.send_msg(Msg::Notification(notification));
+///    }
+///}
+///Msg::Closed(_) => model.rpc.reject_pending(),
+///Msg::Subtract => {
+///    orders.perform_cmd(model.rpc.call("subtract", &[42, 23]).map(Msg::Subtracted));
+///}
+/// ```
+#[derive(Debug)]
+pub struct WebSocketRpc {
+    web_socket: WebSocket,
+    pending: Rc<Pending>,
+}
+
+impl WebSocketRpc {
+    pub fn new(web_socket: WebSocket) -> Self {
+        Self {
+            web_socket,
+            pending: Rc::default(),
+        }
+    }
+
+    pub const fn web_socket(&self) -> &WebSocket {
+        &self.web_socket
+    }
+
+    /// Call `method` and deserialize its result. Pass `&()` as `params` if there are none.
+    ///
+    /// The returned future resolves when the response is passed to `handle_message`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `params` can't be serialized.
+    pub fn call<P: Serialize + ?Sized, R: DeserializeOwned + 'static>(
+        &self,
+        method: &str,
+        params: &P,
+    ) -> impl Future<Output = RpcResult<R>> {
+        let id = next_id();
+        let receiver = self.pending.register(id);
+        let sent = self.send(&RequestObject::new(method, params, Some(id)), &[id]);
+        async move {
+            sent?;
+            decode_result(Ok(receive(receiver).await?))
+        }
+    }
+
+    /// Send a notification.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RpcFailure::WebSocket` when the message can't be sent.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `params` can't be serialized.
+    pub fn notify<P: Serialize + ?Sized>(&self, method: &str, params: &P) -> RpcResult<()> {
+        self.send(&RequestObject::new(method, params, None), &[])
+    }
+
+    /// The returned future resolves when responses to all batch calls are received.
+    pub fn batch(&self, batch: Batch) -> impl Future<Output = RpcResult<BatchResponse>> {
+        let ids = batch.ids();
+        let receivers = ids
+            .iter()
+            .map(|id| (*id, self.pending.register(*id)))
+            .collect::<Vec<_>>();
+        let sent = self.send(&batch.requests, &ids);
+        async move {
+            sent?;
+            let mut responses = HashMap::new();
+            for (id, receiver) in receivers {
+                let result = match receiver.await {
+                    Ok(result) => result,
+                    Err(_) => return Err(RpcFailure::Disconnected),
+                };
+                responses.insert(id, result);
+            }
+            Ok(BatchResponse { responses })
+        }
+    }
+
+    /// Resolve calls waiting for responses contained in the `message`
+    /// and return notifications sent by the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when the message isn't a valid JSON-RPC message.
+    pub fn handle_message(&self, message: &WebSocketMessage) -> RpcResult<Vec<RpcNotification>> {
+        let (responses, notifications) =
+            parse_message(&message.text().map_err(RpcFailure::WebSocket)?)?;
+        self.pending.resolve(responses);
+        Ok(notifications)
+    }
+
+    /// Fail all calls waiting for responses with `RpcFailure::Disconnected`
+    /// - call it when the connection has been closed.
+    pub fn reject_pending(&self) {
+        self.pending.reject_all();
+    }
+
+    fn send<T: Serialize>(&self, message: &T, ids: &[u64]) -> RpcResult<()> {
+        self.web_socket.send_json(message).map_err(|error| {
+            self.pending.remove(ids);
+            RpcFailure::WebSocket(error)
+        })
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::super::fetch::{reset_transport, MockFetch, MockResponse};
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn serialize_requests() {
+        let call = RequestObject::new("subtract", &[42, 23], Some(1));
+        assert_eq!(
+            serde_json::to_string(&call).unwrap(),
+            r#"{"jsonrpc":"2.0","method":"subtract","params":[42,23],"id":1}"#
+        );
+        let notification = RequestObject::new("heartbeat", &(), None);
+        assert_eq!(
+            serde_json::to_string(&notification).unwrap(),
+            r#"{"jsonrpc":"2.0","method":"heartbeat"}"#
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn parse_batch_message() {
+        let (responses, notifications) = parse_message(
+            r#"[
+                {"jsonrpc": "2.0", "result": 19, "id": 1},
+                {"jsonrpc": "2.0", "error": {"code": -32601, "message": "Method not found"}, "id": 2},
+                {"jsonrpc": "2.0", "method": "update", "params": [1, 2]}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(responses[&1], Ok(Value::from(19)));
+        assert_eq!(responses[&2].as_ref().unwrap_err().code, -32601);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].method, "update");
+        assert_eq!(notifications[0].params::<Vec<u8>>().unwrap(), vec![1, 2]);
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn pending_calls_are_resolved_or_rejected() {
+        let pending = Pending::default();
+        let resolved = pending.register(1);
+        let rejected = pending.register(2);
+
+        let mut responses = HashMap::new();
+        responses.insert(1, Ok(Value::from("pong")));
+        pending.resolve(responses);
+        pending.reject_all();
+
+        assert_eq!(receive(resolved).await.unwrap(), Value::from("pong"));
+        assert!(matches!(
+            receive(rejected).await,
+            Err(RpcFailure::Disconnected)
+        ));
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn http_call() {
+        let id = NEXT_ID.with(Cell::get);
+        let mock = MockFetch::new();
+        mock.respond(
+            Method::Post,
+            "/api/rpc",
+            MockResponse::text(format!(
+                r#"{{"jsonrpc": "2.0", "result": 19, "id": {}}}"#,
+                id
+            )),
+        );
+        mock.install();
+
+        let result: RpcResult<i32> = HttpRpc::new("/api/rpc").call("subtract", &[42, 23]).await;
+        reset_transport();
+
+        assert_eq!(result.unwrap(), 19);
+        assert_eq!(
+            mock.requests()[0].body.as_deref(),
+            Some(
+                format!(
+                    r#"{{"jsonrpc":"2.0","method":"subtract","params":[42,23],"id":{}}}"#,
+                    id
+                )
+                .as_str()
+            )
+        );
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn http_call_error() {
+        let id = NEXT_ID.with(Cell::get);
+        let mock = MockFetch::new();
+        mock.respond(
+            Method::Post,
+            "/api/rpc",
+            MockResponse::text(format!(
+                r#"{{"jsonrpc": "2.0", "error": {{"code": -32601, "message": "Method not found"}}, "id": {}}}"#,
+                id
+            )),
+        );
+        mock.install();
+
+        let result: RpcResult<i32> = HttpRpc::new("/api/rpc").call("foo", &()).await;
+        reset_transport();
+
+        match result {
+            Err(RpcFailure::Rpc(error)) => assert_eq!(error.message, "Method not found"),
+            _ => panic!("call should fail with RPC error"),
+        }
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn http_batch() {
+        let mut batch = Batch::new();
+        let sum = batch.call::<_, i32>("sum", &[1, 2]);
+        let missing = batch.call::<_, i32>("sum", &[3, 4]);
+        batch.notify("log", &["summing"]);
+
+        let mock = MockFetch::new();
+        mock.respond(
+            Method::Post,
+            "/api/rpc",
+            MockResponse::text(format!(
+                r#"[{{"jsonrpc": "2.0", "result": 3, "id": {}}}]"#,
+                sum.id
+            )),
+        );
+        mock.install();
+
+        let response = HttpRpc::new("/api/rpc").batch(batch).await;
+        reset_transport();
+
+        let response = response.unwrap();
+        assert_eq!(response.get(&sum).unwrap(), 3);
+        assert!(matches!(
+            response.get(&missing),
+            Err(RpcFailure::InvalidResponse(_))
+        ));
+    }
+}
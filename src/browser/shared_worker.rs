@@ -0,0 +1,336 @@
+//! Typed connection to a [`SharedWorker`](https://developer.mozilla.org/en-US/docs/Web/API/SharedWorker)
+//! - e.g. to share one `WebSocket` or cache between all tabs of your app.
+//!
+//! Messages are serialized with `serde` and posted as plain JS objects,
+//! so the worker can be written in JS or Rust.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!fn init(_: Url, orders: &mut impl Orders<Msg>) -> Init<Model> {
+//!    let worker = SharedWorker::<Command, Event>::connect("/sync_worker.js", Some("sync"))
+//!        .expect("connect to shared worker");
+//!    let (sender, receiver) = worker.split();
+//!    orders.stream(receiver.map(Msg::Worker));
+//!    Init::new(Model { worker: sender })
+//!}
+//!
+//!fn update(msg: Msg, model: &mut Model, _: &mut impl Orders<Msg>) {
+//!    match msg {
+//!        Msg::Refresh => model.worker.send(&Command::Refresh).expect("send command"),
+//!        Msg::Worker(WorkerEvent::Message(Event::Updated(items))) => model.items = items,
+//!        ...
+//!    }
+//!}
+//! ```
+
+use futures::channel::mpsc::{unbounded, UnboundedReceiver};
+use futures::stream::Stream;
+use serde::{de::DeserializeOwned, Serialize};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::{fmt, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{MessageEvent, MessagePort};
+
+/// `SharedWorker` result.
+pub type Result<T> = std::result::Result<T, WorkerError>;
+
+// ------ WorkerError ------
+
+#[derive(Debug, Clone)]
+pub enum WorkerError {
+    /// Invalid script URL or the browser doesn't support `SharedWorker`.
+    ConnectError(JsValue),
+    SendError(JsValue),
+    /// Message data can't be converted to the requested type.
+    DataError(&'static str),
+    SerdeError(Rc<serde_json::Error>),
+}
+
+// ------ WorkerEvent ------
+
+/// Event received by `WorkerReceiver`.
+#[derive(Debug, Clone)]
+pub enum WorkerEvent<In> {
+    Message(In),
+    /// Message that can't be deserialized.
+    InvalidMessage(WorkerError),
+    /// The worker script can't be loaded or it has thrown an error.
+    Error,
+}
+
+// ------ Port ------
+
+enum RawEvent {
+    Message(JsValue),
+    MessageError,
+    Error,
+}
+
+struct Callbacks {
+    on_message: Closure<dyn Fn(JsValue)>,
+    on_message_error: Closure<dyn Fn(JsValue)>,
+    on_error: Closure<dyn Fn(JsValue)>,
+}
+
+struct Port {
+    worker: web_sys::SharedWorker,
+    port: MessagePort,
+    receiver: RefCell<Option<UnboundedReceiver<RawEvent>>>,
+    _callbacks: Callbacks,
+}
+
+impl Port {
+    fn send<T: Serialize + ?Sized>(&self, message: &T) -> Result<()> {
+        self.port
+            .post_message(&serialize(message)?)
+            .map_err(WorkerError::SendError)
+    }
+}
+
+impl Drop for Port {
+    fn drop(&mut self) {
+        self.port.set_onmessage(None);
+        self.port.set_onmessageerror(None);
+        self.worker.set_onerror(None);
+        self.port.close();
+    }
+}
+
+fn callback(f: impl Fn(JsValue) + 'static) -> Closure<dyn Fn(JsValue)> {
+    Closure::wrap(Box::new(f) as Box<dyn Fn(JsValue)>)
+}
+
+fn as_function(closure: &Closure<dyn Fn(JsValue)>) -> &js_sys::Function {
+    closure.as_ref().unchecked_ref()
+}
+
+// ------ SharedWorker ------
+
+/// Connection to a `SharedWorker` - it sends `Out` messages and receives `In` messages.
+///
+/// Received messages are buffered until the connection is split. The port is closed
+/// when the connection is dropped.
+pub struct SharedWorker<Out, In>(Rc<Port>, PhantomData<fn(Out) -> In>);
+
+impl<Out, In> fmt::Debug for SharedWorker<Out, In> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedWorker").finish()
+    }
+}
+
+impl<Out: Serialize, In: DeserializeOwned> SharedWorker<Out, In> {
+    /// Connect to the worker running `script_url` or start it.
+    /// Workers with the same URL and `name` are shared.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WorkerError::ConnectError` when the URL is invalid
+    /// or the browser doesn't support `SharedWorker`.
+    pub fn connect(script_url: &str, name: Option<&str>) -> Result<Self> {
+        let worker = match name {
+            Some(name) => web_sys::SharedWorker::new_with_str(script_url, name),
+            None => web_sys::SharedWorker::new(script_url),
+        }
+        .map_err(WorkerError::ConnectError)?;
+        let port = worker.port();
+
+        let (sender, receiver) = unbounded();
+        let send = Rc::new(move |event| {
+            // The receiver has been dropped when nobody listens anymore.
+            let _ = sender.unbounded_send(event);
+        }) as Rc<dyn Fn(RawEvent)>;
+        let callbacks = Callbacks {
+            on_message: {
+                let send = Rc::clone(&send);
+                callback(move |event| {
+                    let event: MessageEvent = event.unchecked_into();
+                    send(RawEvent::Message(event.data()));
+                })
+            },
+            on_message_error: {
+                let send = Rc::clone(&send);
+                callback(move |_| send(RawEvent::MessageError))
+            },
+            on_error: callback(move |_| send(RawEvent::Error)),
+        };
+        // Setting `onmessage` also starts the port.
+        port.set_onmessage(Some(as_function(&callbacks.on_message)));
+        port.set_onmessageerror(Some(as_function(&callbacks.on_message_error)));
+        worker.set_onerror(Some(as_function(&callbacks.on_error)));
+
+        Ok(Self(
+            Rc::new(Port {
+                worker,
+                port,
+                receiver: RefCell::new(Some(receiver)),
+                _callbacks: callbacks,
+            }),
+            PhantomData,
+        ))
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when serialization fails or the message can't be posted.
+    pub fn send(&self, message: &Out) -> Result<()> {
+        self.0.send(message)
+    }
+
+    pub fn raw_worker(&self) -> &web_sys::SharedWorker {
+        &self.0.worker
+    }
+
+    pub fn raw_port(&self) -> &MessagePort {
+        &self.0.port
+    }
+
+    /// Split into a cloneable sender and a stream of received messages.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the connection has been already split.
+    pub fn split(self) -> (WorkerSender<Out>, WorkerReceiver<In>) {
+        let receiver = self
+            .0
+            .receiver
+            .borrow_mut()
+            .take()
+            .expect("shared worker connection has been already split");
+        (
+            WorkerSender(Rc::clone(&self.0), PhantomData),
+            WorkerReceiver {
+                receiver,
+                _port: self.0,
+                message_type: PhantomData,
+            },
+        )
+    }
+}
+
+// ------ WorkerSender ------
+
+/// Sending half of `SharedWorker`. See `SharedWorker::split`.
+pub struct WorkerSender<Out>(Rc<Port>, PhantomData<fn(Out)>);
+
+impl<Out> Clone for WorkerSender<Out> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0), PhantomData)
+    }
+}
+
+impl<Out> fmt::Debug for WorkerSender<Out> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorkerSender").finish()
+    }
+}
+
+impl<Out: Serialize> WorkerSender<Out> {
+    /// See `SharedWorker::send`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when serialization fails or the message can't be posted.
+    pub fn send(&self, message: &Out) -> Result<()> {
+        self.0.send(message)
+    }
+}
+
+// ------ WorkerReceiver ------
+
+/// Stream of `SharedWorker` events. See `SharedWorker::split`.
+pub struct WorkerReceiver<In> {
+    receiver: UnboundedReceiver<RawEvent>,
+    _port: Rc<Port>,
+    message_type: PhantomData<fn() -> In>,
+}
+
+impl<In> fmt::Debug for WorkerReceiver<In> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorkerReceiver").finish()
+    }
+}
+
+impl<In: DeserializeOwned> Stream for WorkerReceiver<In> {
+    type Item = WorkerEvent<In>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        Stream::poll_next(Pin::new(&mut self.receiver), cx).map(|event| {
+            event.map(|event| match event {
+                RawEvent::Message(data) => deserialize(&data)
+                    .map_or_else(WorkerEvent::InvalidMessage, WorkerEvent::Message),
+                RawEvent::MessageError => WorkerEvent::InvalidMessage(WorkerError::DataError(
+                    "message data can't be cloned",
+                )),
+                RawEvent::Error => WorkerEvent::Error,
+            })
+        })
+    }
+}
+
+pub(crate) fn serialize<T: Serialize + ?Sized>(message: &T) -> Result<JsValue> {
+    let json =
+        serde_json::to_string(message).map_err(|error| WorkerError::SerdeError(Rc::new(error)))?;
+    js_sys::JSON::parse(&json).map_err(WorkerError::SendError)
+}
+
+pub(crate) fn deserialize<In: DeserializeOwned>(data: &JsValue) -> Result<In> {
+    let json = js_sys::JSON::stringify(data)
+        .ok()
+        .and_then(|json| json.as_string())
+        .ok_or(WorkerError::DataError("message data can't be stringified"))?;
+    serde_json::from_str(&json).map_err(|error| WorkerError::SerdeError(Rc::new(error)))
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream::StreamExt;
+    use serde::Deserialize;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Echo {
+        text: String,
+    }
+
+    fn echo_worker_url() -> String {
+        let script = "onconnect = event => { \
+                      const port = event.ports[0]; \
+                      port.onmessage = message => port.postMessage(message.data); \
+                      };";
+        let parts = js_sys::Array::of1(&JsValue::from(script));
+        let mut options = web_sys::BlobPropertyBag::new();
+        options.type_("text/javascript");
+        let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options).unwrap();
+        web_sys::Url::create_object_url_with_blob(&blob).unwrap()
+    }
+
+    #[wasm_bindgen_test(async)]
+    async fn send_and_receive() {
+        let worker = SharedWorker::<Echo, Echo>::connect(&echo_worker_url(), None).unwrap();
+        let (sender, mut receiver) = worker.split();
+        sender
+            .send(&Echo {
+                text: "hello".to_owned(),
+            })
+            .unwrap();
+
+        match receiver.next().await {
+            Some(WorkerEvent::Message(echo)) => assert_eq!(
+                echo,
+                Echo {
+                    text: "hello".to_owned()
+                }
+            ),
+            event => panic!("unexpected event {:?}", event),
+        }
+    }
+}
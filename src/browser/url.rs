@@ -1,15 +1,37 @@
 use crate::browser::util;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::convert::TryFrom;
+use std::fmt;
+use std::iter::FromIterator;
+use std::str::FromStr;
 
 /// For setting up landing page routing. Unlike normal routing, we can't rely
 /// on the popstate state, so must go off path, hash, and search directly.
+///
+/// The state of the current history entry is restored when it has been pushed by Seed.
 pub fn current() -> Url {
     let current_url = util::window().location().href().expect("get `href`");
 
-    web_sys::Url::new(&current_url)
+    let mut url: Url = web_sys::Url::new(&current_url)
         .expect("create `web_sys::Url` from the current URL")
-        .into()
+        .into();
+    url.state = util::history()
+        .state()
+        .ok()
+        .and_then(|state| state.as_string())
+        .and_then(|state| serde_json::from_str::<Url>(&state).ok())
+        .and_then(|pushed_url| pushed_url.state);
+    url
+}
+
+/// Percent-encode `text` to use it as a path segment or a search parameter.
+pub fn encode_component(text: &str) -> String {
+    js_sys::encode_uri_component(text).into()
+}
+
+/// Decode the percent-encoded `text`; `None` when it isn't valid.
+pub fn decode_component(text: &str) -> Option<String> {
+    js_sys::decode_uri_component(text).ok().map(String::from)
 }
 
 /// Contains all information used in pushing and handling routes.
@@ -20,6 +42,9 @@ pub struct Url {
     pub search: Option<String>,
     pub hash: Option<String>,
     pub title: Option<String>,
+    /// State attached to the history entry - see `Url::state`.
+    #[serde(default)]
+    pub state: Option<serde_json::Value>,
 }
 
 impl Url {
@@ -33,6 +58,7 @@ impl Url {
             hash: None,
             search: None,
             title: None,
+            state: None,
         }
     }
 
@@ -54,10 +80,270 @@ impl Url {
         self
     }
 
+    /// Builder-pattern method for defining search from parameters - see `UrlSearch`.
+    pub fn set_search(mut self, search: UrlSearch) -> Self {
+        self.search = if search.is_empty() {
+            None
+        } else {
+            Some(search.to_string())
+        };
+        self
+    }
+
+    /// Parsed search parameters; repeated keys are preserved.
+    pub fn search_params(&self) -> UrlSearch {
+        self.search
+            .as_deref()
+            .map(UrlSearch::parse)
+            .unwrap_or_default()
+    }
+
     pub fn title(mut self, title: &str) -> Self {
         self.title = Some(title.into());
         self
     }
+
+    /// Builder-pattern method for replacing the path.
+    pub fn set_path<T: ToString>(mut self, path: Vec<T>) -> Self {
+        self.path = path.into_iter().map(|p| p.to_string()).collect();
+        self
+    }
+
+    /// Builder-pattern method for appending a path segment, e.g. `Url::new(vec!["users"]).push_path_segment(id)`.
+    pub fn push_path_segment(mut self, segment: impl ToString) -> Self {
+        self.path.push(segment.to_string());
+        self
+    }
+
+    /// Remove and return the last path segment.
+    pub fn pop_path_segment(&mut self) -> Option<String> {
+        self.path.pop()
+    }
+
+    pub fn path_segment(&self, index: usize) -> Option<&str> {
+        self.path.get(index).map(String::as_str)
+    }
+
+    /// Resolve the `relative` url against this one - like a link on the page with this url,
+    /// e.g. `"../images"`, `"details?tab=2"` or `"/about"`.
+    ///
+    /// The search and hash are taken from `relative`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when `relative` isn't a valid relative url.
+    pub fn join(&self, relative: &str) -> Result<Self, String> {
+        let dummy_base_url = format!("http://example.com{}", self.to_href());
+        web_sys::Url::new_with_base(relative, &dummy_base_url)
+            .map(Url::from)
+            .map_err(|_| format!("`{}` is invalid relative URL", relative))
+    }
+
+    /// The url without empty path segments (i.e. trailing and duplicate slashes)
+    /// and with sorted search parameters.
+    pub fn normalized(&self) -> Self {
+        let mut url = self.clone();
+        url.path.retain(|segment| !segment.is_empty());
+        url.search = self.search.as_ref().and_then(|search| {
+            let mut params = search
+                .split('&')
+                .filter(|param| !param.is_empty())
+                .collect::<Vec<_>>();
+            params.sort_unstable();
+            if params.is_empty() {
+                None
+            } else {
+                Some(params.join("&"))
+            }
+        });
+        url
+    }
+
+    /// Both urls have the same path, search parameters and hash -
+    /// trailing slashes and the order of search parameters are ignored, as well as titles and states.
+    pub fn is_equivalent(&self, other: &Self) -> bool {
+        let (url, other) = (self.normalized(), other.normalized());
+        url.path == other.path && url.search == other.search && url.hash == other.hash
+    }
+
+    /// Attach serialized `state` to the history entry created by `push_route` or `replace_route`.
+    /// It's restored on back / forward navigation and passed in `subs::UrlChanged`,
+    /// so it can hold e.g. an open modal or the scroll position.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `state` can't be serialized.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///seed::push_route(Url::new(vec!["photos"]).state(&PhotosState { open_photo: Some(id) }));
+    ///...
+    ///Msg::UrlChanged(subs::UrlChanged(url)) => {
+    ///    let state: Option<PhotosState> = url.typed_state();
+    ///}
+    /// ```
+    pub fn state<T: Serialize + ?Sized>(mut self, state: &T) -> Self {
+        self.state = Some(serde_json::to_value(state).expect("serialize `Url` state"));
+        self
+    }
+
+    /// Root-relative `href` - the path with the search and hash, e.g. `/users/7?tab=posts#bio`.
+    pub fn to_href(&self) -> String {
+        let mut href = String::from("/") + &self.path.join("/");
+        if let Some(search) = &self.search {
+            href = href + "?" + search;
+        }
+        if let Some(hash) = &self.hash {
+            href = href + "#" + hash;
+        }
+        href
+    }
+
+    /// Deserialized state attached by `state`.
+    /// Returns `None` when there is no state or it can't be deserialized to `T`.
+    pub fn typed_state<T: DeserializeOwned>(&self) -> Option<T> {
+        self.state
+            .clone()
+            .and_then(|state| serde_json::from_value(state).ok())
+    }
+}
+
+// ------ UrlSearch ------
+
+/// Search parameters as an ordered multimap - e.g. `?tag=a&tag=b&page=2`.
+///
+/// Keys and values are stored decoded and encoded again by `Display`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///let search = url.search_params();
+///let tags: Vec<&str> = search.get_all("tag");
+///let page = search.get_parsed::<u32>("page").unwrap_or(1);
+///
+///seed::push_route(url.set_search(search.set("page", page + 1)));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UrlSearch {
+    params: Vec<(String, String)>,
+}
+
+impl UrlSearch {
+    /// Parse `search` without the leading `?`; `+` is decoded as a space.
+    pub fn parse(search: &str) -> Self {
+        let decode = |text: &str| {
+            let text = text.replace('+', " ");
+            decode_component(&text).unwrap_or(text)
+        };
+        search
+            .split('&')
+            .filter(|param| !param.is_empty())
+            .map(|param| {
+                let mut parts = param.splitn(2, '=');
+                let key = parts.next().unwrap_or_default();
+                (decode(key), decode(parts.next().unwrap_or_default()))
+            })
+            .collect()
+    }
+
+    /// The first value of `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.get_all(key).into_iter().next()
+    }
+
+    /// All values of `key` in their order.
+    pub fn get_all(&self, key: &str) -> Vec<&str> {
+        self.params
+            .iter()
+            .filter(|(param_key, _)| param_key == key)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    /// The first value of `key` parsed to `T`; `None` when it's missing or invalid.
+    pub fn get_parsed<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key).and_then(|value| value.parse().ok())
+    }
+
+    /// All valid values of `key` parsed to `T`; invalid values are skipped.
+    pub fn get_all_parsed<T: FromStr>(&self, key: &str) -> Vec<T> {
+        self.get_all(key)
+            .into_iter()
+            .filter_map(|value| value.parse().ok())
+            .collect()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.params.iter().any(|(param_key, _)| param_key == key)
+    }
+
+    /// Builder-pattern method for appending a value - other values of `key` are kept.
+    pub fn push(mut self, key: impl ToString, value: impl ToString) -> Self {
+        self.params.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Builder-pattern method for replacing all values of `key` by `value`.
+    /// The value keeps the position of the first original one.
+    pub fn set(mut self, key: impl ToString, value: impl ToString) -> Self {
+        let (key, value) = (key.to_string(), value.to_string());
+        match self
+            .params
+            .iter()
+            .position(|(param_key, _)| param_key == &key)
+        {
+            Some(index) => {
+                self.params[index].1 = value;
+                let mut position = 0;
+                self.params.retain(|(param_key, _)| {
+                    position += 1;
+                    position - 1 == index || param_key != &key
+                });
+            }
+            None => self.params.push((key, value)),
+        }
+        self
+    }
+
+    /// Builder-pattern method for removing all values of `key`.
+    pub fn remove(mut self, key: &str) -> Self {
+        self.params.retain(|(param_key, _)| param_key != key);
+        self
+    }
+
+    /// Key-value pairs in their order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+}
+
+impl<K: ToString, V: ToString> FromIterator<(K, V)> for UrlSearch {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self {
+            params: iter
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for UrlSearch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params = self
+            .params
+            .iter()
+            .map(|(key, value)| format!("{}={}", encode_component(key), encode_component(value)))
+            .collect::<Vec<_>>();
+        write!(f, "{}", params.join("&"))
+    }
 }
 
 impl From<web_sys::Url> for Url {
@@ -96,6 +382,7 @@ impl From<web_sys::Url> for Url {
             hash,
             search,
             title: None,
+            state: None,
         }
     }
 }
@@ -123,3 +410,62 @@ impl From<Vec<&str>> for Url {
         Url::new(path)
     }
 }
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn join_resolves_relative_urls() {
+        let url = Url::new(vec!["docs", "guide", "intro"]);
+        assert_eq!(url.join("../images").unwrap().path, vec!["docs", "images"]);
+
+        let joined = url.join("setup?step=2#top").unwrap();
+        assert_eq!(joined.path, vec!["docs", "guide", "setup"]);
+        assert_eq!(joined.search.as_deref(), Some("step=2"));
+        assert_eq!(joined.hash.as_deref(), Some("top"));
+
+        assert_eq!(url.join("/about").unwrap().path, vec!["about"]);
+    }
+
+    #[wasm_bindgen_test]
+    fn equivalent_urls() {
+        let url = Url::new(vec!["users", ""]).search("b=2&a=1");
+        let other = Url::new(vec!["users"]).search("a=1&b=2");
+        assert!(url.is_equivalent(&other));
+        assert!(!url.is_equivalent(&other.hash("bio")));
+    }
+
+    #[wasm_bindgen_test]
+    fn search_params_keep_repeated_keys() {
+        let url = Url::new(vec!["posts"]).search("tag=a&page=2&tag=b%20c&tag=d+e");
+        let search = url.search_params();
+        assert_eq!(search.get_all("tag"), vec!["a", "b c", "d e"]);
+        assert_eq!(search.get_parsed::<u32>("page"), Some(2));
+        assert_eq!(search.get_parsed::<u32>("tag"), None);
+
+        let search = search.set("tag", "x").push("sort", "new & hot");
+        assert_eq!(search.to_string(), "tag=x&page=2&sort=new%20%26%20hot");
+        assert_eq!(
+            url.set_search(search.remove("tag")).search.as_deref(),
+            Some("page=2&sort=new%20%26%20hot")
+        );
+        assert_eq!(
+            Url::new(vec!["posts"]).search_params(),
+            UrlSearch::default()
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn percent_encoding() {
+        let encoded = encode_component("a b/c");
+        assert_eq!(encoded, "a%20b%2Fc");
+        assert_eq!(decode_component(&encoded).as_deref(), Some("a b/c"));
+        assert_eq!(decode_component("%E0%A4%A"), None);
+    }
+}
@@ -0,0 +1,884 @@
+//! `web_sys::WebSocket` wrapper integrated with `Orders` - callbacks produce your `Msg`s.
+//!
+//! [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/WebSocket)
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!fn init(_: Url, orders: &mut impl Orders<Msg>) -> Init<Model> {
+//!    let web_socket = WebSocket::builder("ws://127.0.0.1:9000/ws", orders)
+//!        .on_open(|| Msg::Opened)
+//!        .on_message(Msg::MessageReceived)
+//!        .on_close(Msg::Closed)
+//!        .build_and_open()
+//!        .expect("open WebSocket");
+//!    Init::new(Model { web_socket })
+//!}
+//!
+//!fn update(msg: Msg, model: &mut Model, _: &mut impl Orders<Msg>) {
+//!    match msg {
+//!        Msg::Send(text) => model.web_socket.send_text(&text).expect("send text"),
+//!        Msg::MessageReceived(message) => {
+//!            let message: ServerMessage = message.json().expect("deserialize message");
+//!            ...
+//!        }
+//!        ...
+//!    }
+//!}
+//! ```
+
+use crate::app::{clock, CmdHandle, CmdManager, Orders};
+use crate::browser::util::error;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::future;
+use futures::stream::{Stream, StreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt,
+    rc::Rc,
+};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+pub use web_sys::{BinaryType, CloseEvent, MessageEvent};
+
+type Dispatch<Ms> = Rc<dyn Fn(Ms)>;
+type PongPredicate = Rc<dyn Fn(&WebSocketMessage) -> bool>;
+
+/// `WebSocket` result.
+pub type Result<T> = std::result::Result<T, WebSocketError>;
+
+// ------ WebSocketError ------
+
+#[derive(Debug, Clone)]
+pub enum WebSocketError {
+    OpenError(JsValue),
+    SendError(JsValue),
+    CloseError(JsValue),
+    /// Message data can't be converted to the requested type.
+    DataError(&'static str),
+    SerdeError(Rc<serde_json::Error>),
+}
+
+// ------ State ------
+
+/// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/WebSocket/readyState)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Connecting,
+    Open,
+    Closing,
+    Closed,
+}
+
+impl From<u16> for State {
+    fn from(ready_state: u16) -> Self {
+        match ready_state {
+            web_sys::WebSocket::CONNECTING => State::Connecting,
+            web_sys::WebSocket::OPEN => State::Open,
+            web_sys::WebSocket::CLOSING => State::Closing,
+            _ => State::Closed,
+        }
+    }
+}
+
+// ------ WebSocketMessage ------
+
+/// Message received by `WebSocket`. See `Builder::on_message`.
+#[derive(Debug, Clone)]
+pub struct WebSocketMessage {
+    data: JsValue,
+    message_event: MessageEvent,
+}
+
+impl WebSocketMessage {
+    pub fn contains_text(&self) -> bool {
+        self.data.is_string()
+    }
+
+    /// # Errors
+    ///
+    /// Returns `WebSocketError::DataError` when the message is binary.
+    pub fn text(&self) -> Result<String> {
+        self.data
+            .as_string()
+            .ok_or(WebSocketError::DataError("message data is not a string"))
+    }
+
+    /// Deserialize text message.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when the message is binary or when deserialization fails.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_str(&self.text()?)
+            .map_err(|error| WebSocketError::SerdeError(Rc::new(error)))
+    }
+
+    /// Binary message data. It requires `BinaryType::Arraybuffer` (the default one).
+    ///
+    /// # Errors
+    ///
+    /// Returns `WebSocketError::DataError` when the message isn't binary or it's a `Blob`.
+    pub fn bytes(&self) -> Result<Vec<u8>> {
+        self.data
+            .dyn_ref::<js_sys::ArrayBuffer>()
+            .map(|buffer| js_sys::Uint8Array::new(buffer).to_vec())
+            .ok_or(WebSocketError::DataError(
+                "message data is not an ArrayBuffer",
+            ))
+    }
+
+    pub const fn raw_message(&self) -> &MessageEvent {
+        &self.message_event
+    }
+}
+
+// ------ Builder ------
+
+/// Created by `WebSocket::builder`.
+pub struct Builder<Ms: 'static> {
+    url: String,
+    protocols: Vec<String>,
+    binary_type: BinaryType,
+    dispatch: Dispatch<Ms>,
+    on_open: Option<Rc<dyn Fn() -> Ms>>,
+    on_close: Option<Rc<dyn Fn(CloseEvent) -> Ms>>,
+    on_error: Option<Rc<dyn Fn() -> Ms>>,
+    on_message: Option<Rc<dyn Fn(WebSocketMessage) -> Ms>>,
+    heartbeat: Option<Heartbeat>,
+    on_stale_connection: Option<Rc<dyn Fn() -> Ms>>,
+}
+
+impl<Ms> fmt::Debug for Builder<Ms> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Builder")
+            .field("url", &self.url)
+            .field("protocols", &self.protocols)
+            .field("binary_type", &self.binary_type)
+            .field("heartbeat", &self.heartbeat)
+            .finish()
+    }
+}
+
+impl<Ms: 'static> Builder<Ms> {
+    /// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/WebSocket/WebSocket)
+    pub fn protocols(mut self, protocols: &[&str]) -> Self {
+        self.protocols = protocols
+            .iter()
+            .map(|protocol| (*protocol).to_owned())
+            .collect();
+        self
+    }
+
+    /// Default is `BinaryType::Arraybuffer` - see `WebSocketMessage::bytes`.
+    pub const fn binary_type(mut self, binary_type: BinaryType) -> Self {
+        self.binary_type = binary_type;
+        self
+    }
+
+    pub fn on_open(mut self, handler: impl FnOnce() -> Ms + Clone + 'static) -> Self {
+        self.on_open = Some(Rc::new(move || handler.clone()()));
+        self
+    }
+
+    pub fn on_close(mut self, handler: impl FnOnce(CloseEvent) -> Ms + Clone + 'static) -> Self {
+        self.on_close = Some(Rc::new(move |event| handler.clone()(event)));
+        self
+    }
+
+    pub fn on_error(mut self, handler: impl FnOnce() -> Ms + Clone + 'static) -> Self {
+        self.on_error = Some(Rc::new(move || handler.clone()()));
+        self
+    }
+
+    pub fn on_message(
+        mut self,
+        handler: impl FnOnce(WebSocketMessage) -> Ms + Clone + 'static,
+    ) -> Self {
+        self.on_message = Some(Rc::new(move |message| handler.clone()(message)));
+        self
+    }
+
+    /// Send heartbeat messages while the connection is open to detect dead connections
+    /// (e.g. silently dropped by a proxy). See `on_stale_connection`.
+    pub fn heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    /// Called when the server hasn't responded to `Heartbeat::max_missed` heartbeats in a row.
+    /// It's called again only after the connection has been alive in the meantime.
+    pub fn on_stale_connection(mut self, handler: impl FnOnce() -> Ms + Clone + 'static) -> Self {
+        self.on_stale_connection = Some(Rc::new(move || handler.clone()()));
+        self
+    }
+
+    /// Create `WebSocket` and start connecting.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WebSocketError::OpenError` when the URL or protocols are invalid.
+    pub fn build_and_open(self) -> Result<WebSocket> {
+        let protocols = self
+            .protocols
+            .iter()
+            .map(JsValue::from)
+            .collect::<js_sys::Array>();
+        let ws = web_sys::WebSocket::new_with_str_sequence(&self.url, &protocols)
+            .map_err(WebSocketError::OpenError)?;
+        ws.set_binary_type(self.binary_type);
+
+        let queue = Queue::default();
+        let message_sender = MessageSender::default();
+        let dispatch = self.dispatch;
+        let on_stale_connection = self.on_stale_connection;
+        let heartbeat_monitor = self.heartbeat.map(|heartbeat| {
+            let (dispatch, handler) = (Rc::clone(&dispatch), on_stale_connection);
+            let on_stale: Rc<dyn Fn()> = Rc::new(move || {
+                if let Some(handler) = &handler {
+                    dispatch(handler());
+                }
+            });
+            Rc::new(HeartbeatMonitor::new(heartbeat, on_stale))
+        });
+        let callbacks = Callbacks {
+            on_open: {
+                let (ws, queue, dispatch) = (ws.clone(), Rc::clone(&queue), Rc::clone(&dispatch));
+                let (handler, heartbeat_monitor) = (self.on_open, heartbeat_monitor.clone());
+                callback(move |_| {
+                    flush_queue(&ws, &queue);
+                    if let Some(heartbeat_monitor) = &heartbeat_monitor {
+                        HeartbeatMonitor::start(heartbeat_monitor, &ws);
+                    }
+                    if let Some(handler) = &handler {
+                        dispatch(handler());
+                    }
+                })
+            },
+            on_close: {
+                let (message_sender, dispatch) = (Rc::clone(&message_sender), Rc::clone(&dispatch));
+                let (handler, heartbeat_monitor) = (self.on_close, heartbeat_monitor.clone());
+                callback(move |event| {
+                    // End the `WebSocketReceiver` stream.
+                    message_sender.replace(None);
+                    if let Some(heartbeat_monitor) = &heartbeat_monitor {
+                        heartbeat_monitor.stop();
+                    }
+                    if let Some(handler) = &handler {
+                        dispatch(handler(event.unchecked_into()));
+                    }
+                })
+            },
+            on_error: {
+                let dispatch = Rc::clone(&dispatch);
+                let handler = self.on_error;
+                callback(move |_| {
+                    if let Some(handler) = &handler {
+                        dispatch(handler());
+                    }
+                })
+            },
+            on_message: {
+                let message_sender = Rc::clone(&message_sender);
+                let handler = self.on_message;
+                callback(move |event| {
+                    let message_event: MessageEvent = event.unchecked_into();
+                    let message = WebSocketMessage {
+                        data: message_event.data(),
+                        message_event,
+                    };
+                    if let Some(heartbeat_monitor) = &heartbeat_monitor {
+                        heartbeat_monitor.message_received(&message);
+                    }
+                    if let Some(sender) = message_sender.borrow().as_ref() {
+                        // The receiver has been dropped when nobody listens anymore.
+                        let _ = sender.unbounded_send(message.clone());
+                    }
+                    if let Some(handler) = &handler {
+                        dispatch(handler(message));
+                    }
+                })
+            },
+        };
+        ws.set_onopen(Some(as_function(&callbacks.on_open)));
+        ws.set_onclose(Some(as_function(&callbacks.on_close)));
+        ws.set_onerror(Some(as_function(&callbacks.on_error)));
+        ws.set_onmessage(Some(as_function(&callbacks.on_message)));
+
+        Ok(WebSocket(Rc::new(Connection {
+            ws,
+            queue,
+            message_sender,
+            _callbacks: callbacks,
+        })))
+    }
+}
+
+fn callback(f: impl Fn(JsValue) + 'static) -> Closure<dyn Fn(JsValue)> {
+    Closure::wrap(Box::new(f) as Box<dyn Fn(JsValue)>)
+}
+
+fn as_function(closure: &Closure<dyn Fn(JsValue)>) -> &js_sys::Function {
+    closure.as_ref().unchecked_ref()
+}
+
+// ------ Heartbeat ------
+
+/// Heartbeat settings. See `Builder::heartbeat`.
+///
+/// _Note:_ Browsers don't allow to send ping frames, so heartbeats are application messages
+/// and the server has to respond to them.
+///
+/// # Example
+///
+/// ```rust,no_run
+///WebSocket::builder(url, orders)
+///    .heartbeat(
+///        Heartbeat::text(30_000, "ping")
+///            .pong(|message| message.text().ok().as_deref() == Some("pong"))
+///            .max_missed(2)
+///            .close_when_stale(),
+///    )
+///    .on_stale_connection(|| Msg::ConnectionLost)
+/// ```
+#[derive(Clone)]
+pub struct Heartbeat {
+    interval: u32,
+    message: String,
+    is_pong: Option<PongPredicate>,
+    max_missed: u32,
+    close_when_stale: bool,
+}
+
+impl fmt::Debug for Heartbeat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Heartbeat")
+            .field("interval", &self.interval)
+            .field("message", &self.message)
+            .field("max_missed", &self.max_missed)
+            .field("close_when_stale", &self.close_when_stale)
+            .finish()
+    }
+}
+
+impl Heartbeat {
+    /// Send text `message` every `interval` milliseconds.
+    pub fn text(interval: u32, message: impl Into<String>) -> Self {
+        Self {
+            interval,
+            message: message.into(),
+            is_pong: None,
+            max_missed: 1,
+            close_when_stale: false,
+        }
+    }
+
+    /// Serialize `message` and send it every `interval` milliseconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `message` can't be serialized.
+    pub fn json<T: Serialize + ?Sized>(interval: u32, message: &T) -> Self {
+        let message = serde_json::to_string(message).expect("serialize heartbeat message");
+        Self::text(interval, message)
+    }
+
+    /// Set which messages answer heartbeats. By default, any received message proves
+    /// that the connection is alive.
+    pub fn pong(mut self, is_pong: impl Fn(&WebSocketMessage) -> bool + 'static) -> Self {
+        self.is_pong = Some(Rc::new(is_pong));
+        self
+    }
+
+    /// How many heartbeats in a row can be unanswered before the connection is stale.
+    /// Default is 1 - i.e. the answer has to arrive before the next heartbeat.
+    /// `0` is treated as 1 - there is no answer to wait for before the first heartbeat.
+    pub const fn max_missed(mut self, max_missed: u32) -> Self {
+        self.max_missed = if max_missed == 0 { 1 } else { max_missed };
+        self
+    }
+
+    /// Close the stale connection. `on_close` is called once the browser closes it.
+    pub const fn close_when_stale(mut self) -> Self {
+        self.close_when_stale = true;
+        self
+    }
+}
+
+struct HeartbeatMonitor {
+    heartbeat: Heartbeat,
+    on_stale: Rc<dyn Fn()>,
+    missed: Cell<u32>,
+    awaiting_pong: Cell<bool>,
+    stale: Cell<bool>,
+    timer: RefCell<Option<CmdHandle>>,
+}
+
+impl HeartbeatMonitor {
+    fn new(heartbeat: Heartbeat, on_stale: Rc<dyn Fn()>) -> Self {
+        Self {
+            heartbeat,
+            on_stale,
+            missed: Cell::new(0),
+            awaiting_pong: Cell::new(false),
+            stale: Cell::new(false),
+            timer: RefCell::new(None),
+        }
+    }
+
+    fn start(this: &Rc<Self>, ws: &web_sys::WebSocket) {
+        // `Weak` - the timer is owned by the monitor.
+        let (monitor, ws) = (Rc::downgrade(this), ws.clone());
+        let timer = clock::interval(this.heartbeat.interval).for_each(move |()| {
+            if let Some(monitor) = monitor.upgrade() {
+                monitor.tick(&ws);
+            }
+            future::ready(())
+        });
+        this.timer
+            .replace(Some(CmdManager::perform_cmd_with_handle(timer)));
+    }
+
+    fn stop(&self) {
+        self.timer.replace(None);
+    }
+
+    fn tick(&self, ws: &web_sys::WebSocket) {
+        if self.awaiting_pong.get() {
+            self.missed.set(self.missed.get() + 1);
+        }
+        if self.missed.get() >= self.heartbeat.max_missed && !self.stale.replace(true) {
+            (self.on_stale)();
+            if self.heartbeat.close_when_stale {
+                // `close` without arguments never fails.
+                ws.close().expect("close WebSocket");
+                return;
+            }
+        }
+        // Keep sending heartbeats to a stale connection to find out it's alive again.
+        if let Err(send_error) = send_now(ws, &Outgoing::Text(self.heartbeat.message.clone())) {
+            error(send_error);
+        }
+        self.awaiting_pong.set(true);
+    }
+
+    fn message_received(&self, message: &WebSocketMessage) {
+        let is_pong = self
+            .heartbeat
+            .is_pong
+            .as_ref()
+            .map_or(true, |is_pong| is_pong(message));
+        if is_pong {
+            self.awaiting_pong.set(false);
+            self.missed.set(0);
+            self.stale.set(false);
+        }
+    }
+}
+
+// ------ Connection ------
+
+type Queue = Rc<RefCell<VecDeque<Outgoing>>>;
+type MessageSender = Rc<RefCell<Option<UnboundedSender<WebSocketMessage>>>>;
+
+enum Outgoing {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+fn send_now(ws: &web_sys::WebSocket, outgoing: &Outgoing) -> Result<()> {
+    match outgoing {
+        Outgoing::Text(text) => ws.send_with_str(text),
+        Outgoing::Bytes(bytes) => ws.send_with_u8_array(bytes),
+    }
+    .map_err(WebSocketError::SendError)
+}
+
+fn flush_queue(ws: &web_sys::WebSocket, queue: &Queue) {
+    for outgoing in queue.borrow_mut().drain(..) {
+        if let Err(send_error) = send_now(ws, &outgoing) {
+            error(send_error);
+        }
+    }
+}
+
+struct Callbacks {
+    on_open: Closure<dyn Fn(JsValue)>,
+    on_close: Closure<dyn Fn(JsValue)>,
+    on_error: Closure<dyn Fn(JsValue)>,
+    on_message: Closure<dyn Fn(JsValue)>,
+}
+
+/// Shared by `WebSocket`, `WebSocketSender` and `WebSocketReceiver`.
+/// The connection is closed when all of them are dropped.
+struct Connection {
+    ws: web_sys::WebSocket,
+    queue: Queue,
+    message_sender: MessageSender,
+    _callbacks: Callbacks,
+}
+
+impl Connection {
+    fn send(&self, outgoing: Outgoing) -> Result<()> {
+        match self.state() {
+            State::Connecting => {
+                self.queue.borrow_mut().push_back(outgoing);
+                Ok(())
+            }
+            State::Open => send_now(&self.ws, &outgoing),
+            State::Closing | State::Closed => Err(WebSocketError::SendError(JsValue::from_str(
+                "WebSocket is closing or closed",
+            ))),
+        }
+    }
+
+    fn send_json<T: Serialize + ?Sized>(&self, data: &T) -> Result<()> {
+        let text = serde_json::to_string(data)
+            .map_err(|error| WebSocketError::SerdeError(Rc::new(error)))?;
+        self.send(Outgoing::Text(text))
+    }
+
+    fn close(&self, code: Option<u16>, reason: Option<&str>) -> Result<()> {
+        self.queue.borrow_mut().clear();
+        match (code, reason) {
+            (None, _) => self.ws.close(),
+            (Some(code), None) => self.ws.close_with_code(code),
+            (Some(code), Some(reason)) => self.ws.close_with_code_and_reason(code, reason),
+        }
+        .map_err(WebSocketError::CloseError)
+    }
+
+    fn state(&self) -> State {
+        State::from(self.ws.ready_state())
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.ws.set_onopen(None);
+        self.ws.set_onclose(None);
+        self.ws.set_onerror(None);
+        self.ws.set_onmessage(None);
+        if matches!(self.state(), State::Connecting | State::Open) {
+            // `close` without arguments never fails.
+            self.ws.close().expect("close WebSocket");
+        }
+    }
+}
+
+// ------ WebSocket ------
+
+/// WebSocket connection. Store it in your `Model`.
+/// The connection is closed and callbacks are removed when it's dropped.
+///
+/// Messages sent before the connection has been opened are queued
+/// and sent once it's open.
+pub struct WebSocket(Rc<Connection>);
+
+impl fmt::Debug for WebSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebSocket")
+            .field("url", &self.0.ws.url())
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+impl WebSocket {
+    /// Create a builder. Messages returned from the builder's callbacks are sent to your app.
+    pub fn builder<Ms: 'static, GMs: 'static>(
+        url: impl Into<String>,
+        orders: &impl Orders<Ms, GMs>,
+    ) -> Builder<Ms> {
+        let (app, msg_mapper) = (orders.clone_app(), orders.msg_mapper());
+        Builder {
+            url: url.into(),
+            protocols: Vec::new(),
+            binary_type: BinaryType::Arraybuffer,
+            dispatch: Rc::new(move |msg| app.update(msg_mapper(msg))),
+            on_open: None,
+            on_close: None,
+            on_error: None,
+            on_message: None,
+            heartbeat: None,
+            on_stale_connection: None,
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Returns `WebSocketError::SendError` when the connection is closing or closed.
+    pub fn send_text(&self, text: &str) -> Result<()> {
+        self.0.send(Outgoing::Text(text.to_owned()))
+    }
+
+    /// Serialize `data` and send it as a text message.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when serialization fails or the connection is closing or closed.
+    pub fn send_json<T: Serialize + ?Sized>(&self, data: &T) -> Result<()> {
+        self.0.send_json(data)
+    }
+
+    /// # Errors
+    ///
+    /// Returns `WebSocketError::SendError` when the connection is closing or closed.
+    pub fn send_bytes(&self, bytes: &[u8]) -> Result<()> {
+        self.0.send(Outgoing::Bytes(bytes.to_vec()))
+    }
+
+    /// Close the connection with an optional code and reason. Queued messages are discarded.
+    ///
+    /// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/WebSocket/close)
+    ///
+    /// # Errors
+    ///
+    /// Returns `WebSocketError::CloseError` when the code or the reason is invalid.
+    pub fn close(&self, code: Option<u16>, reason: Option<&str>) -> Result<()> {
+        self.0.close(code, reason)
+    }
+
+    pub fn state(&self) -> State {
+        self.0.state()
+    }
+
+    /// The number of messages waiting for the connection to open.
+    pub fn queued_messages(&self) -> usize {
+        self.0.queue.borrow().len()
+    }
+
+    /// The number of bytes queued by `send_*` calls but not yet transmitted.
+    pub fn buffered_amount(&self) -> u32 {
+        self.0.ws.buffered_amount()
+    }
+
+    pub fn raw_web_socket(&self) -> &web_sys::WebSocket {
+        &self.0.ws
+    }
+
+    /// Split into a cloneable sender and a stream of received messages.
+    /// The stream ends when the connection is closed.
+    ///
+    /// Messages are still passed to the `on_message` handler (if there is one).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///let (sender, receiver) = web_socket.split();
+    ///orders.stream(receiver.map(Msg::MessageReceived));
+    ///model.chat = Some(Chat::new(sender.clone()));
+    /// ```
+    pub fn split(self) -> (WebSocketSender, WebSocketReceiver) {
+        let (sender, receiver) = unbounded();
+        self.0.message_sender.replace(Some(sender));
+        let connection = Rc::clone(&self.0);
+        (
+            WebSocketSender(self.0),
+            WebSocketReceiver {
+                receiver,
+                _connection: connection,
+            },
+        )
+    }
+}
+
+// ------ WebSocketSender ------
+
+/// Sending half of `WebSocket`. See `WebSocket::split`.
+#[derive(Clone)]
+pub struct WebSocketSender(Rc<Connection>);
+
+impl fmt::Debug for WebSocketSender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebSocketSender")
+            .field("url", &self.0.ws.url())
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+impl WebSocketSender {
+    /// See `WebSocket::send_text`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WebSocketError::SendError` when the connection is closing or closed.
+    pub fn send_text(&self, text: &str) -> Result<()> {
+        self.0.send(Outgoing::Text(text.to_owned()))
+    }
+
+    /// See `WebSocket::send_json`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error when serialization fails or the connection is closing or closed.
+    pub fn send_json<T: Serialize + ?Sized>(&self, data: &T) -> Result<()> {
+        self.0.send_json(data)
+    }
+
+    /// See `WebSocket::send_bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WebSocketError::SendError` when the connection is closing or closed.
+    pub fn send_bytes(&self, bytes: &[u8]) -> Result<()> {
+        self.0.send(Outgoing::Bytes(bytes.to_vec()))
+    }
+
+    /// See `WebSocket::close`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WebSocketError::CloseError` when the code or the reason is invalid.
+    pub fn close(&self, code: Option<u16>, reason: Option<&str>) -> Result<()> {
+        self.0.close(code, reason)
+    }
+
+    pub fn state(&self) -> State {
+        self.0.state()
+    }
+}
+
+// ------ WebSocketReceiver ------
+
+/// Stream of messages received by `WebSocket`. See `WebSocket::split`.
+pub struct WebSocketReceiver {
+    receiver: UnboundedReceiver<WebSocketMessage>,
+    _connection: Rc<Connection>,
+}
+
+impl fmt::Debug for WebSocketReceiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebSocketReceiver").finish()
+    }
+}
+
+impl Stream for WebSocketReceiver {
+    type Item = WebSocketMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        Stream::poll_next(Pin::new(&mut self.receiver), cx)
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestClock;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    /// The connection never opens, so heartbeats aren't answered unless `pong` is called.
+    fn connecting_web_socket() -> web_sys::WebSocket {
+        web_sys::WebSocket::new("ws://127.0.0.1:9/seed-test").unwrap()
+    }
+
+    fn pong() -> WebSocketMessage {
+        let message_event = MessageEvent::new("message").unwrap();
+        WebSocketMessage {
+            data: message_event.data(),
+            message_event,
+        }
+    }
+
+    fn builder() -> Builder<()> {
+        Builder {
+            url: "ws://127.0.0.1:9/seed-test".to_owned(),
+            protocols: Vec::new(),
+            binary_type: BinaryType::Arraybuffer,
+            dispatch: Rc::new(|()| ()),
+            on_open: None,
+            on_close: None,
+            on_error: None,
+            on_message: None,
+            heartbeat: None,
+            on_stale_connection: None,
+        }
+    }
+
+    fn monitor(heartbeat: Heartbeat) -> (Rc<HeartbeatMonitor>, Rc<Cell<u32>>) {
+        let stale_count = Rc::new(Cell::new(0));
+        let on_stale = {
+            let stale_count = Rc::clone(&stale_count);
+            Rc::new(move || stale_count.set(stale_count.get() + 1))
+        };
+        (
+            Rc::new(HeartbeatMonitor::new(heartbeat, on_stale)),
+            stale_count,
+        )
+    }
+
+    async fn heartbeats(clock: &TestClock, count: u64) {
+        clock.advance(1000 * count);
+        clock.flush().await;
+    }
+
+    #[wasm_bindgen_test]
+    fn messages_are_queued_while_connecting() {
+        let web_socket = builder().build_and_open().unwrap();
+        assert_eq!(web_socket.state(), State::Connecting);
+
+        web_socket.send_text("text").unwrap();
+        web_socket.send_bytes(&[1, 2, 3]).unwrap();
+        web_socket.send_json(&[1, 2]).unwrap();
+        assert_eq!(web_socket.queued_messages(), 3);
+
+        let (sender, _receiver) = web_socket.split();
+        sender.send_text("from sender").unwrap();
+        assert_eq!(sender.0.queue.borrow().len(), 4);
+
+        sender.close(None, None).unwrap();
+        assert_eq!(sender.0.queue.borrow().len(), 0);
+        assert!(sender.send_text("after close").is_err());
+    }
+
+    #[wasm_bindgen_test]
+    async fn heartbeat_monitor_marks_stale_once_per_episode() {
+        let clock = TestClock::install();
+        let ws = connecting_web_socket();
+        let (monitor, stale_count) = monitor(Heartbeat::text(1000, "ping").max_missed(2));
+        HeartbeatMonitor::start(&monitor, &ws);
+
+        // The first heartbeat is sent, the next two are missed.
+        heartbeats(&clock, 2).await;
+        assert_eq!(monitor.missed.get(), 1);
+        assert_eq!(stale_count.get(), 0);
+        heartbeats(&clock, 1).await;
+        assert_eq!(stale_count.get(), 1);
+        heartbeats(&clock, 3).await;
+        assert_eq!(stale_count.get(), 1);
+
+        monitor.message_received(&pong());
+        assert_eq!(monitor.missed.get(), 0);
+        assert!(!monitor.stale.get());
+        heartbeats(&clock, 2).await;
+        assert_eq!(stale_count.get(), 1);
+        heartbeats(&clock, 1).await;
+        assert_eq!(stale_count.get(), 2);
+
+        monitor.stop();
+        ws.close().unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    fn zero_max_missed_is_clamped() {
+        let heartbeat = Heartbeat::text(1000, "ping").max_missed(0);
+        assert_eq!(heartbeat.max_missed, 1);
+
+        let ws = connecting_web_socket();
+        let (monitor, stale_count) = monitor(heartbeat);
+        monitor.tick(&ws);
+        assert_eq!(stale_count.get(), 0);
+        monitor.tick(&ws);
+        assert_eq!(stale_count.get(), 1);
+        ws.close().unwrap();
+    }
+}
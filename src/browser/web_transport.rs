@@ -0,0 +1,328 @@
+//! Experimental [WebTransport](https://developer.mozilla.org/en-US/docs/Web/API/WebTransport_API)
+//! support - enable it with the feature `web_transport`.
+//!
+//! Streams and datagrams are exposed as `futures` streams and futures,
+//! so you can pass them to `orders.stream` and `orders.perform_cmd`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!async fn connect() -> Msg {
+//!    Msg::Connected(WebTransport::connect("https://example.com:4999/game").await)
+//!}
+//!
+//!fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+//!    match msg {
+//!        Msg::Connected(Ok(transport)) => {
+//!            let datagrams = transport.datagrams().expect("datagram stream");
+//!            model.stream_handle = Some(orders.stream_with_handle(datagrams.map(Msg::Datagram)));
+//!            model.sender = Some(transport.datagram_sender().expect("datagram sender"));
+//!            model.transport = Some(transport);
+//!        }
+//!        Msg::SendPosition(position) => {
+//!            if let Some(sender) = &model.sender {
+//!                orders.perform_cmd(sender.send(&position.to_bytes()).map(Msg::Sent));
+//!            }
+//!        }
+//!        ...
+//!    }
+//!}
+//! ```
+
+use futures::future::{Future, FutureExt};
+use futures::stream::{self, LocalBoxStream, Stream, StreamExt};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+mod raw {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        pub type WebTransport;
+
+        #[wasm_bindgen(constructor, catch)]
+        pub fn new(url: &str) -> Result<WebTransport, JsValue>;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn ready(this: &WebTransport) -> js_sys::Promise;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn closed(this: &WebTransport) -> js_sys::Promise;
+
+        #[wasm_bindgen(method)]
+        pub fn close(this: &WebTransport);
+
+        #[wasm_bindgen(method, getter)]
+        pub fn datagrams(this: &WebTransport) -> DuplexStream;
+
+        #[wasm_bindgen(method, js_name = createBidirectionalStream)]
+        pub fn create_bidirectional_stream(this: &WebTransport) -> js_sys::Promise;
+
+        #[wasm_bindgen(method, js_name = createUnidirectionalStream)]
+        pub fn create_unidirectional_stream(this: &WebTransport) -> js_sys::Promise;
+
+        #[wasm_bindgen(method, getter, js_name = incomingBidirectionalStreams)]
+        pub fn incoming_bidirectional_streams(this: &WebTransport) -> ReadableStream;
+
+        #[wasm_bindgen(method, getter, js_name = incomingUnidirectionalStreams)]
+        pub fn incoming_unidirectional_streams(this: &WebTransport) -> ReadableStream;
+
+        /// `WebTransportDatagramDuplexStream` or `WebTransportBidirectionalStream`.
+        pub type DuplexStream;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn readable(this: &DuplexStream) -> ReadableStream;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn writable(this: &DuplexStream) -> WritableStream;
+
+        pub type ReadableStream;
+
+        #[wasm_bindgen(method, catch, js_name = getReader)]
+        pub fn get_reader(this: &ReadableStream) -> Result<Reader, JsValue>;
+
+        #[wasm_bindgen(js_name = ReadableStreamDefaultReader)]
+        pub type Reader;
+
+        #[wasm_bindgen(method)]
+        pub fn read(this: &Reader) -> js_sys::Promise;
+
+        #[wasm_bindgen(method)]
+        pub fn cancel(this: &Reader) -> js_sys::Promise;
+
+        pub type ReadResult;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn done(this: &ReadResult) -> bool;
+
+        #[wasm_bindgen(method, getter)]
+        pub fn value(this: &ReadResult) -> JsValue;
+
+        pub type WritableStream;
+
+        #[wasm_bindgen(method, catch, js_name = getWriter)]
+        pub fn get_writer(this: &WritableStream) -> Result<Writer, JsValue>;
+
+        #[wasm_bindgen(js_name = WritableStreamDefaultWriter)]
+        pub type Writer;
+
+        #[wasm_bindgen(method)]
+        pub fn write(this: &Writer, chunk: &JsValue) -> js_sys::Promise;
+
+        #[wasm_bindgen(method)]
+        pub fn close(this: &Writer) -> js_sys::Promise;
+    }
+}
+
+// ------ WebTransport ------
+
+/// WebTransport session. It's closed when dropped.
+pub struct WebTransport(raw::WebTransport);
+
+impl std::fmt::Debug for WebTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WebTransport")
+    }
+}
+
+impl WebTransport {
+    /// Connect to the `url` (`https://...`).
+    ///
+    /// # Errors
+    ///
+    /// Returns error when the browser doesn't support WebTransport or the connection fails.
+    pub async fn connect(url: &str) -> Result<Self, JsValue> {
+        let transport = raw::WebTransport::new(url)?;
+        JsFuture::from(transport.ready()).await?;
+        Ok(Self(transport))
+    }
+
+    /// Resolves when the session is closed, with error if it's been closed abruptly.
+    pub fn closed(&self) -> impl Future<Output = Result<(), JsValue>> {
+        JsFuture::from(self.0.closed()).map(|result| result.map(|_| ()))
+    }
+
+    pub fn close(&self) {
+        self.0.close();
+    }
+
+    /// Unreliable, unordered messages received from the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the stream has been already requested.
+    pub fn datagrams(&self) -> Result<ByteStream, JsValue> {
+        ByteStream::new(&self.0.datagrams().readable())
+    }
+
+    /// Sender for unreliable, unordered messages.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the sender has been already requested.
+    pub fn datagram_sender(&self) -> Result<ByteSender, JsValue> {
+        ByteSender::new(&self.0.datagrams().writable())
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when the stream can't be opened - e.g. the session is closed.
+    pub async fn open_bidirectional_stream(&self) -> Result<BidirectionalStream, JsValue> {
+        let stream = JsFuture::from(self.0.create_bidirectional_stream()).await?;
+        BidirectionalStream::new(&stream.unchecked_into())
+    }
+
+    /// # Errors
+    ///
+    /// Returns error when the stream can't be opened - e.g. the session is closed.
+    pub async fn open_unidirectional_stream(&self) -> Result<ByteSender, JsValue> {
+        let stream = JsFuture::from(self.0.create_unidirectional_stream()).await?;
+        ByteSender::new(&stream.unchecked_into())
+    }
+
+    /// Bidirectional streams opened by the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the stream has been already requested.
+    pub fn incoming_bidirectional_streams(
+        &self,
+    ) -> Result<impl Stream<Item = Result<BidirectionalStream, JsValue>>, JsValue> {
+        let streams = read_chunks(&self.0.incoming_bidirectional_streams())?;
+        Ok(streams.map(|stream| BidirectionalStream::new(&stream?.unchecked_into())))
+    }
+
+    /// Unidirectional streams opened by the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the stream has been already requested.
+    pub fn incoming_unidirectional_streams(
+        &self,
+    ) -> Result<impl Stream<Item = Result<ByteStream, JsValue>>, JsValue> {
+        let streams = read_chunks(&self.0.incoming_unidirectional_streams())?;
+        Ok(streams.map(|stream| ByteStream::new(&stream?.unchecked_into())))
+    }
+}
+
+impl Drop for WebTransport {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+// ------ BidirectionalStream ------
+
+#[derive(Debug)]
+pub struct BidirectionalStream {
+    pub sender: ByteSender,
+    pub receiver: ByteStream,
+}
+
+impl BidirectionalStream {
+    fn new(stream: &raw::DuplexStream) -> Result<Self, JsValue> {
+        Ok(Self {
+            sender: ByteSender::new(&stream.writable())?,
+            receiver: ByteStream::new(&stream.readable())?,
+        })
+    }
+}
+
+// ------ ByteStream ------
+
+/// Stream of received chunks. Reading is cancelled when it's dropped.
+pub struct ByteStream {
+    reader: raw::Reader,
+    chunks: LocalBoxStream<'static, Result<JsValue, JsValue>>,
+}
+
+impl std::fmt::Debug for ByteStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ByteStream")
+    }
+}
+
+impl ByteStream {
+    fn new(readable: &raw::ReadableStream) -> Result<Self, JsValue> {
+        let reader = readable.get_reader()?;
+        Ok(Self {
+            chunks: reader_chunks(reader.clone().unchecked_into()),
+            reader,
+        })
+    }
+}
+
+impl Stream for ByteStream {
+    type Item = Result<Vec<u8>, JsValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.chunks.poll_next_unpin(cx).map(|chunk| {
+            chunk.map(|chunk| chunk.map(|chunk| js_sys::Uint8Array::new(&chunk).to_vec()))
+        })
+    }
+}
+
+impl Drop for ByteStream {
+    fn drop(&mut self) {
+        // The returned promise only signals the cancellation end.
+        let _ = self.reader.cancel();
+    }
+}
+
+fn read_chunks(
+    readable: &raw::ReadableStream,
+) -> Result<LocalBoxStream<'static, Result<JsValue, JsValue>>, JsValue> {
+    Ok(reader_chunks(readable.get_reader()?))
+}
+
+fn reader_chunks(reader: raw::Reader) -> LocalBoxStream<'static, Result<JsValue, JsValue>> {
+    stream::unfold(Some(reader), |reader| async move {
+        let reader = reader?;
+        match JsFuture::from(reader.read()).await {
+            // Stop after the first error.
+            Err(error) => Some((Err(error), None)),
+            Ok(result) => {
+                let result: raw::ReadResult = result.unchecked_into();
+                if result.done() {
+                    None
+                } else {
+                    Some((Ok(result.value()), Some(reader)))
+                }
+            }
+        }
+    })
+    .boxed_local()
+}
+
+// ------ ByteSender ------
+
+/// Sending half of a stream or datagrams. Cloning is cheap.
+#[derive(Clone)]
+pub struct ByteSender(Rc<raw::Writer>);
+
+impl std::fmt::Debug for ByteSender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ByteSender")
+    }
+}
+
+impl ByteSender {
+    fn new(writable: &raw::WritableStream) -> Result<Self, JsValue> {
+        Ok(Self(Rc::new(writable.get_writer()?)))
+    }
+
+    /// Resolves when the chunk has been handed over to the browser.
+    pub fn send(&self, bytes: &[u8]) -> impl Future<Output = Result<(), JsValue>> {
+        let chunk = js_sys::Uint8Array::from(bytes);
+        JsFuture::from(self.0.write(&chunk)).map(|result| result.map(|_| ()))
+    }
+
+    /// Close the stream after all sent chunks have been delivered.
+    pub fn close(&self) -> impl Future<Output = Result<(), JsValue>> {
+        JsFuture::from(self.0.close()).map(|result| result.map(|_| ()))
+    }
+}
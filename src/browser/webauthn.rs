@@ -0,0 +1,439 @@
+//! [Web Authentication](https://developer.mozilla.org/en-US/docs/Web/API/Web_Authentication_API) -
+//! passkeys and security keys.
+//!
+//! Server libraries (`webauthn-rs`, `SimpleWebAuthn`, `py_webauthn`, ...) send options as JSON
+//! with binary values encoded in base64url and expect the same encoding in responses.
+//! `create` and `get` do the conversion - pass the options JSON from the server
+//! and send `to_json()` of the result back.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!Msg::RegistrationOptionsFetched(options_json) => {
+//!    orders.perform_cmd(async move {
+//!        Msg::PasskeyCreated(webauthn::create(&options_json).await)
+//!    });
+//!}
+//!Msg::PasskeyCreated(Ok(credential)) => {
+//!    orders.perform_cmd(async move {
+//!        Msg::Registered(Request::new("/api/passkeys").method(Method::Post).text(credential.to_json()).fetch().await)
+//!    });
+//!}
+//!Msg::PasskeyCreated(Err(WebAuthnError::NotAllowed)) => (), // The user has cancelled the dialog.
+//! ```
+
+use super::util::window;
+use futures::future::Future;
+use serde::Serialize;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// Web Authentication result.
+pub type Result<T> = std::result::Result<T, WebAuthnError>;
+
+// ------ WebAuthnError ------
+
+#[derive(Debug, Clone)]
+pub enum WebAuthnError {
+    /// The browser doesn't support Web Authentication or the page isn't in a secure context.
+    Unsupported,
+    /// Options aren't valid JSON or they contain invalid base64url values.
+    InvalidOptions(&'static str),
+    /// The user has cancelled the dialog, it has timed out or the page isn't focused.
+    NotAllowed,
+    /// The authenticator already contains a credential from `excludeCredentials`.
+    InvalidState,
+    Failed(JsValue),
+}
+
+/// Binary values in creation options - `*` means all array items.
+const CREATION_BUFFERS: &[&[&str]] = &[
+    &["challenge"],
+    &["user", "id"],
+    &["excludeCredentials", "*", "id"],
+];
+
+/// Binary values in request options - `*` means all array items.
+const REQUEST_BUFFERS: &[&[&str]] = &[&["challenge"], &["allowCredentials", "*", "id"]];
+
+// ------ create ------
+
+/// Register a new credential (`navigator.credentials.create`).
+///
+/// `options_json` is `PublicKeyCredentialCreationOptionsJSON` - optionally wrapped in `{ "publicKey": ... }`.
+///
+/// # Errors
+///
+/// See `WebAuthnError`.
+pub fn create(options_json: &str) -> impl Future<Output = Result<RegistrationCredential>> {
+    let options = public_key_options(options_json, CREATION_BUFFERS);
+    async move {
+        let credential = request(options?, |credentials, options| {
+            credentials.create_with_options(options.unchecked_ref())
+        })
+        .await?;
+        registration(&credential).map_err(WebAuthnError::Failed)
+    }
+}
+
+/// Credential created by `create`.
+///
+/// Binary values are encoded in base64url.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationCredential {
+    pub id: String,
+    pub raw_id: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub response: AttestationResponse,
+    /// `"platform"` or `"cross-platform"`.
+    pub authenticator_attachment: Option<String>,
+    pub client_extension_results: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub attestation_object: String,
+    /// E.g. `"internal"`, `"hybrid"` or `"usb"`.
+    pub transports: Vec<String>,
+}
+
+impl RegistrationCredential {
+    /// `RegistrationResponseJSON` for the server.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the serialization fails - it can't happen for credentials returned by the browser.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("serialize registration credential")
+    }
+}
+
+fn registration(credential: &JsValue) -> std::result::Result<RegistrationCredential, JsValue> {
+    let response = js_sys::Reflect::get(credential, &"response".into())?;
+    let transports = call(&response, "getTransports")
+        .map(|transports| {
+            js_sys::Array::from(&transports)
+                .iter()
+                .filter_map(|transport| transport.as_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(RegistrationCredential {
+        id: string(credential, "id")?,
+        raw_id: buffer(credential, "rawId")?,
+        type_: string(credential, "type")?,
+        response: AttestationResponse {
+            client_data_json: buffer(&response, "clientDataJSON")?,
+            attestation_object: buffer(&response, "attestationObject")?,
+            transports,
+        },
+        authenticator_attachment: string(credential, "authenticatorAttachment").ok(),
+        client_extension_results: extension_results(credential),
+    })
+}
+
+// ------ get ------
+
+/// Authenticate by an existing credential (`navigator.credentials.get`).
+///
+/// `options_json` is `PublicKeyCredentialRequestOptionsJSON` - optionally wrapped in `{ "publicKey": ... }`.
+///
+/// # Errors
+///
+/// See `WebAuthnError`.
+pub fn get(options_json: &str) -> impl Future<Output = Result<AuthenticationCredential>> {
+    let options = public_key_options(options_json, REQUEST_BUFFERS);
+    async move {
+        let credential = request(options?, |credentials, options| {
+            credentials.get_with_options(options.unchecked_ref())
+        })
+        .await?;
+        authentication(&credential).map_err(WebAuthnError::Failed)
+    }
+}
+
+/// Credential returned by `get`.
+///
+/// Binary values are encoded in base64url.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticationCredential {
+    pub id: String,
+    pub raw_id: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub response: AssertionResponse,
+    pub authenticator_attachment: Option<String>,
+    pub client_extension_results: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    /// `PublicKeyCredentialUserEntity::id` - it's always set for passkeys.
+    pub user_handle: Option<String>,
+}
+
+impl AuthenticationCredential {
+    /// `AuthenticationResponseJSON` for the server.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the serialization fails - it can't happen for credentials returned by the browser.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("serialize authentication credential")
+    }
+}
+
+fn authentication(credential: &JsValue) -> std::result::Result<AuthenticationCredential, JsValue> {
+    let response = js_sys::Reflect::get(credential, &"response".into())?;
+    Ok(AuthenticationCredential {
+        id: string(credential, "id")?,
+        raw_id: buffer(credential, "rawId")?,
+        type_: string(credential, "type")?,
+        response: AssertionResponse {
+            client_data_json: buffer(&response, "clientDataJSON")?,
+            authenticator_data: buffer(&response, "authenticatorData")?,
+            signature: buffer(&response, "signature")?,
+            user_handle: buffer(&response, "userHandle").ok(),
+        },
+        authenticator_attachment: string(credential, "authenticatorAttachment").ok(),
+        client_extension_results: extension_results(credential),
+    })
+}
+
+// ------ is_platform_authenticator_available ------
+
+/// The device has a built-in authenticator (Touch ID, Windows Hello, Android screen lock, ...).
+/// Use it to decide whether to offer passkeys.
+pub async fn is_platform_authenticator_available() -> bool {
+    let available = async {
+        let promise = call(
+            &public_key_credential()?,
+            "isUserVerifyingPlatformAuthenticatorAvailable",
+        )?
+        .dyn_into::<js_sys::Promise>()?;
+        Ok::<_, JsValue>(JsFuture::from(promise).await?.as_bool())
+    }
+    .await;
+    available.ok().flatten().unwrap_or_default()
+}
+
+// ------ Helpers ------
+
+async fn request(
+    options: js_sys::Object,
+    call: impl FnOnce(
+        &web_sys::CredentialsContainer,
+        &js_sys::Object,
+    ) -> std::result::Result<js_sys::Promise, JsValue>,
+) -> Result<JsValue> {
+    public_key_credential().map_err(|_| WebAuthnError::Unsupported)?;
+    let wrapper = js_sys::Object::new();
+    js_sys::Reflect::set(&wrapper, &"publicKey".into(), &options).map_err(WebAuthnError::Failed)?;
+
+    let promise =
+        call(&window().navigator().credentials(), &wrapper).map_err(WebAuthnError::Failed)?;
+    JsFuture::from(promise).await.map_err(|error| {
+        let name = js_sys::Reflect::get(&error, &"name".into())
+            .ok()
+            .and_then(|name| name.as_string());
+        match name.as_deref() {
+            Some("NotAllowedError") => WebAuthnError::NotAllowed,
+            Some("InvalidStateError") => WebAuthnError::InvalidState,
+            // E.g. an invalid relying party id or an unsupported algorithm.
+            Some("SecurityError") => WebAuthnError::Unsupported,
+            _ => WebAuthnError::Failed(error),
+        }
+    })
+}
+
+fn public_key_credential() -> std::result::Result<JsValue, JsValue> {
+    let constructor = js_sys::Reflect::get(&window(), &"PublicKeyCredential".into())?;
+    if constructor.is_undefined() {
+        return Err("webauthn: Web Authentication isn't supported".into());
+    }
+    Ok(constructor)
+}
+
+/// Parse the options JSON and replace base64url strings in `buffers` with `Uint8Array`s.
+fn public_key_options(json: &str, buffers: &[&[&str]]) -> Result<js_sys::Object> {
+    let options = js_sys::JSON::parse(json)
+        .ok()
+        .filter(JsValue::is_object)
+        .ok_or(WebAuthnError::InvalidOptions(
+            "options aren't a JSON object",
+        ))?;
+    let options = match js_sys::Reflect::get(&options, &"publicKey".into()) {
+        Ok(public_key) if public_key.is_object() => public_key,
+        _ => options,
+    };
+    for path in buffers {
+        decode_buffers(&options, path)?;
+    }
+    Ok(options.unchecked_into())
+}
+
+fn decode_buffers(target: &JsValue, path: &[&str]) -> Result<()> {
+    if let Some((key, rest)) = path.split_first() {
+        if *key == "*" {
+            if let Some(items) = target.dyn_ref::<js_sys::Array>() {
+                for item in items.iter() {
+                    decode_buffers(&item, rest)?;
+                }
+            }
+            return Ok(());
+        }
+        let value = match js_sys::Reflect::get(target, &JsValue::from(*key)) {
+            Ok(value) if !value.is_undefined() && !value.is_null() => value,
+            _ => return Ok(()),
+        };
+        if !rest.is_empty() {
+            return decode_buffers(&value, rest);
+        }
+        let bytes = value
+            .as_string()
+            .and_then(|text| base64url_decode(&text))
+            .ok_or(WebAuthnError::InvalidOptions("invalid base64url value"))?;
+        js_sys::Reflect::set(
+            target,
+            &JsValue::from(*key),
+            &js_sys::Uint8Array::from(bytes.as_slice()),
+        )
+        .map_err(WebAuthnError::Failed)?;
+    }
+    Ok(())
+}
+
+fn call(target: &JsValue, method: &str) -> std::result::Result<JsValue, JsValue> {
+    js_sys::Reflect::get(target, &method.into())?
+        .dyn_into::<js_sys::Function>()?
+        .call0(target)
+}
+
+fn string(target: &JsValue, key: &str) -> std::result::Result<String, JsValue> {
+    js_sys::Reflect::get(target, &key.into())?
+        .as_string()
+        .ok_or_else(|| JsValue::from(format!("webauthn: `{}` isn't a string", key)))
+}
+
+/// Read the `ArrayBuffer` and encode it in base64url.
+fn buffer(target: &JsValue, key: &str) -> std::result::Result<String, JsValue> {
+    let buffer = js_sys::Reflect::get(target, &key.into())?
+        .dyn_into::<js_sys::ArrayBuffer>()
+        .map_err(|_| JsValue::from(format!("webauthn: `{}` isn't an ArrayBuffer", key)))?;
+    Ok(base64url_encode(&js_sys::Uint8Array::new(&buffer).to_vec()))
+}
+
+fn extension_results(credential: &JsValue) -> serde_json::Value {
+    call(credential, "getClientExtensionResults")
+        .and_then(|results| js_sys::JSON::stringify(&results))
+        .ok()
+        .and_then(|json| json.as_string())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_else(|| serde_json::Value::Object(serde_json::Map::new()))
+}
+
+// ------ base64url ------
+
+const BASE64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encode without padding.
+fn base64url_encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len() / 3 * 4 + 4);
+    let (mut buffer, mut bits) = (0_u32, 0);
+    for byte in bytes {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 6 {
+            bits -= 6;
+            text.push(char::from(BASE64URL[(buffer >> bits) as usize & 63]));
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        text.push(char::from(BASE64URL[(buffer << (6 - bits)) as usize & 63]));
+    }
+    text
+}
+
+/// Decode with or without padding; the standard base64 alphabet is accepted too.
+fn base64url_decode(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0_u32, 0);
+    for character in text.trim_end_matches('=').bytes() {
+        let value = match character {
+            b'+' => 62,
+            b'/' => 63,
+            _ => BASE64URL.iter().position(|&item| item == character)?,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn base64url_round_trip() {
+        for (bytes, text) in &[
+            (&b""[..], ""),
+            (&b"f"[..], "Zg"),
+            (&b"fo"[..], "Zm8"),
+            (&b"foo"[..], "Zm9v"),
+            (&[0xfb, 0xff][..], "-_8"),
+        ] {
+            assert_eq!(base64url_encode(bytes), *text);
+            assert_eq!(base64url_decode(text).as_deref(), Some(*bytes));
+        }
+        assert_eq!(base64url_decode("+/8="), Some(vec![0xfb, 0xff]));
+        assert_eq!(base64url_decode("Zm9v!"), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn decode_creation_options() {
+        let json = r#"{"publicKey": {
+            "challenge": "Zm9v",
+            "user": { "id": "Zg", "name": "martin" },
+            "excludeCredentials": [{ "id": "Zm8", "type": "public-key" }]
+        }}"#;
+        let options = public_key_options(json, CREATION_BUFFERS).unwrap();
+
+        let bytes = |value: JsValue| value.dyn_into::<js_sys::Uint8Array>().unwrap().to_vec();
+        let get = |target: &JsValue, key: &str| js_sys::Reflect::get(target, &key.into()).unwrap();
+        assert_eq!(bytes(get(&options, "challenge")), b"foo");
+        assert_eq!(bytes(get(&get(&options, "user"), "id")), b"f");
+        assert_eq!(get(&get(&options, "user"), "name"), "martin");
+        let excluded = get(&options, "excludeCredentials")
+            .dyn_into::<js_sys::Array>()
+            .unwrap();
+        assert_eq!(bytes(get(&excluded.get(0), "id")), b"fo");
+
+        assert!(matches!(
+            public_key_options(r##"{"challenge": "#"}"##, REQUEST_BUFFERS),
+            Err(WebAuthnError::InvalidOptions(_))
+        ));
+    }
+}
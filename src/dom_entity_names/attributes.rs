@@ -48,3 +48,41 @@ macro_rules! make_attrs {
 
 mod attribute_names;
 pub use attribute_names::At;
+
+/// Boolean attributes - their presence means `true`, so they are rendered without a value
+/// or not at all.
+const BOOLEAN_ATTRIBUTES: &[&str] = &[
+    "allowfullscreen",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "hidden",
+    "inert",
+    "ismap",
+    "itemscope",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+];
+
+impl At {
+    /// Is it a boolean attribute (e.g. `disabled`)? Enumerated attributes
+    /// like `contenteditable` or `aria-*` require `"true"` / `"false"` values instead.
+    pub fn is_boolean(&self) -> bool {
+        BOOLEAN_ATTRIBUTES.contains(&self.as_str())
+    }
+}
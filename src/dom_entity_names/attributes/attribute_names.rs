@@ -42,6 +42,12 @@ make_attrs! {
     Translate => "translate", Type => "type", UseMap => "usemap", Value => "value", Width => "width",
     Wrap => "wrap",
 
+    // [Popover API](https://developer.mozilla.org/en-US/docs/Web/API/Popover_API)
+    Popover => "popover", PopoverTarget => "popovertarget", PopoverTargetAction => "popovertargetaction",
+
+    // [Responsive images](https://developer.mozilla.org/en-US/docs/Learn/HTML/Multimedia_and_embedding/Responsive_images)
+    Decoding => "decoding", Loading => "loading", Sizes => "sizes",
+
     // SVG
     // https://developer.mozilla.org/en-US/docs/Web/SVG/Attribute
     AccentHeight => "accent-height", Accumulate => "accumulate", Additive => "additive",
@@ -41,7 +41,13 @@ make_events! {
 
     Change => "change",
 
-    Input => "input",
+    Input => "input", BeforeInput => "beforeinput",
+
+    // [Popover API](https://developer.mozilla.org/en-US/docs/Web/API/Popover_API)
+    BeforeToggle => "beforetoggle", Toggle => "toggle",
+
+    // [MDN ref](https://developer.mozilla.org/en-US/docs/Web/API/HTMLDialogElement/cancel_event)
+    Cancel => "cancel",
 
     // deprecated, @TODO: remove
     TriggerUpdate => "triggerupdate"
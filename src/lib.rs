@@ -17,7 +17,7 @@ pub use crate::{
     },
     browser::service::fetch,
     browser::service::fetch::{Method, Request, ResponseDataResult, ResponseResult},
-    browser::service::routing::push_route,
+    browser::service::routing::{push_route, replace_route},
     browser::service::storage,
     browser::url::Url,
     browser::util::{
@@ -34,6 +34,8 @@ pub mod app;
 pub mod browser;
 pub mod dom_entity_names;
 pub mod helpers;
+pub mod ssg;
+pub mod testing;
 pub mod virtual_dom;
 
 /// Create an element flagged in a way that it will not be rendered. Useful
@@ -84,26 +86,30 @@ pub fn set_timeout(handler: Box<dyn Fn()>, timeout: i32) {
 pub mod prelude {
     pub use crate::{
         app::{
-            builder::init::Init, cmds, streams, subs, AfterMount, App, BeforeMount, CmdHandle,
-            GetElement, MessageMapper, MountType, Orders, RenderTimestampDelta, StreamHandle,
-            SubHandle, UrlHandling,
+            builder::init::Init, cmds, streams, subs, AfterMount, App, BeforeMount, Child,
+            CmdHandle, Component, ComponentMsg, GetElement, MessageMapper, MissingRoot, MountError,
+            MountType, Orders, PerformanceBudget, RenderTimestampDelta, StreamHandle, SubHandle,
+            UnloadGuard, UpdateEffect, UrlHandling,
         },
         browser::dom::css_units::*,
         browser::dom::event_handler::{
-            ev, input_ev, keyboard_ev, mouse_ev, pointer_ev, raw_ev, simple_ev,
+            dialog_close_ev, editor_input_ev, ev, input_ev, key_ev, keyboard_ev, mouse_ev,
+            paste_ev, pointer_data_ev, pointer_ev, popover_toggle_ev, raw_ev, simple_ev,
         },
+        browser::dom::key::Key,
         browser::util::{
             request_animation_frame, ClosureNew, RequestAnimationFrameHandle,
             RequestAnimationFrameTime,
         },
-        browser::Url,
+        browser::{Url, UrlSearch},
         helpers::not,
         // macros are exported in crate root
         // https://github.com/rust-lang-nursery/reference/blob/master/src/macros-by-example.md
         shortcuts::*,
         virtual_dom::{
-            el_ref::el_ref, AsAtValue, At, AtValue, CSSValue, El, ElRef, Ev, EventHandler, Node,
-            St, Tag, ToClasses, UpdateEl, UpdateElForIterator, View,
+            context, el_ref::el_ref, AsAtValue, At, AtValue, CSSValue, CheapEq, El, ElRef, Ev,
+            EventHandler, Node, St, Tag, ToClasses, UnmanagedContent, UpdateEl,
+            UpdateElForIterator, View, ViewCache,
         },
     };
     pub use indexmap::IndexMap; // for attrs and style to work.
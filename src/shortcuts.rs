@@ -79,7 +79,7 @@ element! {
     hr => Hr; li => Li; ol => Ol; p => P; pre => Pre; ul => Ul;
 
     a => A; abbr => Abbr;
-    b => B; bdi => Bdi; bdo => Bdo; br => Br; cite => Cite; code => Code; data => Data;
+    b => B; bdi => Bdi; bdo => Bdo; br => Br; cite => Cite; code => Code;
     dfn => Dfn; em => Em; i => I; kbd => Kbd; mark => Mark; q => Q; rb => Rb;
     rp => Rp; rt => Rt; rtc => Rtc; ruby => Ruby; s => S; samp => Samp; small => Small;
     span => Span; strong => Strong; sub => Sub; sup => Sup; time => Time; tt => Tt;
@@ -203,18 +203,85 @@ macro_rules! custom {
 macro_rules! attrs {
     { $($key:expr => $value:expr $(;)?$(,)?)* } => {
         {
+            #[allow(unused_imports)]
+            use $crate::virtual_dom::values::{
+                ToAtValueForAtValue, ToAtValueForBool, ToAtValueForOptionBool,
+                ToAtValueForOptionToString, ToAtValueForToString
+            };
             let mut vals = IndexMap::new();
             $(
                 // We can handle arguments of multiple types by using this:
-                // Strings, &strs, bools, numbers etc.
+                // Strings, &strs, bools, `Option`s, numbers etc.
                 // And cases like `true.as_attr_value()` or `AtValue::Ignored`.
-                vals.insert($key.into(), (&$value).into());
+                let at: $crate::virtual_dom::At = $key.into();
+                let value = (&&&$crate::virtual_dom::values::AtValueArg(&$value)).to_at_value(&at);
+                vals.insert(at, value);
             )*
             $crate::virtual_dom::Attrs::new(vals)
         }
      };
 }
 
+/// `data-*` attributes - values are converted like in `attrs!`.
+/// Without `=>` pairs it creates the `<data>` element like other element macros.
+///
+/// # Example
+///
+/// ```rust,no_run
+///div![
+///    data!["user-id" => user.id, "admin" => user.is_admin, "team" => user.team],
+///    &user.name,
+///]
+/// ```
+#[macro_export]
+macro_rules! data {
+    ( $($key:expr => $value:expr),+ $(,)? ) => {
+        {
+            #[allow(unused_imports)]
+            use $crate::virtual_dom::values::{
+                ToAtValueForAtValue, ToAtValueForBool, ToAtValueForOptionBool,
+                ToAtValueForOptionToString, ToAtValueForToString
+            };
+            let mut attrs = $crate::virtual_dom::Attrs::empty();
+            $(
+                let at = $crate::virtual_dom::At::from(format!("data-{}", $key));
+                let value = (&&&$crate::virtual_dom::values::AtValueArg(&$value)).to_at_value(&at);
+                attrs.add(at, value);
+            )+
+            attrs
+        }
+    };
+    ( $($part:expr),* $(,)? ) => {
+        {
+            #[allow(unused_mut)]
+            let mut el = El::empty($crate::virtual_dom::Tag::Data);
+            $( $part.update_el(&mut el); )*
+            $crate::virtual_dom::Node::Element(el)
+        }
+    };
+}
+
+/// A registered SVG icon - `icon!(name)`, `icon!(name, size)` or `icon!(name, size, class)`.
+/// See `browser::dom::icons`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///button![icon!("check", "20px", "icon-success"), "Save"]
+/// ```
+#[macro_export]
+macro_rules! icon {
+    ( $name:expr $(,)? ) => {
+        $crate::browser::dom::icons::icon($name, $crate::browser::dom::icons::DEFAULT_SIZE, "")
+    };
+    ( $name:expr, $size:expr $(,)? ) => {
+        $crate::browser::dom::icons::icon($name, $size, "")
+    };
+    ( $name:expr, $size:expr, $class:expr $(,)? ) => {
+        $crate::browser::dom::icons::icon($name, $size, $class)
+    };
+}
+
 /// Convenience macro. Ideal when there are multiple classes, and no other attrs.
 #[macro_export]
 macro_rules! class {
@@ -296,6 +363,31 @@ macro_rules! IF {
     }};
 }
 
+/// Link to the typed route - an anchor with the route's `href`
+/// and `aria-current="page"` when the route is active. See `seed::browser::Route`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///nav![
+///    a_route![Page::Home, "Home"],
+///    a_route![Page::User { id }, C!["user-link"], "Profile"],
+///]
+/// ```
+#[macro_export]
+macro_rules! a_route {
+    ( $route:expr $(, $part:expr )* $(,)? ) => {
+        {
+            let mut el = El::empty($crate::virtual_dom::Tag::A);
+            $crate::browser::route::link_attrs(&$route).update_el(&mut el);
+            $(
+                $part.update_el(&mut el);
+            )*
+            $crate::virtual_dom::Node::Element(el)
+        }
+    };
+}
+
 /// Convenience macro, for brevity.
 #[macro_export]
 macro_rules! id {
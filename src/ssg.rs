@@ -0,0 +1,282 @@
+//! Static site generation - render routes to HTML files (e.g. in a build script),
+//! serve them as static pages and let the app hydrate them on load.
+//!
+//! `title`, `meta`, `link` and `base` elements rendered by `view` are moved into the page head
+//! - create them by `custom!`, e.g. `custom![Tag::from("title"), "About"]`.
+//!
+//! _Note:_ Views are rendered without the browser, so `init` has to create the `Model`
+//! without `Orders` and `view` can't use browser APIs (e.g. `raw!` or `md!`).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!let pages = seed::ssg::render_routes(&["/", "/about", "/blog/first-post"], Model::new, view);
+//!let template = include_str!("index.html"); // contains `<!-- seed:head -->` and `<!-- seed:body -->`
+//!seed::ssg::write_pages(&pages, template, "dist").expect("write pages");
+//! ```
+
+use crate::browser::dom::Namespace;
+use crate::browser::Url;
+use crate::virtual_dom::{AtValue, El, Node, View};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// Replaced by head tags in the page template.
+pub const HEAD_PLACEHOLDER: &str = "<!-- seed:head -->";
+/// Replaced by the rendered view in the page template.
+pub const BODY_PLACEHOLDER: &str = "<!-- seed:body -->";
+
+const HEAD_TAGS: &[&str] = &["title", "meta", "link", "base"];
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+const RAW_TEXT_TAGS: &[&str] = &["script", "style"];
+
+// ------ StaticPage ------
+
+/// Route rendered by `render_routes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticPage {
+    pub route: String,
+    pub head: String,
+    pub body: String,
+}
+
+impl StaticPage {
+    /// Path relative to the output directory - e.g. `index.html` for `/`
+    /// and `blog/first-post/index.html` for `/blog/first-post`.
+    pub fn file_path(&self) -> PathBuf {
+        let path = self.route.split(&['?', '#'][..]).next().unwrap_or_default();
+        path.split('/')
+            .filter(|segment| !segment.is_empty())
+            .chain(std::iter::once("index.html"))
+            .collect()
+    }
+
+    /// Fill `HEAD_PLACEHOLDER` and `BODY_PLACEHOLDER` in the `template`.
+    pub fn to_html(&self, template: &str) -> String {
+        template
+            .replace(HEAD_PLACEHOLDER, &self.head)
+            .replace(BODY_PLACEHOLDER, &self.body)
+    }
+}
+
+// ------ Rendering ------
+
+/// Render each route - `init` creates the `Model` for the route's `Url`.
+pub fn render_routes<Ms: 'static, Mdl, V: View<Ms>>(
+    routes: &[&str],
+    init: impl Fn(Url) -> Mdl,
+    view: impl Fn(&Mdl) -> V,
+) -> Vec<StaticPage> {
+    routes
+        .iter()
+        .map(|route| {
+            let model = init(parse_route(route));
+            let mut nodes = view(&model).els();
+            let mut head_nodes = Vec::new();
+            extract_head_nodes(&mut nodes, &mut head_nodes);
+            StaticPage {
+                route: (*route).to_owned(),
+                head: render_to_string(&head_nodes),
+                body: render_to_string(&nodes),
+            }
+        })
+        .collect()
+}
+
+/// Write pages into `out_dir` - see `StaticPage::file_path` and `StaticPage::to_html`.
+///
+/// # Errors
+///
+/// Returns error when a directory or a file can't be created.
+pub fn write_pages(
+    pages: &[StaticPage],
+    template: &str,
+    out_dir: impl AsRef<Path>,
+) -> io::Result<()> {
+    for page in pages {
+        let file_path = out_dir.as_ref().join(page.file_path());
+        if let Some(dir) = file_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(file_path, page.to_html(template))?;
+    }
+    Ok(())
+}
+
+/// Serialize nodes to HTML. Event handlers are ignored.
+pub fn render_to_string<Ms>(nodes: &[Node<Ms>]) -> String {
+    let mut html = String::new();
+    for node in nodes {
+        write_node(&mut html, node, false);
+    }
+    html
+}
+
+/// It doesn't need `web_sys::Url`, so it works also in build scripts.
+fn parse_route(route: &str) -> Url {
+    let (route, hash) = split_once(route, '#');
+    let (path, search) = split_once(route, '?');
+    let mut url = Url::new(path.trim_start_matches('/').split('/').collect());
+    url.search = search.map(ToOwned::to_owned);
+    url.hash = hash.map(ToOwned::to_owned);
+    url
+}
+
+fn split_once(text: &str, separator: char) -> (&str, Option<&str>) {
+    match text.find(separator) {
+        Some(index) => (&text[..index], Some(&text[index + 1..])),
+        None => (text, None),
+    }
+}
+
+fn extract_head_nodes<Ms>(nodes: &mut Vec<Node<Ms>>, head_nodes: &mut Vec<Node<Ms>>) {
+    let (head, rest): (Vec<_>, Vec<_>) = nodes.drain(..).partition(|node| match node {
+        Node::Element(el) => el.namespace.is_none() && HEAD_TAGS.contains(&el.tag.as_str()),
+        _ => false,
+    });
+    head_nodes.extend(head);
+    *nodes = rest;
+    for node in nodes {
+        if let Node::Element(el) = node {
+            extract_head_nodes(&mut el.children, head_nodes);
+        }
+    }
+}
+
+fn write_node<Ms>(html: &mut String, node: &Node<Ms>, raw_text: bool) {
+    match node {
+        Node::Element(el) => write_el(html, el),
+        Node::Text(text) if raw_text => html.push_str(&text.text),
+        Node::Text(text) => push_escaped(html, &text.text, false),
+        Node::Empty => (),
+    }
+}
+
+fn write_el<Ms>(html: &mut String, el: &El<Ms>) {
+    let tag = el.tag.as_str();
+    html.push('<');
+    html.push_str(tag);
+    for (name, value) in &el.attrs.vals {
+        match value {
+            AtValue::Ignored => (),
+            AtValue::None => {
+                html.push(' ');
+                html.push_str(name.as_str());
+            }
+            AtValue::Some(value) => {
+                html.push(' ');
+                html.push_str(name.as_str());
+                html.push_str("=\"");
+                push_escaped(html, value, true);
+                html.push('"');
+            }
+        }
+    }
+    let style = el.style.to_string();
+    if !style.is_empty() {
+        html.push_str(" style=\"");
+        push_escaped(html, &style, true);
+        html.push('"');
+    }
+
+    let is_html = el.namespace.is_none() || el.namespace == Some(Namespace::Html);
+    if is_html && VOID_TAGS.contains(&tag) {
+        html.push('>');
+        return;
+    }
+    if !is_html && el.children.is_empty() {
+        html.push_str(" />");
+        return;
+    }
+    html.push('>');
+    let raw_text = is_html && RAW_TEXT_TAGS.contains(&tag);
+    for child in &el.children {
+        write_node(html, child, raw_text);
+    }
+    html.push_str("</");
+    html.push_str(tag);
+    html.push('>');
+}
+
+fn push_escaped(html: &mut String, text: &str, attribute: bool) {
+    for c in text.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' if attribute => html.push_str("&quot;"),
+            c => html.push(c),
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    struct Model {
+        path: Vec<String>,
+    }
+
+    fn view(model: &Model) -> Vec<Node<()>> {
+        nodes![
+            custom![Tag::from("title"), format!("Page {}", model.path.join("/"))],
+            div![
+                id!("content"),
+                custom![
+                    Tag::from("meta"),
+                    attrs! {At::Name => "description", At::Content => "A \"quoted\" page"}
+                ],
+                p!["1 < 2 & 3"],
+                input![attrs! {At::Disabled => AtValue::None}],
+                custom![Tag::from("script"), "if (1 < 2) {}"],
+            ],
+        ]
+    }
+
+    #[wasm_bindgen_test]
+    fn render_pages() {
+        let pages = render_routes(
+            &["/", "/blog/post?page=2"],
+            |url| Model { path: url.path },
+            view,
+        );
+
+        assert_eq!(pages[0].file_path(), PathBuf::from("index.html"));
+        assert_eq!(pages[1].file_path(), PathBuf::from("blog/post/index.html"));
+        assert_eq!(
+            pages[1].head,
+            "<title>Page blog/post</title>\
+             <meta name=\"description\" content=\"A &quot;quoted&quot; page\">"
+        );
+        assert_eq!(
+            pages[1].body,
+            "<div id=\"content\"><p>1 &lt; 2 &amp; 3</p><input disabled>\
+             <script>if (1 < 2) {}</script></div>"
+        );
+        assert_eq!(
+            pages[0].to_html("<head><!-- seed:head --></head><body><!-- seed:body --></body>"),
+            format!(
+                "<head>{}</head><body>{}</body>",
+                pages[0].head, pages[0].body
+            )
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn parse_routes() {
+        let url = parse_route("/blog/post?page=2#comments");
+        assert_eq!(url.path, vec!["blog", "post"]);
+        assert_eq!(url.search.as_deref(), Some("page=2"));
+        assert_eq!(url.hash.as_deref(), Some("comments"));
+    }
+}
@@ -0,0 +1,10 @@
+//! Utilities for testing Seed apps without a browser driver.
+//!
+//! They work with the virtual DOM produced by your `view` function,
+//! so you can test interactions like clicks or inputs and observe resulting `Msg`s.
+
+pub mod clock;
+pub mod events;
+
+pub use clock::TestClock;
+pub use events::{find, simulate, Locator, SyntheticEvent, TEST_ID_ATTRIBUTE};
@@ -0,0 +1,131 @@
+use crate::app::clock::{self, VirtualClock};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+
+// ------ TestClock ------
+
+/// Virtual time for `cmds::timeout` and `streams::interval`.
+///
+/// Timers created while the clock is installed don't wait for the real time -
+/// they fire only when you call `advance` or `run_all`.
+/// Timers created before `TestClock::install` aren't affected.
+///
+/// The real clock is restored when `TestClock` is dropped.
+///
+/// # Example
+///
+/// ```rust,no_run
+///let clock = TestClock::install();
+///orders.perform_cmd(cmds::timeout(5000, || Msg::TimedOut));
+///
+///clock.advance(5000);
+///clock.flush().await;
+///// `Msg::TimedOut` has been sent.
+/// ```
+#[derive(Debug)]
+pub struct TestClock {
+    clock: Rc<RefCell<VirtualClock>>,
+}
+
+impl TestClock {
+    /// Replace the real clock with a virtual one starting at 0 ms.
+    pub fn install() -> Self {
+        let clock = Rc::new(RefCell::new(VirtualClock::default()));
+        clock::install(Rc::clone(&clock));
+        Self { clock }
+    }
+
+    /// Current virtual time in milliseconds.
+    pub fn now(&self) -> u64 {
+        self.clock.borrow().now()
+    }
+
+    /// Move the virtual time forward by `ms` milliseconds.
+    /// All timers which become due fire in order; intervals fire once per elapsed period.
+    ///
+    /// Futures and streams are only woken up - call `flush` to let them run.
+    pub fn advance(&self, ms: u64) {
+        self.clock.borrow_mut().advance(ms);
+    }
+
+    /// Advance the time until all one-shot timers (i.e. timeouts) have fired.
+    pub fn run_all(&self) {
+        let last_due = self.clock.borrow_mut().last_one_shot_due();
+        if let Some(last_due) = last_due {
+            self.advance(last_due.saturating_sub(self.now()));
+        }
+    }
+
+    /// The number of timers waiting for the virtual time.
+    pub fn pending_timers(&self) -> usize {
+        self.clock.borrow_mut().pending_timers()
+    }
+
+    /// Yield to the executor so futures and streams woken up by `advance`
+    /// can produce their messages.
+    pub async fn flush(&self) {
+        let promise = js_sys::Promise::resolve(&JsValue::UNDEFINED);
+        JsFuture::from(promise)
+            .await
+            .expect("TestClock: resolve promise - failed");
+    }
+}
+
+impl Drop for TestClock {
+    fn drop(&mut self) {
+        clock::uninstall();
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{cmds, streams};
+    use futures::{FutureExt, StreamExt};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn timeout_fires_after_advance() {
+        let clock = TestClock::install();
+        let mut timeout = cmds::timeout(5000, || "timed out").boxed_local();
+
+        clock.advance(4999);
+        assert_eq!((&mut timeout).now_or_never(), None);
+
+        clock.advance(1);
+        assert_eq!(timeout.now_or_never(), Some("timed out"));
+        assert_eq!(clock.now(), 5000);
+        assert_eq!(clock.pending_timers(), 0);
+    }
+
+    #[wasm_bindgen_test]
+    fn interval_fires_per_period() {
+        let clock = TestClock::install();
+        let mut ticks = streams::interval(1000, || "tick").boxed_local();
+
+        clock.advance(3500);
+        let mut count = 0;
+        while let Some(Some(_)) = ticks.next().now_or_never() {
+            count += 1;
+        }
+        assert_eq!(count, 3);
+        assert_eq!(clock.pending_timers(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn run_all_fires_timeouts() {
+        let clock = TestClock::install();
+        let first = cmds::timeout(100, || 1);
+        let second = cmds::timeout(2000, || 2);
+
+        clock.run_all();
+        assert_eq!(first.now_or_never(), Some(1));
+        assert_eq!(second.now_or_never(), Some(2));
+        assert_eq!(clock.now(), 2000);
+    }
+}
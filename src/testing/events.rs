@@ -0,0 +1,290 @@
+use crate::browser::util::{self, document};
+use crate::virtual_dom::{At, AtValue, El, Ev, Node, Selector};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// Attribute used by `Locator::TestId`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///button![attrs! {At::from(TEST_ID_ATTRIBUTE) => "save"}, "Save"]
+/// ```
+pub const TEST_ID_ATTRIBUTE: &str = "data-testid";
+
+// ------ Locator ------
+
+/// Describes how to find an element in the virtual DOM.
+#[derive(Debug, Clone, Copy)]
+pub enum Locator<'a> {
+    /// Element with the given `id`.
+    Id(&'a str),
+    /// Element which contains the given class.
+    Class(&'a str),
+    /// Element with the attribute `data-testid` set to the given value.
+    TestId(&'a str),
+    /// Element which matches the given CSS selector - see `Selector` for supported syntax.
+    Selector(&'a str),
+    /// Element whose trimmed text equals the given one - see `El::find_by_text`.
+    Text(&'a str),
+}
+
+impl Locator<'_> {
+    fn matches<Ms>(self, el: &El<Ms>) -> bool {
+        let attr_value = |at: &At| match el.attrs.vals.get(at) {
+            Some(AtValue::Some(value)) => Some(value.as_str()),
+            _ => None,
+        };
+        match self {
+            Locator::Id(id) => attr_value(&At::Id) == Some(id),
+            Locator::Class(class) => attr_value(&At::Class).map_or(false, |classes| {
+                classes.split_whitespace().any(|c| c == class)
+            }),
+            Locator::TestId(test_id) => {
+                attr_value(&At::Custom(TEST_ID_ATTRIBUTE.into())) == Some(test_id)
+            }
+            Locator::Text(text) => el.get_text().trim() == text,
+            // Selectors need ancestors, so they are resolved directly in `find`.
+            Locator::Selector(_) => false,
+        }
+    }
+}
+
+/// Find the first element (depth-first) which matches the `locator`.
+///
+/// # Example
+///
+/// ```rust,no_run
+///let nodes = view(&model).els();
+///let button = testing::find(&nodes, Locator::Id("increment")).expect("increment button");
+/// ```
+///
+/// # Panics
+///
+/// Panics if `Locator::Selector` contains an invalid selector.
+pub fn find<'a, Ms>(nodes: &'a [Node<Ms>], locator: Locator) -> Option<&'a El<Ms>> {
+    if let Locator::Selector(selector) = locator {
+        let selector = Selector::parse(selector).unwrap_or_else(|err| panic!("{}", err));
+        return selector.select_in(nodes).into_iter().next();
+    }
+    for node in nodes {
+        if let Node::Element(el) = node {
+            if locator.matches(el) {
+                return Some(el);
+            }
+            if let Some(el) = find(&el.children, locator) {
+                return Some(el);
+            }
+        }
+    }
+    None
+}
+
+// ------ SyntheticEvent ------
+
+#[derive(Debug, Clone)]
+enum EventKind {
+    Mouse,
+    Input(String),
+    Keyboard(String),
+    Plain,
+}
+
+/// Event created in tests to simulate user interactions.
+#[derive(Debug, Clone)]
+pub struct SyntheticEvent {
+    trigger: Ev,
+    kind: EventKind,
+}
+
+impl SyntheticEvent {
+    /// `click` `MouseEvent`.
+    pub fn click() -> Self {
+        Self {
+            trigger: Ev::Click,
+            kind: EventKind::Mouse,
+        }
+    }
+
+    /// `input` `Event` whose target has the given `value`.
+    /// It works with `input_ev` because the target element is created under the hood.
+    pub fn input(value: impl Into<String>) -> Self {
+        Self {
+            trigger: Ev::Input,
+            kind: EventKind::Input(value.into()),
+        }
+    }
+
+    /// `keydown` `KeyboardEvent` with the given `key` (e.g. `"Enter"` or `"a"`).
+    ///
+    /// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/key)
+    pub fn key_press(key: impl Into<String>) -> Self {
+        Self {
+            trigger: Ev::KeyDown,
+            kind: EventKind::Keyboard(key.into()),
+        }
+    }
+
+    /// Event without any data.
+    pub fn new(trigger: impl Into<Ev>) -> Self {
+        Self {
+            trigger: trigger.into(),
+            kind: EventKind::Plain,
+        }
+    }
+
+    /// Change the event trigger - e.g. `SyntheticEvent::key_press("Enter").trigger(Ev::KeyUp)`.
+    pub fn trigger(mut self, trigger: impl Into<Ev>) -> Self {
+        self.trigger = trigger.into();
+        self
+    }
+
+    fn to_web_sys_event(&self) -> web_sys::Event {
+        let trigger = self.trigger.as_str();
+        match &self.kind {
+            EventKind::Mouse => web_sys::MouseEvent::new(trigger)
+                .expect("create `MouseEvent`")
+                .unchecked_into(),
+            EventKind::Input(_) => web_sys::InputEvent::new(trigger)
+                .expect("create `InputEvent`")
+                .unchecked_into(),
+            EventKind::Keyboard(key) => {
+                let mut init = web_sys::KeyboardEventInit::new();
+                init.key(key);
+                web_sys::KeyboardEvent::new_with_keyboard_event_init_dict(trigger, &init)
+                    .expect("create `KeyboardEvent`")
+                    .unchecked_into()
+            }
+            EventKind::Plain => web_sys::Event::new(trigger).expect("create `Event`"),
+        }
+    }
+}
+
+// ------ simulate ------
+
+/// Invoke `el`'s event handlers registered for the `event`'s trigger and return produced `Msg`s.
+///
+/// The event is dispatched on a detached DOM element so handlers can read its target
+/// (e.g. `input_ev` gets the value passed to `SyntheticEvent::input`).
+///
+/// # Example
+///
+/// ```rust,no_run
+///let nodes = view(&model).els();
+///let input = testing::find(&nodes, Locator::TestId("new-todo")).unwrap();
+///let messages = testing::simulate(input, SyntheticEvent::input("Buy milk"));
+///assert!(matches!(messages.as_slice(), [Msg::NewTodoTitleChanged(title)] if title == "Buy milk"));
+/// ```
+pub fn simulate<Ms: 'static>(el: &El<Ms>, event: SyntheticEvent) -> Vec<Ms> {
+    let target = match &el.namespace {
+        Some(namespace) => document().create_element_ns(Some(namespace.as_str()), el.tag.as_str()),
+        None => document().create_element(el.tag.as_str()),
+    }
+    .expect("create target element");
+
+    if let EventKind::Input(value) = &event.kind {
+        util::set_value(&target, value).unwrap_or_else(|err| {
+            crate::error(err);
+        });
+    }
+
+    let handlers = el.event_handler_manager.event_handlers(&event.trigger);
+    let messages = Rc::new(RefCell::new(Vec::new()));
+
+    let callback = Closure::wrap(Box::new({
+        let messages = Rc::clone(&messages);
+        move |event: web_sys::Event| {
+            for handler in &handlers {
+                messages
+                    .borrow_mut()
+                    .push((handler.callback)(event.clone()));
+            }
+        }
+    }) as Box<dyn FnMut(web_sys::Event)>);
+
+    let trigger = event.trigger.as_str();
+    target
+        .add_event_listener_with_callback(trigger, callback.as_ref().unchecked_ref())
+        .expect("attach simulation listener");
+    target
+        .dispatch_event(&event.to_web_sys_event())
+        .expect("dispatch synthetic event");
+    target
+        .remove_event_listener_with_callback(trigger, callback.as_ref().unchecked_ref())
+        .expect("detach simulation listener");
+
+    messages.replace(Vec::new())
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, PartialEq)]
+    enum Msg {
+        Clicked,
+        InputChanged(String),
+        KeyPressed(String),
+    }
+
+    fn view() -> Vec<Node<Msg>> {
+        vec![div![
+            button![
+                id!("save"),
+                C!["primary"],
+                ev(Ev::Click, |_| Msg::Clicked),
+                "Save"
+            ],
+            input![
+                attrs! {At::from(TEST_ID_ATTRIBUTE) => "title"},
+                input_ev(Ev::Input, Msg::InputChanged),
+                keyboard_ev(Ev::KeyDown, |event| Msg::KeyPressed(event.key())),
+            ],
+        ]]
+    }
+
+    #[wasm_bindgen_test]
+    fn simulate_click() {
+        let nodes = view();
+        let button = find(&nodes, Locator::Id("save")).expect("button");
+        assert_eq!(
+            simulate(button, SyntheticEvent::click()),
+            vec![Msg::Clicked]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn find_by_selector_and_text() {
+        let nodes = view();
+        let by_selector = find(&nodes, Locator::Selector("div > button.primary"));
+        let by_text = find(&nodes, Locator::Text("Save"));
+        assert_eq!(by_selector.map(|el| &el.tag), Some(&Tag::Button));
+        assert_eq!(by_text.map(|el| &el.tag), Some(&Tag::Button));
+    }
+
+    #[wasm_bindgen_test]
+    fn simulate_input() {
+        let nodes = view();
+        let input = find(&nodes, Locator::TestId("title")).expect("input");
+        assert_eq!(
+            simulate(input, SyntheticEvent::input("Seed")),
+            vec![Msg::InputChanged("Seed".to_owned())]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn simulate_key_press() {
+        let nodes = view();
+        let input = find(&nodes, Locator::TestId("title")).expect("input");
+        assert_eq!(
+            simulate(input, SyntheticEvent::key_press("Enter")),
+            vec![Msg::KeyPressed("Enter".to_owned())]
+        );
+    }
+}
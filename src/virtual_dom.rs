@@ -1,25 +1,31 @@
 pub mod attrs;
+pub mod context;
 pub mod el_ref;
 pub mod event_handler_manager;
 pub mod mailbox;
 pub mod node;
 pub mod patch;
+pub mod selector;
 pub mod style;
 pub mod to_classes;
 pub mod update_el;
+pub mod validation;
 pub mod values;
 pub mod view;
+pub mod view_cache;
 
 pub use attrs::Attrs;
 pub use el_ref::{el_ref, ElRef, SharedNodeWs};
-pub use event_handler_manager::{EventHandler, EventHandlerManager, Listener};
+pub use event_handler_manager::{EventHandler, EventHandlerManager, Listener, ListenerOptions};
 pub use mailbox::Mailbox;
-pub use node::{El, IntoNodes, Node, Text};
+pub use node::{El, IntoNodes, Node, Text, UnmanagedContent};
+pub use selector::Selector;
 pub use style::Style;
 pub use to_classes::ToClasses;
 pub use update_el::{UpdateEl, UpdateElForIterator};
 pub use values::{AsAtValue, AtValue, CSSValue};
 pub use view::View;
+pub use view_cache::{CheapEq, ViewCache};
 
 pub use crate::dom_entity_names::{At, Ev, St, Tag};
 
@@ -270,6 +276,43 @@ pub mod tests {
         }
     }
 
+    #[wasm_bindgen_test]
+    fn unmanaged_content_not_patched() {
+        let app = create_app();
+        let mailbox = Mailbox::new(|_msg: Msg| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+
+        let mut vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            seed::empty(),
+            div![UnmanagedContent, "initial"],
+            &app,
+        );
+        let el_ws = parent.first_element_child().unwrap();
+        assert_eq!(el_ws.inner_html(), "initial");
+
+        // The user edits the content.
+        el_ws.set_inner_html("edited <b>text</b>");
+        vdom = call_patch(
+            &doc,
+            &parent,
+            &mailbox,
+            vdom,
+            div![UnmanagedContent, class!["editor"], "initial"],
+            &app,
+        );
+        assert_eq!(el_ws.inner_html(), "edited <b>text</b>");
+        assert_eq!(el_ws.get_attribute("class"), Some("editor".to_string()));
+
+        call_patch(&doc, &parent, &mailbox, vdom, div!["managed"], &app);
+        assert!(el_ws.is_same_node(parent.first_child().as_ref()));
+        assert_eq!(el_ws.inner_html(), "managed");
+    }
+
     /// Test if attribute `disabled` is correctly added and then removed.
     #[wasm_bindgen_test]
     fn attr_disabled() {
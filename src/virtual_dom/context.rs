@@ -0,0 +1,113 @@
+//! Values provided to a part of the view tree - theme, locale, permissions, etc. -
+//! so nested view functions don't need to pass them through their arguments.
+//!
+//! `provide` makes the value available to `get` while its closure renders the subtree;
+//! the innermost value of the given type wins. Values are available only while rendering -
+//! clone them into event handlers which need them.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!fn view(model: &Model) -> Node<Msg> {
+//!    context::provide(model.theme, || {
+//!        div![view_header(), view_content(model)]
+//!    })
+//!}
+//!
+//!fn view_button(label: &str) -> Node<Msg> {
+//!    let theme = context::expect::<Theme>();
+//!    button![C![theme.button_class()], label]
+//!}
+//! ```
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+thread_local! {
+    static PROVIDED: RefCell<Vec<(TypeId, Rc<dyn Any>)>> = RefCell::new(Vec::new());
+}
+
+/// Removes the provided value also when the closure panics.
+struct ProvidedGuard;
+
+impl Drop for ProvidedGuard {
+    fn drop(&mut self) {
+        PROVIDED.with(|provided| provided.borrow_mut().pop());
+    }
+}
+
+/// Make `value` available to `get` in `render` and in all functions it calls.
+pub fn provide<T: 'static, R>(value: T, render: impl FnOnce() -> R) -> R {
+    PROVIDED.with(|provided| {
+        provided
+            .borrow_mut()
+            .push((TypeId::of::<T>(), Rc::new(value)));
+    });
+    let _guard = ProvidedGuard;
+    render()
+}
+
+/// The innermost provided value of the type `T`; `None` when it hasn't been provided.
+pub fn get<T: 'static>() -> Option<Rc<T>> {
+    PROVIDED.with(|provided| {
+        provided
+            .borrow()
+            .iter()
+            .rev()
+            .find(|(type_id, _)| *type_id == TypeId::of::<T>())
+            .and_then(|(_, value)| Rc::clone(value).downcast::<T>().ok())
+    })
+}
+
+/// The innermost provided value of the type `T`.
+///
+/// # Panics
+///
+/// Panics when the value hasn't been provided.
+pub fn expect<T: 'static>() -> Rc<T> {
+    get().unwrap_or_else(|| {
+        panic!(
+            "context value `{}` hasn't been provided",
+            std::any::type_name::<T>()
+        )
+    })
+}
+
+/// The innermost provided value of the type `T` or `T::default()`.
+pub fn get_or_default<T: 'static + Default>() -> Rc<T> {
+    get().unwrap_or_default()
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Theme {
+        Light,
+        Dark,
+    }
+
+    #[wasm_bindgen_test]
+    fn innermost_value_wins() {
+        assert_eq!(get::<Theme>(), None);
+        provide(Theme::Dark, || {
+            provide("locale", || {
+                assert_eq!(*expect::<Theme>(), Theme::Dark);
+                provide(Theme::Light, || {
+                    assert_eq!(*expect::<Theme>(), Theme::Light);
+                });
+                assert_eq!(*expect::<Theme>(), Theme::Dark);
+                assert_eq!(*expect::<&str>(), "locale");
+            });
+        });
+        assert_eq!(get::<Theme>(), None);
+        assert_eq!(*get_or_default::<u32>(), 0);
+    }
+}
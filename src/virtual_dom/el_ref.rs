@@ -1,6 +1,6 @@
 use crate::util::document;
 use std::{cell::RefCell, marker::PhantomData, rc::Rc};
-use wasm_bindgen::JsCast;
+use wasm_bindgen::{JsCast, JsValue};
 
 /// Attaches given `ElRef` to the DOM element.
 ///
@@ -112,6 +112,41 @@ impl<E: Clone + JsCast> ElRef<E> {
             phantom: PhantomData,
         }
     }
+
+    /// Send all next events of the pointer `pointer_id` (see `Pointer.id`) to the referenced
+    /// element, e.g. to continue dragging when the pointer leaves the element.
+    /// The capture is released on `pointerup` or by `release_pointer_capture`.
+    ///
+    /// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/Element/setPointerCapture)
+    ///
+    /// # Errors
+    ///
+    /// Returns error when the element isn't in the DOM or the pointer isn't active.
+    pub fn set_pointer_capture(&self, pointer_id: i32) -> Result<(), JsValue> {
+        self.element()?.set_pointer_capture(pointer_id)
+    }
+
+    /// [MDN reference](https://developer.mozilla.org/en-US/docs/Web/API/Element/releasePointerCapture)
+    ///
+    /// # Errors
+    ///
+    /// Returns error when the element isn't in the DOM or the pointer isn't active.
+    pub fn release_pointer_capture(&self, pointer_id: i32) -> Result<(), JsValue> {
+        self.element()?.release_pointer_capture(pointer_id)
+    }
+
+    /// Returns `false` also when the element isn't in the DOM.
+    pub fn has_pointer_capture(&self, pointer_id: i32) -> bool {
+        self.map_type::<web_sys::Element>()
+            .get()
+            .map_or(false, |element| element.has_pointer_capture(pointer_id))
+    }
+
+    fn element(&self) -> Result<web_sys::Element, JsValue> {
+        self.map_type::<web_sys::Element>()
+            .get()
+            .ok_or_else(|| JsValue::from("`ElRef` isn't attached to an element in the DOM"))
+    }
 }
 
 impl<E> Default for ElRef<E> {
@@ -5,7 +5,7 @@ use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
 pub mod event_handler;
 pub mod listener;
 
-pub use event_handler::EventHandler;
+pub use event_handler::{EventHandler, ListenerOptions};
 pub use listener::Listener;
 
 // ------ EventHandlerManager ------
@@ -13,7 +13,8 @@ pub use listener::Listener;
 #[derive(Debug, Default)]
 /// Manages event handlers and listeners for elements.
 pub struct EventHandlerManager<Ms> {
-    groups: BTreeMap<Ev, Group<Ms>>,
+    // Handlers with the same trigger but different options need different listeners.
+    groups: BTreeMap<(Ev, ListenerOptions), Group<Ms>>,
 }
 
 // @TODO remove custom impl once https://github.com/rust-lang/rust/issues/26925 is fixed
@@ -51,17 +52,21 @@ impl<Ms> EventHandlerManager<Ms> {
     ) {
         let event_target = event_target.into();
 
-        for (trigger, group) in &mut self.groups {
+        for ((trigger, options), group) in &mut self.groups {
             if group.listener.is_none() {
                 group.listener = old_manager
                     .as_mut()
                     .and_then(|old_manager| {
-                        old_manager
-                            .take_and_setup_listener(trigger, Rc::clone(&group.event_handlers))
+                        old_manager.take_and_setup_listener(
+                            trigger,
+                            *options,
+                            Rc::clone(&group.event_handlers),
+                        )
                     })
                     .or_else(|| {
                         Some(Listener::new(
                             trigger.clone(),
+                            *options,
                             event_target.clone(),
                             Rc::clone(&group.event_handlers),
                             mailbox.clone(),
@@ -75,11 +80,12 @@ impl<Ms> EventHandlerManager<Ms> {
     /// It doesn't create listeners automatically - you have to call `attach_listeners`.
     pub fn add_event_handlers(&mut self, event_handlers: Vec<EventHandler<Ms>>) {
         for handler in event_handlers {
-            if let Some(group) = self.groups.get_mut(&handler.trigger) {
+            let key = (handler.trigger.clone(), handler.options);
+            if let Some(group) = self.groups.get_mut(&key) {
                 group.event_handlers.borrow_mut().push(handler);
             } else {
                 self.groups.insert(
-                    handler.trigger.clone(),
+                    key,
                     Group {
                         event_handlers: Rc::new(RefCell::new(vec![handler])),
                         listener: None,
@@ -89,14 +95,30 @@ impl<Ms> EventHandlerManager<Ms> {
         }
     }
 
+    /// Returns clones of event handlers with the given trigger.
+    /// Capture-phase handlers are first, like in the browser.
+    /// It's useful when you want to call handlers without the DOM - e.g. in tests.
+    pub fn event_handlers(&self, trigger: &Ev) -> Vec<EventHandler<Ms>> {
+        let mut event_handlers: Vec<_> = self
+            .groups
+            .iter()
+            .filter(|((group_trigger, _), _)| group_trigger == trigger)
+            .flat_map(|(_, group)| group.event_handlers.borrow().clone())
+            .collect();
+        event_handlers.sort_by_key(|handler| !handler.options.capture);
+        event_handlers
+    }
+
     /// This method is used in `attach_listeners` method to move listeners from the old manager.
+    /// Only listeners with the same `options` can be reused.
     pub fn take_and_setup_listener(
         &mut self,
         trigger: &Ev,
+        options: ListenerOptions,
         event_handlers: Rc<RefCell<Vec<EventHandler<Ms>>>>,
     ) -> Option<Listener<Ms>> {
         self.groups
-            .get_mut(trigger)
+            .get_mut(&(trigger.clone(), options))
             .and_then(|group| group.listener.take())
             .map(|listener| {
                 listener.set_event_handlers(event_handlers);
@@ -117,7 +139,7 @@ impl<Ms: 'static, OtherMs: 'static> MessageMapper<Ms, OtherMs> for EventHandlerM
             groups: self
                 .groups
                 .into_iter()
-                .map(|(trigger, group)| (trigger, group.map_msg(f.clone())))
+                .map(|(key, group)| (key, group.map_msg(f.clone())))
                 .collect(),
         }
     }
@@ -166,3 +188,58 @@ impl<Ms: 'static, OtherMs: 'static> MessageMapper<Ms, OtherMs> for Group<Ms> {
         }
     }
 }
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn capture_handlers_first() {
+        let manager = EventHandlerManager::with_event_handlers(vec![
+            EventHandler::new(Ev::Click, |_| "bubble"),
+            EventHandler::new(Ev::Click, |_| "capture").capture(),
+            EventHandler::new(Ev::Click, |_| "passive").passive(),
+        ]);
+        let event = web_sys::Event::new("click").unwrap();
+
+        let msgs: Vec<_> = manager
+            .event_handlers(&Ev::Click)
+            .iter()
+            .map(|handler| (handler.callback)(event.clone()))
+            .collect();
+        assert_eq!(msgs, vec!["capture", "bubble", "passive"]);
+    }
+
+    #[wasm_bindgen_test]
+    fn reattach_listener_when_options_change() {
+        let target = crate::browser::util::document()
+            .create_element("div")
+            .unwrap();
+        let msgs = Rc::new(RefCell::new(Vec::new()));
+        let mailbox = Mailbox::new({
+            let msgs = Rc::clone(&msgs);
+            move |msg| msgs.borrow_mut().push(msg)
+        });
+
+        let mut old_manager =
+            EventHandlerManager::with_event_handlers(vec![EventHandler::new(Ev::Click, |_| "old")]);
+        old_manager.attach_listeners(target.clone(), None, &mailbox);
+
+        let mut new_manager =
+            EventHandlerManager::with_event_handlers(vec![
+                EventHandler::new(Ev::Click, |_| "new").capture()
+            ]);
+        new_manager.attach_listeners(target.clone(), Some(&mut old_manager), &mailbox);
+        drop(old_manager);
+
+        target
+            .dispatch_event(&web_sys::Event::new("click").unwrap())
+            .unwrap();
+        assert_eq!(*msgs.borrow(), vec!["new"]);
+    }
+}
@@ -2,10 +2,27 @@ use crate::app::MessageMapper;
 use crate::virtual_dom::Ev;
 use std::{fmt, rc::Rc};
 
-/// `EventHandler`s are called by DOM event listeners with the same trigger (an event to listen to).
+// ------ ListenerOptions ------
+
+/// [addEventListener options](https://developer.mozilla.org/en-US/docs/Web/API/EventTarget/addEventListener#Parameters)
+/// of the DOM event listener which calls the handler.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ListenerOptions {
+    /// The handler can't prevent the default action - the browser doesn't have to wait
+    /// for it while scrolling.
+    pub passive: bool,
+    /// The handler is called in the capture phase - i.e. before handlers of descendants.
+    pub capture: bool,
+}
+
+// ------ EventHandler ------
+
+/// `EventHandler`s are called by DOM event listeners with the same trigger (an event to listen to)
+/// and the same `ListenerOptions`.
 pub struct EventHandler<Ms> {
     pub trigger: Ev,
     pub callback: Rc<dyn Fn(web_sys::Event) -> Ms>,
+    pub options: ListenerOptions,
 }
 
 // @TODO remove custom impl once https://github.com/rust-lang/rust/issues/26925 is fixed
@@ -14,6 +31,7 @@ impl<Ms> Clone for EventHandler<Ms> {
         Self {
             trigger: self.trigger.clone(),
             callback: Rc::clone(&self.callback),
+            options: self.options,
         }
     }
 }
@@ -23,8 +41,65 @@ impl<Ms> EventHandler<Ms> {
         Self {
             trigger: trigger.into(),
             callback: Rc::new(callback),
+            options: ListenerOptions::default(),
         }
     }
+
+    /// Register the handler as passive - e.g. for `Ev::Scroll`, `Ev::TouchMove` or `Ev::Wheel`.
+    /// Calling `prevent_default` in passive handlers doesn't have any effect.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///ev(Ev::TouchMove, |_| Msg::Scrolled).passive()
+    /// ```
+    pub const fn passive(mut self) -> Self {
+        self.options.passive = true;
+        self
+    }
+
+    /// Call the handler in the capture phase - before handlers of descendants.
+    pub const fn capture(mut self) -> Self {
+        self.options.capture = true;
+        self
+    }
+}
+
+impl<Ms: 'static> EventHandler<Ms> {
+    /// Call `event.prevent_default()` before the handler - e.g. to handle form submits
+    /// or links without a closure that takes the raw event.
+    /// It doesn't have any effect in `passive` handlers.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///form![
+    ///    ev(Ev::Submit, |_| Msg::Submitted).prevent_default(),
+    ///    ...
+    ///]
+    ///a![
+    ///    attrs!{At::Href => "/item"},
+    ///    ev(Ev::Click, |_| Msg::ItemClicked).prevent_default().stop_propagation(),
+    ///]
+    /// ```
+    pub fn prevent_default(self) -> Self {
+        self.wrap_callback(web_sys::Event::prevent_default)
+    }
+
+    /// Call `event.stop_propagation()` before the handler - i.e. ancestors' handlers
+    /// of the same event aren't called.
+    pub fn stop_propagation(self) -> Self {
+        self.wrap_callback(web_sys::Event::stop_propagation)
+    }
+
+    fn wrap_callback(mut self, before: impl Fn(&web_sys::Event) + 'static) -> Self {
+        let callback = self.callback;
+        self.callback = Rc::new(move |event| {
+            before(&event);
+            callback(event)
+        });
+        self
+    }
 }
 
 impl<Ms: 'static, OtherMs: 'static> MessageMapper<Ms, OtherMs> for EventHandler<Ms> {
@@ -41,6 +116,7 @@ impl<Ms: 'static, OtherMs: 'static> MessageMapper<Ms, OtherMs> for EventHandler<
         EventHandler {
             trigger: self.trigger,
             callback: Rc::new(new_callback),
+            options: self.options,
         }
     }
 }
@@ -50,3 +126,31 @@ impl<Ms> fmt::Debug for EventHandler<Ms> {
         write!(f, "EventHandler('{}')", self.trigger.as_str())
     }
 }
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn prevent_default_and_stop_propagation() {
+        let handler = EventHandler::new(Ev::KeyDown, |event: web_sys::Event| {
+            event.default_prevented()
+        })
+        .prevent_default()
+        .stop_propagation();
+        let mut init = web_sys::KeyboardEventInit::new();
+        init.cancelable(true);
+        let event: web_sys::Event =
+            web_sys::KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init)
+                .unwrap()
+                .into();
+
+        assert!((handler.callback)(event.clone()));
+        assert!(event.cancel_bubble());
+    }
+}
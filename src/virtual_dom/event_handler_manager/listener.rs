@@ -1,5 +1,6 @@
+use crate::app::leak_detector::{Counted, Resource};
 use crate::browser::util::ClosureNew;
-use crate::virtual_dom::{Ev, EventHandler, Mailbox};
+use crate::virtual_dom::{Ev, EventHandler, ListenerOptions, Mailbox};
 use enclose::enc;
 use std::{
     cell::{Cell, RefCell},
@@ -14,18 +15,22 @@ use wasm_bindgen::{closure::Closure, JsCast};
 pub struct Listener<Ms> {
     // Event to listen to.
     trigger: Ev,
+    options: ListenerOptions,
     // "portal" to event handlers - it allows to call event handlers from the JS world.
     portal: Portal<Rc<RefCell<Vec<EventHandler<Ms>>>>>,
     // `callback` is invoked from the JS world and calls event handlers in the `portal`.
     callback: Closure<dyn FnMut(web_sys::Event)>,
     // Element where the listener is attached.
     event_target: web_sys::EventTarget,
+    // See `leak_detector`.
+    _counted: Counted,
 }
 
 impl<Ms> Listener<Ms> {
     /// Create a new listener and attach it to the element.
     pub fn new(
         trigger: Ev,
+        options: ListenerOptions,
         event_target: web_sys::EventTarget,
         event_handlers: Rc<RefCell<Vec<EventHandler<Ms>>>>,
         mailbox: Mailbox<Ms>,
@@ -51,15 +56,25 @@ impl<Ms> Listener<Ms> {
             }),
         );
 
+        let mut listener_options = web_sys::AddEventListenerOptions::new();
+        listener_options
+            .passive(options.passive)
+            .capture(options.capture);
         event_target
-            .add_event_listener_with_callback(trigger.as_str(), callback.as_ref().unchecked_ref())
+            .add_event_listener_with_callback_and_add_event_listener_options(
+                trigger.as_str(),
+                callback.as_ref().unchecked_ref(),
+                &listener_options,
+            )
             .expect("attach listener");
 
         Self {
             trigger,
+            options,
             callback,
             event_target,
             portal: portal_to_event_handlers,
+            _counted: Counted::new(Resource::Listener),
         }
     }
 
@@ -71,9 +86,11 @@ impl<Ms> Listener<Ms> {
 impl<Ms> Drop for Listener<Ms> {
     fn drop(&mut self) {
         self.event_target
-            .remove_event_listener_with_callback(
+            // Only `capture` identifies the listener.
+            .remove_event_listener_with_callback_and_bool(
                 self.trigger.as_str(),
                 self.callback.as_ref().unchecked_ref(),
+                self.options.capture,
             )
             .expect("detach listener");
     }
@@ -1,12 +1,12 @@
-use super::{AtValue, CSSValue, EventHandler, St};
-use crate::app::MessageMapper;
+use super::{AtValue, CSSValue, EventHandler, Selector, St};
+use crate::app::{query::Query, MessageMapper};
 use std::borrow::Cow;
 
 pub mod el;
 pub mod into_nodes;
 pub mod text;
 
-pub use el::El;
+pub use el::{El, UnmanagedContent};
 pub use into_nodes::IntoNodes;
 pub use text::Text;
 
@@ -115,6 +115,33 @@ impl<Ms> Node<Ms> {
     }
 }
 
+// Queries
+impl<Ms> Node<Ms> {
+    /// See `El::select`
+    pub fn select(&self, selector: &str) -> Vec<&El<Ms>> {
+        self.el().map(|el| el.select(selector)).unwrap_or_default()
+    }
+
+    /// See `El::select_with`
+    pub fn select_with(&self, selector: &Selector) -> Vec<&El<Ms>> {
+        self.el()
+            .map(|el| el.select_with(selector))
+            .unwrap_or_default()
+    }
+
+    /// See `El::select_first`
+    pub fn select_first(&self, selector: &str) -> Option<&El<Ms>> {
+        self.el().and_then(|el| el.select_first(selector))
+    }
+
+    /// See `El::find_by_text`
+    pub fn find_by_text(&self, text: &str) -> Vec<&El<Ms>> {
+        self.el()
+            .map(|el| el.find_by_text(text))
+            .unwrap_or_default()
+    }
+}
+
 // Convenience methods
 impl<Ms> Node<Ms> {
     pub fn new_text(text: impl Into<Cow<'static, str>>) -> Self {
@@ -159,6 +186,36 @@ impl<Ms> Node<Ms> {
     }
 }
 
+// Suspense
+impl<Ms> Node<Ms> {
+    /// Render `ready` with the query's data, or `loading` while there are no data yet.
+    /// Stale data are rendered also during a refetch. The view is re-rendered
+    /// automatically when `QueryClient::update` stores the fetched data.
+    pub fn suspense<T, E>(
+        query: Option<&Query<T, E>>,
+        loading: Node<Ms>,
+        ready: impl FnOnce(&T) -> Node<Ms>,
+    ) -> Node<Ms> {
+        query
+            .and_then(|query| query.data.as_ref())
+            .map_or(loading, ready)
+    }
+
+    /// `suspense` which renders `failed` when the query has failed and there are no data.
+    pub fn suspense_or_error<T, E>(
+        query: Option<&Query<T, E>>,
+        loading: Node<Ms>,
+        failed: impl FnOnce(&E) -> Node<Ms>,
+        ready: impl FnOnce(&T) -> Node<Ms>,
+    ) -> Node<Ms> {
+        match query.map(|query| (&query.data, &query.error)) {
+            Some((Some(data), _)) => ready(data),
+            Some((None, Some(error))) => failed(error),
+            _ => loading,
+        }
+    }
+}
+
 // Backing node manipulation
 impl<Ms> Node<Ms> {
     pub fn strip_ws_nodes_from_self_and_children(&mut self) {
@@ -1,10 +1,10 @@
 use super::super::{
-    At, AtValue, Attrs, CSSValue, EventHandler, EventHandlerManager, Node, SharedNodeWs, St, Style,
-    Tag, Text,
+    AsAtValue, At, AtValue, Attrs, CSSValue, Ev, EventHandler, EventHandlerManager, Node, Selector,
+    SharedNodeWs, St, Style, Tag, Text, UpdateEl,
 };
 use crate::app::MessageMapper;
 use crate::browser::{
-    dom::{virtual_dom_bridge, Namespace},
+    dom::{event_handler::ev, virtual_dom_bridge, Namespace},
     util,
 };
 use std::borrow::Cow;
@@ -29,8 +29,37 @@ pub struct El<Ms: 'static> {
     /// The actual DOM element/node.
     pub node_ws: Option<web_sys::Node>,
     pub refs: Vec<SharedNodeWs>,
+    /// Children are rendered only when the element is created; they aren't patched afterwards.
+    /// See `UnmanagedContent`.
+    pub unmanaged_content: bool,
 }
 
+// ------ UnmanagedContent ------
+
+/// Opt-out of patching the element children.
+///
+/// Seed renders the children only when it creates the element and it doesn't patch
+/// them afterwards - the browser or a JS library owns the content. Use it for `contenteditable`
+/// regions (see `browser::dom::content_editable`) or elements managed by JS libraries.
+///
+/// Attributes, styles and event handlers of the element itself are still patched. Event handlers
+/// and `ElRef`s of the children work only until the first re-render.
+///
+/// When the element becomes managed again, its DOM content is replaced by the new children.
+///
+/// # Example
+///
+/// ```rust,no_run
+///div![
+///    UnmanagedContent,
+///    attrs! {At::ContentEditable => true},
+///    el_ref(&model.editor),
+///    raw![&model.initial_html],
+///]
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct UnmanagedContent;
+
 // @TODO remove custom impl once https://github.com/rust-lang/rust/issues/26925 is fixed
 impl<Ms> Clone for El<Ms> {
     fn clone(&self) -> Self {
@@ -43,6 +72,7 @@ impl<Ms> Clone for El<Ms> {
             namespace: self.namespace.clone(),
             node_ws: self.node_ws.clone(),
             refs: self.refs.clone(),
+            unmanaged_content: self.unmanaged_content,
         }
     }
 }
@@ -71,6 +101,7 @@ impl<Ms: 'static, OtherMs: 'static> MessageMapper<Ms, OtherMs> for El<Ms> {
             namespace: self.namespace,
             event_handler_manager: self.event_handler_manager.map_msg(f),
             refs: self.refs,
+            unmanaged_content: self.unmanaged_content,
         }
     }
 }
@@ -94,6 +125,7 @@ impl<Ms> El<Ms> {
             namespace: None,
             node_ws: None,
             refs: Vec::new(),
+            unmanaged_content: false,
         }
     }
 
@@ -211,6 +243,52 @@ impl<Ms> El<Ms> {
             .collect()
     }
 
+    /// Returns the element and its descendants which match the CSS `selector`.
+    /// See `Selector` for supported syntax.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the selector is invalid. Use `Selector::parse` and `select_with` to handle errors.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    ///let buttons = el.select("button.primary");
+    /// ```
+    pub fn select(&self, selector: &str) -> Vec<&El<Ms>> {
+        self.select_with(&Selector::parse(selector).unwrap_or_else(|err| panic!("{}", err)))
+    }
+
+    /// Returns the element and its descendants which match the `selector`.
+    pub fn select_with(&self, selector: &Selector) -> Vec<&El<Ms>> {
+        selector.select_in_el(self)
+    }
+
+    /// Returns the first (depth-first) element which matches the CSS `selector`.
+    /// See `El::select`.
+    pub fn select_first(&self, selector: &str) -> Option<&El<Ms>> {
+        self.select(selector).into_iter().next()
+    }
+
+    /// Returns the element and its descendants whose trimmed text (see `El::get_text`)
+    /// equals `text`.
+    pub fn find_by_text(&self, text: &str) -> Vec<&El<Ms>> {
+        let mut found = Vec::new();
+        self.collect_by_text(text, &mut found);
+        found
+    }
+
+    fn collect_by_text<'a>(&'a self, text: &str, found: &mut Vec<&'a El<Ms>>) {
+        if self.get_text().trim() == text {
+            found.push(self);
+        }
+        for child in &self.children {
+            if let Node::Element(child_el) = child {
+                child_el.collect_by_text(text, found);
+            }
+        }
+    }
+
     #[cfg(debug_assertions)]
     /// Warn user about potential bugs when having scripts and `Takeover` mount type.
     pub fn warn_about_script_tags(&self) {
@@ -247,3 +325,159 @@ impl<Ms> El<Ms> {
         }
     }
 }
+
+// ------ Builder ------
+
+/// A builder-style alternative to element macros - e.g. for programmatic node construction.
+///
+/// # Example
+///
+/// ```rust,no_run
+///El::tag(Tag::Div)
+///    .class("card")
+///    .attr(At::Title, "Card")
+///    .child(El::tag(Tag::H2).text(title))
+///    .children(items.iter().map(view_item))
+///    .on(Ev::Click, |_| Msg::CardClicked)
+///    .into_node()
+/// ```
+impl<Ms> El<Ms> {
+    /// Create an empty element - the same as `El::empty`.
+    pub fn tag(tag: Tag) -> Self {
+        Self::empty(tag)
+    }
+
+    /// Boolean attributes (see `At::is_boolean`) with `true` / `false` values are rendered
+    /// like in `attrs!` - without a value or not at all.
+    pub fn attr(mut self, key: impl Into<At>, value: impl Into<AtValue>) -> Self {
+        let at = key.into();
+        let value = match value.into() {
+            // `bool`s are converted to `"true"` / `"false"` by `Into<AtValue>`.
+            AtValue::Some(value) if at.is_boolean() && (value == "true" || value == "false") => {
+                (value == "true").as_at_value()
+            }
+            value => value,
+        };
+        self.attrs.vals.insert(at, value);
+        self
+    }
+
+    pub fn id(self, id: impl Into<Cow<'static, str>>) -> Self {
+        self.attr(At::Id, id.into().into_owned())
+    }
+
+    pub fn class(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.add_class(name);
+        self
+    }
+
+    pub fn style(mut self, key: impl Into<St>, value: impl Into<CSSValue>) -> Self {
+        self.add_style(key, value);
+        self
+    }
+
+    pub fn child(mut self, child: impl Into<Node<Ms>>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+
+    pub fn children(mut self, children: impl IntoIterator<Item = Node<Ms>>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    pub fn text(mut self, text: impl Into<Cow<'static, str>>) -> Self {
+        self.add_text(text);
+        self
+    }
+
+    /// Add an event handler created by `ev`.
+    pub fn on(
+        self,
+        trigger: impl Into<Ev>,
+        handler: impl FnOnce(web_sys::Event) -> Ms + 'static + Clone,
+    ) -> Self {
+        self.event_handler(ev(trigger, handler))
+    }
+
+    /// Add an event handler - e.g. created by `mouse_ev` or `input_ev`.
+    pub fn event_handler(mut self, event_handler: EventHandler<Ms>) -> Self {
+        self.add_event_handler(event_handler);
+        self
+    }
+
+    /// Update the element by anything accepted by element macros - e.g. `ElRef`, `Attrs`
+    /// or `UnmanagedContent`.
+    pub fn with(mut self, part: impl UpdateEl<Ms>) -> Self {
+        part.update_el(&mut self);
+        self
+    }
+
+    pub fn into_node(self) -> Node<Ms> {
+        Node::Element(self)
+    }
+}
+
+impl<Ms> From<El<Ms>> for Node<Ms> {
+    fn from(el: El<Ms>) -> Self {
+        Node::Element(el)
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::virtual_dom::{ToClasses, UpdateEl};
+    use indexmap::IndexMap;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Clone)]
+    enum Msg {
+        Clicked,
+    }
+
+    #[wasm_bindgen_test]
+    fn builder_matches_macros() {
+        let built: Node<Msg> = El::tag(Tag::Div)
+            .class("card")
+            .id("main")
+            .style(St::Color, "red")
+            .child(El::tag(Tag::H2).text("Title"))
+            .children(vec![Node::new_text("a"), Node::new_text("b")])
+            .on(Ev::Click, |_| Msg::Clicked)
+            .into_node();
+        let expected: Node<Msg> = div![
+            C!["card"],
+            attrs! {At::Id => "main"},
+            style! {St::Color => "red"},
+            h2!["Title"],
+            "a",
+            "b",
+        ];
+
+        let (built, expected) = (built.el().unwrap(), expected.el().unwrap());
+        assert_eq!(built.attrs, expected.attrs);
+        assert_eq!(built.style, expected.style);
+        assert_eq!(built.children.len(), 3);
+        assert_eq!(built.select("h2").len(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn builder_renders_boolean_attributes() {
+        let built: El<Msg> = El::tag(Tag::Button)
+            .attr(At::Disabled, false)
+            .attr(At::Hidden, true)
+            .attr(At::from("aria-pressed"), false);
+        let expected: Node<Msg> = button![attrs! {
+            At::Disabled => false,
+            At::Hidden => true,
+            At::from("aria-pressed") => false,
+        }];
+        assert_eq!(built.attrs, expected.el().unwrap().attrs);
+        assert_eq!(built.attrs.vals[&At::Disabled], AtValue::Ignored);
+    }
+}
@@ -1,6 +1,9 @@
 //! This module contains code related to patching the VDOM. It can be considered
 //! a subset of the `vdom` module.
 
+#[cfg(any(test, debug_assertions))]
+pub mod plan;
+
 use super::{El, Mailbox, Node, View};
 use crate::app::App;
 use crate::browser::dom::virtual_dom_bridge;
@@ -57,17 +60,26 @@ fn patch_el<'a, Ms, Mdl, ElC: View<Ms>, GMs>(
             ref_.set(old_el_ws.clone());
         }
 
-        let old_children_iter = old.children.into_iter();
-        let new_children_iter = new.children.iter_mut();
+        if new.unmanaged_content {
+            // The DOM content isn't described by any children anymore - see `UnmanagedContent`.
+            new.children.clear();
+        } else {
+            if old.unmanaged_content {
+                old_el_ws.set_text_content(None);
+                old.children.clear();
+            }
+            let old_children_iter = old.children.into_iter();
+            let new_children_iter = new.children.iter_mut();
 
-        patch_els(
-            document,
-            mailbox,
-            app,
-            &old_el_ws,
-            old_children_iter,
-            new_children_iter,
-        );
+            patch_els(
+                document,
+                mailbox,
+                app,
+                &old_el_ws,
+                old_children_iter,
+                new_children_iter,
+            );
+        }
         new.node_ws = Some(old_el_ws);
     }
     new.node_ws.as_ref()
@@ -272,6 +284,7 @@ pub(crate) fn patch<'a, Ms, Mdl, ElC: View<Ms>, GMs>(
                         .expect("old_node_ws missing when changing text");
 
                     if new_text != &old_text {
+                        virtual_dom_bridge::count_patch();
                         old_node_ws.set_text_content(Some(&new_text.text));
                     }
                     new_text.node_ws.replace(old_node_ws);
@@ -0,0 +1,357 @@
+//! DOM operations computed from a diff of two virtual DOMs, without touching the DOM.
+//!
+//! The plan mirrors the patch algorithm - children are matched by their index, so nodes
+//! are never moved; a different node at the same index is replaced or recreated.
+//! Each op is one DOM operation, except that `UpdateAttrs` sets each attribute separately and
+//! created or replaced nodes are inserted together with their attributes and descendants.
+//! It's available only in debug and test builds.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!let old = view(&Model { count: 1 });
+//!let new = view(&Model { count: 2 });
+//!assert_eq!(
+//!    patch::plan::plan(&old, &new).ops(),
+//!    &[PatchOp::UpdateText { path: "div[0] > #text[0]".to_owned(), text: "2".to_owned() }]
+//!);
+//! ```
+
+use crate::virtual_dom::{El, Node};
+use std::fmt;
+
+// ------ PatchOp ------
+
+/// A DOM operation with the path of the node, e.g. `ul[1] > li[3]`.
+/// The index is the position among siblings, text nodes are named `#text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchOp {
+    /// A new node and its descendants are inserted.
+    Create {
+        path: String,
+    },
+    /// The node is replaced by a new node - e.g. because its tag has changed.
+    Replace {
+        path: String,
+    },
+    Remove {
+        path: String,
+    },
+    /// Attributes are added, changed or removed.
+    UpdateAttrs {
+        path: String,
+        names: Vec<String>,
+    },
+    UpdateStyle {
+        path: String,
+    },
+    UpdateText {
+        path: String,
+        text: String,
+    },
+}
+
+impl PatchOp {
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Create { path }
+            | Self::Replace { path }
+            | Self::Remove { path }
+            | Self::UpdateAttrs { path, .. }
+            | Self::UpdateStyle { path }
+            | Self::UpdateText { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for PatchOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Create { path } => write!(f, "create {}", path),
+            Self::Replace { path } => write!(f, "replace {}", path),
+            Self::Remove { path } => write!(f, "remove {}", path),
+            Self::UpdateAttrs { path, names } => {
+                write!(f, "update attributes {} of {}", names.join(", "), path)
+            }
+            Self::UpdateStyle { path } => write!(f, "update style of {}", path),
+            Self::UpdateText { path, text } => write!(f, "update text of {} to {:?}", path, text),
+        }
+    }
+}
+
+// ------ PatchPlan ------
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PatchPlan(Vec<PatchOp>);
+
+impl PatchPlan {
+    pub fn ops(&self) -> &[PatchOp] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl IntoIterator for PatchPlan {
+    type Item = PatchOp;
+    type IntoIter = std::vec::IntoIter<PatchOp>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Operations performed by patching `old` nodes to `new` nodes.
+pub fn plan<Ms>(old: &[Node<Ms>], new: &[Node<Ms>]) -> PatchPlan {
+    let mut ops = Vec::new();
+    plan_children("", old, new, &mut ops);
+    PatchPlan(ops)
+}
+
+fn plan_children<Ms>(
+    parent_path: &str,
+    old: &[Node<Ms>],
+    new: &[Node<Ms>],
+    ops: &mut Vec<PatchOp>,
+) {
+    for (index, (old, new)) in old.iter().zip(new).enumerate() {
+        plan_node(parent_path, index, old, new, ops);
+    }
+    for (index, new) in new.iter().enumerate().skip(old.len()) {
+        if let Some(path) = node_path(parent_path, index, new) {
+            ops.push(PatchOp::Create { path });
+        }
+    }
+    for (index, old) in old.iter().enumerate().skip(new.len()) {
+        if let Some(path) = node_path(parent_path, index, old) {
+            ops.push(PatchOp::Remove { path });
+        }
+    }
+}
+
+fn plan_node<Ms>(
+    parent_path: &str,
+    index: usize,
+    old: &Node<Ms>,
+    new: &Node<Ms>,
+    ops: &mut Vec<PatchOp>,
+) {
+    match (old, new) {
+        (Node::Element(old), Node::Element(new)) => {
+            let path = child_path(parent_path, old.tag.as_str(), index);
+            if old.tag != new.tag || old.namespace != new.namespace || old.is_custom() {
+                ops.push(PatchOp::Replace { path });
+            } else {
+                plan_el(&path, old, new, ops);
+            }
+        }
+        (Node::Text(old), Node::Text(new)) => {
+            if old != new {
+                ops.push(PatchOp::UpdateText {
+                    path: child_path(parent_path, "#text", index),
+                    text: new.text.to_string(),
+                });
+            }
+        }
+        (Node::Empty, Node::Empty) => (),
+        (Node::Empty, new) => {
+            ops.extend(node_path(parent_path, index, new).map(|path| PatchOp::Create { path }))
+        }
+        (old, Node::Empty) => {
+            ops.extend(node_path(parent_path, index, old).map(|path| PatchOp::Remove { path }))
+        }
+        (old, _) => {
+            ops.extend(node_path(parent_path, index, old).map(|path| PatchOp::Replace { path }))
+        }
+    }
+}
+
+/// Operations on the element with the same tag.
+fn plan_el<Ms>(path: &str, old: &El<Ms>, new: &El<Ms>, ops: &mut Vec<PatchOp>) {
+    let mut names = new
+        .attrs
+        .vals
+        .iter()
+        .filter(|(at, value)| old.attrs.vals.get(*at) != Some(value))
+        .map(|(at, _)| at.as_str().to_owned())
+        .collect::<Vec<_>>();
+    names.extend(
+        old.attrs
+            .vals
+            .keys()
+            .filter(|at| !new.attrs.vals.contains_key(*at))
+            .map(|at| at.as_str().to_owned()),
+    );
+    if !names.is_empty() {
+        ops.push(PatchOp::UpdateAttrs {
+            path: path.to_owned(),
+            names,
+        });
+    }
+    if old.style != new.style {
+        ops.push(PatchOp::UpdateStyle {
+            path: path.to_owned(),
+        });
+    }
+
+    if new.unmanaged_content {
+        return;
+    }
+    // Children of the previously unmanaged content are recreated.
+    let old_children: &[Node<Ms>] = if old.unmanaged_content {
+        &[]
+    } else {
+        &old.children
+    };
+    plan_children(path, old_children, &new.children, ops);
+}
+
+/// The path of an element or text node; `Node::Empty` doesn't have any.
+fn node_path<Ms>(parent_path: &str, index: usize, node: &Node<Ms>) -> Option<String> {
+    match node {
+        Node::Element(el) => Some(child_path(parent_path, el.tag.as_str(), index)),
+        Node::Text(_) => Some(child_path(parent_path, "#text", index)),
+        Node::Empty => None,
+    }
+}
+
+fn child_path(parent_path: &str, name: &str, index: usize) -> String {
+    if parent_path.is_empty() {
+        format!("{}[{}]", name, index)
+    } else {
+        format!("{} > {}[{}]", parent_path, name, index)
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::App;
+    use crate::browser::dom::virtual_dom_bridge;
+    use crate::browser::util;
+    use crate::virtual_dom::{patch, At, Mailbox, St, ToClasses, UpdateEl};
+    use indexmap::IndexMap;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn view(count: u32, active: bool) -> Vec<Node<()>> {
+        vec![div![
+            C![IF!(active => "active")],
+            span![count.to_string()],
+            IF!(count > 1 => p!["many"]),
+        ]]
+    }
+
+    #[wasm_bindgen_test]
+    fn only_changed_text_is_patched() {
+        assert!(plan(&view(2, false), &view(2, false)).is_empty());
+        assert_eq!(
+            plan(&view(2, false), &view(3, false)).ops(),
+            &[PatchOp::UpdateText {
+                path: "div[0] > span[0] > #text[0]".to_owned(),
+                text: "3".to_owned(),
+            }]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn created_removed_and_replaced_nodes() {
+        assert_eq!(
+            plan(&view(1, false), &view(2, true)).ops(),
+            &[
+                PatchOp::UpdateAttrs {
+                    path: "div[0]".to_owned(),
+                    names: vec!["class".to_owned()],
+                },
+                PatchOp::UpdateText {
+                    path: "div[0] > span[0] > #text[0]".to_owned(),
+                    text: "2".to_owned(),
+                },
+                PatchOp::Create {
+                    path: "div[0] > p[1]".to_owned(),
+                },
+            ]
+        );
+        assert_eq!(
+            plan(&view(2, false), &view(1, false)).ops(),
+            &[
+                PatchOp::UpdateText {
+                    path: "div[0] > span[0] > #text[0]".to_owned(),
+                    text: "1".to_owned(),
+                },
+                PatchOp::Remove {
+                    path: "div[0] > p[1]".to_owned(),
+                },
+            ]
+        );
+
+        let old: Vec<Node<()>> = vec![div![style! {St::Color => "red"}], Node::new_text("a")];
+        let new: Vec<Node<()>> = vec![section![], span!["a"]];
+        let ops = plan(&old, &new)
+            .into_iter()
+            .map(|op| op.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ops, vec!["replace div[0]", "replace #text[1]"]);
+    }
+
+    /// The number of DOM operations performed by patching `old` to `new` in a real DOM.
+    fn patched_count(mut old: Vec<Node<()>>, mut new: Vec<Node<()>>) -> usize {
+        let root = util::document().create_element("div").unwrap();
+        util::body().append_child(&root).unwrap();
+        let app: App<(), (), Node<()>> =
+            App::start(root, |_, _| (), |_, _, _| (), |_| Node::<()>::Empty);
+        let document = util::document();
+        let parent = document.create_element("div").unwrap();
+        let mailbox = Mailbox::new(|_: ()| ());
+
+        patch::patch_els(
+            &document,
+            &mailbox,
+            &app,
+            &parent,
+            Vec::new().into_iter(),
+            old.iter_mut(),
+        );
+        virtual_dom_bridge::take_patch_count();
+        patch::patch_els(
+            &document,
+            &mailbox,
+            &app,
+            &parent,
+            old.into_iter(),
+            new.iter_mut(),
+        );
+        virtual_dom_bridge::take_patch_count()
+    }
+
+    #[wasm_bindgen_test]
+    fn plan_matches_dom_operations() {
+        let changes: Vec<(Vec<Node<()>>, Vec<Node<()>>)> = vec![
+            (view(2, false), view(2, false)),
+            (view(2, false), view(3, true)),
+            (vec![div![span!["a"]]], vec![div![span!["b"], span![]]]),
+            (vec![div![span!["a"], span![]]], vec![div![span!["b"]]]),
+            (
+                vec![div![attrs! {At::Title => "a"}, style! {St::Color => "red"}]],
+                vec![div![
+                    attrs! {At::Title => "b"},
+                    style! {St::Color => "blue"}
+                ]],
+            ),
+            (vec![div![], div![]], vec![Node::new_text("a")]),
+        ];
+        for (old, new) in changes {
+            let planned = plan(&old, &new).len();
+            assert_eq!(patched_count(old, new), planned);
+        }
+    }
+}
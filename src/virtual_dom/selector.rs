@@ -0,0 +1,466 @@
+//! A subset of CSS selectors for querying the virtual DOM.
+//!
+//! Supported syntax: type (`button`), universal (`*`), id (`#save`), class (`.primary`),
+//! attribute (`[disabled]`, `[type="text"]`), descendant (`div p`) and child (`ul > li`)
+//! combinators and selector lists (`h1, h2`).
+
+use super::{At, AtValue, El, Node};
+use std::{fmt, iter::Peekable, str::Chars};
+
+// ------ Selector ------
+
+/// Parsed selector.
+///
+/// # Example
+///
+/// ```rust,no_run
+///let selector = Selector::parse("ul.todo-list > li").unwrap();
+///let items = node.select_with(&selector);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    complex_selectors: Vec<ComplexSelector>,
+}
+
+impl Selector {
+    /// Parse the selector.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the selector is empty or contains unsupported syntax.
+    pub fn parse(selector: &str) -> Result<Self, String> {
+        let complex_selectors = split_selector_list(selector)
+            .into_iter()
+            .map(ComplexSelector::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("invalid selector `{}`: {}", selector, err))?;
+        Ok(Self { complex_selectors })
+    }
+
+    /// Does the element match the selector?
+    ///
+    /// `ancestors` have to be ordered from the root to the element's parent.
+    pub fn matches<Ms>(&self, el: &El<Ms>, ancestors: &[&El<Ms>]) -> bool {
+        self.complex_selectors
+            .iter()
+            .any(|complex_selector| complex_selector.matches(el, ancestors))
+    }
+
+    /// Returns all elements in the `nodes` and their descendants which match the selector.
+    pub fn select_in<'a, Ms>(&self, nodes: &'a [Node<Ms>]) -> Vec<&'a El<Ms>> {
+        let mut matched = Vec::new();
+        let mut ancestors = Vec::new();
+        for node in nodes {
+            if let Node::Element(el) = node {
+                self.collect(el, &mut ancestors, &mut matched);
+            }
+        }
+        matched
+    }
+
+    /// Returns `el` and all its descendants which match the selector.
+    pub fn select_in_el<'a, Ms>(&self, el: &'a El<Ms>) -> Vec<&'a El<Ms>> {
+        let mut matched = Vec::new();
+        self.collect(el, &mut Vec::new(), &mut matched);
+        matched
+    }
+
+    fn collect<'a, Ms>(
+        &self,
+        el: &'a El<Ms>,
+        ancestors: &mut Vec<&'a El<Ms>>,
+        matched: &mut Vec<&'a El<Ms>>,
+    ) {
+        if self.matches(el, ancestors) {
+            matched.push(el);
+        }
+        ancestors.push(el);
+        for child in &el.children {
+            if let Node::Element(child_el) = child {
+                self.collect(child_el, ancestors, matched);
+            }
+        }
+        ancestors.pop();
+    }
+}
+
+// ------ ComplexSelector ------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+/// Compound selectors joined by combinators, e.g. `div.card > p`.
+#[derive(Debug, Clone, PartialEq)]
+struct ComplexSelector {
+    // The combinator in the first item is ignored.
+    parts: Vec<(Combinator, CompoundSelector)>,
+}
+
+impl ComplexSelector {
+    fn parse(selector: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut chars = selector.trim().chars().peekable();
+        let mut combinator = Combinator::Descendant;
+
+        while chars.peek().is_some() {
+            parts.push((combinator, CompoundSelector::parse(&mut chars)?));
+
+            let mut whitespace = false;
+            while chars.peek().map_or(false, |c| c.is_whitespace()) {
+                chars.next();
+                whitespace = true;
+            }
+            combinator = match chars.peek() {
+                Some('>') => {
+                    chars.next();
+                    while chars.peek().map_or(false, |c| c.is_whitespace()) {
+                        chars.next();
+                    }
+                    Combinator::Child
+                }
+                Some(_) if whitespace => Combinator::Descendant,
+                Some(c) => return Err(format!("unexpected character `{}`", c)),
+                None => break,
+            };
+            if chars.peek().is_none() {
+                return Err("missing selector after combinator".to_owned());
+            }
+        }
+
+        if parts.is_empty() {
+            return Err("empty selector".to_owned());
+        }
+        Ok(Self { parts })
+    }
+
+    fn matches<Ms>(&self, el: &El<Ms>, ancestors: &[&El<Ms>]) -> bool {
+        self.matches_part(self.parts.len() - 1, el, ancestors)
+    }
+
+    fn matches_part<Ms>(&self, index: usize, el: &El<Ms>, ancestors: &[&El<Ms>]) -> bool {
+        let (combinator, compound_selector) = &self.parts[index];
+        if !compound_selector.matches(el) {
+            return false;
+        }
+        if index == 0 {
+            return true;
+        }
+        match combinator {
+            Combinator::Child => ancestors.split_last().map_or(false, |(parent, ancestors)| {
+                self.matches_part(index - 1, parent, ancestors)
+            }),
+            Combinator::Descendant => (0..ancestors.len())
+                .rev()
+                .any(|i| self.matches_part(index - 1, ancestors[i], &ancestors[..i])),
+        }
+    }
+}
+
+// ------ CompoundSelector ------
+
+/// Simple selectors without combinators, e.g. `input.large[type="text"]`.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CompoundSelector {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attrs: Vec<(String, Option<String>)>,
+}
+
+impl CompoundSelector {
+    fn parse(chars: &mut Peekable<Chars>) -> Result<Self, String> {
+        let mut compound_selector = Self::default();
+        let mut empty = true;
+
+        if chars.peek() == Some(&'*') {
+            chars.next();
+            empty = false;
+        } else {
+            let tag = take_identifier(chars);
+            if !tag.is_empty() {
+                compound_selector.tag = Some(tag);
+                empty = false;
+            }
+        }
+
+        loop {
+            match chars.peek() {
+                Some('#') => {
+                    chars.next();
+                    compound_selector.id = Some(required_identifier(chars, "id")?);
+                }
+                Some('.') => {
+                    chars.next();
+                    compound_selector
+                        .classes
+                        .push(required_identifier(chars, "class")?);
+                }
+                Some('[') => {
+                    chars.next();
+                    compound_selector.attrs.push(parse_attribute(chars)?);
+                }
+                _ => break,
+            }
+            empty = false;
+        }
+
+        if empty {
+            return Err(match chars.peek() {
+                Some(c) => format!("unexpected character `{}`", c),
+                None => "missing selector".to_owned(),
+            });
+        }
+        Ok(compound_selector)
+    }
+
+    fn matches<Ms>(&self, el: &El<Ms>) -> bool {
+        if let Some(tag) = &self.tag {
+            if !el.tag.as_str().eq_ignore_ascii_case(tag) {
+                return false;
+            }
+        }
+        if let Some(id) = &self.id {
+            if attr_value(el, &At::Id) != Some(id) {
+                return false;
+            }
+        }
+        if !self.classes.is_empty() {
+            let el_classes = attr_value(el, &At::Class).unwrap_or_default();
+            let has_class = |class: &String| el_classes.split_whitespace().any(|c| c == class);
+            if !self.classes.iter().all(has_class) {
+                return false;
+            }
+        }
+        self.attrs.iter().all(|(name, value)| {
+            match (el.attrs.vals.get(&At::from(name.clone())), value) {
+                (None, _) | (Some(AtValue::Ignored), _) => false,
+                (Some(_), None) => true,
+                (Some(AtValue::None), Some(value)) => value.is_empty(),
+                (Some(AtValue::Some(actual)), Some(value)) => actual == value,
+            }
+        })
+    }
+}
+
+fn attr_value<'a, Ms>(el: &'a El<Ms>, at: &At) -> Option<&'a str> {
+    match el.attrs.vals.get(at) {
+        Some(AtValue::Some(value)) => Some(value.as_str()),
+        _ => None,
+    }
+}
+
+fn take_identifier(chars: &mut Peekable<Chars>) -> String {
+    let mut identifier = String::new();
+    while let Some(&c) = chars.peek() {
+        if c.is_alphanumeric() || c == '-' || c == '_' {
+            identifier.push(c);
+            chars.next();
+        } else {
+            break;
+        }
+    }
+    identifier
+}
+
+fn required_identifier(chars: &mut Peekable<Chars>, kind: &str) -> Result<String, String> {
+    let identifier = take_identifier(chars);
+    if identifier.is_empty() {
+        Err(format!("missing {} name", kind))
+    } else {
+        Ok(identifier)
+    }
+}
+
+/// Split the selector list on top-level commas - commas in attribute values
+/// (e.g. `[title="a,b"]`) don't separate selectors.
+fn split_selector_list(selector: &str) -> Vec<&str> {
+    let mut selectors = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    let mut in_brackets = false;
+    for (index, character) in selector.char_indices() {
+        match (quote, character) {
+            (Some(open_quote), _) if character == open_quote => quote = None,
+            (None, '"') | (None, '\'') => quote = Some(character),
+            (None, '[') => in_brackets = true,
+            (None, ']') => in_brackets = false,
+            (None, ',') if !in_brackets => {
+                selectors.push(&selector[start..index]);
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+    selectors.push(&selector[start..]);
+    selectors
+}
+
+fn parse_attribute(chars: &mut Peekable<Chars>) -> Result<(String, Option<String>), String> {
+    let name = required_identifier(chars, "attribute")?;
+    match chars.next() {
+        Some(']') => Ok((name, None)),
+        Some('=') => {
+            let value = match chars.peek() {
+                Some(&quote) if quote == '"' || quote == '\'' => {
+                    chars.next();
+                    let value = chars.by_ref().take_while(|&c| c != quote).collect();
+                    value
+                }
+                _ => take_identifier(chars),
+            };
+            match chars.next() {
+                Some(']') => Ok((name, Some(value))),
+                _ => Err("missing `]`".to_owned()),
+            }
+        }
+        _ => Err("missing `]`".to_owned()),
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let complex_selectors = self
+            .complex_selectors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        write!(f, "{}", complex_selectors.join(", "))
+    }
+}
+
+impl fmt::Display for ComplexSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (combinator, compound_selector)) in self.parts.iter().enumerate() {
+            if index > 0 {
+                match combinator {
+                    Combinator::Descendant => write!(f, " ")?,
+                    Combinator::Child => write!(f, " > ")?,
+                }
+            }
+            write!(f, "{}", compound_selector)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CompoundSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut written = false;
+        if let Some(tag) = &self.tag {
+            write!(f, "{}", tag)?;
+            written = true;
+        }
+        if let Some(id) = &self.id {
+            write!(f, "#{}", id)?;
+            written = true;
+        }
+        for class in &self.classes {
+            write!(f, ".{}", class)?;
+            written = true;
+        }
+        for (name, value) in &self.attrs {
+            match value {
+                Some(value) => write!(f, "[{}=\"{}\"]", name, value)?,
+                None => write!(f, "[{}]", name)?,
+            }
+            written = true;
+        }
+        if !written {
+            write!(f, "*")?;
+        }
+        Ok(())
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use wasm_bindgen_test::*;
+
+    type Ms = ();
+
+    fn node() -> Node<Ms> {
+        div![
+            C!["card"],
+            h1![id!("title"), "Todos"],
+            ul![
+                C!["todo-list"],
+                li![C!["todo", "completed"], "Seed"],
+                li![C!["todo"], span!["Wasm"]],
+            ],
+            input![attrs! {At::Type => "text", At::Disabled => AtValue::None}],
+            button![C!["primary"], "Save"],
+        ]
+    }
+
+    fn tags(els: Vec<&El<Ms>>) -> Vec<String> {
+        els.into_iter().map(|el| el.tag.to_string()).collect()
+    }
+
+    #[wasm_bindgen_test]
+    fn select_compound() {
+        let node = node();
+        assert_eq!(tags(node.select("button.primary")), vec!["button"]);
+        assert_eq!(tags(node.select("#title")), vec!["h1"]);
+        assert_eq!(node.select("li.todo.completed").len(), 1);
+        assert_eq!(node.select("input[type=\"text\"][disabled]").len(), 1);
+        assert!(node.select("input[type=number]").is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn select_combinators() {
+        let node = node();
+        assert_eq!(node.select("div li").len(), 2);
+        assert_eq!(node.select(".todo-list > li").len(), 2);
+        assert!(node.select("div > li").is_empty());
+        assert_eq!(tags(node.select("ul span")), vec!["span"]);
+        assert_eq!(tags(node.select("h1, button")), vec!["h1", "button"]);
+    }
+
+    #[wasm_bindgen_test]
+    fn select_includes_root() {
+        assert_eq!(tags(node().select("div.card")), vec!["div"]);
+    }
+
+    #[wasm_bindgen_test]
+    fn find_by_text() {
+        let node = node();
+        assert_eq!(tags(node.find_by_text("Save")), vec!["button"]);
+        assert!(node.find_by_text("Nothing").is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn commas_in_attribute_values() {
+        let node: Node<Ms> = div![
+            attrs! {At::Title => "a,b"},
+            span![attrs! {"data-x" => "1,2"}],
+            p![],
+        ];
+        assert_eq!(tags(node.select(r#"[title="a,b"]"#)), vec!["div"]);
+        assert_eq!(tags(node.select("[data-x='1,2'], p")), vec!["span", "p"]);
+        assert_eq!(
+            Selector::parse(r#"[title="a,b"], p"#).unwrap().to_string(),
+            r#"[title="a,b"], p"#
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn parse_invalid() {
+        assert!(Selector::parse("").is_err());
+        assert!(Selector::parse("div >").is_err());
+        assert!(Selector::parse("input[type").is_err());
+        assert!(Selector::parse("li:first-child").is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn display() {
+        let selector = Selector::parse("ul.todo-list>li ,  *[href='/']").unwrap();
+        assert_eq!(selector.to_string(), "ul.todo-list > li, [href=\"/\"]");
+    }
+}
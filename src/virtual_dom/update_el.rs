@@ -1,4 +1,4 @@
-use super::{Attrs, El, ElRef, EventHandler, Node, Style, Tag, Text};
+use super::{Attrs, El, ElRef, EventHandler, Node, Style, Tag, Text, UnmanagedContent};
 
 // ------ Traits ------
 
@@ -61,6 +61,12 @@ impl<Ms> UpdateEl<Ms> for Tag {
     }
 }
 
+impl<Ms> UpdateEl<Ms> for UnmanagedContent {
+    fn update_el(self, el: &mut El<Ms>) {
+        el.unmanaged_content = true;
+    }
+}
+
 impl<Ms, E: Clone> UpdateEl<Ms> for ElRef<E> {
     fn update_el(self, el: &mut El<Ms>) {
         el.refs.push(self.shared_node_ws);
@@ -123,6 +129,27 @@ impl<Ms, T: UpdateEl<Ms>> UpdateEl<Ms> for Option<T> {
     }
 }
 
+/// Both variants are rendered - e.g. `Ok(view_user(user))` or `Err(view_error(error))`.
+impl<Ms, T: UpdateEl<Ms>, E: UpdateEl<Ms>> UpdateEl<Ms> for Result<T, E> {
+    fn update_el(self, el: &mut El<Ms>) {
+        match self {
+            Ok(value) => value.update_el(el),
+            Err(error) => error.update_el(el),
+        }
+    }
+}
+
+/// The conditional `(predicate, value)` - `value` is used only when `predicate` is `true`.
+/// Use `IF!` when `value` is expensive to create.
+impl<Ms, T: UpdateEl<Ms>> UpdateEl<Ms> for (bool, T) {
+    fn update_el(self, el: &mut El<Ms>) {
+        let (predicate, value) = self;
+        if predicate {
+            value.update_el(el)
+        }
+    }
+}
+
 impl<Ms, T: UpdateEl<Ms>> UpdateEl<Ms> for Vec<T> {
     fn update_el(self, el: &mut El<Ms>) {
         self.into_iter().update_el(el)
@@ -135,6 +162,13 @@ impl<Ms, T: UpdateEl<Ms> + Clone> UpdateEl<Ms> for &[T] {
     }
 }
 
+#[cfg(feature = "persistent_collections")]
+impl<Ms, T: UpdateEl<Ms> + Clone> UpdateEl<Ms> for im_rc::Vector<T> {
+    fn update_el(self, el: &mut El<Ms>) {
+        self.into_iter().update_el(el)
+    }
+}
+
 // ------ ------ Tests ------ ------
 
 #[cfg(test)]
@@ -388,4 +422,27 @@ mod tests {
         let _el: Node<Ms> = div![option];
         assert!(true);
     }
+
+    #[wasm_bindgen_test]
+    fn update_el_option_vec() {
+        let option: Option<Vec<Node<Ms>>> = Some(vec![span!["foo_1"], span!["foo_2"]]);
+        let el: Node<Ms> = div![option, None::<Vec<Node<Ms>>>];
+        assert_eq!(el.el().map(|el| el.children.len()), Some(2));
+    }
+
+    #[wasm_bindgen_test]
+    fn update_el_result() {
+        let ok: Result<Node<Ms>, Node<Ms>> = Ok(span!["user"]);
+        let err: Result<Node<Ms>, &str> = Err("error");
+        let el: Node<Ms> = div![ok, &err];
+        assert_eq!(el.el().map(|el| el.children.len()), Some(2));
+    }
+
+    #[wasm_bindgen_test]
+    fn update_el_conditional() {
+        let el: Node<Ms> = div![(true, span!["shown"]), (false, C!["hidden"])];
+        let el = el.el().unwrap();
+        assert_eq!(el.children.len(), 1);
+        assert!(el.attrs.vals.get(&At::Class).is_none());
+    }
 }
@@ -0,0 +1,312 @@
+//! Checks of the virtual DOM returned by `view`.
+//!
+//! - `validate` - invalid HTML nesting. Browsers "fix" invalid markup - e.g. `<div>` inside `<p>`
+//!   closes the paragraph - so the DOM doesn't match the virtual DOM and patches fail in subtle ways.
+//!   Seed runs the checks after each `view` call in debug builds and warns in the console -
+//!   once per distinct warning, so the same problem isn't reported on every render.
+//! - `differences` - nondeterministic output of `view`, see `App::strict_mode`.
+
+use super::{El, Node};
+
+/// Elements closing an open `<p>`.
+const BLOCKS_IN_P: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+/// Interactive elements which can't be nested in `INTERACTIVE_CONTAINERS`.
+const INTERACTIVE: &[&str] = &["a", "button", "select", "textarea"];
+const INTERACTIVE_CONTAINERS: &[&str] = &["a", "button"];
+
+/// Allowed parents of elements with a restricted context.
+fn allowed_parents(tag: &str) -> Option<&'static [&'static str]> {
+    Some(match tag {
+        "tr" => &["table", "thead", "tbody", "tfoot"],
+        "td" | "th" => &["tr"],
+        "thead" | "tbody" | "tfoot" | "caption" | "colgroup" => &["table"],
+        "col" => &["colgroup"],
+        "li" => &["ul", "ol", "menu"],
+        "dt" | "dd" => &["dl", "div"],
+        "option" => &["select", "datalist", "optgroup"],
+        "optgroup" => &["select"],
+        "legend" => &["fieldset"],
+        "summary" => &["details"],
+        _ => return None,
+    })
+}
+
+/// Warnings about invalid nesting in `nodes` with paths of the offending elements,
+/// e.g. `div[0] > p[1] > div[0]: <div> isn't allowed inside <p>`.
+/// The index is the position among siblings.
+///
+/// Contexts of the top-level nodes are unknown, so they aren't checked.
+/// Custom elements are ignored.
+pub fn validate<Ms>(nodes: &[Node<Ms>]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    validate_children(nodes, &mut Vec::new(), &mut warnings);
+    warnings
+}
+
+fn validate_children<'a, Ms>(
+    nodes: &'a [Node<Ms>],
+    ancestors: &mut Vec<(&'a El<Ms>, usize)>,
+    warnings: &mut Vec<String>,
+) {
+    for (index, node) in nodes.iter().enumerate() {
+        if let Node::Element(el) = node {
+            ancestors.push((el, index));
+            if let Some(problem) = problem(ancestors) {
+                warnings.push(format!("{}: {}", path(ancestors), problem));
+            }
+            validate_children(&el.children, ancestors, warnings);
+            ancestors.pop();
+        }
+    }
+}
+
+/// The invalid nesting of the last element in `ancestors`.
+fn problem<Ms>(ancestors: &[(&El<Ms>, usize)]) -> Option<String> {
+    let (el, _) = ancestors.last()?;
+    let tag = el.tag.as_str();
+    let ancestors = &ancestors[..ancestors.len() - 1];
+    let parent = ancestors.last().map(|(parent, _)| parent.tag.as_str());
+
+    if let Some(parent) = parent {
+        if parent == "p" && BLOCKS_IN_P.contains(&tag) {
+            return Some(format!("<{}> isn't allowed inside <p>", tag));
+        }
+        if let Some(allowed) = allowed_parents(tag) {
+            if !allowed.contains(&parent) && !is_custom(parent) {
+                return Some(format!(
+                    "<{}> is allowed only inside {}, not <{}>",
+                    tag,
+                    allowed
+                        .iter()
+                        .map(|allowed| format!("<{}>", allowed))
+                        .collect::<Vec<_>>()
+                        .join(" / "),
+                    parent
+                ));
+            }
+        }
+    }
+
+    let closest = |tags: &[&str]| {
+        ancestors
+            .iter()
+            .rev()
+            .map(|(ancestor, _)| ancestor.tag.as_str())
+            .find(|ancestor| tags.contains(ancestor))
+    };
+    if INTERACTIVE.contains(&tag) {
+        if let Some(container) = closest(INTERACTIVE_CONTAINERS) {
+            return Some(format!(
+                "interactive <{}> isn't allowed inside <{}>",
+                tag, container
+            ));
+        }
+    }
+    if (tag == "form" || tag == "label") && closest(&[tag]).is_some() {
+        return Some(format!("<{}> isn't allowed inside another <{}>", tag, tag));
+    }
+    None
+}
+
+/// Differences between two virtual DOMs with element paths, e.g.
+/// `div[0] > span[1]: attribute id changed from Some("a1") to Some("a2")`.
+/// Event handlers and refs aren't compared.
+pub fn differences<Ms>(old: &[Node<Ms>], new: &[Node<Ms>]) -> Vec<String> {
+    let mut differences = Vec::new();
+    children_differences(old, new, &mut Vec::new(), &mut differences);
+    differences
+}
+
+fn children_differences<'a, Ms>(
+    old: &'a [Node<Ms>],
+    new: &'a [Node<Ms>],
+    ancestors: &mut Vec<(&'a El<Ms>, usize)>,
+    differences: &mut Vec<String>,
+) {
+    let prefix = |ancestors: &[(&El<Ms>, usize)]| {
+        if ancestors.is_empty() {
+            String::new()
+        } else {
+            format!("{}: ", path(ancestors))
+        }
+    };
+    if old.len() != new.len() {
+        differences.push(format!(
+            "{}number of children changed from {} to {}",
+            prefix(ancestors),
+            old.len(),
+            new.len()
+        ));
+    }
+    for (index, (old, new)) in old.iter().zip(new).enumerate() {
+        match (old, new) {
+            (Node::Element(old), Node::Element(new)) => {
+                ancestors.push((new, index));
+                let prefix = prefix(ancestors);
+                if old.tag != new.tag {
+                    differences.push(format!("{}tag changed from <{}>", prefix, old.tag));
+                } else {
+                    for (at, value) in &new.attrs.vals {
+                        match old.attrs.vals.get(at) {
+                            Some(old_value) if old_value == value => (),
+                            Some(old_value) => differences.push(format!(
+                                "{}attribute {} changed from {:?} to {:?}",
+                                prefix,
+                                at.as_str(),
+                                old_value,
+                                value
+                            )),
+                            None => differences.push(format!(
+                                "{}attribute {} added",
+                                prefix,
+                                at.as_str()
+                            )),
+                        }
+                    }
+                    for at in old.attrs.vals.keys() {
+                        if !new.attrs.vals.contains_key(at) {
+                            differences.push(format!(
+                                "{}attribute {} removed",
+                                prefix,
+                                at.as_str()
+                            ));
+                        }
+                    }
+                    if old.style != new.style {
+                        differences.push(format!(
+                            "{}style changed from \"{}\" to \"{}\"",
+                            prefix, old.style, new.style
+                        ));
+                    }
+                    children_differences(&old.children, &new.children, ancestors, differences);
+                }
+                ancestors.pop();
+            }
+            (Node::Text(old), Node::Text(new)) => {
+                if old != new {
+                    differences.push(format!(
+                        "{}text [{}] changed from {:?} to {:?}",
+                        prefix(ancestors),
+                        index,
+                        old.text,
+                        new.text
+                    ));
+                }
+            }
+            (Node::Empty, Node::Empty) => (),
+            _ => differences.push(format!(
+                "{}node [{}] changed its kind",
+                prefix(ancestors),
+                index
+            )),
+        }
+    }
+}
+
+/// Custom elements (e.g. Web Components) may be rendered by the browser in any context.
+fn is_custom(tag: &str) -> bool {
+    tag.contains('-')
+}
+
+fn path<Ms>(ancestors: &[(&El<Ms>, usize)]) -> String {
+    ancestors
+        .iter()
+        .map(|(el, index)| format!("{}[{}]", el.tag.as_str(), index))
+        .collect::<Vec<_>>()
+        .join(" > ")
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::virtual_dom::UpdateEl;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn invalid_nesting_is_reported_with_path() {
+        let nodes: Vec<Node<()>> = vec![
+            div![p!["text", div!["block"]]],
+            tr![td!["top-level rows aren't checked"]],
+            ul![div![li!["item"]]],
+            a![button!["click"]],
+        ];
+        assert_eq!(
+            validate(&nodes),
+            vec![
+                "div[0] > p[0] > div[1]: <div> isn't allowed inside <p>".to_owned(),
+                "ul[2] > div[0] > li[0]: <li> is allowed only inside <ul> / <ol> / <menu>, not <div>"
+                    .to_owned(),
+                "a[3] > button[0]: interactive <button> isn't allowed inside <a>".to_owned(),
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn valid_nesting() {
+        let nodes: Vec<Node<()>> = vec![table![
+            thead![tr![th!["name"]]],
+            tbody![tr![td![a!["link"]]]],
+        ]];
+        assert!(validate(&nodes).is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn view_differences() {
+        let view = |id: &'static str, count: u32| -> Vec<Node<()>> {
+            vec![div![p![
+                span!["static"],
+                Node::Element({
+                    let mut el = El::empty(crate::virtual_dom::Tag::Span);
+                    el.add_attr("id", id);
+                    el
+                }),
+                count.to_string()
+            ]]]
+        };
+        assert!(differences(&view("a", 1), &view("a", 1)).is_empty());
+        assert_eq!(
+            differences(&view("a", 1), &view("b", 2)),
+            vec![
+                r#"div[0] > p[0] > span[1]: attribute id changed from Some("a") to Some("b")"#
+                    .to_owned(),
+                r#"div[0] > p[0]: text [2] changed from "1" to "2""#.to_owned(),
+            ]
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::virtual_dom::At;
+
 // ------------- CSSValue -------------
 
 /// CSS property value.
@@ -68,12 +70,21 @@ impl<T: ToString> ToCSSValueForOptionToString for Option<T> {
 
 /// Attribute value.
 ///
+/// `attrs!` converts values and references to them according to their types:
+/// - `bool` - boolean attributes (see `At::is_boolean`) are rendered without a value
+///   or not at all, other attributes get `"true"` / `"false"`.
+/// - `Option<T>` - `None` isn't rendered.
+/// - Numbers, strings and other values implementing `ToString` are rendered as strings.
+///
 /// # Example
 ///
 /// ```rust,no_run
 ///attrs! {
-///    At::Disabled => false.as_at_value(),  // same as `=> AtValue::Ignored`
+///    At::Disabled => model.saving,  // `disabled` or nothing
 ///    At::Value => model.message,
+///    At::TabIndex => 2,
+///    At::Title => model.tooltip,  // `Option<String>`
+///    At::from("aria-expanded") => model.menu_open,  // `"true"` / `"false"`
 ///    At::AutoFocus => AtValue::None,
 ///}
 /// ```
@@ -115,3 +126,223 @@ impl AsAtValue for bool {
         }
     }
 }
+
+// ----------- ToAtValue impls ------------
+
+// `attrs!` calls `to_at_value` on `&&&AtValueArg(&value)`. The method resolution strips
+// the references one by one, so impls with more references win - e.g. `bool` and `&bool`
+// get the boolean attribute handling instead of being rendered by `ToString`.
+#[doc(hidden)]
+pub struct AtValueArg<'a, T: ?Sized>(pub &'a T);
+
+impl<T: ?Sized> Clone for AtValueArg<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for AtValueArg<'_, T> {}
+
+// impl ToAtValue for bool and &bool
+#[doc(hidden)]
+pub trait ToAtValueForBool {
+    fn to_at_value(self, at: &At) -> AtValue;
+}
+
+fn bool_to_at_value(value: bool, at: &At) -> AtValue {
+    match (value, at.is_boolean()) {
+        (true, true) => AtValue::None,
+        (false, true) => AtValue::Ignored,
+        (value, false) => AtValue::Some(value.to_string()),
+    }
+}
+
+impl ToAtValueForBool for &&&AtValueArg<'_, bool> {
+    fn to_at_value(self, at: &At) -> AtValue {
+        bool_to_at_value(*self.0, at)
+    }
+}
+
+impl ToAtValueForBool for &&&AtValueArg<'_, &bool> {
+    fn to_at_value(self, at: &At) -> AtValue {
+        bool_to_at_value(**self.0, at)
+    }
+}
+
+// impl ToAtValue for Option<bool> and &Option<bool>
+#[doc(hidden)]
+pub trait ToAtValueForOptionBool {
+    fn to_at_value(self, at: &At) -> AtValue;
+}
+
+impl ToAtValueForOptionBool for &&AtValueArg<'_, Option<bool>> {
+    fn to_at_value(self, at: &At) -> AtValue {
+        self.0
+            .map_or(AtValue::Ignored, |value| bool_to_at_value(value, at))
+    }
+}
+
+impl ToAtValueForOptionBool for &&AtValueArg<'_, &Option<bool>> {
+    fn to_at_value(self, at: &At) -> AtValue {
+        self.0
+            .map_or(AtValue::Ignored, |value| bool_to_at_value(value, at))
+    }
+}
+
+// impl ToAtValue for AtValue and &AtValue
+#[doc(hidden)]
+pub trait ToAtValueForAtValue {
+    fn to_at_value(self, at: &At) -> AtValue;
+}
+
+impl ToAtValueForAtValue for &&AtValueArg<'_, AtValue> {
+    fn to_at_value(self, _: &At) -> AtValue {
+        self.0.clone()
+    }
+}
+
+impl ToAtValueForAtValue for &&AtValueArg<'_, &AtValue> {
+    fn to_at_value(self, _: &At) -> AtValue {
+        (*self.0).clone()
+    }
+}
+
+// impl<T: ToString> ToAtValue for Option<T> and &Option<T>
+#[doc(hidden)]
+pub trait ToAtValueForOptionToString {
+    fn to_at_value(self, at: &At) -> AtValue;
+}
+
+impl<T: ToString> ToAtValueForOptionToString for &AtValueArg<'_, Option<T>> {
+    fn to_at_value(self, _: &At) -> AtValue {
+        self.0
+            .as_ref()
+            .map_or(AtValue::Ignored, |value| AtValue::Some(value.to_string()))
+    }
+}
+
+impl<T: ToString> ToAtValueForOptionToString for &AtValueArg<'_, &Option<T>> {
+    fn to_at_value(self, _: &At) -> AtValue {
+        self.0
+            .as_ref()
+            .map_or(AtValue::Ignored, |value| AtValue::Some(value.to_string()))
+    }
+}
+
+// impl<T: ToString> ToAtValue for T
+#[doc(hidden)]
+pub trait ToAtValueForToString {
+    fn to_at_value(self, at: &At) -> AtValue;
+}
+
+impl<T: ToString + ?Sized> ToAtValueForToString for AtValueArg<'_, T> {
+    fn to_at_value(self, _: &At) -> AtValue {
+        AtValue::Some(self.0.to_string())
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::virtual_dom::{El, UpdateEl};
+    use indexmap::IndexMap;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn typed_attribute_values() {
+        let title: Option<String> = None;
+        let message = String::from("hello");
+        let attrs = attrs! {
+            At::Disabled => true,
+            At::ReadOnly => false,
+            At::from("aria-expanded") => false,
+            At::TabIndex => 2,
+            At::Title => title,
+            At::Alt => Some("logo"),
+            At::Required => Some(false),
+            At::Value => message,
+            At::AutoFocus => AtValue::None,
+        };
+        let vals = attrs.vals.into_iter().collect::<Vec<_>>();
+        assert_eq!(
+            vals,
+            vec![
+                (At::Disabled, AtValue::None),
+                (At::ReadOnly, AtValue::Ignored),
+                (At::from("aria-expanded"), AtValue::Some("false".to_owned())),
+                (At::TabIndex, AtValue::Some("2".to_owned())),
+                (At::Title, AtValue::Ignored),
+                (At::Alt, AtValue::Some("logo".to_owned())),
+                (At::Required, AtValue::Ignored),
+                (At::Value, AtValue::Some("hello".to_owned())),
+                (At::AutoFocus, AtValue::None),
+            ]
+        );
+        // `message` isn't moved.
+        assert_eq!(message, "hello");
+
+        // References - e.g. `Model` fields - are converted like values.
+        struct Model {
+            disabled: bool,
+            hidden: bool,
+            required: Option<bool>,
+            checked: Option<bool>,
+            expanded: bool,
+            tooltip: Option<String>,
+            value: AtValue,
+        }
+        let model = Model {
+            disabled: false,
+            hidden: true,
+            required: Some(false),
+            checked: Some(true),
+            expanded: false,
+            tooltip: Some("Save".to_owned()),
+            value: AtValue::None,
+        };
+        let attrs = attrs! {
+            At::Disabled => &model.disabled,
+            At::Hidden => &model.hidden,
+            At::Required => &model.required,
+            At::Checked => &model.checked,
+            At::from("aria-expanded") => &model.expanded,
+            At::Title => &model.tooltip,
+            At::AutoFocus => &model.value,
+        };
+        assert_eq!(
+            attrs.vals.into_iter().collect::<Vec<_>>(),
+            vec![
+                (At::Disabled, AtValue::Ignored),
+                (At::Hidden, AtValue::None),
+                (At::Required, AtValue::Ignored),
+                (At::Checked, AtValue::None),
+                (At::from("aria-expanded"), AtValue::Some("false".to_owned())),
+                (At::Title, AtValue::Some("Save".to_owned())),
+                (At::AutoFocus, AtValue::None),
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn data_attributes() {
+        let attrs = data!["user-id" => 42, "admin" => true, "team" => None::<&str>];
+        assert_eq!(
+            attrs.vals.into_iter().collect::<Vec<_>>(),
+            vec![
+                (At::from("data-user-id"), AtValue::Some("42".to_owned())),
+                (At::from("data-admin"), AtValue::Some("true".to_owned())),
+                (At::from("data-team"), AtValue::Ignored),
+            ]
+        );
+
+        let el: crate::virtual_dom::Node<()> = data![attrs! {At::Value => 7}, "seven"];
+        assert_eq!(
+            el.el().map(|el| el.tag.clone()),
+            Some(crate::virtual_dom::Tag::Data)
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! Skip re-rendering of unchanged view sections.
+//!
+//! `ViewCache` stores the last rendered `Node` together with the data it has been rendered from
+//! and reuses it while the data is unchanged. The check has to be cheap, so it's done
+//! by `CheapEq` - e.g. by pointer comparison of `Rc`s. Enable the feature
+//! `persistent_collections` to compare [`im-rc`](https://docs.rs/im-rc) collections in O(1) -
+//! clones of an unchanged collection share its structure.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//!struct Model {
+//!    rows: im_rc::Vector<Row>,
+//!    filter: String,
+//!    table_view: ViewCache<(im_rc::Vector<Row>, String), Msg>,
+//!}
+//!
+//!fn view(model: &Model) -> Node<Msg> {
+//!    div![
+//!        view_toolbar(model),
+//!        // `view_table` isn't called when only the toolbar has changed.
+//!        model.table_view.view(
+//!            (model.rows.clone(), model.filter.clone()),
+//!            |(rows, filter)| view_table(rows, filter),
+//!        ),
+//!    ]
+//!}
+//! ```
+
+use super::Node;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
+// ------ CheapEq ------
+
+/// Fast equality check for `ViewCache` keys.
+///
+/// `true` means the values are equal; `false` means they may differ - the section is re-rendered
+/// then, so false negatives cost only performance. E.g. two `Rc`s are equal when they point
+/// to the same value, even if an equal value behind another pointer would be equal too.
+pub trait CheapEq {
+    fn cheap_eq(&self, other: &Self) -> bool;
+}
+
+macro_rules! cheap_eq_by_partial_eq {
+    ($($type:ty),*) => {
+        $(
+            impl CheapEq for $type {
+                fn cheap_eq(&self, other: &Self) -> bool {
+                    self == other
+                }
+            }
+        )*
+    };
+}
+
+cheap_eq_by_partial_eq!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    String,
+    &'static str
+);
+
+impl<T: ?Sized> CheapEq for Rc<T> {
+    fn cheap_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(self, other)
+    }
+}
+
+impl<T: CheapEq> CheapEq for Option<T> {
+    fn cheap_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(value), Some(other)) => value.cheap_eq(other),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+macro_rules! cheap_eq_for_tuple {
+    ($($name:ident $index:tt),*) => {
+        impl<$($name: CheapEq),*> CheapEq for ($($name,)*) {
+            fn cheap_eq(&self, other: &Self) -> bool {
+                $(self.$index.cheap_eq(&other.$index))&&*
+            }
+        }
+    };
+}
+
+cheap_eq_for_tuple!(A 0);
+cheap_eq_for_tuple!(A 0, B 1);
+cheap_eq_for_tuple!(A 0, B 1, C 2);
+cheap_eq_for_tuple!(A 0, B 1, C 2, D 3);
+cheap_eq_for_tuple!(A 0, B 1, C 2, D 3, E 4);
+
+#[cfg(feature = "persistent_collections")]
+mod persistent_collections {
+    use super::CheapEq;
+    use std::hash::{BuildHasher, Hash};
+
+    impl<A: Clone> CheapEq for im_rc::Vector<A> {
+        fn cheap_eq(&self, other: &Self) -> bool {
+            self.ptr_eq(other)
+        }
+    }
+
+    impl<K: Hash + Eq + Clone, V: Clone, S: BuildHasher> CheapEq for im_rc::HashMap<K, V, S> {
+        fn cheap_eq(&self, other: &Self) -> bool {
+            self.ptr_eq(other)
+        }
+    }
+
+    impl<A: Hash + Eq + Clone, S: BuildHasher> CheapEq for im_rc::HashSet<A, S> {
+        fn cheap_eq(&self, other: &Self) -> bool {
+            self.ptr_eq(other)
+        }
+    }
+
+    impl<K: Ord + Clone, V: Clone> CheapEq for im_rc::OrdMap<K, V> {
+        fn cheap_eq(&self, other: &Self) -> bool {
+            self.ptr_eq(other)
+        }
+    }
+
+    impl<A: Ord + Clone> CheapEq for im_rc::OrdSet<A> {
+        fn cheap_eq(&self, other: &Self) -> bool {
+            self.ptr_eq(other)
+        }
+    }
+}
+
+// ------ ViewCache ------
+
+/// The last rendered section and the key it has been rendered from. See the module docs.
+pub struct ViewCache<K, Ms: 'static> {
+    cached: RefCell<Option<(K, Node<Ms>)>>,
+    renders: Cell<usize>,
+}
+
+impl<K, Ms> Default for ViewCache<K, Ms> {
+    fn default() -> Self {
+        Self {
+            cached: RefCell::new(None),
+            renders: Cell::new(0),
+        }
+    }
+}
+
+impl<K, Ms> fmt::Debug for ViewCache<K, Ms> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ViewCache")
+            .field("renders", &self.renders.get())
+            .finish()
+    }
+}
+
+impl<K: CheapEq, Ms> ViewCache<K, Ms> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the cached `Node` when `key` is `CheapEq` to the previous key,
+    /// otherwise render it by `view` and cache it.
+    pub fn view(&self, key: K, view: impl FnOnce(&K) -> Node<Ms>) -> Node<Ms> {
+        let mut cached = self.cached.borrow_mut();
+        if let Some((cached_key, node)) = cached.as_ref() {
+            if cached_key.cheap_eq(&key) {
+                return node.clone();
+            }
+        }
+        let node = view(&key);
+        self.renders.set(self.renders.get() + 1);
+        *cached = Some((key, node.clone()));
+        node
+    }
+
+    /// Forget the cached `Node` - the next `view` call renders it.
+    pub fn invalidate(&self) {
+        self.cached.replace(None);
+    }
+
+    /// How many times the section has been rendered - e.g. for tests and profiling.
+    pub fn renders(&self) -> usize {
+        self.renders.get()
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    type Ms = ();
+
+    #[wasm_bindgen_test]
+    fn view_is_reused_while_key_is_unchanged() {
+        let cache = ViewCache::<(Rc<Vec<u32>>, u32), Ms>::new();
+        let rows = Rc::new(vec![1, 2, 3]);
+        let view = |(rows, page): &(Rc<Vec<u32>>, u32)| -> Node<Ms> {
+            div![rows.iter().map(|row| li![row]), page]
+        };
+
+        cache.view((Rc::clone(&rows), 1), view);
+        cache.view((Rc::clone(&rows), 1), view);
+        assert_eq!(cache.renders(), 1);
+
+        cache.view((Rc::clone(&rows), 2), view);
+        assert_eq!(cache.renders(), 2);
+
+        // An equal vector behind another pointer isn't detected.
+        cache.view((Rc::new(vec![1, 2, 3]), 2), view);
+        assert_eq!(cache.renders(), 3);
+
+        cache.invalidate();
+        cache.view((rows, 2), view);
+        assert_eq!(cache.renders(), 4);
+    }
+
+    #[wasm_bindgen_test]
+    fn cheap_eq_of_options() {
+        assert!(Some(1).cheap_eq(&Some(1)));
+        assert!(!Some(1).cheap_eq(&None));
+        assert!(None::<u8>.cheap_eq(&None));
+    }
+}